@@ -0,0 +1,95 @@
+//! Role-based access control for the Credit contract.
+//!
+//! The admin implicitly holds every role. Operational keys (the backend risk
+//! engine, an incident-response pauser, a treasury operator, a rate feed, a
+//! compliance officer, a monitoring guardian, a co-signer for large debt
+//! forgiveness) are granted a single role each so they never need the full
+//! admin key.
+
+use soroban_sdk::{contracttype, Address, Env, Symbol};
+
+use crate::storage::{self, DataKey};
+
+/// Operational roles that can be granted alongside the admin.
+#[contracttype]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Role {
+    /// Opens credit lines and updates risk parameters.
+    RiskEngine = 0,
+    /// Pauses and unpauses draws and repayments.
+    Pauser = 1,
+    /// Moves protocol fees and treasury funds.
+    Treasurer = 2,
+    /// Publishes the reference rate for variable-rate lines.
+    RateOracle = 3,
+    /// Maintains the borrower allowlist.
+    Compliance = 4,
+    /// Pauses draws and suspends individual lines in an emergency, and nothing else.
+    Guardian = 5,
+    /// Co-signs debt forgiveness above the approval threshold.
+    Approver = 6,
+}
+
+/// Returns true if `account` has been explicitly granted `role`.
+pub fn has_role(env: &Env, role: Role, account: &Address) -> bool {
+    storage::has_persistent(env, &DataKey::Role(role, account.clone()))
+}
+
+pub fn grant_role(env: &Env, role: Role, account: &Address) {
+    storage::set_persistent(env, &DataKey::Role(role, account.clone()), &true);
+}
+
+pub fn revoke_role(env: &Env, role: Role, account: &Address) {
+    storage::remove_persistent(env, &DataKey::Role(role, account.clone()));
+}
+
+fn designated_key(env: &Env, name: &str) -> DataKey {
+    DataKey::Config(Symbol::new(env, name))
+}
+
+/// Make `account` the address designated under `name`: it is granted `role` and
+/// the previously designated address, if different, loses it. Roles granted
+/// directly through `grant_role` are left alone.
+fn designate(env: &Env, name: &str, role: Role, account: &Address) {
+    let key = designated_key(env, name);
+    let previous: Option<Address> = storage::get_instance(env, &key);
+    if let Some(previous) = previous {
+        if previous != *account {
+            revoke_role(env, role, &previous);
+        }
+    }
+    grant_role(env, role, account);
+    storage::set_instance(env, &key, account);
+}
+
+/// The designated risk engine address, if one has been set.
+pub fn risk_engine(env: &Env) -> Option<Address> {
+    storage::get_instance(env, &designated_key(env, "risk_engine"))
+}
+
+/// Make `engine` the designated risk engine, holding `RiskEngine`.
+pub fn set_risk_engine(env: &Env, engine: &Address) {
+    designate(env, "risk_engine", Role::RiskEngine, engine);
+}
+
+/// The designated guardian address, if one has been set.
+pub fn guardian(env: &Env) -> Option<Address> {
+    storage::get_instance(env, &designated_key(env, "guardian"))
+}
+
+/// Make `guardian` the designated guardian, holding `Guardian`.
+pub fn set_guardian(env: &Env, guardian: &Address) {
+    designate(env, "guardian", Role::Guardian, guardian);
+}
+
+/// Require `caller` to authorize and to be either the admin or a holder of `role`.
+///
+/// # Panics
+/// * `"unauthorized"` – caller is neither the admin nor a holder of `role`
+pub fn require_role(env: &Env, role: Role, caller: &Address) {
+    caller.require_auth();
+    let admin = crate::require_admin(env);
+    if *caller != admin && !has_role(env, role, caller) {
+        panic!("unauthorized");
+    }
+}
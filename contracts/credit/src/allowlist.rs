@@ -0,0 +1,55 @@
+//! Borrower allowlist for regulated deployments.
+//!
+//! While the gate is enabled, lines can only be opened for, drawn by, or
+//! transferred to borrowers that the admin or a `Compliance` role holder has
+//! allowlisted. Denying a borrower leaves their line in place, so repayments
+//! and closing still work. The gate is off by default.
+
+use soroban_sdk::{panic_with_error, Address, Env, Symbol};
+
+use crate::errors::ContractError;
+use crate::storage::{self, DataKey};
+
+/// Instance storage key for the gate switch.
+fn enabled_key(env: &Env) -> DataKey {
+    DataKey::Config(Symbol::new(env, "allowlist"))
+}
+
+fn entry_key(borrower: &Address) -> DataKey {
+    DataKey::Allowlisted(borrower.clone())
+}
+
+pub fn is_enabled(env: &Env) -> bool {
+    storage::get_instance(env, &enabled_key(env)).unwrap_or(false)
+}
+
+pub fn set_enabled(env: &Env, enabled: bool) {
+    storage::set_instance(env, &enabled_key(env), &enabled);
+}
+
+/// True if `borrower` is on the allowlist, whether or not the gate is enabled.
+pub fn is_listed(env: &Env, borrower: &Address) -> bool {
+    storage::has_persistent(env, &entry_key(borrower))
+}
+
+pub fn allow(env: &Env, borrower: &Address) {
+    storage::set_persistent(env, &entry_key(borrower), &true);
+}
+
+pub fn deny(env: &Env, borrower: &Address) {
+    storage::remove_persistent(env, &entry_key(borrower));
+}
+
+/// Why `borrower` may not borrow, if the gate rejects them.
+pub fn check(env: &Env, borrower: &Address) -> Result<(), ContractError> {
+    if is_enabled(env) && !is_listed(env, borrower) {
+        return Err(ContractError::NotAllowlisted);
+    }
+    Ok(())
+}
+
+/// # Panics
+/// * `ContractError::NotAllowlisted` – the gate is enabled and `borrower` is not listed
+pub fn require_allowed(env: &Env, borrower: &Address) {
+    check(env, borrower).unwrap_or_else(|error| panic_with_error!(env, error));
+}
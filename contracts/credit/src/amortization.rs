@@ -0,0 +1,199 @@
+//! Repayment modes: how a scheduled line's minimum payment is billed.
+//!
+//! `Revolving` lines, the default, bill `min_payment_bps` of the utilized
+//! amount each cycle. `InterestOnly` lines bill the interest accrued so far,
+//! leaving principal to be repaid at will or at maturity. `Amortizing(n)`
+//! lines pay principal down in `n` straight-line installments counted from
+//! the due date at which the mode was set: each bill is the utilized amount
+//! divided by the installments left, rounded up, plus accrued interest, and
+//! the last installment bills the whole balance. Principal drawn part way
+//! through is spread over the installments that remain.
+
+use soroban_sdk::{contracttype, Address, Env, Vec};
+
+use crate::interest;
+use crate::math;
+use crate::schedule;
+use crate::storage::{self, DataKey};
+use crate::types::CreditLineData;
+
+/// Most installments an amortizing line can be set to.
+pub const MAX_INSTALLMENTS: u32 = 360;
+
+/// How a scheduled line's minimum payment is computed each cycle.
+#[contracttype]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum RepaymentMode {
+    Revolving,
+    InterestOnly,
+    /// Repaid over `n_installments` cycles.
+    Amortizing(u32),
+}
+
+/// A line's repayment mode and the due date it took effect at.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct RepaymentPlan {
+    pub mode: RepaymentMode,
+    /// Due date when the mode was set; installments count from the one after it.
+    pub start_due_ts: u64,
+}
+
+/// One projected installment of an amortizing line.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Installment {
+    pub due_ts: u64,
+    pub principal: i128,
+    pub interest: i128,
+    pub payment: i128,
+    /// Utilized amount once the installment is paid.
+    pub balance_after: i128,
+}
+
+#[contracttype]
+#[derive(Clone)]
+pub enum AmortizationKey {
+    /// Repayment plan of a borrower's line; absent means revolving (persistent).
+    Plan(Address),
+}
+
+fn plan_key(borrower: &Address) -> DataKey {
+    DataKey::Amortization(AmortizationKey::Plan(borrower.clone()))
+}
+
+pub fn get(env: &Env, borrower: &Address) -> RepaymentPlan {
+    storage::get_persistent(env, &plan_key(borrower)).unwrap_or(RepaymentPlan {
+        mode: RepaymentMode::Revolving,
+        start_due_ts: 0,
+    })
+}
+
+/// Switch `line` to `mode` from its next bill on.
+///
+/// # Panics
+/// * `"credit line has no schedule"`
+/// * `"n_installments out of range"` – zero or above `MAX_INSTALLMENTS`
+/// * `"min_payment_bps must be greater than zero"` – `Revolving` on a line that would never bill
+pub fn set(env: &Env, line: &CreditLineData, mode: RepaymentMode) -> RepaymentPlan {
+    if !schedule::is_scheduled(line) {
+        panic!("credit line has no schedule");
+    }
+    match mode {
+        RepaymentMode::Revolving if line.min_payment_bps == 0 => {
+            panic!("min_payment_bps must be greater than zero")
+        }
+        RepaymentMode::Amortizing(n_installments)
+            if n_installments == 0 || n_installments > MAX_INSTALLMENTS =>
+        {
+            panic!("n_installments out of range")
+        }
+        _ => {}
+    }
+    let plan = RepaymentPlan {
+        mode,
+        start_due_ts: line.next_due_ts,
+    };
+    if mode == RepaymentMode::Revolving {
+        storage::remove_persistent(env, &plan_key(&line.borrower));
+    } else {
+        storage::set_persistent(env, &plan_key(&line.borrower), &plan);
+    }
+    plan
+}
+
+pub fn transfer(env: &Env, from: &Address, to: &Address) {
+    if let Some(plan) = storage::get_persistent::<RepaymentPlan>(env, &plan_key(from)) {
+        storage::remove_persistent(env, &plan_key(from));
+        storage::set_persistent(env, &plan_key(to), &plan);
+    }
+}
+
+/// Installment number of the bill due at `due_ts`, from 1.
+fn installment_number(plan: &RepaymentPlan, line: &CreditLineData, due_ts: u64) -> u64 {
+    (due_ts.saturating_sub(plan.start_due_ts) / line.cycle_length_secs).max(1)
+}
+
+/// Principal part of the `number`th of `n_installments` on `utilized` (rounded up).
+fn principal_installment(utilized: i128, n_installments: u32, number: u64) -> i128 {
+    if utilized <= 0 {
+        return 0;
+    }
+    let left = (n_installments as u64).saturating_sub(number - 1).max(1) as i128;
+    (utilized + left - 1) / left
+}
+
+/// Minimum due at `line.next_due_ts` under the line's mode, on top of
+/// `arrears` still unpaid from earlier cycles.
+pub fn bill(env: &Env, line: &CreditLineData, arrears: i128) -> i128 {
+    let plan = get(env, &line.borrower);
+    let principal = match plan.mode {
+        RepaymentMode::Revolving => {
+            let due = math::checked(
+                env,
+                schedule::minimum_payment(line.utilized_amount, line.min_payment_bps),
+            );
+            return arrears.saturating_add(due).min(line.utilized_amount);
+        }
+        RepaymentMode::InterestOnly => 0,
+        RepaymentMode::Amortizing(n_installments) => principal_installment(
+            line.utilized_amount,
+            n_installments,
+            installment_number(&plan, line, line.next_due_ts),
+        ),
+    };
+    // Repayments settle interest first, so unpaid interest is still in
+    // `accrued_interest` and whatever arrears exceed it are unpaid principal.
+    principal
+        .saturating_add(arrears.max(line.accrued_interest))
+        .min(interest::outstanding(line))
+}
+
+/// Installments of an amortizing `line` from its current bill on, assuming no
+/// more draws, the current bill paid by its due date, and each later
+/// installment paid as it is billed, at the rates the line accrues at now.
+/// Empty for other modes.
+pub fn projected_schedule(env: &Env, line: &CreditLineData) -> Vec<Installment> {
+    let mut installments = Vec::new(env);
+    let plan = get(env, &line.borrower);
+    let RepaymentMode::Amortizing(n_installments) = plan.mode else {
+        return installments;
+    };
+    if !schedule::is_scheduled(line) {
+        return installments;
+    }
+    let mut line = line.clone();
+    interest::accrue(env, &mut line);
+    schedule::roll_forward(env, &mut line);
+    if line.min_due_amount > 0 {
+        let due_ts = line.next_due_ts;
+        interest::project_to(env, &mut line, due_ts);
+        let due = line.min_due_amount;
+        let interest = interest::apply_repayment(env, &mut line, due);
+        installments.push_back(Installment {
+            due_ts,
+            principal: due - interest,
+            interest,
+            payment: due,
+            balance_after: line.utilized_amount,
+        });
+    }
+    while line.utilized_amount > 0 {
+        let billed_ts = line.next_due_ts;
+        interest::project_to(env, &mut line, billed_ts);
+        line.next_due_ts = line.next_due_ts.saturating_add(line.cycle_length_secs);
+        let number = installment_number(&plan, &line, line.next_due_ts);
+        let principal = principal_installment(line.utilized_amount, n_installments, number);
+        let interest = line.accrued_interest;
+        line.utilized_amount -= principal;
+        line.accrued_interest = 0;
+        installments.push_back(Installment {
+            due_ts: line.next_due_ts,
+            principal,
+            interest,
+            payment: principal + interest,
+            balance_after: line.utilized_amount,
+        });
+    }
+    installments
+}
@@ -0,0 +1,93 @@
+//! Append-only audit trail of privileged operations.
+//!
+//! Every admin or role-gated operation (line status changes, risk updates,
+//! configuration changes) appends an `AuditEntry`. Entries are stored in
+//! fixed-size chunks of persistent storage, like the borrower index, and are
+//! never modified or removed.
+
+use soroban_sdk::{contracttype, Address, Env, IntoVal, Symbol, Val, Vec};
+
+use crate::events::publish_config_updated;
+use crate::storage::{self, DataKey};
+
+/// Number of entries stored per chunk.
+const CHUNK_SIZE: u32 = 64;
+
+/// One privileged operation.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct AuditEntry {
+    /// Account that authorized the operation; the contract itself for operations
+    /// authorized by a risk engine signature.
+    pub actor: Address,
+    /// Operation name; for configuration changes, the `config` event name.
+    pub action: Symbol,
+    /// Borrower the operation applied to; `None` for protocol-wide operations.
+    pub subject: Option<Address>,
+    pub ts: u64,
+}
+
+#[contracttype]
+#[derive(Clone)]
+pub enum AuditKey {
+    /// Number of entries (instance).
+    Count,
+    /// Chunk `n` holds entries `n * CHUNK_SIZE .. (n + 1) * CHUNK_SIZE` (persistent).
+    Chunk(u32),
+}
+
+pub fn count(env: &Env) -> u32 {
+    storage::get_instance(env, &DataKey::Audit(AuditKey::Count)).unwrap_or(0)
+}
+
+fn chunk(env: &Env, index: u32) -> Vec<AuditEntry> {
+    storage::get_persistent(env, &DataKey::Audit(AuditKey::Chunk(index))).unwrap_or(Vec::new(env))
+}
+
+/// Append an entry for `action` by `actor` on `subject`.
+pub fn record(env: &Env, actor: &Address, action: Symbol, subject: Option<Address>) {
+    let pos = count(env);
+    let mut last = chunk(env, pos / CHUNK_SIZE);
+    last.push_back(AuditEntry {
+        actor: actor.clone(),
+        action,
+        subject,
+        ts: env.ledger().timestamp(),
+    });
+    storage::set_persistent(
+        env,
+        &DataKey::Audit(AuditKey::Chunk(pos / CHUNK_SIZE)),
+        &last,
+    );
+    storage::set_instance(env, &DataKey::Audit(AuditKey::Count), &(pos + 1));
+}
+
+/// Record a change to the configuration entry `name`, write it through to the
+/// stored `ProtocolConfig` and publish its new value.
+pub fn config_changed<V: IntoVal<Env, Val>>(
+    env: &Env,
+    actor: &Address,
+    name: Symbol,
+    subject: Option<Address>,
+    value: V,
+) {
+    record(env, actor, name.clone(), subject);
+    crate::config::sync(env);
+    publish_config_updated(env, name, value);
+}
+
+/// Up to `limit` entries starting at position `start`, oldest first.
+pub fn list(env: &Env, start: u32, limit: u32) -> Vec<AuditEntry> {
+    let mut out = Vec::new(env);
+    let end = start.saturating_add(limit).min(count(env));
+    let mut pos = start;
+    while pos < end {
+        let current = chunk(env, pos / CHUNK_SIZE);
+        let chunk_end = ((pos / CHUNK_SIZE + 1) * CHUNK_SIZE).min(end);
+        while pos < chunk_end {
+            out.push_back(current.get_unchecked(pos % CHUNK_SIZE));
+            pos += 1;
+        }
+    }
+    out
+}
@@ -0,0 +1,116 @@
+//! Autopay of scheduled minimum payments from a pre-approved allowance.
+//!
+//! A borrower opts in with `enable_autopay`, naming the most that may be
+//! pulled in any one billing cycle, and approves this contract on the line's
+//! token. From `AUTOPAY_WINDOW_SECS` before each due date a keeper can call
+//! `run_autopay`, which pays as much of the minimum due as the cap, the
+//! allowance and the borrower's balance allow, through the allowance
+//! repayment path. The cap is counted per due date, so a cycle left overdue
+//! keeps its spent cap until it is paid or billed on.
+
+use soroban_sdk::{contracttype, token, Address, Env};
+
+use crate::storage::{self, DataKey};
+use crate::types::CreditLineData;
+
+/// How long before the due date autopay can run.
+pub const AUTOPAY_WINDOW_SECS: u64 = 86_400;
+
+/// A borrower's autopay consent.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Autopay {
+    /// Most autopay may pull for one due date.
+    pub max_per_cycle: i128,
+    /// Due date `paid_this_cycle` counts against.
+    pub cycle_due_ts: u64,
+    /// Amount autopay has pulled for `cycle_due_ts`.
+    pub paid_this_cycle: i128,
+}
+
+#[contracttype]
+#[derive(Clone)]
+pub enum AutopayKey {
+    /// Autopay consent of a borrower (persistent).
+    Consent(Address),
+}
+
+fn consent_key(borrower: &Address) -> DataKey {
+    DataKey::Autopay(AutopayKey::Consent(borrower.clone()))
+}
+
+pub fn get(env: &Env, borrower: &Address) -> Option<Autopay> {
+    storage::get_persistent(env, &consent_key(borrower))
+}
+
+/// Record `borrower`'s consent, replacing any previous cap. What was already
+/// pulled for the current due date still counts.
+///
+/// # Panics
+/// * `"max_per_cycle must be positive"`
+pub fn enable(env: &Env, borrower: &Address, max_per_cycle: i128) -> Autopay {
+    if max_per_cycle <= 0 {
+        panic!("max_per_cycle must be positive");
+    }
+    let autopay = match get(env, borrower) {
+        Some(existing) => Autopay {
+            max_per_cycle,
+            ..existing
+        },
+        None => Autopay {
+            max_per_cycle,
+            cycle_due_ts: 0,
+            paid_this_cycle: 0,
+        },
+    };
+    storage::set_persistent(env, &consent_key(borrower), &autopay);
+    autopay
+}
+
+/// Remove `borrower`'s consent.
+///
+/// # Panics
+/// * `"autopay not enabled"`
+pub fn disable(env: &Env, borrower: &Address) {
+    if get(env, borrower).is_none() {
+        panic!("autopay not enabled");
+    }
+    storage::remove_persistent(env, &consent_key(borrower));
+}
+
+/// Drop any consent of `borrower`, e.g. when their line moves to a new address.
+pub fn clear(env: &Env, borrower: &Address) {
+    storage::remove_persistent(env, &consent_key(borrower));
+}
+
+/// Amount autopay can pull now towards `line`'s minimum due, which the caller
+/// has rolled forward, and book it against the cycle's cap.
+///
+/// # Panics
+/// * `"autopay not enabled"`
+/// * `"payment not due"` – no minimum billed, or the due date is still outside the window
+/// * `"nothing to autopay"` – the cap, allowance or balance is used up
+pub fn take(env: &Env, line: &CreditLineData) -> i128 {
+    let mut autopay = get(env, &line.borrower).expect("autopay not enabled");
+    let now = env.ledger().timestamp();
+    if line.min_due_amount <= 0 || now.saturating_add(AUTOPAY_WINDOW_SECS) < line.next_due_ts {
+        panic!("payment not due");
+    }
+    if autopay.cycle_due_ts != line.next_due_ts {
+        autopay.cycle_due_ts = line.next_due_ts;
+        autopay.paid_this_cycle = 0;
+    }
+    let token_client = token::Client::new(env, &line.token);
+    let allowance = token_client.allowance(&line.borrower, &env.current_contract_address());
+    let amount = line
+        .min_due_amount
+        .min(autopay.max_per_cycle - autopay.paid_this_cycle)
+        .min(allowance)
+        .min(token_client.balance(&line.borrower));
+    if amount <= 0 {
+        panic!("nothing to autopay");
+    }
+    autopay.paid_this_cycle += amount;
+    storage::set_persistent(env, &consent_key(&line.borrower), &autopay);
+    amount
+}
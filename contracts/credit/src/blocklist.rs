@@ -0,0 +1,41 @@
+//! Sanctions blocklist.
+//!
+//! The admin or a `Compliance` role holder can block an address outright. A
+//! blocked borrower cannot draw, and no draw can pay out to a blocked recipient
+//! or operator, whatever the allowlist says. Repayments always come from the
+//! line's own borrower, so they are not affected.
+
+use soroban_sdk::{panic_with_error, Address, Env};
+
+use crate::errors::ContractError;
+use crate::storage::{self, DataKey};
+
+fn entry_key(account: &Address) -> DataKey {
+    DataKey::Blocked(account.clone())
+}
+
+pub fn is_blocked(env: &Env, account: &Address) -> bool {
+    storage::has_persistent(env, &entry_key(account))
+}
+
+pub fn block(env: &Env, account: &Address) {
+    storage::set_persistent(env, &entry_key(account), &true);
+}
+
+pub fn unblock(env: &Env, account: &Address) {
+    storage::remove_persistent(env, &entry_key(account));
+}
+
+/// Why a draw by `borrower` paying out to `recipient` is rejected, if it is.
+pub fn check(env: &Env, borrower: &Address, recipient: &Address) -> Result<(), ContractError> {
+    if is_blocked(env, borrower) || (recipient != borrower && is_blocked(env, recipient)) {
+        return Err(ContractError::Blocked);
+    }
+    Ok(())
+}
+
+/// # Panics
+/// * `ContractError::Blocked` – `borrower` or `recipient` is blocked
+pub fn require_not_blocked(env: &Env, borrower: &Address, recipient: &Address) {
+    check(env, borrower, recipient).unwrap_or_else(|error| panic_with_error!(env, error));
+}
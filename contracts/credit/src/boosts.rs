@@ -0,0 +1,52 @@
+//! Temporary limit boosts.
+//!
+//! The risk engine can raise a line's limit by `limit_boost` until
+//! `boost_expires_at`, e.g. for a seasonal peak. Draws count the boost while it
+//! is unexpired; an expired boost is ignored and cleared by the next draw.
+//! Repayments are not affected, so debt drawn under a boost simply stays
+//! outstanding once it lapses.
+
+use soroban_sdk::Env;
+
+use crate::types::CreditLineData;
+
+/// Replace any boost on `line` with `extra_amount` until `expires_at`.
+///
+/// # Panics
+/// * `"boost amount must be positive"`
+/// * `"boost expiry must be in the future"`
+pub fn grant(env: &Env, line: &mut CreditLineData, extra_amount: i128, expires_at: u64) {
+    if extra_amount <= 0 {
+        panic!("boost amount must be positive");
+    }
+    if expires_at <= env.ledger().timestamp() {
+        panic!("boost expiry must be in the future");
+    }
+    line.limit_boost = extra_amount;
+    line.boost_expires_at = expires_at;
+}
+
+/// The boost currently in force on `line`; 0 once it has expired.
+pub fn active(env: &Env, line: &CreditLineData) -> i128 {
+    if env.ledger().timestamp() < line.boost_expires_at {
+        line.limit_boost
+    } else {
+        0
+    }
+}
+
+/// Clear an expired boost from `line`, returning its amount and expiry.
+pub fn clear_if_expired(env: &Env, line: &mut CreditLineData) -> Option<(i128, u64)> {
+    if line.limit_boost == 0 || active(env, line) != 0 {
+        return None;
+    }
+    let expired = (line.limit_boost, line.boost_expires_at);
+    line.limit_boost = 0;
+    line.boost_expires_at = 0;
+    Some(expired)
+}
+
+/// `line`'s credit limit including any active boost.
+pub fn effective_limit(env: &Env, line: &CreditLineData) -> i128 {
+    line.credit_limit.saturating_add(active(env, line))
+}
@@ -0,0 +1,112 @@
+//! On-chain index of borrowers with open credit lines.
+//!
+//! Borrowers are stored in fixed-size chunks of persistent storage so that no
+//! single entry grows with the number of lines. A line enters the index when it
+//! is opened and leaves it when it is closed; removal swaps the last borrower
+//! into the freed slot, so ordering is not stable across closes.
+
+use soroban_sdk::{contracttype, Address, Env, Vec};
+
+use crate::storage::{self, DataKey};
+use crate::types::{CreditLineData, CreditStatus};
+
+/// Number of borrowers stored per chunk.
+const CHUNK_SIZE: u32 = 64;
+
+#[contracttype]
+#[derive(Clone)]
+pub enum BorrowerKey {
+    /// Number of indexed borrowers (instance).
+    Count,
+    /// Chunk `n` holds positions `n * CHUNK_SIZE .. (n + 1) * CHUNK_SIZE` (persistent).
+    Chunk(u32),
+    /// Position of a borrower in the index (persistent).
+    Position(Address),
+}
+
+pub fn count(env: &Env) -> u32 {
+    storage::get_instance(env, &DataKey::Borrowers(BorrowerKey::Count)).unwrap_or(0)
+}
+
+fn chunk(env: &Env, index: u32) -> Vec<Address> {
+    storage::get_persistent(env, &DataKey::Borrowers(BorrowerKey::Chunk(index)))
+        .unwrap_or(Vec::new(env))
+}
+
+fn set_chunk(env: &Env, index: u32, chunk: &Vec<Address>) {
+    if chunk.is_empty() {
+        storage::remove_persistent(env, &DataKey::Borrowers(BorrowerKey::Chunk(index)));
+    } else {
+        storage::set_persistent(env, &DataKey::Borrowers(BorrowerKey::Chunk(index)), chunk);
+    }
+}
+
+fn position(env: &Env, borrower: &Address) -> Option<u32> {
+    storage::get_persistent(
+        env,
+        &DataKey::Borrowers(BorrowerKey::Position(borrower.clone())),
+    )
+}
+
+fn add(env: &Env, borrower: &Address) {
+    if position(env, borrower).is_some() {
+        return;
+    }
+    let pos = count(env);
+    let mut last = chunk(env, pos / CHUNK_SIZE);
+    last.push_back(borrower.clone());
+    set_chunk(env, pos / CHUNK_SIZE, &last);
+    storage::set_persistent(
+        env,
+        &DataKey::Borrowers(BorrowerKey::Position(borrower.clone())),
+        &pos,
+    );
+    storage::set_instance(env, &DataKey::Borrowers(BorrowerKey::Count), &(pos + 1));
+}
+
+pub fn remove(env: &Env, borrower: &Address) {
+    let Some(pos) = position(env, borrower) else {
+        return;
+    };
+    let last_pos = count(env) - 1;
+    let mut last_chunk = chunk(env, last_pos / CHUNK_SIZE);
+    let moved = last_chunk.pop_back().expect("borrower index corrupted");
+    set_chunk(env, last_pos / CHUNK_SIZE, &last_chunk);
+
+    if pos != last_pos {
+        let mut target = chunk(env, pos / CHUNK_SIZE);
+        target.set(pos % CHUNK_SIZE, moved.clone());
+        set_chunk(env, pos / CHUNK_SIZE, &target);
+        storage::set_persistent(env, &DataKey::Borrowers(BorrowerKey::Position(moved)), &pos);
+    }
+    storage::remove_persistent(
+        env,
+        &DataKey::Borrowers(BorrowerKey::Position(borrower.clone())),
+    );
+    storage::set_instance(env, &DataKey::Borrowers(BorrowerKey::Count), &last_pos);
+}
+
+/// Keep the index in sync with a credit line write.
+pub fn track(env: &Env, line: &CreditLineData) {
+    if line.status == CreditStatus::Closed {
+        remove(env, &line.borrower);
+    } else {
+        add(env, &line.borrower);
+    }
+}
+
+/// Up to `limit` borrowers starting at position `start`.
+pub fn list(env: &Env, start: u32, limit: u32) -> Vec<Address> {
+    let mut out = Vec::new(env);
+    let end = start.saturating_add(limit).min(count(env));
+    let mut pos = start;
+    while pos < end {
+        let current = chunk(env, pos / CHUNK_SIZE);
+        let chunk_end = ((pos / CHUNK_SIZE + 1) * CHUNK_SIZE).min(end);
+        while pos < chunk_end {
+            out.push_back(current.get_unchecked(pos % CHUNK_SIZE));
+            pos += 1;
+        }
+    }
+    out
+}
@@ -0,0 +1,234 @@
+//! Collateral for secured credit lines.
+//!
+//! A borrower may deposit a single collateral token against their line. The
+//! admin-configured `CollateralConfig` splits the limit into an unsecured
+//! portion (`unsecured_bps` of `credit_limit`) and a collateral-backed portion
+//! (`ltv_bps` of the collateral value). Collateral in the line's own token
+//! counts 1:1. Any other token must be on the admin's collateral allowlist and
+//! is valued through the price oracle; without an oracle it counts for
+//! nothing, so a borrower cannot raise their limit with a token they minted.
+//! With the default config every line is fully unsecured.
+
+use soroban_sdk::{contracttype, token, Address, Env, Symbol, Vec};
+
+use crate::math::{self, apply_bps, mul_div, Rounding};
+use crate::oracle;
+use crate::storage::{self, DataKey};
+use crate::tiers;
+use crate::types::CreditLineData;
+
+/// Collateral policy (admin-configured).
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct CollateralConfig {
+    /// Loan-to-value applied to collateral, in basis points.
+    pub ltv_bps: u32,
+    /// Portion of `credit_limit` drawable without collateral, in basis points.
+    pub unsecured_bps: u32,
+}
+
+impl Default for CollateralConfig {
+    fn default() -> Self {
+        Self {
+            ltv_bps: 0,
+            unsecured_bps: 10_000,
+        }
+    }
+}
+
+#[contracttype]
+#[derive(Clone)]
+pub enum CollateralKey {
+    /// Collateral of a token held across all lines (instance).
+    Held(Address),
+}
+
+/// Instance storage key for collateral config.
+fn collateral_config_key(env: &Env) -> DataKey {
+    DataKey::Config(Symbol::new(env, "collat_cfg"))
+}
+
+/// Instance storage key for the collateral token allowlist.
+fn tokens_key(env: &Env) -> DataKey {
+    DataKey::Config(Symbol::new(env, "collat_tokens"))
+}
+
+pub fn get_config(env: &Env) -> CollateralConfig {
+    storage::get_instance(env, &collateral_config_key(env)).unwrap_or_default()
+}
+
+/// Tokens other than a line's own that may be deposited as collateral.
+pub fn allowed_tokens(env: &Env) -> Vec<Address> {
+    storage::get_instance(env, &tokens_key(env)).unwrap_or(Vec::new(env))
+}
+
+pub fn set_allowed(env: &Env, token: &Address, allowed: bool) {
+    let mut tokens = allowed_tokens(env);
+    match (tokens.first_index_of(token), allowed) {
+        (None, true) => tokens.push_back(token.clone()),
+        (Some(index), false) => {
+            tokens.remove(index);
+        }
+        _ => return,
+    }
+    storage::set_instance(env, &tokens_key(env), &tokens);
+}
+
+/// # Panics
+/// * `"ltv_bps cannot exceed 10000"`
+/// * `"unsecured_bps cannot exceed 10000"`
+pub fn set_config(env: &Env, config: &CollateralConfig) {
+    if config.ltv_bps > 10_000 {
+        panic!("ltv_bps cannot exceed 10000");
+    }
+    if config.unsecured_bps > 10_000 {
+        panic!("unsecured_bps cannot exceed 10000");
+    }
+    storage::set_instance(env, &collateral_config_key(env), config);
+}
+
+/// How a line's collateral converts to credit token units.
+enum Valuation {
+    /// Collateral is the credit token.
+    Par,
+    /// Oracle prices of (collateral token, credit token).
+    Priced(i128, i128),
+    /// Another token with no oracle to price it.
+    Unpriced,
+}
+
+fn valuation(env: &Env, line: &CreditLineData) -> Valuation {
+    let Some(collateral_token) = line.collateral_token.as_ref() else {
+        return Valuation::Par;
+    };
+    if *collateral_token == line.token {
+        return Valuation::Par;
+    }
+    match oracle::get_config(env) {
+        Some(config) => Valuation::Priced(
+            oracle::fresh_price(env, &config, collateral_token),
+            oracle::fresh_price(env, &config, &line.token),
+        ),
+        None => Valuation::Unpriced,
+    }
+}
+
+/// Value of `amount` of the line's collateral in credit token units (rounded
+/// down); 0 for collateral that cannot be priced.
+pub fn value_of(env: &Env, line: &CreditLineData, amount: i128) -> i128 {
+    match valuation(env, line) {
+        Valuation::Par => amount,
+        Valuation::Priced(collateral_price, credit_price) => math::checked(
+            env,
+            mul_div(amount, collateral_price, credit_price, Rounding::Down),
+        ),
+        Valuation::Unpriced => 0,
+    }
+}
+
+/// Collateral amount worth `value` credit token units (rounded up).
+///
+/// # Panics
+/// * `"collateral has no price"` – collateral in another token and no oracle
+pub fn amount_for_value(env: &Env, line: &CreditLineData, value: i128) -> i128 {
+    match valuation(env, line) {
+        Valuation::Par => value,
+        Valuation::Priced(collateral_price, credit_price) => math::checked(
+            env,
+            mul_div(value, credit_price, collateral_price, Rounding::Up),
+        ),
+        Valuation::Unpriced => panic!("collateral has no price"),
+    }
+}
+
+/// Value of the line's collateral in credit token units.
+pub fn collateral_value(env: &Env, line: &CreditLineData) -> i128 {
+    value_of(env, line, line.collateral_amount)
+}
+
+/// Maximum utilized amount allowed for `line` under the collateral policy, with
+/// the LTV of the line's tier if it is in one.
+pub fn max_drawable(env: &Env, line: &CreditLineData) -> i128 {
+    let config = get_config(env);
+    let ltv_bps = tiers::of_line(env, &line.borrower).map_or(config.ltv_bps, |tier| tier.ltv_bps);
+    let unsecured = math::checked(
+        env,
+        apply_bps(line.credit_limit, config.unsecured_bps, Rounding::Down),
+    );
+    let secured = math::checked(
+        env,
+        apply_bps(collateral_value(env, line), ltv_bps, Rounding::Down),
+    );
+    unsecured.saturating_add(secured).min(line.credit_limit)
+}
+
+/// Pull `amount` of `token` from the borrower into the contract and record it.
+///
+/// # Panics
+/// * `"amount must be positive"`
+/// * `"collateral token mismatch"` – line already holds a different token
+/// * `"collateral token not allowed"` – neither the line's token nor on the allowlist
+pub fn deposit(env: &Env, line: &mut CreditLineData, token: &Address, amount: i128) {
+    if amount <= 0 {
+        panic!("amount must be positive");
+    }
+    match &line.collateral_token {
+        Some(existing) if existing != token => panic!("collateral token mismatch"),
+        _ => {}
+    }
+    if *token != line.token && !allowed_tokens(env).contains(token) {
+        panic!("collateral token not allowed");
+    }
+    token::Client::new(env, token).transfer(
+        &line.borrower,
+        &env.current_contract_address(),
+        &amount,
+    );
+    line.collateral_token = Some(token.clone());
+    line.collateral_amount = math::checked(env, math::add(line.collateral_amount, amount));
+}
+
+/// Send all collateral held for `line` to `to` and clear the balance.
+/// Returns the amount released.
+pub fn release_all(env: &Env, line: &mut CreditLineData, to: &Address) -> i128 {
+    let amount = line.collateral_amount;
+    if amount > 0 {
+        if let Some(token) = &line.collateral_token {
+            token::Client::new(env, token).transfer(&env.current_contract_address(), to, &amount);
+        }
+    }
+    line.collateral_amount = 0;
+    line.collateral_token = None;
+    amount
+}
+
+/// Collateral of `token` held by the contract across all lines.
+pub fn total_held(env: &Env, token: &Address) -> i128 {
+    storage::get_instance(
+        env,
+        &DataKey::Collateral(CollateralKey::Held(token.clone())),
+    )
+    .unwrap_or(0)
+}
+
+fn adjust_held(env: &Env, line: &CreditLineData, sign: i128) {
+    if let (Some(token), true) = (&line.collateral_token, line.collateral_amount != 0) {
+        let held = math::checked(
+            env,
+            math::add(total_held(env, token), sign * line.collateral_amount),
+        );
+        storage::set_instance(
+            env,
+            &DataKey::Collateral(CollateralKey::Held(token.clone())),
+            &held,
+        );
+    }
+}
+
+/// Adjust the held totals for a credit line moving from `before` to `after`.
+pub fn track(env: &Env, before: Option<&CreditLineData>, after: &CreditLineData) {
+    if let Some(before) = before {
+        adjust_held(env, before, -1);
+    }
+    adjust_held(env, after, 1);
+}
@@ -0,0 +1,47 @@
+//! Sale of defaulted debt to a collections address.
+//!
+//! The admin can assign the claim on a Defaulted line to a collector, who pays
+//! a `price` into the reserve. The pool books the difference between the price
+//! and the outstanding principal as a loss (or gain), and from then on
+//! repayments and liquidation proceeds from the line go to the collector.
+
+use soroban_sdk::{token, Address, Env};
+
+use crate::interest;
+use crate::liquidity;
+use crate::types::{CreditLineData, CreditStatus};
+
+/// Record `collector` as the line's creditor after it pays `price` into the
+/// reserve, settling the pool's claim. The caller saves the line.
+///
+/// # Panics
+/// * `"price must be non-negative"`
+/// * `"credit line is not defaulted"`
+/// * `"debt already assigned"`
+pub fn assign(env: &Env, line: &mut CreditLineData, collector: &Address, price: i128) {
+    if price < 0 {
+        panic!("price must be non-negative");
+    }
+    if line.status != CreditStatus::Defaulted {
+        panic!("credit line is not defaulted");
+    }
+    if line.creditor.is_some() {
+        panic!("debt already assigned");
+    }
+    interest::accrue(env, line);
+
+    if price > 0 {
+        token::Client::new(env, &line.token).transfer(
+            collector,
+            &env.current_contract_address(),
+            &price,
+        );
+    }
+    let principal = line.utilized_amount;
+    if price < principal {
+        liquidity::realize_loss(env, &line.token, principal - price);
+    } else {
+        liquidity::accrue_to_pool(env, &line.token, price - principal);
+    }
+    line.creditor = Some(collector.clone());
+}
@@ -0,0 +1,311 @@
+//! Whole-protocol configuration view and batched updates.
+//!
+//! Each feature module keeps owning its own config entry, which the rest of the
+//! contract reads. Every change is written through to one stored
+//! `ProtocolConfig` instance entry, so frontends and auditors read everything
+//! in a single call, and `apply` writes any subset of the settings in one
+//! transaction.
+
+use soroban_sdk::{contracttype, symbol_short, Address, BytesN, Env, Symbol, TryFromVal, Val, Vec};
+
+use crate::access;
+use crate::allowlist;
+use crate::audit;
+use crate::collateral::{self, CollateralConfig};
+use crate::default_interest::{self, DefaultInterestConfig};
+use crate::draw_limits::{self, DrawLimits};
+use crate::dust;
+use crate::exposure;
+use crate::fees::{self, FeeConfig};
+use crate::flash::{self, FlashConfig};
+use crate::insurance::{self, InsuranceConfig};
+use crate::interest;
+use crate::keeper::{self, KeeperConfig};
+use crate::large_draws::{self, LargeDrawConfig};
+use crate::liquidation::{self, LiquidationConfig};
+use crate::oracle::{self, OracleConfig};
+use crate::pause::{self, PauseState};
+use crate::rates;
+use crate::reserve::{self, ReserveConfig};
+use crate::revenue::{self, RevenueSplit};
+use crate::rewards::{self, RewardsConfig};
+use crate::risk_adjust::{self, RiskAdjustmentConfig};
+use crate::scoring;
+use crate::signed;
+use crate::sources::{self, LiquiditySource, RepaymentRoute};
+use crate::staking::{self, StakingConfig};
+use crate::storage::{self, DataKey};
+use crate::swap;
+use crate::terms::{self, RepaymentTerms};
+use crate::tiers::{self, Tier};
+use crate::timelock;
+use crate::tokens;
+use crate::treasury;
+use crate::types::InterestConfig;
+use crate::velocity::{self, VelocityConfig};
+
+/// Every protocol-wide setting, as currently stored.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ProtocolConfig {
+    pub admin: Address,
+    /// Default token for new lines.
+    pub token: Address,
+    pub supported_tokens: Vec<Address>,
+    pub liquidity_sources: Vec<LiquiditySource>,
+    pub repayment_route: RepaymentRoute,
+    pub fees: FeeConfig,
+    pub treasury: Option<Address>,
+    pub reserve_factor_bps: u32,
+    /// Division of collected interest; derived from the reserve factor until set.
+    pub revenue_split: RevenueSplit,
+    /// Reference rate tracked by Variable lines.
+    pub reference_rate_bps: u32,
+    pub pause: PauseState,
+    pub reserve: ReserveConfig,
+    /// Reserve ratio below which draws are blocked; 0 for none.
+    pub reserve_critical_ratio_bps: u32,
+    pub draw_limits: DrawLimits,
+    pub velocity: VelocityConfig,
+    pub large_draw: LargeDrawConfig,
+    pub insurance: InsuranceConfig,
+    pub rewards: RewardsConfig,
+    pub staking: StakingConfig,
+    pub flash: FlashConfig,
+    /// Interest convention given to new lines.
+    pub interest: InterestConfig,
+    /// Interest on Defaulted lines.
+    pub default_interest: DefaultInterestConfig,
+    /// Grace period and prepayment penalty given to new lines.
+    pub repayment_terms: RepaymentTerms,
+    pub dust_threshold: i128,
+    pub collateral: CollateralConfig,
+    pub liquidation: LiquidationConfig,
+    pub keeper: KeeperConfig,
+    pub risk_adjustment: RiskAdjustmentConfig,
+    pub risk_engine: Option<Address>,
+    pub risk_engine_key: Option<BytesN<32>>,
+    /// Emergency guardian, if designated.
+    pub guardian: Option<Address>,
+    /// Contract that scores borrowers on-chain, if any.
+    pub scoring_contract: Option<Address>,
+    /// Adapter `repay_with_token` swaps through, if registered.
+    pub swap_adapter: Option<Address>,
+    /// Whether the borrower allowlist gate is on.
+    pub allowlist_enabled: bool,
+    /// Cap on protocol-wide outstanding principal; 0 for none.
+    pub exposure_cap: i128,
+    /// Minimum delay of timelocked changes; 0 if changes apply directly.
+    pub timelock_delay: u64,
+}
+
+/// One entry of a partial configuration update.
+///
+/// The admin and default token are fixed at `init`; pause flags, liquidity
+/// sources, the reference rate (a `RateOracle` duty), the allowlist gate (a
+/// `Compliance` duty) and the timelock delay keep their own entrypoints.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum ConfigUpdate {
+    RepaymentRoute(RepaymentRoute),
+    Fees(FeeConfig),
+    Treasury(Address),
+    ReserveFactorBps(u32),
+    RevenueSplit(RevenueSplit),
+    Reserve(ReserveConfig),
+    ReserveCriticalBps(u32),
+    DrawLimits(DrawLimits),
+    Velocity(VelocityConfig),
+    LargeDraw(LargeDrawConfig),
+    Insurance(InsuranceConfig),
+    Rewards(RewardsConfig),
+    Staking(StakingConfig),
+    Flash(FlashConfig),
+    Interest(InterestConfig),
+    DefaultInterest(DefaultInterestConfig),
+    RepaymentTerms(RepaymentTerms),
+    DustThreshold(i128),
+    Collateral(CollateralConfig),
+    Liquidation(LiquidationConfig),
+    CollateralToken(Address, bool),
+    PriceOracle(OracleConfig),
+    Keeper(KeeperConfig),
+    Tier(u32, Tier),
+    RiskAdjustment(RiskAdjustmentConfig),
+    ExposureCap(i128),
+}
+
+/// Instance storage key for the stored `ProtocolConfig`.
+fn config_key(env: &Env) -> DataKey {
+    DataKey::Config(Symbol::new(env, "protocol"))
+}
+
+/// The stored configuration. Assembled from the modules' entries instead when
+/// it has not been written yet, or was written by a release with another
+/// layout; the next change stores it again.
+pub fn get(env: &Env) -> ProtocolConfig {
+    storage::get_instance::<Val>(env, &config_key(env))
+        .and_then(|raw| ProtocolConfig::try_from_val(env, &raw).ok())
+        .unwrap_or_else(|| assemble(env))
+}
+
+/// Rewrite the stored configuration from the modules' entries. Runs after
+/// every configuration change.
+pub fn sync(env: &Env) {
+    storage::set_instance(env, &config_key(env), &assemble(env));
+}
+
+fn assemble(env: &Env) -> ProtocolConfig {
+    ProtocolConfig {
+        admin: crate::require_admin(env),
+        token: crate::require_token(env),
+        supported_tokens: tokens::list(env),
+        liquidity_sources: sources::list(env),
+        repayment_route: sources::get_route(env),
+        fees: fees::get_config(env),
+        treasury: treasury::get_treasury(env),
+        reserve_factor_bps: treasury::reserve_factor_bps(env),
+        revenue_split: revenue::get_split(env),
+        reference_rate_bps: rates::reference_rate_bps(env),
+        pause: pause::get_pause_state(env),
+        reserve: reserve::get_config(env),
+        reserve_critical_ratio_bps: reserve::critical_ratio_bps(env),
+        draw_limits: draw_limits::get_config(env),
+        velocity: velocity::get_config(env),
+        large_draw: large_draws::get_config(env),
+        insurance: insurance::get_config(env),
+        rewards: rewards::get_config(env),
+        staking: staking::get_config(env),
+        flash: flash::get_config(env),
+        interest: interest::get_config(env),
+        default_interest: default_interest::get_config(env),
+        repayment_terms: terms::get_config(env),
+        dust_threshold: dust::get_threshold(env),
+        collateral: collateral::get_config(env),
+        liquidation: liquidation::get_config(env),
+        keeper: keeper::get_config(env),
+        risk_adjustment: risk_adjust::get_config(env),
+        risk_engine: access::risk_engine(env),
+        risk_engine_key: signed::get_public_key(env),
+        guardian: access::guardian(env),
+        scoring_contract: scoring::get_contract(env),
+        swap_adapter: swap::get_adapter(env),
+        allowlist_enabled: allowlist::is_enabled(env),
+        exposure_cap: exposure::protocol_cap(env),
+        timelock_delay: timelock::delay(env),
+    }
+}
+
+/// Write each entry of `updates` in order through its module's setter, with
+/// the same validation, `config` event and audit entry as the individual
+/// entrypoint.
+pub fn apply(env: &Env, actor: &Address, updates: Vec<ConfigUpdate>) {
+    for update in updates.iter() {
+        match update {
+            ConfigUpdate::RepaymentRoute(route) => {
+                sources::set_route(env, &route);
+                audit::config_changed(env, actor, symbol_short!("repay_rt"), None, route);
+            }
+            ConfigUpdate::Fees(config) => {
+                fees::set_config(env, &config);
+                audit::config_changed(env, actor, symbol_short!("fee_cfg"), None, config);
+            }
+            ConfigUpdate::Treasury(treasury) => {
+                treasury::set_treasury(env, &treasury);
+                audit::config_changed(env, actor, symbol_short!("treasury"), None, treasury);
+            }
+            ConfigUpdate::ReserveFactorBps(bps) => {
+                treasury::set_reserve_factor_bps(env, bps);
+                audit::config_changed(env, actor, symbol_short!("res_fctr"), None, bps);
+            }
+            ConfigUpdate::RevenueSplit(split) => {
+                revenue::set_split(env, &split);
+                audit::config_changed(env, actor, symbol_short!("rev_split"), None, split);
+            }
+            ConfigUpdate::Reserve(config) => {
+                reserve::set_config(env, &config);
+                audit::config_changed(env, actor, symbol_short!("reserve"), None, config);
+            }
+            ConfigUpdate::ReserveCriticalBps(bps) => {
+                reserve::set_critical_ratio_bps(env, bps);
+                audit::config_changed(env, actor, symbol_short!("res_crit"), None, bps);
+            }
+            ConfigUpdate::DrawLimits(limits) => {
+                draw_limits::set_config(env, &limits);
+                audit::config_changed(env, actor, symbol_short!("draw_lim"), None, limits);
+            }
+            ConfigUpdate::Velocity(config) => {
+                velocity::set_config(env, &config);
+                audit::config_changed(env, actor, symbol_short!("velocity"), None, config);
+            }
+            ConfigUpdate::LargeDraw(config) => {
+                large_draws::set_config(env, &config);
+                audit::config_changed(env, actor, symbol_short!("ld_cfg"), None, config);
+            }
+            ConfigUpdate::Insurance(config) => {
+                insurance::set_config(env, &config);
+                audit::config_changed(env, actor, symbol_short!("ins_cfg"), None, config);
+            }
+            ConfigUpdate::Rewards(config) => {
+                rewards::set_config(env, &config);
+                audit::config_changed(env, actor, symbol_short!("rewards"), None, config);
+            }
+            ConfigUpdate::Staking(config) => {
+                staking::set_config(env, &config);
+                audit::config_changed(env, actor, symbol_short!("staking"), None, config);
+            }
+            ConfigUpdate::Flash(config) => {
+                flash::set_config(env, &config);
+                audit::config_changed(env, actor, symbol_short!("flash"), None, config);
+            }
+            ConfigUpdate::Interest(config) => {
+                interest::set_config(env, &config);
+                audit::config_changed(env, actor, symbol_short!("int_cfg"), None, config);
+            }
+            ConfigUpdate::DefaultInterest(config) => {
+                default_interest::set_config(env, &config);
+                audit::config_changed(env, actor, symbol_short!("dflt_int"), None, config);
+            }
+            ConfigUpdate::RepaymentTerms(terms) => {
+                terms::set_config(env, &terms);
+                audit::config_changed(env, actor, symbol_short!("terms"), None, terms);
+            }
+            ConfigUpdate::DustThreshold(threshold) => {
+                dust::set_threshold(env, threshold);
+                audit::config_changed(env, actor, symbol_short!("dust"), None, threshold);
+            }
+            ConfigUpdate::Collateral(config) => {
+                collateral::set_config(env, &config);
+                audit::config_changed(env, actor, symbol_short!("col_cfg"), None, config);
+            }
+            ConfigUpdate::Liquidation(config) => {
+                liquidation::set_config(env, &config);
+                audit::config_changed(env, actor, symbol_short!("liq_cfg"), None, config);
+            }
+            ConfigUpdate::CollateralToken(token, allowed) => {
+                collateral::set_allowed(env, &token, allowed);
+                audit::config_changed(env, actor, symbol_short!("col_token"), Some(token), allowed);
+            }
+            ConfigUpdate::PriceOracle(config) => {
+                oracle::set_config(env, &config);
+                audit::config_changed(env, actor, symbol_short!("oracle"), None, config);
+            }
+            ConfigUpdate::Keeper(config) => {
+                keeper::set_config(env, &config);
+                audit::config_changed(env, actor, symbol_short!("keeper"), None, config);
+            }
+            ConfigUpdate::Tier(tier_id, tier) => {
+                tiers::set(env, tier_id, &tier);
+                audit::config_changed(env, actor, symbol_short!("tier"), None, (tier_id, tier));
+            }
+            ConfigUpdate::RiskAdjustment(config) => {
+                risk_adjust::set_config(env, &config);
+                audit::config_changed(env, actor, symbol_short!("risk_adj"), None, config);
+            }
+            ConfigUpdate::ExposureCap(cap) => {
+                exposure::set_protocol_cap(env, cap);
+                audit::config_changed(env, actor, symbol_short!("exp_cap"), None, cap);
+            }
+        }
+    }
+}
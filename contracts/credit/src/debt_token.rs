@@ -0,0 +1,77 @@
+//! Read-only SEP-41 view of outstanding debt.
+//!
+//! The contract answers the SEP-41 token interface so wallets and portfolio
+//! trackers can show debt positions as a token balance. A borrower's balance
+//! is their principal plus interest, accrued up to now. Only lines in the
+//! default token count, so balances and supply share its units and decimals; a
+//! line in another token has a balance of 0. The supply is the principal plus
+//! the interest stored on each of those lines as of its last accrual, kept as a
+//! running total so reading it never iterates over lines; it trails the sum of
+//! balances until lines are accrued. The token cannot be transferred, approved
+//! or burned.
+
+use soroban_sdk::{token, Env, String, Symbol};
+
+use crate::interest;
+use crate::math;
+use crate::storage::{self, DataKey};
+use crate::types::CreditLineData;
+
+/// Instance storage key for the debt outstanding on default-token lines.
+fn supply_key(env: &Env) -> DataKey {
+    DataKey::Config(Symbol::new(env, "debt_sup"))
+}
+
+fn in_default_token(env: &Env, line: &CreditLineData) -> bool {
+    line.token == crate::require_token(env)
+}
+
+/// Principal plus stored interest `line` adds to the supply.
+fn counted(env: &Env, line: &CreditLineData) -> i128 {
+    if in_default_token(env, line) {
+        line.utilized_amount.saturating_add(line.accrued_interest)
+    } else {
+        0
+    }
+}
+
+/// Update the supply for a credit line moving from `before` to `after`.
+pub fn track(env: &Env, before: Option<&CreditLineData>, after: &CreditLineData) {
+    let delta = counted(env, after) - before.map_or(0, |line| counted(env, line));
+    if delta != 0 {
+        let total = math::checked(env, math::add(total_supply(env), delta));
+        storage::set_instance(env, &supply_key(env), &total);
+    }
+}
+
+/// Principal plus interest accrued up to now; 0 for a line in another token.
+pub fn balance(env: &Env, line: &CreditLineData) -> i128 {
+    if !in_default_token(env, line) {
+        return 0;
+    }
+    line.utilized_amount
+        .saturating_add(interest::quote(env, line, 0))
+}
+
+pub fn total_supply(env: &Env) -> i128 {
+    storage::get_instance(env, &supply_key(env)).unwrap_or(0)
+}
+
+/// Decimals of the default token, the only token the debt token counts.
+pub fn decimals(env: &Env) -> u32 {
+    token::Client::new(env, &crate::require_token(env)).decimals()
+}
+
+pub fn name(env: &Env) -> String {
+    String::from_str(env, "Creditra Debt")
+}
+
+pub fn symbol(env: &Env) -> String {
+    String::from_str(env, "CRDEBT")
+}
+
+/// # Panics
+/// * `"debt token is non-transferable"`
+pub fn reject_transfer() -> ! {
+    panic!("debt token is non-transferable")
+}
@@ -0,0 +1,98 @@
+//! Interest on Defaulted lines.
+//!
+//! Jurisdictions differ on whether interest may keep running once a loan has
+//! defaulted, so the admin chooses the behaviour protocol-wide: accrual can
+//! continue, optionally at a penalty rate, stop at the moment of default, or
+//! stop a set time after it. The time each line defaulted is kept beside the
+//! line for this. Lines that defaulted before it was recorded are treated as
+//! defaulting at their last accrual. Interest up to the default always
+//! accrues on the line's normal terms.
+
+use soroban_sdk::{contracttype, Address, Env, Symbol};
+
+use crate::storage::{self, DataKey};
+use crate::types::{CreditLineData, CreditStatus};
+
+/// What happens to interest once a line defaults.
+#[contracttype]
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum DefaultAccrual {
+    /// Keep accruing, at `penalty_rate_bps` if set.
+    #[default]
+    Continue = 0,
+    /// Stop accruing at the default.
+    Freeze = 1,
+    /// Keep accruing for `freeze_after_secs` after the default, then stop.
+    FreezeAfter = 2,
+}
+
+/// Post-default interest policy (admin-configured).
+#[contracttype]
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct DefaultInterestConfig {
+    pub accrual: DefaultAccrual,
+    /// Rate defaulted lines accrue at while they accrue; 0 keeps the line's own rate.
+    pub penalty_rate_bps: u32,
+    /// Under `FreezeAfter`, how long after the default accrual stops.
+    pub freeze_after_secs: u64,
+}
+
+#[contracttype]
+#[derive(Clone)]
+pub enum DefaultInterestKey {
+    /// When a Defaulted line defaulted (persistent).
+    DefaultedAt(Address),
+}
+
+/// Instance storage key for the policy.
+fn config_key(env: &Env) -> DataKey {
+    DataKey::Config(Symbol::new(env, "default_int"))
+}
+
+fn defaulted_at_key(borrower: &Address) -> DataKey {
+    DataKey::DefaultInterest(DefaultInterestKey::DefaultedAt(borrower.clone()))
+}
+
+pub fn get_config(env: &Env) -> DefaultInterestConfig {
+    storage::get_instance(env, &config_key(env)).unwrap_or_default()
+}
+
+/// # Panics
+/// * `"interest_rate_bps exceeds maximum"` – `penalty_rate_bps` above the rate maximum
+/// * `"freeze_after_secs must be greater than zero"` – under `FreezeAfter`
+pub fn set_config(env: &Env, config: &DefaultInterestConfig) {
+    if config.penalty_rate_bps > crate::MAX_INTEREST_RATE_BPS {
+        panic!("interest_rate_bps exceeds maximum");
+    }
+    if config.accrual == DefaultAccrual::FreezeAfter && config.freeze_after_secs == 0 {
+        panic!("freeze_after_secs must be greater than zero");
+    }
+    storage::set_instance(env, &config_key(env), config);
+}
+
+/// When `line`, which is Defaulted, defaulted.
+pub fn defaulted_at(env: &Env, line: &CreditLineData) -> u64 {
+    storage::get_persistent(env, &defaulted_at_key(&line.borrower)).unwrap_or(line.last_accrual_ts)
+}
+
+/// Record the default time of a credit line moving from `before` to `after`.
+pub fn track(env: &Env, before: Option<&CreditLineData>, after: &CreditLineData) {
+    let was = before.is_some_and(|line| line.status == CreditStatus::Defaulted);
+    let is = after.status == CreditStatus::Defaulted;
+    if !was && is {
+        storage::set_persistent(
+            env,
+            &defaulted_at_key(&after.borrower),
+            &env.ledger().timestamp(),
+        );
+    } else if was && !is {
+        storage::remove_persistent(env, &defaulted_at_key(&after.borrower));
+    }
+}
+
+pub fn transfer(env: &Env, from: &Address, to: &Address) {
+    if let Some(ts) = storage::get_persistent::<u64>(env, &defaulted_at_key(from)) {
+        storage::remove_persistent(env, &defaulted_at_key(from));
+        storage::set_persistent(env, &defaulted_at_key(to), &ts);
+    }
+}
@@ -0,0 +1,154 @@
+//! Credit delegation between users.
+//!
+//! A borrower can hand a slice of their unused limit to another address with
+//! `delegate_credit`. The delegatee draws on the slice with
+//! `draw_delegated_credit`: the funds go to the delegatee but the draw lands on
+//! the delegator's line, so the delegator carries the debt and the utilization.
+//! Undrawn slices are held against the delegator's limit until they expire or
+//! are revoked. A delegation keeps what its delegatee has drawn until it is
+//! revoked, and a delegator has at most `MAX_DELEGATEES` of them.
+
+use soroban_sdk::{contracttype, Address, Env, Vec};
+
+use crate::storage::{self, DataKey};
+
+/// Most delegatees a delegator can have at once.
+pub const MAX_DELEGATEES: u32 = 10;
+
+/// A slice of a delegator's limit drawable by one delegatee.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Delegation {
+    /// Amount the delegatee may still draw.
+    pub remaining: i128,
+    /// Amount the delegatee has drawn so far.
+    pub drawn: i128,
+    /// Timestamp from which the delegation can no longer be drawn.
+    pub expires_at: u64,
+}
+
+#[contracttype]
+#[derive(Clone)]
+pub enum DelegationKey {
+    /// Delegation by (delegator, delegatee) (persistent).
+    Grant(Address, Address),
+    /// Delegatees of a delegator (persistent).
+    Delegatees(Address),
+}
+
+fn grant_key(delegator: &Address, delegatee: &Address) -> DataKey {
+    DataKey::Delegation(DelegationKey::Grant(delegator.clone(), delegatee.clone()))
+}
+
+fn delegatees_key(delegator: &Address) -> DataKey {
+    DataKey::Delegation(DelegationKey::Delegatees(delegator.clone()))
+}
+
+pub fn get(env: &Env, delegator: &Address, delegatee: &Address) -> Option<Delegation> {
+    storage::get_persistent(env, &grant_key(delegator, delegatee))
+}
+
+pub fn delegatees(env: &Env, delegator: &Address) -> Vec<Address> {
+    storage::get_persistent(env, &delegatees_key(delegator)).unwrap_or(Vec::new(env))
+}
+
+fn is_live(env: &Env, delegation: &Delegation) -> bool {
+    env.ledger().timestamp() < delegation.expires_at
+}
+
+/// Undrawn amount of `delegator`'s live delegations, held against the limit.
+pub fn reserved(env: &Env, delegator: &Address) -> i128 {
+    let mut total: i128 = 0;
+    for delegatee in delegatees(env, delegator).iter() {
+        if let Some(delegation) = get(env, delegator, &delegatee) {
+            if is_live(env, &delegation) {
+                total = total.saturating_add(delegation.remaining);
+            }
+        }
+    }
+    total
+}
+
+/// Replace any delegation from `delegator` to `delegatee` with a fresh one of
+/// `amount` until `expires_at`. The caller checks that it fits under the limit.
+///
+/// # Panics
+/// * `"amount must be positive"`
+/// * `"cannot delegate to self"`
+/// * `"expiry must be in the future"`
+/// * `"too many delegations"` – the delegator already has `MAX_DELEGATEES`
+pub fn grant(
+    env: &Env,
+    delegator: &Address,
+    delegatee: &Address,
+    amount: i128,
+    expires_at: u64,
+) -> Delegation {
+    if amount <= 0 {
+        panic!("amount must be positive");
+    }
+    if delegator == delegatee {
+        panic!("cannot delegate to self");
+    }
+    if expires_at <= env.ledger().timestamp() {
+        panic!("expiry must be in the future");
+    }
+    let mut delegatees = delegatees(env, delegator);
+    if !delegatees.contains(delegatee) {
+        if delegatees.len() >= MAX_DELEGATEES {
+            panic!("too many delegations");
+        }
+        delegatees.push_back(delegatee.clone());
+        storage::set_persistent(env, &delegatees_key(delegator), &delegatees);
+    }
+    let delegation = Delegation {
+        remaining: amount,
+        drawn: 0,
+        expires_at,
+    };
+    storage::set_persistent(env, &grant_key(delegator, delegatee), &delegation);
+    delegation
+}
+
+/// Remove the delegation from `delegator` to `delegatee`, returning it.
+///
+/// # Panics
+/// * `"delegation not found"`
+pub fn revoke(env: &Env, delegator: &Address, delegatee: &Address) -> Delegation {
+    let delegation = get(env, delegator, delegatee).expect("delegation not found");
+    storage::remove_persistent(env, &grant_key(delegator, delegatee));
+    let mut delegatees = delegatees(env, delegator);
+    if let Some(index) = delegatees.first_index_of(delegatee) {
+        delegatees.remove(index);
+    }
+    if delegatees.is_empty() {
+        storage::remove_persistent(env, &delegatees_key(delegator));
+    } else {
+        storage::set_persistent(env, &delegatees_key(delegator), &delegatees);
+    }
+    delegation
+}
+
+/// Move `amount` of the delegation from remaining to drawn, returning it.
+///
+/// # Panics
+/// * `"amount must be positive"`
+/// * `"delegation not found"`
+/// * `"delegation expired"`
+/// * `"exceeds delegated credit"`
+pub fn consume(env: &Env, delegator: &Address, delegatee: &Address, amount: i128) -> Delegation {
+    if amount <= 0 {
+        panic!("amount must be positive");
+    }
+    let mut delegation = get(env, delegator, delegatee).expect("delegation not found");
+    if !is_live(env, &delegation) {
+        panic!("delegation expired");
+    }
+    if amount > delegation.remaining {
+        panic!("exceeds delegated credit");
+    }
+    delegation.remaining -= amount;
+    delegation.drawn += amount;
+    storage::set_persistent(env, &grant_key(delegator, delegatee), &delegation);
+    delegation
+}
@@ -0,0 +1,104 @@
+//! Delinquency and days past due.
+//!
+//! When `process_overdue` records a missed payment on an Active line the line
+//! becomes Delinquent, and `delinquent_since_ts` keeps the due date of the
+//! earliest unpaid cycle. Delinquent lines cannot draw. Repaying the arrears
+//! cures the line back to Active; missing enough cycles defaults it instead.
+
+use soroban_sdk::{Env, Symbol};
+
+use crate::boosts;
+use crate::delegation;
+use crate::math::SECONDS_PER_DAY;
+use crate::schedule;
+use crate::storage::{self, DataKey};
+use crate::streams;
+use crate::types::{CreditLineData, CreditStatus, LineHealth};
+
+/// Instance storage key for the number of Delinquent lines.
+fn count_key(env: &Env) -> DataKey {
+    DataKey::Config(Symbol::new(env, "delinq_n"))
+}
+
+/// Number of lines currently Delinquent.
+pub fn count(env: &Env) -> u32 {
+    storage::get_instance(env, &count_key(env)).unwrap_or(0)
+}
+
+/// Update the Delinquent count for a credit line moving from `before` to `after`.
+pub fn track(env: &Env, before: Option<&CreditLineData>, after: &CreditLineData) {
+    let was = before.is_some_and(|line| line.status == CreditStatus::Delinquent);
+    let is = after.status == CreditStatus::Delinquent;
+    if was != is {
+        let count = if is {
+            count(env).saturating_add(1)
+        } else {
+            count(env).saturating_sub(1)
+        };
+        storage::set_instance(env, &count_key(env), &count);
+    }
+}
+
+/// Record that the payment due at `missed_due_ts` was missed. An Active line
+/// becomes Delinquent; other statuses are left to the caller.
+pub fn mark(line: &mut CreditLineData, missed_due_ts: u64) {
+    if line.delinquent_since_ts == 0 {
+        line.delinquent_since_ts = missed_due_ts;
+    }
+    if line.status == CreditStatus::Active {
+        line.status = CreditStatus::Delinquent;
+    }
+}
+
+/// Clear delinquency once `line` owes no scheduled minimum. Returns true if a
+/// Delinquent line went back to Active.
+pub fn cure_if_current(line: &mut CreditLineData) -> bool {
+    if line.delinquent_since_ts == 0 || line.min_due_amount > 0 {
+        return false;
+    }
+    line.delinquent_since_ts = 0;
+    if line.status == CreditStatus::Delinquent {
+        line.status = CreditStatus::Active;
+        return true;
+    }
+    false
+}
+
+/// Health of `line` at the current ledger time.
+pub fn health(env: &Env, line: &CreditLineData) -> LineHealth {
+    let mut line = line.clone();
+    schedule::roll_forward(env, &mut line);
+    let overdue = schedule::is_overdue(env, &line);
+    let behind = overdue || line.delinquent_since_ts != 0;
+    let since = if line.delinquent_since_ts != 0 {
+        line.delinquent_since_ts
+    } else if overdue {
+        line.next_due_ts
+    } else {
+        0
+    };
+    let now = env.ledger().timestamp();
+    let days_past_due = if since != 0 && now > since {
+        ((now - since) / SECONDS_PER_DAY) as u32
+    } else {
+        0
+    };
+    let available = if line.status == CreditStatus::Active {
+        (boosts::effective_limit(env, &line)
+            - line.utilized_amount
+            - streams::locked(env, &line.borrower)
+            - delegation::reserved(env, &line.borrower))
+        .max(0)
+    } else {
+        0
+    };
+    LineHealth {
+        status: line.status,
+        utilized_amount: line.utilized_amount,
+        available,
+        amount_past_due: if behind { line.min_due_amount } else { 0 },
+        days_past_due,
+        delinquent_since_ts: line.delinquent_since_ts,
+        missed_cycles: line.missed_cycles,
+    }
+}
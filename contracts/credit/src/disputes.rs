@@ -0,0 +1,96 @@
+//! Customer-protection disputes on credit lines.
+//!
+//! The admin flags a line while the borrower disputes it, recording the
+//! off-chain dispute id. Until the dispute is resolved the line cannot be
+//! defaulted, by the admin or a keeper, and its limit cannot be lowered;
+//! repayments carry on as usual. The flag is the `restrictions::DISPUTED` bit,
+//! so it shows in `get_credit_line`; the dispute record is kept beside the line.
+
+use soroban_sdk::{contracttype, Address, Env};
+
+use crate::restrictions;
+use crate::storage::{self, DataKey};
+use crate::types::CreditLineData;
+
+/// Open dispute on a line.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Dispute {
+    /// Off-chain identifier of the dispute case.
+    pub dispute_id: u64,
+    pub flagged_at: u64,
+}
+
+fn dispute_key(borrower: &Address) -> DataKey {
+    DataKey::Dispute(borrower.clone())
+}
+
+pub fn get(env: &Env, borrower: &Address) -> Option<Dispute> {
+    storage::get_persistent(env, &dispute_key(borrower))
+}
+
+pub fn is_disputed(line: &CreditLineData) -> bool {
+    restrictions::is_frozen(line, restrictions::DISPUTED)
+}
+
+/// Flag `line` as disputed under `dispute_id`. The caller saves the line.
+///
+/// # Panics
+/// * `"credit line already disputed"`
+pub fn flag(env: &Env, line: &mut CreditLineData, dispute_id: u64) -> Dispute {
+    if is_disputed(line) {
+        panic!("credit line already disputed");
+    }
+    let dispute = Dispute {
+        dispute_id,
+        flagged_at: env.ledger().timestamp(),
+    };
+    line.restrictions |= restrictions::DISPUTED;
+    storage::set_persistent(env, &dispute_key(&line.borrower), &dispute);
+    dispute
+}
+
+/// Clear the dispute on `line` and return it. The caller saves the line.
+///
+/// # Panics
+/// * `"credit line not disputed"`
+pub fn resolve(env: &Env, line: &mut CreditLineData) -> Dispute {
+    let dispute = get(env, &line.borrower)
+        .filter(|_| is_disputed(line))
+        .expect("credit line not disputed");
+    line.restrictions &= !restrictions::DISPUTED;
+    storage::remove_persistent(env, &dispute_key(&line.borrower));
+    dispute
+}
+
+/// # Panics
+/// * `"credit line is disputed"`
+pub fn require_not_disputed(line: &CreditLineData) {
+    if is_disputed(line) {
+        panic!("credit line is disputed");
+    }
+}
+
+/// `credit_limit` is in the line's own units: reference units when it has a
+/// `reference_limit`, token units otherwise.
+///
+/// # Panics
+/// * `"credit line is disputed"` – `credit_limit` would lower a disputed line's limit
+pub fn require_limit_allowed(line: &CreditLineData, credit_limit: i128) {
+    let current = if line.reference_limit > 0 {
+        line.reference_limit
+    } else {
+        line.credit_limit
+    };
+    if credit_limit < current {
+        require_not_disputed(line);
+    }
+}
+
+/// Move the dispute record along with a transferred line.
+pub fn transfer(env: &Env, from: &Address, to: &Address) {
+    if let Some(dispute) = get(env, from) {
+        storage::remove_persistent(env, &dispute_key(from));
+        storage::set_persistent(env, &dispute_key(to), &dispute);
+    }
+}
@@ -0,0 +1,124 @@
+//! Minimum and maximum size of a single draw, and the minimum time between draws.
+//!
+//! Stops dust draws that waste ledger entries and whale draws that drain the
+//! reserve in one shot, and throttles rapid-fire draws from a compromised
+//! borrower key. A global `DrawLimits` applies to every line; a line's own
+//! `min_draw_amount` / `max_draw_amount` and its stored cooldown override it
+//! field by field. A bound of 0 means no bound.
+//!
+//! `CreditLineData` is at the contract-type field limit, so the per-line
+//! cooldown and the time of the line's last draw live in their own entries.
+
+use soroban_sdk::{contracttype, Address, Env, Symbol};
+
+use crate::storage::{self, DataKey};
+use crate::types::CreditLineData;
+
+/// Per-draw bounds (admin-configured). 0 disables a bound.
+#[contracttype]
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct DrawLimits {
+    pub min_draw_amount: i128,
+    pub max_draw_amount: i128,
+    /// Cooldown after a draw before the line can draw again.
+    pub min_seconds_between_draws: u64,
+}
+
+#[contracttype]
+#[derive(Clone)]
+pub enum DrawLimitKey {
+    /// Per-line cooldown override (persistent).
+    Cooldown(Address),
+    /// Timestamp of a line's last draw (persistent).
+    LastDraw(Address),
+}
+
+/// Instance storage key for the global draw limits.
+fn draw_limits_key(env: &Env) -> DataKey {
+    DataKey::Config(Symbol::new(env, "draw_lim"))
+}
+
+pub fn get_config(env: &Env) -> DrawLimits {
+    storage::get_instance(env, &draw_limits_key(env)).unwrap_or_default()
+}
+
+pub fn set_config(env: &Env, limits: &DrawLimits) {
+    validate(limits);
+    storage::set_instance(env, &draw_limits_key(env), limits);
+}
+
+/// # Panics
+/// * `"draw limits cannot be negative"`
+/// * `"min_draw_amount exceeds max_draw_amount"`
+pub fn validate(limits: &DrawLimits) {
+    if limits.min_draw_amount < 0 || limits.max_draw_amount < 0 {
+        panic!("draw limits cannot be negative");
+    }
+    if limits.max_draw_amount > 0 && limits.min_draw_amount > limits.max_draw_amount {
+        panic!("min_draw_amount exceeds max_draw_amount");
+    }
+}
+
+/// Bounds in force for `line`: its overrides where set, the global limits otherwise.
+pub fn effective(env: &Env, line: &CreditLineData) -> DrawLimits {
+    let global = get_config(env);
+    DrawLimits {
+        min_draw_amount: if line.min_draw_amount > 0 {
+            line.min_draw_amount
+        } else {
+            global.min_draw_amount
+        },
+        max_draw_amount: if line.max_draw_amount > 0 {
+            line.max_draw_amount
+        } else {
+            global.max_draw_amount
+        },
+        min_seconds_between_draws: match line_cooldown(env, &line.borrower) {
+            0 => global.min_seconds_between_draws,
+            cooldown => cooldown,
+        },
+    }
+}
+
+fn line_cooldown(env: &Env, borrower: &Address) -> u64 {
+    storage::get_persistent(
+        env,
+        &DataKey::DrawLimits(DrawLimitKey::Cooldown(borrower.clone())),
+    )
+    .unwrap_or(0)
+}
+
+/// Store `line`'s overrides from `limits`; the caller saves the line.
+pub fn set_line_limits(env: &Env, line: &mut CreditLineData, limits: &DrawLimits) {
+    line.min_draw_amount = limits.min_draw_amount;
+    line.max_draw_amount = limits.max_draw_amount;
+    let key = DataKey::DrawLimits(DrawLimitKey::Cooldown(line.borrower.clone()));
+    if limits.min_seconds_between_draws == 0 {
+        storage::remove_persistent(env, &key);
+    } else {
+        storage::set_persistent(env, &key, &limits.min_seconds_between_draws);
+    }
+}
+
+/// True while `borrower` is within the cooldown of `limits` since its last draw.
+pub fn cooling_down(env: &Env, borrower: &Address, limits: &DrawLimits) -> bool {
+    if limits.min_seconds_between_draws == 0 {
+        return false;
+    }
+    let last_draw: Option<u64> = storage::get_persistent(
+        env,
+        &DataKey::DrawLimits(DrawLimitKey::LastDraw(borrower.clone())),
+    );
+    last_draw.is_some_and(|last| {
+        env.ledger().timestamp() < last.saturating_add(limits.min_seconds_between_draws)
+    })
+}
+
+/// Record a draw by `borrower` at the current time.
+pub fn record_draw(env: &Env, borrower: &Address) {
+    storage::set_persistent(
+        env,
+        &DataKey::DrawLimits(DrawLimitKey::LastDraw(borrower.clone())),
+        &env.ledger().timestamp(),
+    );
+}
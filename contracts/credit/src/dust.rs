@@ -0,0 +1,28 @@
+//! Dust tolerance for borrower-initiated closes.
+//!
+//! Interest accrual can leave a residue too small to be worth a separate
+//! repayment. A borrower may close a line whose utilized amount is at or below
+//! the admin-configured threshold; the residue is written off. The default
+//! threshold of 0 requires the line to be fully repaid.
+
+use soroban_sdk::{Env, Symbol};
+
+use crate::storage::{self, DataKey};
+
+/// Instance storage key for the dust threshold.
+fn dust_key(env: &Env) -> DataKey {
+    DataKey::Config(Symbol::new(env, "dust"))
+}
+
+pub fn get_threshold(env: &Env) -> i128 {
+    storage::get_instance(env, &dust_key(env)).unwrap_or(0)
+}
+
+/// # Panics
+/// * `"dust threshold must be non-negative"`
+pub fn set_threshold(env: &Env, threshold: i128) {
+    if threshold < 0 {
+        panic!("dust threshold must be non-negative");
+    }
+    storage::set_instance(env, &dust_key(env), &threshold);
+}
@@ -0,0 +1,51 @@
+//! Contract error codes for the Credit contract.
+
+use soroban_sdk::contracterror;
+
+/// Typed errors surfaced to callers via `panic_with_error!`, and reported by the
+/// `preview_draw` / `preview_repay` views for the checks they simulate.
+#[contracterror]
+#[derive(Clone, Copy, Debug, Eq, PartialEq, PartialOrd, Ord)]
+#[repr(u32)]
+pub enum ContractError {
+    /// The requested operation is paused.
+    Paused = 1,
+    /// An operation with the same `op_id` was already applied.
+    DuplicateOperation = 2,
+    /// A draw would exceed a velocity cap.
+    RateLimited = 3,
+    /// The borrower has no credit line.
+    LineNotFound = 4,
+    /// The credit line is closed.
+    LineClosed = 5,
+    /// The credit line is not Active.
+    LineNotActive = 6,
+    /// The amount is not positive.
+    InvalidAmount = 7,
+    /// The operation is frozen on the line by its restrictions.
+    Restricted = 8,
+    /// The line has reached its maturity.
+    Matured = 9,
+    /// The draw is outside the line's minimum or maximum draw amount.
+    DrawLimit = 10,
+    /// The draw is above the large-draw threshold and needs approval.
+    ApprovalRequired = 11,
+    /// The draw would exceed the credit limit.
+    CreditLimitExceeded = 12,
+    /// The draw would exceed the collateralized limit.
+    CollateralLimitExceeded = 13,
+    /// The draw would take the reserve below its minimum ratio.
+    ReserveTooLow = 14,
+    /// An intermediate amount overflowed `i128`, or a divisor was not positive.
+    Overflow = 15,
+    /// The line drew within its draw cooldown.
+    DrawCooldown = 16,
+    /// The draw's purpose is not among the line's allowed purposes.
+    PurposeNotAllowed = 17,
+    /// The borrower is not on the allowlist while the gate is enabled.
+    NotAllowlisted = 18,
+    /// The borrower or the draw's recipient is on the blocklist.
+    Blocked = 19,
+    /// The draw would take protocol or tier exposure above its cap.
+    ExposureCapExceeded = 20,
+}
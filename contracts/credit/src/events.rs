@@ -1,9 +1,57 @@
 //! Event types and topic constants for the Credit contract.
 //! Stable event schemas for indexing and analytics.
+//!
+//! Every topic starts with `"credit"` followed by the event name. Events about
+//! a single borrower's line carry the borrower `Address` as a third topic, so
+//! indexers can subscribe to one borrower's activity:
+//!
+//! | Topic | Data |
+//! |---|---|
+//! | `("credit", <lifecycle>, borrower)` | `CreditLineEvent` (`opened`, `suspend`, `resume`, `delinq`, `cured`, `closed`, `default`, …) |
+//! | `("credit", "drawn", borrower)` | `DrawnEvent` |
+//! | `("credit", "draw", borrower)` | `(borrower, amount, new_utilized_amount)` |
+//! | `("credit", "repay", borrower)` | `RepaymentEvent` |
+//! | `("credit", "restrict", borrower)` | `RestrictionsEvent` |
+//! | `("credit", "boost", borrower)` | `LimitBoostEvent` |
+//!
+//! Other events use a two-part `("credit", <name>)` topic, except
+//! configuration changes, which are published as `("credit", "config", <name>)`
+//! with `(event_version, sequence, new_value)` as data so the configuration can
+//! be rebuilt from the event stream alone.
+//!
+//! Every event struct carries `event_version`, the schema version it was
+//! published under, and `sequence`, a contract-wide counter that increases by
+//! one per event. Indexers can use `sequence` to detect gaps and drop
+//! duplicates, and `event_version` to decode events from before an upgrade.
 
-use soroban_sdk::{contracttype, symbol_short, Address, Env, Symbol};
+use soroban_sdk::{contracttype, symbol_short, Address, BytesN, Env, IntoVal, Symbol, Val};
 
-use crate::types::CreditStatus;
+use crate::amortization::RepaymentMode;
+use crate::reserve::ReserveLevel;
+use crate::storage::{self, DataKey};
+use crate::timelock::TimelockAction;
+use crate::types::{CreditStatus, RateMode};
+
+/// Schema version of the event structs below. Bump when a field is added,
+/// removed, or changes meaning.
+pub const EVENT_VERSION: u32 = 6;
+
+/// Instance storage key for the last sequence number handed out.
+fn sequence_key(env: &Env) -> DataKey {
+    DataKey::Config(Symbol::new(env, "event_seq"))
+}
+
+/// Last sequence number handed out; 0 before the first event.
+pub fn current_sequence(env: &Env) -> u64 {
+    storage::get_instance(env, &sequence_key(env)).unwrap_or(0)
+}
+
+/// Take the next sequence number for an event about to be published.
+pub fn next_sequence(env: &Env) -> u64 {
+    let sequence = current_sequence(env).checked_add(1).expect("overflow");
+    storage::set_instance(env, &sequence_key(env), &sequence);
+    sequence
+}
 
 /// Event emitted when a credit line lifecycle event occurs (opened, suspend, closed, default).
 #[contracttype]
@@ -14,7 +62,17 @@ pub struct CreditLineEvent {
     pub status: CreditStatus,
     pub credit_limit: i128,
     pub interest_rate_bps: u32,
+    pub rate_mode: RateMode,
     pub risk_score: u32,
+    /// Suspension reason code; 0 unless the line is suspended.
+    pub suspension_reason: u32,
+    /// Time the suspension lifts; 0 for none or an indefinite suspension.
+    pub suspended_until: u64,
+    /// True when elapsed time triggered the change rather than a caller's decision:
+    /// a keeper auto-default, or an expired suspension lifted by a draw.
+    pub auto: bool,
+    pub event_version: u32,
+    pub sequence: u64,
 }
 
 /// Event emitted when a borrower repays credit.
@@ -24,8 +82,81 @@ pub struct CreditLineEvent {
 pub struct RepaymentEvent {
     pub borrower: Address,
     pub amount: i128,
+    /// Excess returned to the borrower by `repay_credit_with_refund`; 0 otherwise.
+    pub refunded: i128,
     pub new_utilized_amount: i128,
     pub timestamp: u64,
+    /// Part of `amount` that reduced principal.
+    pub principal_portion: i128,
+    /// Part of `amount` that settled accrued interest.
+    pub interest_portion: i128,
+    /// Part of `interest_portion` booked as protocol fees under the revenue split.
+    pub fee_portion: i128,
+    /// Charged on top of `amount` for paying off a term line before maturity.
+    pub prepayment_penalty: i128,
+    pub event_version: u32,
+    pub sequence: u64,
+}
+
+/// Event emitted when interest collected on a repayment is divided between the buckets.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct RevenueSplitEvent {
+    pub token: Address,
+    pub interest: i128,
+    pub lp_share: i128,
+    pub treasury_share: i128,
+    pub insurance_share: i128,
+    pub event_version: u32,
+    pub sequence: u64,
+}
+
+/// Publish a revenue split event.
+pub fn publish_revenue_split(env: &Env, event: RevenueSplitEvent) {
+    env.events()
+        .publish((symbol_short!("credit"), symbol_short!("rev_split")), event);
+}
+
+/// Event emitted when a borrower accrues reward points (`rwd_accr`) or claims them (`rwd_claim`).
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct RewardEvent {
+    pub borrower: Address,
+    pub points: i128,
+    /// Unclaimed points afterwards.
+    pub balance: i128,
+    pub event_version: u32,
+    pub sequence: u64,
+}
+
+/// Publish a reward accrual or claim event.
+pub fn publish_reward_event(env: &Env, action: Symbol, event: RewardEvent) {
+    env.events().publish(
+        (symbol_short!("credit"), action, event.borrower.clone()),
+        event,
+    );
+}
+
+/// Event emitted when a borrower stakes (`stake`) or unstakes (`unstake`).
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct StakeEvent {
+    pub borrower: Address,
+    pub amount: i128,
+    /// The borrower's stake afterwards.
+    pub staked: i128,
+    /// Discount the stake now earns, in basis points.
+    pub discount_bps: u32,
+    pub event_version: u32,
+    pub sequence: u64,
+}
+
+/// Publish a stake or unstake event.
+pub fn publish_staking_event(env: &Env, action: Symbol, event: StakeEvent) {
+    env.events().publish(
+        (symbol_short!("credit"), action, event.borrower.clone()),
+        event,
+    );
 }
 
 /// Event emitted when admin updates risk parameters for a credit line.
@@ -35,7 +166,14 @@ pub struct RiskParametersUpdatedEvent {
     pub borrower: Address,
     pub credit_limit: i128,
     pub interest_rate_bps: u32,
+    pub rate_mode: RateMode,
     pub risk_score: u32,
+    /// Rate the line accrues at now: the promotional rate while one runs.
+    pub active_rate_bps: u32,
+    /// End of the promotional rate; 0 when there is none.
+    pub promo_until_ts: u64,
+    pub event_version: u32,
+    pub sequence: u64,
 }
 
 /// Event emitted when a borrower draws credit.
@@ -43,26 +181,49 @@ pub struct RiskParametersUpdatedEvent {
 #[derive(Clone, Debug, Eq, PartialEq)]
 pub struct DrawnEvent {
     pub borrower: Address,
+    /// Address that received the drawn funds (the borrower unless drawn via `draw_credit_to`).
+    pub recipient: Address,
     pub amount: i128,
     pub new_utilized_amount: i128,
     pub timestamp: u64,
+    /// Credit limit left undrawn after this draw.
+    pub remaining_available: i128,
+    /// Token the draw was paid out in.
+    pub token: Address,
+    /// Purpose code the draw was tagged with, if any.
+    pub purpose: Option<Symbol>,
+    pub event_version: u32,
+    pub sequence: u64,
 }
 
 /// Publish a credit line lifecycle event.
 pub fn publish_credit_line_event(env: &Env, topic: (Symbol, Symbol), event: CreditLineEvent) {
-    env.events().publish(topic, event);
+    env.events()
+        .publish((topic.0, topic.1, event.borrower.clone()), event);
 }
 
 /// Publish a repayment event.
 pub fn publish_repayment_event(env: &Env, event: RepaymentEvent) {
-    env.events()
-        .publish((symbol_short!("credit"), symbol_short!("repay")), event);
+    env.events().publish(
+        (
+            symbol_short!("credit"),
+            symbol_short!("repay"),
+            event.borrower.clone(),
+        ),
+        event,
+    );
 }
 
 /// Publish a drawn event.
 pub fn publish_drawn_event(env: &Env, event: DrawnEvent) {
-    env.events()
-        .publish((symbol_short!("credit"), symbol_short!("drawn")), event);
+    env.events().publish(
+        (
+            symbol_short!("credit"),
+            symbol_short!("drawn"),
+            event.borrower.clone(),
+        ),
+        event,
+    );
 }
 
 /// Publish a risk parameters updated event.
@@ -70,3 +231,872 @@ pub fn publish_risk_parameters_updated(env: &Env, event: RiskParametersUpdatedEv
     env.events()
         .publish((symbol_short!("credit"), symbol_short!("risk_upd")), event);
 }
+
+/// Event emitted when pause flags change.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct PauseEvent {
+    pub caller: Address,
+    pub draws_paused: bool,
+    pub repays_paused: bool,
+    pub event_version: u32,
+    pub sequence: u64,
+}
+
+/// Publish a pause event.
+pub fn publish_pause_event(env: &Env, event: PauseEvent) {
+    env.events()
+        .publish((symbol_short!("credit"), symbol_short!("paused")), event);
+}
+
+/// Publish an unpause event.
+pub fn publish_unpause_event(env: &Env, event: PauseEvent) {
+    env.events()
+        .publish((symbol_short!("credit"), symbol_short!("unpaused")), event);
+}
+
+/// Event emitted when a keeper processes a missed payment.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct OverdueProcessedEvent {
+    pub borrower: Address,
+    pub keeper: Address,
+    pub missed_cycles: u32,
+    pub status: CreditStatus,
+    pub keeper_reward: i128,
+    pub event_version: u32,
+    pub sequence: u64,
+}
+
+/// Publish an overdue processed event.
+pub fn publish_overdue_processed(env: &Env, event: OverdueProcessedEvent) {
+    env.events()
+        .publish((symbol_short!("credit"), symbol_short!("overdue")), event);
+}
+
+/// Event emitted when collateral is deposited or returned.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct CollateralEvent {
+    pub borrower: Address,
+    pub token: Address,
+    pub amount: i128,
+    pub new_collateral_amount: i128,
+    pub event_version: u32,
+    pub sequence: u64,
+}
+
+/// Publish a collateral deposited event.
+pub fn publish_collateral_deposited(env: &Env, event: CollateralEvent) {
+    env.events()
+        .publish((symbol_short!("credit"), symbol_short!("col_dep")), event);
+}
+
+/// Publish a collateral returned event.
+pub fn publish_collateral_returned(env: &Env, event: CollateralEvent) {
+    env.events()
+        .publish((symbol_short!("credit"), symbol_short!("col_ret")), event);
+}
+
+/// Event emitted when collateral is seized from a defaulted credit line.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct LiquidationEvent {
+    pub borrower: Address,
+    pub liquidator: Address,
+    pub collateral_token: Address,
+    pub seized_amount: i128,
+    pub keeper_bonus: i128,
+    pub recovered_value: i128,
+    /// Principal and interest left after the recovery (bad debt if no collateral remains).
+    pub remaining_debt: i128,
+    pub event_version: u32,
+    pub sequence: u64,
+}
+
+/// Publish a liquidation event.
+pub fn publish_liquidation_event(env: &Env, event: LiquidationEvent) {
+    env.events()
+        .publish((symbol_short!("credit"), symbol_short!("liquidate")), event);
+}
+
+/// Event emitted when a lender deposits or withdraws liquidity.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct LiquidityEvent {
+    pub lender: Address,
+    pub token: Address,
+    pub amount: i128,
+    /// Shares minted (deposit) or burned (withdrawal).
+    pub shares: i128,
+    pub new_share_balance: i128,
+    pub event_version: u32,
+    pub sequence: u64,
+}
+
+/// Publish a liquidity deposit event.
+pub fn publish_liquidity_deposited(env: &Env, event: LiquidityEvent) {
+    env.events()
+        .publish((symbol_short!("credit"), symbol_short!("lp_dep")), event);
+}
+
+/// Publish a liquidity withdrawal event.
+pub fn publish_liquidity_withdrawn(env: &Env, event: LiquidityEvent) {
+    env.events()
+        .publish((symbol_short!("credit"), symbol_short!("lp_wd")), event);
+}
+
+/// Event emitted when accumulated protocol fees are sent to the treasury.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ProtocolFeesCollectedEvent {
+    pub caller: Address,
+    pub treasury: Address,
+    pub token: Address,
+    pub amount: i128,
+    pub event_version: u32,
+    pub sequence: u64,
+}
+
+/// Publish a protocol fees collected event.
+pub fn publish_protocol_fees_collected(env: &Env, event: ProtocolFeesCollectedEvent) {
+    env.events()
+        .publish((symbol_short!("credit"), symbol_short!("fees_out")), event);
+}
+
+/// Event emitted when stray tokens sent to the contract are swept out.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct SweepEvent {
+    pub caller: Address,
+    pub token: Address,
+    pub to: Address,
+    pub amount: i128,
+    pub event_version: u32,
+    pub sequence: u64,
+}
+
+/// Publish a sweep event.
+pub fn publish_sweep(env: &Env, event: SweepEvent) {
+    env.events()
+        .publish((symbol_short!("credit"), symbol_short!("sweep")), event);
+}
+
+/// Event emitted when a draw or origination fee is charged to a borrower.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct FeeChargedEvent {
+    pub borrower: Address,
+    pub fee_type: Symbol,
+    pub amount: i128,
+    pub event_version: u32,
+    pub sequence: u64,
+}
+
+/// Publish a fee charged event.
+pub fn publish_fee_charged(env: &Env, event: FeeChargedEvent) {
+    env.events()
+        .publish((symbol_short!("credit"), symbol_short!("fee")), event);
+}
+
+/// Event emitted when the admin writes off debt on a defaulted line.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct WriteOffEvent {
+    pub borrower: Address,
+    pub amount: i128,
+    pub written_off_amount: i128,
+    pub remaining_outstanding: i128,
+    pub event_version: u32,
+    pub sequence: u64,
+}
+
+/// Publish a write-off event.
+pub fn publish_write_off(env: &Env, event: WriteOffEvent) {
+    env.events()
+        .publish((symbol_short!("credit"), symbol_short!("write_off")), event);
+}
+
+/// Event emitted when part of a line's debt is forgiven.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ForgivenessEvent {
+    pub borrower: Address,
+    /// Amount forgiven, principal and interest.
+    pub amount: i128,
+    pub interest_portion: i128,
+    pub principal_portion: i128,
+    pub reason_code: u32,
+    /// Second approver, if one co-signed.
+    pub approver: Option<Address>,
+    pub remaining_outstanding: i128,
+    pub event_version: u32,
+    pub sequence: u64,
+}
+
+/// Publish a forgiveness event.
+pub fn publish_forgiveness(env: &Env, event: ForgivenessEvent) {
+    env.events().publish(
+        (
+            symbol_short!("credit"),
+            symbol_short!("forgive"),
+            event.borrower.clone(),
+        ),
+        event,
+    );
+}
+
+/// Event emitted when a line's metadata hash is set or cleared.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct MetadataHashEvent {
+    pub borrower: Address,
+    pub previous: Option<BytesN<32>>,
+    pub metadata_hash: Option<BytesN<32>>,
+    pub event_version: u32,
+    pub sequence: u64,
+}
+
+/// Publish a metadata hash event.
+pub fn publish_metadata_hash(env: &Env, event: MetadataHashEvent) {
+    env.events().publish(
+        (
+            symbol_short!("credit"),
+            symbol_short!("metadata"),
+            event.borrower.clone(),
+        ),
+        event,
+    );
+}
+
+/// Event emitted when a line's repayment mode is set.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct RepaymentModeEvent {
+    pub borrower: Address,
+    pub mode: RepaymentMode,
+    /// Due date the mode took effect at; it bills from the next cycle on.
+    pub start_due_ts: u64,
+    pub event_version: u32,
+    pub sequence: u64,
+}
+
+/// Publish a repayment mode event.
+pub fn publish_repayment_mode(env: &Env, event: RepaymentModeEvent) {
+    env.events().publish(
+        (
+            symbol_short!("credit"),
+            symbol_short!("pay_mode"),
+            event.borrower.clone(),
+        ),
+        event,
+    );
+}
+
+/// Event emitted when a borrower enables (`ap_on`) or disables (`ap_off`)
+/// autopay. `max_per_cycle` is 0 when disabled.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct AutopayConsentEvent {
+    pub borrower: Address,
+    pub max_per_cycle: i128,
+    pub event_version: u32,
+    pub sequence: u64,
+}
+
+/// Publish an autopay consent event.
+pub fn publish_autopay_consent(env: &Env, action: Symbol, event: AutopayConsentEvent) {
+    env.events().publish(
+        (symbol_short!("credit"), action, event.borrower.clone()),
+        event,
+    );
+}
+
+/// Event emitted when autopay pulls a payment.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct AutopayEvent {
+    pub borrower: Address,
+    pub amount: i128,
+    /// Due date the payment was made towards.
+    pub due_ts: u64,
+    /// Minimum still owed for `due_ts`; 0 once the cycle is paid.
+    pub min_due_remaining: i128,
+    pub event_version: u32,
+    pub sequence: u64,
+}
+
+/// Publish an autopay event.
+pub fn publish_autopay(env: &Env, event: AutopayEvent) {
+    env.events().publish(
+        (
+            symbol_short!("credit"),
+            symbol_short!("autopay"),
+            event.borrower.clone(),
+        ),
+        event,
+    );
+}
+
+/// Event emitted when a scheduled line's statement cycle is closed. The
+/// closing balance equals the opening balance plus draws and interest charged,
+/// less payments; fees are already included in draws.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct StatementEvent {
+    pub borrower: Address,
+    pub cycle_start: u64,
+    pub cycle_end: u64,
+    /// Principal plus accrued interest at the start of the cycle.
+    pub opening_balance: i128,
+    pub closing_balance: i128,
+    pub draws: i128,
+    pub interest_charged: i128,
+    /// Draw fees, insurance premiums and prepayment penalties charged.
+    pub fees: i128,
+    pub payments: i128,
+    pub min_due: i128,
+    pub next_due_ts: u64,
+    pub event_version: u32,
+    pub sequence: u64,
+}
+
+/// Publish a statement event.
+pub fn publish_statement(env: &Env, event: StatementEvent) {
+    env.events().publish(
+        (
+            symbol_short!("credit"),
+            symbol_short!("statement"),
+            event.borrower.clone(),
+        ),
+        event,
+    );
+}
+
+/// Event emitted when debt from another lender is refinanced onto a line.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct RefinanceEvent {
+    pub borrower: Address,
+    pub payoff_recipient: Address,
+    /// Amount drawn on the line.
+    pub amount: i128,
+    /// Amount paid to `payoff_recipient`, net of the draw fee and premium.
+    pub paid_out: i128,
+    /// The line's limit after any extension.
+    pub credit_limit: i128,
+    /// True if the line was opened for the refinancing.
+    pub opened: bool,
+    pub event_version: u32,
+    pub sequence: u64,
+}
+
+/// Publish a refinancing event.
+pub fn publish_refinance(env: &Env, event: RefinanceEvent) {
+    env.events().publish(
+        (
+            symbol_short!("credit"),
+            symbol_short!("refinance"),
+            event.borrower.clone(),
+        ),
+        event,
+    );
+}
+
+/// Event emitted when a flash draw is repaid.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct FlashDrawEvent {
+    pub receiver: Address,
+    pub token: Address,
+    pub amount: i128,
+    pub fee: i128,
+    pub event_version: u32,
+    pub sequence: u64,
+}
+
+/// Publish a flash draw event.
+pub fn publish_flash_draw(env: &Env, event: FlashDrawEvent) {
+    env.events().publish(
+        (
+            symbol_short!("credit"),
+            symbol_short!("flash"),
+            event.receiver.clone(),
+        ),
+        event,
+    );
+}
+
+/// Event emitted when a borrower delegates credit (`dlg_new`), revokes a
+/// delegation (`dlg_rev`), or a delegatee draws on one (`dlg_draw`). `amount`
+/// is the slice granted, the undrawn amount released, or the amount drawn.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct DelegationEvent {
+    pub delegator: Address,
+    pub delegatee: Address,
+    pub amount: i128,
+    /// Amount the delegatee may still draw afterwards.
+    pub remaining: i128,
+    /// Amount the delegatee has drawn in total.
+    pub drawn: i128,
+    pub expires_at: u64,
+    pub event_version: u32,
+    pub sequence: u64,
+}
+
+/// Publish a delegation event.
+pub fn publish_delegation_event(env: &Env, action: Symbol, event: DelegationEvent) {
+    env.events().publish(
+        (symbol_short!("credit"), action, event.delegator.clone()),
+        event,
+    );
+}
+
+/// Event emitted when a borrower approves or revokes a draw operator.
+/// A revocation is reported with `max_amount` and `expiry_ledger` set to zero.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct OperatorApprovedEvent {
+    pub borrower: Address,
+    pub operator: Address,
+    pub max_amount: i128,
+    pub expiry_ledger: u32,
+    pub event_version: u32,
+    pub sequence: u64,
+}
+
+/// Publish an operator approval event.
+pub fn publish_operator_approved(env: &Env, topic: (Symbol, Symbol), event: OperatorApprovedEvent) {
+    env.events().publish(topic, event);
+}
+
+/// Event emitted when a draw leaves a token's reserve ratio below the warning threshold.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ReserveLowEvent {
+    pub token: Address,
+    pub reserve: i128,
+    pub outstanding: i128,
+    pub ratio_bps: i128,
+    pub event_version: u32,
+    pub sequence: u64,
+}
+
+/// Publish a low reserve warning.
+pub fn publish_reserve_low(env: &Env, event: ReserveLowEvent) {
+    env.events()
+        .publish((symbol_short!("credit"), symbol_short!("res_low")), event);
+}
+
+/// Event emitted when a draw takes a token's reserve ratio below a threshold it was at or above.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ReserveThresholdEvent {
+    pub token: Address,
+    pub level: ReserveLevel,
+    pub threshold_bps: u32,
+    pub reserve: i128,
+    pub outstanding: i128,
+    /// Ratio after the draw.
+    pub ratio_bps: i128,
+    pub event_version: u32,
+    pub sequence: u64,
+}
+
+/// Publish a reserve threshold crossing.
+pub fn publish_reserve_threshold(env: &Env, event: ReserveThresholdEvent) {
+    env.events().publish(
+        (
+            symbol_short!("credit"),
+            symbol_short!("res_thr"),
+            event.token.clone(),
+        ),
+        event,
+    );
+}
+
+/// Event emitted when a large draw is requested (`ld_req`) or approved and executed (`ld_appr`).
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct LargeDrawEvent {
+    pub borrower: Address,
+    pub request_id: u64,
+    pub amount: i128,
+    pub expiry_ledger: u32,
+    pub event_version: u32,
+    pub sequence: u64,
+}
+
+/// Publish a large draw request or approval event.
+pub fn publish_large_draw_event(env: &Env, topic: (Symbol, Symbol), event: LargeDrawEvent) {
+    env.events().publish(topic, event);
+}
+
+/// Event emitted when the admin corrects a line's utilized amount.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct AdjustmentEvent {
+    pub borrower: Address,
+    pub delta: i128,
+    /// Operator-defined code for why the correction was made.
+    pub reason_code: u32,
+    pub old_utilized_amount: i128,
+    pub new_utilized_amount: i128,
+    pub event_version: u32,
+    pub sequence: u64,
+}
+
+/// Publish a utilized amount correction event.
+pub fn publish_adjustment(env: &Env, event: AdjustmentEvent) {
+    let topics = (
+        symbol_short!("credit"),
+        symbol_short!("adjust"),
+        event.borrower.clone(),
+    );
+    env.events().publish(topics, event);
+}
+
+/// Event emitted when a draw is scheduled (`sd_new`), executed (`sd_exec`), or
+/// cancelled (`sd_cancel`).
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ScheduledDrawEvent {
+    pub borrower: Address,
+    pub draw_id: u64,
+    pub amount: i128,
+    pub execute_after_ts: u64,
+    pub event_version: u32,
+    pub sequence: u64,
+}
+
+/// Publish a scheduled draw event.
+pub fn publish_scheduled_draw_event(env: &Env, topic: (Symbol, Symbol), event: ScheduledDrawEvent) {
+    env.events().publish(topic, event);
+}
+
+/// Event emitted when a stream is opened (`stream`) or claimed from (`stream_cl`).
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct StreamEvent {
+    pub borrower: Address,
+    pub total_amount: i128,
+    /// Amount claimed by this call; 0 on open.
+    pub amount: i128,
+    /// Amount claimed so far, including this call.
+    pub claimed: i128,
+    pub start_ts: u64,
+    pub duration_secs: u64,
+    pub event_version: u32,
+    pub sequence: u64,
+}
+
+/// Publish a stream open or claim event.
+pub fn publish_stream_event(env: &Env, topic: (Symbol, Symbol), event: StreamEvent) {
+    env.events().publish(topic, event);
+}
+
+/// Event emitted when a credit line's maturity is set or the line is renewed.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct MaturityEvent {
+    pub borrower: Address,
+    pub maturity_ts: u64,
+    pub credit_limit: i128,
+    pub interest_rate_bps: u32,
+    pub event_version: u32,
+    pub sequence: u64,
+}
+
+/// Publish a maturity or renewal event.
+pub fn publish_maturity_event(env: &Env, topic: (Symbol, Symbol), event: MaturityEvent) {
+    env.events().publish(topic, event);
+}
+
+/// Event emitted when the insurance fund absorbs a defaulted line's loss.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct InsurancePayoutEvent {
+    pub borrower: Address,
+    pub token: Address,
+    pub covered: i128,
+    pub uncovered: i128,
+    pub event_version: u32,
+    pub sequence: u64,
+}
+
+/// Publish an insurance payout event.
+pub fn publish_insurance_payout(env: &Env, event: InsurancePayoutEvent) {
+    env.events()
+        .publish((symbol_short!("credit"), symbol_short!("insured")), event);
+}
+
+/// Event emitted when a defaulted line's claim is sold to a collector.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct DebtAssignedEvent {
+    pub borrower: Address,
+    pub collector: Address,
+    pub price: i128,
+    pub outstanding: i128,
+    pub event_version: u32,
+    pub sequence: u64,
+}
+
+/// Publish a debt assigned event.
+pub fn publish_debt_assigned(env: &Env, event: DebtAssignedEvent) {
+    env.events()
+        .publish((symbol_short!("credit"), symbol_short!("assigned")), event);
+}
+
+/// Event emitted when a credit line position moves to a new borrower.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct PositionTransferEvent {
+    pub position_id: u64,
+    pub old_borrower: Address,
+    pub new_borrower: Address,
+    pub event_version: u32,
+    pub sequence: u64,
+}
+
+/// Publish a position transfer event.
+pub fn publish_position_transfer(env: &Env, event: PositionTransferEvent) {
+    env.events()
+        .publish((symbol_short!("credit"), symbol_short!("transfer")), event);
+}
+
+/// Event emitted once when the contract is initialized.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct InitializedEvent {
+    pub admin: Address,
+    /// Default token for new lines; also the first supported token.
+    pub token: Address,
+    pub event_version: u32,
+    pub sequence: u64,
+}
+
+/// Publish an initialized event.
+pub fn publish_initialized(env: &Env, event: InitializedEvent) {
+    env.events()
+        .publish((symbol_short!("credit"), symbol_short!("init")), event);
+}
+
+/// Event emitted when a token is added to or removed from the supported denominations.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct LiquidityTokenSetEvent {
+    pub token: Address,
+    pub supported: bool,
+    pub event_version: u32,
+    pub sequence: u64,
+}
+
+/// Publish a liquidity token set event.
+pub fn publish_liquidity_token_set(env: &Env, event: LiquidityTokenSetEvent) {
+    env.events()
+        .publish((symbol_short!("credit"), symbol_short!("token_set")), event);
+}
+
+/// Event emitted when a liquidity source is registered, re-prioritized, or removed.
+/// A removal is reported with `registered` false and `priority` zero.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct LiquiditySourceSetEvent {
+    pub source: Address,
+    pub priority: u32,
+    pub registered: bool,
+    pub event_version: u32,
+    pub sequence: u64,
+}
+
+/// Publish a liquidity source set event.
+pub fn publish_liquidity_source_set(env: &Env, event: LiquiditySourceSetEvent) {
+    env.events()
+        .publish((symbol_short!("credit"), symbol_short!("src_set")), event);
+}
+
+/// Event emitted when a treasurer moves liquidity between sources.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ReserveRebalancedEvent {
+    pub caller: Address,
+    pub token: Address,
+    pub from: Address,
+    pub to: Address,
+    pub amount: i128,
+    /// Liquidity available to draws afterwards: the reserve plus what sources can supply.
+    pub total_available: i128,
+    pub event_version: u32,
+    pub sequence: u64,
+}
+
+/// Publish a reserve rebalance event.
+pub fn publish_reserve_rebalanced(env: &Env, event: ReserveRebalancedEvent) {
+    env.events()
+        .publish((symbol_short!("credit"), symbol_short!("rebalance")), event);
+}
+
+/// Publish the new value of the configuration entry `name`.
+pub fn publish_config_updated<V: IntoVal<Env, Val>>(env: &Env, name: Symbol, value: V) {
+    let value: Val = value.into_val(env);
+    env.events().publish(
+        (symbol_short!("credit"), symbol_short!("config"), name),
+        (EVENT_VERSION, next_sequence(env), value),
+    );
+}
+
+/// Event emitted when the admin changes the operations frozen on a line.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct RestrictionsEvent {
+    pub borrower: Address,
+    /// New restriction flags; 0 when all are lifted.
+    pub restrictions: u32,
+    pub event_version: u32,
+    pub sequence: u64,
+}
+
+/// Publish a restrictions set event.
+pub fn publish_restrictions_set(env: &Env, event: RestrictionsEvent) {
+    env.events().publish(
+        (
+            symbol_short!("credit"),
+            symbol_short!("restrict"),
+            event.borrower.clone(),
+        ),
+        event,
+    );
+}
+
+/// Event emitted when a limit boost is granted, or found expired and cleared.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct LimitBoostEvent {
+    pub borrower: Address,
+    /// Boost granted, or the boost that lapsed when `expired` is set.
+    pub amount: i128,
+    pub expires_at: u64,
+    pub expired: bool,
+    pub event_version: u32,
+    pub sequence: u64,
+}
+
+/// Publish a limit boost event.
+pub fn publish_limit_boost(env: &Env, event: LimitBoostEvent) {
+    env.events().publish(
+        (
+            symbol_short!("credit"),
+            symbol_short!("boost"),
+            event.borrower.clone(),
+        ),
+        event,
+    );
+}
+
+/// Event emitted when a promotional rate is set on a line.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct PromoRateEvent {
+    pub borrower: Address,
+    pub promo_rate_bps: u32,
+    pub promo_until_ts: u64,
+    /// Rate the line falls back to once the promotion ends.
+    pub standard_rate_bps: u32,
+    pub event_version: u32,
+    pub sequence: u64,
+}
+
+/// Publish a promotional rate event.
+pub fn publish_promo_rate(env: &Env, event: PromoRateEvent) {
+    env.events().publish(
+        (
+            symbol_short!("credit"),
+            symbol_short!("promo"),
+            event.borrower.clone(),
+        ),
+        event,
+    );
+}
+
+/// Event emitted when a borrower is added to or removed from the allowlist.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct AllowlistEvent {
+    pub borrower: Address,
+    pub allowed: bool,
+    pub event_version: u32,
+    pub sequence: u64,
+}
+
+/// Publish an allowlist change event.
+pub fn publish_allowlist_event(env: &Env, event: AllowlistEvent) {
+    env.events().publish(
+        (
+            symbol_short!("credit"),
+            symbol_short!("allowlist"),
+            event.borrower.clone(),
+        ),
+        event,
+    );
+}
+
+/// Event emitted when an address is added to or removed from the blocklist.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct BlocklistEvent {
+    pub account: Address,
+    pub blocked: bool,
+    pub event_version: u32,
+    pub sequence: u64,
+}
+
+/// Publish a blocklist change event.
+pub fn publish_blocklist_event(env: &Env, event: BlocklistEvent) {
+    env.events().publish(
+        (
+            symbol_short!("credit"),
+            symbol_short!("blocklist"),
+            event.account.clone(),
+        ),
+        event,
+    );
+}
+
+/// Event emitted when a line is flagged as disputed (`dispute`) or its dispute
+/// is resolved (`dispute_r`).
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct DisputeEvent {
+    pub borrower: Address,
+    pub dispute_id: u64,
+    pub event_version: u32,
+    pub sequence: u64,
+}
+
+/// Publish a dispute flag or resolution event.
+pub fn publish_dispute_event(env: &Env, action: Symbol, event: DisputeEvent) {
+    env.events().publish(
+        (symbol_short!("credit"), action, event.borrower.clone()),
+        event,
+    );
+}
+
+/// Event emitted when a timelocked action is queued (`tl_queue`), executed
+/// (`tl_exec`), or cancelled (`tl_cancel`).
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct TimelockEvent {
+    pub action_id: u64,
+    pub action: TimelockAction,
+    pub eta: u64,
+    pub event_version: u32,
+    pub sequence: u64,
+}
+
+/// Publish a timelock event.
+pub fn publish_timelock_event(env: &Env, action: Symbol, event: TimelockEvent) {
+    env.events()
+        .publish((symbol_short!("credit"), action), event);
+}
@@ -0,0 +1,76 @@
+//! Aggregate exposure caps.
+//!
+//! Exposure is outstanding principal (`utilized_amount`). The protocol-wide
+//! total is `ProtocolStats::total_outstanding_principal`, summed across tokens,
+//! and each risk tier keeps its own total (see `tiers::track`); both counters
+//! are maintained on every credit line write, so checking a cap never iterates
+//! over lines. A cap of 0 means no cap. Lowering a cap below the current
+//! exposure blocks new draws but not repayments.
+
+use soroban_sdk::{panic_with_error, Address, Env, Symbol};
+
+use crate::errors::ContractError;
+use crate::stats;
+use crate::storage::{self, DataKey};
+use crate::tiers::{self, TierKey};
+
+/// Instance storage key for the protocol-wide cap.
+fn protocol_cap_key(env: &Env) -> DataKey {
+    DataKey::Config(Symbol::new(env, "exp_cap"))
+}
+
+pub fn protocol_cap(env: &Env) -> i128 {
+    storage::get_instance(env, &protocol_cap_key(env)).unwrap_or(0)
+}
+
+/// # Panics
+/// * `"cap cannot be negative"`
+pub fn set_protocol_cap(env: &Env, cap: i128) {
+    if cap < 0 {
+        panic!("cap cannot be negative");
+    }
+    storage::set_instance(env, &protocol_cap_key(env), &cap);
+}
+
+pub fn tier_cap(env: &Env, tier_id: u32) -> i128 {
+    storage::get_persistent(env, &DataKey::Tiers(TierKey::Cap(tier_id))).unwrap_or(0)
+}
+
+/// # Panics
+/// * `"cap cannot be negative"`
+/// * `"tier not found"`
+pub fn set_tier_cap(env: &Env, tier_id: u32, cap: i128) {
+    if cap < 0 {
+        panic!("cap cannot be negative");
+    }
+    if tiers::get(env, tier_id).is_none() {
+        panic!("tier not found");
+    }
+    storage::set_persistent(env, &DataKey::Tiers(TierKey::Cap(tier_id)), &cap);
+}
+
+fn exceeds(cap: i128, outstanding: i128, added: i128) -> bool {
+    cap > 0 && outstanding.saturating_add(added) > cap
+}
+
+/// Whether adding `added` to `borrower`'s line keeps the protocol and its
+/// tier within their caps.
+pub fn check(env: &Env, borrower: &Address, added: i128) -> Result<(), ContractError> {
+    let total = stats::counters(env).total_outstanding_principal;
+    if exceeds(protocol_cap(env), total, added) {
+        return Err(ContractError::ExposureCapExceeded);
+    }
+    if let Some(assigned) = tiers::line_tier(env, borrower) {
+        let tier_total = tiers::outstanding(env, assigned.tier_id);
+        if exceeds(tier_cap(env, assigned.tier_id), tier_total, added) {
+            return Err(ContractError::ExposureCapExceeded);
+        }
+    }
+    Ok(())
+}
+
+/// # Panics
+/// * `ContractError::ExposureCapExceeded`
+pub fn require_within_caps(env: &Env, borrower: &Address, added: i128) {
+    check(env, borrower, added).unwrap_or_else(|error| panic_with_error!(env, error));
+}
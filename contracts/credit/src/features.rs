@@ -0,0 +1,51 @@
+//! Interface version and optional subsystem discovery.
+//!
+//! Integrators (wallets, the factory, the backend) read these after an upgrade
+//! instead of probing entrypoints. `INTERFACE_VERSION` moves whenever an
+//! entrypoint is added, removed or changes its signature, or an event changes
+//! shape. `supported` names the optional subsystems this build carries; a name
+//! is only ever added, so its presence is enough to rely on the subsystem.
+
+use soroban_sdk::{symbol_short, vec, Env, Symbol, Vec};
+
+/// Version of the contract's public interface.
+pub const INTERFACE_VERSION: u32 = 1;
+
+/// Optional subsystems supported by this build.
+pub fn supported(env: &Env) -> Vec<Symbol> {
+    vec![
+        env,
+        // Lines in tokens other than the default, each with its own pool.
+        symbol_short!("multi_tok"),
+        symbol_short!("collatrl"),
+        symbol_short!("liquidate"),
+        // Billing cycles, minimum payments and grace periods.
+        symbol_short!("schedules"),
+        symbol_short!("amortize"),
+        symbol_short!("autopay"),
+        symbol_short!("statement"),
+        symbol_short!("var_rate"),
+        symbol_short!("promo"),
+        symbol_short!("dflt_int"),
+        symbol_short!("forgive"),
+        symbol_short!("insurance"),
+        symbol_short!("liq_src"),
+        symbol_short!("flash"),
+        symbol_short!("swap"),
+        symbol_short!("oracle"),
+        symbol_short!("staking"),
+        symbol_short!("rewards"),
+        symbol_short!("debt_tok"),
+        symbol_short!("delegate"),
+        symbol_short!("operators"),
+        symbol_short!("streams"),
+        symbol_short!("sched_drw"),
+        symbol_short!("large_drw"),
+        symbol_short!("velocity"),
+        symbol_short!("allowlist"),
+        symbol_short!("sig_risk"),
+        symbol_short!("scoring"),
+        symbol_short!("disputes"),
+        symbol_short!("timelock"),
+    ]
+}
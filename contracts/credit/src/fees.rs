@@ -0,0 +1,64 @@
+//! Draw and origination fees.
+//!
+//! A `draw_fee_bps` slice of every draw is withheld from the disbursement, and
+//! an optional flat origination fee is added to a new line's utilized amount.
+//! Both are booked as protocol fees and reach the treasury through
+//! `collect_protocol_fees`.
+
+use soroban_sdk::{contracttype, Env, Symbol};
+
+use crate::math::{self, apply_bps, Rounding};
+use crate::storage::{self, DataKey};
+use crate::tiers;
+use crate::types::CreditLineData;
+
+/// Fee schedule (admin-configured).
+#[contracttype]
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct FeeConfig {
+    /// Share of each draw withheld as a fee, in basis points.
+    pub draw_fee_bps: u32,
+    /// Flat fee added to the utilized amount when a line is opened.
+    pub origination_fee: i128,
+}
+
+/// Instance storage key for fee config.
+fn fee_config_key(env: &Env) -> DataKey {
+    DataKey::Config(Symbol::new(env, "fee_cfg"))
+}
+
+pub fn get_config(env: &Env) -> FeeConfig {
+    storage::get_instance(env, &fee_config_key(env)).unwrap_or_default()
+}
+
+/// # Panics
+/// * `"draw_fee_bps cannot exceed 10000"`
+/// * `"origination_fee cannot be negative"`
+/// * `"premium and draw fee cannot exceed 10000 bps"`
+pub fn set_config(env: &Env, config: &FeeConfig) {
+    if config.draw_fee_bps > 10_000 {
+        panic!("draw_fee_bps cannot exceed 10000");
+    }
+    crate::insurance::check_withholding(
+        crate::insurance::get_config(env).premium_bps,
+        config.draw_fee_bps,
+    );
+    if config.origination_fee < 0 {
+        panic!("origination_fee cannot be negative");
+    }
+    storage::set_instance(env, &fee_config_key(env), config);
+}
+
+/// Fee withheld from a draw of `amount` on `line` (rounded down), at its
+/// tier's rate if it is in one.
+pub fn draw_fee(env: &Env, line: &CreditLineData, amount: i128) -> i128 {
+    let bps = match tiers::of_line(env, &line.borrower) {
+        Some(tier) => tier.draw_fee_bps,
+        None => get_config(env).draw_fee_bps,
+    };
+    math::checked(env, apply_bps(amount, bps, Rounding::Down))
+}
+
+pub fn origination_fee(env: &Env) -> i128 {
+    get_config(env).origination_fee
+}
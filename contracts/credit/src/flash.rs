@@ -0,0 +1,99 @@
+//! Flash draws: reserve tokens borrowed and repaid within one invocation.
+//!
+//! `flash_draw` sends reserve tokens to a receiver contract, calls its
+//! `on_flash_draw` callback, and reverts unless the contract's balance is back
+//! up by the amount plus the flash fee when the callback returns. The receiver
+//! repays by transferring the tokens back itself, so no allowance is needed and
+//! the reserve is whole at the end of every transaction. The fee is booked as a
+//! protocol fee, like the draw fee. Flash draws are off until the admin sets a
+//! non-zero `max_amount`.
+
+use soroban_sdk::{contractclient, contracttype, token, Address, Bytes, Env, Symbol};
+
+use crate::math::{self, apply_bps, Rounding};
+use crate::storage::{self, DataKey};
+
+/// Interface a flash draw receiver must implement.
+/// Only the generated `FlashReceiverClient` is used.
+#[allow(dead_code)]
+#[contractclient(name = "FlashReceiverClient")]
+pub trait FlashReceiver {
+    /// Use `amount` of `token` and transfer `amount + fee` back to the caller
+    /// before returning.
+    fn on_flash_draw(env: Env, token: Address, amount: i128, fee: i128, data: Bytes);
+}
+
+/// Flash draw settings (admin-configured).
+#[contracttype]
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct FlashConfig {
+    /// Fee on the amount drawn, in basis points.
+    pub fee_bps: u32,
+    /// Largest flash draw; 0 disables flash draws.
+    pub max_amount: i128,
+}
+
+/// Instance storage key for flash draw config.
+fn config_key(env: &Env) -> DataKey {
+    DataKey::Config(Symbol::new(env, "flash"))
+}
+
+pub fn get_config(env: &Env) -> FlashConfig {
+    storage::get_instance(env, &config_key(env)).unwrap_or_default()
+}
+
+/// # Panics
+/// * `"fee_bps cannot exceed 10000"`
+/// * `"max_amount cannot be negative"`
+pub fn set_config(env: &Env, config: &FlashConfig) {
+    if config.fee_bps > 10_000 {
+        panic!("fee_bps cannot exceed 10000");
+    }
+    if config.max_amount < 0 {
+        panic!("max_amount cannot be negative");
+    }
+    storage::set_instance(env, &config_key(env), config);
+}
+
+/// Fee charged on a flash draw of `amount`.
+pub fn fee(env: &Env, amount: i128) -> i128 {
+    math::checked(
+        env,
+        apply_bps(amount, get_config(env).fee_bps, Rounding::Down),
+    )
+}
+
+/// Lend `amount` of `token` to `receiver` for the duration of its callback.
+/// Returns the fee it paid.
+///
+/// # Panics
+/// * `"amount must be positive"`
+/// * `"flash draws not enabled"`
+/// * `"flash draw exceeds maximum"`
+/// * `"insufficient liquidity"` – the contract holds less than `amount`
+/// * `"flash draw not repaid"` – the balance is short of `amount + fee` afterwards
+pub fn execute(env: &Env, token: &Address, receiver: &Address, amount: i128, data: &Bytes) -> i128 {
+    if amount <= 0 {
+        panic!("amount must be positive");
+    }
+    let config = get_config(env);
+    if config.max_amount == 0 {
+        panic!("flash draws not enabled");
+    }
+    if amount > config.max_amount {
+        panic!("flash draw exceeds maximum");
+    }
+    let fee = fee(env, amount);
+    let token_client = token::Client::new(env, token);
+    let contract = env.current_contract_address();
+    let balance_before = token_client.balance(&contract);
+    if amount > balance_before {
+        panic!("insufficient liquidity");
+    }
+    token_client.transfer(&contract, receiver, &amount);
+    FlashReceiverClient::new(env, receiver).on_flash_draw(token, &amount, &fee, data);
+    if token_client.balance(&contract) < math::checked(env, math::add(balance_before, fee)) {
+        panic!("flash draw not repaid");
+    }
+    fee
+}
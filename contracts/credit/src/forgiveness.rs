@@ -0,0 +1,94 @@
+//! Partial debt forgiveness with a second approval for large amounts.
+//!
+//! The admin can forgive part of a line's debt, interest first, for a reason
+//! code recorded with the event. The threshold applies to the total forgiven
+//! on a line, not to each call: once that total would pass the approval
+//! threshold, every further forgiveness also needs the authorization of a
+//! second key holding the `Approver` role, so no single key can wipe out a
+//! large balance, in one call or in many. A threshold of 0 needs no second
+//! approval. Forgiven principal is a loss to the pool, like a write-off, and
+//! the total forgiven is kept in the protocol stats.
+
+use soroban_sdk::{contracttype, Address, Env, Symbol};
+
+use crate::access::{self, Role};
+use crate::storage::{self, DataKey};
+
+#[contracttype]
+#[derive(Clone)]
+pub enum ForgivenessKey {
+    /// Total forgiven on a borrower's line (persistent).
+    Forgiven(Address),
+}
+
+/// Instance storage key for the approval threshold.
+fn threshold_key(env: &Env) -> DataKey {
+    DataKey::Config(Symbol::new(env, "forgive_thr"))
+}
+
+fn forgiven_key(borrower: &Address) -> DataKey {
+    DataKey::Forgiveness(ForgivenessKey::Forgiven(borrower.clone()))
+}
+
+/// Total forgiven on `borrower`'s line so far.
+pub fn forgiven(env: &Env, borrower: &Address) -> i128 {
+    storage::get_persistent(env, &forgiven_key(borrower)).unwrap_or(0)
+}
+
+pub fn get_threshold(env: &Env) -> i128 {
+    storage::get_instance(env, &threshold_key(env)).unwrap_or(0)
+}
+
+/// # Panics
+/// * `"threshold cannot be negative"`
+pub fn set_threshold(env: &Env, threshold: i128) {
+    if threshold < 0 {
+        panic!("threshold cannot be negative");
+    }
+    storage::set_instance(env, &threshold_key(env), &threshold);
+}
+
+/// Check the second approval for forgiving `amount` more on `borrower`'s line,
+/// then add it to the line's total. An `approver` given when none is needed is
+/// still checked.
+///
+/// # Panics
+/// * `"forgiveness requires approval"` – the line's total would pass the threshold and no approver was given
+/// * `"approver must be a second key"` – the approver is the admin
+/// * `"approver lacks Approver role"`
+pub fn approve(
+    env: &Env,
+    admin: &Address,
+    borrower: &Address,
+    amount: i128,
+    approver: &Option<Address>,
+) {
+    let total = forgiven(env, borrower).saturating_add(amount);
+    require_approval(env, admin, total, approver);
+    storage::set_persistent(env, &forgiven_key(borrower), &total);
+}
+
+fn require_approval(env: &Env, admin: &Address, total: i128, approver: &Option<Address>) {
+    let threshold = get_threshold(env);
+    let Some(approver) = approver else {
+        if threshold > 0 && total > threshold {
+            panic!("forgiveness requires approval");
+        }
+        return;
+    };
+    if approver == admin {
+        panic!("approver must be a second key");
+    }
+    if !access::has_role(env, Role::Approver, approver) {
+        panic!("approver lacks Approver role");
+    }
+    approver.require_auth();
+}
+
+pub fn transfer(env: &Env, from: &Address, to: &Address) {
+    let total = forgiven(env, from);
+    if total > 0 {
+        storage::remove_persistent(env, &forgiven_key(from));
+        storage::set_persistent(env, &forgiven_key(to), &total);
+    }
+}
@@ -0,0 +1,71 @@
+//! Credit limits denominated in a reference currency.
+//!
+//! A line may carry its limit in the price oracle's base asset (e.g. USD)
+//! while draws settle in the line's token. Each draw converts the new
+//! utilization at a fresh oracle price and checks it against
+//! `reference_limit`. `credit_limit` keeps the token-unit equivalent at the
+//! last observed price, so commitments, collateral, and views keep working in
+//! token units between draws.
+
+use soroban_sdk::{Address, Env};
+
+use crate::errors::ContractError;
+use crate::math::{self, mul_div, Rounding};
+use crate::oracle::{self, OracleConfig, PriceOracleClient};
+use crate::types::CreditLineData;
+
+/// Oracle config required for reference-currency limits.
+///
+/// # Panics
+/// * `"price oracle not configured"`
+fn require_config(env: &Env) -> OracleConfig {
+    oracle::get_config(env).expect("price oracle not configured")
+}
+
+/// Fresh price of `token` in reference units, and the oracle's price scale.
+fn quote(env: &Env, token: &Address) -> (i128, i128) {
+    let config = require_config(env);
+    let price = oracle::fresh_price(env, &config, token);
+    let decimals = PriceOracleClient::new(env, &config.oracle).decimals();
+    let scale = 10_i128.checked_pow(decimals).ok_or(ContractError::Overflow);
+    (price, math::checked(env, scale))
+}
+
+/// Value of `amount` tokens in reference units (rounded up).
+fn to_reference(amount: i128, price: i128, scale: i128) -> Result<i128, ContractError> {
+    mul_div(amount, price, scale, Rounding::Up)
+}
+
+/// Token amount worth `reference` reference units (rounded down).
+fn to_native(reference: i128, price: i128, scale: i128) -> Result<i128, ContractError> {
+    mul_div(reference, scale, price, Rounding::Down)
+}
+
+/// Re-price a reference-limit line for a utilization of `utilized` tokens:
+/// refreshes the token-unit `credit_limit` and `reference_utilized` at the
+/// current price. No-op for lines whose limit is in token units.
+pub fn reprice(env: &Env, line: &mut CreditLineData, utilized: i128) {
+    if line.reference_limit == 0 {
+        return;
+    }
+    let (price, scale) = quote(env, &line.token);
+    line.credit_limit = math::checked(env, to_native(line.reference_limit, price, scale));
+    line.reference_utilized = math::checked(env, to_reference(utilized, price, scale));
+}
+
+/// Scale `reference_utilized` down in proportion to a principal reduction from
+/// `previous_utilized`, without consulting the oracle.
+pub fn apply_principal_reduction(env: &Env, line: &mut CreditLineData, previous_utilized: i128) {
+    if line.reference_utilized == 0 || previous_utilized == 0 {
+        return;
+    }
+    line.reference_utilized = math::checked(
+        env,
+        mul_div(
+            line.reference_utilized,
+            line.utilized_amount,
+            previous_utilized,
+            Rounding::Down,
+        ),
+    );
+}
@@ -0,0 +1,117 @@
+//! Per-borrower activity history.
+//!
+//! A compact behavioral record updated on every draw and repayment, giving the
+//! risk engine verifiable on-chain inputs for credit scoring, and a log of the
+//! borrower's most recent draws and repayments so light clients can show recent
+//! activity without an event indexer.
+
+use soroban_sdk::{contracttype, Address, Env, Vec};
+
+use crate::math;
+use crate::storage::{self, DataKey};
+
+/// Lifetime activity counters for a borrower.
+#[contracttype]
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct BorrowerHistory {
+    pub draw_count: u32,
+    pub repay_count: u32,
+    /// Repayments made while no scheduled minimum payment was overdue.
+    pub on_time_payments: u32,
+    /// Repayments made while a scheduled minimum payment was overdue.
+    pub late_payments: u32,
+    /// Total amount ever drawn.
+    pub lifetime_volume: i128,
+    pub last_activity_ts: u64,
+}
+
+/// Entries kept in a borrower's transaction log; older ones are dropped.
+pub const TRANSACTION_LOG_SIZE: u32 = 50;
+
+#[contracttype]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum TransactionKind {
+    Draw = 0,
+    Repay = 1,
+}
+
+/// One entry of a borrower's transaction log.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Transaction {
+    pub kind: TransactionKind,
+    pub amount: i128,
+    pub timestamp: u64,
+}
+
+pub fn get(env: &Env, borrower: &Address) -> BorrowerHistory {
+    storage::get_persistent(env, &DataKey::History(borrower.clone())).unwrap_or_default()
+}
+
+fn set(env: &Env, borrower: &Address, history: &BorrowerHistory) {
+    storage::set_persistent(env, &DataKey::History(borrower.clone()), history);
+}
+
+fn transactions(env: &Env, borrower: &Address) -> Vec<Transaction> {
+    storage::get_persistent(env, &DataKey::Transactions(borrower.clone()))
+        .unwrap_or_else(|| Vec::new(env))
+}
+
+fn log_transaction(env: &Env, borrower: &Address, kind: TransactionKind, amount: i128) {
+    let mut log = transactions(env, borrower);
+    if log.len() >= TRANSACTION_LOG_SIZE {
+        log.pop_front();
+    }
+    log.push_back(Transaction {
+        kind,
+        amount,
+        timestamp: env.ledger().timestamp(),
+    });
+    storage::set_persistent(env, &DataKey::Transactions(borrower.clone()), &log);
+}
+
+/// Up to `limit` of `borrower`'s logged transactions, newest first, skipping the
+/// `start` most recent.
+pub fn page(env: &Env, borrower: &Address, start: u32, limit: u32) -> Vec<Transaction> {
+    let log = transactions(env, borrower);
+    let mut out = Vec::new(env);
+    let end = start.saturating_add(limit).min(log.len());
+    for pos in start..end {
+        out.push_back(log.get_unchecked(log.len() - 1 - pos));
+    }
+    out
+}
+
+pub fn record_draw(env: &Env, borrower: &Address, amount: i128) {
+    let mut history = get(env, borrower);
+    history.draw_count += 1;
+    history.lifetime_volume = math::checked(env, math::add(history.lifetime_volume, amount));
+    history.last_activity_ts = env.ledger().timestamp();
+    set(env, borrower, &history);
+    log_transaction(env, borrower, TransactionKind::Draw, amount);
+}
+
+pub fn record_repayment(env: &Env, borrower: &Address, amount: i128, late: bool) {
+    let mut history = get(env, borrower);
+    history.repay_count += 1;
+    if late {
+        history.late_payments += 1;
+    } else {
+        history.on_time_payments += 1;
+    }
+    history.last_activity_ts = env.ledger().timestamp();
+    set(env, borrower, &history);
+    log_transaction(env, borrower, TransactionKind::Repay, amount);
+}
+
+/// Move `from`'s history and transaction log to `to`.
+pub fn transfer(env: &Env, from: &Address, to: &Address) {
+    let history = get(env, from);
+    storage::remove_persistent(env, &DataKey::History(from.clone()));
+    set(env, to, &history);
+    let log = transactions(env, from);
+    storage::remove_persistent(env, &DataKey::Transactions(from.clone()));
+    if !log.is_empty() {
+        storage::set_persistent(env, &DataKey::Transactions(to.clone()), &log);
+    }
+}
@@ -0,0 +1,28 @@
+//! Idempotency keys for backend-initiated operations.
+//!
+//! The backend may retry a transaction after a timeout. Passing the same
+//! `op_id` on the retry makes it fail with `ContractError::DuplicateOperation`
+//! instead of applying the operation twice.
+
+use soroban_sdk::{panic_with_error, BytesN, Env};
+
+use crate::errors::ContractError;
+use crate::storage::{self, DataKey};
+
+pub fn is_consumed(env: &Env, op_id: &BytesN<32>) -> bool {
+    storage::has_persistent(env, &DataKey::Operation(op_id.clone()))
+}
+
+/// Record `op_id` as consumed; a `None` id skips the check.
+///
+/// # Panics
+/// * `ContractError::DuplicateOperation` – `op_id` was already consumed
+pub fn consume(env: &Env, op_id: &Option<BytesN<32>>) {
+    let Some(op_id) = op_id else {
+        return;
+    };
+    if is_consumed(env, op_id) {
+        panic_with_error!(env, ContractError::DuplicateOperation);
+    }
+    storage::set_persistent(env, &DataKey::Operation(op_id.clone()), &true);
+}
@@ -0,0 +1,98 @@
+//! Insurance fund for bad debt.
+//!
+//! A `premium_bps` slice of every draw is withheld from the disbursement and
+//! set aside in a per-token fund. When a line defaults, the fund absorbs the
+//! loss first: it retires as much of the line's principal as it can, so only
+//! the uncovered remainder is left to be written off against the pool.
+
+use soroban_sdk::{contracttype, Address, Env, Symbol};
+
+use crate::math::{self, apply_bps, Rounding};
+use crate::storage::{self, DataKey};
+use crate::types::CreditLineData;
+
+/// Insurance premium schedule (admin-configured).
+#[contracttype]
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct InsuranceConfig {
+    /// Share of each draw paid into the insurance fund, in basis points.
+    pub premium_bps: u32,
+}
+
+#[contracttype]
+#[derive(Clone)]
+pub enum InsuranceKey {
+    Fund(Address),
+}
+
+/// Instance storage key for insurance config.
+fn insurance_config_key(env: &Env) -> DataKey {
+    DataKey::Config(Symbol::new(env, "insurance"))
+}
+
+pub fn get_config(env: &Env) -> InsuranceConfig {
+    storage::get_instance(env, &insurance_config_key(env)).unwrap_or_default()
+}
+
+/// # Panics
+/// * `"premium and draw fee cannot exceed 10000 bps"`
+pub fn set_config(env: &Env, config: &InsuranceConfig) {
+    check_withholding(
+        config.premium_bps,
+        crate::fees::get_config(env).draw_fee_bps,
+    );
+    storage::set_instance(env, &insurance_config_key(env), config);
+}
+
+/// The premium and draw fee are both withheld from a draw, so together they
+/// cannot exceed the drawn amount.
+///
+/// # Panics
+/// * `"premium and draw fee cannot exceed 10000 bps"`
+pub fn check_withholding(premium_bps: u32, draw_fee_bps: u32) {
+    if premium_bps as u64 + draw_fee_bps as u64 > 10_000 {
+        panic!("premium and draw fee cannot exceed 10000 bps");
+    }
+}
+
+/// Premium withheld from a draw of `amount` (rounded down).
+pub fn premium(env: &Env, amount: i128) -> i128 {
+    math::checked(
+        env,
+        apply_bps(amount, get_config(env).premium_bps, Rounding::Down),
+    )
+}
+
+pub fn fund_balance(env: &Env, token: &Address) -> i128 {
+    storage::get_instance(env, &DataKey::Insurance(InsuranceKey::Fund(token.clone()))).unwrap_or(0)
+}
+
+fn set_fund_balance(env: &Env, token: &Address, amount: i128) {
+    storage::set_instance(
+        env,
+        &DataKey::Insurance(InsuranceKey::Fund(token.clone())),
+        &amount,
+    );
+}
+
+/// Add `amount` of `token` to the insurance fund.
+pub fn pay_premium(env: &Env, token: &Address, amount: i128) {
+    if amount > 0 {
+        let total = math::checked(env, math::add(fund_balance(env, token), amount));
+        set_fund_balance(env, token, total);
+    }
+}
+
+/// Retire as much of a defaulted line's principal as the fund can cover and
+/// return `(covered, uncovered)`. The caller saves the line.
+pub fn cover_default(env: &Env, line: &mut CreditLineData) -> (i128, i128) {
+    let fund = fund_balance(env, &line.token);
+    let covered = fund.min(line.utilized_amount).max(0);
+    if covered > 0 {
+        set_fund_balance(env, &line.token, fund - covered);
+        let previous_utilized = line.utilized_amount;
+        line.utilized_amount -= covered;
+        crate::fx::apply_principal_reduction(env, line, previous_utilized);
+    }
+    (covered, line.utilized_amount)
+}
@@ -0,0 +1,235 @@
+//! Interest accrual on utilized credit.
+//!
+//! Interest accrues at the line's `interest_rate_bps` per year under its
+//! `InterestConfig`: simple interest on `utilized_amount`, or compounded daily
+//! or monthly on the outstanding balance, over a 365- or 360-day year. It is
+//! folded into `accrued_interest` whenever the line is touched. Repayments
+//! settle accrued interest before principal. Defaulted lines accrue under the
+//! protocol's post-default policy (see `default_interest`).
+//!
+//! New lines take the protocol default convention; the admin can override it
+//! per line.
+
+use soroban_sdk::{Env, Symbol};
+
+use crate::default_interest::{self, DefaultAccrual};
+use crate::errors::ContractError;
+use crate::math::{self, Rounding, SECONDS_PER_DAY, SECONDS_PER_YEAR};
+use crate::promo;
+use crate::staking;
+use crate::storage::{self, DataKey};
+use crate::types::{Compounding, CreditLineData, CreditStatus, DayCount, InterestConfig};
+
+/// Instance storage key for the default interest convention.
+fn interest_config_key(env: &Env) -> DataKey {
+    DataKey::Config(Symbol::new(env, "int_cfg"))
+}
+
+/// Convention given to newly opened lines.
+pub fn get_config(env: &Env) -> InterestConfig {
+    storage::get_instance(env, &interest_config_key(env)).unwrap_or_default()
+}
+
+pub fn set_config(env: &Env, config: &InterestConfig) {
+    storage::set_instance(env, &interest_config_key(env), config);
+}
+
+fn year_secs(day_count: DayCount) -> u64 {
+    match day_count {
+        DayCount::Actual365 => SECONDS_PER_YEAR,
+        DayCount::Actual360 => 360 * SECONDS_PER_DAY,
+    }
+}
+
+/// Interest owed on `principal` at `rate_bps` over `elapsed_secs` (rounded down).
+pub fn simple_interest(
+    principal: i128,
+    rate_bps: u32,
+    elapsed_secs: u64,
+    day_count: DayCount,
+) -> Result<i128, ContractError> {
+    if principal <= 0 || rate_bps == 0 || elapsed_secs == 0 {
+        return Ok(0);
+    }
+    math::per_second_interest(
+        principal,
+        rate_bps,
+        elapsed_secs,
+        year_secs(day_count),
+        Rounding::Down,
+    )
+}
+
+/// Interest on `balance` at `rate_bps` over `elapsed_secs`, compounded every
+/// `period_secs`; a trailing partial period accrues simply (rounded down).
+fn compound_interest(
+    balance: i128,
+    rate_bps: u32,
+    elapsed_secs: u64,
+    period_secs: u64,
+    day_count: DayCount,
+) -> Result<i128, ContractError> {
+    if balance <= 0 || rate_bps == 0 || elapsed_secs == 0 {
+        return Ok(0);
+    }
+    let year = year_secs(day_count);
+    let factor = math::add(
+        math::RATE_SCALE,
+        math::periodic_rate(rate_bps, period_secs, year)?,
+    )?;
+    let growth = math::pow_scaled(factor, elapsed_secs / period_secs)?;
+    let grown = math::mul_div(balance, growth, math::RATE_SCALE, Rounding::Down)?;
+    let partial = math::per_second_interest(
+        grown,
+        rate_bps,
+        elapsed_secs % period_secs,
+        year,
+        Rounding::Down,
+    )?;
+    Ok(math::add(grown, partial)? - balance)
+}
+
+/// Interest `line` accrues at `rate_bps` over the next `elapsed_secs`.
+fn interest_over(
+    line: &CreditLineData,
+    rate_bps: u32,
+    elapsed_secs: u64,
+) -> Result<i128, ContractError> {
+    let config = line.interest_config;
+    match config.compounding {
+        Compounding::Simple => simple_interest(
+            line.utilized_amount,
+            rate_bps,
+            elapsed_secs,
+            config.day_count,
+        ),
+        Compounding::Daily => compound_interest(
+            outstanding(line),
+            rate_bps,
+            elapsed_secs,
+            SECONDS_PER_DAY,
+            config.day_count,
+        ),
+        Compounding::Monthly => compound_interest(
+            outstanding(line),
+            rate_bps,
+            elapsed_secs,
+            30 * SECONDS_PER_DAY,
+            config.day_count,
+        ),
+    }
+}
+
+/// Accrue `line` from `last_accrual_ts` up to `to`: nothing during the grace
+/// period, then the promotional rate until it ends, then the standard rate,
+/// each less `discount_bps`.
+fn accrue_to(line: &mut CreditLineData, to: u64, discount_bps: u32) -> Result<(), ContractError> {
+    if to <= line.last_accrual_ts {
+        return Ok(());
+    }
+    if line.grace_until_ts > line.last_accrual_ts {
+        line.last_accrual_ts = line.grace_until_ts.min(to);
+    }
+    let from = line.last_accrual_ts;
+    if line.promo_until_ts > from {
+        let promo_end = line.promo_until_ts.min(to);
+        let rate_bps = line.promo_rate_bps.saturating_sub(discount_bps);
+        let interest = interest_over(line, rate_bps, promo_end - from)?;
+        line.accrued_interest = math::add(line.accrued_interest, interest)?;
+        line.last_accrual_ts = promo_end;
+        promo::clear_if_ended(line);
+    }
+    let rate_bps = line.interest_rate_bps.saturating_sub(discount_bps);
+    let interest = interest_over(line, rate_bps, to - line.last_accrual_ts)?;
+    line.accrued_interest = math::add(line.accrued_interest, interest)?;
+    line.last_accrual_ts = to;
+    Ok(())
+}
+
+/// Accrue a Defaulted `line` up to `to` under the post-default policy: normal
+/// terms up to the default, then at the penalty or standard rate until the
+/// policy's freeze point. Time past the freeze point accrues nothing.
+fn accrue_defaulted(
+    env: &Env,
+    line: &mut CreditLineData,
+    to: u64,
+    discount_bps: u32,
+) -> Result<(), ContractError> {
+    let config = default_interest::get_config(env);
+    let defaulted_at = default_interest::defaulted_at(env, line);
+    accrue_to(line, to.min(defaulted_at), discount_bps)?;
+    let stop = match config.accrual {
+        DefaultAccrual::Continue => to,
+        DefaultAccrual::Freeze => defaulted_at,
+        DefaultAccrual::FreezeAfter => defaulted_at.saturating_add(config.freeze_after_secs),
+    }
+    .min(to);
+    if stop > line.last_accrual_ts {
+        let rate_bps = if config.penalty_rate_bps > 0 {
+            config.penalty_rate_bps
+        } else {
+            line.interest_rate_bps.saturating_sub(discount_bps)
+        };
+        let interest = interest_over(line, rate_bps, stop - line.last_accrual_ts)?;
+        line.accrued_interest = math::add(line.accrued_interest, interest)?;
+    }
+    line.last_accrual_ts = line.last_accrual_ts.max(to);
+    Ok(())
+}
+
+/// Accrue `line` up to `to` under the terms that apply to its status.
+fn accrue_until(env: &Env, line: &mut CreditLineData, to: u64, discount_bps: u32) {
+    let accrued = if line.status == CreditStatus::Defaulted {
+        accrue_defaulted(env, line, to, discount_bps)
+    } else {
+        accrue_to(line, to, discount_bps)
+    };
+    math::checked(env, accrued);
+}
+
+/// Fold interest accrued since `last_accrual_ts` into `accrued_interest`, then
+/// re-price Variable lines for the next period.
+pub fn accrue(env: &Env, line: &mut CreditLineData) {
+    let now = env.ledger().timestamp();
+    let discount_bps = staking::discount_bps(env, &line.borrower);
+    accrue_until(env, line, now, discount_bps);
+    promo::clear_if_ended(line);
+    crate::rates::refresh(env, line);
+}
+
+/// `accrued_interest` the line would carry `horizon_secs` from now if nothing
+/// else touched it, at the rates it would accrue at from now on.
+pub fn quote(env: &Env, line: &CreditLineData, horizon_secs: u64) -> i128 {
+    let mut line = line.clone();
+    accrue(env, &mut line);
+    project_to(
+        env,
+        &mut line,
+        env.ledger().timestamp().saturating_add(horizon_secs),
+    );
+    line.accrued_interest
+}
+
+/// Accrue an already accrued `line` on to `to`, at the rates it would accrue
+/// at from now on. For projections on a copy of the line.
+pub fn project_to(env: &Env, line: &mut CreditLineData, to: u64) {
+    let discount_bps = staking::discount_bps(env, &line.borrower);
+    accrue_until(env, line, to, discount_bps);
+}
+
+/// Principal plus accrued interest.
+pub fn outstanding(line: &CreditLineData) -> i128 {
+    line.utilized_amount.saturating_add(line.accrued_interest)
+}
+
+/// Apply up to `amount` to the line's debt, interest first, and return the
+/// interest portion. Overpayment beyond the outstanding balance is not applied.
+pub fn apply_repayment(env: &Env, line: &mut CreditLineData, amount: i128) -> i128 {
+    let interest = amount.min(line.accrued_interest).max(0);
+    let principal = (amount - interest).min(line.utilized_amount).max(0);
+    let previous_utilized = line.utilized_amount;
+    line.accrued_interest -= interest;
+    line.utilized_amount -= principal;
+    crate::fx::apply_principal_reduction(env, line, previous_utilized);
+    interest
+}
@@ -0,0 +1,105 @@
+//! Permissionless delinquency enforcement.
+//!
+//! Anyone may call `process_overdue` once a scheduled line's minimum payment is
+//! more than `grace_period_secs` past due. Each call records one missed cycle,
+//! bills the next cycle on top of the unpaid amount, marks the line Delinquent,
+//! and escalates to Defaulted once `default_after_missed_cycles` is reached, or
+//! once the line has been delinquent for `auto_default_after_secs`. Disputed
+//! lines keep recording missed cycles but are never escalated. The caller is
+//! paid `keeper_reward` in the line's token out of the protocol fees accrued in
+//! it, capped at what has accrued.
+
+use soroban_sdk::{contracttype, Env, Symbol};
+
+use crate::amortization;
+use crate::delinquency;
+use crate::disputes;
+use crate::schedule;
+use crate::storage::{self, DataKey};
+use crate::suspension;
+use crate::types::{CreditLineData, CreditStatus};
+
+/// Keeper policy (admin-configured).
+#[contracttype]
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct KeeperConfig {
+    /// Seconds after `next_due_ts` before a missed payment can be processed.
+    pub grace_period_secs: u64,
+    /// Missed cycles after which the line is defaulted; 0 never escalates.
+    pub default_after_missed_cycles: u32,
+    /// Flat reward paid to the caller of `process_overdue`, in units of the line's token.
+    pub keeper_reward: i128,
+    /// Seconds after `delinquent_since_ts` at which the line is defaulted; 0 never escalates.
+    pub auto_default_after_secs: u64,
+}
+
+/// Instance storage key for keeper config.
+fn keeper_config_key(env: &Env) -> DataKey {
+    DataKey::Config(Symbol::new(env, "keeper_cfg"))
+}
+
+pub fn get_config(env: &Env) -> KeeperConfig {
+    storage::get_instance(env, &keeper_config_key(env)).unwrap_or_default()
+}
+
+/// # Panics
+/// * `"keeper_reward must be non-negative"`
+pub fn set_config(env: &Env, config: &KeeperConfig) {
+    if config.keeper_reward < 0 {
+        panic!("keeper_reward must be non-negative");
+    }
+    storage::set_instance(env, &keeper_config_key(env), config);
+}
+
+/// Record one missed cycle on `line` and return its new status.
+///
+/// # Panics
+/// * `"credit line not eligible"` – line is unscheduled, Defaulted, or Closed
+/// * `"payment not overdue"` – nothing owed, or still within the grace period
+pub fn record_missed_cycle(env: &Env, line: &mut CreditLineData, config: &KeeperConfig) {
+    if !schedule::is_scheduled(line)
+        || line.status == CreditStatus::Defaulted
+        || line.status == CreditStatus::Closed
+    {
+        panic!("credit line not eligible");
+    }
+    schedule::roll_forward(env, line);
+    let now = env.ledger().timestamp();
+    if line.min_due_amount == 0 || now <= line.next_due_ts.saturating_add(config.grace_period_secs)
+    {
+        panic!("payment not overdue");
+    }
+
+    line.missed_cycles = line.missed_cycles.saturating_add(1);
+    delinquency::mark(line, line.next_due_ts);
+    // Bill the next cycle on top of what is still unpaid.
+    line.next_due_ts = line.next_due_ts.saturating_add(line.cycle_length_secs);
+    line.min_due_amount = amortization::bill(env, line, line.min_due_amount);
+
+    if config.default_after_missed_cycles > 0
+        && line.missed_cycles >= config.default_after_missed_cycles
+        && !disputes::is_disputed(line)
+    {
+        line.status = CreditStatus::Defaulted;
+        suspension::clear(line);
+    }
+}
+
+/// Default `line` if it has been delinquent for at least `auto_default_after_secs`.
+/// Returns true if it was defaulted.
+pub fn auto_default_if_due(env: &Env, line: &mut CreditLineData, config: &KeeperConfig) -> bool {
+    let due = config.auto_default_after_secs > 0
+        && line.delinquent_since_ts != 0
+        && line.status != CreditStatus::Defaulted
+        && line.status != CreditStatus::Closed
+        && !disputes::is_disputed(line)
+        && env.ledger().timestamp()
+            >= line
+                .delinquent_since_ts
+                .saturating_add(config.auto_default_after_secs);
+    if due {
+        line.status = CreditStatus::Defaulted;
+        suspension::clear(line);
+    }
+    due
+}
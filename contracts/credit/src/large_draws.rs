@@ -0,0 +1,126 @@
+//! Circuit breaker for large draws.
+//!
+//! Draws above the admin-configured `threshold` cannot be made directly. The
+//! borrower files a request with `request_large_draw`, and the admin or a
+//! `RiskEngine` role holder executes it with `approve_large_draw` before it
+//! expires. A borrower has at most one pending request; a new one replaces it.
+
+use soroban_sdk::{contracttype, Address, Env, Symbol};
+
+use crate::storage::{self, DataKey};
+
+/// Large-draw policy (admin-configured).
+#[contracttype]
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct LargeDrawConfig {
+    /// Draws above this amount need approval; 0 disables the breaker.
+    pub threshold: i128,
+    /// Ledgers a request stays approvable after it is filed.
+    pub expiry_ledgers: u32,
+}
+
+/// A large draw awaiting approval.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct PendingDraw {
+    pub request_id: u64,
+    pub amount: i128,
+    /// Last ledger sequence at which the request can be approved.
+    pub expiry_ledger: u32,
+}
+
+#[contracttype]
+#[derive(Clone)]
+pub enum LargeDrawKey {
+    /// Pending request for a borrower (persistent).
+    Pending(Address),
+    /// Last issued request id (instance).
+    LastId,
+}
+
+/// Instance storage key for large-draw config.
+fn config_key(env: &Env) -> DataKey {
+    DataKey::Config(Symbol::new(env, "large_draw"))
+}
+
+pub fn get_config(env: &Env) -> LargeDrawConfig {
+    storage::get_instance(env, &config_key(env)).unwrap_or_default()
+}
+
+/// # Panics
+/// * `"threshold cannot be negative"`
+pub fn set_config(env: &Env, config: &LargeDrawConfig) {
+    if config.threshold < 0 {
+        panic!("threshold cannot be negative");
+    }
+    storage::set_instance(env, &config_key(env), config);
+}
+
+/// True if a direct draw of `amount` must go through approval instead.
+pub fn needs_approval(env: &Env, amount: i128) -> bool {
+    let threshold = get_config(env).threshold;
+    threshold > 0 && amount > threshold
+}
+
+pub fn get_pending(env: &Env, borrower: &Address) -> Option<PendingDraw> {
+    storage::get_persistent(
+        env,
+        &DataKey::LargeDraw(LargeDrawKey::Pending(borrower.clone())),
+    )
+}
+
+/// Drop any pending request for `borrower`.
+pub fn cancel(env: &Env, borrower: &Address) {
+    storage::remove_persistent(
+        env,
+        &DataKey::LargeDraw(LargeDrawKey::Pending(borrower.clone())),
+    );
+}
+
+/// File a request for `amount`, replacing any pending one.
+///
+/// # Panics
+/// * `"draw does not need approval"` – amount is within the threshold
+pub fn request(env: &Env, borrower: &Address, amount: i128) -> PendingDraw {
+    if !needs_approval(env, amount) {
+        panic!("draw does not need approval");
+    }
+    let request_id: u64 =
+        storage::get_instance(env, &DataKey::LargeDraw(LargeDrawKey::LastId)).unwrap_or(0) + 1;
+    storage::set_instance(env, &DataKey::LargeDraw(LargeDrawKey::LastId), &request_id);
+    let pending = PendingDraw {
+        request_id,
+        amount,
+        expiry_ledger: env
+            .ledger()
+            .sequence()
+            .saturating_add(get_config(env).expiry_ledgers),
+    };
+    storage::set_persistent(
+        env,
+        &DataKey::LargeDraw(LargeDrawKey::Pending(borrower.clone())),
+        &pending,
+    );
+    pending
+}
+
+/// Remove and return the pending request `request_id` for execution.
+///
+/// # Panics
+/// * `"no pending draw"`
+/// * `"request id mismatch"`
+/// * `"draw request expired"`
+pub fn take(env: &Env, borrower: &Address, request_id: u64) -> PendingDraw {
+    let pending = get_pending(env, borrower).expect("no pending draw");
+    if pending.request_id != request_id {
+        panic!("request id mismatch");
+    }
+    if env.ledger().sequence() > pending.expiry_ledger {
+        panic!("draw request expired");
+    }
+    storage::remove_persistent(
+        env,
+        &DataKey::LargeDraw(LargeDrawKey::Pending(borrower.clone())),
+    );
+    pending
+}
@@ -8,44 +8,170 @@
 //! defense-in-depth measure; if a token or future integration ever called back, the guard
 //! would revert.
 
+mod access;
+mod allowlist;
+mod amortization;
+mod audit;
+mod autopay;
+mod blocklist;
+mod boosts;
+mod borrowers;
+mod collateral;
+mod collections;
+mod config;
+mod debt_token;
+mod default_interest;
+mod delegation;
+mod delinquency;
+mod disputes;
+mod draw_limits;
+mod dust;
+mod errors;
 mod events;
+mod exposure;
+mod features;
+mod fees;
+mod flash;
+mod forgiveness;
+mod fx;
+mod history;
+mod idempotency;
+mod insurance;
+mod interest;
+mod keeper;
+mod large_draws;
+mod liquidation;
+mod liquidity;
+mod math;
+mod metadata;
+mod monitor;
+mod onboarding;
+mod operators;
+mod oracle;
+mod pause;
+mod positions;
+mod preview;
+mod promo;
+mod purposes;
+mod rates;
+mod reserve;
+mod restrictions;
+mod revenue;
+mod rewards;
+mod risk_adjust;
+mod schedule;
+mod scheduled_draws;
+mod schema;
+mod scoring;
+mod signed;
+mod sources;
+mod staking;
+mod statements;
+mod stats;
+mod storage;
+mod streams;
+mod suspension;
+mod swap;
+mod terms;
+mod tiers;
+mod timelock;
+mod tokens;
+mod treasury;
+mod ttl;
 mod types;
+mod velocity;
 
 // token import from our branch — needed for actual token transfer in draw_credit
-use soroban_sdk::{contract, contractimpl, symbol_short, token, Address, Env, Symbol};
+use soroban_sdk::token::TokenInterface;
+use soroban_sdk::{
+    contract, contractimpl, panic_with_error, symbol_short, token, Address, Bytes, BytesN, Env,
+    String, Symbol, Vec,
+};
 
+use access::Role;
+use amortization::{Installment, RepaymentMode, RepaymentPlan};
+use audit::AuditEntry;
+use autopay::Autopay;
+use collateral::CollateralConfig;
+use config::{ConfigUpdate, ProtocolConfig};
+use default_interest::DefaultInterestConfig;
+use delegation::Delegation;
+use disputes::Dispute;
+use draw_limits::DrawLimits;
+pub use errors::ContractError;
 use events::{
-    publish_credit_line_event, publish_drawn_event, publish_repayment_event,
-    publish_risk_parameters_updated, CreditLineEvent, DrawnEvent, RepaymentEvent,
-    RiskParametersUpdatedEvent,
+    publish_adjustment, publish_allowlist_event, publish_autopay, publish_autopay_consent,
+    publish_blocklist_event, publish_collateral_deposited, publish_collateral_returned,
+    publish_credit_line_event, publish_debt_assigned, publish_delegation_event,
+    publish_dispute_event, publish_drawn_event, publish_fee_charged, publish_flash_draw,
+    publish_forgiveness, publish_initialized, publish_insurance_payout, publish_large_draw_event,
+    publish_limit_boost, publish_liquidation_event, publish_liquidity_deposited,
+    publish_liquidity_source_set, publish_liquidity_token_set, publish_liquidity_withdrawn,
+    publish_maturity_event, publish_metadata_hash, publish_operator_approved,
+    publish_overdue_processed, publish_pause_event, publish_position_transfer, publish_promo_rate,
+    publish_protocol_fees_collected, publish_refinance, publish_repayment_event,
+    publish_repayment_mode, publish_reserve_low, publish_reserve_rebalanced,
+    publish_reserve_threshold, publish_restrictions_set, publish_revenue_split,
+    publish_reward_event, publish_risk_parameters_updated, publish_scheduled_draw_event,
+    publish_staking_event, publish_statement, publish_stream_event, publish_sweep,
+    publish_timelock_event, publish_unpause_event, publish_write_off, AdjustmentEvent,
+    AllowlistEvent, AutopayConsentEvent, AutopayEvent, BlocklistEvent, CollateralEvent,
+    CreditLineEvent, DebtAssignedEvent, DelegationEvent, DisputeEvent, DrawnEvent, FeeChargedEvent,
+    FlashDrawEvent, ForgivenessEvent, InitializedEvent, InsurancePayoutEvent, LargeDrawEvent,
+    LimitBoostEvent, LiquidationEvent, LiquidityEvent, LiquiditySourceSetEvent,
+    LiquidityTokenSetEvent, MaturityEvent, MetadataHashEvent, OperatorApprovedEvent,
+    OverdueProcessedEvent, PauseEvent, PositionTransferEvent, PromoRateEvent,
+    ProtocolFeesCollectedEvent, RefinanceEvent, RepaymentEvent, RepaymentModeEvent,
+    ReserveLowEvent, ReserveRebalancedEvent, ReserveThresholdEvent, RestrictionsEvent,
+    RevenueSplitEvent, RewardEvent, RiskParametersUpdatedEvent, ScheduledDrawEvent, StakeEvent,
+    StatementEvent, StreamEvent, SweepEvent, TimelockEvent, WriteOffEvent, EVENT_VERSION,
+};
+use fees::FeeConfig;
+use flash::FlashConfig;
+use history::{BorrowerHistory, Transaction};
+use insurance::InsuranceConfig;
+use keeper::KeeperConfig;
+use large_draws::{LargeDrawConfig, PendingDraw};
+use liquidation::LiquidationConfig;
+use monitor::{HealthReport, ReserveInfo};
+use onboarding::LineTerms;
+use operators::OperatorApproval;
+use oracle::OracleConfig;
+use pause::PauseState;
+use preview::{DrawPreview, RepayPreview};
+use purposes::DrawRecord;
+use reserve::ReserveConfig;
+use revenue::{RevenueShares, RevenueSplit};
+use rewards::RewardsConfig;
+use risk_adjust::RiskAdjustmentConfig;
+use scheduled_draws::ScheduledDraw;
+use signed::{SignedOpenCreditLine, SignedRiskUpdate};
+use sources::{LiquiditySource, RepaymentRoute};
+use staking::StakingConfig;
+use statements::StatementCycle;
+use stats::ProtocolStats;
+use storage::DataKey;
+use streams::Stream;
+use terms::RepaymentTerms;
+use tiers::{LineTier, Tier};
+use timelock::{QueuedAction, TimelockAction};
+use types::{
+    CreditLineData, CreditStatus, InterestConfig, LineHealth, NextPayment, RateMode,
+    RepaymentSchedule,
 };
-use types::{CreditLineData, CreditStatus};
+use velocity::{SpendCap, VelocityConfig};
 
 /// Maximum interest rate in basis points (100%).
 const MAX_INTEREST_RATE_BPS: u32 = 10_000;
 /// Maximum risk score (0–100 scale).
 const MAX_RISK_SCORE: u32 = 100;
 
-/// Instance storage key for reentrancy guard.
-fn reentrancy_key(env: &Env) -> Symbol {
-    Symbol::new(env, "reentrancy")
-}
-
-/// Instance storage key for admin.
-fn admin_key(env: &Env) -> Symbol {
-    Symbol::new(env, "admin")
-}
-
-/// Instance storage key for reserve token address.
-fn token_key(env: &Env) -> Symbol {
-    Symbol::new(env, "token")
+fn require_admin(env: &Env) -> Address {
+    storage::get_instance(env, &DataKey::Admin).expect("admin not set")
 }
 
-fn require_admin(env: &Env) -> Address {
-    env.storage()
-        .instance()
-        .get(&admin_key(env))
-        .expect("admin not set")
+fn require_token(env: &Env) -> Address {
+    storage::get_instance(env, &DataKey::Token).expect("token not configured")
 }
 
 fn require_admin_auth(env: &Env) -> Address {
@@ -54,364 +180,4743 @@ fn require_admin_auth(env: &Env) -> Address {
     admin
 }
 
-fn set_reentrancy_guard(env: &Env) {
-    let key = reentrancy_key(env);
-    let current: bool = env.storage().instance().get(&key).unwrap_or(false);
-    if current {
+/// Persist a credit line, keeping protocol-wide aggregates in sync.
+fn save_credit_line(env: &Env, line: &CreditLineData) {
+    let previous = schema::read_counted(env, &line.borrower);
+    liquidity::track_commitment(env, previous.as_ref(), line);
+    collateral::track(env, previous.as_ref(), line);
+    borrowers::track(env, line);
+    stats::track(env, previous.as_ref(), line);
+    debt_token::track(env, previous.as_ref(), line);
+    delinquency::track(env, previous.as_ref(), line);
+    default_interest::track(env, previous.as_ref(), line);
+    tiers::track(env, previous.as_ref(), line);
+    schema::write(env, line);
+    ttl::extend_line(env, &line.borrower);
+}
+
+/// Read a credit line, migrating it to the current layout and extending its
+/// TTL if it exists.
+fn load_credit_line(env: &Env, borrower: &Address) -> Option<CreditLineData> {
+    let (mut line, current) = schema::read(env, borrower)?;
+    let repriced = tiers::sync(env, &mut line);
+    if current && !repriced {
+        ttl::extend_line(env, borrower);
+    } else {
+        save_credit_line(env, &line);
+    }
+    Some(line)
+}
+
+/// Run `f` holding the reentrancy guard and release it when `f` returns.
+///
+/// A panic inside `f` aborts the invocation, which rolls back the guard write
+/// along with every other storage write, so the guard cannot be left set.
+///
+/// # Panics
+/// * `"reentrancy guard"` – the guard is already held
+fn with_reentrancy_guard<T>(env: &Env, f: impl FnOnce() -> T) -> T {
+    if storage::get_instance(env, &DataKey::Guard).unwrap_or(false) {
         panic!("reentrancy guard");
     }
-    env.storage().instance().set(&key, &true);
+    storage::set_instance(env, &DataKey::Guard, &true);
+    let result = f();
+    storage::set_instance(env, &DataKey::Guard, &false);
+    result
 }
 
-fn clear_reentrancy_guard(env: &Env) {
-    env.storage().instance().set(&reentrancy_key(env), &false);
+/// Report a stake or unstake of `amount`, leaving `staked`.
+fn publish_stake_event(env: &Env, action: Symbol, borrower: &Address, amount: i128, staked: i128) {
+    publish_staking_event(
+        env,
+        action,
+        StakeEvent {
+            borrower: borrower.clone(),
+            amount,
+            staked,
+            discount_bps: staking::discount_bps(env, borrower),
+            event_version: EVENT_VERSION,
+            sequence: events::next_sequence(env),
+        },
+    );
 }
 
-#[contract]
-pub struct Credit;
+/// Report a delegation change of `amount`, leaving `delegation`.
+fn publish_delegation(
+    env: &Env,
+    action: Symbol,
+    delegator: Address,
+    delegatee: Address,
+    amount: i128,
+    delegation: &Delegation,
+) {
+    publish_delegation_event(
+        env,
+        action,
+        DelegationEvent {
+            delegator,
+            delegatee,
+            amount,
+            remaining: delegation.remaining,
+            drawn: delegation.drawn,
+            expires_at: delegation.expires_at,
+            event_version: EVENT_VERSION,
+            sequence: events::next_sequence(env),
+        },
+    );
+}
 
-#[contractimpl]
-impl Credit {
-    /// Initialize the contract with admin and reserve token address.
-    pub fn init(env: Env, admin: Address, token: Address) {
-        if env.storage().instance().has(&admin_key(&env)) {
-            panic!("Already initialized");
-        }
-        env.storage().instance().set(&admin_key(&env), &admin);
-        env.storage().instance().set(&token_key(&env), &token);
-    }
+/// Report a line's current terms as a risk parameters update.
+fn publish_risk_adjusted(env: &Env, line: &CreditLineData) {
+    publish_risk_parameters_updated(
+        env,
+        RiskParametersUpdatedEvent {
+            borrower: line.borrower.clone(),
+            credit_limit: line.credit_limit,
+            interest_rate_bps: line.interest_rate_bps,
+            rate_mode: line.rate_mode,
+            risk_score: line.risk_score,
+            active_rate_bps: promo::active_rate(env, line),
+            promo_until_ts: line.promo_until_ts,
+            event_version: EVENT_VERSION,
+            sequence: events::next_sequence(env),
+        },
+    );
+}
 
-    /// Open a new credit line for a borrower (called by backend/risk engine).
-    ///
-    /// # Panics
-    /// * If `credit_limit` <= 0
-    /// * If `interest_rate_bps` > 10000
-    /// * If `risk_score` > 100
-    /// * If an Active credit line already exists for the borrower
-    pub fn open_credit_line(
-        env: Env,
-        borrower: Address,
-        credit_limit: i128,
-        interest_rate_bps: u32,
-        risk_score: u32,
-    ) {
-        require_admin_auth(&env);
-        assert!(credit_limit > 0, "credit_limit must be greater than zero");
+/// Shared open path: validates terms and opens the line. Callers handle authorization.
+fn open_line(
+    env: &Env,
+    borrower: Address,
+    credit_limit: i128,
+    interest_rate_bps: u32,
+    risk_score: u32,
+    schedule: Option<RepaymentSchedule>,
+    rate_mode: RateMode,
+    token: Option<Address>,
+) {
+    let risk_score = scoring::resolve(env, &borrower, risk_score);
+    assert!(credit_limit > 0, "credit_limit must be greater than zero");
+    assert!(
+        interest_rate_bps <= 10_000,
+        "interest_rate_bps cannot exceed 10000 (100%)"
+    );
+    assert!(risk_score <= 100, "risk_score must be between 0 and 100");
+    allowlist::require_allowed(env, &borrower);
+
+    if let Some(existing) = load_credit_line(env, &borrower) {
         assert!(
-            interest_rate_bps <= 10_000,
-            "interest_rate_bps cannot exceed 10000 (100%)"
+            existing.status != CreditStatus::Active && existing.status != CreditStatus::Delinquent,
+            "borrower already has an active credit line"
         );
-        assert!(risk_score <= 100, "risk_score must be between 0 and 100");
+        assert!(
+            existing.collateral_amount == 0,
+            "existing credit line still holds collateral"
+        );
+        tiers::unassign(env, &existing);
+    }
+    let token = tokens::resolve(env, token);
+    let origination_fee = fees::origination_fee(env);
+    if origination_fee > credit_limit {
+        panic!("origination fee exceeds credit limit");
+    }
 
-        if let Some(existing) = env
-            .storage()
-            .persistent()
-            .get::<Address, CreditLineData>(&borrower)
-        {
-            assert!(
-                existing.status != CreditStatus::Active,
-                "borrower already has an active credit line"
-            );
-        }
-        let credit_line = CreditLineData {
+    let mut credit_line = CreditLineData {
+        borrower: borrower.clone(),
+        token: token.clone(),
+        credit_limit,
+        utilized_amount: origination_fee,
+        interest_rate_bps,
+        risk_score,
+        status: CreditStatus::Active,
+        cycle_length_secs: 0,
+        min_payment_bps: 0,
+        next_due_ts: 0,
+        min_due_amount: 0,
+        missed_cycles: 0,
+        collateral_token: None,
+        collateral_amount: 0,
+        accrued_interest: 0,
+        last_accrual_ts: env.ledger().timestamp(),
+        recovered_amount: 0,
+        written_off_amount: 0,
+        creditor: None,
+        maturity_ts: 0,
+        rate_mode,
+        rate_spread_bps: 0,
+        reference_limit: 0,
+        reference_utilized: 0,
+        min_draw_amount: 0,
+        max_draw_amount: 0,
+        position_id: 0,
+        suspension_reason: 0,
+        suspended_until: 0,
+        restrictions: 0,
+        limit_boost: 0,
+        boost_expires_at: 0,
+        delinquent_since_ts: 0,
+        interest_config: interest::get_config(env),
+        promo_rate_bps: 0,
+        promo_until_ts: 0,
+        rate_floor_bps: 0,
+        rate_cap_bps: 0,
+        grace_until_ts: 0,
+        prepayment_penalty_bps: 0,
+    };
+    rates::set_rate(env, &mut credit_line, interest_rate_bps);
+    terms::apply(env, &mut credit_line);
+    if let Some(schedule) = schedule {
+        schedule::validate(env, &borrower, &schedule);
+        schedule::attach(env, &mut credit_line, &schedule);
+    }
+
+    save_credit_line(env, &credit_line);
+    statements::start(env, &credit_line);
+    exposure::require_within_caps(env, &borrower, 0);
+    treasury::accrue_fees(env, &token, origination_fee);
+
+    publish_credit_line_event(
+        env,
+        (symbol_short!("credit"), symbol_short!("opened")),
+        CreditLineEvent {
+            event_type: symbol_short!("opened"),
             borrower: borrower.clone(),
+            status: CreditStatus::Active,
             credit_limit,
-            utilized_amount: 0,
-            interest_rate_bps,
+            interest_rate_bps: credit_line.interest_rate_bps,
+            rate_mode,
             risk_score,
-            status: CreditStatus::Active,
-        };
-
-        env.storage().persistent().set(&borrower, &credit_line);
-
-        publish_credit_line_event(
-            &env,
-            (symbol_short!("credit"), symbol_short!("opened")),
-            CreditLineEvent {
-                event_type: symbol_short!("opened"),
-                borrower: borrower.clone(),
-                status: CreditStatus::Active,
-                credit_limit,
-                interest_rate_bps,
-                risk_score,
+            suspension_reason: credit_line.suspension_reason,
+            suspended_until: credit_line.suspended_until,
+            auto: false,
+            event_version: EVENT_VERSION,
+            sequence: events::next_sequence(env),
+        },
+    );
+    if origination_fee > 0 {
+        publish_fee_charged(
+            env,
+            FeeChargedEvent {
+                borrower,
+                fee_type: symbol_short!("origin"),
+                amount: origination_fee,
+                event_version: EVENT_VERSION,
+                sequence: events::next_sequence(env),
             },
         );
     }
+}
 
-    /// Draw from credit line: verifies limit, updates utilized_amount,
-    /// and transfers the protocol token from the contract reserve to the borrower.
-    ///
-    /// # Panics
-    /// - `"Credit line not found"` – borrower has no open credit line
-    /// - `"credit line is closed"` – line is closed
-    /// - `"Credit line not active"` – line is suspended or defaulted
-    /// - `"exceeds credit limit"` – draw would push utilized_amount past credit_limit
-    /// - `"amount must be positive"` – amount is zero or negative
-    /// - `"reentrancy guard"` – re-entrant call detected
-    pub fn draw_credit(env: Env, borrower: Address, amount: i128) {
-        set_reentrancy_guard(&env);
-        borrower.require_auth();
+/// Shared risk update path. Callers handle authorization.
+fn update_risk(
+    env: &Env,
+    borrower: Address,
+    credit_limit: i128,
+    interest_rate_bps: u32,
+    risk_score: u32,
+) {
+    let mut credit_line: CreditLineData =
+        load_credit_line(env, &borrower).expect("Credit line not found");
+    restrictions::require_updates_allowed(&credit_line);
+
+    // Reference-limit lines take the new limit in reference units.
+    let reference_line = credit_line.reference_limit > 0;
+    let utilized = if reference_line {
+        credit_line.reference_utilized
+    } else {
+        credit_line.utilized_amount
+    };
+    if credit_limit < 0 {
+        panic!("credit_limit must be non-negative");
+    }
+    if credit_limit < utilized {
+        panic!("credit_limit cannot be less than utilized amount");
+    }
+    disputes::require_limit_allowed(&credit_line, credit_limit);
+    if interest_rate_bps > MAX_INTEREST_RATE_BPS {
+        panic!("interest_rate_bps exceeds maximum");
+    }
+    if risk_score > MAX_RISK_SCORE {
+        panic!("risk_score exceeds maximum");
+    }
 
-        if amount <= 0 {
-            clear_reentrancy_guard(&env);
-            panic!("amount must be positive");
-        }
+    interest::accrue(env, &mut credit_line);
+    credit_line.credit_limit = credit_limit;
+    if reference_line {
+        credit_line.reference_limit = credit_limit;
+        let utilized = credit_line.utilized_amount;
+        fx::reprice(env, &mut credit_line, utilized);
+    }
+    rates::set_rate(env, &mut credit_line, interest_rate_bps);
+    credit_line.risk_score = risk_score;
+    save_credit_line(env, &credit_line);
 
-        let mut credit_line: CreditLineData = env
-            .storage()
-            .persistent()
-            .get(&borrower)
-            .expect("Credit line not found");
+    publish_risk_parameters_updated(
+        env,
+        RiskParametersUpdatedEvent {
+            borrower: borrower.clone(),
+            credit_limit,
+            interest_rate_bps: credit_line.interest_rate_bps,
+            rate_mode: credit_line.rate_mode,
+            risk_score,
+            active_rate_bps: promo::active_rate(env, &credit_line),
+            promo_until_ts: credit_line.promo_until_ts,
+            event_version: EVENT_VERSION,
+            sequence: events::next_sequence(env),
+        },
+    );
+}
 
-        if credit_line.borrower != borrower {
-            clear_reentrancy_guard(&env);
-            panic!("Borrower mismatch for credit line");
-        }
-        if credit_line.status == CreditStatus::Closed {
-            clear_reentrancy_guard(&env);
-            panic!("credit line is closed");
-        }
+/// Accrue interest on `borrower`'s line up to now and save it. Returns the interest
+/// added, or `None` if the borrower has no line.
+fn checkpoint_interest(env: &Env, borrower: &Address) -> Option<i128> {
+    let mut credit_line: CreditLineData = load_credit_line(env, borrower)?;
+    let before = credit_line.accrued_interest;
+    interest::accrue(env, &mut credit_line);
+    save_credit_line(env, &credit_line);
+    Some(credit_line.accrued_interest - before)
+}
 
-        if credit_line.status != CreditStatus::Active {
-            clear_reentrancy_guard(&env);
-            panic!("Credit line not active");
-        }
+/// Load a non-Closed credit line for a maturity change to `maturity_ts` (0 clears it).
+fn load_term_line(env: &Env, borrower: &Address, maturity_ts: u64) -> CreditLineData {
+    let credit_line: CreditLineData =
+        load_credit_line(env, borrower).expect("Credit line not found");
+    if credit_line.status == CreditStatus::Closed {
+        panic!("credit line is closed");
+    }
+    if maturity_ts != 0 && maturity_ts <= env.ledger().timestamp() {
+        panic!("maturity must be in the future");
+    }
+    restrictions::require_updates_allowed(&credit_line);
+    credit_line
+}
 
-        let new_utilized = credit_line
-            .utilized_amount
-            .checked_add(amount)
-            .expect("overflow");
+/// Shared draw path: checks and books the draw against `borrower`'s line and
+/// sends the disbursement to `recipient`. `approved` skips the large-draw breaker
+/// for requests already approved. Returns the amount paid out, net of the draw
+/// fee and insurance premium. Callers handle authorization.
+fn draw_to(
+    env: &Env,
+    borrower: Address,
+    recipient: Address,
+    amount: i128,
+    purpose: Option<Symbol>,
+    approved: bool,
+) -> i128 {
+    pause::require_draws_not_paused(env);
+    with_reentrancy_guard(env, || {
+        draw_guarded(env, borrower, recipient, amount, purpose, approved)
+    })
+}
 
-        if new_utilized > credit_line.credit_limit {
-            clear_reentrancy_guard(&env);
-            panic!("exceeds credit limit");
-        }
+fn publish_scheduled_draw(env: &Env, action: Symbol, borrower: Address, draw: &ScheduledDraw) {
+    publish_scheduled_draw_event(
+        env,
+        (symbol_short!("credit"), action),
+        ScheduledDrawEvent {
+            borrower,
+            draw_id: draw.draw_id,
+            amount: draw.amount,
+            execute_after_ts: draw.execute_after_ts,
+            event_version: EVENT_VERSION,
+            sequence: events::next_sequence(env),
+        },
+    );
+}
 
-        // Checks-effects-interactions: update state before external token call
-        credit_line.utilized_amount = new_utilized;
-        env.storage().persistent().set(&borrower, &credit_line);
+fn publish_timelock(env: &Env, action: Symbol, queued: &QueuedAction) {
+    publish_timelock_event(
+        env,
+        action,
+        TimelockEvent {
+            action_id: queued.action_id,
+            action: queued.action.clone(),
+            eta: queued.eta,
+            event_version: EVENT_VERSION,
+            sequence: events::next_sequence(env),
+        },
+    );
+}
 
-        let token_address: Address = env
-            .storage()
-            .instance()
-            .get(&token_key(&env))
-            .expect("token not configured");
+fn publish_stream(env: &Env, action: Symbol, borrower: Address, stream: &Stream, amount: i128) {
+    publish_stream_event(
+        env,
+        (symbol_short!("credit"), action),
+        StreamEvent {
+            borrower,
+            total_amount: stream.total_amount,
+            amount,
+            claimed: stream.claimed,
+            start_ts: stream.start_ts,
+            duration_secs: stream.duration_secs,
+            event_version: EVENT_VERSION,
+            sequence: events::next_sequence(env),
+        },
+    );
+}
 
-        let token_client = token::Client::new(&env, &token_address);
-        token_client.transfer(&env.current_contract_address(), &borrower, &amount);
+/// Pause the flagged operations on behalf of `caller`. Callers handle authorization.
+fn pause_operations(env: &Env, caller: Address, draws: bool, repays: bool) {
+    audit::record(env, &caller, symbol_short!("pause"), None);
+
+    let mut state = pause::get_pause_state(env);
+    state.draws_paused |= draws;
+    state.repays_paused |= repays;
+    pause::set_pause_state(env, &state);
+    config::sync(env);
+
+    publish_pause_event(
+        env,
+        PauseEvent {
+            caller,
+            draws_paused: state.draws_paused,
+            repays_paused: state.repays_paused,
+            event_version: EVENT_VERSION,
+            sequence: events::next_sequence(env),
+        },
+    );
+}
 
-        clear_reentrancy_guard(&env);
+/// Suspend `borrower`'s line on behalf of `actor`. Callers handle authorization.
+fn suspend_line(env: &Env, actor: &Address, borrower: Address, reason: u32, until_ts: Option<u64>) {
+    audit::record(env, actor, symbol_short!("suspend"), Some(borrower.clone()));
 
-        let timestamp = env.ledger().timestamp();
-        publish_drawn_event(
-            &env,
-            DrawnEvent {
-                borrower: borrower.clone(),
-                amount,
-                new_utilized_amount: credit_line.utilized_amount,
-                timestamp,
-            },
-        );
+    let mut credit_line: CreditLineData =
+        load_credit_line(env, &borrower).expect("Credit line not found");
 
-        env.events().publish(
-            (symbol_short!("credit"), symbol_short!("draw")),
-            (borrower, amount, new_utilized),
-        );
-    }
+    suspension::suspend(env, &mut credit_line, reason, until_ts);
+    save_credit_line(env, &credit_line);
 
-    /// Repay credit (borrower).
-    /// Reverts if credit line does not exist, is Closed, or borrower has not authorized.
-    /// Reduces utilized_amount by amount (capped at 0). Emits RepaymentEvent.
-    pub fn repay_credit(env: Env, borrower: Address, amount: i128) {
-        set_reentrancy_guard(&env);
-        borrower.require_auth();
+    publish_credit_line_event(
+        env,
+        (symbol_short!("credit"), symbol_short!("suspend")),
+        CreditLineEvent {
+            event_type: symbol_short!("suspend"),
+            borrower: borrower.clone(),
+            status: CreditStatus::Suspended,
+            credit_limit: credit_line.credit_limit,
+            interest_rate_bps: credit_line.interest_rate_bps,
+            rate_mode: credit_line.rate_mode,
+            risk_score: credit_line.risk_score,
+            suspension_reason: credit_line.suspension_reason,
+            suspended_until: credit_line.suspended_until,
+            auto: false,
+            event_version: EVENT_VERSION,
+            sequence: events::next_sequence(env),
+        },
+    );
+}
 
-        let mut credit_line: CreditLineData = env
-            .storage()
-            .persistent()
-            .get(&borrower)
-            .expect("Credit line not found");
+/// Body of `draw_to`, run under the reentrancy guard.
+fn draw_guarded(
+    env: &Env,
+    borrower: Address,
+    recipient: Address,
+    amount: i128,
+    purpose: Option<Symbol>,
+    approved: bool,
+) -> i128 {
+    if amount <= 0 {
+        panic!("amount must be positive");
+    }
+    if !approved && large_draws::needs_approval(env, amount) {
+        panic!("draw requires approval");
+    }
 
-        if credit_line.borrower != borrower {
-            clear_reentrancy_guard(&env);
-            panic!("Borrower mismatch for credit line");
-        }
-        if credit_line.status == CreditStatus::Closed {
-            clear_reentrancy_guard(&env);
-            panic!("credit line is closed");
-        }
+    let mut credit_line: CreditLineData =
+        load_credit_line(env, &borrower).expect("Credit line not found");
 
-        if amount <= 0 {
-            clear_reentrancy_guard(&env);
-            panic!("amount must be positive");
-        }
+    if credit_line.borrower != borrower {
+        panic!("Borrower mismatch for credit line");
+    }
+    if credit_line.status == CreditStatus::Closed {
+        panic!("credit line is closed");
+    }
+    allowlist::require_allowed(env, &borrower);
+    blocklist::require_not_blocked(env, &borrower, &recipient);
+    scoring::refresh(env, &mut credit_line);
+
+    let resumed = suspension::lift_if_expired(env, &mut credit_line);
+    let expired_boost = boosts::clear_if_expired(env, &mut credit_line);
+    if credit_line.status != CreditStatus::Active {
+        panic!("Credit line not active");
+    }
+    restrictions::require_draws_allowed(&credit_line);
+    restrictions::require_transfers_allowed(&credit_line);
+    if credit_line.maturity_ts != 0 && env.ledger().timestamp() >= credit_line.maturity_ts {
+        panic!("credit line has matured");
+    }
+    if !purposes::is_allowed(env, &borrower, &purpose) {
+        panic!("purpose not allowed");
+    }
+    let draw_limits = draw_limits::effective(env, &credit_line);
+    if amount < draw_limits.min_draw_amount {
+        panic!("draw below minimum");
+    }
+    if draw_limits.max_draw_amount > 0 && amount > draw_limits.max_draw_amount {
+        panic!("draw exceeds maximum");
+    }
+    if draw_limits::cooling_down(env, &borrower, &draw_limits) {
+        panic_with_error!(env, ContractError::DrawCooldown);
+    }
+    if !velocity::try_record_draw(env, &borrower, amount) {
+        panic_with_error!(env, ContractError::RateLimited);
+    }
 
-        let new_utilized = credit_line.utilized_amount.saturating_sub(amount).max(0);
-        credit_line.utilized_amount = new_utilized;
-        env.storage().persistent().set(&borrower, &credit_line);
+    let new_utilized = math::checked(env, math::add(credit_line.utilized_amount, amount));
+    fx::reprice(env, &mut credit_line, new_utilized);
 
-        let timestamp = env.ledger().timestamp();
-        publish_repayment_event(
-            &env,
-            RepaymentEvent {
+    let boost = boosts::active(env, &credit_line);
+    let locked =
+        streams::locked(env, &borrower).saturating_add(delegation::reserved(env, &borrower));
+    if new_utilized.saturating_add(locked) > boosts::effective_limit(env, &credit_line) {
+        panic!("exceeds credit limit");
+    }
+    if new_utilized > collateral::max_drawable(env, &credit_line).saturating_add(boost) {
+        panic!("exceeds collateralized limit");
+    }
+    exposure::require_within_caps(env, &borrower, amount);
+
+    // Checks-effects-interactions: update state before external token call
+    let outstanding_before = liquidity::outstanding(env, &credit_line.token);
+    interest::accrue(env, &mut credit_line);
+    schedule::roll_forward(env, &mut credit_line);
+    credit_line.utilized_amount = new_utilized;
+    save_credit_line(env, &credit_line);
+    draw_limits::record_draw(env, &borrower);
+    if resumed {
+        publish_credit_line_event(
+            env,
+            (symbol_short!("credit"), symbol_short!("resume")),
+            CreditLineEvent {
+                event_type: symbol_short!("resume"),
+                borrower: borrower.clone(),
+                status: CreditStatus::Active,
+                credit_limit: credit_line.credit_limit,
+                interest_rate_bps: credit_line.interest_rate_bps,
+                rate_mode: credit_line.rate_mode,
+                risk_score: credit_line.risk_score,
+                suspension_reason: credit_line.suspension_reason,
+                suspended_until: credit_line.suspended_until,
+                auto: true,
+                event_version: EVENT_VERSION,
+                sequence: events::next_sequence(env),
+            },
+        );
+    }
+    if let Some((amount, expires_at)) = expired_boost {
+        publish_limit_boost(
+            env,
+            LimitBoostEvent {
                 borrower: borrower.clone(),
                 amount,
-                new_utilized_amount: new_utilized,
-                timestamp,
+                expires_at,
+                expired: true,
+                event_version: EVENT_VERSION,
+                sequence: events::next_sequence(env),
+            },
+        );
+    }
+    history::record_draw(env, &borrower, amount);
+    purposes::record_draw(env, &borrower, amount, purpose.clone());
+    let fee = fees::draw_fee(env, &credit_line, amount);
+    treasury::accrue_fees(env, &credit_line.token, fee);
+    let premium = insurance::premium(env, amount);
+    insurance::pay_premium(env, &credit_line.token, premium);
+    let withheld = fee + premium;
+    statements::record(env, &credit_line, amount, withheld, 0, 0);
+
+    let token_client = token::Client::new(env, &credit_line.token);
+    let reserve_before = token_client.balance(&env.current_contract_address());
+    let reserve_after = reserve_before - (amount - withheld);
+    let outstanding = liquidity::outstanding(env, &credit_line.token);
+    let ratio_before = math::checked(env, reserve::ratio_bps(reserve_before, outstanding_before));
+    reserve::require_above_critical(env, ratio_before);
+    let ratio_bps = math::checked(env, reserve::ratio_bps(reserve_after, outstanding));
+    let reserve_config = reserve::get_config(env);
+    if ratio_bps < reserve_config.min_reserve_ratio_bps as i128 {
+        panic!("reserve ratio too low");
+    }
+    sources::disburse(env, &credit_line.token, &recipient, amount - withheld);
+
+    for (level, threshold_bps) in reserve::crossed(env, ratio_before, ratio_bps).iter() {
+        publish_reserve_threshold(
+            env,
+            ReserveThresholdEvent {
+                token: credit_line.token.clone(),
+                level,
+                threshold_bps,
+                reserve: reserve_after,
+                outstanding,
+                ratio_bps,
+                event_version: EVENT_VERSION,
+                sequence: events::next_sequence(env),
             },
         );
-
-        clear_reentrancy_guard(&env);
-        // TODO: accept token from borrower
     }
 
-    /// Update risk parameters for an existing credit line (admin only).
-    pub fn update_risk_parameters(
-        env: Env,
-        borrower: Address,
-        credit_limit: i128,
-        interest_rate_bps: u32,
-        risk_score: u32,
-    ) {
-        require_admin_auth(&env);
-
-        let mut credit_line: CreditLineData = env
-            .storage()
-            .persistent()
-            .get(&borrower)
-            .expect("Credit line not found");
-
-        if credit_limit < 0 {
-            panic!("credit_limit must be non-negative");
-        }
-        if credit_limit < credit_line.utilized_amount {
-            panic!("credit_limit cannot be less than utilized amount");
-        }
-        if interest_rate_bps > MAX_INTEREST_RATE_BPS {
-            panic!("interest_rate_bps exceeds maximum");
-        }
-        if risk_score > MAX_RISK_SCORE {
-            panic!("risk_score exceeds maximum");
-        }
-
-        credit_line.credit_limit = credit_limit;
-        credit_line.interest_rate_bps = interest_rate_bps;
-        credit_line.risk_score = risk_score;
-        env.storage().persistent().set(&borrower, &credit_line);
+    if ratio_bps < reserve_config.warning_ratio_bps as i128 {
+        publish_reserve_low(
+            env,
+            ReserveLowEvent {
+                token: credit_line.token.clone(),
+                reserve: reserve_after,
+                outstanding,
+                ratio_bps,
+                event_version: EVENT_VERSION,
+                sequence: events::next_sequence(env),
+            },
+        );
+    }
 
-        publish_risk_parameters_updated(
-            &env,
-            RiskParametersUpdatedEvent {
+    let timestamp = env.ledger().timestamp();
+    publish_drawn_event(
+        env,
+        DrawnEvent {
+            borrower: borrower.clone(),
+            recipient,
+            amount,
+            new_utilized_amount: credit_line.utilized_amount,
+            timestamp,
+            remaining_available: credit_line.credit_limit + boost - credit_line.utilized_amount,
+            token: credit_line.token.clone(),
+            purpose,
+            event_version: EVENT_VERSION,
+            sequence: events::next_sequence(env),
+        },
+    );
+
+    if fee > 0 {
+        publish_fee_charged(
+            env,
+            FeeChargedEvent {
                 borrower: borrower.clone(),
-                credit_limit,
-                interest_rate_bps,
-                risk_score,
+                fee_type: symbol_short!("draw"),
+                amount: fee,
+                event_version: EVENT_VERSION,
+                sequence: events::next_sequence(env),
             },
         );
     }
 
-    /// Suspend a credit line (admin only). Emits a CreditLineSuspended event.
-    pub fn suspend_credit_line(env: Env, borrower: Address) {
-        require_admin_auth(&env);
-
-        let mut credit_line: CreditLineData = env
-            .storage()
-            .persistent()
-            .get(&borrower)
-            .expect("Credit line not found");
+    if premium > 0 {
+        publish_fee_charged(
+            env,
+            FeeChargedEvent {
+                borrower: borrower.clone(),
+                fee_type: symbol_short!("premium"),
+                amount: premium,
+                event_version: EVENT_VERSION,
+                sequence: events::next_sequence(env),
+            },
+        );
+    }
 
-        credit_line.status = CreditStatus::Suspended;
-        env.storage().persistent().set(&borrower, &credit_line);
+    env.events().publish(
+        (
+            symbol_short!("credit"),
+            symbol_short!("draw"),
+            borrower.clone(),
+        ),
+        (borrower, amount, new_utilized),
+    );
+    amount - withheld
+}
 
-        publish_credit_line_event(
-            &env,
-            (symbol_short!("credit"), symbol_short!("suspend")),
-            CreditLineEvent {
-                event_type: symbol_short!("suspend"),
-                borrower: borrower.clone(),
-                status: CreditStatus::Suspended,
-                credit_limit: credit_line.credit_limit,
-                interest_rate_bps: credit_line.interest_rate_bps,
-                risk_score: credit_line.risk_score,
+/// Publish an InsurancePayoutEvent when the fund covered part of a default.
+fn publish_insurance_payout_if_any(env: &Env, credit_line: &CreditLineData, payout: (i128, i128)) {
+    let (covered, uncovered) = payout;
+    if covered > 0 {
+        publish_insurance_payout(
+            env,
+            InsurancePayoutEvent {
+                borrower: credit_line.borrower.clone(),
+                token: credit_line.token.clone(),
+                covered,
+                uncovered,
+                event_version: EVENT_VERSION,
+                sequence: events::next_sequence(env),
             },
         );
     }
+}
 
-    /// Close a credit line. Callable by admin (force-close) or by borrower when utilization is zero.
-    /// Close a credit line. Callable by admin (force-close) or by borrower when utilization is zero.
-    ///
-    /// # Arguments
-    /// * `closer` - Must be either the contract admin or the borrower (only when utilized_amount == 0).
-    pub fn close_credit_line(env: Env, borrower: Address, closer: Address) {
-        closer.require_auth();
+/// Write off up to `amount` of the line's debt, interest first, charging the principal
+/// portion to the pool. Returns the amount written off. The caller saves the line.
+fn write_off_debt(env: &Env, credit_line: &mut CreditLineData, amount: i128) -> i128 {
+    interest::accrue(env, credit_line);
+    let written = amount.min(interest::outstanding(credit_line));
+    let interest_written = interest::apply_repayment(env, credit_line, written);
+    credit_line.written_off_amount =
+        math::checked(env, math::add(credit_line.written_off_amount, written));
+    // An assigned line's loss falls on the collector, not the pool.
+    if credit_line.creditor.is_none() {
+        liquidity::realize_loss(env, &credit_line.token, written - interest_written);
+    }
 
-        let admin: Address = require_admin(&env);
+    publish_write_off(
+        env,
+        WriteOffEvent {
+            borrower: credit_line.borrower.clone(),
+            amount: written,
+            written_off_amount: credit_line.written_off_amount,
+            remaining_outstanding: interest::outstanding(credit_line),
+            event_version: EVENT_VERSION,
+            sequence: events::next_sequence(env),
+        },
+    );
+    written
+}
 
-        let mut credit_line: CreditLineData = env
-            .storage()
-            .persistent()
-            .get(&borrower)
-            .expect("Credit line not found");
+/// Shared close path. Callers handle authorization of `closer`.
+fn close_line(env: &Env, borrower: Address, closer: Address) {
+    let admin: Address = require_admin(env);
 
-        if credit_line.status == CreditStatus::Closed {
-            return;
-        }
+    let mut credit_line: CreditLineData =
+        load_credit_line(env, &borrower).expect("Credit line not found");
 
-        let allowed = closer == admin || (closer == borrower && credit_line.utilized_amount == 0);
-        if !allowed {
-            if closer == borrower {
-                panic!("cannot close: utilized amount not zero");
-            }
-            panic!("unauthorized");
-        }
+    if credit_line.status == CreditStatus::Closed {
+        return;
+    }
 
-        credit_line.status = CreditStatus::Closed;
-        env.storage().persistent().set(&borrower, &credit_line);
+    let within_dust = credit_line.utilized_amount <= dust::get_threshold(env);
+    let allowed = closer == admin || (closer == borrower && within_dust);
+    if !allowed {
+        if closer == borrower {
+            panic!("cannot close: utilized amount not zero");
+        }
+        panic!("unauthorized");
+    }
+    if closer == admin {
+        audit::record(env, &admin, symbol_short!("close"), Some(borrower.clone()));
+    }
+    // A borrower closing within the dust threshold has the residue written off.
+    if closer == borrower && credit_line.utilized_amount > 0 {
+        write_off_debt(env, &mut credit_line, i128::MAX);
+    }
 
-        publish_credit_line_event(
-            &env,
-            (symbol_short!("credit"), symbol_short!("closed")),
-            CreditLineEvent {
-                event_type: symbol_short!("closed"),
+    credit_line.status = CreditStatus::Closed;
+    let collateral_token = credit_line.collateral_token.clone();
+    let returned = if credit_line.utilized_amount == 0
+        && !restrictions::is_frozen(&credit_line, restrictions::TRANSFERS_FROZEN)
+    {
+        collateral::release_all(env, &mut credit_line, &borrower)
+    } else {
+        0
+    };
+    save_credit_line(env, &credit_line);
+
+    if let (Some(token), true) = (collateral_token, returned > 0) {
+        publish_collateral_returned(
+            env,
+            CollateralEvent {
                 borrower: borrower.clone(),
-                status: CreditStatus::Closed,
-                credit_limit: credit_line.credit_limit,
-                interest_rate_bps: credit_line.interest_rate_bps,
-                risk_score: credit_line.risk_score,
+                token,
+                amount: returned,
+                new_collateral_amount: 0,
+                event_version: EVENT_VERSION,
+                sequence: events::next_sequence(env),
             },
         );
     }
 
-    /// Mark a credit line as defaulted (admin only). Emits a CreditLineDefaulted event.
-    pub fn default_credit_line(env: Env, borrower: Address) {
-        require_admin_auth(&env);
+    publish_credit_line_event(
+        env,
+        (symbol_short!("credit"), symbol_short!("closed")),
+        CreditLineEvent {
+            event_type: symbol_short!("closed"),
+            borrower: borrower.clone(),
+            status: CreditStatus::Closed,
+            credit_limit: credit_line.credit_limit,
+            interest_rate_bps: credit_line.interest_rate_bps,
+            rate_mode: credit_line.rate_mode,
+            risk_score: credit_line.risk_score,
+            suspension_reason: credit_line.suspension_reason,
+            suspended_until: credit_line.suspended_until,
+            auto: false,
+            event_version: EVENT_VERSION,
+            sequence: events::next_sequence(env),
+        },
+    );
+}
 
-        let mut credit_line: CreditLineData = env
-            .storage()
-            .persistent()
-            .get(&borrower)
-            .expect("Credit line not found");
+/// How the shared repayment path collects funds from the borrower.
+#[derive(Clone, Copy, PartialEq)]
+enum Funding {
+    /// Direct transfer of the applied amount under the borrower's auth.
+    Transfer,
+    /// `transfer_from` of the applied amount against the borrower's allowance.
+    Allowance,
+    /// Direct transfer of the full amount; any excess is refunded.
+    TransferWithRefund,
+    /// Full amount already received from a swap; any excess is refunded.
+    Swapped,
+}
 
-        credit_line.status = CreditStatus::Defaulted;
-        env.storage().persistent().set(&borrower, &credit_line);
+/// Shared repayment path. Callers handle authorization.
+fn repay(env: &Env, borrower: Address, amount: i128, funding: Funding) {
+    pause::require_repays_not_paused(env);
+    with_reentrancy_guard(env, || repay_guarded(env, borrower, amount, funding));
+}
+
+/// Body of `repay`, run under the reentrancy guard.
+fn repay_guarded(env: &Env, borrower: Address, amount: i128, funding: Funding) {
+    let mut credit_line: CreditLineData =
+        load_credit_line(env, &borrower).expect("Credit line not found");
+
+    if credit_line.borrower != borrower {
+        panic!("Borrower mismatch for credit line");
+    }
+    if credit_line.status == CreditStatus::Closed {
+        panic!("credit line is closed");
+    }
+    restrictions::require_repays_allowed(&credit_line);
+
+    if amount <= 0 {
+        panic!("amount must be positive");
+    }
 
+    interest::accrue(env, &mut credit_line);
+    schedule::roll_forward(env, &mut credit_line);
+    let late = schedule::is_overdue(env, &credit_line);
+    let minimum_pending = credit_line.min_due_amount > 0;
+    let paid = amount.min(interest::outstanding(&credit_line));
+    if credit_line.status == CreditStatus::Defaulted {
+        credit_line.recovered_amount =
+            math::checked(env, math::add(credit_line.recovered_amount, paid));
+    }
+    let interest_paid = interest::apply_repayment(env, &mut credit_line, paid);
+    let prepayment_penalty =
+        if interest::outstanding(&credit_line) == 0 && credit_line.creditor.is_none() {
+            terms::prepayment_penalty(env, &credit_line, paid - interest_paid)
+        } else {
+            0
+        };
+    schedule::apply_repayment(&mut credit_line, paid);
+    let cured = delinquency::cure_if_current(&mut credit_line);
+    let score_adjusted = minimum_pending
+        && !late
+        && credit_line.min_due_amount == 0
+        && risk_adjust::on_time_payment(env, &mut credit_line);
+    save_credit_line(env, &credit_line);
+    if cured {
         publish_credit_line_event(
-            &env,
-            (symbol_short!("credit"), symbol_short!("default")),
+            env,
+            (symbol_short!("credit"), symbol_short!("cured")),
             CreditLineEvent {
-                event_type: symbol_short!("default"),
+                event_type: symbol_short!("cured"),
                 borrower: borrower.clone(),
-                status: CreditStatus::Defaulted,
+                status: CreditStatus::Active,
                 credit_limit: credit_line.credit_limit,
                 interest_rate_bps: credit_line.interest_rate_bps,
+                rate_mode: credit_line.rate_mode,
                 risk_score: credit_line.risk_score,
+                suspension_reason: credit_line.suspension_reason,
+                suspended_until: credit_line.suspended_until,
+                auto: false,
+                event_version: EVENT_VERSION,
+                sequence: events::next_sequence(env),
             },
         );
     }
+    if score_adjusted {
+        publish_risk_adjusted(env, &credit_line);
+    }
+    let fee_portion = if credit_line.creditor.is_none() {
+        let shares = revenue::distribute(env, &credit_line.token, interest_paid);
+        if interest_paid > 0 {
+            publish_revenue_split(
+                env,
+                RevenueSplitEvent {
+                    token: credit_line.token.clone(),
+                    interest: interest_paid,
+                    lp_share: shares.lp,
+                    treasury_share: shares.treasury,
+                    insurance_share: shares.insurance,
+                    event_version: EVENT_VERSION,
+                    sequence: events::next_sequence(env),
+                },
+            );
+        }
+        shares.treasury
+    } else {
+        0
+    };
+    if !late && credit_line.status != CreditStatus::Defaulted && credit_line.creditor.is_none() {
+        let points = rewards::accrue(env, &borrower, interest_paid);
+        if points > 0 {
+            publish_reward_event(
+                env,
+                symbol_short!("rwd_accr"),
+                RewardEvent {
+                    borrower: borrower.clone(),
+                    points,
+                    balance: rewards::points(env, &borrower),
+                    event_version: EVENT_VERSION,
+                    sequence: events::next_sequence(env),
+                },
+            );
+        }
+    }
+    stats::record_repayment(
+        env,
+        paid - interest_paid,
+        interest_paid,
+        fee_portion + prepayment_penalty,
+    );
+    if paid > 0 {
+        history::record_repayment(env, &borrower, paid, late);
+    }
 
-    /// Get credit line data for a borrower (view function).
-    pub fn get_credit_line(env: Env, borrower: Address) -> Option<CreditLineData> {
-        env.storage().persistent().get(&borrower)
+    let pulled = match funding {
+        Funding::TransferWithRefund | Funding::Swapped => amount,
+        Funding::Transfer | Funding::Allowance => paid,
+    };
+    let refunded = pulled - paid;
+    if refunded > 0 {
+        restrictions::require_transfers_allowed(&credit_line);
+    }
+    let pulled = if funding == Funding::Swapped {
+        // The swapped funds are already held; only the penalty is pulled.
+        prepayment_penalty
+    } else {
+        math::checked(env, math::add(pulled, prepayment_penalty))
+    };
+    treasury::accrue_fees(env, &credit_line.token, prepayment_penalty);
+    statements::record(
+        env,
+        &credit_line,
+        0,
+        prepayment_penalty,
+        paid,
+        interest_paid,
+    );
+    let token_client = token::Client::new(env, &credit_line.token);
+    let contract = env.current_contract_address();
+    if pulled > 0 {
+        if funding == Funding::Allowance {
+            token_client.transfer_from(&contract, &borrower, &contract, &pulled);
+        } else {
+            token_client.transfer(&borrower, &contract, &pulled);
+        }
+    }
+    if refunded > 0 {
+        token_client.transfer(&contract, &borrower, &refunded);
+    }
+    match &credit_line.creditor {
+        Some(collector) if paid > 0 => token_client.transfer(&contract, collector, &paid),
+        Some(_) => {}
+        None => sources::route_repayment(env, &credit_line.token, paid),
+    }
+
+    let timestamp = env.ledger().timestamp();
+    publish_repayment_event(
+        env,
+        RepaymentEvent {
+            borrower: borrower.clone(),
+            amount: paid,
+            refunded,
+            new_utilized_amount: credit_line.utilized_amount,
+            timestamp,
+            principal_portion: paid - interest_paid,
+            interest_portion: interest_paid,
+            fee_portion,
+            prepayment_penalty,
+            event_version: EVENT_VERSION,
+            sequence: events::next_sequence(env),
+        },
+    );
+}
+
+#[contract]
+pub struct Credit;
+
+#[contractimpl]
+impl Credit {
+    /// Initialize the contract with admin and reserve token address.
+    pub fn init(env: Env, admin: Address, token: Address) {
+        if storage::has_instance(&env, &DataKey::Admin) {
+            panic!("Already initialized");
+        }
+        storage::set_instance(&env, &DataKey::Admin, &admin);
+        storage::set_instance(&env, &DataKey::Token, &token);
+        tokens::set_supported(&env, &token, true);
+        config::sync(&env);
+        publish_initialized(
+            &env,
+            InitializedEvent {
+                admin,
+                token,
+                event_version: EVENT_VERSION,
+                sequence: events::next_sequence(&env),
+            },
+        );
+    }
+
+    /// Get the admin address, or `None` before `init` (view function).
+    pub fn get_admin(env: Env) -> Option<Address> {
+        storage::get_instance(&env, &DataKey::Admin)
+    }
+
+    /// Grant `role` to `account` (admin only).
+    pub fn grant_role(env: Env, role: Role, account: Address) {
+        let admin = require_admin_auth(&env);
+        access::grant_role(&env, role, &account);
+        audit::config_changed(
+            &env,
+            &admin,
+            symbol_short!("role"),
+            None,
+            (role, account, true),
+        );
+    }
+
+    /// Revoke `role` from `account` (admin only). No-op if the role was not granted.
+    pub fn revoke_role(env: Env, role: Role, account: Address) {
+        let admin = require_admin_auth(&env);
+        access::revoke_role(&env, role, &account);
+        audit::config_changed(
+            &env,
+            &admin,
+            symbol_short!("role"),
+            None,
+            (role, account, false),
+        );
+    }
+
+    /// Returns true if `account` has been granted `role` (view function).
+    /// The admin implicitly holds every role but is not reported here.
+    pub fn has_role(env: Env, role: Role, account: Address) -> bool {
+        access::has_role(&env, role, &account)
+    }
+
+    /// Designate `engine` as the risk engine allowed to open credit lines and update
+    /// risk parameters (admin only). Grants it the `RiskEngine` role and revokes the
+    /// role from the previously designated address.
+    pub fn set_risk_engine(env: Env, engine: Address) {
+        let admin = require_admin_auth(&env);
+        access::set_risk_engine(&env, &engine);
+        audit::config_changed(&env, &admin, symbol_short!("risk_eng"), None, engine);
+    }
+
+    /// Get the designated risk engine, if set (view function).
+    pub fn get_risk_engine(env: Env) -> Option<Address> {
+        access::risk_engine(&env)
+    }
+
+    /// Set the contract that scores borrowers on-chain (admin only). Once set,
+    /// `open_credit_line` takes the risk score from `score(borrower)` and ignores the
+    /// caller-supplied one, and each draw refreshes the line's score before drawing.
+    pub fn set_scoring_contract(env: Env, contract: Address) {
+        let admin = require_admin_auth(&env);
+        scoring::set_contract(&env, &contract);
+        audit::config_changed(&env, &admin, symbol_short!("scoring"), None, contract);
+    }
+
+    /// Register the swap adapter used by `repay_with_token` (admin only).
+    pub fn set_swap_adapter(env: Env, adapter: Address) {
+        let admin = require_admin_auth(&env);
+        swap::set_adapter(&env, &adapter);
+        audit::config_changed(&env, &admin, symbol_short!("swap"), None, adapter);
+    }
+
+    /// Get the registered swap adapter, if any (view function).
+    pub fn get_swap_adapter(env: Env) -> Option<Address> {
+        swap::get_adapter(&env)
+    }
+
+    /// Designate the emergency guardian (admin only): `guardian` is granted the `Guardian`
+    /// role and the previously designated guardian, if different, loses it. The guardian
+    /// can only pause draws and suspend individual lines.
+    pub fn set_guardian(env: Env, guardian: Address) {
+        let admin = require_admin_auth(&env);
+        access::set_guardian(&env, &guardian);
+        audit::config_changed(&env, &admin, symbol_short!("guardian"), None, guardian);
+    }
+
+    /// Get the designated guardian, if set (view function).
+    pub fn get_guardian(env: Env) -> Option<Address> {
+        access::guardian(&env)
+    }
+
+    /// Get the configured scoring contract, if any (view function).
+    pub fn get_scoring_contract(env: Env) -> Option<Address> {
+        scoring::get_contract(&env)
+    }
+
+    /// Turn the borrower allowlist gate on or off (admin or `Compliance` role). While it
+    /// is on, only allowlisted borrowers can have a line opened, draw, or receive a
+    /// transferred line.
+    pub fn set_allowlist_enabled(env: Env, caller: Address, enabled: bool) {
+        access::require_role(&env, Role::Compliance, &caller);
+        allowlist::set_enabled(&env, enabled);
+        audit::config_changed(&env, &caller, symbol_short!("allowlist"), None, enabled);
+    }
+
+    /// Returns true if the borrower allowlist gate is on (view function).
+    pub fn is_allowlist_enabled(env: Env) -> bool {
+        allowlist::is_enabled(&env)
+    }
+
+    /// Add `borrower` to the allowlist (admin or `Compliance` role). Emits an AllowlistEvent.
+    pub fn allow_borrower(env: Env, caller: Address, borrower: Address) {
+        access::require_role(&env, Role::Compliance, &caller);
+        audit::record(
+            &env,
+            &caller,
+            symbol_short!("allow"),
+            Some(borrower.clone()),
+        );
+        allowlist::allow(&env, &borrower);
+        publish_allowlist_event(
+            &env,
+            AllowlistEvent {
+                borrower,
+                allowed: true,
+                event_version: EVENT_VERSION,
+                sequence: events::next_sequence(&env),
+            },
+        );
+    }
+
+    /// Remove `borrower` from the allowlist (admin or `Compliance` role). An existing
+    /// line stays open for repayment. Emits an AllowlistEvent.
+    pub fn deny_borrower(env: Env, caller: Address, borrower: Address) {
+        access::require_role(&env, Role::Compliance, &caller);
+        audit::record(&env, &caller, symbol_short!("deny"), Some(borrower.clone()));
+        allowlist::deny(&env, &borrower);
+        publish_allowlist_event(
+            &env,
+            AllowlistEvent {
+                borrower,
+                allowed: false,
+                event_version: EVENT_VERSION,
+                sequence: events::next_sequence(&env),
+            },
+        );
+    }
+
+    /// Returns true if `borrower` is on the allowlist, whether or not the gate is on
+    /// (view function).
+    pub fn is_allowlisted(env: Env, borrower: Address) -> bool {
+        allowlist::is_listed(&env, &borrower)
+    }
+
+    /// Block `account` (admin or `Compliance` role): it can no longer draw or receive a
+    /// draw as recipient or operator. Emits a BlocklistEvent.
+    pub fn block_address(env: Env, caller: Address, account: Address) {
+        access::require_role(&env, Role::Compliance, &caller);
+        audit::record(&env, &caller, symbol_short!("block"), Some(account.clone()));
+        blocklist::block(&env, &account);
+        publish_blocklist_event(
+            &env,
+            BlocklistEvent {
+                account,
+                blocked: true,
+                event_version: EVENT_VERSION,
+                sequence: events::next_sequence(&env),
+            },
+        );
+    }
+
+    /// Remove `account` from the blocklist (admin or `Compliance` role). Emits a
+    /// BlocklistEvent.
+    pub fn unblock_address(env: Env, caller: Address, account: Address) {
+        access::require_role(&env, Role::Compliance, &caller);
+        audit::record(
+            &env,
+            &caller,
+            symbol_short!("unblock"),
+            Some(account.clone()),
+        );
+        blocklist::unblock(&env, &account);
+        publish_blocklist_event(
+            &env,
+            BlocklistEvent {
+                account,
+                blocked: false,
+                event_version: EVENT_VERSION,
+                sequence: events::next_sequence(&env),
+            },
+        );
+    }
+
+    /// Returns true if `account` is on the blocklist (view function).
+    pub fn is_blocked(env: Env, account: Address) -> bool {
+        blocklist::is_blocked(&env, &account)
+    }
+
+    /// Pause draws and/or repayments (admin or `Pauser` role). Flags passed as
+    /// `false` are left unchanged, so repayments can keep flowing while draws are halted.
+    pub fn pause(env: Env, caller: Address, draws: bool, repays: bool) {
+        access::require_role(&env, Role::Pauser, &caller);
+        pause_operations(&env, caller, draws, repays);
+    }
+
+    /// Pause draws in an emergency (admin or `Guardian` role). Repayments keep flowing,
+    /// and only the admin or a `Pauser` can unpause.
+    pub fn emergency_pause(env: Env, caller: Address) {
+        access::require_role(&env, Role::Guardian, &caller);
+        pause_operations(&env, caller, true, false);
+    }
+
+    /// Unpause draws and/or repayments (admin or `Pauser` role). Flags passed as
+    /// `false` are left unchanged.
+    pub fn unpause(env: Env, caller: Address, draws: bool, repays: bool) {
+        access::require_role(&env, Role::Pauser, &caller);
+        audit::record(&env, &caller, symbol_short!("unpause"), None);
+
+        let mut state = pause::get_pause_state(&env);
+        if draws {
+            state.draws_paused = false;
+        }
+        if repays {
+            state.repays_paused = false;
+        }
+        pause::set_pause_state(&env, &state);
+        config::sync(&env);
+
+        publish_unpause_event(
+            &env,
+            PauseEvent {
+                caller,
+                draws_paused: state.draws_paused,
+                repays_paused: state.repays_paused,
+                event_version: EVENT_VERSION,
+                sequence: events::next_sequence(&env),
+            },
+        );
+    }
+
+    /// Get the current pause flags (view function).
+    pub fn get_pause_state(env: Env) -> PauseState {
+        pause::get_pause_state(&env)
+    }
+
+    /// Add or remove a token from the denominations new lines may use (admin only).
+    /// Existing lines keep their token either way.
+    ///
+    /// # Panics
+    /// * `"cannot remove default token"` – the token given to `init` is always supported
+    pub fn set_token_supported(env: Env, token: Address, supported: bool) {
+        let admin = require_admin_auth(&env);
+        audit::record(&env, &admin, symbol_short!("token"), None);
+        tokens::set_supported(&env, &token, supported);
+        config::sync(&env);
+        publish_liquidity_token_set(
+            &env,
+            LiquidityTokenSetEvent {
+                token,
+                supported,
+                event_version: EVENT_VERSION,
+                sequence: events::next_sequence(&env),
+            },
+        );
+    }
+
+    /// Get the tokens new lines may be denominated in (view function).
+    pub fn get_supported_tokens(env: Env) -> Vec<Address> {
+        tokens::list(&env)
+    }
+
+    /// Get the default liquidity token given to `init`, or `None` before `init`
+    /// (view function).
+    pub fn get_liquidity_token(env: Env) -> Option<Address> {
+        storage::get_instance(&env, &DataKey::Token)
+    }
+
+    /// Deposit `amount` of `token` as lender liquidity, minting shares in that token's
+    /// pool at the current share price.
+    ///
+    /// # Panics
+    /// * `"token not supported"`
+    /// * `"amount must be positive"` – amount is zero or negative
+    /// * `"deposit too small"` – amount would mint zero shares
+    pub fn deposit_liquidity(env: Env, lender: Address, token: Address, amount: i128) {
+        lender.require_auth();
+        let token = tokens::resolve(&env, Some(token));
+        let shares = liquidity::deposit(&env, &token, &lender, amount);
+        publish_liquidity_deposited(
+            &env,
+            LiquidityEvent {
+                new_share_balance: liquidity::shares_of(&env, &token, &lender),
+                lender,
+                token,
+                amount,
+                shares,
+                event_version: EVENT_VERSION,
+                sequence: events::next_sequence(&env),
+            },
+        );
+    }
+
+    /// Withdraw `amount` of `token`, burning the lender's shares in that pool worth it.
+    ///
+    /// # Panics
+    /// * `"amount must be positive"` – amount is zero or negative
+    /// * `"insufficient shares"` – amount exceeds the value of the lender's shares
+    /// * `"withdrawal exceeds free liquidity"` – the token's reserve would no longer
+    ///   cover the undrawn credit committed to Active lines in it
+    pub fn withdraw_liquidity(env: Env, lender: Address, token: Address, amount: i128) {
+        lender.require_auth();
+        let shares = liquidity::withdraw(&env, &token, &lender, amount);
+        publish_liquidity_withdrawn(
+            &env,
+            LiquidityEvent {
+                new_share_balance: liquidity::shares_of(&env, &token, &lender),
+                lender,
+                token,
+                amount,
+                shares,
+                event_version: EVENT_VERSION,
+                sequence: events::next_sequence(&env),
+            },
+        );
+    }
+
+    /// Get the shares of `token`'s pool held by a lender (view function).
+    pub fn get_lender_shares(env: Env, lender: Address, token: Address) -> i128 {
+        liquidity::shares_of(&env, &token, &lender)
+    }
+
+    /// Get the total shares outstanding in `token`'s pool (view function).
+    pub fn get_total_shares(env: Env, token: Address) -> i128 {
+        liquidity::total_shares(&env, &token)
+    }
+
+    /// Get the assets owned by `token`'s pool: deposits plus earned interest (view function).
+    pub fn get_pool_assets(env: Env, token: Address) -> i128 {
+        liquidity::pool_assets(&env, &token)
+    }
+
+    /// Get `token` pool assets per share, scaled by 10^7 (view function).
+    pub fn get_share_price(env: Env, token: Address) -> i128 {
+        liquidity::share_price(&env, &token)
+    }
+
+    /// Shares that depositing `amount` of `token` would mint (view function).
+    pub fn convert_to_shares(env: Env, token: Address, amount: i128) -> i128 {
+        liquidity::convert_to_shares(&env, &token, amount)
+    }
+
+    /// Assets redeemable for `shares` of `token`'s pool (view function).
+    pub fn convert_to_assets(env: Env, token: Address, shares: i128) -> i128 {
+        liquidity::convert_to_assets(&env, &token, shares)
+    }
+
+    /// Get the undrawn credit committed to Active lines in `token` (view function).
+    pub fn get_undrawn_commitments(env: Env, token: Address) -> i128 {
+        liquidity::undrawn_commitments(&env, &token)
+    }
+
+    /// Set the draw and origination fee schedule (admin only).
+    ///
+    /// # Panics
+    /// * `"draw_fee_bps cannot exceed 10000"`
+    /// * `"origination_fee cannot be negative"`
+    /// * `"premium and draw fee cannot exceed 10000 bps"`
+    pub fn set_fee_config(env: Env, config: FeeConfig) {
+        let admin = require_admin_auth(&env);
+        timelock::require_unlocked(&env);
+        fees::set_config(&env, &config);
+        audit::config_changed(&env, &admin, symbol_short!("fee_cfg"), None, config);
+    }
+
+    /// Get the draw and origination fee schedule (view function).
+    pub fn get_fee_config(env: Env) -> FeeConfig {
+        fees::get_config(&env)
+    }
+
+    /// Set the insurance premium withheld from each draw (admin only).
+    ///
+    /// # Panics
+    /// * `"premium and draw fee cannot exceed 10000 bps"`
+    pub fn set_insurance_config(env: Env, config: InsuranceConfig) {
+        let admin = require_admin_auth(&env);
+        timelock::require_unlocked(&env);
+        insurance::set_config(&env, &config);
+        audit::config_changed(&env, &admin, symbol_short!("ins_cfg"), None, config);
+    }
+
+    /// Get the insurance premium config (view function).
+    pub fn get_insurance_config(env: Env) -> InsuranceConfig {
+        insurance::get_config(&env)
+    }
+
+    /// Get the insurance fund balance held for `token` (view function).
+    pub fn get_insurance_fund(env: Env, token: Address) -> i128 {
+        insurance::fund_balance(&env, &token)
+    }
+
+    /// Set the reward program: points per unit of interest paid on time, the optional
+    /// reward token, and the emission caps (admin only). A `rate_bps` of 0 turns accrual off.
+    ///
+    /// # Panics
+    /// * `"reward caps cannot be negative"`
+    /// * `"reward token cannot be a credit token"`
+    pub fn set_rewards_config(env: Env, config: RewardsConfig) {
+        let admin = require_admin_auth(&env);
+        rewards::set_config(&env, &config);
+        audit::config_changed(&env, &admin, symbol_short!("rewards"), None, config);
+    }
+
+    /// Get the reward program settings (view function).
+    pub fn get_rewards_config(env: Env) -> RewardsConfig {
+        rewards::get_config(&env)
+    }
+
+    /// Pause or resume reward accrual and claims (admin or `Pauser` role).
+    pub fn set_rewards_paused(env: Env, caller: Address, paused: bool) {
+        access::require_role(&env, Role::Pauser, &caller);
+        rewards::set_paused(&env, paused);
+        audit::config_changed(&env, &caller, symbol_short!("rwd_pause"), None, paused);
+    }
+
+    /// Returns true while reward accrual and claims are paused (view function).
+    pub fn is_rewards_paused(env: Env) -> bool {
+        rewards::is_paused(&env)
+    }
+
+    /// Set the staking program: the token staked, the discount tiers, and the balance
+    /// above which stakes are locked (admin only).
+    ///
+    /// # Panics
+    /// * `"staking token cannot be a credit token"`
+    /// * `"staking token cannot change while staked"`
+    /// * `"staking tiers must ascend"`
+    /// * `"discount_bps cannot exceed 10000"`
+    /// * `"unstake_threshold cannot be negative"`
+    pub fn set_staking_config(env: Env, config: StakingConfig) {
+        let admin = require_admin_auth(&env);
+        timelock::require_unlocked(&env);
+        staking::set_config(&env, &config);
+        audit::config_changed(&env, &admin, symbol_short!("staking"), None, config);
+    }
+
+    /// Get the staking program settings (view function).
+    pub fn get_staking_config(env: Env) -> StakingConfig {
+        staking::get_config(&env)
+    }
+
+    /// Set the flash draw fee and maximum (admin only). A `max_amount` of 0 disables
+    /// flash draws.
+    ///
+    /// # Panics
+    /// * `"fee_bps cannot exceed 10000"`
+    /// * `"max_amount cannot be negative"`
+    pub fn set_flash_config(env: Env, config: FlashConfig) {
+        let admin = require_admin_auth(&env);
+        timelock::require_unlocked(&env);
+        flash::set_config(&env, &config);
+        audit::config_changed(&env, &admin, symbol_short!("flash"), None, config);
+    }
+
+    /// Get the flash draw settings (view function).
+    pub fn get_flash_config(env: Env) -> FlashConfig {
+        flash::get_config(&env)
+    }
+
+    /// Stake `amount` of the staking token to earn a rate discount on the borrower's line.
+    /// Interest up to now is accrued at the previous discount first.
+    ///
+    /// # Panics
+    /// * `"Credit line not found"`
+    /// * `"amount must be positive"`
+    /// * `"staking not enabled"`
+    pub fn stake(env: Env, borrower: Address, amount: i128) {
+        borrower.require_auth();
+        let mut credit_line = load_credit_line(&env, &borrower).expect("Credit line not found");
+        interest::accrue(&env, &mut credit_line);
+        save_credit_line(&env, &credit_line);
+        let staked = staking::stake(&env, &borrower, amount);
+        publish_stake_event(&env, symbol_short!("stake"), &borrower, amount, staked);
+    }
+
+    /// Withdraw `amount` of the borrower's stake. Only allowed while the line's
+    /// outstanding balance is at most the unstake threshold.
+    ///
+    /// # Panics
+    /// * `"amount must be positive"`
+    /// * `"insufficient stake"`
+    /// * `"unstake locked while balance outstanding"`
+    pub fn unstake(env: Env, borrower: Address, amount: i128) {
+        borrower.require_auth();
+        let outstanding = match load_credit_line(&env, &borrower) {
+            Some(mut credit_line) => {
+                interest::accrue(&env, &mut credit_line);
+                save_credit_line(&env, &credit_line);
+                interest::outstanding(&credit_line)
+            }
+            None => 0,
+        };
+        let staked = staking::unstake(&env, &borrower, amount, outstanding);
+        publish_stake_event(&env, symbol_short!("unstake"), &borrower, amount, staked);
+    }
+
+    /// Get the amount a borrower has staked (view function).
+    pub fn get_stake(env: Env, borrower: Address) -> i128 {
+        staking::staked(&env, &borrower)
+    }
+
+    /// Get the rate a borrower's line accrues at right now: the promotional or standard
+    /// rate less the staking discount (view function).
+    ///
+    /// # Panics
+    /// * `"Credit line not found"`
+    pub fn get_effective_rate(env: Env, borrower: Address) -> u32 {
+        let credit_line = load_credit_line(&env, &borrower).expect("Credit line not found");
+        staking::effective_rate(&env, &credit_line)
+    }
+
+    /// Get a borrower's unclaimed reward points (view function).
+    pub fn get_rewards(env: Env, borrower: Address) -> i128 {
+        rewards::points(&env, &borrower)
+    }
+
+    /// Claim a borrower's reward points, paid out in the reward token if one is set.
+    /// Returns the points claimed.
+    ///
+    /// # Panics
+    /// * `"rewards are paused"`
+    pub fn claim_rewards(env: Env, borrower: Address) -> i128 {
+        borrower.require_auth();
+        let claimed = rewards::claim(&env, &borrower);
+        if claimed > 0 {
+            publish_reward_event(
+                &env,
+                symbol_short!("rwd_claim"),
+                RewardEvent {
+                    borrower,
+                    points: claimed,
+                    balance: 0,
+                    event_version: EVENT_VERSION,
+                    sequence: events::next_sequence(&env),
+                },
+            );
+        }
+        claimed
+    }
+
+    /// Set the global minimum and maximum size of a single draw and the minimum time
+    /// between a line's draws; 0 disables a bound (admin only). Draws within the
+    /// cooldown revert with `ContractError::DrawCooldown`.
+    ///
+    /// # Panics
+    /// * `"draw limits cannot be negative"`
+    /// * `"min_draw_amount exceeds max_draw_amount"`
+    pub fn set_draw_limits(env: Env, limits: DrawLimits) {
+        let admin = require_admin_auth(&env);
+        draw_limits::set_config(&env, &limits);
+        audit::config_changed(&env, &admin, symbol_short!("draw_lim"), None, limits);
+    }
+
+    /// Set the draw velocity caps: per borrower per window of ledgers, and protocol-wide per
+    /// ledger; 0 disables a cap (admin only). Draws over a cap revert with
+    /// `ContractError::RateLimited`.
+    ///
+    /// # Panics
+    /// * `"velocity caps cannot be negative"`
+    /// * `"window_ledgers must be positive"` – a borrower cap needs a window
+    pub fn set_velocity_config(env: Env, config: VelocityConfig) {
+        let admin = require_admin_auth(&env);
+        velocity::set_config(&env, &config);
+        audit::config_changed(&env, &admin, symbol_short!("velocity"), None, config);
+    }
+
+    /// Set the minimum and warning reserve ratios in basis points of outstanding principal;
+    /// 0 disables a threshold (admin only).
+    ///
+    /// # Panics
+    /// * `"warning_ratio_bps below min_reserve_ratio_bps"`
+    pub fn set_reserve_config(env: Env, config: ReserveConfig) {
+        let admin = require_admin_auth(&env);
+        timelock::require_unlocked(&env);
+        reserve::set_config(&env, &config);
+        audit::config_changed(&env, &admin, symbol_short!("reserve"), None, config);
+    }
+
+    /// Set the critical reserve ratio in basis points of outstanding principal; 0 disables
+    /// it (admin only). While a token's ratio is below it, draws in that token are blocked.
+    ///
+    /// # Panics
+    /// * `"warning_ratio_bps below critical_ratio_bps"`
+    pub fn set_reserve_critical_ratio(env: Env, bps: u32) {
+        let admin = require_admin_auth(&env);
+        timelock::require_unlocked(&env);
+        reserve::set_critical_ratio_bps(&env, bps);
+        audit::config_changed(&env, &admin, symbol_short!("res_crit"), None, bps);
+    }
+
+    /// Register an external reserve account that draws may pull from once the contract's
+    /// own reserve runs short (admin only). Sources are used in ascending `priority` and
+    /// must approve this contract to spend their tokens. Re-adding updates the priority.
+    pub fn add_liquidity_source(env: Env, source: Address, priority: u32) {
+        let admin = require_admin_auth(&env);
+        audit::record(&env, &admin, symbol_short!("src_add"), None);
+        sources::add(&env, &source, priority);
+        config::sync(&env);
+        publish_liquidity_source_set(
+            &env,
+            LiquiditySourceSetEvent {
+                source,
+                priority,
+                registered: true,
+                event_version: EVENT_VERSION,
+                sequence: events::next_sequence(&env),
+            },
+        );
+    }
+
+    /// Remove a registered liquidity source (admin only).
+    ///
+    /// # Panics
+    /// * `"liquidity source not found"`
+    pub fn remove_liquidity_source(env: Env, source: Address) {
+        let admin = require_admin_auth(&env);
+        audit::record(&env, &admin, symbol_short!("src_rm"), None);
+        sources::remove(&env, &source);
+        config::sync(&env);
+        publish_liquidity_source_set(
+            &env,
+            LiquiditySourceSetEvent {
+                source,
+                priority: 0,
+                registered: false,
+                event_version: EVENT_VERSION,
+                sequence: events::next_sequence(&env),
+            },
+        );
+    }
+
+    /// Move `amount` of `token` from liquidity source `from` to source `to`, using the
+    /// allowance `from` gave this contract (admin or `Treasurer` role). The move may not
+    /// take the liquidity available to draws below what the reserve policy requires.
+    ///
+    /// # Panics
+    /// * `"amount must be positive"`
+    /// * `"cannot rebalance to the same source"`
+    /// * `"liquidity source not found"` – `from` or `to` is not registered
+    /// * `"rebalance breaches reserve policy"` – available liquidity would no longer cover
+    ///   undrawn commitments, fees and insurance, or the minimum or critical reserve ratio
+    pub fn rebalance_reserve(
+        env: Env,
+        caller: Address,
+        token: Address,
+        from: Address,
+        to: Address,
+        amount: i128,
+    ) {
+        access::require_role(&env, Role::Treasurer, &caller);
+        audit::record(&env, &caller, symbol_short!("rebalance"), None);
+        sources::rebalance(&env, &token, &from, &to, amount);
+        publish_reserve_rebalanced(
+            &env,
+            ReserveRebalancedEvent {
+                caller,
+                total_available: sources::total_available(&env, &token),
+                token,
+                from,
+                to,
+                amount,
+                event_version: EVENT_VERSION,
+                sequence: events::next_sequence(&env),
+            },
+        );
+    }
+
+    /// Get the liquidity sources in priority order (view function).
+    pub fn get_liquidity_sources(env: Env) -> Vec<LiquiditySource> {
+        sources::list(&env)
+    }
+
+    /// Get the highest-priority liquidity source, or `None` when draws are paid from
+    /// the contract's own reserve alone (view function).
+    pub fn get_liquidity_source(env: Env) -> Option<Address> {
+        sources::top(&env)
+    }
+
+    /// Set where repayments are forwarded: kept in the reserve (default), the
+    /// highest-priority source, or a fixed sink (admin only).
+    pub fn set_repayment_route(env: Env, route: RepaymentRoute) {
+        let admin = require_admin_auth(&env);
+        sources::set_route(&env, &route);
+        audit::config_changed(&env, &admin, symbol_short!("repay_rt"), None, route);
+    }
+
+    /// Get the repayment route (view function).
+    pub fn get_repayment_route(env: Env) -> RepaymentRoute {
+        sources::get_route(&env)
+    }
+
+    /// Get the reserve ratio thresholds (view function).
+    pub fn get_reserve_config(env: Env) -> ReserveConfig {
+        reserve::get_config(&env)
+    }
+
+    /// Get the critical reserve ratio; 0 if disabled (view function).
+    pub fn get_reserve_critical_ratio(env: Env) -> u32 {
+        reserve::critical_ratio_bps(&env)
+    }
+
+    /// Get `token`'s reserve ratio: contract balance over outstanding principal, in basis
+    /// points; `i128::MAX` when nothing is outstanding (view function).
+    pub fn get_reserve_ratio_bps(env: Env, token: Address) -> i128 {
+        let reserve = token::Client::new(&env, &token).balance(&env.current_contract_address());
+        math::checked(
+            &env,
+            reserve::ratio_bps(reserve, liquidity::outstanding(&env, &token)),
+        )
+    }
+
+    /// Get the draw velocity caps (view function).
+    pub fn get_velocity_config(env: Env) -> VelocityConfig {
+        velocity::get_config(&env)
+    }
+
+    /// Amount the borrower has drawn in the current velocity window (view function).
+    pub fn get_drawn_in_window(env: Env, borrower: Address) -> i128 {
+        velocity::drawn_in_window(&env, &borrower)
+    }
+
+    /// Get the borrower's per-epoch spending cap; zero when none is set (view function).
+    pub fn get_epoch_spend_cap(env: Env, borrower: Address) -> SpendCap {
+        velocity::get_spend_cap(&env, &borrower)
+    }
+
+    /// Amount the borrower has drawn in the current spend-cap epoch (view function).
+    pub fn get_spent_in_epoch(env: Env, borrower: Address) -> i128 {
+        velocity::spent_in_epoch(&env, &borrower)
+    }
+
+    /// Get the global draw limits (view function).
+    pub fn get_draw_limits(env: Env) -> DrawLimits {
+        draw_limits::get_config(&env)
+    }
+
+    /// Override the draw limits for one line; a 0 field falls back to the global limit
+    /// (admin only).
+    ///
+    /// # Panics
+    /// * `"Credit line not found"`
+    /// * `"credit line is closed"`
+    /// * `"draw limits cannot be negative"`
+    /// * `"min_draw_amount exceeds max_draw_amount"`
+    pub fn set_line_draw_limits(env: Env, borrower: Address, limits: DrawLimits) {
+        let admin = require_admin_auth(&env);
+        draw_limits::validate(&limits);
+        let mut credit_line: CreditLineData =
+            load_credit_line(&env, &borrower).expect("Credit line not found");
+        if credit_line.status == CreditStatus::Closed {
+            panic!("credit line is closed");
+        }
+        restrictions::require_updates_allowed(&credit_line);
+        draw_limits::set_line_limits(&env, &mut credit_line, &limits);
+        save_credit_line(&env, &credit_line);
+        audit::config_changed(
+            &env,
+            &admin,
+            symbol_short!("line_lim"),
+            Some(borrower.clone()),
+            (borrower, limits),
+        );
+    }
+
+    /// Restrict one line's draws to `allowed` purpose codes; an empty list lifts the
+    /// restriction (admin only). Draws on a restricted line without an allowed purpose
+    /// revert with `"purpose not allowed"`.
+    ///
+    /// # Panics
+    /// * `"Credit line not found"`
+    /// * `"credit line is closed"`
+    /// * `"updates frozen"`
+    pub fn set_allowed_purposes(env: Env, borrower: Address, allowed: Vec<Symbol>) {
+        let admin = require_admin_auth(&env);
+        let credit_line: CreditLineData =
+            load_credit_line(&env, &borrower).expect("Credit line not found");
+        if credit_line.status == CreditStatus::Closed {
+            panic!("credit line is closed");
+        }
+        restrictions::require_updates_allowed(&credit_line);
+        purposes::set_allowed(&env, &borrower, &allowed);
+        audit::config_changed(
+            &env,
+            &admin,
+            symbol_short!("purposes"),
+            Some(borrower.clone()),
+            (borrower, allowed),
+        );
+    }
+
+    /// Get the purpose codes a line may draw for; empty when unrestricted (view function).
+    pub fn get_allowed_purposes(env: Env, borrower: Address) -> Vec<Symbol> {
+        purposes::allowed(&env, &borrower)
+    }
+
+    /// Get the borrower's most recent draws with their purpose codes, oldest first
+    /// (view function).
+    pub fn get_draw_log(env: Env, borrower: Address) -> Vec<DrawRecord> {
+        purposes::log(&env, &borrower)
+    }
+
+    /// Set the first-payment grace period and prepayment penalty given to new lines
+    /// (admin only).
+    ///
+    /// # Panics
+    /// * `"prepayment_penalty_bps cannot exceed 10000"`
+    pub fn set_repayment_terms(env: Env, terms: RepaymentTerms) {
+        let admin = require_admin_auth(&env);
+        timelock::require_unlocked(&env);
+        terms::set_config(&env, &terms);
+        audit::config_changed(&env, &admin, symbol_short!("terms"), None, terms);
+    }
+
+    /// Get the repayment terms given to new lines (view function).
+    pub fn get_repayment_terms(env: Env) -> RepaymentTerms {
+        terms::get_config(&env)
+    }
+
+    /// Set the compounding and day-count convention given to new lines (admin only).
+    pub fn set_interest_config(env: Env, config: InterestConfig) {
+        let admin = require_admin_auth(&env);
+        timelock::require_unlocked(&env);
+        interest::set_config(&env, &config);
+        audit::config_changed(&env, &admin, symbol_short!("int_cfg"), None, config);
+    }
+
+    /// Get the convention given to new lines (view function).
+    pub fn get_interest_config(env: Env) -> InterestConfig {
+        interest::get_config(&env)
+    }
+
+    /// Set how Defaulted lines accrue interest (admin only): continue, optionally at
+    /// `penalty_rate_bps`, freeze at the default, or freeze `freeze_after_secs` after it.
+    /// Applies to every Defaulted line from its next accrual.
+    ///
+    /// # Panics
+    /// * `"interest_rate_bps exceeds maximum"`
+    /// * `"freeze_after_secs must be greater than zero"`
+    pub fn set_default_interest_config(env: Env, config: DefaultInterestConfig) {
+        let admin = require_admin_auth(&env);
+        timelock::require_unlocked(&env);
+        default_interest::set_config(&env, &config);
+        audit::config_changed(&env, &admin, symbol_short!("dflt_int"), None, config);
+    }
+
+    /// Get the post-default interest policy (view function).
+    pub fn get_default_interest_config(env: Env) -> DefaultInterestConfig {
+        default_interest::get_config(&env)
+    }
+
+    /// Change one line's interest convention (admin only). Interest up to now
+    /// accrues under the previous convention.
+    ///
+    /// # Panics
+    /// * `"Credit line not found"`
+    /// * `"credit line is closed"`
+    pub fn set_line_interest_config(env: Env, borrower: Address, config: InterestConfig) {
+        let admin = require_admin_auth(&env);
+        let mut credit_line: CreditLineData =
+            load_credit_line(&env, &borrower).expect("Credit line not found");
+        if credit_line.status == CreditStatus::Closed {
+            panic!("credit line is closed");
+        }
+        restrictions::require_updates_allowed(&credit_line);
+        interest::accrue(&env, &mut credit_line);
+        credit_line.interest_config = config;
+        save_credit_line(&env, &credit_line);
+        audit::config_changed(
+            &env,
+            &admin,
+            symbol_short!("line_int"),
+            Some(borrower.clone()),
+            (borrower, config),
+        );
+    }
+
+    /// Set the treasury address that receives protocol fees (admin only).
+    pub fn set_treasury(env: Env, treasury: Address) {
+        let admin = require_admin_auth(&env);
+        treasury::set_treasury(&env, &treasury);
+        audit::config_changed(&env, &admin, symbol_short!("treasury"), None, treasury);
+    }
+
+    /// Set the share of collected interest kept by the protocol, in basis points (admin only).
+    ///
+    /// # Panics
+    /// * `"reserve_factor_bps cannot exceed 10000"`
+    pub fn set_reserve_factor_bps(env: Env, bps: u32) {
+        let admin = require_admin_auth(&env);
+        timelock::require_unlocked(&env);
+        treasury::set_reserve_factor_bps(&env, bps);
+        audit::config_changed(&env, &admin, symbol_short!("res_fctr"), None, bps);
+    }
+
+    /// Get the treasury address, if set (view function).
+    pub fn get_treasury(env: Env) -> Option<Address> {
+        treasury::get_treasury(&env)
+    }
+
+    /// Get the protocol reserve factor in basis points (view function).
+    pub fn get_reserve_factor_bps(env: Env) -> u32 {
+        treasury::reserve_factor_bps(&env)
+    }
+
+    /// Set how collected interest is divided between liquidity providers, the treasury and
+    /// the insurance fund, in basis points summing to 10000 (admin only). Once set, the
+    /// split replaces the reserve factor.
+    ///
+    /// # Panics
+    /// * `"revenue split must sum to 10000"`
+    pub fn set_revenue_split(env: Env, split: RevenueSplit) {
+        let admin = require_admin_auth(&env);
+        timelock::require_unlocked(&env);
+        revenue::set_split(&env, &split);
+        audit::config_changed(&env, &admin, symbol_short!("rev_split"), None, split);
+    }
+
+    /// Get the revenue split in effect; derived from the reserve factor until one is set
+    /// (view function).
+    pub fn get_revenue_split(env: Env) -> RevenueSplit {
+        revenue::get_split(&env)
+    }
+
+    /// Get the interest in `token` credited to each bucket so far (view function).
+    pub fn get_revenue_distributed(env: Env, token: Address) -> RevenueShares {
+        revenue::distributed(&env, &token)
+    }
+
+    /// Get protocol fees in `token` accumulated and not yet collected (view function).
+    pub fn get_protocol_fees(env: Env, token: Address) -> i128 {
+        treasury::accrued_fees(&env, &token)
+    }
+
+    /// Transfer accumulated protocol fees in `token` to the treasury (admin or `Treasurer`
+    /// role). Returns the amount transferred.
+    ///
+    /// # Panics
+    /// * `"treasury not set"` – no treasury address configured
+    pub fn collect_protocol_fees(env: Env, caller: Address, token: Address) -> i128 {
+        access::require_role(&env, Role::Treasurer, &caller);
+        audit::record(&env, &caller, symbol_short!("fee_coll"), None);
+        let treasury = treasury::get_treasury(&env).expect("treasury not set");
+
+        let amount = treasury::take_accrued_fees(&env, &token);
+        if amount > 0 {
+            token::Client::new(&env, &token).transfer(
+                &env.current_contract_address(),
+                &treasury,
+                &amount,
+            );
+        }
+
+        publish_protocol_fees_collected(
+            &env,
+            ProtocolFeesCollectedEvent {
+                caller,
+                treasury,
+                token,
+                amount,
+                event_version: EVENT_VERSION,
+                sequence: events::next_sequence(&env),
+            },
+        );
+        amount
+    }
+
+    /// Send `amount` of a token mistakenly sent to the contract to `to` (admin or
+    /// `Treasurer` role). Supported credit tokens can never be swept, and collateral
+    /// and stakes held for borrowers stay untouched.
+    ///
+    /// # Panics
+    /// * `"amount must be positive"`
+    /// * `"cannot sweep credit token"` – `token` is a supported denomination or still in use
+    /// * `"sweep exceeds stray balance"` – amount would dip into held collateral or stakes
+    pub fn sweep_token(env: Env, caller: Address, token: Address, to: Address, amount: i128) {
+        access::require_role(&env, Role::Treasurer, &caller);
+        audit::record(&env, &caller, symbol_short!("sweep"), None);
+        if amount <= 0 {
+            panic!("amount must be positive");
+        }
+        // Delisted tokens still back open lines, LP shares, fees, or insurance until those run off.
+        let in_use = tokens::is_supported(&env, &token)
+            || liquidity::outstanding(&env, &token) != 0
+            || liquidity::total_shares(&env, &token) != 0
+            || treasury::accrued_fees(&env, &token) != 0
+            || insurance::fund_balance(&env, &token) != 0;
+        if in_use {
+            panic!("cannot sweep credit token");
+        }
+        let token_client = token::Client::new(&env, &token);
+        let stray = token_client.balance(&env.current_contract_address())
+            - collateral::total_held(&env, &token)
+            - staking::held(&env, &token);
+        if amount > stray {
+            panic!("sweep exceeds stray balance");
+        }
+        token_client.transfer(&env.current_contract_address(), &to, &amount);
+        publish_sweep(
+            &env,
+            SweepEvent {
+                caller,
+                token,
+                to,
+                amount,
+                event_version: EVENT_VERSION,
+                sequence: events::next_sequence(&env),
+            },
+        );
+    }
+
+    /// Open a new credit line for a borrower (called by backend/risk engine).
+    /// A configured origination fee is added to the new line's utilized amount.
+    ///
+    /// # Arguments
+    /// * `caller` - Must be the admin or hold the `RiskEngine` role.
+    /// * `schedule` - Optional billing cycle; the first cycle starts at open time.
+    /// * `token` - Denomination; `None` uses the default token given to `init`.
+    /// * `op_id` - Optional idempotency key; reusing one fails with `DuplicateOperation`.
+    ///
+    /// # Panics
+    /// * If `caller` is neither the admin nor a risk engine
+    /// * If `credit_limit` <= 0
+    /// * If `interest_rate_bps` > 10000
+    /// * If `risk_score` > 100
+    /// * If an Active credit line already exists for the borrower
+    /// * If `schedule` has a zero cycle length or `min_payment_bps` > 10000
+    /// * If the origination fee exceeds `credit_limit`
+    /// * If `token` is not supported
+    /// * `ContractError::NotAllowlisted` – the allowlist is enabled and `borrower` is not on it
+    /// * `ContractError::ExposureCapExceeded` – the origination fee would take exposure above a cap
+    pub fn open_credit_line(
+        env: Env,
+        caller: Address,
+        borrower: Address,
+        credit_limit: i128,
+        interest_rate_bps: u32,
+        risk_score: u32,
+        schedule: Option<RepaymentSchedule>,
+        rate_mode: RateMode,
+        token: Option<Address>,
+        op_id: Option<BytesN<32>>,
+    ) {
+        access::require_role(&env, Role::RiskEngine, &caller);
+        audit::record(&env, &caller, symbol_short!("open"), Some(borrower.clone()));
+        idempotency::consume(&env, &op_id);
+        open_line(
+            &env,
+            borrower,
+            credit_limit,
+            interest_rate_bps,
+            risk_score,
+            schedule,
+            rate_mode,
+            token,
+        );
+    }
+
+    /// Open a credit line on the terms of risk tier `tier_id` instead of a raw limit and
+    /// rate (admin or `RiskEngine` role). The line follows later updates to the tier.
+    /// Otherwise behaves like `open_credit_line`.
+    ///
+    /// # Panics
+    /// * `"tier not found"`
+    /// * Any `open_credit_line` panic
+    pub fn open_credit_line_in_tier(
+        env: Env,
+        caller: Address,
+        borrower: Address,
+        tier_id: u32,
+        risk_score: u32,
+        schedule: Option<RepaymentSchedule>,
+        rate_mode: RateMode,
+        token: Option<Address>,
+        op_id: Option<BytesN<32>>,
+    ) {
+        access::require_role(&env, Role::RiskEngine, &caller);
+        audit::record(&env, &caller, symbol_short!("open"), Some(borrower.clone()));
+        idempotency::consume(&env, &op_id);
+        let tier = tiers::get(&env, tier_id).expect("tier not found");
+        open_line(
+            &env,
+            borrower.clone(),
+            tier.max_limit,
+            tier.rate_bps,
+            risk_score,
+            schedule,
+            rate_mode,
+            token,
+        );
+        let credit_line = load_credit_line(&env, &borrower).expect("Credit line not found");
+        tiers::assign(&env, &credit_line, tier_id);
+        exposure::require_within_caps(&env, &borrower, 0);
+    }
+
+    /// Create or replace risk tier `tier_id` (admin only). Lines in the tier take the new
+    /// limit and rate the next time they are touched, accruing interest at the old rate
+    /// first; its LTV and draw fee apply from their next draw.
+    ///
+    /// # Panics
+    /// * `"credit_limit must be greater than zero"` – `max_limit` is not positive
+    /// * `"interest_rate_bps exceeds maximum"`
+    /// * `"ltv_bps cannot exceed 10000"`
+    /// * `"draw_fee_bps cannot exceed 10000"`
+    /// * `"premium and draw fee cannot exceed 10000 bps"`
+    pub fn set_tier(env: Env, tier_id: u32, tier: Tier) {
+        let admin = require_admin_auth(&env);
+        timelock::require_unlocked(&env);
+        tiers::set(&env, tier_id, &tier);
+        audit::config_changed(&env, &admin, symbol_short!("tier"), None, (tier_id, tier));
+    }
+
+    /// Cap the outstanding principal across the lines in tier `tier_id`; 0 removes the
+    /// cap (admin only). Draws and tier openings that would exceed it revert with
+    /// `ContractError::ExposureCapExceeded`.
+    ///
+    /// # Panics
+    /// * `"cap cannot be negative"`
+    /// * `"tier not found"`
+    pub fn set_exposure_cap(env: Env, tier_id: u32, cap: i128) {
+        let admin = require_admin_auth(&env);
+        exposure::set_tier_cap(&env, tier_id, cap);
+        audit::config_changed(
+            &env,
+            &admin,
+            symbol_short!("tier_cap"),
+            None,
+            (tier_id, cap),
+        );
+    }
+
+    /// Get a tier's exposure cap; 0 when uncapped (view function).
+    pub fn get_exposure_cap(env: Env, tier_id: u32) -> i128 {
+        exposure::tier_cap(&env, tier_id)
+    }
+
+    /// Get the outstanding principal across the lines in a tier (view function).
+    pub fn get_tier_exposure(env: Env, tier_id: u32) -> i128 {
+        tiers::outstanding(&env, tier_id)
+    }
+
+    /// Cap the protocol-wide outstanding principal; 0 removes the cap (admin only).
+    /// Draws and openings that would exceed it revert with
+    /// `ContractError::ExposureCapExceeded`.
+    ///
+    /// # Panics
+    /// * `"cap cannot be negative"`
+    pub fn set_protocol_exposure_cap(env: Env, cap: i128) {
+        let admin = require_admin_auth(&env);
+        exposure::set_protocol_cap(&env, cap);
+        audit::config_changed(&env, &admin, symbol_short!("exp_cap"), None, cap);
+    }
+
+    /// Get the protocol-wide exposure cap; 0 when uncapped (view function).
+    pub fn get_protocol_exposure_cap(env: Env) -> i128 {
+        exposure::protocol_cap(&env)
+    }
+
+    /// Get a risk tier's terms, if defined (view function).
+    pub fn get_tier(env: Env, tier_id: u32) -> Option<Tier> {
+        tiers::get(&env, tier_id)
+    }
+
+    /// Get the tier a borrower's line is in and the tier version it last synced to, if
+    /// any (view function).
+    pub fn get_line_tier(env: Env, borrower: Address) -> Option<LineTier> {
+        tiers::line_tier(&env, &borrower)
+    }
+
+    /// Publish the reference rate that Variable lines track (admin or `RateOracle` role).
+    /// Variable lines pick up the new rate the next time they accrue interest.
+    ///
+    /// # Panics
+    /// * `"interest_rate_bps exceeds maximum"`
+    pub fn set_reference_rate_bps(env: Env, caller: Address, bps: u32) {
+        access::require_role(&env, Role::RateOracle, &caller);
+        rates::set_reference_rate_bps(&env, bps);
+        audit::config_changed(&env, &caller, symbol_short!("ref_rate"), None, bps);
+    }
+
+    /// Get the reference rate for Variable lines (view function).
+    pub fn get_reference_rate_bps(env: Env) -> u32 {
+        rates::reference_rate_bps(&env)
+    }
+
+    /// Register the ed25519 public key that signs risk engine payloads (admin only).
+    pub fn set_risk_engine_key(env: Env, public_key: BytesN<32>) {
+        let admin = require_admin_auth(&env);
+        signed::set_public_key(&env, &public_key);
+        audit::config_changed(&env, &admin, symbol_short!("re_key"), None, public_key);
+    }
+
+    /// Get the registered risk engine public key, if any (view function).
+    pub fn get_risk_engine_key(env: Env) -> Option<BytesN<32>> {
+        signed::get_public_key(&env)
+    }
+
+    /// Returns true if an idempotency key has been consumed (view function).
+    pub fn is_operation_consumed(env: Env, op_id: BytesN<32>) -> bool {
+        idempotency::is_consumed(&env, &op_id)
+    }
+
+    /// Returns true if a signed-operation nonce has been consumed (view function).
+    pub fn is_nonce_used(env: Env, nonce: u64) -> bool {
+        signed::is_nonce_used(&env, nonce)
+    }
+
+    /// Open a credit line from a payload signed by the risk engine key. Anyone may submit.
+    ///
+    /// # Panics
+    /// * `"risk engine key not set"`
+    /// * `"nonce already used"`
+    /// * If the signature does not verify
+    /// * Any `open_credit_line` validation panic
+    pub fn open_credit_line_signed(env: Env, payload: SignedOpenCreditLine, signature: BytesN<64>) {
+        signed::verify(&env, &payload, payload.nonce, &signature);
+        audit::record(
+            &env,
+            &env.current_contract_address(),
+            symbol_short!("open"),
+            Some(payload.borrower.clone()),
+        );
+        let schedule = payload.schedule();
+        open_line(
+            &env,
+            payload.borrower,
+            payload.credit_limit,
+            payload.interest_rate_bps,
+            payload.risk_score,
+            schedule,
+            payload.rate_mode,
+            payload.token,
+        );
+    }
+
+    /// Commit the Merkle root of approved `(borrower, terms)` pairs for self-serve
+    /// opening with `claim_credit_line` (admin or `RiskEngine` role). Replaces any
+    /// previously committed root.
+    pub fn commit_open_root(env: Env, caller: Address, root: BytesN<32>) {
+        access::require_role(&env, Role::RiskEngine, &caller);
+        onboarding::set_root(&env, &root);
+        audit::config_changed(&env, &caller, symbol_short!("open_root"), None, root);
+    }
+
+    /// Get the committed onboarding root, if any (view function).
+    pub fn get_open_root(env: Env) -> Option<BytesN<32>> {
+        onboarding::get_root(&env)
+    }
+
+    /// Returns true if the borrower has claimed a line under the committed root (view function).
+    pub fn is_line_claimed(env: Env, borrower: Address) -> bool {
+        onboarding::is_claimed(&env, &borrower)
+    }
+
+    /// Open the borrower's own credit line on `terms` approved in the committed root
+    /// (borrower). `proof` lists the sibling hashes from the borrower's leaf up to the
+    /// root. Each borrower can claim once per root.
+    ///
+    /// # Panics
+    /// * `"open root not committed"`
+    /// * `"credit line already claimed"`
+    /// * `"invalid merkle proof"` – the terms are not in the committed root
+    /// * Any `open_credit_line` validation panic
+    pub fn claim_credit_line(
+        env: Env,
+        borrower: Address,
+        terms: LineTerms,
+        proof: Vec<BytesN<32>>,
+    ) {
+        borrower.require_auth();
+        onboarding::claim(&env, &borrower, &terms, &proof);
+        audit::record(
+            &env,
+            &borrower,
+            symbol_short!("open"),
+            Some(borrower.clone()),
+        );
+        let schedule = terms.schedule();
+        open_line(
+            &env,
+            borrower,
+            terms.credit_limit,
+            terms.interest_rate_bps,
+            terms.risk_score,
+            schedule,
+            terms.rate_mode,
+            terms.token,
+        );
+    }
+
+    /// Update risk parameters from a payload signed by the risk engine key. Anyone may submit.
+    ///
+    /// # Panics
+    /// * `"risk engine key not set"`
+    /// * `"nonce already used"`
+    /// * If the signature does not verify
+    /// * Any `update_risk_parameters` validation panic
+    pub fn update_risk_parameters_signed(
+        env: Env,
+        payload: SignedRiskUpdate,
+        signature: BytesN<64>,
+    ) {
+        signed::verify(&env, &payload, payload.nonce, &signature);
+        audit::record(
+            &env,
+            &env.current_contract_address(),
+            symbol_short!("risk_upd"),
+            Some(payload.borrower.clone()),
+        );
+        update_risk(
+            &env,
+            payload.borrower,
+            payload.credit_limit,
+            payload.interest_rate_bps,
+            payload.risk_score,
+        );
+    }
+
+    /// Draw from credit line: verifies limit, updates utilized_amount,
+    /// and transfers the protocol token from the contract reserve to the borrower.
+    /// The full `amount` counts against the limit; any draw fee is withheld from the transfer.
+    /// `purpose` optionally tags the draw with a category code, recorded in the borrower's
+    /// draw log and the DrawnEvent.
+    ///
+    /// # Panics
+    /// - `"Credit line not found"` – borrower has no open credit line
+    /// - `"credit line is closed"` – line is closed
+    /// - `"Credit line not active"` – line is suspended or defaulted
+    /// - `"credit line has matured"` – past `maturity_ts`; only repayment is allowed
+    /// - `"exceeds credit limit"` – draw would push utilized_amount past credit_limit
+    /// - `"exceeds collateralized limit"` – draw exceeds the unsecured portion plus collateral LTV
+    /// - `"draw below minimum"` / `"draw exceeds maximum"` – outside the per-draw limits
+    /// - `"purpose not allowed"` – the line is restricted to purposes not including `purpose`
+    /// - `"insufficient liquidity"` – reserve and liquidity sources cannot cover the draw
+    /// - `"reserve ratio too low"` – draw would push the reserve below `min_reserve_ratio_bps`
+    /// - `"draw requires approval"` – above the large-draw threshold; use `request_large_draw`
+    /// - `"amount must be positive"` – amount is zero or negative
+    /// - `"reentrancy guard"` – re-entrant call detected
+    /// - `ContractError::Paused` – draws are paused
+    /// - `ContractError::DrawCooldown` – the line drew within its draw cooldown
+    /// - `ContractError::RateLimited` – draw would exceed a velocity cap or the line's spend cap
+    /// - `"transfers frozen"` – the line's outgoing transfers are frozen
+    /// - `ContractError::NotAllowlisted` – the allowlist is enabled and `borrower` is not on it
+    /// - `ContractError::Blocked` – `borrower` is on the blocklist
+    /// - `ContractError::ExposureCapExceeded` – draw would take protocol or tier exposure above its cap
+    pub fn draw_credit(env: Env, borrower: Address, amount: i128, purpose: Option<Symbol>) {
+        borrower.require_auth();
+        draw_to(&env, borrower.clone(), borrower, amount, purpose, false);
+    }
+
+    /// Set the large-draw breaker: draws above `threshold` (0 disables) must be requested
+    /// and approved, and requests stay approvable for `expiry_ledgers` (admin only).
+    ///
+    /// # Panics
+    /// * `"threshold cannot be negative"`
+    pub fn set_large_draw_config(env: Env, config: LargeDrawConfig) {
+        let admin = require_admin_auth(&env);
+        large_draws::set_config(&env, &config);
+        audit::config_changed(&env, &admin, symbol_short!("ld_cfg"), None, config);
+    }
+
+    /// Get the large-draw breaker policy (view function).
+    pub fn get_large_draw_config(env: Env) -> LargeDrawConfig {
+        large_draws::get_config(&env)
+    }
+
+    /// Request a draw above the large-draw threshold (borrower). Replaces any pending
+    /// request and returns the new request id. Emits a LargeDrawEvent.
+    ///
+    /// # Panics
+    /// * `"Credit line not found"`
+    /// * `"draw does not need approval"` – amount is within the threshold
+    pub fn request_large_draw(env: Env, borrower: Address, amount: i128) -> u64 {
+        borrower.require_auth();
+        if !storage::has_persistent(&env, &DataKey::Line(borrower.clone())) {
+            panic!("Credit line not found");
+        }
+        let pending = large_draws::request(&env, &borrower, amount);
+        publish_large_draw_event(
+            &env,
+            (symbol_short!("credit"), symbol_short!("ld_req")),
+            LargeDrawEvent {
+                borrower,
+                request_id: pending.request_id,
+                amount: pending.amount,
+                expiry_ledger: pending.expiry_ledger,
+                event_version: EVENT_VERSION,
+                sequence: events::next_sequence(&env),
+            },
+        );
+        pending.request_id
+    }
+
+    /// Approve and execute a pending large draw (admin or `RiskEngine` role). The draw
+    /// goes to the borrower and is otherwise checked like `draw_credit`. Emits a
+    /// LargeDrawEvent.
+    ///
+    /// # Panics
+    /// * `"no pending draw"`
+    /// * `"request id mismatch"` – `request_id` is not the borrower's pending request
+    /// * `"draw request expired"`
+    /// * Any `draw_credit` panic
+    pub fn approve_large_draw(env: Env, caller: Address, borrower: Address, request_id: u64) {
+        access::require_role(&env, Role::RiskEngine, &caller);
+        audit::record(
+            &env,
+            &caller,
+            symbol_short!("ld_appr"),
+            Some(borrower.clone()),
+        );
+        let pending = large_draws::take(&env, &borrower, request_id);
+        draw_to(
+            &env,
+            borrower.clone(),
+            borrower.clone(),
+            pending.amount,
+            None,
+            true,
+        );
+        publish_large_draw_event(
+            &env,
+            (symbol_short!("credit"), symbol_short!("ld_appr")),
+            LargeDrawEvent {
+                borrower,
+                request_id,
+                amount: pending.amount,
+                expiry_ledger: pending.expiry_ledger,
+                event_version: EVENT_VERSION,
+                sequence: events::next_sequence(&env),
+            },
+        );
+    }
+
+    /// Get the borrower's pending large draw, if any (view function).
+    pub fn get_pending_draw(env: Env, borrower: Address) -> Option<PendingDraw> {
+        large_draws::get_pending(&env, &borrower)
+    }
+
+    /// Open a stream that vests `total_amount` linearly over `duration_secs` (borrower).
+    /// The unclaimed amount is held against the credit limit. Emits a StreamEvent.
+    ///
+    /// # Panics
+    /// * `"Credit line not found"`
+    /// * `"credit line is closed"`
+    /// * `"amount must be positive"`
+    /// * `"duration_secs must be positive"`
+    /// * `"stream already open"`
+    /// * `"exceeds credit limit"`
+    pub fn open_stream(env: Env, borrower: Address, total_amount: i128, duration_secs: u64) {
+        borrower.require_auth();
+        let credit_line: CreditLineData =
+            load_credit_line(&env, &borrower).expect("Credit line not found");
+        if credit_line.status == CreditStatus::Closed {
+            panic!("credit line is closed");
+        }
+        let stream = streams::open(&env, &borrower, total_amount, duration_secs);
+        // Checked after `open` so an invalid amount reports its own panic.
+        let committed = math::checked(&env, math::add(credit_line.utilized_amount, total_amount))
+            .saturating_add(delegation::reserved(&env, &borrower));
+        if committed > boosts::effective_limit(&env, &credit_line) {
+            panic!("exceeds credit limit");
+        }
+        publish_stream(&env, symbol_short!("stream"), borrower, &stream, 0);
+    }
+
+    /// Draw the vested, unclaimed part of the borrower's stream (borrower). The claim is
+    /// checked and charged like `draw_credit`. Returns the amount drawn. Emits a
+    /// StreamEvent.
+    ///
+    /// # Panics
+    /// * `"no open stream"`
+    /// * `"nothing to claim"` – nothing has vested since the last claim
+    /// * Any `draw_credit` panic
+    pub fn claim_streamed(env: Env, borrower: Address) -> i128 {
+        borrower.require_auth();
+        let (stream, amount) = streams::take_claimable(&env, &borrower);
+        draw_to(
+            &env,
+            borrower.clone(),
+            borrower.clone(),
+            amount,
+            None,
+            false,
+        );
+        publish_stream(&env, symbol_short!("stream_cl"), borrower, &stream, amount);
+        amount
+    }
+
+    /// Get the borrower's open stream, if any (view function).
+    pub fn get_stream(env: Env, borrower: Address) -> Option<Stream> {
+        streams::get(&env, &borrower)
+    }
+
+    /// Amount of the borrower's stream vested and not yet claimed (view function).
+    pub fn get_stream_claimable(env: Env, borrower: Address) -> i128 {
+        streams::get(&env, &borrower).map_or(0, |stream| streams::claimable(&env, &stream))
+    }
+
+    /// Schedule a draw of `amount` to run at or after `execute_after_ts` (borrower).
+    /// Returns the new draw id. Emits a ScheduledDrawEvent.
+    ///
+    /// # Panics
+    /// * `"Credit line not found"`
+    /// * `"amount must be positive"`
+    /// * `"execution time must be in the future"`
+    pub fn schedule_draw(env: Env, borrower: Address, amount: i128, execute_after_ts: u64) -> u64 {
+        borrower.require_auth();
+        if !storage::has_persistent(&env, &DataKey::Line(borrower.clone())) {
+            panic!("Credit line not found");
+        }
+        let draw = scheduled_draws::schedule(&env, &borrower, amount, execute_after_ts);
+        publish_scheduled_draw(&env, symbol_short!("sd_new"), borrower, &draw);
+        draw.draw_id
+    }
+
+    /// Execute a due scheduled draw (anyone). The funds go to the borrower and the draw
+    /// is checked like `draw_credit` as of now. Emits a ScheduledDrawEvent.
+    ///
+    /// # Panics
+    /// * `"scheduled draw not found"`
+    /// * `"scheduled draw not due"` – before `execute_after_ts`
+    /// * Any `draw_credit` panic
+    pub fn execute_scheduled_draw(env: Env, borrower: Address, draw_id: u64) {
+        let draw = scheduled_draws::take_due(&env, &borrower, draw_id);
+        draw_to(
+            &env,
+            borrower.clone(),
+            borrower.clone(),
+            draw.amount,
+            None,
+            false,
+        );
+        publish_scheduled_draw(&env, symbol_short!("sd_exec"), borrower, &draw);
+    }
+
+    /// Cancel a scheduled draw (borrower). Emits a ScheduledDrawEvent.
+    ///
+    /// # Panics
+    /// * `"scheduled draw not found"`
+    pub fn cancel_scheduled_draw(env: Env, borrower: Address, draw_id: u64) {
+        borrower.require_auth();
+        let draw = scheduled_draws::cancel(&env, &borrower, draw_id);
+        publish_scheduled_draw(&env, symbol_short!("sd_cancel"), borrower, &draw);
+    }
+
+    /// Get one of the borrower's scheduled draws, if still pending (view function).
+    pub fn get_scheduled_draw(env: Env, borrower: Address, draw_id: u64) -> Option<ScheduledDraw> {
+        scheduled_draws::get(&env, &borrower, draw_id)
+    }
+
+    /// Draw from credit line and send the funds to `recipient` (e.g. a merchant) instead of
+    /// the borrower. Same checks, fees, and panics as `draw_credit`; the borrower authorizes.
+    /// Reverts with `ContractError::Blocked` if `recipient` is on the blocklist.
+    pub fn draw_credit_to(env: Env, borrower: Address, recipient: Address, amount: i128) {
+        borrower.require_auth();
+        draw_to(&env, borrower, recipient, amount, None, false);
+    }
+
+    /// Approve `operator` to draw on the borrower's line up to `max_amount` until
+    /// `expiry_ledger` (borrower only). Replaces any existing approval.
+    ///
+    /// # Panics
+    /// * `"max_amount must be positive"`
+    /// * `"expiry_ledger is in the past"`
+    pub fn approve_operator(
+        env: Env,
+        borrower: Address,
+        operator: Address,
+        max_amount: i128,
+        expiry_ledger: u32,
+    ) {
+        borrower.require_auth();
+        operators::approve(
+            &env,
+            &borrower,
+            &operator,
+            &OperatorApproval {
+                max_amount,
+                expiry_ledger,
+            },
+        );
+        publish_operator_approved(
+            &env,
+            (symbol_short!("credit"), symbol_short!("op_appr")),
+            OperatorApprovedEvent {
+                borrower,
+                operator,
+                max_amount,
+                expiry_ledger,
+                event_version: EVENT_VERSION,
+                sequence: events::next_sequence(&env),
+            },
+        );
+    }
+
+    /// Revoke an operator's draw approval (borrower only).
+    pub fn revoke_operator(env: Env, borrower: Address, operator: Address) {
+        borrower.require_auth();
+        operators::revoke(&env, &borrower, &operator);
+        publish_operator_approved(
+            &env,
+            (symbol_short!("credit"), symbol_short!("op_revoke")),
+            OperatorApprovedEvent {
+                borrower,
+                operator,
+                max_amount: 0,
+                expiry_ledger: 0,
+                event_version: EVENT_VERSION,
+                sequence: events::next_sequence(&env),
+            },
+        );
+    }
+
+    /// Get the remaining approval for an operator, if any (view function).
+    pub fn get_operator_approval(
+        env: Env,
+        borrower: Address,
+        operator: Address,
+    ) -> Option<OperatorApproval> {
+        operators::get(&env, &borrower, &operator)
+    }
+
+    /// Draw on the borrower's line as an approved operator; funds go to the operator.
+    /// Consumes the operator's allowance and otherwise behaves like `draw_credit`.
+    ///
+    /// # Panics
+    /// * `"operator not approved"`
+    /// * `"operator approval expired"` – current ledger is past `expiry_ledger`
+    /// * `"exceeds operator allowance"`
+    /// * `ContractError::Blocked` – `operator` is on the blocklist
+    /// * Any `draw_credit` panic
+    pub fn draw_credit_as_operator(env: Env, operator: Address, borrower: Address, amount: i128) {
+        operator.require_auth();
+        if amount > 0 {
+            operators::consume(&env, &borrower, &operator, amount);
+        }
+        draw_to(&env, borrower, operator, amount, None, false);
+    }
+
+    /// Delegate `amount` of the borrower's unused limit to `delegatee` until `expires_at`
+    /// (delegator only). The delegatee can draw it with `draw_delegated_credit`; the
+    /// undrawn part is held against the delegator's limit. Replaces any existing delegation
+    /// to the same delegatee. Emits a DelegationEvent.
+    ///
+    /// # Panics
+    /// * `"Credit line not found"`
+    /// * `"credit line is closed"`
+    /// * `"amount must be positive"`
+    /// * `"cannot delegate to self"`
+    /// * `"expiry must be in the future"`
+    /// * `"too many delegations"` – the delegator already has ten delegatees
+    /// * `"delegation exceeds available credit"` – more than the unused limit
+    pub fn delegate_credit(
+        env: Env,
+        delegator: Address,
+        delegatee: Address,
+        amount: i128,
+        expires_at: u64,
+    ) {
+        delegator.require_auth();
+        let credit_line: CreditLineData =
+            load_credit_line(&env, &delegator).expect("Credit line not found");
+        if credit_line.status == CreditStatus::Closed {
+            panic!("credit line is closed");
+        }
+        let delegation = delegation::grant(&env, &delegator, &delegatee, amount, expires_at);
+        // Checked after `grant` so the new slice replaces any previous one.
+        let committed = credit_line
+            .utilized_amount
+            .saturating_add(streams::locked(&env, &delegator))
+            .saturating_add(delegation::reserved(&env, &delegator));
+        if committed > boosts::effective_limit(&env, &credit_line) {
+            panic!("delegation exceeds available credit");
+        }
+        publish_delegation(
+            &env,
+            symbol_short!("dlg_new"),
+            delegator,
+            delegatee,
+            amount,
+            &delegation,
+        );
+    }
+
+    /// Revoke a delegation, releasing its undrawn part (delegator only). Amounts already
+    /// drawn stay on the delegator's line. Emits a DelegationEvent.
+    ///
+    /// # Panics
+    /// * `"delegation not found"`
+    pub fn revoke_delegation(env: Env, delegator: Address, delegatee: Address) {
+        delegator.require_auth();
+        let delegation = delegation::revoke(&env, &delegator, &delegatee);
+        publish_delegation(
+            &env,
+            symbol_short!("dlg_rev"),
+            delegator,
+            delegatee,
+            delegation.remaining,
+            &delegation,
+        );
+    }
+
+    /// Get the delegation from `delegator` to `delegatee`, if any (view function).
+    pub fn get_delegation(env: Env, delegator: Address, delegatee: Address) -> Option<Delegation> {
+        delegation::get(&env, &delegator, &delegatee)
+    }
+
+    /// Get the addresses the borrower has delegated credit to (view function).
+    pub fn get_delegatees(env: Env, delegator: Address) -> Vec<Address> {
+        delegation::delegatees(&env, &delegator)
+    }
+
+    /// Draw on a delegation as its delegatee; funds go to the delegatee and the debt to the
+    /// delegator's line. Otherwise behaves like `draw_credit`. Emits a DelegationEvent.
+    ///
+    /// # Panics
+    /// * `"amount must be positive"`
+    /// * `"delegation not found"`
+    /// * `"delegation expired"` – at or after `expires_at`
+    /// * `"exceeds delegated credit"`
+    /// * `ContractError::Blocked` – `delegatee` is on the blocklist
+    /// * Any `draw_credit` panic
+    pub fn draw_delegated_credit(env: Env, delegatee: Address, delegator: Address, amount: i128) {
+        delegatee.require_auth();
+        let delegation = delegation::consume(&env, &delegator, &delegatee, amount);
+        draw_to(
+            &env,
+            delegator.clone(),
+            delegatee.clone(),
+            amount,
+            None,
+            false,
+        );
+        publish_delegation(
+            &env,
+            symbol_short!("dlg_draw"),
+            delegator,
+            delegatee,
+            amount,
+            &delegation,
+        );
+    }
+
+    /// Lend `amount` of the default token to `receiver` for the duration of its
+    /// `on_flash_draw(token, amount, fee, data)` callback (receiver authorizes). The
+    /// callback must transfer `amount + fee` back before it returns. The fee is booked
+    /// as a protocol fee. Returns the fee. Emits a FlashDrawEvent.
+    ///
+    /// # Panics
+    /// * `"amount must be positive"`
+    /// * `"flash draws not enabled"` – `max_amount` is 0
+    /// * `"flash draw exceeds maximum"`
+    /// * `"insufficient liquidity"` – the contract holds less than `amount`
+    /// * `"flash draw not repaid"` – the balance is short of `amount + fee` afterwards
+    /// * `ContractError::Paused` – draws are paused
+    /// * `ContractError::Blocked` – `receiver` is on the blocklist
+    pub fn flash_draw(env: Env, receiver: Address, amount: i128, data: Bytes) -> i128 {
+        receiver.require_auth();
+        pause::require_draws_not_paused(&env);
+        blocklist::require_not_blocked(&env, &receiver, &receiver);
+        let token = require_token(&env);
+        let fee = with_reentrancy_guard(&env, || {
+            flash::execute(&env, &token, &receiver, amount, &data)
+        });
+        treasury::accrue_fees(&env, &token, fee);
+        publish_flash_draw(
+            &env,
+            FlashDrawEvent {
+                receiver,
+                token,
+                amount,
+                fee,
+                event_version: EVENT_VERSION,
+                sequence: events::next_sequence(&env),
+            },
+        );
+        fee
+    }
+
+    /// Refinance `external_debt_amount` owed to another lender onto the borrower's line,
+    /// paying the draw straight to the old lender's `payoff_recipient` (admin or
+    /// `RiskEngine` role, and the borrower). A borrower without an open line gets one on
+    /// `terms`; an open line is extended to `terms.credit_limit` if that is higher, keeping
+    /// its other terms. The draw is checked and charged like `draw_credit` but skips the
+    /// large-draw breaker. Returns the amount paid out. Emits a RefinanceEvent.
+    ///
+    /// # Panics
+    /// * Any `open_credit_line` panic when opening a line
+    /// * Any `update_risk_parameters` panic when extending one
+    /// * Any `draw_credit` panic
+    pub fn refinance_in(
+        env: Env,
+        caller: Address,
+        borrower: Address,
+        external_debt_amount: i128,
+        payoff_recipient: Address,
+        terms: LineTerms,
+    ) -> i128 {
+        access::require_role(&env, Role::RiskEngine, &caller);
+        borrower.require_auth();
+        audit::record(
+            &env,
+            &caller,
+            symbol_short!("refinance"),
+            Some(borrower.clone()),
+        );
+        let opened = match load_credit_line(&env, &borrower) {
+            Some(line) if line.status != CreditStatus::Closed => {
+                if terms.credit_limit > line.credit_limit {
+                    update_risk(
+                        &env,
+                        borrower.clone(),
+                        terms.credit_limit,
+                        line.interest_rate_bps,
+                        line.risk_score,
+                    );
+                }
+                false
+            }
+            _ => {
+                let schedule = terms.schedule();
+                open_line(
+                    &env,
+                    borrower.clone(),
+                    terms.credit_limit,
+                    terms.interest_rate_bps,
+                    terms.risk_score,
+                    schedule,
+                    terms.rate_mode,
+                    terms.token,
+                );
+                true
+            }
+        };
+        let paid_out = draw_to(
+            &env,
+            borrower.clone(),
+            payoff_recipient.clone(),
+            external_debt_amount,
+            None,
+            true,
+        );
+        let credit_line: CreditLineData =
+            load_credit_line(&env, &borrower).expect("Credit line not found");
+        publish_refinance(
+            &env,
+            RefinanceEvent {
+                borrower,
+                payoff_recipient,
+                amount: external_debt_amount,
+                paid_out,
+                credit_limit: credit_line.credit_limit,
+                opened,
+                event_version: EVENT_VERSION,
+                sequence: events::next_sequence(&env),
+            },
+        );
+        paid_out
+    }
+
+    /// Repay credit (borrower).
+    /// Reverts if credit line does not exist, is Closed, or borrower has not authorized.
+    /// Settles accrued interest first, then principal; payment is capped at the outstanding
+    /// balance and only the applied amount is transferred directly from the borrower, so no
+    /// prior `approve` is needed. Repaid interest, less the protocol's reserve factor,
+    /// accrues to the liquidity pool. Emits RepaymentEvent.
+    /// Reverts with `ContractError::Paused` while repayments are paused.
+    pub fn repay_credit(env: Env, borrower: Address, amount: i128) {
+        borrower.require_auth();
+        repay(&env, borrower, amount, Funding::Transfer);
+    }
+
+    /// Repay credit by pulling the applied amount through a token allowance the borrower
+    /// granted to this contract, for smart wallets that approve ahead of time.
+    /// Callable by anyone; the allowance is the borrower's authorization. Otherwise
+    /// behaves like `repay_credit`.
+    ///
+    /// # Panics
+    /// * Any `repay_credit` panic
+    /// * If the allowance does not cover the applied amount
+    pub fn repay_credit_with_allowance(env: Env, borrower: Address, amount: i128) {
+        repay(&env, borrower, amount, Funding::Allowance);
+    }
+
+    /// Repay credit (borrower), pulling the full `amount` and refunding whatever exceeds
+    /// the outstanding balance in the same invocation, so wallets can send a round amount
+    /// when interest makes the exact payoff hard to predict. The refund is reported in the
+    /// RepaymentEvent. Otherwise behaves like `repay_credit`.
+    ///
+    /// # Panics
+    /// * `"transfers frozen"` – a refund is due and the line's transfers are frozen
+    /// * Any `repay_credit` panic
+    pub fn repay_credit_with_refund(env: Env, borrower: Address, amount: i128) {
+        borrower.require_auth();
+        repay(&env, borrower, amount, Funding::TransferWithRefund);
+    }
+
+    /// Opt in to autopay (borrower): keepers may pull up to `max_per_cycle` per due date
+    /// towards the line's minimum payment through the borrower's token allowance to this
+    /// contract. Replaces any previous cap. Emits an AutopayConsentEvent.
+    ///
+    /// # Panics
+    /// * `"Credit line not found"`
+    /// * `"credit line has no schedule"`
+    /// * `"max_per_cycle must be positive"`
+    pub fn enable_autopay(env: Env, borrower: Address, max_per_cycle: i128) {
+        borrower.require_auth();
+        let credit_line: CreditLineData =
+            load_credit_line(&env, &borrower).expect("Credit line not found");
+        if !schedule::is_scheduled(&credit_line) {
+            panic!("credit line has no schedule");
+        }
+        autopay::enable(&env, &borrower, max_per_cycle);
+        publish_autopay_consent(
+            &env,
+            symbol_short!("ap_on"),
+            AutopayConsentEvent {
+                borrower,
+                max_per_cycle,
+                event_version: EVENT_VERSION,
+                sequence: events::next_sequence(&env),
+            },
+        );
+    }
+
+    /// Opt out of autopay (borrower). Emits an AutopayConsentEvent.
+    ///
+    /// # Panics
+    /// * `"autopay not enabled"`
+    pub fn disable_autopay(env: Env, borrower: Address) {
+        borrower.require_auth();
+        autopay::disable(&env, &borrower);
+        publish_autopay_consent(
+            &env,
+            symbol_short!("ap_off"),
+            AutopayConsentEvent {
+                borrower,
+                max_per_cycle: 0,
+                event_version: EVENT_VERSION,
+                sequence: events::next_sequence(&env),
+            },
+        );
+    }
+
+    /// Get the borrower's autopay consent, if any (view function).
+    pub fn get_autopay(env: Env, borrower: Address) -> Option<Autopay> {
+        autopay::get(&env, &borrower)
+    }
+
+    /// Pay a scheduled minimum under the borrower's autopay consent (callable by anyone).
+    ///
+    /// From `AUTOPAY_WINDOW_SECS` before the due date, pulls as much of the minimum due
+    /// as the per-cycle cap, the borrower's allowance to this contract and their balance
+    /// allow, and applies it like `repay_credit_with_allowance`. Paying the whole minimum
+    /// marks the cycle paid. Returns the amount paid. Emits an AutopayEvent.
+    ///
+    /// # Panics
+    /// * `"Credit line not found"`
+    /// * `"autopay not enabled"`
+    /// * `"payment not due"` – no minimum billed, or the due date is not yet in the window
+    /// * `"nothing to autopay"` – the cap, allowance or balance is used up
+    /// * Any `repay_credit` panic
+    pub fn run_autopay(env: Env, borrower: Address) -> i128 {
+        let mut credit_line: CreditLineData =
+            load_credit_line(&env, &borrower).expect("Credit line not found");
+        schedule::roll_forward(&env, &mut credit_line);
+        let due_ts = credit_line.next_due_ts;
+        let amount = autopay::take(&env, &credit_line);
+        repay(&env, borrower.clone(), amount, Funding::Allowance);
+        let credit_line: CreditLineData =
+            load_credit_line(&env, &borrower).expect("Credit line not found");
+        let min_due_remaining = if credit_line.next_due_ts == due_ts {
+            credit_line.min_due_amount
+        } else {
+            0
+        };
+        publish_autopay(
+            &env,
+            AutopayEvent {
+                borrower,
+                amount,
+                due_ts,
+                min_due_remaining,
+                event_version: EVENT_VERSION,
+                sequence: events::next_sequence(&env),
+            },
+        );
+        amount
+    }
+
+    /// Repay in `token_in` instead of the line's token (borrower). `amount_in` is swapped
+    /// through the swap adapter into the line's token, and everything received is applied
+    /// as by `repay_credit_with_refund`: any excess over the outstanding balance is
+    /// refunded in the line's token. A prepayment penalty is still pulled in the line's
+    /// token. Returns the amount received from the swap.
+    ///
+    /// # Panics
+    /// * `"Credit line not found"`
+    /// * `"amount must be positive"`
+    /// * `"min_repay_out cannot be negative"`
+    /// * `"token_in is the line token"`
+    /// * `"swap adapter not set"`
+    /// * `"slippage exceeded"` – the swap returned less than `min_repay_out`
+    /// * Any `repay_credit_with_refund` panic
+    pub fn repay_with_token(
+        env: Env,
+        borrower: Address,
+        token_in: Address,
+        amount_in: i128,
+        min_repay_out: i128,
+    ) -> i128 {
+        borrower.require_auth();
+        pause::require_repays_not_paused(&env);
+        let credit_line: CreditLineData =
+            load_credit_line(&env, &borrower).expect("Credit line not found");
+        let received = with_reentrancy_guard(&env, || {
+            swap::swap_in(
+                &env,
+                &borrower,
+                &token_in,
+                &credit_line.token,
+                amount_in,
+                min_repay_out,
+            )
+        });
+        repay(&env, borrower, received, Funding::Swapped);
+        received
+    }
+
+    /// Repay the full outstanding balance, principal and accrued interest, and close the line
+    /// in one invocation (borrower). Collateral is returned. Emits RepaymentEvent and the
+    /// `closed` CreditLineEvent.
+    ///
+    /// # Panics
+    /// * Any `repay_credit` panic other than the amount check
+    pub fn repay_and_close(env: Env, borrower: Address) {
+        borrower.require_auth();
+        repay(&env, borrower.clone(), i128::MAX, Funding::Transfer);
+        close_line(&env, borrower.clone(), borrower);
+    }
+
+    /// Update risk parameters for an existing credit line.
+    ///
+    /// # Arguments
+    /// * `caller` - Must be the admin or hold the `RiskEngine` role.
+    /// * `op_id` - Optional idempotency key; reusing one fails with `DuplicateOperation`.
+    pub fn update_risk_parameters(
+        env: Env,
+        caller: Address,
+        borrower: Address,
+        credit_limit: i128,
+        interest_rate_bps: u32,
+        risk_score: u32,
+        op_id: Option<BytesN<32>>,
+    ) {
+        access::require_role(&env, Role::RiskEngine, &caller);
+        audit::record(
+            &env,
+            &caller,
+            symbol_short!("risk_upd"),
+            Some(borrower.clone()),
+        );
+        idempotency::consume(&env, &op_id);
+        update_risk(&env, borrower, credit_limit, interest_rate_bps, risk_score);
+    }
+
+    /// Tie the borrower's line to an off-chain document by its hash, e.g. of the signed
+    /// credit agreement, or clear it with `None` (admin or `RiskEngine` role). Emits a
+    /// MetadataHashEvent carrying the previous hash.
+    ///
+    /// # Panics
+    /// * `"Credit line not found"`
+    pub fn set_line_metadata_hash(
+        env: Env,
+        caller: Address,
+        borrower: Address,
+        metadata_hash: Option<BytesN<32>>,
+    ) {
+        access::require_role(&env, Role::RiskEngine, &caller);
+        if !storage::has_persistent(&env, &DataKey::Line(borrower.clone())) {
+            panic!("Credit line not found");
+        }
+        audit::record(
+            &env,
+            &caller,
+            symbol_short!("metadata"),
+            Some(borrower.clone()),
+        );
+        let previous = metadata::set(&env, &borrower, &metadata_hash);
+        publish_metadata_hash(
+            &env,
+            MetadataHashEvent {
+                borrower,
+                previous,
+                metadata_hash,
+                event_version: EVENT_VERSION,
+                sequence: events::next_sequence(&env),
+            },
+        );
+    }
+
+    /// Get the metadata hash of the borrower's line, if any (view function).
+    pub fn get_line_metadata_hash(env: Env, borrower: Address) -> Option<BytesN<32>> {
+        metadata::get(&env, &borrower)
+    }
+
+    /// Grant `borrower` `extra_amount` of limit on top of `credit_limit` until
+    /// `expires_at`, replacing any current boost. Draws stop counting the boost once it
+    /// expires. Emits a LimitBoostEvent.
+    ///
+    /// # Arguments
+    /// * `caller` - Must be the admin or hold the `RiskEngine` role.
+    ///
+    /// # Panics
+    /// * `"Credit line not found"`
+    /// * `"credit line is closed"`
+    /// * `"boost amount must be positive"`
+    /// * `"boost expiry must be in the future"`
+    /// * `"updates frozen"`
+    pub fn grant_limit_boost(
+        env: Env,
+        caller: Address,
+        borrower: Address,
+        extra_amount: i128,
+        expires_at: u64,
+    ) {
+        access::require_role(&env, Role::RiskEngine, &caller);
+        audit::record(
+            &env,
+            &caller,
+            symbol_short!("boost"),
+            Some(borrower.clone()),
+        );
+        let mut credit_line: CreditLineData =
+            load_credit_line(&env, &borrower).expect("Credit line not found");
+        if credit_line.status == CreditStatus::Closed {
+            panic!("credit line is closed");
+        }
+        restrictions::require_updates_allowed(&credit_line);
+        boosts::grant(&env, &mut credit_line, extra_amount, expires_at);
+        save_credit_line(&env, &credit_line);
+
+        publish_limit_boost(
+            &env,
+            LimitBoostEvent {
+                borrower,
+                amount: extra_amount,
+                expires_at,
+                expired: false,
+                event_version: EVENT_VERSION,
+                sequence: events::next_sequence(&env),
+            },
+        );
+    }
+
+    /// Bound the rates `borrower`'s line can be priced at (admin only); 0 disables a
+    /// bound. Interest up to now accrues at the current rate, which is then moved
+    /// into the new bounds.
+    ///
+    /// # Panics
+    /// * `"Credit line not found"`
+    /// * `"credit line is closed"`
+    /// * `"interest_rate_bps exceeds maximum"`
+    /// * `"rate floor exceeds rate cap"`
+    /// * `"updates frozen"`
+    pub fn set_rate_bounds(env: Env, borrower: Address, rate_floor_bps: u32, rate_cap_bps: u32) {
+        let admin = require_admin_auth(&env);
+        let mut credit_line: CreditLineData =
+            load_credit_line(&env, &borrower).expect("Credit line not found");
+        if credit_line.status == CreditStatus::Closed {
+            panic!("credit line is closed");
+        }
+        restrictions::require_updates_allowed(&credit_line);
+        interest::accrue(&env, &mut credit_line);
+        rates::set_bounds(&env, &mut credit_line, rate_floor_bps, rate_cap_bps);
+        save_credit_line(&env, &credit_line);
+        audit::config_changed(
+            &env,
+            &admin,
+            symbol_short!("rate_bnd"),
+            Some(borrower.clone()),
+            (borrower, rate_floor_bps, rate_cap_bps),
+        );
+    }
+
+    /// Cap what `borrower` may draw per `cap.epoch_secs` seconds at `cap.amount`, on top of
+    /// the credit limit; a zero `amount` removes the cap. Draws over it revert with
+    /// `ContractError::RateLimited`.
+    ///
+    /// # Arguments
+    /// * `caller` - Must be the admin or hold the `RiskEngine` role.
+    ///
+    /// # Panics
+    /// * `"Credit line not found"`
+    /// * `"credit line is closed"`
+    /// * `"updates frozen"`
+    /// * `"spend cap cannot be negative"`
+    /// * `"epoch_secs must be positive"`
+    pub fn set_epoch_spend_cap(env: Env, caller: Address, borrower: Address, cap: SpendCap) {
+        access::require_role(&env, Role::RiskEngine, &caller);
+        let credit_line: CreditLineData =
+            load_credit_line(&env, &borrower).expect("Credit line not found");
+        if credit_line.status == CreditStatus::Closed {
+            panic!("credit line is closed");
+        }
+        restrictions::require_updates_allowed(&credit_line);
+        velocity::set_spend_cap(&env, &borrower, &cap);
+        audit::config_changed(
+            &env,
+            &caller,
+            symbol_short!("spend_cap"),
+            Some(borrower.clone()),
+            (borrower, cap),
+        );
+    }
+
+    /// Accrue `borrower`'s interest at `promo_bps` instead of the standard rate until
+    /// `promo_until_ts`, replacing any current promotion. Interest up to now accrues at
+    /// the previous rate, and the line falls back to its standard rate once the
+    /// promotion ends. Emits a PromoRateEvent.
+    ///
+    /// # Arguments
+    /// * `caller` - Must be the admin or hold the `RiskEngine` role.
+    ///
+    /// # Panics
+    /// * `"Credit line not found"`
+    /// * `"credit line is closed"`
+    /// * `"interest_rate_bps exceeds maximum"`
+    /// * `"promo expiry must be in the future"`
+    /// * `"updates frozen"`
+    pub fn set_promo_rate(
+        env: Env,
+        caller: Address,
+        borrower: Address,
+        promo_bps: u32,
+        promo_until_ts: u64,
+    ) {
+        access::require_role(&env, Role::RiskEngine, &caller);
+        audit::record(
+            &env,
+            &caller,
+            symbol_short!("promo"),
+            Some(borrower.clone()),
+        );
+        let mut credit_line: CreditLineData =
+            load_credit_line(&env, &borrower).expect("Credit line not found");
+        if credit_line.status == CreditStatus::Closed {
+            panic!("credit line is closed");
+        }
+        restrictions::require_updates_allowed(&credit_line);
+        interest::accrue(&env, &mut credit_line);
+        promo::grant(&env, &mut credit_line, promo_bps, promo_until_ts);
+        save_credit_line(&env, &credit_line);
+
+        publish_promo_rate(
+            &env,
+            PromoRateEvent {
+                borrower,
+                promo_rate_bps: promo_bps,
+                promo_until_ts,
+                standard_rate_bps: credit_line.interest_rate_bps,
+                event_version: EVENT_VERSION,
+                sequence: events::next_sequence(&env),
+            },
+        );
+    }
+
+    /// Set how `borrower`'s scheduled line bills its minimum payment: `Revolving`,
+    /// `InterestOnly`, or `Amortizing(n_installments)`. The bill already issued stands;
+    /// the new mode applies from the next cycle, and amortizing installments count from
+    /// there. Emits a RepaymentModeEvent.
+    ///
+    /// # Arguments
+    /// * `caller` - Must be the admin or hold the `RiskEngine` role.
+    ///
+    /// # Panics
+    /// * `"Credit line not found"`
+    /// * `"credit line is closed"`
+    /// * `"credit line has no schedule"`
+    /// * `"n_installments out of range"` – zero or above `MAX_INSTALLMENTS`
+    /// * `"updates frozen"`
+    pub fn set_repayment_mode(env: Env, caller: Address, borrower: Address, mode: RepaymentMode) {
+        access::require_role(&env, Role::RiskEngine, &caller);
+        audit::record(
+            &env,
+            &caller,
+            symbol_short!("pay_mode"),
+            Some(borrower.clone()),
+        );
+        let mut credit_line: CreditLineData =
+            load_credit_line(&env, &borrower).expect("Credit line not found");
+        if credit_line.status == CreditStatus::Closed {
+            panic!("credit line is closed");
+        }
+        restrictions::require_updates_allowed(&credit_line);
+        // Bill any cycle that has already ended under the previous mode.
+        interest::accrue(&env, &mut credit_line);
+        schedule::roll_forward(&env, &mut credit_line);
+        save_credit_line(&env, &credit_line);
+        let plan = amortization::set(&env, &credit_line, mode);
+
+        publish_repayment_mode(
+            &env,
+            RepaymentModeEvent {
+                borrower,
+                mode,
+                start_due_ts: plan.start_due_ts,
+                event_version: EVENT_VERSION,
+                sequence: events::next_sequence(&env),
+            },
+        );
+    }
+
+    /// Get the repayment mode of `borrower`'s line; `Revolving` unless set (view function).
+    pub fn get_repayment_mode(env: Env, borrower: Address) -> RepaymentPlan {
+        amortization::get(&env, &borrower)
+    }
+
+    /// Project the installments of an amortizing line from its current bill on, assuming
+    /// no further draws and each bill paid on time, at the rates the line accrues at now
+    /// (view function). Empty for revolving and interest-only lines.
+    ///
+    /// # Panics
+    /// * `"Credit line not found"`
+    pub fn get_amortization_schedule(env: Env, borrower: Address) -> Vec<Installment> {
+        let credit_line: CreditLineData =
+            load_credit_line(&env, &borrower).expect("Credit line not found");
+        amortization::projected_schedule(&env, &credit_line)
+    }
+
+    /// Suspend a credit line (admin only) for `reason`, one of the `suspension` reason
+    /// codes. With `until_ts`, the suspension lifts on the first draw at or after that
+    /// time. Emits a CreditLineSuspended event carrying the reason and expiry.
+    ///
+    /// # Panics
+    /// * `"Credit line not found"`
+    /// * `"invalid suspension reason"`
+    /// * `"suspension expiry must be in the future"`
+    pub fn suspend_credit_line(env: Env, borrower: Address, reason: u32, until_ts: Option<u64>) {
+        let admin = require_admin_auth(&env);
+        suspend_line(&env, &admin, borrower, reason, until_ts);
+    }
+
+    /// Suspend a credit line in an emergency (admin or `Guardian` role), as
+    /// `suspend_credit_line` does. Only the admin can resume it.
+    ///
+    /// # Panics
+    /// * `"unauthorized"`
+    /// * `"Credit line not found"`
+    /// * `"invalid suspension reason"`
+    /// * `"suspension expiry must be in the future"`
+    pub fn emergency_suspend(
+        env: Env,
+        caller: Address,
+        borrower: Address,
+        reason: u32,
+        until_ts: Option<u64>,
+    ) {
+        access::require_role(&env, Role::Guardian, &caller);
+        suspend_line(&env, &caller, borrower, reason, until_ts);
+    }
+
+    /// Replace the operations frozen on a line with `restrictions`, a combination of the
+    /// `restrictions` flags; 0 lifts them all (admin only). Unlike suspension, the line
+    /// keeps its status. Emits a RestrictionsEvent.
+    ///
+    /// # Panics
+    /// * `"Credit line not found"`
+    /// * `"invalid restrictions"` – unknown flag bits are set
+    pub fn set_line_restrictions(env: Env, borrower: Address, restrictions: u32) {
+        let admin = require_admin_auth(&env);
+        audit::record(
+            &env,
+            &admin,
+            symbol_short!("restrict"),
+            Some(borrower.clone()),
+        );
+        restrictions::validate(restrictions);
+        let mut credit_line: CreditLineData =
+            load_credit_line(&env, &borrower).expect("Credit line not found");
+        // The dispute flag is owned by `flag_dispute` / `resolve_dispute`.
+        credit_line.restrictions =
+            restrictions | (credit_line.restrictions & restrictions::DISPUTED);
+        save_credit_line(&env, &credit_line);
+
+        publish_restrictions_set(
+            &env,
+            RestrictionsEvent {
+                borrower,
+                restrictions: credit_line.restrictions,
+                event_version: EVENT_VERSION,
+                sequence: events::next_sequence(&env),
+            },
+        );
+    }
+
+    /// Flag a credit line as disputed under the off-chain `dispute_id` (admin only).
+    /// While disputed the line cannot be defaulted and its limit cannot be lowered;
+    /// repayments continue. The flag shows as the `DISPUTED` restriction bit.
+    /// Emits a DisputeEvent.
+    ///
+    /// # Panics
+    /// * `"Credit line not found"`
+    /// * `"credit line is closed"`
+    /// * `"credit line already disputed"`
+    pub fn flag_dispute(env: Env, borrower: Address, dispute_id: u64) {
+        let admin = require_admin_auth(&env);
+        audit::record(
+            &env,
+            &admin,
+            symbol_short!("dispute"),
+            Some(borrower.clone()),
+        );
+        let mut credit_line: CreditLineData =
+            load_credit_line(&env, &borrower).expect("Credit line not found");
+        if credit_line.status == CreditStatus::Closed {
+            panic!("credit line is closed");
+        }
+        disputes::flag(&env, &mut credit_line, dispute_id);
+        save_credit_line(&env, &credit_line);
+
+        publish_dispute_event(
+            &env,
+            symbol_short!("dispute"),
+            DisputeEvent {
+                borrower,
+                dispute_id,
+                event_version: EVENT_VERSION,
+                sequence: events::next_sequence(&env),
+            },
+        );
+    }
+
+    /// Resolve the dispute on a credit line, lifting its protections (admin only).
+    /// Emits a DisputeEvent.
+    ///
+    /// # Panics
+    /// * `"Credit line not found"`
+    /// * `"credit line not disputed"`
+    pub fn resolve_dispute(env: Env, borrower: Address) {
+        let admin = require_admin_auth(&env);
+        audit::record(
+            &env,
+            &admin,
+            symbol_short!("dispute_r"),
+            Some(borrower.clone()),
+        );
+        let mut credit_line: CreditLineData =
+            load_credit_line(&env, &borrower).expect("Credit line not found");
+        let dispute = disputes::resolve(&env, &mut credit_line);
+        save_credit_line(&env, &credit_line);
+
+        publish_dispute_event(
+            &env,
+            symbol_short!("dispute_r"),
+            DisputeEvent {
+                borrower,
+                dispute_id: dispute.dispute_id,
+                event_version: EVENT_VERSION,
+                sequence: events::next_sequence(&env),
+            },
+        );
+    }
+
+    /// Get the open dispute on a borrower's line, if any (view function).
+    pub fn get_dispute(env: Env, borrower: Address) -> Option<Dispute> {
+        disputes::get(&env, &borrower)
+    }
+
+    /// Resume a suspended credit line (admin only). Emits a CreditLineResumed event.
+    ///
+    /// # Panics
+    /// * `"Credit line not found"` – borrower has no credit line
+    /// * `"credit line is not suspended"` – line is Active, Defaulted, or Closed
+    pub fn resume_credit_line(env: Env, borrower: Address) {
+        let admin = require_admin_auth(&env);
+        audit::record(
+            &env,
+            &admin,
+            symbol_short!("resume"),
+            Some(borrower.clone()),
+        );
+
+        let mut credit_line: CreditLineData =
+            load_credit_line(&env, &borrower).expect("Credit line not found");
+
+        if credit_line.status != CreditStatus::Suspended {
+            panic!("credit line is not suspended");
+        }
+
+        credit_line.status = CreditStatus::Active;
+        suspension::clear(&mut credit_line);
+        save_credit_line(&env, &credit_line);
+
+        publish_credit_line_event(
+            &env,
+            (symbol_short!("credit"), symbol_short!("resume")),
+            CreditLineEvent {
+                event_type: symbol_short!("resume"),
+                borrower: borrower.clone(),
+                status: CreditStatus::Active,
+                credit_limit: credit_line.credit_limit,
+                interest_rate_bps: credit_line.interest_rate_bps,
+                rate_mode: credit_line.rate_mode,
+                risk_score: credit_line.risk_score,
+                suspension_reason: credit_line.suspension_reason,
+                suspended_until: credit_line.suspended_until,
+                auto: false,
+                event_version: EVENT_VERSION,
+                sequence: events::next_sequence(&env),
+            },
+        );
+    }
+
+    /// Close a credit line. Callable by admin (force-close) or by borrower when utilization is
+    /// at or below the dust threshold; a borrower close writes off any remaining residue.
+    /// Collateral is returned to the borrower when utilization is zero; on a force-close with
+    /// outstanding debt, or while the line's transfers are frozen, it stays held against the line.
+    ///
+    /// # Arguments
+    /// * `closer` - Must be either the contract admin or the borrower (only when utilized_amount
+    ///   is within the dust threshold).
+    pub fn close_credit_line(env: Env, borrower: Address, closer: Address) {
+        closer.require_auth();
+        close_line(&env, borrower, closer);
+    }
+
+    /// Set the utilized amount up to which a borrower may close their own line, with the
+    /// residue written off (admin only). 0 requires full repayment.
+    ///
+    /// # Panics
+    /// * `"dust threshold must be non-negative"`
+    pub fn set_dust_threshold(env: Env, threshold: i128) {
+        let admin = require_admin_auth(&env);
+        dust::set_threshold(&env, threshold);
+        audit::config_changed(&env, &admin, symbol_short!("dust"), None, threshold);
+    }
+
+    /// Get the dust threshold for borrower closes (view function).
+    pub fn get_dust_threshold(env: Env) -> i128 {
+        dust::get_threshold(&env)
+    }
+
+    /// Set or clear (`0`) the maturity of a credit line (admin only). From `maturity_ts`
+    /// on, draws are rejected and the borrower can only repay.
+    ///
+    /// # Panics
+    /// * `"Credit line not found"`
+    /// * `"credit line is closed"`
+    /// * `"maturity must be in the future"`
+    pub fn set_maturity(env: Env, borrower: Address, maturity_ts: u64) {
+        let admin = require_admin_auth(&env);
+        audit::record(
+            &env,
+            &admin,
+            symbol_short!("maturity"),
+            Some(borrower.clone()),
+        );
+        let mut credit_line = load_term_line(&env, &borrower, maturity_ts);
+        credit_line.maturity_ts = maturity_ts;
+        save_credit_line(&env, &credit_line);
+
+        publish_maturity_event(
+            &env,
+            (symbol_short!("credit"), symbol_short!("maturity")),
+            MaturityEvent {
+                borrower,
+                maturity_ts,
+                credit_limit: credit_line.credit_limit,
+                interest_rate_bps: credit_line.interest_rate_bps,
+                event_version: EVENT_VERSION,
+                sequence: events::next_sequence(&env),
+            },
+        );
+    }
+
+    /// Renew a term credit line with a new maturity and terms (admin only). Interest is
+    /// accrued at the old rate first; draws are allowed again until the new maturity.
+    ///
+    /// # Panics
+    /// * `"Credit line not found"`
+    /// * `"credit line is closed"`
+    /// * `"maturity must be in the future"`
+    /// * `"credit_limit cannot be less than utilized amount"`
+    /// * `"credit line is disputed"` – `credit_limit` would lower a disputed line's limit
+    /// * `"interest_rate_bps exceeds maximum"`
+    /// * `"interest_rate_bps outside rate bounds"`
+    pub fn renew_credit_line(
+        env: Env,
+        borrower: Address,
+        maturity_ts: u64,
+        credit_limit: i128,
+        interest_rate_bps: u32,
+    ) {
+        let admin = require_admin_auth(&env);
+        audit::record(&env, &admin, symbol_short!("renew"), Some(borrower.clone()));
+        let mut credit_line = load_term_line(&env, &borrower, maturity_ts);
+        if credit_limit < credit_line.utilized_amount {
+            panic!("credit_limit cannot be less than utilized amount");
+        }
+        disputes::require_limit_allowed(&credit_line, credit_limit);
+        if interest_rate_bps > MAX_INTEREST_RATE_BPS {
+            panic!("interest_rate_bps exceeds maximum");
+        }
+
+        interest::accrue(&env, &mut credit_line);
+        credit_line.maturity_ts = maturity_ts;
+        credit_line.credit_limit = credit_limit;
+        rates::set_rate(&env, &mut credit_line, interest_rate_bps);
+        save_credit_line(&env, &credit_line);
+
+        publish_maturity_event(
+            &env,
+            (symbol_short!("credit"), symbol_short!("renewed")),
+            MaturityEvent {
+                borrower,
+                maturity_ts,
+                credit_limit,
+                interest_rate_bps,
+                event_version: EVENT_VERSION,
+                sequence: events::next_sequence(&env),
+            },
+        );
+    }
+
+    /// Restructure a Suspended or Defaulted line back to Active with renegotiated terms
+    /// (admin only). Interest is accrued at the old rate first; `utilized_amount`, accrued
+    /// interest, and collateral carry over. Missed cycles are cleared and `schedule`
+    /// replaces the existing one, starting a fresh cycle now (`None` removes it).
+    ///
+    /// # Panics
+    /// * `"Credit line not found"`
+    /// * `"credit line is not suspended or defaulted"`
+    /// * `"credit_limit cannot be less than utilized amount"`
+    /// * `"credit line is disputed"` – `credit_limit` would lower a disputed line's limit
+    /// * `"interest_rate_bps exceeds maximum"`
+    /// * `"interest_rate_bps outside rate bounds"`
+    /// * If `schedule` has a zero cycle length or `min_payment_bps` > 10000
+    pub fn restructure_credit_line(
+        env: Env,
+        borrower: Address,
+        credit_limit: i128,
+        interest_rate_bps: u32,
+        schedule: Option<RepaymentSchedule>,
+    ) {
+        let admin = require_admin_auth(&env);
+        audit::record(
+            &env,
+            &admin,
+            symbol_short!("restruct"),
+            Some(borrower.clone()),
+        );
+
+        let mut credit_line: CreditLineData =
+            load_credit_line(&env, &borrower).expect("Credit line not found");
+
+        if credit_line.status != CreditStatus::Suspended
+            && credit_line.status != CreditStatus::Delinquent
+            && credit_line.status != CreditStatus::Defaulted
+        {
+            panic!("credit line is not suspended or defaulted");
+        }
+        if credit_limit < credit_line.utilized_amount {
+            panic!("credit_limit cannot be less than utilized amount");
+        }
+        disputes::require_limit_allowed(&credit_line, credit_limit);
+        if interest_rate_bps > MAX_INTEREST_RATE_BPS {
+            panic!("interest_rate_bps exceeds maximum");
+        }
+
+        interest::accrue(&env, &mut credit_line);
+        credit_line.credit_limit = credit_limit;
+        rates::set_rate(&env, &mut credit_line, interest_rate_bps);
+        credit_line.missed_cycles = 0;
+        credit_line.delinquent_since_ts = 0;
+        match schedule {
+            Some(schedule) => {
+                schedule::validate(&env, &borrower, &schedule);
+                schedule::attach(&env, &mut credit_line, &schedule);
+            }
+            None => schedule::detach(&mut credit_line),
+        }
+        credit_line.status = CreditStatus::Active;
+        suspension::clear(&mut credit_line);
+        save_credit_line(&env, &credit_line);
+
+        publish_credit_line_event(
+            &env,
+            (symbol_short!("credit"), symbol_short!("restruct")),
+            CreditLineEvent {
+                event_type: symbol_short!("restruct"),
+                borrower,
+                status: CreditStatus::Active,
+                credit_limit,
+                interest_rate_bps: credit_line.interest_rate_bps,
+                rate_mode: credit_line.rate_mode,
+                risk_score: credit_line.risk_score,
+                suspension_reason: credit_line.suspension_reason,
+                suspended_until: credit_line.suspended_until,
+                auto: false,
+                event_version: EVENT_VERSION,
+                sequence: events::next_sequence(&env),
+            },
+        );
+    }
+
+    /// Mark a credit line as defaulted (admin only). Emits a CreditLineDefaulted event.
+    ///
+    /// # Panics
+    /// * `"Credit line not found"`
+    /// * `"credit line is disputed"`
+    pub fn default_credit_line(env: Env, borrower: Address) {
+        let admin = require_admin_auth(&env);
+        audit::record(
+            &env,
+            &admin,
+            symbol_short!("default"),
+            Some(borrower.clone()),
+        );
+
+        let mut credit_line: CreditLineData =
+            load_credit_line(&env, &borrower).expect("Credit line not found");
+        disputes::require_not_disputed(&credit_line);
+
+        credit_line.status = CreditStatus::Defaulted;
+        suspension::clear(&mut credit_line);
+        let payout = insurance::cover_default(&env, &mut credit_line);
+        save_credit_line(&env, &credit_line);
+        publish_insurance_payout_if_any(&env, &credit_line, payout);
+
+        publish_credit_line_event(
+            &env,
+            (symbol_short!("credit"), symbol_short!("default")),
+            CreditLineEvent {
+                event_type: symbol_short!("default"),
+                borrower: borrower.clone(),
+                status: CreditStatus::Defaulted,
+                credit_limit: credit_line.credit_limit,
+                interest_rate_bps: credit_line.interest_rate_bps,
+                rate_mode: credit_line.rate_mode,
+                risk_score: credit_line.risk_score,
+                suspension_reason: credit_line.suspension_reason,
+                suspended_until: credit_line.suspended_until,
+                auto: false,
+                event_version: EVENT_VERSION,
+                sequence: events::next_sequence(&env),
+            },
+        );
+    }
+
+    /// Mint a transferable position id for the borrower's line (borrower only).
+    ///
+    /// # Panics
+    /// * `"Credit line not found"`
+    /// * `"position already minted"`
+    pub fn mint_position(env: Env, borrower: Address) -> u64 {
+        borrower.require_auth();
+        let mut credit_line: CreditLineData =
+            load_credit_line(&env, &borrower).expect("Credit line not found");
+        let position_id = positions::mint(&env, &mut credit_line);
+        save_credit_line(&env, &credit_line);
+        position_id
+    }
+
+    /// Get the current borrower of a minted position (view function).
+    pub fn get_position_owner(env: Env, position_id: u64) -> Option<Address> {
+        positions::owner(&env, position_id)
+    }
+
+    /// Move a minted credit line position to `new_borrower`, with both parties' auth.
+    ///
+    /// The line is re-keyed to the new address along with its activity history.
+    /// Any pending large-draw request is dropped. Emits a PositionTransferEvent.
+    ///
+    /// # Panics
+    /// * `"Credit line not found"`
+    /// * `"position not minted"`
+    /// * `"credit line is closed"`
+    /// * `"new borrower already has a credit line"`
+    /// * `ContractError::NotAllowlisted` – the allowlist is enabled and `new_borrower` is not on it
+    pub fn transfer_credit_line(env: Env, old_borrower: Address, new_borrower: Address) {
+        old_borrower.require_auth();
+        new_borrower.require_auth();
+
+        let mut credit_line: CreditLineData =
+            load_credit_line(&env, &old_borrower).expect("Credit line not found");
+        if credit_line.position_id == 0 {
+            panic!("position not minted");
+        }
+        if credit_line.status == CreditStatus::Closed {
+            panic!("credit line is closed");
+        }
+        if storage::has_persistent(&env, &DataKey::Line(new_borrower.clone())) {
+            panic!("new borrower already has a credit line");
+        }
+        allowlist::require_allowed(&env, &new_borrower);
+
+        // Pool, collateral, and stats totals are unchanged, so only the key moves.
+        storage::remove_persistent(&env, &DataKey::Line(old_borrower.clone()));
+        borrowers::remove(&env, &old_borrower);
+        credit_line.borrower = new_borrower.clone();
+        borrowers::track(&env, &credit_line);
+        schema::write(&env, &credit_line);
+        ttl::extend_line(&env, &new_borrower);
+        positions::set_owner(&env, credit_line.position_id, &new_borrower);
+        history::transfer(&env, &old_borrower, &new_borrower);
+        disputes::transfer(&env, &old_borrower, &new_borrower);
+        tiers::transfer(&env, &old_borrower, &new_borrower);
+        metadata::transfer(&env, &old_borrower, &new_borrower);
+        statements::transfer(&env, &old_borrower, &new_borrower);
+        autopay::clear(&env, &old_borrower);
+        amortization::transfer(&env, &old_borrower, &new_borrower);
+        default_interest::transfer(&env, &old_borrower, &new_borrower);
+        forgiveness::transfer(&env, &old_borrower, &new_borrower);
+        large_draws::cancel(&env, &old_borrower);
+
+        publish_position_transfer(
+            &env,
+            PositionTransferEvent {
+                position_id: credit_line.position_id,
+                old_borrower,
+                new_borrower,
+                event_version: EVENT_VERSION,
+                sequence: events::next_sequence(&env),
+            },
+        );
+    }
+
+    /// Sell the claim on a defaulted line to `collector` for `price` (admin only).
+    ///
+    /// The collector pays `price` into the reserve and is recorded as the line's
+    /// creditor. The pool books the gap between the price and the outstanding
+    /// principal as a loss (or gain); later repayments and liquidation proceeds
+    /// are forwarded to the collector. Emits a DebtAssignedEvent.
+    ///
+    /// # Panics
+    /// * `"Credit line not found"`
+    /// * `"price must be non-negative"`
+    /// * `"credit line is not defaulted"`
+    /// * `"debt already assigned"`
+    pub fn assign_debt(env: Env, borrower: Address, collector: Address, price: i128) {
+        let admin = require_admin_auth(&env);
+        audit::record(
+            &env,
+            &admin,
+            symbol_short!("assign"),
+            Some(borrower.clone()),
+        );
+        collector.require_auth();
+
+        let mut credit_line: CreditLineData =
+            load_credit_line(&env, &borrower).expect("Credit line not found");
+
+        collections::assign(&env, &mut credit_line, &collector, price);
+        save_credit_line(&env, &credit_line);
+
+        publish_debt_assigned(
+            &env,
+            DebtAssignedEvent {
+                borrower,
+                collector,
+                price,
+                outstanding: interest::outstanding(&credit_line),
+                event_version: EVENT_VERSION,
+                sequence: events::next_sequence(&env),
+            },
+        );
+    }
+
+    /// Write off up to `amount` of a defaulted line's debt, interest first (admin only).
+    /// Written-off principal is charged against the liquidity pool. Returns the amount
+    /// written off, capped at the outstanding balance. Emits a WriteOffEvent.
+    ///
+    /// # Panics
+    /// * `"Credit line not found"`
+    /// * `"amount must be positive"`
+    /// * `"credit line is not defaulted"`
+    pub fn write_off(env: Env, borrower: Address, amount: i128) -> i128 {
+        let admin = require_admin_auth(&env);
+        audit::record(
+            &env,
+            &admin,
+            symbol_short!("write_off"),
+            Some(borrower.clone()),
+        );
+        if amount <= 0 {
+            panic!("amount must be positive");
+        }
+
+        let mut credit_line: CreditLineData =
+            load_credit_line(&env, &borrower).expect("Credit line not found");
+        if credit_line.status != CreditStatus::Defaulted {
+            panic!("credit line is not defaulted");
+        }
+
+        let written = write_off_debt(&env, &mut credit_line, amount);
+        save_credit_line(&env, &credit_line);
+        written
+    }
+
+    /// Forgive up to `amount` of a line's debt, interest first (admin only), for
+    /// `reason_code`, an off-chain reason code recorded in the event. Once the total
+    /// forgiven on the line would pass the approval threshold, each forgiveness also needs
+    /// `approver`, a second key holding the `Approver` role, to authorize. Forgiven principal is charged against the liquidity pool, as
+    /// for a write-off. Returns the amount forgiven, capped at the outstanding balance.
+    /// Emits a ForgivenessEvent.
+    ///
+    /// # Panics
+    /// * `"amount must be positive"`
+    /// * `"Credit line not found"`
+    /// * `"credit line is closed"`
+    /// * `"nothing to forgive"` – the line owes nothing
+    /// * `"forgiveness requires approval"` – the line's total would pass the threshold
+    ///   without an approver
+    /// * `"approver must be a second key"` – the approver is the admin
+    /// * `"approver lacks Approver role"`
+    pub fn forgive_debt(
+        env: Env,
+        borrower: Address,
+        amount: i128,
+        reason_code: u32,
+        approver: Option<Address>,
+    ) -> i128 {
+        let admin = require_admin_auth(&env);
+        audit::record(
+            &env,
+            &admin,
+            symbol_short!("forgive"),
+            Some(borrower.clone()),
+        );
+        if amount <= 0 {
+            panic!("amount must be positive");
+        }
+        let mut credit_line: CreditLineData =
+            load_credit_line(&env, &borrower).expect("Credit line not found");
+        if credit_line.status == CreditStatus::Closed {
+            panic!("credit line is closed");
+        }
+        interest::accrue(&env, &mut credit_line);
+        let forgiven = amount.min(interest::outstanding(&credit_line));
+        if forgiven == 0 {
+            panic!("nothing to forgive");
+        }
+        forgiveness::approve(&env, &admin, &borrower, forgiven, &approver);
+
+        let interest_portion = interest::apply_repayment(&env, &mut credit_line, forgiven);
+        let principal_portion = forgiven - interest_portion;
+        // An assigned line's loss falls on the collector, not the pool.
+        if credit_line.creditor.is_none() {
+            liquidity::realize_loss(&env, &credit_line.token, principal_portion);
+        }
+        credit_line.min_due_amount = credit_line
+            .min_due_amount
+            .min(interest::outstanding(&credit_line));
+        save_credit_line(&env, &credit_line);
+        stats::record_forgiveness(&env, forgiven);
+
+        publish_forgiveness(
+            &env,
+            ForgivenessEvent {
+                borrower,
+                amount: forgiven,
+                interest_portion,
+                principal_portion,
+                reason_code,
+                approver,
+                remaining_outstanding: interest::outstanding(&credit_line),
+                event_version: EVENT_VERSION,
+                sequence: events::next_sequence(&env),
+            },
+        );
+        forgiven
+    }
+
+    /// Set the total forgiven on a line above which `forgive_debt` needs a second
+    /// approver; 0 requires none (admin only).
+    ///
+    /// # Panics
+    /// * `"threshold cannot be negative"`
+    pub fn set_forgiveness_threshold(env: Env, threshold: i128) {
+        let admin = require_admin_auth(&env);
+        forgiveness::set_threshold(&env, threshold);
+        audit::config_changed(&env, &admin, symbol_short!("forgive"), None, threshold);
+    }
+
+    /// Get the forgiveness approval threshold (view function).
+    pub fn get_forgiveness_threshold(env: Env) -> i128 {
+        forgiveness::get_threshold(&env)
+    }
+
+    /// Get the total forgiven on a borrower's line (view function).
+    pub fn get_forgiven_total(env: Env, borrower: Address) -> i128 {
+        forgiveness::forgiven(&env, &borrower)
+    }
+
+    /// Correct a line's utilized amount by `delta` for an operational error such as a
+    /// reversed off-chain settlement (admin only). Interest is accrued first, the result
+    /// must stay within `0..=credit_limit`, and an AdjustmentEvent is always emitted.
+    ///
+    /// # Panics
+    /// * `"delta must be non-zero"`
+    /// * `"Credit line not found"`
+    /// * `"credit line is closed"`
+    /// * `"utilized_amount out of bounds"` – the result would be negative or above the limit
+    pub fn adjust_utilized(env: Env, borrower: Address, delta: i128, reason_code: u32) {
+        let admin = require_admin_auth(&env);
+        audit::record(
+            &env,
+            &admin,
+            symbol_short!("adjust"),
+            Some(borrower.clone()),
+        );
+        if delta == 0 {
+            panic!("delta must be non-zero");
+        }
+        let mut credit_line: CreditLineData =
+            load_credit_line(&env, &borrower).expect("Credit line not found");
+        if credit_line.status == CreditStatus::Closed {
+            panic!("credit line is closed");
+        }
+        interest::accrue(&env, &mut credit_line);
+        let old_utilized_amount = credit_line.utilized_amount;
+        let new_utilized_amount = math::checked(&env, math::add(old_utilized_amount, delta));
+        if new_utilized_amount < 0 || new_utilized_amount > credit_line.credit_limit {
+            panic!("utilized_amount out of bounds");
+        }
+        credit_line.utilized_amount = new_utilized_amount;
+        save_credit_line(&env, &credit_line);
+
+        publish_adjustment(
+            &env,
+            AdjustmentEvent {
+                borrower,
+                delta,
+                reason_code,
+                old_utilized_amount,
+                new_utilized_amount,
+                event_version: EVENT_VERSION,
+                sequence: events::next_sequence(&env),
+            },
+        );
+    }
+
+    /// Set the collateral policy: LTV and unsecured portion of each limit (admin only).
+    pub fn set_collateral_config(env: Env, config: CollateralConfig) {
+        let admin = require_admin_auth(&env);
+        timelock::require_unlocked(&env);
+        collateral::set_config(&env, &config);
+        audit::config_changed(&env, &admin, symbol_short!("col_cfg"), None, config);
+    }
+
+    /// Get the collateral policy (view function).
+    pub fn get_collateral_config(env: Env) -> CollateralConfig {
+        collateral::get_config(&env)
+    }
+
+    /// Allow or disallow `token` as collateral on lines in other tokens (admin only).
+    /// A line's own token is always accepted. Allowed tokens only count towards the
+    /// limit while the price oracle prices them. Collateral already deposited stays.
+    pub fn set_collateral_token(env: Env, token: Address, allowed: bool) {
+        let admin = require_admin_auth(&env);
+        timelock::require_unlocked(&env);
+        collateral::set_allowed(&env, &token, allowed);
+        audit::config_changed(
+            &env,
+            &admin,
+            symbol_short!("col_token"),
+            Some(token),
+            allowed,
+        );
+    }
+
+    /// Get the tokens allowed as collateral besides each line's own (view function).
+    pub fn get_collateral_tokens(env: Env) -> Vec<Address> {
+        collateral::allowed_tokens(&env)
+    }
+
+    /// Deposit `amount` of `token` as collateral against the borrower's line.
+    ///
+    /// # Panics
+    /// * `"Credit line not found"` – borrower has no credit line
+    /// * `"credit line not active"` – line is Defaulted or Closed
+    /// * `"amount must be positive"` – amount is zero or negative
+    /// * `"collateral token mismatch"` – line already holds a different collateral token
+    /// * `"collateral token not allowed"` – not the line's token and not allowed by the admin
+    pub fn deposit_collateral(env: Env, borrower: Address, token: Address, amount: i128) {
+        borrower.require_auth();
+
+        let mut credit_line: CreditLineData =
+            load_credit_line(&env, &borrower).expect("Credit line not found");
+
+        if credit_line.status == CreditStatus::Defaulted
+            || credit_line.status == CreditStatus::Closed
+        {
+            panic!("credit line not active");
+        }
+
+        collateral::deposit(&env, &mut credit_line, &token, amount);
+        save_credit_line(&env, &credit_line);
+
+        publish_collateral_deposited(
+            &env,
+            CollateralEvent {
+                borrower,
+                token,
+                amount,
+                new_collateral_amount: credit_line.collateral_amount,
+                event_version: EVENT_VERSION,
+                sequence: events::next_sequence(&env),
+            },
+        );
+    }
+
+    /// Set the price oracle used to value collateral (admin only). Prices older than
+    /// `max_age_secs` are rejected.
+    pub fn set_price_oracle(env: Env, oracle: Address, max_age_secs: u64) {
+        let admin = require_admin_auth(&env);
+        timelock::require_unlocked(&env);
+        let config = OracleConfig {
+            oracle,
+            max_age_secs,
+        };
+        oracle::set_config(&env, &config);
+        audit::config_changed(&env, &admin, symbol_short!("oracle"), None, config);
+    }
+
+    /// Get the configured price oracle, if any (view function).
+    pub fn get_price_oracle(env: Env) -> Option<OracleConfig> {
+        oracle::get_config(&env)
+    }
+
+    /// Express a line's credit limit in the price oracle's reference currency (admin only).
+    /// Draws still settle in the line's token; each draw is valued at a fresh oracle
+    /// price and checked against `reference_limit`. Passing 0 returns the line to a
+    /// token-unit limit at the last converted `credit_limit`.
+    ///
+    /// # Panics
+    /// * `"Credit line not found"`
+    /// * `"credit line is closed"`
+    /// * `"credit_limit must be non-negative"`
+    /// * `"price oracle not configured"` – a non-zero limit needs the oracle
+    pub fn set_reference_limit(env: Env, borrower: Address, reference_limit: i128) {
+        let admin = require_admin_auth(&env);
+        audit::record(
+            &env,
+            &admin,
+            symbol_short!("ref_limit"),
+            Some(borrower.clone()),
+        );
+        let mut credit_line: CreditLineData =
+            load_credit_line(&env, &borrower).expect("Credit line not found");
+        if credit_line.status == CreditStatus::Closed {
+            panic!("credit line is closed");
+        }
+        if reference_limit < 0 {
+            panic!("credit_limit must be non-negative");
+        }
+        restrictions::require_updates_allowed(&credit_line);
+
+        credit_line.reference_limit = reference_limit;
+        credit_line.reference_utilized = 0;
+        let utilized = credit_line.utilized_amount;
+        fx::reprice(&env, &mut credit_line, utilized);
+        save_credit_line(&env, &credit_line);
+        publish_risk_adjusted(&env, &credit_line);
+    }
+
+    /// Set the liquidation policy (admin only).
+    pub fn set_liquidation_config(env: Env, config: LiquidationConfig) {
+        let admin = require_admin_auth(&env);
+        timelock::require_unlocked(&env);
+        liquidation::set_config(&env, &config);
+        audit::config_changed(&env, &admin, symbol_short!("liq_cfg"), None, config);
+    }
+
+    /// Get the liquidation policy (view function).
+    pub fn get_liquidation_config(env: Env) -> LiquidationConfig {
+        liquidation::get_config(&env)
+    }
+
+    /// Seize collateral from a Defaulted line and apply it to the outstanding debt.
+    ///
+    /// # Arguments
+    /// * `liquidator` - The admin, or anyone when liquidation is open to keepers.
+    ///   Keepers (but not the admin) receive `keeper_bonus_bps` of the seized collateral.
+    ///
+    /// # Panics
+    /// * `"unauthorized"` – liquidation is admin-only and `liquidator` is not the admin
+    /// * `"credit line not defaulted"` – line is not Defaulted
+    /// * `"no collateral to liquidate"` – line holds no collateral
+    /// * `"nothing to liquidate"` – line has no outstanding debt
+    /// * `"collateral has no price"` – collateral in another token and no price oracle
+    pub fn liquidate(env: Env, liquidator: Address, borrower: Address) {
+        liquidator.require_auth();
+
+        let admin = require_admin(&env);
+        let is_admin = liquidator == admin;
+        if !is_admin && !liquidation::get_config(&env).open_to_keepers {
+            panic!("unauthorized");
+        }
+
+        let mut credit_line: CreditLineData =
+            load_credit_line(&env, &borrower).expect("Credit line not found");
+
+        let outcome = liquidation::liquidate(&env, &mut credit_line, &liquidator, !is_admin);
+        save_credit_line(&env, &credit_line);
+
+        publish_liquidation_event(
+            &env,
+            LiquidationEvent {
+                borrower,
+                liquidator,
+                collateral_token: outcome.collateral_token,
+                seized_amount: outcome.seized_amount,
+                keeper_bonus: outcome.keeper_bonus,
+                recovered_value: outcome.recovered_value,
+                remaining_debt: interest::outstanding(&credit_line),
+                event_version: EVENT_VERSION,
+                sequence: events::next_sequence(&env),
+            },
+        );
+    }
+
+    /// Set the automatic risk score adjustment policy (admin only).
+    ///
+    /// # Panics
+    /// * `"max_score cannot exceed 100"`
+    /// * `"min_score cannot exceed max_score"`
+    pub fn set_risk_adjustment_config(env: Env, config: RiskAdjustmentConfig) {
+        let admin = require_admin_auth(&env);
+        risk_adjust::set_config(&env, &config);
+        audit::config_changed(&env, &admin, symbol_short!("risk_adj"), None, config);
+    }
+
+    /// Get the automatic risk score adjustment policy (view function).
+    pub fn get_risk_adjustment_config(env: Env) -> RiskAdjustmentConfig {
+        risk_adjust::get_config(&env)
+    }
+
+    /// Set the keeper policy for overdue processing (admin only).
+    pub fn set_keeper_config(env: Env, config: KeeperConfig) {
+        let admin = require_admin_auth(&env);
+        timelock::require_unlocked(&env);
+        keeper::set_config(&env, &config);
+        audit::config_changed(&env, &admin, symbol_short!("keeper"), None, config);
+    }
+
+    /// Get the keeper policy (view function).
+    pub fn get_keeper_config(env: Env) -> KeeperConfig {
+        keeper::get_config(&env)
+    }
+
+    /// Process a missed payment on a scheduled line (callable by anyone).
+    ///
+    /// Once the minimum payment is more than the grace period past due, records a
+    /// missed cycle, bills the next cycle on top of the arrears, and marks the
+    /// line Delinquent — or defaults it after `default_after_missed_cycles`. A line
+    /// delinquent for `auto_default_after_secs` is defaulted without waiting for
+    /// another missed cycle; its default event has `auto` set. Pays `keeper` the
+    /// configured reward in the line's token out of the protocol fees accrued in it,
+    /// capped at those fees.
+    ///
+    /// # Panics
+    /// * `"Credit line not found"` – borrower has no credit line
+    /// * `"credit line not eligible"` – line is unscheduled, Defaulted, or Closed
+    /// * `"payment not overdue"` – nothing owed, or still within the grace period
+    pub fn process_overdue(env: Env, keeper: Address, borrower: Address) {
+        keeper.require_auth();
+
+        let mut credit_line: CreditLineData =
+            load_credit_line(&env, &borrower).expect("Credit line not found");
+
+        let config = keeper::get_config(&env);
+        let previous_status = credit_line.status;
+        let mut auto = keeper::auto_default_if_due(&env, &mut credit_line, &config);
+        let mut score_adjusted = false;
+        if !auto {
+            keeper::record_missed_cycle(&env, &mut credit_line, &config);
+            score_adjusted = risk_adjust::missed_payment(&env, &mut credit_line);
+            auto = keeper::auto_default_if_due(&env, &mut credit_line, &config);
+        }
+        let payout = if credit_line.status == CreditStatus::Defaulted {
+            insurance::cover_default(&env, &mut credit_line)
+        } else {
+            (0, 0)
+        };
+        save_credit_line(&env, &credit_line);
+        publish_insurance_payout_if_any(&env, &credit_line, payout);
+        if score_adjusted {
+            publish_risk_adjusted(&env, &credit_line);
+        }
+
+        if credit_line.status != previous_status {
+            let event_type = if credit_line.status == CreditStatus::Defaulted {
+                symbol_short!("default")
+            } else {
+                symbol_short!("delinq")
+            };
+            publish_credit_line_event(
+                &env,
+                (symbol_short!("credit"), event_type.clone()),
+                CreditLineEvent {
+                    event_type,
+                    borrower: borrower.clone(),
+                    status: credit_line.status,
+                    credit_limit: credit_line.credit_limit,
+                    interest_rate_bps: credit_line.interest_rate_bps,
+                    rate_mode: credit_line.rate_mode,
+                    risk_score: credit_line.risk_score,
+                    suspension_reason: credit_line.suspension_reason,
+                    suspended_until: credit_line.suspended_until,
+                    auto,
+                    event_version: EVENT_VERSION,
+                    sequence: events::next_sequence(&env),
+                },
+            );
+        }
+
+        let keeper_reward = treasury::take(&env, &credit_line.token, config.keeper_reward);
+        if keeper_reward > 0 {
+            token::Client::new(&env, &credit_line.token).transfer(
+                &env.current_contract_address(),
+                &keeper,
+                &keeper_reward,
+            );
+        }
+
+        publish_overdue_processed(
+            &env,
+            OverdueProcessedEvent {
+                borrower,
+                keeper,
+                missed_cycles: credit_line.missed_cycles,
+                status: credit_line.status,
+                keeper_reward,
+                event_version: EVENT_VERSION,
+                sequence: events::next_sequence(&env),
+            },
+        );
+    }
+
+    /// Get the next payment due on a scheduled credit line (view function).
+    /// Returns `None` if the borrower has no line or the line has no schedule.
+    /// `overdue` is set once the due date has passed with the minimum unpaid.
+    pub fn get_next_payment(env: Env, borrower: Address) -> Option<NextPayment> {
+        let credit_line: CreditLineData = load_credit_line(&env, &borrower)?;
+        schedule::next_payment(&env, &credit_line)
+    }
+
+    /// Predict the outcome of `draw_credit(borrower, amount, purpose)` without executing it (view
+    /// function). A draw that would revert is reported through `error`, a
+    /// `ContractError` code, instead of reverting.
+    pub fn preview_draw(
+        env: Env,
+        borrower: Address,
+        amount: i128,
+        purpose: Option<Symbol>,
+    ) -> DrawPreview {
+        preview::draw(&env, &borrower, amount, &purpose)
+    }
+
+    /// Predict the outcome of `repay_credit(borrower, amount)` without executing it (view
+    /// function). A repayment that would revert is reported through `error`, a
+    /// `ContractError` code, instead of reverting.
+    pub fn preview_repay(env: Env, borrower: Address, amount: i128) -> RepayPreview {
+        preview::repay(&env, &borrower, amount)
+    }
+
+    /// Get the standing of a borrower's line, including days past due (view function).
+    pub fn get_health(env: Env, borrower: Address) -> Option<LineHealth> {
+        let credit_line: CreditLineData = load_credit_line(&env, &borrower)?;
+        Some(delinquency::health(&env, &credit_line))
+    }
+
+    /// `get_health` for each of `borrowers`, in the same order, for dashboards that
+    /// read many lines in one call (view function).
+    pub fn get_healths(env: Env, borrowers: Vec<Address>) -> Vec<Option<LineHealth>> {
+        let mut healths = Vec::new(&env);
+        for borrower in borrowers.iter() {
+            let health =
+                load_credit_line(&env, &borrower).map(|line| delinquency::health(&env, &line));
+            healths.push_back(health);
+        }
+        healths
+    }
+
+    /// Report reserve coverage, pause flags, oracle freshness, delinquent lines and
+    /// instance TTL in one call, for off-chain monitors (view function).
+    pub fn health_check(env: Env) -> HealthReport {
+        monitor::report(&env)
+    }
+
+    /// Report reserve balance, outstanding principal, commitments, utilization and
+    /// interest earned for every supported token (view function).
+    pub fn get_reserve_breakdown(env: Env) -> Vec<(Address, ReserveInfo)> {
+        monitor::breakdown(&env)
+    }
+
+    /// Get a borrower's lifetime draw and repayment history (view function).
+    pub fn get_borrower_history(env: Env, borrower: Address) -> BorrowerHistory {
+        history::get(&env, &borrower)
+    }
+
+    /// Page through a borrower's most recent draws and repayments, newest first (view
+    /// function). Returns up to `limit` entries after skipping the `start` most recent;
+    /// only the last `TRANSACTION_LOG_SIZE` are kept.
+    pub fn get_transactions(
+        env: Env,
+        borrower: Address,
+        start: u32,
+        limit: u32,
+    ) -> Vec<Transaction> {
+        history::page(&env, &borrower, start, limit)
+    }
+
+    /// Get aggregate protocol counters (view function).
+    pub fn get_protocol_stats(env: Env) -> ProtocolStats {
+        stats::get(&env)
+    }
+
+    /// Get the version of the contract's public interface (view function). It moves
+    /// whenever an entrypoint or event changes shape.
+    pub fn interface_version(_env: Env) -> u32 {
+        features::INTERFACE_VERSION
+    }
+
+    /// Get the optional subsystems this deployment supports, e.g. `collatrl`,
+    /// `schedules` or `multi_tok` (view function).
+    pub fn supported_features(env: Env) -> Vec<Symbol> {
+        features::supported(&env)
+    }
+
+    /// Get every protocol-wide setting in one call (view function).
+    pub fn get_config(env: Env) -> ProtocolConfig {
+        config::get(&env)
+    }
+
+    /// Apply a batch of configuration changes in one call (admin only). Each entry is
+    /// validated and emits the same `config` event as its own setter; if any entry is
+    /// invalid the whole batch reverts.
+    ///
+    /// # Panics
+    /// * Any panic of the individual setter for an entry
+    pub fn set_config(env: Env, updates: Vec<ConfigUpdate>) {
+        let admin = require_admin_auth(&env);
+        timelock::require_unlocked(&env);
+        config::apply(&env, &admin, updates);
+    }
+
+    /// Set the minimum delay between queuing and executing a timelocked change (admin
+    /// only). Once it is non-zero, changes to fees, the reserve, rates and tiers, repayment
+    /// terms, insurance, staking, collateral, liquidation, keepers and the price oracle,
+    /// and batched config changes, must go through `queue_action`, and so must later delay
+    /// changes.
+    ///
+    /// # Panics
+    /// * `"change must be queued through the timelock"` – a delay is already set
+    pub fn set_timelock_delay(env: Env, delay_secs: u64) {
+        let admin = require_admin_auth(&env);
+        timelock::set_initial_delay(&env, delay_secs);
+        audit::config_changed(&env, &admin, symbol_short!("tl_delay"), None, delay_secs);
+    }
+
+    /// Get the timelock's minimum delay in seconds; 0 if none (view function).
+    pub fn get_timelock_delay(env: Env) -> u64 {
+        timelock::delay(&env)
+    }
+
+    /// Queue `action` for execution at or after `eta` (admin only). Returns the action
+    /// id. Emits a TimelockEvent.
+    ///
+    /// # Panics
+    /// * `"eta is before the timelock delay"`
+    pub fn queue_action(env: Env, action: TimelockAction, eta: u64) -> u64 {
+        let admin = require_admin_auth(&env);
+        let queued = timelock::queue(&env, action, eta);
+        audit::record(&env, &admin, symbol_short!("tl_queue"), None);
+        publish_timelock(&env, symbol_short!("tl_queue"), &queued);
+        queued.action_id
+    }
+
+    /// Execute a queued action once its `eta` has passed (admin only). Config entries are
+    /// validated now, as by `set_config`, and emit their usual `config` events. Emits a
+    /// TimelockEvent.
+    ///
+    /// # Panics
+    /// * `"queued action not found"`
+    /// * `"queued action not due"`
+    /// * Any panic of the individual setter for a config entry
+    pub fn execute_action(env: Env, action_id: u64) {
+        let admin = require_admin_auth(&env);
+        let queued = timelock::take_due(&env, action_id);
+        audit::record(&env, &admin, symbol_short!("tl_exec"), None);
+        publish_timelock(&env, symbol_short!("tl_exec"), &queued);
+        match queued.action {
+            TimelockAction::Config(updates) => config::apply(&env, &admin, updates),
+            TimelockAction::Upgrade(hash) => env.deployer().update_current_contract_wasm(hash),
+            TimelockAction::SetDelay(delay_secs) => {
+                timelock::set_delay(&env, delay_secs);
+                audit::config_changed(&env, &admin, symbol_short!("tl_delay"), None, delay_secs);
+            }
+        }
+    }
+
+    /// Drop a queued action without executing it (admin only). Emits a TimelockEvent.
+    ///
+    /// # Panics
+    /// * `"queued action not found"`
+    pub fn cancel_action(env: Env, action_id: u64) {
+        let admin = require_admin_auth(&env);
+        let queued = timelock::cancel(&env, action_id);
+        audit::record(&env, &admin, symbol_short!("tl_cancel"), None);
+        publish_timelock(&env, symbol_short!("tl_cancel"), &queued);
+    }
+
+    /// Get a queued action, if it has not been executed or cancelled (view function).
+    pub fn get_queued_action(env: Env, action_id: u64) -> Option<QueuedAction> {
+        timelock::get(&env, action_id)
+    }
+
+    /// Sequence number of the most recently published event; 0 if none (view function).
+    pub fn get_event_sequence(env: Env) -> u64 {
+        events::current_sequence(&env)
+    }
+
+    /// Number of borrowers with a credit line that is not Closed (view function).
+    pub fn count_borrowers(env: Env) -> u32 {
+        borrowers::count(&env)
+    }
+
+    /// Page through borrowers with a credit line that is not Closed (view function).
+    /// Returns up to `limit` addresses starting at index `start`. Closing a line moves the
+    /// last borrower into its slot, so ordering is not stable across closes.
+    pub fn list_borrowers(env: Env, start: u32, limit: u32) -> Vec<Address> {
+        borrowers::list(&env, start, limit)
+    }
+
+    /// Number of entries in the audit log (view function).
+    pub fn get_audit_count(env: Env) -> u32 {
+        audit::count(&env)
+    }
+
+    /// Page through the audit log of admin and role-gated operations, oldest first (view
+    /// function). Returns up to `limit` entries starting at index `start`.
+    pub fn get_audit_log(env: Env, start: u32, limit: u32) -> Vec<AuditEntry> {
+        audit::list(&env, start, limit)
+    }
+
+    /// Upgrade a borrower's stored line to the current layout (callable by anyone).
+    /// Returns true if the entry was migrated, false if it was already current.
+    ///
+    /// Lines are also migrated automatically the first time they are touched.
+    ///
+    /// # Panics
+    /// * `"Credit line not found"`
+    pub fn migrate_credit_line(env: Env, borrower: Address) -> bool {
+        let (line, current) = schema::read(&env, &borrower).expect("Credit line not found");
+        if !current {
+            save_credit_line(&env, &line);
+        }
+        !current
+    }
+
+    /// Upgrade the stored lines of `borrowers` to the current layout (admin only).
+    /// Borrowers without a line are skipped. Returns the number of lines migrated.
+    pub fn migrate_lines(env: Env, borrowers: Vec<Address>) -> u32 {
+        require_admin_auth(&env);
+        let mut migrated = 0;
+        for borrower in borrowers.iter() {
+            if let Some((line, false)) = schema::read(&env, &borrower) {
+                save_credit_line(&env, &line);
+                migrated += 1;
+            }
+        }
+        migrated
+    }
+
+    /// Fold interest accrued since the last checkpoint into the stored line (callable by
+    /// anyone). Keepers use this to keep idle lines' balances current for dashboards and
+    /// liquidation checks. Returns the interest added.
+    ///
+    /// # Panics
+    /// * `"Credit line not found"`
+    pub fn accrue_interest(env: Env, borrower: Address) -> i128 {
+        checkpoint_interest(&env, &borrower).expect("Credit line not found")
+    }
+
+    /// `accrue_interest` for each of `borrowers` (callable by anyone). Borrowers without
+    /// a line are skipped. Returns the total interest added.
+    pub fn accrue_many(env: Env, borrowers: Vec<Address>) -> i128 {
+        let mut total: i128 = 0;
+        for borrower in borrowers.iter() {
+            if let Some(added) = checkpoint_interest(&env, &borrower) {
+                total = total.saturating_add(added);
+            }
+        }
+        total
+    }
+
+    /// Close the current statement cycle of a scheduled line (callable by anyone).
+    ///
+    /// Keepers call this once `cycle_length_secs` has passed since the cycle started.
+    /// Accrues interest and bills the line as a draw or repayment would, then publishes
+    /// a StatementEvent for the cycle and opens the next one at the closing balance. A
+    /// line opened before statements existed has its first cycle opened instead, with
+    /// no event. Returns true if a statement was published.
+    ///
+    /// # Panics
+    /// * `"Credit line not found"`
+    /// * `"credit line is closed"`
+    /// * `"credit line has no schedule"`
+    /// * `"cycle not ended"` – less than a cycle length since the cycle started
+    pub fn close_cycle(env: Env, borrower: Address) -> bool {
+        let mut credit_line: CreditLineData =
+            load_credit_line(&env, &borrower).expect("Credit line not found");
+        if credit_line.status == CreditStatus::Closed {
+            panic!("credit line is closed");
+        }
+        if credit_line.cycle_length_secs == 0 {
+            panic!("credit line has no schedule");
+        }
+        interest::accrue(&env, &mut credit_line);
+        schedule::roll_forward(&env, &mut credit_line);
+        save_credit_line(&env, &credit_line);
+        let Some(cycle) = statements::close(&env, &credit_line) else {
+            return false;
+        };
+        publish_statement(
+            &env,
+            StatementEvent {
+                borrower,
+                cycle_start: cycle.cycle_start,
+                cycle_end: env.ledger().timestamp(),
+                opening_balance: cycle.opening_balance,
+                closing_balance: interest::outstanding(&credit_line),
+                draws: cycle.draws,
+                interest_charged: statements::interest_charged(&cycle, &credit_line),
+                fees: cycle.fees,
+                payments: cycle.payments,
+                min_due: credit_line.min_due_amount,
+                next_due_ts: credit_line.next_due_ts,
+                event_version: EVENT_VERSION,
+                sequence: events::next_sequence(&env),
+            },
+        );
+        true
+    }
+
+    /// Get the running totals of a scheduled line's open statement cycle (view function).
+    pub fn get_statement_cycle(env: Env, borrower: Address) -> Option<StatementCycle> {
+        statements::get(&env, &borrower)
+    }
+
+    /// Project a line's accrued interest `horizon_secs` from now, assuming no draws or
+    /// repayments in between and the rate it accrues at from now on (view function).
+    ///
+    /// # Panics
+    /// * `"Credit line not found"`
+    pub fn quote_interest(env: Env, borrower: Address, horizon_secs: u64) -> i128 {
+        let credit_line: CreditLineData =
+            load_credit_line(&env, &borrower).expect("Credit line not found");
+        interest::quote(&env, &credit_line, horizon_secs)
+    }
+
+    /// Extend a credit line's storage TTL to at least `ledgers` (callable by anyone).
+    ///
+    /// Lets keepers keep dormant-but-open lines from being archived. `ledgers` is
+    /// capped at the network's maximum TTL.
+    ///
+    /// # Panics
+    /// * `"Credit line not found"`
+    /// * `"ledgers must be positive"`
+    pub fn bump_credit_line(env: Env, borrower: Address, ledgers: u32) {
+        if !storage::has_persistent(&env, &DataKey::Line(borrower.clone())) {
+            panic!("Credit line not found");
+        }
+        ttl::bump_line(&env, &borrower, ledgers);
+    }
+
+    /// Extend the contract instance's storage TTL to at least `ledgers` (callable by anyone).
+    ///
+    /// # Panics
+    /// * `"ledgers must be positive"`
+    pub fn bump_instance(env: Env, ledgers: u32) {
+        ttl::bump_instance(&env, ledgers);
+    }
+
+    /// Get credit line data for a borrower (view function).
+    pub fn get_credit_line(env: Env, borrower: Address) -> Option<CreditLineData> {
+        load_credit_line(&env, &borrower)
+    }
+
+    /// Get credit line data for each of `borrowers`, in the same order; `None` for a
+    /// borrower without a line (view function).
+    pub fn get_credit_lines(env: Env, borrowers: Vec<Address>) -> Vec<Option<CreditLineData>> {
+        let mut lines = Vec::new(&env);
+        for borrower in borrowers.iter() {
+            lines.push_back(load_credit_line(&env, &borrower));
+        }
+        lines
+    }
+
+    /// Outstanding debt across lines in the default token, as the supply of the debt
+    /// token (view function). Interest is counted as of each line's last accrual.
+    pub fn total_supply(env: Env) -> i128 {
+        debt_token::total_supply(&env)
+    }
+}
+
+/// SEP-41 view of outstanding debt. Balances are read-only: every state-changing
+/// token function reverts with `"debt token is non-transferable"`.
+#[contractimpl]
+impl TokenInterface for Credit {
+    /// Always 0: the debt token cannot be approved.
+    fn allowance(_env: Env, _from: Address, _spender: Address) -> i128 {
+        0
+    }
+
+    fn approve(
+        _env: Env,
+        _from: Address,
+        _spender: Address,
+        _amount: i128,
+        _expiration_ledger: u32,
+    ) {
+        debt_token::reject_transfer()
+    }
+
+    /// Outstanding principal plus interest accrued up to now on `id`'s line; 0 without one
+    /// or for a line in another token than the default.
+    fn balance(env: Env, id: Address) -> i128 {
+        load_credit_line(&env, &id).map_or(0, |line| debt_token::balance(&env, &line))
+    }
+
+    fn transfer(_env: Env, _from: Address, _to: Address, _amount: i128) {
+        debt_token::reject_transfer()
+    }
+
+    fn transfer_from(_env: Env, _spender: Address, _from: Address, _to: Address, _amount: i128) {
+        debt_token::reject_transfer()
+    }
+
+    fn burn(_env: Env, _from: Address, _amount: i128) {
+        debt_token::reject_transfer()
+    }
+
+    fn burn_from(_env: Env, _spender: Address, _from: Address, _amount: i128) {
+        debt_token::reject_transfer()
+    }
+
+    /// Decimals of the default token.
+    fn decimals(env: Env) -> u32 {
+        debt_token::decimals(&env)
+    }
+
+    fn name(env: Env) -> String {
+        debt_token::name(&env)
+    }
+
+    fn symbol(env: Env) -> String {
+        debt_token::symbol(&env)
     }
 }
 
@@ -421,953 +4926,9744 @@ impl Credit {
 #[cfg(test)]
 mod test {
     use super::*;
+    use default_interest::DefaultAccrual;
     use soroban_sdk::testutils::Address as _;
-    use soroban_sdk::token;
+    use soroban_sdk::{token, Symbol};
+
+    // ── helpers ───────────────────────────────────────────────────────────────
+
+    fn setup_token<'a>(
+        env: &'a Env,
+        contract_id: &'a Address,
+        reserve_amount: i128,
+    ) -> (Address, token::StellarAssetClient<'a>) {
+        let token_admin = Address::generate(env);
+        let token_id = env.register_stellar_asset_contract_v2(token_admin);
+        let token_address = token_id.address();
+        let sac = token::StellarAssetClient::new(env, &token_address);
+        if reserve_amount > 0 {
+            sac.mint(contract_id, &reserve_amount);
+        }
+        (token_address, sac)
+    }
+
+    fn setup_contract_with_credit_line<'a>(
+        env: &'a Env,
+        borrower: &'a Address,
+        credit_limit: i128,
+        reserve_amount: i128,
+    ) -> (CreditClient<'a>, Address, Address) {
+        let admin = Address::generate(env);
+        let contract_id = env.register(Credit, ());
+        let (token_address, _sac) = setup_token(env, &contract_id, reserve_amount);
+        let client = CreditClient::new(env, &contract_id);
+        client.init(&admin, &token_address);
+        client.open_credit_line(
+            &admin,
+            borrower,
+            &credit_limit,
+            &300_u32,
+            &70_u32,
+            &None,
+            &RateMode::Fixed,
+            &None,
+            &None,
+        );
+        (client, token_address, admin)
+    }
+
+    // ── draw_credit: token transfer (#39) ─────────────────────────────────────
+
+    #[test]
+    fn test_draw_transfers_correct_amount_to_borrower() {
+        let env = Env::default();
+        env.mock_all_auths();
+        let borrower = Address::generate(&env);
+        let (client, token_address, _admin) =
+            setup_contract_with_credit_line(&env, &borrower, 1_000, 1_000);
+        let token_client = token::Client::new(&env, &token_address);
+        let before = token_client.balance(&borrower);
+        client.draw_credit(&borrower, &500, &None);
+        assert_eq!(token_client.balance(&borrower) - before, 500);
+    }
+
+    #[test]
+    fn test_draw_reduces_contract_reserve() {
+        let env = Env::default();
+        env.mock_all_auths();
+        let borrower = Address::generate(&env);
+        let admin = Address::generate(&env);
+        let contract_id = env.register(Credit, ());
+        let (token_address, _sac) = setup_token(&env, &contract_id, 1_000);
+        let client = CreditClient::new(&env, &contract_id);
+        client.init(&admin, &token_address);
+        client.open_credit_line(
+            &admin,
+            &borrower,
+            &1_000,
+            &300_u32,
+            &70_u32,
+            &None,
+            &RateMode::Fixed,
+            &None,
+            &None,
+        );
+        let token_client = token::Client::new(&env, &token_address);
+        let reserve_before = token_client.balance(&contract_id);
+        client.draw_credit(&borrower, &300, &None);
+        assert_eq!(reserve_before - token_client.balance(&contract_id), 300);
+    }
+
+    #[test]
+    fn test_draw_updates_utilized_amount() {
+        let env = Env::default();
+        env.mock_all_auths();
+        let borrower = Address::generate(&env);
+        let (client, _token, _admin) =
+            setup_contract_with_credit_line(&env, &borrower, 1_000, 1_000);
+        client.draw_credit(&borrower, &400, &None);
+        assert_eq!(
+            client.get_credit_line(&borrower).unwrap().utilized_amount,
+            400
+        );
+    }
+
+    #[test]
+    fn test_draw_accumulates_across_multiple_draws() {
+        let env = Env::default();
+        env.mock_all_auths();
+        let borrower = Address::generate(&env);
+        let (client, token_address, _admin) =
+            setup_contract_with_credit_line(&env, &borrower, 1_000, 1_000);
+        client.draw_credit(&borrower, &200, &None);
+        client.draw_credit(&borrower, &300, &None);
+        let token_client = token::Client::new(&env, &token_address);
+        assert_eq!(token_client.balance(&borrower), 500);
+        assert_eq!(
+            client.get_credit_line(&borrower).unwrap().utilized_amount,
+            500
+        );
+    }
+
+    #[test]
+    fn test_draw_exact_credit_limit() {
+        let env = Env::default();
+        env.mock_all_auths();
+        let borrower = Address::generate(&env);
+        let (client, token_address, _admin) =
+            setup_contract_with_credit_line(&env, &borrower, 1_000, 1_000);
+        client.draw_credit(&borrower, &1_000, &None);
+        let token_client = token::Client::new(&env, &token_address);
+        assert_eq!(token_client.balance(&borrower), 1_000);
+        assert_eq!(
+            client.get_credit_line(&borrower).unwrap().utilized_amount,
+            1_000
+        );
+    }
+
+    #[test]
+    fn test_draw_requires_borrower_auth() {
+        let env = Env::default();
+        env.mock_all_auths();
+        let borrower = Address::generate(&env);
+        let (client, _token, _admin) =
+            setup_contract_with_credit_line(&env, &borrower, 1_000, 1_000);
+        client.draw_credit(&borrower, &100, &None);
+        assert!(
+            env.auths().iter().any(|(addr, _)| *addr == borrower),
+            "draw_credit must require borrower authorization"
+        );
+    }
+
+    #[test]
+    fn test_multiple_borrowers_draw_independently() {
+        let env = Env::default();
+        env.mock_all_auths();
+        let admin = Address::generate(&env);
+        let b1 = Address::generate(&env);
+        let b2 = Address::generate(&env);
+        let contract_id = env.register(Credit, ());
+        let (token_address, _sac) = setup_token(&env, &contract_id, 3_000);
+        let client = CreditClient::new(&env, &contract_id);
+        client.init(&admin, &token_address);
+        client.open_credit_line(
+            &admin,
+            &b1,
+            &1_000,
+            &300_u32,
+            &70_u32,
+            &None,
+            &RateMode::Fixed,
+            &None,
+            &None,
+        );
+        client.open_credit_line(
+            &admin,
+            &b2,
+            &2_000,
+            &400_u32,
+            &80_u32,
+            &None,
+            &RateMode::Fixed,
+            &None,
+            &None,
+        );
+        client.draw_credit(&b1, &500, &None);
+        client.draw_credit(&b2, &1_000, &None);
+        let token_client = token::Client::new(&env, &token_address);
+        assert_eq!(token_client.balance(&b1), 500);
+        assert_eq!(token_client.balance(&b2), 1_000);
+        assert_eq!(client.get_credit_line(&b1).unwrap().utilized_amount, 500);
+        assert_eq!(client.get_credit_line(&b2).unwrap().utilized_amount, 1_000);
+    }
+
+    // ── draw_credit: guards ───────────────────────────────────────────────────
+
+    #[test]
+    #[should_panic(expected = "exceeds credit limit")]
+    fn test_draw_exceeds_credit_limit() {
+        let env = Env::default();
+        env.mock_all_auths();
+        let borrower = Address::generate(&env);
+        let (client, _token, _admin) = setup_contract_with_credit_line(&env, &borrower, 500, 1_000);
+        client.draw_credit(&borrower, &600, &None);
+    }
+
+    #[test]
+    #[should_panic(expected = "exceeds credit limit")]
+    fn test_draw_cumulative_exceeds_limit() {
+        let env = Env::default();
+        env.mock_all_auths();
+        let borrower = Address::generate(&env);
+        let (client, _token, _admin) = setup_contract_with_credit_line(&env, &borrower, 500, 1_000);
+        client.draw_credit(&borrower, &400, &None);
+        client.draw_credit(&borrower, &200, &None);
+    }
+
+    #[test]
+    #[should_panic(expected = "Credit line not active")]
+    fn test_draw_on_suspended_line_fails() {
+        let env = Env::default();
+        env.mock_all_auths();
+        let borrower = Address::generate(&env);
+        let (client, _token, _admin) =
+            setup_contract_with_credit_line(&env, &borrower, 1_000, 1_000);
+        client.suspend_credit_line(&borrower, &0, &None);
+        client.draw_credit(&borrower, &100, &None);
+    }
+
+    #[test]
+    #[should_panic(expected = "credit line is closed")]
+    fn test_draw_on_closed_line_fails() {
+        let env = Env::default();
+        env.mock_all_auths();
+        let borrower = Address::generate(&env);
+        let (client, _token, admin) =
+            setup_contract_with_credit_line(&env, &borrower, 1_000, 1_000);
+        client.close_credit_line(&borrower, &admin);
+        client.draw_credit(&borrower, &100, &None);
+    }
+
+    #[test]
+    #[should_panic(expected = "Credit line not active")]
+    fn test_draw_on_defaulted_line_fails() {
+        let env = Env::default();
+        env.mock_all_auths();
+        let borrower = Address::generate(&env);
+        let (client, _token, _admin) =
+            setup_contract_with_credit_line(&env, &borrower, 1_000, 1_000);
+        client.default_credit_line(&borrower);
+        client.draw_credit(&borrower, &100, &None);
+    }
+
+    #[test]
+    #[should_panic(expected = "amount must be positive")]
+    fn test_draw_zero_amount_fails() {
+        let env = Env::default();
+        env.mock_all_auths();
+        let borrower = Address::generate(&env);
+        let (client, _token, _admin) =
+            setup_contract_with_credit_line(&env, &borrower, 1_000, 1_000);
+        client.draw_credit(&borrower, &0, &None);
+    }
+
+    #[test]
+    #[should_panic(expected = "amount must be positive")]
+    fn test_draw_negative_amount_fails() {
+        let env = Env::default();
+        env.mock_all_auths();
+        let borrower = Address::generate(&env);
+        let (client, _token, _admin) =
+            setup_contract_with_credit_line(&env, &borrower, 1_000, 1_000);
+        client.draw_credit(&borrower, &-50, &None);
+    }
+
+    #[test]
+    #[should_panic(expected = "Credit line not found")]
+    fn test_draw_no_credit_line_fails() {
+        let env = Env::default();
+        env.mock_all_auths();
+        let stranger = Address::generate(&env);
+        let admin = Address::generate(&env);
+        let contract_id = env.register(Credit, ());
+        let (token_address, _sac) = setup_token(&env, &contract_id, 1_000);
+        let client = CreditClient::new(&env, &contract_id);
+        client.init(&admin, &token_address);
+        client.draw_credit(&stranger, &100, &None);
+    }
+
+    // ── open_credit_line validation ───────────────────────────────────────────
+
+    #[test]
+    #[should_panic(expected = "borrower already has an active credit line")]
+    fn test_open_credit_line_duplicate_active_borrower_reverts() {
+        let env = Env::default();
+        env.mock_all_auths();
+        let borrower = Address::generate(&env);
+        let (client, _token, admin) = setup_contract_with_credit_line(&env, &borrower, 1_000, 0);
+        client.open_credit_line(
+            &admin,
+            &borrower,
+            &2_000,
+            &400_u32,
+            &60_u32,
+            &None,
+            &RateMode::Fixed,
+            &None,
+            &None,
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "credit_limit must be greater than zero")]
+    fn test_open_credit_line_zero_limit_reverts() {
+        let env = Env::default();
+        env.mock_all_auths();
+        let admin = Address::generate(&env);
+        let borrower = Address::generate(&env);
+        let contract_id = env.register(Credit, ());
+        let (token_address, _) = setup_token(&env, &contract_id, 0);
+        let client = CreditClient::new(&env, &contract_id);
+        client.init(&admin, &token_address);
+        client.open_credit_line(
+            &admin,
+            &borrower,
+            &0,
+            &300_u32,
+            &70_u32,
+            &None,
+            &RateMode::Fixed,
+            &None,
+            &None,
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "credit_limit must be greater than zero")]
+    fn test_open_credit_line_negative_limit_reverts() {
+        let env = Env::default();
+        env.mock_all_auths();
+        let admin = Address::generate(&env);
+        let borrower = Address::generate(&env);
+        let contract_id = env.register(Credit, ());
+        let (token_address, _) = setup_token(&env, &contract_id, 0);
+        let client = CreditClient::new(&env, &contract_id);
+        client.init(&admin, &token_address);
+        client.open_credit_line(
+            &admin,
+            &borrower,
+            &-1,
+            &300_u32,
+            &70_u32,
+            &None,
+            &RateMode::Fixed,
+            &None,
+            &None,
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "interest_rate_bps cannot exceed 10000 (100%)")]
+    fn test_open_credit_line_interest_rate_exceeds_max_reverts() {
+        let env = Env::default();
+        env.mock_all_auths();
+        let admin = Address::generate(&env);
+        let borrower = Address::generate(&env);
+        let contract_id = env.register(Credit, ());
+        let (token_address, _) = setup_token(&env, &contract_id, 0);
+        let client = CreditClient::new(&env, &contract_id);
+        client.init(&admin, &token_address);
+        client.open_credit_line(
+            &admin,
+            &borrower,
+            &1_000,
+            &10_001_u32,
+            &70_u32,
+            &None,
+            &RateMode::Fixed,
+            &None,
+            &None,
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "risk_score must be between 0 and 100")]
+    fn test_open_credit_line_risk_score_exceeds_max_reverts() {
+        let env = Env::default();
+        env.mock_all_auths();
+        let admin = Address::generate(&env);
+        let borrower = Address::generate(&env);
+        let contract_id = env.register(Credit, ());
+        let (token_address, _) = setup_token(&env, &contract_id, 0);
+        let client = CreditClient::new(&env, &contract_id);
+        client.init(&admin, &token_address);
+        client.open_credit_line(
+            &admin,
+            &borrower,
+            &1_000,
+            &300_u32,
+            &101_u32,
+            &None,
+            &RateMode::Fixed,
+            &None,
+            &None,
+        );
+    }
+
+    // ── lifecycle ─────────────────────────────────────────────────────────────
+
+    #[test]
+    fn test_init_and_open_credit_line() {
+        let env = Env::default();
+        env.mock_all_auths();
+        let borrower = Address::generate(&env);
+        let (client, _token, _admin) = setup_contract_with_credit_line(&env, &borrower, 1_000, 0);
+        let line = client.get_credit_line(&borrower).unwrap();
+        assert_eq!(line.borrower, borrower);
+        assert_eq!(line.credit_limit, 1_000);
+        assert_eq!(line.utilized_amount, 0);
+        assert_eq!(line.interest_rate_bps, 300);
+        assert_eq!(line.risk_score, 70);
+        assert_eq!(line.status, CreditStatus::Active);
+    }
+
+    #[test]
+    fn test_suspend_credit_line() {
+        let env = Env::default();
+        env.mock_all_auths();
+        let borrower = Address::generate(&env);
+        let (client, _token, _admin) = setup_contract_with_credit_line(&env, &borrower, 1_000, 0);
+        client.suspend_credit_line(&borrower, &0, &None);
+        assert_eq!(
+            client.get_credit_line(&borrower).unwrap().status,
+            CreditStatus::Suspended
+        );
+    }
+
+    #[test]
+    fn test_suspend_records_reason_and_expiry() {
+        use soroban_sdk::testutils::Events;
+        use soroban_sdk::TryIntoVal;
+        let env = Env::default();
+        env.mock_all_auths();
+        let borrower = Address::generate(&env);
+        let (client, _token, _admin) = setup_contract_with_credit_line(&env, &borrower, 1_000, 0);
+        client.suspend_credit_line(&borrower, &suspension::REASON_FRAUD_REVIEW, &Some(500));
+        let event: CreditLineEvent = env
+            .events()
+            .all()
+            .last()
+            .unwrap()
+            .2
+            .try_into_val(&env)
+            .unwrap();
+        assert_eq!(event.suspension_reason, suspension::REASON_FRAUD_REVIEW);
+        assert_eq!(event.suspended_until, 500);
+        let line = client.get_credit_line(&borrower).unwrap();
+        assert_eq!(line.suspension_reason, suspension::REASON_FRAUD_REVIEW);
+        assert_eq!(line.suspended_until, 500);
+
+        client.resume_credit_line(&borrower);
+        let line = client.get_credit_line(&borrower).unwrap();
+        assert_eq!(line.suspension_reason, 0);
+        assert_eq!(line.suspended_until, 0);
+    }
+
+    #[test]
+    #[should_panic(expected = "invalid suspension reason")]
+    fn test_suspend_unknown_reason_reverts() {
+        let env = Env::default();
+        env.mock_all_auths();
+        let borrower = Address::generate(&env);
+        let (client, _token, _admin) = setup_contract_with_credit_line(&env, &borrower, 1_000, 0);
+        client.suspend_credit_line(&borrower, &99, &None);
+    }
+
+    #[test]
+    #[should_panic(expected = "suspension expiry must be in the future")]
+    fn test_suspend_past_expiry_reverts() {
+        use soroban_sdk::testutils::Ledger;
+        let env = Env::default();
+        env.mock_all_auths();
+        env.ledger().with_mut(|li| li.timestamp = 1_000);
+        let borrower = Address::generate(&env);
+        let (client, _token, _admin) = setup_contract_with_credit_line(&env, &borrower, 1_000, 0);
+        client.suspend_credit_line(&borrower, &1, &Some(1_000));
+    }
+
+    #[test]
+    #[should_panic(expected = "Credit line not active")]
+    fn test_draw_before_suspension_expiry_reverts() {
+        use soroban_sdk::testutils::Ledger;
+        let env = Env::default();
+        env.mock_all_auths();
+        let borrower = Address::generate(&env);
+        let (client, _token, _admin) =
+            setup_contract_with_credit_line(&env, &borrower, 1_000, 1_000);
+        client.suspend_credit_line(&borrower, &1, &Some(500));
+        env.ledger().with_mut(|li| li.timestamp = 499);
+        client.draw_credit(&borrower, &100, &None);
+    }
+
+    #[test]
+    fn test_draw_after_suspension_expiry_resumes_line() {
+        use soroban_sdk::testutils::{Events, Ledger};
+        use soroban_sdk::{TryFromVal, TryIntoVal};
+        let env = Env::default();
+        env.mock_all_auths();
+        let borrower = Address::generate(&env);
+        let (client, _token, _admin) =
+            setup_contract_with_credit_line(&env, &borrower, 1_000, 1_000);
+        client.suspend_credit_line(&borrower, &1, &Some(500));
+        env.ledger().with_mut(|li| li.timestamp = 500);
+        client.draw_credit(&borrower, &100, &None);
+
+        let resumed = env.events().all().iter().any(|(_, topics, data)| {
+            Symbol::try_from_val(&env, &topics.get(1).unwrap()).unwrap() == symbol_short!("resume")
+                && TryIntoVal::<Env, CreditLineEvent>::try_into_val(&data, &env)
+                    .unwrap()
+                    .status
+                    == CreditStatus::Active
+        });
+        assert!(resumed);
+        let line = client.get_credit_line(&borrower).unwrap();
+        assert_eq!(line.status, CreditStatus::Active);
+        assert_eq!(line.utilized_amount, 100);
+        assert_eq!(line.suspended_until, 0);
+    }
+
+    #[test]
+    #[should_panic(expected = "draws frozen")]
+    fn test_draws_frozen_blocks_draw() {
+        let env = Env::default();
+        env.mock_all_auths();
+        let borrower = Address::generate(&env);
+        let (client, _token, _admin) =
+            setup_contract_with_credit_line(&env, &borrower, 1_000, 1_000);
+        client.set_line_restrictions(&borrower, &restrictions::DRAWS_FROZEN);
+        client.draw_credit(&borrower, &100, &None);
+    }
+
+    #[test]
+    fn test_draws_frozen_still_allows_repay_and_updates() {
+        let env = Env::default();
+        env.mock_all_auths();
+        let borrower = Address::generate(&env);
+        let (client, _token, admin) =
+            setup_contract_with_credit_line(&env, &borrower, 1_000, 1_000);
+        client.draw_credit(&borrower, &300, &None);
+        client.set_line_restrictions(&borrower, &restrictions::DRAWS_FROZEN);
+        client.repay_credit(&borrower, &100);
+        client.update_risk_parameters(&admin, &borrower, &800, &300, &60, &None);
+
+        let line = client.get_credit_line(&borrower).unwrap();
+        assert_eq!(line.status, CreditStatus::Active);
+        assert_eq!(line.utilized_amount, 200);
+        assert_eq!(line.credit_limit, 800);
+        assert_eq!(line.restrictions, restrictions::DRAWS_FROZEN);
+    }
+
+    #[test]
+    #[should_panic(expected = "repayments frozen")]
+    fn test_repays_frozen_blocks_repay() {
+        let env = Env::default();
+        env.mock_all_auths();
+        let borrower = Address::generate(&env);
+        let (client, _token, _admin) =
+            setup_contract_with_credit_line(&env, &borrower, 1_000, 1_000);
+        client.draw_credit(&borrower, &300, &None);
+        client.set_line_restrictions(&borrower, &restrictions::REPAYS_FROZEN);
+        client.repay_credit(&borrower, &100);
+    }
+
+    #[test]
+    #[should_panic(expected = "updates frozen")]
+    fn test_updates_frozen_blocks_risk_update() {
+        let env = Env::default();
+        env.mock_all_auths();
+        let borrower = Address::generate(&env);
+        let (client, _token, admin) = setup_contract_with_credit_line(&env, &borrower, 1_000, 0);
+        client.set_line_restrictions(&borrower, &restrictions::UPDATES_FROZEN);
+        client.update_risk_parameters(&admin, &borrower, &2_000, &300, &70, &None);
+    }
+
+    #[test]
+    fn test_clearing_restrictions_reenables_draws() {
+        let env = Env::default();
+        env.mock_all_auths();
+        let borrower = Address::generate(&env);
+        let (client, _token, _admin) =
+            setup_contract_with_credit_line(&env, &borrower, 1_000, 1_000);
+        client.set_line_restrictions(
+            &borrower,
+            &(restrictions::DRAWS_FROZEN | restrictions::UPDATES_FROZEN),
+        );
+        client.set_line_restrictions(&borrower, &0);
+        client.draw_credit(&borrower, &100, &None);
+        assert_eq!(
+            client.get_credit_line(&borrower).unwrap().utilized_amount,
+            100
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "invalid restrictions")]
+    fn test_unknown_restriction_bits_revert() {
+        let env = Env::default();
+        env.mock_all_auths();
+        let borrower = Address::generate(&env);
+        let (client, _token, _admin) = setup_contract_with_credit_line(&env, &borrower, 1_000, 0);
+        client.set_line_restrictions(&borrower, &8);
+    }
+
+    // ── disputes ──────────────────────────────────────────────────────────────
+
+    #[test]
+    fn test_disputed_line_keeps_repaying_until_resolved() {
+        use soroban_sdk::testutils::Events;
+        use soroban_sdk::TryIntoVal;
+        let env = Env::default();
+        env.mock_all_auths();
+        let borrower = Address::generate(&env);
+        let (client, _token, admin) =
+            setup_contract_with_credit_line(&env, &borrower, 1_000, 1_000);
+        client.draw_credit(&borrower, &300, &None);
+        client.flag_dispute(&borrower, &42);
+
+        let event = env
+            .events()
+            .all()
+            .iter()
+            .find_map(|e| TryIntoVal::<Env, DisputeEvent>::try_into_val(&e.2, &env).ok())
+            .unwrap();
+        assert_eq!(event.dispute_id, 42);
+
+        let line = client.get_credit_line(&borrower).unwrap();
+        assert_eq!(line.restrictions, restrictions::DISPUTED);
+        assert_eq!(client.get_dispute(&borrower).unwrap().dispute_id, 42);
+
+        client.repay_credit(&borrower, &100);
+        client.update_risk_parameters(&admin, &borrower, &1_500, &300, &70, &None);
+        client.set_line_restrictions(&borrower, &restrictions::DRAWS_FROZEN);
+        assert_eq!(
+            client.get_credit_line(&borrower).unwrap().restrictions,
+            restrictions::DRAWS_FROZEN | restrictions::DISPUTED
+        );
+
+        client.resolve_dispute(&borrower);
+        assert_eq!(client.get_dispute(&borrower), None);
+        assert_eq!(
+            client.get_credit_line(&borrower).unwrap().restrictions,
+            restrictions::DRAWS_FROZEN
+        );
+        client.default_credit_line(&borrower);
+    }
+
+    #[test]
+    #[should_panic(expected = "credit line is disputed")]
+    fn test_disputed_line_cannot_be_defaulted() {
+        let env = Env::default();
+        env.mock_all_auths();
+        let borrower = Address::generate(&env);
+        let (client, _token, _admin) = setup_contract_with_credit_line(&env, &borrower, 1_000, 0);
+        client.flag_dispute(&borrower, &1);
+        client.default_credit_line(&borrower);
+    }
+
+    #[test]
+    #[should_panic(expected = "credit line is disputed")]
+    fn test_disputed_line_limit_cannot_be_lowered() {
+        let env = Env::default();
+        env.mock_all_auths();
+        let borrower = Address::generate(&env);
+        let (client, _token, admin) = setup_contract_with_credit_line(&env, &borrower, 1_000, 0);
+        client.flag_dispute(&borrower, &1);
+        client.update_risk_parameters(&admin, &borrower, &800, &300, &70, &None);
+    }
+
+    #[test]
+    #[should_panic(expected = "credit line not disputed")]
+    fn test_resolve_undisputed_line_reverts() {
+        let env = Env::default();
+        env.mock_all_auths();
+        let borrower = Address::generate(&env);
+        let (client, _token, _admin) = setup_contract_with_credit_line(&env, &borrower, 1_000, 0);
+        client.resolve_dispute(&borrower);
+    }
+
+    #[test]
+    fn test_close_credit_line() {
+        let env = Env::default();
+        env.mock_all_auths();
+        let borrower = Address::generate(&env);
+        let (client, _token, admin) = setup_contract_with_credit_line(&env, &borrower, 1_000, 0);
+        client.close_credit_line(&borrower, &admin);
+        assert_eq!(
+            client.get_credit_line(&borrower).unwrap().status,
+            CreditStatus::Closed
+        );
+    }
+
+    #[test]
+    fn test_default_credit_line() {
+        let env = Env::default();
+        env.mock_all_auths();
+        let borrower = Address::generate(&env);
+        let (client, _token, _admin) = setup_contract_with_credit_line(&env, &borrower, 1_000, 0);
+        client.default_credit_line(&borrower);
+        assert_eq!(
+            client.get_credit_line(&borrower).unwrap().status,
+            CreditStatus::Defaulted
+        );
+    }
+
+    #[test]
+    fn test_full_lifecycle() {
+        let env = Env::default();
+        env.mock_all_auths();
+        let borrower = Address::generate(&env);
+        let (client, _token, admin) =
+            setup_contract_with_credit_line(&env, &borrower, 5_000, 5_000);
+        assert_eq!(
+            client.get_credit_line(&borrower).unwrap().status,
+            CreditStatus::Active
+        );
+        client.suspend_credit_line(&borrower, &0, &None);
+        assert_eq!(
+            client.get_credit_line(&borrower).unwrap().status,
+            CreditStatus::Suspended
+        );
+        client.close_credit_line(&borrower, &admin);
+        assert_eq!(
+            client.get_credit_line(&borrower).unwrap().status,
+            CreditStatus::Closed
+        );
+    }
+
+    #[test]
+    fn test_close_credit_line_borrower_when_utilized_zero() {
+        let env = Env::default();
+        env.mock_all_auths();
+        let borrower = Address::generate(&env);
+        let (client, _token, _admin) = setup_contract_with_credit_line(&env, &borrower, 1_000, 0);
+        client.close_credit_line(&borrower, &borrower);
+        let line = client.get_credit_line(&borrower).unwrap();
+        assert_eq!(line.status, CreditStatus::Closed);
+        assert_eq!(line.utilized_amount, 0);
+    }
+
+    #[test]
+    #[should_panic(expected = "cannot close: utilized amount not zero")]
+    fn test_close_credit_line_borrower_rejected_when_utilized_nonzero() {
+        let env = Env::default();
+        env.mock_all_auths();
+        let borrower = Address::generate(&env);
+        let (client, _token, _admin) =
+            setup_contract_with_credit_line(&env, &borrower, 1_000, 1_000);
+        client.draw_credit(&borrower, &300, &None);
+        client.close_credit_line(&borrower, &borrower);
+    }
+
+    #[test]
+    fn test_close_credit_line_admin_force_close_with_utilization() {
+        let env = Env::default();
+        env.mock_all_auths();
+        let borrower = Address::generate(&env);
+        let (client, _token, admin) =
+            setup_contract_with_credit_line(&env, &borrower, 1_000, 1_000);
+        client.draw_credit(&borrower, &300, &None);
+        client.close_credit_line(&borrower, &admin);
+        let line = client.get_credit_line(&borrower).unwrap();
+        assert_eq!(line.status, CreditStatus::Closed);
+        assert_eq!(line.utilized_amount, 300);
+    }
+
+    #[test]
+    fn test_close_credit_line_idempotent_when_already_closed() {
+        let env = Env::default();
+        env.mock_all_auths();
+        let borrower = Address::generate(&env);
+        let (client, _token, admin) = setup_contract_with_credit_line(&env, &borrower, 1_000, 0);
+        client.close_credit_line(&borrower, &admin);
+        client.close_credit_line(&borrower, &admin);
+        assert_eq!(
+            client.get_credit_line(&borrower).unwrap().status,
+            CreditStatus::Closed
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "unauthorized")]
+    fn test_close_credit_line_unauthorized_closer() {
+        let env = Env::default();
+        env.mock_all_auths();
+        let borrower = Address::generate(&env);
+        let other = Address::generate(&env);
+        let (client, _token, _admin) = setup_contract_with_credit_line(&env, &borrower, 1_000, 0);
+        client.close_credit_line(&borrower, &other);
+    }
+
+    #[test]
+    #[should_panic(expected = "Credit line not found")]
+    fn test_suspend_nonexistent_credit_line() {
+        let env = Env::default();
+        env.mock_all_auths();
+        let borrower = Address::generate(&env);
+        let admin = Address::generate(&env);
+        let contract_id = env.register(Credit, ());
+        let (token_address, _) = setup_token(&env, &contract_id, 0);
+        let client = CreditClient::new(&env, &contract_id);
+        client.init(&admin, &token_address);
+        client.suspend_credit_line(&borrower, &0, &None);
+    }
+
+    #[test]
+    #[should_panic(expected = "Credit line not found")]
+    fn test_close_nonexistent_credit_line() {
+        let env = Env::default();
+        env.mock_all_auths();
+        let borrower = Address::generate(&env);
+        let admin = Address::generate(&env);
+        let contract_id = env.register(Credit, ());
+        let (token_address, _) = setup_token(&env, &contract_id, 0);
+        let client = CreditClient::new(&env, &contract_id);
+        client.init(&admin, &token_address);
+        client.close_credit_line(&borrower, &admin);
+    }
+
+    #[test]
+    #[should_panic(expected = "Credit line not found")]
+    fn test_default_nonexistent_credit_line() {
+        let env = Env::default();
+        env.mock_all_auths();
+        let borrower = Address::generate(&env);
+        let admin = Address::generate(&env);
+        let contract_id = env.register(Credit, ());
+        let (token_address, _) = setup_token(&env, &contract_id, 0);
+        let client = CreditClient::new(&env, &contract_id);
+        client.init(&admin, &token_address);
+        client.default_credit_line(&borrower);
+    }
+
+    // ── update_risk_parameters ────────────────────────────────────────────────
+
+    #[test]
+    fn test_update_risk_parameters_success() {
+        let env = Env::default();
+        env.mock_all_auths();
+        let borrower = Address::generate(&env);
+        let (client, _token, admin) = setup_contract_with_credit_line(&env, &borrower, 1_000, 0);
+        client.update_risk_parameters(&admin, &borrower, &2_000, &400_u32, &85_u32, &None);
+        let line = client.get_credit_line(&borrower).unwrap();
+        assert_eq!(line.credit_limit, 2_000);
+        assert_eq!(line.interest_rate_bps, 400);
+        assert_eq!(line.risk_score, 85);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_update_risk_parameters_unauthorized_caller() {
+        let env = Env::default();
+        let admin = Address::generate(&env);
+        let borrower = Address::generate(&env);
+        let token = Address::generate(&env);
+        let contract_id = env.register(Credit, ());
+        let client = CreditClient::new(&env, &contract_id);
+
+        client.init(&admin, &token);
+        client.open_credit_line(
+            &admin,
+            &borrower,
+            &1_000,
+            &300_u32,
+            &70_u32,
+            &None,
+            &RateMode::Fixed,
+            &None,
+            &None,
+        );
+        client.update_risk_parameters(&admin, &borrower, &2_000, &400_u32, &85_u32, &None);
+    }
+
+    #[test]
+    #[should_panic(expected = "Credit line not found")]
+    fn test_update_risk_parameters_nonexistent_line() {
+        let env = Env::default();
+        env.mock_all_auths();
+        let borrower = Address::generate(&env);
+        let admin = Address::generate(&env);
+        let contract_id = env.register(Credit, ());
+        let (token_address, _) = setup_token(&env, &contract_id, 0);
+        let client = CreditClient::new(&env, &contract_id);
+        client.init(&admin, &token_address);
+        client.update_risk_parameters(&admin, &borrower, &1_000, &300_u32, &70_u32, &None);
+    }
+
+    #[test]
+    #[should_panic(expected = "credit_limit cannot be less than utilized amount")]
+    fn test_update_risk_parameters_credit_limit_below_utilized() {
+        let env = Env::default();
+        env.mock_all_auths();
+        let borrower = Address::generate(&env);
+        let (client, _token, admin) =
+            setup_contract_with_credit_line(&env, &borrower, 1_000, 1_000);
+        client.draw_credit(&borrower, &500, &None);
+        client.update_risk_parameters(&admin, &borrower, &300, &300_u32, &70_u32, &None);
+    }
+
+    #[test]
+    #[should_panic(expected = "credit_limit must be non-negative")]
+    fn test_update_risk_parameters_negative_credit_limit() {
+        let env = Env::default();
+        env.mock_all_auths();
+        let borrower = Address::generate(&env);
+        let (client, _token, admin) = setup_contract_with_credit_line(&env, &borrower, 1_000, 0);
+        client.update_risk_parameters(&admin, &borrower, &-1, &300_u32, &70_u32, &None);
+    }
+
+    #[test]
+    #[should_panic(expected = "interest_rate_bps exceeds maximum")]
+    fn test_update_risk_parameters_interest_rate_exceeds_max() {
+        let env = Env::default();
+        env.mock_all_auths();
+        let borrower = Address::generate(&env);
+        let (client, _token, admin) = setup_contract_with_credit_line(&env, &borrower, 1_000, 0);
+        client.update_risk_parameters(&admin, &borrower, &1_000, &10_001_u32, &70_u32, &None);
+    }
+
+    #[test]
+    #[should_panic(expected = "risk_score exceeds maximum")]
+    fn test_update_risk_parameters_risk_score_exceeds_max() {
+        let env = Env::default();
+        env.mock_all_auths();
+        let borrower = Address::generate(&env);
+        let (client, _token, admin) = setup_contract_with_credit_line(&env, &borrower, 1_000, 0);
+        client.update_risk_parameters(&admin, &borrower, &1_000, &300_u32, &101_u32, &None);
+    }
+
+    #[test]
+    fn test_update_risk_parameters_at_boundaries() {
+        let env = Env::default();
+        env.mock_all_auths();
+        let borrower = Address::generate(&env);
+        let (client, _token, admin) = setup_contract_with_credit_line(&env, &borrower, 1_000, 0);
+        client.update_risk_parameters(&admin, &borrower, &1_000, &10_000_u32, &100_u32, &None);
+        let line = client.get_credit_line(&borrower).unwrap();
+        assert_eq!(line.interest_rate_bps, 10_000);
+        assert_eq!(line.risk_score, 100);
+    }
+
+    // ── repay_credit ──────────────────────────────────────────────────────────
+
+    #[test]
+    fn test_repay_credit_reduces_utilized_amount() {
+        let env = Env::default();
+        env.mock_all_auths();
+        let borrower = Address::generate(&env);
+        let (client, _token, _admin) =
+            setup_contract_with_credit_line(&env, &borrower, 1_000, 1_000);
+        client.draw_credit(&borrower, &500, &None);
+        client.repay_credit(&borrower, &200);
+        assert_eq!(
+            client.get_credit_line(&borrower).unwrap().utilized_amount,
+            300
+        );
+    }
+
+    #[test]
+    fn test_repay_credit_needs_no_allowance() {
+        let env = Env::default();
+        env.mock_all_auths();
+        let borrower = Address::generate(&env);
+        let (client, token_address, _admin) =
+            setup_contract_with_credit_line(&env, &borrower, 1_000, 1_000);
+        client.draw_credit(&borrower, &500, &None);
+        let token_client = token::Client::new(&env, &token_address);
+        assert_eq!(token_client.allowance(&borrower, &client.address), 0);
+        client.repay_credit(&borrower, &200);
+        assert_eq!(token_client.balance(&borrower), 300);
+        assert_eq!(token_client.balance(&client.address), 700);
+    }
+
+    #[test]
+    fn test_repay_credit_with_allowance_pulls_approved_funds() {
+        let env = Env::default();
+        env.mock_all_auths();
+        let borrower = Address::generate(&env);
+        let (client, token_address, _admin) =
+            setup_contract_with_credit_line(&env, &borrower, 1_000, 1_000);
+        client.draw_credit(&borrower, &500, &None);
+        let token_client = token::Client::new(&env, &token_address);
+        token_client.approve(&borrower, &client.address, &300, &1_000);
+
+        client.repay_credit_with_allowance(&borrower, &200);
+        assert_eq!(
+            client.get_credit_line(&borrower).unwrap().utilized_amount,
+            300
+        );
+        assert_eq!(token_client.allowance(&borrower, &client.address), 100);
+        assert_eq!(token_client.balance(&borrower), 300);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_repay_credit_with_allowance_without_approval_reverts() {
+        let env = Env::default();
+        env.mock_all_auths();
+        let borrower = Address::generate(&env);
+        let (client, _token, _admin) =
+            setup_contract_with_credit_line(&env, &borrower, 1_000, 1_000);
+        client.draw_credit(&borrower, &500, &None);
+        client.repay_credit_with_allowance(&borrower, &200);
+    }
+
+    #[test]
+    fn test_repay_credit_with_refund_returns_excess() {
+        use soroban_sdk::testutils::Events;
+        use soroban_sdk::TryIntoVal;
+        let env = Env::default();
+        env.mock_all_auths();
+        let borrower = Address::generate(&env);
+        let (client, token_address, _admin) =
+            setup_contract_with_credit_line(&env, &borrower, 1_000, 1_000);
+        client.draw_credit(&borrower, &500, &None);
+        token::StellarAssetClient::new(&env, &token_address).mint(&borrower, &100);
+
+        client.repay_credit_with_refund(&borrower, &550);
+        let event: RepaymentEvent = env
+            .events()
+            .all()
+            .last()
+            .unwrap()
+            .2
+            .try_into_val(&env)
+            .unwrap();
+        assert_eq!(event.amount, 500);
+        assert_eq!(event.refunded, 50);
+        assert_eq!(event.new_utilized_amount, 0);
+        assert_eq!(
+            token::Client::new(&env, &token_address).balance(&borrower),
+            100
+        );
+    }
+
+    #[test]
+    fn test_repayment_event_breaks_down_portions() {
+        use soroban_sdk::testutils::{Events, Ledger};
+        use soroban_sdk::TryIntoVal;
+        let env = Env::default();
+        env.mock_all_auths();
+        let borrower = Address::generate(&env);
+        let (client, _token, _admin) =
+            setup_contract_with_credit_line(&env, &borrower, 1_000, 1_000);
+        client.set_reserve_factor_bps(&1_000);
+        client.draw_credit(&borrower, &1_000, &None);
+        env.ledger()
+            .with_mut(|li| li.timestamp += math::SECONDS_PER_YEAR);
+
+        client.repay_credit(&borrower, &100);
+        let event: RepaymentEvent = env
+            .events()
+            .all()
+            .last()
+            .unwrap()
+            .2
+            .try_into_val(&env)
+            .unwrap();
+        assert_eq!(event.interest_portion, 30);
+        assert_eq!(event.principal_portion, 70);
+        assert_eq!(event.fee_portion, 3);
+        assert_eq!(event.new_utilized_amount, 930);
+    }
+
+    #[test]
+    fn test_drawn_event_reports_remaining_available_and_token() {
+        use soroban_sdk::testutils::Events;
+        use soroban_sdk::TryIntoVal;
+        let env = Env::default();
+        env.mock_all_auths();
+        let borrower = Address::generate(&env);
+        let (client, token_address, _admin) =
+            setup_contract_with_credit_line(&env, &borrower, 1_000, 1_000);
+        client.draw_credit(&borrower, &300, &None);
+        let events = env.events().all();
+        let event: DrawnEvent = events
+            .get(events.len() - 2)
+            .unwrap()
+            .2
+            .try_into_val(&env)
+            .unwrap();
+        assert_eq!(event.remaining_available, 700);
+        assert_eq!(event.token, token_address);
+    }
+
+    #[test]
+    fn test_draw_purpose_recorded_in_log_and_event() {
+        use soroban_sdk::testutils::Events;
+        use soroban_sdk::TryIntoVal;
+        let env = Env::default();
+        env.mock_all_auths();
+        let borrower = Address::generate(&env);
+        let (client, _token, _admin) =
+            setup_contract_with_credit_line(&env, &borrower, 1_000, 1_000);
+        client.draw_credit(&borrower, &100, &None);
+        client.draw_credit(&borrower, &200, &Some(symbol_short!("payroll")));
+        let event = env
+            .events()
+            .all()
+            .iter()
+            .find_map(|e| TryIntoVal::<Env, DrawnEvent>::try_into_val(&e.2, &env).ok())
+            .unwrap();
+        assert_eq!(event.purpose, Some(symbol_short!("payroll")));
+
+        let log = client.get_draw_log(&borrower);
+        assert_eq!(log.len(), 2);
+        assert_eq!(log.get(0).unwrap().purpose, None);
+        assert_eq!(log.get(1).unwrap().amount, 200);
+        assert_eq!(log.get(1).unwrap().purpose, Some(symbol_short!("payroll")));
+    }
+
+    #[test]
+    fn test_draw_log_keeps_most_recent_entries() {
+        let env = Env::default();
+        env.mock_all_auths();
+        let borrower = Address::generate(&env);
+        let (client, _token, _admin) =
+            setup_contract_with_credit_line(&env, &borrower, 1_000, 1_000);
+        for amount in 1..=(purposes::DRAW_LOG_SIZE as i128 + 2) {
+            client.draw_credit(&borrower, &amount, &None);
+        }
+        let log = client.get_draw_log(&borrower);
+        assert_eq!(log.len(), purposes::DRAW_LOG_SIZE);
+        assert_eq!(log.get(0).unwrap().amount, 3);
+    }
+
+    #[test]
+    fn test_restricted_line_requires_allowed_purpose() {
+        let env = Env::default();
+        env.mock_all_auths();
+        let borrower = Address::generate(&env);
+        let (client, _token, _admin) =
+            setup_contract_with_credit_line(&env, &borrower, 1_000, 1_000);
+        let allowed = soroban_sdk::vec![&env, symbol_short!("payroll"), symbol_short!("rent")];
+        client.set_allowed_purposes(&borrower, &allowed);
+        assert_eq!(client.get_allowed_purposes(&borrower), allowed);
+
+        assert!(client.try_draw_credit(&borrower, &100, &None).is_err());
+        assert!(client
+            .try_draw_credit(&borrower, &100, &Some(symbol_short!("travel")))
+            .is_err());
+        assert_eq!(
+            client
+                .preview_draw(&borrower, &100, &Some(symbol_short!("travel")))
+                .error,
+            ContractError::PurposeNotAllowed as u32
+        );
+        client.draw_credit(&borrower, &100, &Some(symbol_short!("rent")));
+
+        client.set_allowed_purposes(&borrower, &soroban_sdk::vec![&env]);
+        client.draw_credit(&borrower, &100, &None);
+        assert_eq!(
+            client.get_credit_line(&borrower).unwrap().utilized_amount,
+            200
+        );
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_repay_credit_with_refund_requires_full_amount_upfront() {
+        let env = Env::default();
+        env.mock_all_auths();
+        let borrower = Address::generate(&env);
+        let (client, _token, _admin) =
+            setup_contract_with_credit_line(&env, &borrower, 1_000, 1_000);
+        client.draw_credit(&borrower, &500, &None);
+        // Only the drawn 500 is on hand; the excess must be transferable before refund.
+        client.repay_credit_with_refund(&borrower, &550);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_open_credit_line_unauthorized() {
+        let env = Env::default();
+        let admin = Address::generate(&env);
+        let borrower = Address::generate(&env);
+        let token = Address::generate(&env);
+        let contract_id = env.register(Credit, ());
+        let client = CreditClient::new(&env, &contract_id);
+
+        client.init(&admin, &token);
+        // No mock_all_auths for admin
+        client.open_credit_line(
+            &admin,
+            &borrower,
+            &1000,
+            &300,
+            &70,
+            &None,
+            &RateMode::Fixed,
+            &None,
+            &None,
+        );
+    }
+
+    #[test]
+    fn test_get_nonexistent_credit_line() {
+        let env = Env::default();
+        let borrower = Address::generate(&env);
+        let contract_id = env.register(Credit, ());
+        let client = CreditClient::new(&env, &contract_id);
+
+        assert!(client.get_credit_line(&borrower).is_none());
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_draw_credit_overflow() {
+        let env = Env::default();
+        env.mock_all_auths();
+        let admin = Address::generate(&env);
+        let borrower = Address::generate(&env);
+        let contract_id = env.register(Credit, ());
+        let (token_address, _) = setup_token(&env, &contract_id, 0);
+        let client = CreditClient::new(&env, &contract_id);
+
+        client.init(&admin, &token_address);
+        client.open_credit_line(
+            &admin,
+            &borrower,
+            &i128::MAX,
+            &300,
+            &70,
+            &None,
+            &RateMode::Fixed,
+            &None,
+            &None,
+        );
+        client.draw_credit(&borrower, &i128::MAX, &None);
+        client.draw_credit(&borrower, &1, &None);
+    }
+
+    #[test]
+    fn test_repay_credit_saturates_at_zero() {
+        let env = Env::default();
+        env.mock_all_auths();
+        let borrower = Address::generate(&env);
+        let (client, _token, _admin) =
+            setup_contract_with_credit_line(&env, &borrower, 1_000, 1_000);
+        client.draw_credit(&borrower, &100, &None);
+        client.repay_credit(&borrower, &500);
+        assert_eq!(
+            client.get_credit_line(&borrower).unwrap().utilized_amount,
+            0
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "amount must be positive")]
+    fn test_repay_credit_rejects_non_positive_amount() {
+        let env = Env::default();
+        env.mock_all_auths();
+        let borrower = Address::generate(&env);
+        let (client, _token, _admin) = setup_contract_with_credit_line(&env, &borrower, 1_000, 0);
+        client.repay_credit(&borrower, &0);
+    }
+
+    #[test]
+    #[should_panic(expected = "Credit line not found")]
+    fn test_repay_credit_nonexistent_line() {
+        let env = Env::default();
+        env.mock_all_auths();
+        let stranger = Address::generate(&env);
+        let admin = Address::generate(&env);
+        let contract_id = env.register(Credit, ());
+        let (token_address, _) = setup_token(&env, &contract_id, 0);
+        let client = CreditClient::new(&env, &contract_id);
+        client.init(&admin, &token_address);
+        client.repay_credit(&stranger, &100);
+    }
+
+    #[test]
+    #[should_panic(expected = "credit line is closed")]
+    fn test_repay_credit_rejected_when_closed() {
+        let env = Env::default();
+        env.mock_all_auths();
+        let borrower = Address::generate(&env);
+        let (client, _token, admin) = setup_contract_with_credit_line(&env, &borrower, 1_000, 0);
+        client.close_credit_line(&borrower, &admin);
+        client.repay_credit(&borrower, &100);
+    }
+
+    #[test]
+    fn test_repay_credit_succeeds_when_suspended() {
+        let env = Env::default();
+        env.mock_all_auths();
+        let borrower = Address::generate(&env);
+        let (client, _token, _admin) =
+            setup_contract_with_credit_line(&env, &borrower, 1_000, 1_000);
+
+        client.draw_credit(&borrower, &300, &None);
+        client.suspend_credit_line(&borrower, &0, &None);
+
+        client.repay_credit(&borrower, &100);
+
+        let line = client.get_credit_line(&borrower).unwrap();
+        assert_eq!(line.status, CreditStatus::Suspended);
+        assert_eq!(line.utilized_amount, 200);
+    }
+
+    // ── admin-only enforcement ────────────────────────────────────────────────
+
+    #[test]
+    #[should_panic]
+    fn test_suspend_credit_line_unauthorized() {
+        let env = Env::default();
+        let borrower = Address::generate(&env);
+        let admin = Address::generate(&env);
+        let contract_id = env.register(Credit, ());
+        let (token_address, _) = setup_token(&env, &contract_id, 0);
+        let client = CreditClient::new(&env, &contract_id);
+
+        client.init(&admin, &token_address);
+        client.open_credit_line(
+            &admin,
+            &borrower,
+            &1_000,
+            &300,
+            &70,
+            &None,
+            &RateMode::Fixed,
+            &None,
+            &None,
+        );
+
+        // No mock_all_auths
+        client.suspend_credit_line(&borrower, &0, &None);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_default_credit_line_unauthorized() {
+        let env = Env::default();
+        let borrower = Address::generate(&env);
+        let admin = Address::generate(&env);
+        let contract_id = env.register(Credit, ());
+        let (token_address, _) = setup_token(&env, &contract_id, 0);
+        let client = CreditClient::new(&env, &contract_id);
+
+        client.init(&admin, &token_address);
+        client.open_credit_line(
+            &admin,
+            &borrower,
+            &1_000,
+            &300,
+            &70,
+            &None,
+            &RateMode::Fixed,
+            &None,
+            &None,
+        );
+
+        // No mock_all_auths
+        client.default_credit_line(&borrower);
+    }
+
+    #[test]
+    #[should_panic(expected = "Already initialized")]
+    fn test_init_twice() {
+        let env = Env::default();
+        let admin = Address::generate(&env);
+        let token = Address::generate(&env);
+        let contract_id = env.register(Credit, ());
+        let client = CreditClient::new(&env, &contract_id);
+
+        client.init(&admin, &token);
+        client.init(&admin, &token);
+    }
+
+    #[test]
+    fn test_interface_discovery() {
+        let env = Env::default();
+        let contract_id = env.register(Credit, ());
+        let client = CreditClient::new(&env, &contract_id);
+        assert_eq!(client.interface_version(), features::INTERFACE_VERSION);
+        let features = client.supported_features();
+        for name in [
+            symbol_short!("collatrl"),
+            symbol_short!("schedules"),
+            symbol_short!("multi_tok"),
+        ] {
+            assert!(features.contains(&name));
+        }
+    }
+
+    #[test]
+    fn test_config_address_views() {
+        let env = Env::default();
+        env.mock_all_auths();
+        let admin = Address::generate(&env);
+        let token = Address::generate(&env);
+        let contract_id = env.register(Credit, ());
+        let client = CreditClient::new(&env, &contract_id);
+        assert_eq!(client.get_admin(), None);
+        assert_eq!(client.get_liquidity_token(), None);
+
+        client.init(&admin, &token);
+        assert_eq!(client.get_admin(), Some(admin));
+        assert_eq!(client.get_liquidity_token(), Some(token));
+        assert_eq!(client.get_liquidity_source(), None);
+
+        let first = Address::generate(&env);
+        let second = Address::generate(&env);
+        client.add_liquidity_source(&second, &5);
+        client.add_liquidity_source(&first, &1);
+        assert_eq!(client.get_liquidity_source(), Some(first.clone()));
+        client.remove_liquidity_source(&first);
+        assert_eq!(client.get_liquidity_source(), Some(second));
+    }
+
+    #[test]
+    #[should_panic(expected = "Credit line not active")]
+    fn test_draw_credit_suspended() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let admin = Address::generate(&env);
+        let borrower = Address::generate(&env);
+
+        let contract_id = env.register(Credit, ());
+        let client = CreditClient::new(&env, &contract_id);
+
+        let (token_address, _) = setup_token(&env, &contract_id, 0);
+        client.init(&admin, &token_address);
+        client.open_credit_line(
+            &admin,
+            &borrower,
+            &1000_i128,
+            &300_u32,
+            &70_u32,
+            &None,
+            &RateMode::Fixed,
+            &None,
+            &None,
+        );
+        client.suspend_credit_line(&borrower, &0, &None);
+
+        client.draw_credit(&borrower, &100_i128, &None);
+    }
+
+    // ── reentrancy guard ──────────────────────────────────────────────────────
+
+    #[test]
+    fn test_reentrancy_guard_cleared_after_draw() {
+        let env = Env::default();
+        env.mock_all_auths();
+        let borrower = Address::generate(&env);
+        let (client, _token, _admin) =
+            setup_contract_with_credit_line(&env, &borrower, 1_000, 1_000);
+        client.draw_credit(&borrower, &100, &None);
+        client.draw_credit(&borrower, &100, &None);
+        assert_eq!(
+            client.get_credit_line(&borrower).unwrap().utilized_amount,
+            200
+        );
+    }
+
+    #[test]
+    fn test_reentrancy_guard_cleared_after_repay() {
+        let env = Env::default();
+        env.mock_all_auths();
+        let borrower = Address::generate(&env);
+        let (client, _token, _admin) =
+            setup_contract_with_credit_line(&env, &borrower, 1_000, 1_000);
+        client.draw_credit(&borrower, &200, &None);
+        client.repay_credit(&borrower, &50);
+        client.repay_credit(&borrower, &50);
+        assert_eq!(
+            client.get_credit_line(&borrower).unwrap().utilized_amount,
+            100
+        );
+    }
+
+    #[test]
+    fn test_reentrancy_guard_released_after_draw_panics_mid_call() {
+        let env = Env::default();
+        env.mock_all_auths();
+        let borrower = Address::generate(&env);
+        let (client, _token, _admin) =
+            setup_contract_with_credit_line(&env, &borrower, 1_000, 1_000);
+        // Fails after the line has been saved, while the guard is held.
+        client.set_reserve_config(&ReserveConfig {
+            min_reserve_ratio_bps: 10_000,
+            warning_ratio_bps: 0,
+        });
+        assert!(client.try_draw_credit(&borrower, &600, &None).is_err());
+
+        client.set_reserve_config(&ReserveConfig::default());
+        client.draw_credit(&borrower, &100, &None);
+        assert_eq!(
+            client.get_credit_line(&borrower).unwrap().utilized_amount,
+            100
+        );
+    }
+
+    #[test]
+    fn test_reentrancy_guard_released_after_repay_panics_mid_call() {
+        let env = Env::default();
+        env.mock_all_auths();
+        let borrower = Address::generate(&env);
+        let (client, token_address, _admin) =
+            setup_contract_with_credit_line(&env, &borrower, 1_000, 1_000);
+        client.draw_credit(&borrower, &300, &None);
+        // Spend the drawn funds so the token transfer fails after the line is saved.
+        token::Client::new(&env, &token_address).transfer(
+            &borrower,
+            &Address::generate(&env),
+            &300,
+        );
+        assert!(client.try_repay_credit(&borrower, &100).is_err());
+        assert_eq!(
+            client.get_credit_line(&borrower).unwrap().utilized_amount,
+            300
+        );
+
+        token::StellarAssetClient::new(&env, &token_address).mint(&borrower, &100);
+        client.repay_credit(&borrower, &100);
+        assert_eq!(
+            client.get_credit_line(&borrower).unwrap().utilized_amount,
+            200
+        );
+    }
+
+    // ── resume_credit_line ────────────────────────────────────────────────────
+
+    #[test]
+    fn test_resume_credit_line_restores_active_and_allows_draw() {
+        let env = Env::default();
+        env.mock_all_auths();
+        let borrower = Address::generate(&env);
+        let (client, _token, _admin) =
+            setup_contract_with_credit_line(&env, &borrower, 1_000, 1_000);
+        client.suspend_credit_line(&borrower, &0, &None);
+        client.resume_credit_line(&borrower);
+        assert_eq!(
+            client.get_credit_line(&borrower).unwrap().status,
+            CreditStatus::Active
+        );
+        client.draw_credit(&borrower, &100, &None);
+        assert_eq!(
+            client.get_credit_line(&borrower).unwrap().utilized_amount,
+            100
+        );
+    }
+
+    #[test]
+    fn test_event_resume_credit_line() {
+        use soroban_sdk::testutils::Events;
+        use soroban_sdk::{TryFromVal, TryIntoVal};
+        let env = Env::default();
+        env.mock_all_auths();
+        let borrower = Address::generate(&env);
+        let (client, _token, _admin) = setup_contract_with_credit_line(&env, &borrower, 1_000, 0);
+        client.suspend_credit_line(&borrower, &0, &None);
+        client.resume_credit_line(&borrower);
+        let events = env.events().all();
+        let (_contract, topics, data) = events.last().unwrap();
+        assert_eq!(
+            Symbol::try_from_val(&env, &topics.get(1).unwrap()).unwrap(),
+            symbol_short!("resume")
+        );
+        let event_data: CreditLineEvent = data.try_into_val(&env).unwrap();
+        assert_eq!(event_data.status, CreditStatus::Active);
+    }
+
+    #[test]
+    #[should_panic(expected = "credit line is not suspended")]
+    fn test_resume_active_credit_line_reverts() {
+        let env = Env::default();
+        env.mock_all_auths();
+        let borrower = Address::generate(&env);
+        let (client, _token, _admin) = setup_contract_with_credit_line(&env, &borrower, 1_000, 0);
+        client.resume_credit_line(&borrower);
+    }
+
+    #[test]
+    #[should_panic(expected = "credit line is not suspended")]
+    fn test_resume_defaulted_credit_line_reverts() {
+        let env = Env::default();
+        env.mock_all_auths();
+        let borrower = Address::generate(&env);
+        let (client, _token, _admin) = setup_contract_with_credit_line(&env, &borrower, 1_000, 0);
+        client.default_credit_line(&borrower);
+        client.resume_credit_line(&borrower);
+    }
+
+    #[test]
+    #[should_panic(expected = "credit line is not suspended")]
+    fn test_resume_closed_credit_line_reverts() {
+        let env = Env::default();
+        env.mock_all_auths();
+        let borrower = Address::generate(&env);
+        let (client, _token, admin) = setup_contract_with_credit_line(&env, &borrower, 1_000, 0);
+        client.close_credit_line(&borrower, &admin);
+        client.resume_credit_line(&borrower);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_resume_credit_line_unauthorized() {
+        let env = Env::default();
+        let borrower = Address::generate(&env);
+        let admin = Address::generate(&env);
+        let contract_id = env.register(Credit, ());
+        let (token_address, _) = setup_token(&env, &contract_id, 0);
+        let client = CreditClient::new(&env, &contract_id);
+        env.mock_all_auths();
+        client.init(&admin, &token_address);
+        client.open_credit_line(
+            &admin,
+            &borrower,
+            &1_000,
+            &300,
+            &70,
+            &None,
+            &RateMode::Fixed,
+            &None,
+            &None,
+        );
+        client.suspend_credit_line(&borrower, &0, &None);
+        env.set_auths(&[]);
+        client.resume_credit_line(&borrower);
+    }
+
+    // ── repayment schedules ───────────────────────────────────────────────────
+
+    fn setup_scheduled_line<'a>(
+        env: &'a Env,
+        borrower: &'a Address,
+        cycle_length_secs: u64,
+        min_payment_bps: u32,
+    ) -> (CreditClient<'a>, Address, Address) {
+        let admin = Address::generate(env);
+        let contract_id = env.register(Credit, ());
+        let (token_address, _sac) = setup_token(env, &contract_id, 10_000);
+        let client = CreditClient::new(env, &contract_id);
+        client.init(&admin, &token_address);
+        client.open_credit_line(
+            &admin,
+            borrower,
+            &10_000,
+            &300_u32,
+            &70_u32,
+            &Some(RepaymentSchedule {
+                cycle_length_secs,
+                min_payment_bps,
+            }),
+            &RateMode::Fixed,
+            &None,
+            &None,
+        );
+        (client, token_address, admin)
+    }
+
+    #[test]
+    fn test_get_next_payment_none_without_schedule() {
+        let env = Env::default();
+        env.mock_all_auths();
+        let borrower = Address::generate(&env);
+        let (client, _token, _admin) = setup_contract_with_credit_line(&env, &borrower, 1_000, 0);
+        assert!(client.get_next_payment(&borrower).is_none());
+        assert!(client.get_next_payment(&Address::generate(&env)).is_none());
+    }
+
+    #[test]
+    fn test_schedule_first_cycle_has_nothing_due() {
+        use soroban_sdk::testutils::Ledger;
+        let env = Env::default();
+        env.mock_all_auths();
+        env.ledger().with_mut(|li| li.timestamp = 1_000);
+        let borrower = Address::generate(&env);
+        let (client, _token, _admin) = setup_scheduled_line(&env, &borrower, 100, 1_000);
+        client.draw_credit(&borrower, &5_000, &None);
+        let next = client.get_next_payment(&borrower).unwrap();
+        assert_eq!(next.due_ts, 1_100);
+        assert_eq!(next.min_due_amount, 0);
+        assert!(!next.overdue);
+    }
+
+    #[test]
+    fn test_schedule_rolls_to_minimum_due_from_utilized() {
+        use soroban_sdk::testutils::Ledger;
+        let env = Env::default();
+        env.mock_all_auths();
+        env.ledger().with_mut(|li| li.timestamp = 1_000);
+        let borrower = Address::generate(&env);
+        let (client, _token, _admin) = setup_scheduled_line(&env, &borrower, 100, 1_000);
+        client.draw_credit(&borrower, &5_000, &None);
+        env.ledger().with_mut(|li| li.timestamp = 1_100);
+        let next = client.get_next_payment(&borrower).unwrap();
+        assert_eq!(next.due_ts, 1_200);
+        assert_eq!(next.min_due_amount, 500);
+        assert!(!next.overdue);
+    }
+
+    #[test]
+    fn test_schedule_missed_payment_is_overdue() {
+        use soroban_sdk::testutils::Ledger;
+        let env = Env::default();
+        env.mock_all_auths();
+        env.ledger().with_mut(|li| li.timestamp = 1_000);
+        let borrower = Address::generate(&env);
+        let (client, _token, _admin) = setup_scheduled_line(&env, &borrower, 100, 1_000);
+        client.draw_credit(&borrower, &5_000, &None);
+        env.ledger().with_mut(|li| li.timestamp = 1_250);
+        let next = client.get_next_payment(&borrower).unwrap();
+        assert_eq!(next.due_ts, 1_200);
+        assert_eq!(next.min_due_amount, 500);
+        assert!(next.overdue);
+    }
+
+    #[test]
+    fn test_schedule_repayment_clears_minimum_due() {
+        use soroban_sdk::testutils::Ledger;
+        let env = Env::default();
+        env.mock_all_auths();
+        env.ledger().with_mut(|li| li.timestamp = 1_000);
+        let borrower = Address::generate(&env);
+        let (client, _token, _admin) = setup_scheduled_line(&env, &borrower, 100, 1_000);
+        client.draw_credit(&borrower, &5_000, &None);
+        env.ledger().with_mut(|li| li.timestamp = 1_150);
+        client.repay_credit(&borrower, &300);
+        assert_eq!(
+            client.get_next_payment(&borrower).unwrap().min_due_amount,
+            200
+        );
+        client.repay_credit(&borrower, &200);
+        let line = client.get_credit_line(&borrower).unwrap();
+        assert_eq!(line.min_due_amount, 0);
+        assert_eq!(line.next_due_ts, 1_200);
+
+        // The next cycle is billed on the remaining 4_500 once 1_200 passes.
+        env.ledger().with_mut(|li| li.timestamp = 1_250);
+        let next = client.get_next_payment(&borrower).unwrap();
+        assert_eq!(next.due_ts, 1_300);
+        assert_eq!(next.min_due_amount, 450);
+        assert!(!next.overdue);
+    }
+
+    #[test]
+    fn test_schedule_idle_line_skips_elapsed_cycles() {
+        use soroban_sdk::testutils::Ledger;
+        let env = Env::default();
+        env.mock_all_auths();
+        env.ledger().with_mut(|li| li.timestamp = 1_000);
+        let borrower = Address::generate(&env);
+        let (client, _token, _admin) = setup_scheduled_line(&env, &borrower, 100, 1_000);
+        env.ledger().with_mut(|li| li.timestamp = 10_050);
+        let next = client.get_next_payment(&borrower).unwrap();
+        assert_eq!(next.due_ts, 10_100);
+        assert_eq!(next.min_due_amount, 0);
+    }
+
+    #[test]
+    fn test_schedule_zero_bill_skips_long_elapsed_time() {
+        use soroban_sdk::testutils::Ledger;
+        let env = Env::default();
+        env.mock_all_auths();
+        let first = Address::generate(&env);
+        let (client, _token, admin) = setup_contract_with_credit_line(&env, &first, 1_000, 2_000);
+        let borrower = Address::generate(&env);
+        client.open_credit_line(
+            &admin,
+            &borrower,
+            &1_000,
+            &0,
+            &70,
+            &Some(RepaymentSchedule {
+                cycle_length_secs: 60,
+                min_payment_bps: 1_000,
+            }),
+            &RateMode::Fixed,
+            &None,
+            &None,
+        );
+        // Interest-only at 0% bills nothing, cycle after cycle.
+        client.set_repayment_mode(&admin, &borrower, &RepaymentMode::InterestOnly);
+        client.draw_credit(&borrower, &400, &None);
+        env.ledger()
+            .with_mut(|li| li.timestamp = 30 * math::SECONDS_PER_DAY + 30);
+
+        client.repay_credit(&borrower, &100);
+        let line = client.get_credit_line(&borrower).unwrap();
+        assert_eq!(line.utilized_amount, 300);
+        assert_eq!(line.min_due_amount, 0);
+        assert_eq!(line.next_due_ts, 30 * math::SECONDS_PER_DAY + 60);
+    }
+
+    #[test]
+    #[should_panic(expected = "min_payment_bps must be greater than zero")]
+    fn test_open_revolving_line_zero_min_payment_reverts() {
+        let env = Env::default();
+        env.mock_all_auths();
+        let borrower = Address::generate(&env);
+        setup_scheduled_line(&env, &borrower, 100, 0);
+    }
+
+    #[test]
+    #[should_panic(expected = "cycle_length_secs must be greater than zero")]
+    fn test_open_credit_line_zero_cycle_length_reverts() {
+        let env = Env::default();
+        env.mock_all_auths();
+        let borrower = Address::generate(&env);
+        setup_scheduled_line(&env, &borrower, 0, 1_000);
+    }
+
+    #[test]
+    #[should_panic(expected = "min_payment_bps cannot exceed 10000")]
+    fn test_open_credit_line_min_payment_bps_exceeds_max_reverts() {
+        let env = Env::default();
+        env.mock_all_auths();
+        let borrower = Address::generate(&env);
+        setup_scheduled_line(&env, &borrower, 100, 10_001);
+    }
+
+    // ── keeper: process_overdue ───────────────────────────────────────────────
+
+    /// Scheduled line with 5_000 drawn at t=1_000; the 500 minimum falls due at 1_200.
+    fn setup_overdue_line<'a>(
+        env: &'a Env,
+        borrower: &'a Address,
+        config: &KeeperConfig,
+    ) -> (CreditClient<'a>, Address, Address) {
+        use soroban_sdk::testutils::Ledger;
+        env.ledger().with_mut(|li| li.timestamp = 1_000);
+        let (client, token_address, admin) = setup_scheduled_line(env, borrower, 100, 1_000);
+        client.set_keeper_config(config);
+        client.draw_credit(borrower, &5_000, &None);
+        (client, token_address, admin)
+    }
+
+    /// Open a throwaway line charging `fee` at origination, booking it as protocol fees.
+    fn book_origination_fee(env: &Env, client: &CreditClient, admin: &Address, fee: i128) {
+        let fees = client.get_fee_config();
+        client.set_fee_config(&FeeConfig {
+            origination_fee: fee,
+            ..fees.clone()
+        });
+        client.open_credit_line(
+            admin,
+            &Address::generate(env),
+            &1_000,
+            &300_u32,
+            &70_u32,
+            &None,
+            &RateMode::Fixed,
+            &None,
+            &None,
+        );
+        client.set_fee_config(&fees);
+    }
+
+    #[test]
+    fn test_process_overdue_marks_delinquent_and_pays_keeper() {
+        use soroban_sdk::testutils::Ledger;
+        let env = Env::default();
+        env.mock_all_auths();
+        let borrower = Address::generate(&env);
+        let keeper = Address::generate(&env);
+        let config = KeeperConfig {
+            grace_period_secs: 50,
+            default_after_missed_cycles: 3,
+            keeper_reward: 10,
+            auto_default_after_secs: 0,
+        };
+        let (client, token_address, admin) = setup_overdue_line(&env, &borrower, &config);
+        book_origination_fee(&env, &client, &admin, 25);
+        let token_client = token::Client::new(&env, &token_address);
+        let contract_balance = token_client.balance(&client.address);
+        env.ledger().with_mut(|li| li.timestamp = 1_251);
+        client.process_overdue(&keeper, &borrower);
+
+        assert_eq!(token_client.balance(&client.address), contract_balance - 10);
+        assert_eq!(client.get_protocol_fees(&token_address), 15);
+        let line = client.get_credit_line(&borrower).unwrap();
+        assert_eq!(line.status, CreditStatus::Delinquent);
+        assert_eq!(line.delinquent_since_ts, 1_200);
+        assert_eq!(line.missed_cycles, 1);
+        assert_eq!(line.next_due_ts, 1_300);
+        assert_eq!(line.min_due_amount, 1_000);
+        assert_eq!(
+            token::Client::new(&env, &token_address).balance(&keeper),
+            10
+        );
+    }
+
+    #[test]
+    fn test_process_overdue_caps_keeper_reward_at_accrued_fees() {
+        use soroban_sdk::testutils::{Events, Ledger};
+        use soroban_sdk::TryIntoVal;
+        let env = Env::default();
+        env.mock_all_auths();
+        let borrower = Address::generate(&env);
+        let keeper = Address::generate(&env);
+        let config = KeeperConfig {
+            grace_period_secs: 0,
+            default_after_missed_cycles: 0,
+            keeper_reward: 10,
+            auto_default_after_secs: 0,
+        };
+        let (client, token_address, admin) = setup_overdue_line(&env, &borrower, &config);
+        book_origination_fee(&env, &client, &admin, 4);
+        let token_client = token::Client::new(&env, &token_address);
+        let contract_balance = token_client.balance(&client.address);
+        env.ledger().with_mut(|li| li.timestamp = 1_201);
+        client.process_overdue(&keeper, &borrower);
+
+        let event: OverdueProcessedEvent = env
+            .events()
+            .all()
+            .last()
+            .unwrap()
+            .2
+            .try_into_val(&env)
+            .unwrap();
+        assert_eq!(event.keeper_reward, 4);
+        assert_eq!(token_client.balance(&keeper), 4);
+        assert_eq!(token_client.balance(&client.address), contract_balance - 4);
+        assert_eq!(client.get_protocol_fees(&token_address), 0);
+
+        // Nothing left to pay from: the next cycle is processed without a reward.
+        env.ledger().with_mut(|li| li.timestamp = 1_301);
+        client.process_overdue(&keeper, &borrower);
+        assert_eq!(token_client.balance(&keeper), 4);
+        assert_eq!(token_client.balance(&client.address), contract_balance - 4);
+    }
+
+    #[test]
+    #[should_panic(expected = "payment not overdue")]
+    fn test_process_overdue_within_grace_period_reverts() {
+        use soroban_sdk::testutils::Ledger;
+        let env = Env::default();
+        env.mock_all_auths();
+        let borrower = Address::generate(&env);
+        let config = KeeperConfig {
+            grace_period_secs: 50,
+            default_after_missed_cycles: 0,
+            keeper_reward: 0,
+            auto_default_after_secs: 0,
+        };
+        let (client, _token, _admin) = setup_overdue_line(&env, &borrower, &config);
+        env.ledger().with_mut(|li| li.timestamp = 1_250);
+        client.process_overdue(&Address::generate(&env), &borrower);
+    }
+
+    #[test]
+    #[should_panic(expected = "payment not overdue")]
+    fn test_process_overdue_same_cycle_twice_reverts() {
+        use soroban_sdk::testutils::Ledger;
+        let env = Env::default();
+        env.mock_all_auths();
+        let borrower = Address::generate(&env);
+        let config = KeeperConfig::default();
+        let (client, _token, _admin) = setup_overdue_line(&env, &borrower, &config);
+        env.ledger().with_mut(|li| li.timestamp = 1_201);
+        client.process_overdue(&Address::generate(&env), &borrower);
+        client.process_overdue(&Address::generate(&env), &borrower);
+    }
+
+    #[test]
+    fn test_process_overdue_escalates_to_default_after_missed_cycles() {
+        use soroban_sdk::testutils::{Events, Ledger};
+        use soroban_sdk::TryIntoVal;
+        let env = Env::default();
+        env.mock_all_auths();
+        let borrower = Address::generate(&env);
+        let keeper = Address::generate(&env);
+        let config = KeeperConfig {
+            grace_period_secs: 0,
+            default_after_missed_cycles: 2,
+            keeper_reward: 0,
+            auto_default_after_secs: 0,
+        };
+        let (client, _token, _admin) = setup_overdue_line(&env, &borrower, &config);
+        env.ledger().with_mut(|li| li.timestamp = 1_201);
+        client.process_overdue(&keeper, &borrower);
+        env.ledger().with_mut(|li| li.timestamp = 1_301);
+        client.process_overdue(&keeper, &borrower);
+        let event: OverdueProcessedEvent = env
+            .events()
+            .all()
+            .last()
+            .unwrap()
+            .2
+            .try_into_val(&env)
+            .unwrap();
+        assert_eq!(event.status, CreditStatus::Defaulted);
+        assert_eq!(event.missed_cycles, 2);
+        assert_eq!(event.keeper, keeper);
+
+        let line = client.get_credit_line(&borrower).unwrap();
+        assert_eq!(line.status, CreditStatus::Defaulted);
+        assert_eq!(line.missed_cycles, 2);
+    }
+
+    #[test]
+    fn test_process_overdue_does_not_default_disputed_line() {
+        use soroban_sdk::testutils::Ledger;
+        let env = Env::default();
+        env.mock_all_auths();
+        let borrower = Address::generate(&env);
+        let keeper = Address::generate(&env);
+        let config = KeeperConfig {
+            grace_period_secs: 0,
+            default_after_missed_cycles: 1,
+            keeper_reward: 0,
+            auto_default_after_secs: 0,
+        };
+        let (client, _token, _admin) = setup_overdue_line(&env, &borrower, &config);
+        client.flag_dispute(&borrower, &7);
+        env.ledger().with_mut(|li| li.timestamp = 1_201);
+        client.process_overdue(&keeper, &borrower);
+
+        let line = client.get_credit_line(&borrower).unwrap();
+        assert_eq!(line.status, CreditStatus::Delinquent);
+        assert_eq!(line.missed_cycles, 1);
+    }
+
+    #[test]
+    fn test_repaying_arrears_resets_missed_cycles() {
+        use soroban_sdk::testutils::Ledger;
+        let env = Env::default();
+        env.mock_all_auths();
+        let borrower = Address::generate(&env);
+        let config = KeeperConfig::default();
+        let (client, _token, _admin) = setup_overdue_line(&env, &borrower, &config);
+        env.ledger().with_mut(|li| li.timestamp = 1_201);
+        client.process_overdue(&Address::generate(&env), &borrower);
+        client.repay_credit(&borrower, &1_000);
+        let line = client.get_credit_line(&borrower).unwrap();
+        assert_eq!(line.missed_cycles, 0);
+        assert_eq!(line.min_due_amount, 0);
+    }
+
+    #[test]
+    fn test_process_overdue_auto_defaults_long_delinquent_line() {
+        use soroban_sdk::testutils::{Events, Ledger};
+        use soroban_sdk::TryIntoVal;
+        let env = Env::default();
+        env.mock_all_auths();
+        let borrower = Address::generate(&env);
+        let config = KeeperConfig {
+            grace_period_secs: 0,
+            default_after_missed_cycles: 0,
+            keeper_reward: 0,
+            auto_default_after_secs: 1_000,
+        };
+        let (client, _token, _admin) = setup_overdue_line(&env, &borrower, &config);
+        env.ledger().with_mut(|li| li.timestamp = 1_201);
+        client.process_overdue(&Address::generate(&env), &borrower);
+        assert_eq!(
+            client.get_credit_line(&borrower).unwrap().status,
+            CreditStatus::Delinquent
+        );
+
+        // Past the threshold the line defaults before another missed cycle is recorded.
+        env.ledger().with_mut(|li| li.timestamp = 2_200);
+        client.process_overdue(&Address::generate(&env), &borrower);
+        let event = env
+            .events()
+            .all()
+            .iter()
+            .find_map(|(_contract, _topics, data)| {
+                TryIntoVal::<Env, CreditLineEvent>::try_into_val(&data, &env).ok()
+            })
+            .unwrap();
+        assert_eq!(event.event_type, symbol_short!("default"));
+        assert!(event.auto);
+        let line = client.get_credit_line(&borrower).unwrap();
+        assert_eq!(line.status, CreditStatus::Defaulted);
+        assert_eq!(line.missed_cycles, 1);
+    }
+
+    #[test]
+    #[should_panic(expected = "payment not overdue")]
+    fn test_process_overdue_before_auto_default_period_reverts() {
+        use soroban_sdk::testutils::Ledger;
+        let env = Env::default();
+        env.mock_all_auths();
+        let borrower = Address::generate(&env);
+        let config = KeeperConfig {
+            grace_period_secs: 0,
+            default_after_missed_cycles: 0,
+            keeper_reward: 0,
+            auto_default_after_secs: 1_000,
+        };
+        let (client, _token, _admin) = setup_overdue_line(&env, &borrower, &config);
+        env.ledger().with_mut(|li| li.timestamp = 1_201);
+        client.process_overdue(&Address::generate(&env), &borrower);
+        env.ledger().with_mut(|li| li.timestamp = 1_250);
+        client.process_overdue(&Address::generate(&env), &borrower);
+    }
+
+    #[test]
+    #[should_panic(expected = "Credit line not active")]
+    fn test_delinquent_line_cannot_draw() {
+        use soroban_sdk::testutils::Ledger;
+        let env = Env::default();
+        env.mock_all_auths();
+        let borrower = Address::generate(&env);
+        let (client, _token, _admin) =
+            setup_overdue_line(&env, &borrower, &KeeperConfig::default());
+        env.ledger().with_mut(|li| li.timestamp = 1_201);
+        client.process_overdue(&Address::generate(&env), &borrower);
+        client.draw_credit(&borrower, &100, &None);
+    }
+
+    #[test]
+    fn test_repaying_arrears_cures_delinquency() {
+        use soroban_sdk::testutils::{Events, Ledger};
+        use soroban_sdk::TryIntoVal;
+        let env = Env::default();
+        env.mock_all_auths();
+        let borrower = Address::generate(&env);
+        let (client, _token, _admin) =
+            setup_overdue_line(&env, &borrower, &KeeperConfig::default());
+        env.ledger().with_mut(|li| li.timestamp = 1_201);
+        client.process_overdue(&Address::generate(&env), &borrower);
+
+        client.repay_credit(&borrower, &400);
+        assert_eq!(
+            client.get_credit_line(&borrower).unwrap().status,
+            CreditStatus::Delinquent
+        );
+        client.repay_credit(&borrower, &600);
+        let cured = env
+            .events()
+            .all()
+            .iter()
+            .find_map(|(_contract, _topics, data)| {
+                TryIntoVal::<Env, CreditLineEvent>::try_into_val(&data, &env).ok()
+            })
+            .unwrap();
+        assert_eq!(cured.event_type, symbol_short!("cured"));
+        let line = client.get_credit_line(&borrower).unwrap();
+        assert_eq!(line.status, CreditStatus::Active);
+        assert_eq!(line.delinquent_since_ts, 0);
+        client.draw_credit(&borrower, &100, &None);
+    }
+
+    #[test]
+    fn test_get_health_reports_days_past_due() {
+        use soroban_sdk::testutils::Ledger;
+        let env = Env::default();
+        env.mock_all_auths();
+        let borrower = Address::generate(&env);
+        let (client, _token, _admin) =
+            setup_overdue_line(&env, &borrower, &KeeperConfig::default());
+        let health = client.get_health(&borrower).unwrap();
+        assert_eq!(health.days_past_due, 0);
+        assert_eq!(health.amount_past_due, 0);
+
+        env.ledger().with_mut(|li| li.timestamp = 1_201);
+        client.process_overdue(&Address::generate(&env), &borrower);
+        env.ledger()
+            .with_mut(|li| li.timestamp = 1_200 + 3 * 86_400 + 10);
+        let health = client.get_health(&borrower).unwrap();
+        assert_eq!(health.status, CreditStatus::Delinquent);
+        assert_eq!(health.days_past_due, 3);
+        assert_eq!(health.delinquent_since_ts, 1_200);
+        assert!(health.amount_past_due > 0);
+        assert_eq!(health.available, 0);
+        assert!(client.get_health(&Address::generate(&env)).is_none());
+    }
+
+    #[test]
+    fn test_bulk_line_and_health_reads() {
+        let env = Env::default();
+        env.mock_all_auths();
+        let borrower = Address::generate(&env);
+        let stranger = Address::generate(&env);
+        let (client, _token, _admin) =
+            setup_contract_with_credit_line(&env, &borrower, 1_000, 1_000);
+        client.draw_credit(&borrower, &250, &None);
+        let borrowers = soroban_sdk::vec![&env, stranger.clone(), borrower.clone()];
+
+        let lines = client.get_credit_lines(&borrowers);
+        assert_eq!(lines.len(), 2);
+        assert_eq!(lines.get(0).unwrap(), None);
+        assert_eq!(lines.get(1).unwrap(), client.get_credit_line(&borrower));
+
+        let healths = client.get_healths(&borrowers);
+        assert_eq!(healths.len(), 2);
+        assert_eq!(healths.get(0).unwrap(), None);
+        let health = healths.get(1).unwrap().unwrap();
+        assert_eq!(health, client.get_health(&borrower).unwrap());
+        assert_eq!(health.utilized_amount, 250);
+        assert!(client.get_credit_lines(&soroban_sdk::vec![&env]).is_empty());
+    }
+
+    #[test]
+    #[should_panic(expected = "credit line not eligible")]
+    fn test_process_overdue_unscheduled_line_reverts() {
+        let env = Env::default();
+        env.mock_all_auths();
+        let borrower = Address::generate(&env);
+        let (client, _token, _admin) = setup_contract_with_credit_line(&env, &borrower, 1_000, 0);
+        client.process_overdue(&Address::generate(&env), &borrower);
+    }
+
+    #[test]
+    #[should_panic(expected = "keeper_reward must be non-negative")]
+    fn test_set_keeper_config_negative_reward_reverts() {
+        let env = Env::default();
+        env.mock_all_auths();
+        let borrower = Address::generate(&env);
+        let (client, _token, _admin) = setup_contract_with_credit_line(&env, &borrower, 1_000, 0);
+        client.set_keeper_config(&KeeperConfig {
+            grace_period_secs: 0,
+            default_after_missed_cycles: 0,
+            keeper_reward: -1,
+            auto_default_after_secs: 0,
+        });
+    }
+
+    // ── collateral ────────────────────────────────────────────────────────────
+
+    /// Allow `collateral_token` as collateral and price it at par with `credit_token`.
+    fn accept_collateral_at_par(
+        env: &Env,
+        client: &CreditClient,
+        credit_token: &Address,
+        collateral_token: &Address,
+    ) {
+        let oracle_id = env.register(mock_oracle::MockOracle, ());
+        let oracle = mock_oracle::MockOracleClient::new(env, &oracle_id);
+        let now = env.ledger().timestamp();
+        oracle.set_price(credit_token, &1_0000000, &now);
+        oracle.set_price(collateral_token, &1_0000000, &now);
+        client.set_price_oracle(&oracle_id, &300);
+        client.set_collateral_token(collateral_token, &true);
+    }
+
+    #[test]
+    #[should_panic(expected = "collateral token not allowed")]
+    fn test_deposit_unlisted_collateral_token_reverts() {
+        let env = Env::default();
+        env.mock_all_auths();
+        let borrower = Address::generate(&env);
+        let (client, _token, _admin) = setup_contract_with_credit_line(&env, &borrower, 1_000, 0);
+        let (minted, _) = setup_token(&env, &borrower, 1_000_000);
+        client.deposit_collateral(&borrower, &minted, &1_000_000);
+    }
+
+    #[test]
+    #[should_panic(expected = "exceeds collateralized limit")]
+    fn test_unpriced_collateral_adds_nothing_to_limit() {
+        let env = Env::default();
+        env.mock_all_auths();
+        let borrower = Address::generate(&env);
+        let (client, _token, _admin) =
+            setup_contract_with_credit_line(&env, &borrower, 1_000, 1_000);
+        client.set_collateral_config(&CollateralConfig {
+            ltv_bps: 5_000,
+            unsecured_bps: 2_000,
+        });
+        let (collateral_token, _) = setup_token(&env, &borrower, 1_000_000);
+        client.set_collateral_token(&collateral_token, &true);
+        assert_eq!(
+            client.get_collateral_tokens(),
+            soroban_sdk::vec![&env, collateral_token.clone()]
+        );
+        client.deposit_collateral(&borrower, &collateral_token, &1_000_000);
+        // No oracle prices the token, so only the 200 unsecured portion is drawable.
+        client.draw_credit(&borrower, &201, &None);
+    }
+
+    #[test]
+    fn test_credit_token_collateral_counts_at_par() {
+        let env = Env::default();
+        env.mock_all_auths();
+        let borrower = Address::generate(&env);
+        let (client, token_address, _admin) =
+            setup_contract_with_credit_line(&env, &borrower, 1_000, 1_000);
+        client.set_collateral_config(&CollateralConfig {
+            ltv_bps: 5_000,
+            unsecured_bps: 2_000,
+        });
+        token::StellarAssetClient::new(&env, &token_address).mint(&borrower, &600);
+        client.deposit_collateral(&borrower, &token_address, &600);
+        // 200 unsecured + 600 * 50% = 500
+        client.draw_credit(&borrower, &500, &None);
+    }
+
+    #[test]
+    fn test_deposit_collateral_transfers_and_records_balance() {
+        let env = Env::default();
+        env.mock_all_auths();
+        let borrower = Address::generate(&env);
+        let (client, _token, _admin) = setup_contract_with_credit_line(&env, &borrower, 1_000, 0);
+        let (collateral_token, _) = setup_token(&env, &borrower, 500);
+        client.set_collateral_token(&collateral_token, &true);
+        client.deposit_collateral(&borrower, &collateral_token, &300);
+
+        let line = client.get_credit_line(&borrower).unwrap();
+        assert_eq!(line.collateral_token, Some(collateral_token.clone()));
+        assert_eq!(line.collateral_amount, 300);
+        let collateral_client = token::Client::new(&env, &collateral_token);
+        assert_eq!(collateral_client.balance(&borrower), 200);
+        assert_eq!(collateral_client.balance(&client.address), 300);
+    }
+
+    #[test]
+    #[should_panic(expected = "exceeds collateralized limit")]
+    fn test_draw_beyond_unsecured_portion_without_collateral_reverts() {
+        let env = Env::default();
+        env.mock_all_auths();
+        let borrower = Address::generate(&env);
+        let (client, _token, _admin) =
+            setup_contract_with_credit_line(&env, &borrower, 1_000, 1_000);
+        client.set_collateral_config(&CollateralConfig {
+            ltv_bps: 5_000,
+            unsecured_bps: 2_000,
+        });
+        client.draw_credit(&borrower, &201, &None);
+    }
+
+    #[test]
+    fn test_collateral_raises_drawable_amount_by_ltv() {
+        let env = Env::default();
+        env.mock_all_auths();
+        let borrower = Address::generate(&env);
+        let (client, token_address, _admin) =
+            setup_contract_with_credit_line(&env, &borrower, 1_000, 1_000);
+        client.set_collateral_config(&CollateralConfig {
+            ltv_bps: 5_000,
+            unsecured_bps: 2_000,
+        });
+        let (collateral_token, _) = setup_token(&env, &borrower, 600);
+        accept_collateral_at_par(&env, &client, &token_address, &collateral_token);
+        client.deposit_collateral(&borrower, &collateral_token, &600);
+        // 200 unsecured + 600 * 50% = 500
+        client.draw_credit(&borrower, &500, &None);
+        assert_eq!(
+            client.get_credit_line(&borrower).unwrap().utilized_amount,
+            500
+        );
+    }
+
+    #[test]
+    fn test_close_credit_line_returns_collateral() {
+        let env = Env::default();
+        env.mock_all_auths();
+        let borrower = Address::generate(&env);
+        let (client, _token, _admin) = setup_contract_with_credit_line(&env, &borrower, 1_000, 0);
+        let (collateral_token, _) = setup_token(&env, &borrower, 500);
+        client.set_collateral_token(&collateral_token, &true);
+        client.deposit_collateral(&borrower, &collateral_token, &500);
+        client.close_credit_line(&borrower, &borrower);
+
+        let line = client.get_credit_line(&borrower).unwrap();
+        assert_eq!(line.collateral_amount, 0);
+        assert_eq!(line.collateral_token, None);
+        assert_eq!(
+            token::Client::new(&env, &collateral_token).balance(&borrower),
+            500
+        );
+    }
+
+    #[test]
+    fn test_force_close_with_utilization_keeps_collateral() {
+        let env = Env::default();
+        env.mock_all_auths();
+        let borrower = Address::generate(&env);
+        let (client, _token, admin) =
+            setup_contract_with_credit_line(&env, &borrower, 1_000, 1_000);
+        let (collateral_token, _) = setup_token(&env, &borrower, 500);
+        client.set_collateral_token(&collateral_token, &true);
+        client.deposit_collateral(&borrower, &collateral_token, &500);
+        client.draw_credit(&borrower, &100, &None);
+        client.close_credit_line(&borrower, &admin);
+
+        let line = client.get_credit_line(&borrower).unwrap();
+        assert_eq!(line.status, CreditStatus::Closed);
+        assert_eq!(line.collateral_amount, 500);
+        assert_eq!(
+            token::Client::new(&env, &collateral_token).balance(&client.address),
+            500
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "collateral token mismatch")]
+    fn test_deposit_collateral_second_token_reverts() {
+        let env = Env::default();
+        env.mock_all_auths();
+        let borrower = Address::generate(&env);
+        let (client, _token, _admin) = setup_contract_with_credit_line(&env, &borrower, 1_000, 0);
+        let (first, _) = setup_token(&env, &borrower, 100);
+        let (second, _) = setup_token(&env, &borrower, 100);
+        client.set_collateral_token(&first, &true);
+        client.set_collateral_token(&second, &true);
+        client.deposit_collateral(&borrower, &first, &100);
+        client.deposit_collateral(&borrower, &second, &100);
+    }
+
+    #[test]
+    #[should_panic(expected = "credit line not active")]
+    fn test_deposit_collateral_on_defaulted_line_reverts() {
+        let env = Env::default();
+        env.mock_all_auths();
+        let borrower = Address::generate(&env);
+        let (client, _token, _admin) = setup_contract_with_credit_line(&env, &borrower, 1_000, 0);
+        let (collateral_token, _) = setup_token(&env, &borrower, 100);
+        client.default_credit_line(&borrower);
+        client.deposit_collateral(&borrower, &collateral_token, &100);
+    }
+
+    #[test]
+    #[should_panic(expected = "ltv_bps cannot exceed 10000")]
+    fn test_set_collateral_config_ltv_exceeds_max_reverts() {
+        let env = Env::default();
+        env.mock_all_auths();
+        let borrower = Address::generate(&env);
+        let (client, _token, _admin) = setup_contract_with_credit_line(&env, &borrower, 1_000, 0);
+        client.set_collateral_config(&CollateralConfig {
+            ltv_bps: 10_001,
+            unsecured_bps: 0,
+        });
+    }
+
+    // ── liquidation ───────────────────────────────────────────────────────────
+
+    /// Defaulted line with `drawn` outstanding and `collateral` deposited.
+    fn setup_defaulted_collateralized_line<'a>(
+        env: &'a Env,
+        borrower: &'a Address,
+        drawn: i128,
+        collateral: i128,
+    ) -> (CreditClient<'a>, Address, Address) {
+        let (client, token_address, admin) =
+            setup_contract_with_credit_line(env, borrower, 1_000, 1_000);
+        let (collateral_token, _) = setup_token(env, borrower, collateral);
+        accept_collateral_at_par(env, &client, &token_address, &collateral_token);
+        client.deposit_collateral(borrower, &collateral_token, &collateral);
+        client.draw_credit(borrower, &drawn, &None);
+        client.default_credit_line(borrower);
+        (client, collateral_token, admin)
+    }
+
+    #[test]
+    fn test_admin_liquidation_seizes_collateral_covering_debt() {
+        let env = Env::default();
+        env.mock_all_auths();
+        let borrower = Address::generate(&env);
+        let (client, collateral_token, admin) =
+            setup_defaulted_collateralized_line(&env, &borrower, 400, 1_000);
+        client.liquidate(&admin, &borrower);
+
+        let line = client.get_credit_line(&borrower).unwrap();
+        assert_eq!(line.utilized_amount, 0);
+        assert_eq!(line.collateral_amount, 600);
+        // No auction address: seized collateral stays in the contract reserve.
+        assert_eq!(
+            token::Client::new(&env, &collateral_token).balance(&client.address),
+            1_000
+        );
+    }
+
+    #[test]
+    fn test_liquidation_event_reports_debt_with_interest() {
+        use soroban_sdk::testutils::{Events, Ledger};
+        use soroban_sdk::TryIntoVal;
+        let env = Env::default();
+        env.mock_all_auths();
+        let borrower = Address::generate(&env);
+        let (client, collateral_token, admin) =
+            setup_defaulted_collateralized_line(&env, &borrower, 700, 300);
+        env.ledger()
+            .with_mut(|li| li.timestamp += 365 * 24 * 60 * 60);
+        client.accrue_interest(&borrower);
+        let line = client.get_credit_line(&borrower).unwrap();
+        let interest = line.accrued_interest;
+        assert!(interest > 0);
+        // Fresh prices a year on.
+        accept_collateral_at_par(&env, &client, &line.token, &collateral_token);
+
+        client.liquidate(&admin, &borrower);
+        let event: LiquidationEvent = env
+            .events()
+            .all()
+            .last()
+            .unwrap()
+            .2
+            .try_into_val(&env)
+            .unwrap();
+        assert_eq!(event.recovered_value, 300);
+        assert_eq!(event.remaining_debt, 400 + interest);
+    }
+
+    #[test]
+    fn test_liquidation_undercollateralized_leaves_bad_debt() {
+        use soroban_sdk::testutils::Events;
+        use soroban_sdk::TryIntoVal;
+        let env = Env::default();
+        env.mock_all_auths();
+        let borrower = Address::generate(&env);
+        let (client, collateral_token, admin) =
+            setup_defaulted_collateralized_line(&env, &borrower, 700, 300);
+        let auction = Address::generate(&env);
+        client.set_liquidation_config(&LiquidationConfig {
+            open_to_keepers: false,
+            keeper_bonus_bps: 0,
+            auction_address: Some(auction.clone()),
+        });
+        client.liquidate(&admin, &borrower);
+        let event: LiquidationEvent = env
+            .events()
+            .all()
+            .last()
+            .unwrap()
+            .2
+            .try_into_val(&env)
+            .unwrap();
+        assert_eq!(event.seized_amount, 300);
+        assert_eq!(event.recovered_value, 300);
+        assert_eq!(event.remaining_debt, 400);
+
+        let line = client.get_credit_line(&borrower).unwrap();
+        assert_eq!(line.utilized_amount, 400);
+        assert_eq!(line.collateral_amount, 0);
+        assert_eq!(line.collateral_token, None);
+        assert_eq!(
+            token::Client::new(&env, &collateral_token).balance(&auction),
+            300
+        );
+    }
+
+    #[test]
+    fn test_keeper_liquidation_pays_bonus() {
+        let env = Env::default();
+        env.mock_all_auths();
+        let borrower = Address::generate(&env);
+        let keeper = Address::generate(&env);
+        let (client, collateral_token, _admin) =
+            setup_defaulted_collateralized_line(&env, &borrower, 400, 1_000);
+        client.set_liquidation_config(&LiquidationConfig {
+            open_to_keepers: true,
+            keeper_bonus_bps: 500,
+            auction_address: None,
+        });
+        client.liquidate(&keeper, &borrower);
+
+        let line = client.get_credit_line(&borrower).unwrap();
+        assert_eq!(line.utilized_amount, 0);
+        // 400 debt + 5% bonus = 420 seized; 20 to the keeper.
+        assert_eq!(line.collateral_amount, 580);
+        assert_eq!(
+            token::Client::new(&env, &collateral_token).balance(&keeper),
+            20
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "unauthorized")]
+    fn test_keeper_liquidation_rejected_when_admin_only() {
+        let env = Env::default();
+        env.mock_all_auths();
+        let borrower = Address::generate(&env);
+        let (client, _collateral_token, _admin) =
+            setup_defaulted_collateralized_line(&env, &borrower, 400, 1_000);
+        client.liquidate(&Address::generate(&env), &borrower);
+    }
+
+    #[test]
+    #[should_panic(expected = "credit line not defaulted")]
+    fn test_liquidate_active_line_reverts() {
+        let env = Env::default();
+        env.mock_all_auths();
+        let borrower = Address::generate(&env);
+        let (client, _token, admin) =
+            setup_contract_with_credit_line(&env, &borrower, 1_000, 1_000);
+        let (collateral_token, _) = setup_token(&env, &borrower, 100);
+        client.set_collateral_token(&collateral_token, &true);
+        client.deposit_collateral(&borrower, &collateral_token, &100);
+        client.draw_credit(&borrower, &50, &None);
+        client.liquidate(&admin, &borrower);
+    }
+
+    #[test]
+    #[should_panic(expected = "collateral has no price")]
+    fn test_liquidate_unpriced_collateral_reverts() {
+        let env = Env::default();
+        env.mock_all_auths();
+        let borrower = Address::generate(&env);
+        let (client, _token, admin) =
+            setup_contract_with_credit_line(&env, &borrower, 1_000, 1_000);
+        let (collateral_token, _) = setup_token(&env, &borrower, 100);
+        client.set_collateral_token(&collateral_token, &true);
+        client.deposit_collateral(&borrower, &collateral_token, &100);
+        client.draw_credit(&borrower, &50, &None);
+        client.default_credit_line(&borrower);
+        client.liquidate(&admin, &borrower);
+    }
+
+    #[test]
+    #[should_panic(expected = "no collateral to liquidate")]
+    fn test_liquidate_without_collateral_reverts() {
+        let env = Env::default();
+        env.mock_all_auths();
+        let borrower = Address::generate(&env);
+        let (client, _token, admin) =
+            setup_contract_with_credit_line(&env, &borrower, 1_000, 1_000);
+        client.draw_credit(&borrower, &50, &None);
+        client.default_credit_line(&borrower);
+        client.liquidate(&admin, &borrower);
+    }
+
+    // ── price oracle ──────────────────────────────────────────────────────────
+
+    mod mock_oracle {
+        use crate::oracle::{Asset, PriceData};
+        use soroban_sdk::{contract, contractimpl, Address, Env};
+
+        #[contract]
+        pub struct MockOracle;
+
+        #[contractimpl]
+        impl MockOracle {
+            pub fn set_price(env: Env, token: Address, price: i128, timestamp: u64) {
+                env.storage()
+                    .instance()
+                    .set(&token, &PriceData { price, timestamp });
+            }
+
+            pub fn lastprice(env: Env, asset: Asset) -> Option<PriceData> {
+                match asset {
+                    Asset::Stellar(token) => env.storage().instance().get(&token),
+                    Asset::Other(_) => None,
+                }
+            }
+
+            pub fn decimals(_env: Env) -> u32 {
+                7
+            }
+        }
+    }
+
+    /// Line with `collateral` of a second token deposited and an oracle pricing
+    /// collateral at `collateral_price` and the credit token at 1_0000000.
+    fn setup_oracle_priced_collateral<'a>(
+        env: &'a Env,
+        borrower: &'a Address,
+        collateral: i128,
+        collateral_price: i128,
+    ) -> (CreditClient<'a>, mock_oracle::MockOracleClient<'a>, Address) {
+        use soroban_sdk::testutils::Ledger;
+        env.ledger().with_mut(|li| li.timestamp = 10_000);
+        let (client, credit_token, admin) =
+            setup_contract_with_credit_line(env, borrower, 1_000, 1_000);
+        let (collateral_token, _) = setup_token(env, borrower, collateral);
+        let oracle_id = env.register(mock_oracle::MockOracle, ());
+        let oracle = mock_oracle::MockOracleClient::new(env, &oracle_id);
+        oracle.set_price(&credit_token, &1_0000000, &10_000);
+        oracle.set_price(&collateral_token, &collateral_price, &10_000);
+        client.set_price_oracle(&oracle_id, &300);
+        client.set_collateral_token(&collateral_token, &true);
+        client.set_collateral_config(&CollateralConfig {
+            ltv_bps: 5_000,
+            unsecured_bps: 0,
+        });
+        client.deposit_collateral(borrower, &collateral_token, &collateral);
+        (client, oracle, admin)
+    }
+
+    #[test]
+    fn test_oracle_price_scales_collateral_value() {
+        let env = Env::default();
+        env.mock_all_auths();
+        let borrower = Address::generate(&env);
+        // 400 collateral at 2.0 = 800 credit units; 50% LTV = 400 drawable.
+        let (client, _oracle, _admin) =
+            setup_oracle_priced_collateral(&env, &borrower, 400, 2_0000000);
+        client.draw_credit(&borrower, &400, &None);
+        assert_eq!(
+            client.get_credit_line(&borrower).unwrap().utilized_amount,
+            400
+        );
+        assert_eq!(client.get_price_oracle().unwrap().max_age_secs, 300);
+    }
+
+    #[test]
+    #[should_panic(expected = "exceeds collateralized limit")]
+    fn test_oracle_price_caps_drawable_amount() {
+        let env = Env::default();
+        env.mock_all_auths();
+        let borrower = Address::generate(&env);
+        // 400 collateral at 0.5 = 200 credit units; 50% LTV = 100 drawable.
+        let (client, _oracle, _admin) =
+            setup_oracle_priced_collateral(&env, &borrower, 400, 5_000000);
+        client.draw_credit(&borrower, &101, &None);
+    }
+
+    #[test]
+    #[should_panic(expected = "stale oracle price")]
+    fn test_stale_oracle_price_reverts() {
+        use soroban_sdk::testutils::Ledger;
+        let env = Env::default();
+        env.mock_all_auths();
+        let borrower = Address::generate(&env);
+        let (client, _oracle, _admin) =
+            setup_oracle_priced_collateral(&env, &borrower, 400, 2_0000000);
+        env.ledger().with_mut(|li| li.timestamp = 10_301);
+        client.draw_credit(&borrower, &100, &None);
+    }
+
+    #[test]
+    fn test_oracle_valued_liquidation_seizes_price_adjusted_amount() {
+        let env = Env::default();
+        env.mock_all_auths();
+        let borrower = Address::generate(&env);
+        let (client, _oracle, admin) =
+            setup_oracle_priced_collateral(&env, &borrower, 400, 2_0000000);
+        client.draw_credit(&borrower, &300, &None);
+        client.default_credit_line(&borrower);
+        client.liquidate(&admin, &borrower);
+        // 300 debt at a collateral price of 2.0 needs 150 collateral.
+        let line = client.get_credit_line(&borrower).unwrap();
+        assert_eq!(line.utilized_amount, 0);
+        assert_eq!(line.collateral_amount, 250);
+    }
+
+    // ── reference-currency limits ─────────────────────────────────────────────
+
+    /// Line with a reference-currency limit of `reference_limit` and the credit
+    /// token priced at `price` reference units (7 decimals).
+    fn setup_reference_limit_line<'a>(
+        env: &'a Env,
+        borrower: &'a Address,
+        reference_limit: i128,
+        price: i128,
+    ) -> (
+        CreditClient<'a>,
+        mock_oracle::MockOracleClient<'a>,
+        Address,
+        Address,
+    ) {
+        use soroban_sdk::testutils::Ledger;
+        env.ledger().with_mut(|li| li.timestamp = 10_000);
+        let (client, token_address, admin) =
+            setup_contract_with_credit_line(env, borrower, 1_000, 1_000);
+        let oracle_id = env.register(mock_oracle::MockOracle, ());
+        let oracle = mock_oracle::MockOracleClient::new(env, &oracle_id);
+        oracle.set_price(&token_address, &price, &10_000);
+        client.set_price_oracle(&oracle_id, &300);
+        client.set_reference_limit(borrower, &reference_limit);
+        (client, oracle, token_address, admin)
+    }
+
+    #[test]
+    fn test_reference_limit_converts_to_token_units() {
+        let env = Env::default();
+        env.mock_all_auths();
+        let borrower = Address::generate(&env);
+        let (client, _oracle, _token, _admin) =
+            setup_reference_limit_line(&env, &borrower, 1_000, 2_0000000);
+        let line = client.get_credit_line(&borrower).unwrap();
+        assert_eq!(line.reference_limit, 1_000);
+        assert_eq!(line.credit_limit, 500);
+
+        client.draw_credit(&borrower, &500, &None);
+        let line = client.get_credit_line(&borrower).unwrap();
+        assert_eq!(line.utilized_amount, 500);
+        assert_eq!(line.reference_utilized, 1_000);
+    }
+
+    #[test]
+    #[should_panic(expected = "exceeds credit limit")]
+    fn test_reference_limit_rechecks_existing_debt_at_current_price() {
+        let env = Env::default();
+        env.mock_all_auths();
+        let borrower = Address::generate(&env);
+        let (client, oracle, token_address, _admin) =
+            setup_reference_limit_line(&env, &borrower, 1_000, 2_0000000);
+        client.draw_credit(&borrower, &200, &None);
+        // The token doubles against the reference currency: 300 now costs 1_200.
+        oracle.set_price(&token_address, &4_0000000, &10_000);
+        client.draw_credit(&borrower, &100, &None);
+    }
+
+    #[test]
+    fn test_repayment_reduces_reference_utilization_pro_rata() {
+        let env = Env::default();
+        env.mock_all_auths();
+        let borrower = Address::generate(&env);
+        let (client, oracle, token_address, _admin) =
+            setup_reference_limit_line(&env, &borrower, 1_000, 2_0000000);
+        client.draw_credit(&borrower, &400, &None);
+        // A stale feed must not block repayment.
+        oracle.set_price(&token_address, &2_0000000, &0);
+        client.repay_credit(&borrower, &100);
+        let line = client.get_credit_line(&borrower).unwrap();
+        assert_eq!(line.utilized_amount, 300);
+        assert_eq!(line.reference_utilized, 600);
+    }
+
+    #[test]
+    fn test_update_risk_parameters_sets_reference_limit() {
+        let env = Env::default();
+        env.mock_all_auths();
+        let borrower = Address::generate(&env);
+        let (client, _oracle, _token, admin) =
+            setup_reference_limit_line(&env, &borrower, 1_000, 2_0000000);
+        client.update_risk_parameters(&admin, &borrower, &3_000, &300_u32, &70_u32, &None);
+        let line = client.get_credit_line(&borrower).unwrap();
+        assert_eq!(line.reference_limit, 3_000);
+        assert_eq!(line.credit_limit, 1_500);
+    }
+
+    #[test]
+    #[should_panic(expected = "price oracle not configured")]
+    fn test_reference_limit_requires_oracle() {
+        let env = Env::default();
+        env.mock_all_auths();
+        let borrower = Address::generate(&env);
+        let (client, _token, _admin) = setup_contract_with_credit_line(&env, &borrower, 1_000, 0);
+        client.set_reference_limit(&borrower, &1_000);
+    }
+
+    // ── liquidity providers ───────────────────────────────────────────────────
+
+    #[test]
+    fn test_deposit_and_withdraw_liquidity() {
+        let env = Env::default();
+        env.mock_all_auths();
+        let admin = Address::generate(&env);
+        let lender = Address::generate(&env);
+        let contract_id = env.register(Credit, ());
+        let (token_address, _) = setup_token(&env, &lender, 1_000);
+        let client = CreditClient::new(&env, &contract_id);
+        client.init(&admin, &token_address);
+
+        client.deposit_liquidity(&lender, &token_address, &1_000);
+        assert_eq!(client.get_lender_shares(&lender, &token_address), 1_000);
+        assert_eq!(client.get_total_shares(&token_address), 1_000);
+        assert_eq!(client.get_pool_assets(&token_address), 1_000);
+
+        client.withdraw_liquidity(&lender, &token_address, &400);
+        assert_eq!(client.get_lender_shares(&lender, &token_address), 600);
+        assert_eq!(client.get_total_shares(&token_address), 600);
+        assert_eq!(client.get_pool_assets(&token_address), 600);
+        let token_client = token::Client::new(&env, &token_address);
+        assert_eq!(token_client.balance(&lender), 400);
+        assert_eq!(token_client.balance(&contract_id), 600);
+    }
+
+    #[test]
+    #[should_panic(expected = "insufficient shares")]
+    fn test_withdraw_liquidity_beyond_shares_reverts() {
+        let env = Env::default();
+        env.mock_all_auths();
+        let borrower = Address::generate(&env);
+        let lender = Address::generate(&env);
+        let (client, token_address, _admin) =
+            setup_contract_with_credit_line(&env, &borrower, 1, 1_000);
+        client.withdraw_liquidity(&lender, &token_address, &1);
+    }
+
+    #[test]
+    fn test_undrawn_commitments_track_line_changes() {
+        let env = Env::default();
+        env.mock_all_auths();
+        let borrower = Address::generate(&env);
+        let (client, token_address, admin) =
+            setup_contract_with_credit_line(&env, &borrower, 1_000, 1_000);
+        assert_eq!(client.get_undrawn_commitments(&token_address), 1_000);
+        client.draw_credit(&borrower, &300, &None);
+        assert_eq!(client.get_undrawn_commitments(&token_address), 700);
+        client.repay_credit(&borrower, &100);
+        assert_eq!(client.get_undrawn_commitments(&token_address), 800);
+        client.suspend_credit_line(&borrower, &0, &None);
+        assert_eq!(client.get_undrawn_commitments(&token_address), 0);
+        client.resume_credit_line(&borrower);
+        assert_eq!(client.get_undrawn_commitments(&token_address), 800);
+        client.update_risk_parameters(&admin, &borrower, &500, &300_u32, &70_u32, &None);
+        assert_eq!(client.get_undrawn_commitments(&token_address), 300);
+        client.close_credit_line(&borrower, &admin);
+        assert_eq!(client.get_undrawn_commitments(&token_address), 0);
+    }
+
+    #[test]
+    #[should_panic(expected = "withdrawal exceeds free liquidity")]
+    fn test_withdraw_liquidity_below_commitments_reverts() {
+        let env = Env::default();
+        env.mock_all_auths();
+        let borrower = Address::generate(&env);
+        let lender = Address::generate(&env);
+        let (client, token_address, _admin) =
+            setup_contract_with_credit_line(&env, &borrower, 600, 0);
+        token::StellarAssetClient::new(&env, &token_address).mint(&lender, &1_000);
+        client.deposit_liquidity(&lender, &token_address, &1_000);
+        client.withdraw_liquidity(&lender, &token_address, &401);
+    }
+
+    #[test]
+    fn test_withdraw_liquidity_up_to_free_reserve() {
+        let env = Env::default();
+        env.mock_all_auths();
+        let borrower = Address::generate(&env);
+        let lender = Address::generate(&env);
+        let (client, token_address, _admin) =
+            setup_contract_with_credit_line(&env, &borrower, 600, 0);
+        token::StellarAssetClient::new(&env, &token_address).mint(&lender, &1_000);
+        client.deposit_liquidity(&lender, &token_address, &1_000);
+        client.withdraw_liquidity(&lender, &token_address, &400);
+        assert_eq!(client.get_lender_shares(&lender, &token_address), 600);
+    }
+
+    // ── interest accrual and pool yield ───────────────────────────────────────
+
+    #[test]
+    fn test_interest_accrues_on_utilized_amount() {
+        use soroban_sdk::testutils::Ledger;
+        let env = Env::default();
+        env.mock_all_auths();
+        let borrower = Address::generate(&env);
+        let (client, _token, _admin) =
+            setup_contract_with_credit_line(&env, &borrower, 1_000, 1_000);
+        client.draw_credit(&borrower, &1_000, &None);
+        env.ledger()
+            .with_mut(|li| li.timestamp += math::SECONDS_PER_YEAR);
+        // Touch the line with a minimal repayment; 3% of 1_000 over a year = 30.
+        client.repay_credit(&borrower, &1);
+        let line = client.get_credit_line(&borrower).unwrap();
+        assert_eq!(line.accrued_interest, 29);
+        assert_eq!(line.utilized_amount, 1_000);
+    }
+
+    #[test]
+    fn test_accrue_interest_checkpoints_idle_line() {
+        use soroban_sdk::testutils::Ledger;
+        let env = Env::default();
+        env.mock_all_auths();
+        let borrower = Address::generate(&env);
+        let (client, _token, _admin) =
+            setup_contract_with_credit_line(&env, &borrower, 1_000, 1_000);
+        client.draw_credit(&borrower, &1_000, &None);
+        env.ledger()
+            .with_mut(|li| li.timestamp += math::SECONDS_PER_YEAR);
+
+        assert_eq!(client.accrue_interest(&borrower), 30);
+        let line = client.get_credit_line(&borrower).unwrap();
+        assert_eq!(line.accrued_interest, 30);
+        assert_eq!(line.last_accrual_ts, env.ledger().timestamp());
+        // A second checkpoint in the same ledger adds nothing.
+        assert_eq!(client.accrue_interest(&borrower), 0);
+    }
+
+    #[test]
+    fn test_accrue_many_skips_unknown_borrowers() {
+        use soroban_sdk::testutils::Ledger;
+        let env = Env::default();
+        env.mock_all_auths();
+        let borrower = Address::generate(&env);
+        let (client, _token, _admin) =
+            setup_contract_with_credit_line(&env, &borrower, 1_000, 1_000);
+        client.draw_credit(&borrower, &1_000, &None);
+        env.ledger()
+            .with_mut(|li| li.timestamp += math::SECONDS_PER_YEAR);
+
+        let batch = soroban_sdk::vec![&env, borrower.clone(), Address::generate(&env)];
+        assert_eq!(client.accrue_many(&batch), 30);
+        assert_eq!(
+            client.get_credit_line(&borrower).unwrap().accrued_interest,
+            30
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "Credit line not found")]
+    fn test_accrue_interest_unknown_borrower_reverts() {
+        let env = Env::default();
+        env.mock_all_auths();
+        let borrower = Address::generate(&env);
+        let (client, _token, _admin) = setup_contract_with_credit_line(&env, &borrower, 1_000, 0);
+        client.accrue_interest(&Address::generate(&env));
+    }
+
+    #[test]
+    fn test_actual_360_day_count_accrues_full_rate_in_360_days() {
+        use soroban_sdk::testutils::Ledger;
+        use types::{Compounding, DayCount};
+        let env = Env::default();
+        env.mock_all_auths();
+        let borrower = Address::generate(&env);
+        let (client, _token, admin) =
+            setup_contract_with_credit_line(&env, &borrower, 20_000, 20_000);
+        client.update_risk_parameters(&admin, &borrower, &20_000, &1_000, &70, &None);
+        client.set_line_interest_config(
+            &borrower,
+            &InterestConfig {
+                compounding: Compounding::Simple,
+                day_count: DayCount::Actual360,
+            },
+        );
+        client.draw_credit(&borrower, &10_000, &None);
+        env.ledger()
+            .with_mut(|li| li.timestamp += 360 * math::SECONDS_PER_DAY);
+
+        assert_eq!(client.accrue_interest(&borrower), 1_000);
+    }
+
+    #[test]
+    fn test_compounding_accrues_on_outstanding_balance() {
+        use soroban_sdk::testutils::Ledger;
+        use types::{Compounding, DayCount};
+        let env = Env::default();
+        env.mock_all_auths();
+        let borrower = Address::generate(&env);
+        let (client, _token, admin) =
+            setup_contract_with_credit_line(&env, &borrower, 200_000, 200_000);
+        client.set_interest_config(&InterestConfig {
+            compounding: Compounding::Daily,
+            day_count: DayCount::Actual365,
+        });
+        // Lines keep the convention they were opened with.
+        assert_eq!(
+            client.get_credit_line(&borrower).unwrap().interest_config,
+            InterestConfig::default()
+        );
+        let daily = Address::generate(&env);
+        client.open_credit_line(
+            &admin,
+            &daily,
+            &20_000,
+            &1_000,
+            &70,
+            &None,
+            &RateMode::Fixed,
+            &None,
+            &None,
+        );
+        client.draw_credit(&daily, &10_000, &None);
+        let monthly = Address::generate(&env);
+        client.open_credit_line(
+            &admin,
+            &monthly,
+            &200_000,
+            &1_200,
+            &70,
+            &None,
+            &RateMode::Fixed,
+            &None,
+            &None,
+        );
+        client.set_line_interest_config(
+            &monthly,
+            &InterestConfig {
+                compounding: Compounding::Monthly,
+                day_count: DayCount::Actual360,
+            },
+        );
+        client.draw_credit(&monthly, &100_000, &None);
+
+        // 1% a month for 12 months is ~12.68%.
+        env.ledger()
+            .with_mut(|li| li.timestamp += 360 * math::SECONDS_PER_DAY);
+        assert_eq!(client.accrue_interest(&monthly), 12_682);
+        // 10% compounded daily for 365 days is ~10.52%.
+        env.ledger()
+            .with_mut(|li| li.timestamp += 5 * math::SECONDS_PER_DAY);
+        assert_eq!(client.accrue_interest(&daily), 1_051);
+    }
+
+    #[test]
+    fn test_quote_interest_projects_without_writing() {
+        use soroban_sdk::testutils::Ledger;
+        let env = Env::default();
+        env.mock_all_auths();
+        let borrower = Address::generate(&env);
+        let (client, _token, _admin) =
+            setup_contract_with_credit_line(&env, &borrower, 1_000, 1_000);
+        client.draw_credit(&borrower, &1_000, &None);
+        env.ledger()
+            .with_mut(|li| li.timestamp += math::SECONDS_PER_YEAR);
+
+        assert_eq!(
+            client.quote_interest(&borrower, &math::SECONDS_PER_YEAR),
+            60
+        );
+        assert_eq!(
+            client.get_credit_line(&borrower).unwrap().accrued_interest,
+            0
+        );
+        assert_eq!(client.quote_interest(&borrower, &0), 30);
+    }
+
+    #[test]
+    fn test_line_interest_config_change_accrues_under_previous_convention() {
+        use soroban_sdk::testutils::Ledger;
+        use types::{Compounding, DayCount};
+        let env = Env::default();
+        env.mock_all_auths();
+        let borrower = Address::generate(&env);
+        let (client, _token, _admin) =
+            setup_contract_with_credit_line(&env, &borrower, 1_000, 1_000);
+        client.draw_credit(&borrower, &1_000, &None);
+        env.ledger()
+            .with_mut(|li| li.timestamp += math::SECONDS_PER_YEAR);
+        let config = InterestConfig {
+            compounding: Compounding::Daily,
+            day_count: DayCount::Actual360,
+        };
+
+        client.set_line_interest_config(&borrower, &config);
+        let line = client.get_credit_line(&borrower).unwrap();
+        assert_eq!(line.accrued_interest, 30);
+        assert_eq!(line.interest_config, config);
+    }
+
+    #[test]
+    fn test_promo_rate_falls_back_to_standard_rate() {
+        use soroban_sdk::testutils::{Events, Ledger};
+        use soroban_sdk::TryIntoVal;
+        let env = Env::default();
+        env.mock_all_auths();
+        let borrower = Address::generate(&env);
+        let (client, _token, admin) =
+            setup_contract_with_credit_line(&env, &borrower, 1_000, 1_000);
+        client.draw_credit(&borrower, &1_000, &None);
+        let promo_until = env.ledger().timestamp() + math::SECONDS_PER_YEAR / 2;
+
+        client.set_promo_rate(&admin, &borrower, &0, &promo_until);
+        let event = env
+            .events()
+            .all()
+            .iter()
+            .find_map(|(_contract, _topics, data)| {
+                TryIntoVal::<Env, PromoRateEvent>::try_into_val(&data, &env).ok()
+            })
+            .unwrap();
+        assert_eq!(event.promo_rate_bps, 0);
+        assert_eq!(event.promo_until_ts, promo_until);
+        assert_eq!(event.standard_rate_bps, 300);
+        let line = client.get_credit_line(&borrower).unwrap();
+        assert_eq!(line.promo_rate_bps, 0);
+        assert_eq!(line.promo_until_ts, promo_until);
+
+        // Half a year free, then half a year at 3%.
+        assert_eq!(
+            client.quote_interest(&borrower, &math::SECONDS_PER_YEAR),
+            15
+        );
+        env.ledger()
+            .with_mut(|li| li.timestamp += math::SECONDS_PER_YEAR);
+        assert_eq!(client.accrue_interest(&borrower), 15);
+        let line = client.get_credit_line(&borrower).unwrap();
+        assert_eq!(line.promo_until_ts, 0);
+        assert_eq!(line.interest_rate_bps, 300);
+    }
+
+    #[test]
+    fn test_risk_update_event_reports_active_promo_rate() {
+        use soroban_sdk::testutils::Events;
+        use soroban_sdk::TryIntoVal;
+        let env = Env::default();
+        env.mock_all_auths();
+        let borrower = Address::generate(&env);
+        let (client, _token, admin) = setup_contract_with_credit_line(&env, &borrower, 1_000, 0);
+        client.set_promo_rate(&admin, &borrower, &100, &1_000);
+
+        client.update_risk_parameters(&admin, &borrower, &1_000, &500, &70, &None);
+        let event = env
+            .events()
+            .all()
+            .iter()
+            .find_map(|(_contract, _topics, data)| {
+                TryIntoVal::<Env, RiskParametersUpdatedEvent>::try_into_val(&data, &env).ok()
+            })
+            .unwrap();
+        assert_eq!(event.interest_rate_bps, 500);
+        assert_eq!(event.active_rate_bps, 100);
+        assert_eq!(event.promo_until_ts, 1_000);
+    }
+
+    #[test]
+    fn test_grace_period_accrues_no_interest() {
+        use soroban_sdk::testutils::Ledger;
+        let env = Env::default();
+        env.mock_all_auths();
+        let first = Address::generate(&env);
+        let (client, _token, admin) = setup_contract_with_credit_line(&env, &first, 1_000, 2_000);
+        let grace = math::SECONDS_PER_YEAR / 2;
+        client.set_repayment_terms(&RepaymentTerms {
+            grace_period_secs: grace,
+            prepayment_penalty_bps: 0,
+        });
+        let borrower = Address::generate(&env);
+        client.open_credit_line(
+            &admin,
+            &borrower,
+            &1_000,
+            &300,
+            &70,
+            &None,
+            &RateMode::Fixed,
+            &None,
+            &None,
+        );
+        client.draw_credit(&borrower, &1_000, &None);
+
+        let next = client.get_next_payment(&borrower).unwrap();
+        assert_eq!(next.grace_until_ts, grace);
+        assert_eq!(next.min_due_amount, 0);
+        env.ledger()
+            .with_mut(|li| li.timestamp += math::SECONDS_PER_YEAR);
+        // Half a year of grace, then half a year at 3%.
+        assert_eq!(client.accrue_interest(&borrower), 15);
+        assert_eq!(client.get_next_payment(&borrower), None);
+    }
+
+    #[test]
+    fn test_grace_period_bills_no_minimum() {
+        use soroban_sdk::testutils::Ledger;
+        let env = Env::default();
+        env.mock_all_auths();
+        let first = Address::generate(&env);
+        let (client, _token, admin) = setup_contract_with_credit_line(&env, &first, 1_000, 2_000);
+        let day = math::SECONDS_PER_DAY;
+        client.set_repayment_terms(&RepaymentTerms {
+            grace_period_secs: 70 * day,
+            prepayment_penalty_bps: 0,
+        });
+        let borrower = Address::generate(&env);
+        client.open_credit_line(
+            &admin,
+            &borrower,
+            &1_000,
+            &300,
+            &70,
+            &Some(RepaymentSchedule {
+                cycle_length_secs: 30 * day,
+                min_payment_bps: 1_000,
+            }),
+            &RateMode::Fixed,
+            &None,
+            &None,
+        );
+        client.draw_credit(&borrower, &1_000, &None);
+
+        // The cycle due on day 60 falls inside the grace period and bills nothing.
+        env.ledger().with_mut(|li| li.timestamp = 35 * day);
+        let next = client.get_next_payment(&borrower).unwrap();
+        assert_eq!((next.due_ts, next.min_due_amount), (60 * day, 0));
+        env.ledger().with_mut(|li| li.timestamp = 65 * day);
+        let next = client.get_next_payment(&borrower).unwrap();
+        assert_eq!((next.due_ts, next.min_due_amount), (90 * day, 100));
+    }
+
+    #[test]
+    fn test_grace_period_over_many_cycles_bills_once() {
+        use soroban_sdk::testutils::Ledger;
+        let env = Env::default();
+        env.mock_all_auths();
+        let first = Address::generate(&env);
+        let (client, _token, admin) = setup_contract_with_credit_line(&env, &first, 1_000, 2_000);
+        let day = math::SECONDS_PER_DAY;
+        client.set_repayment_terms(&RepaymentTerms {
+            grace_period_secs: 30 * day,
+            prepayment_penalty_bps: 0,
+        });
+        let borrower = Address::generate(&env);
+        client.open_credit_line(
+            &admin,
+            &borrower,
+            &1_000,
+            &300,
+            &70,
+            &Some(RepaymentSchedule {
+                cycle_length_secs: 60,
+                min_payment_bps: 1_000,
+            }),
+            &RateMode::Fixed,
+            &None,
+            &None,
+        );
+        client.draw_credit(&borrower, &400, &None);
+
+        // 43_200 one-minute cycles fall inside the grace period.
+        env.ledger().with_mut(|li| li.timestamp = 30 * day + 30);
+        let next = client.get_next_payment(&borrower).unwrap();
+        assert_eq!((next.due_ts, next.min_due_amount), (30 * day + 60, 40));
+        client.repay_credit(&borrower, &40);
+        let line = client.get_credit_line(&borrower).unwrap();
+        assert_eq!((line.next_due_ts, line.min_due_amount), (30 * day + 60, 0));
+    }
+
+    #[test]
+    fn test_prepayment_penalty_charged_on_early_payoff() {
+        use soroban_sdk::testutils::Events;
+        use soroban_sdk::TryIntoVal;
+        let env = Env::default();
+        env.mock_all_auths();
+        let first = Address::generate(&env);
+        let (client, token, admin) = setup_contract_with_credit_line(&env, &first, 1_000, 2_000);
+        client.set_repayment_terms(&RepaymentTerms {
+            grace_period_secs: 0,
+            prepayment_penalty_bps: 200,
+        });
+        let borrower = Address::generate(&env);
+        client.open_credit_line(
+            &admin,
+            &borrower,
+            &1_000,
+            &300,
+            &70,
+            &None,
+            &RateMode::Fixed,
+            &None,
+            &None,
+        );
+        client.set_maturity(&borrower, &1_000);
+        client.draw_credit(&borrower, &1_000, &None);
+        token::StellarAssetClient::new(&env, &token).mint(&borrower, &20);
+        assert_eq!(
+            client
+                .get_next_payment(&borrower)
+                .unwrap()
+                .prepayment_penalty,
+            20
+        );
+        assert_eq!(client.preview_repay(&borrower, &400).prepayment_penalty, 0);
+        assert_eq!(
+            client.preview_repay(&borrower, &1_000).prepayment_penalty,
+            20
+        );
+
+        client.repay_credit(&borrower, &1_000);
+        let event = env
+            .events()
+            .all()
+            .iter()
+            .find_map(|(_contract, _topics, data)| {
+                TryIntoVal::<Env, RepaymentEvent>::try_into_val(&data, &env).ok()
+            })
+            .unwrap();
+        assert_eq!(event.amount, 1_000);
+        assert_eq!(event.prepayment_penalty, 20);
+        assert_eq!(token::Client::new(&env, &token).balance(&borrower), 0);
+        assert_eq!(client.get_protocol_fees(&token), 20);
+    }
+
+    #[test]
+    #[should_panic(expected = "promo expiry must be in the future")]
+    fn test_promo_rate_expiry_must_be_in_future() {
+        let env = Env::default();
+        env.mock_all_auths();
+        let borrower = Address::generate(&env);
+        let (client, _token, admin) = setup_contract_with_credit_line(&env, &borrower, 1_000, 0);
+        client.set_promo_rate(&admin, &borrower, &100, &env.ledger().timestamp());
+    }
+
+    #[test]
+    #[should_panic(expected = "unauthorized")]
+    fn test_set_promo_rate_requires_risk_engine() {
+        let env = Env::default();
+        env.mock_all_auths();
+        let borrower = Address::generate(&env);
+        let (client, _token, _admin) = setup_contract_with_credit_line(&env, &borrower, 1_000, 0);
+        client.set_promo_rate(&Address::generate(&env), &borrower, &100, &1_000);
+    }
+
+    #[test]
+    fn test_preview_draw_matches_draw() {
+        let env = Env::default();
+        env.mock_all_auths();
+        let borrower = Address::generate(&env);
+        let (client, token_address, _admin) =
+            setup_contract_with_credit_line(&env, &borrower, 1_000, 1_000);
+        client.set_fee_config(&FeeConfig {
+            draw_fee_bps: 100,
+            origination_fee: 0,
+        });
+        let preview = client.preview_draw(&borrower, &400, &None);
+        assert_eq!(preview.error, 0);
+        assert_eq!(preview.fee, 4);
+        assert_eq!(preview.net_amount, 396);
+        assert_eq!(preview.new_utilized_amount, 400);
+        assert_eq!(preview.remaining_available, 600);
+
+        client.draw_credit(&borrower, &400, &None);
+        assert_eq!(
+            token::Client::new(&env, &token_address).balance(&borrower),
+            preview.net_amount
+        );
+        assert_eq!(
+            client.get_credit_line(&borrower).unwrap().utilized_amount,
+            preview.new_utilized_amount
+        );
+    }
+
+    #[test]
+    fn test_preview_draw_reports_revert_reason() {
+        let env = Env::default();
+        env.mock_all_auths();
+        let borrower = Address::generate(&env);
+        let (client, _token, admin) =
+            setup_contract_with_credit_line(&env, &borrower, 1_000, 1_000);
+        let preview = client.preview_draw(&borrower, &1_001, &None);
+        assert_eq!(preview.error, ContractError::CreditLimitExceeded as u32);
+        assert_eq!(preview.new_utilized_amount, 0);
+        assert_eq!(
+            client
+                .preview_draw(&Address::generate(&env), &1, &None)
+                .error,
+            ContractError::LineNotFound as u32
+        );
+        client.pause(&admin, &true, &false);
+        assert_eq!(
+            client.preview_draw(&borrower, &100, &None).error,
+            ContractError::Paused as u32
+        );
+    }
+
+    #[test]
+    fn test_preview_repay_matches_repay_split() {
+        use soroban_sdk::testutils::{Events, Ledger};
+        use soroban_sdk::TryIntoVal;
+        let env = Env::default();
+        env.mock_all_auths();
+        let borrower = Address::generate(&env);
+        let (client, _token, _admin) =
+            setup_contract_with_credit_line(&env, &borrower, 1_000, 1_000);
+        client.set_reserve_factor_bps(&1_000);
+        client.draw_credit(&borrower, &1_000, &None);
+        env.ledger()
+            .with_mut(|li| li.timestamp += math::SECONDS_PER_YEAR);
+
+        let preview = client.preview_repay(&borrower, &500);
+        assert_eq!(preview.error, 0);
+        assert_eq!(preview.interest_portion, 30);
+        assert_eq!(preview.principal_portion, 470);
+        assert_eq!(preview.fee_portion, 3);
+        assert_eq!(preview.new_utilized_amount, 530);
+
+        client.repay_credit(&borrower, &500);
+        let event: RepaymentEvent = env
+            .events()
+            .all()
+            .last()
+            .unwrap()
+            .2
+            .try_into_val(&env)
+            .unwrap();
+        assert_eq!(event.amount, preview.applied);
+        assert_eq!(event.interest_portion, preview.interest_portion);
+        assert_eq!(event.fee_portion, preview.fee_portion);
+    }
+
+    #[test]
+    fn test_repay_settles_interest_then_principal_and_pulls_tokens() {
+        use soroban_sdk::testutils::Ledger;
+        let env = Env::default();
+        env.mock_all_auths();
+        let borrower = Address::generate(&env);
+        let (client, token_address, _admin) =
+            setup_contract_with_credit_line(&env, &borrower, 1_000, 1_000);
+        client.draw_credit(&borrower, &1_000, &None);
+        env.ledger()
+            .with_mut(|li| li.timestamp += math::SECONDS_PER_YEAR);
+        client.repay_credit(&borrower, &500);
+
+        let line = client.get_credit_line(&borrower).unwrap();
+        assert_eq!(line.accrued_interest, 0);
+        assert_eq!(line.utilized_amount, 530);
+        let token_client = token::Client::new(&env, &token_address);
+        assert_eq!(token_client.balance(&borrower), 500);
+        assert_eq!(token_client.balance(&client.address), 500);
+    }
+
+    #[test]
+    fn test_repaid_interest_raises_share_price() {
+        use soroban_sdk::testutils::Ledger;
+        let env = Env::default();
+        env.mock_all_auths();
+        let borrower = Address::generate(&env);
+        let lender = Address::generate(&env);
+        let late_lender = Address::generate(&env);
+        let (client, token_address, _admin) =
+            setup_contract_with_credit_line(&env, &borrower, 1_000, 0);
+        let sac = token::StellarAssetClient::new(&env, &token_address);
+        sac.mint(&lender, &10_000);
+        sac.mint(&borrower, &30);
+        sac.mint(&late_lender, &1_003);
+
+        client.deposit_liquidity(&lender, &token_address, &10_000);
+        assert_eq!(client.get_share_price(&token_address), 10_000_000);
+        client.draw_credit(&borrower, &1_000, &None);
+        env.ledger()
+            .with_mut(|li| li.timestamp += math::SECONDS_PER_YEAR);
+        client.repay_credit(&borrower, &1_030);
+
+        assert_eq!(client.get_pool_assets(&token_address), 10_030);
+        assert_eq!(client.get_share_price(&token_address), 10_030_000);
+        assert_eq!(client.convert_to_assets(&token_address, &10_000), 10_030);
+        assert_eq!(client.convert_to_shares(&token_address, &1_003), 1_000);
+
+        client.deposit_liquidity(&late_lender, &token_address, &1_003);
+        assert_eq!(
+            client.get_lender_shares(&late_lender, &token_address),
+            1_000
+        );
+        assert_eq!(
+            client.convert_to_assets(
+                &token_address,
+                &client.get_lender_shares(&lender, &token_address)
+            ),
+            10_030
+        );
+    }
+
+    #[test]
+    fn test_lender_withdraws_principal_plus_yield() {
+        use soroban_sdk::testutils::Ledger;
+        let env = Env::default();
+        env.mock_all_auths();
+        let borrower = Address::generate(&env);
+        let lender = Address::generate(&env);
+        let (client, token_address, admin) =
+            setup_contract_with_credit_line(&env, &borrower, 1_000, 0);
+        let sac = token::StellarAssetClient::new(&env, &token_address);
+        sac.mint(&lender, &10_000);
+        sac.mint(&borrower, &30);
+
+        client.deposit_liquidity(&lender, &token_address, &10_000);
+        client.draw_credit(&borrower, &1_000, &None);
+        env.ledger()
+            .with_mut(|li| li.timestamp += math::SECONDS_PER_YEAR);
+        client.repay_credit(&borrower, &1_030);
+        client.close_credit_line(&borrower, &admin);
+
+        client.withdraw_liquidity(&lender, &token_address, &10_030);
+        assert_eq!(client.get_lender_shares(&lender, &token_address), 0);
+        assert_eq!(
+            token::Client::new(&env, &token_address).balance(&lender),
+            10_030
+        );
+    }
+
+    // ── reserve factor and treasury ───────────────────────────────────────────
+
+    #[test]
+    fn test_reserve_factor_skims_interest_to_protocol() {
+        use soroban_sdk::testutils::Ledger;
+        let env = Env::default();
+        env.mock_all_auths();
+        let borrower = Address::generate(&env);
+        let lender = Address::generate(&env);
+        let treasurer = Address::generate(&env);
+        let treasury_addr = Address::generate(&env);
+        let (client, token_address, _admin) =
+            setup_contract_with_credit_line(&env, &borrower, 1_000, 0);
+        let sac = token::StellarAssetClient::new(&env, &token_address);
+        sac.mint(&lender, &10_000);
+        sac.mint(&borrower, &30);
+        client.set_treasury(&treasury_addr);
+        client.set_reserve_factor_bps(&2_000);
+        client.grant_role(&Role::Treasurer, &treasurer);
+
+        client.deposit_liquidity(&lender, &token_address, &10_000);
+        client.draw_credit(&borrower, &1_000, &None);
+        env.ledger()
+            .with_mut(|li| li.timestamp += math::SECONDS_PER_YEAR);
+        client.repay_credit(&borrower, &1_030);
+
+        // 30 interest: 20% (6) to the protocol, 24 to the pool.
+        assert_eq!(client.get_protocol_fees(&token_address), 6);
+        assert_eq!(client.get_pool_assets(&token_address), 10_024);
+
+        assert_eq!(client.collect_protocol_fees(&treasurer, &token_address), 6);
+        assert_eq!(client.get_protocol_fees(&token_address), 0);
+        assert_eq!(
+            token::Client::new(&env, &token_address).balance(&treasury_addr),
+            6
+        );
+    }
+
+    #[test]
+    fn test_revenue_split_divides_interest_between_buckets() {
+        use soroban_sdk::testutils::{Events, Ledger};
+        use soroban_sdk::{TryFromVal, TryIntoVal};
+        let env = Env::default();
+        env.mock_all_auths();
+        let borrower = Address::generate(&env);
+        let lender = Address::generate(&env);
+        let (client, token_address, _admin) =
+            setup_contract_with_credit_line(&env, &borrower, 1_000, 0);
+        let sac = token::StellarAssetClient::new(&env, &token_address);
+        sac.mint(&lender, &10_000);
+        sac.mint(&borrower, &30);
+        client.set_reserve_factor_bps(&2_000);
+        assert_eq!(
+            client.get_revenue_split(),
+            RevenueSplit {
+                lp_bps: 8_000,
+                treasury_bps: 2_000,
+                insurance_bps: 0,
+            }
+        );
+        client.set_revenue_split(&RevenueSplit {
+            lp_bps: 7_000,
+            treasury_bps: 1_500,
+            insurance_bps: 1_500,
+        });
+
+        client.deposit_liquidity(&lender, &token_address, &10_000);
+        client.draw_credit(&borrower, &1_000, &None);
+        env.ledger()
+            .with_mut(|li| li.timestamp += math::SECONDS_PER_YEAR);
+        client.repay_credit(&borrower, &1_030);
+
+        // 30 interest: 4 to the treasury and 4 to insurance, rounded down; 22 to the pool.
+        let (_contract, _topics, data) = env
+            .events()
+            .all()
+            .iter()
+            .find(|(_, topics, _)| {
+                Symbol::try_from_val(&env, &topics.get(1).unwrap()).ok()
+                    == Some(symbol_short!("rev_split"))
+            })
+            .unwrap();
+        let event: RevenueSplitEvent = data.try_into_val(&env).unwrap();
+        assert_eq!(event.interest, 30);
+        assert_eq!(event.lp_share, 22);
+        assert_eq!(event.treasury_share, 4);
+        assert_eq!(event.insurance_share, 4);
+
+        assert_eq!(client.get_protocol_fees(&token_address), 4);
+        assert_eq!(client.get_insurance_fund(&token_address), 4);
+        assert_eq!(client.get_pool_assets(&token_address), 10_022);
+        assert_eq!(
+            client.get_revenue_distributed(&token_address),
+            RevenueShares {
+                lp: 22,
+                treasury: 4,
+                insurance: 4,
+            }
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "revenue split must sum to 10000")]
+    fn test_revenue_split_not_summing_to_whole_reverts() {
+        let env = Env::default();
+        env.mock_all_auths();
+        let borrower = Address::generate(&env);
+        let (client, _token, _admin) = setup_contract_with_credit_line(&env, &borrower, 1_000, 0);
+        client.set_revenue_split(&RevenueSplit {
+            lp_bps: 7_000,
+            treasury_bps: 2_000,
+            insurance_bps: 500,
+        });
+    }
+
+    #[test]
+    #[should_panic(expected = "unauthorized")]
+    fn test_collect_protocol_fees_requires_treasurer_role() {
+        let env = Env::default();
+        env.mock_all_auths();
+        let borrower = Address::generate(&env);
+        let (client, token_address, _admin) =
+            setup_contract_with_credit_line(&env, &borrower, 1_000, 0);
+        client.set_treasury(&Address::generate(&env));
+        client.collect_protocol_fees(&Address::generate(&env), &token_address);
+    }
+
+    #[test]
+    #[should_panic(expected = "treasury not set")]
+    fn test_collect_protocol_fees_without_treasury_reverts() {
+        let env = Env::default();
+        env.mock_all_auths();
+        let borrower = Address::generate(&env);
+        let (client, token_address, admin) =
+            setup_contract_with_credit_line(&env, &borrower, 1_000, 0);
+        client.collect_protocol_fees(&admin, &token_address);
+    }
+
+    #[test]
+    #[should_panic(expected = "reserve_factor_bps cannot exceed 10000")]
+    fn test_set_reserve_factor_bps_exceeds_max_reverts() {
+        let env = Env::default();
+        env.mock_all_auths();
+        let borrower = Address::generate(&env);
+        let (client, _token, _admin) = setup_contract_with_credit_line(&env, &borrower, 1_000, 0);
+        client.set_reserve_factor_bps(&10_001);
+    }
+
+    // ── draw and origination fees ─────────────────────────────────────────────
+
+    #[test]
+    fn test_draw_fee_withheld_from_disbursement() {
+        use soroban_sdk::testutils::Events;
+        use soroban_sdk::{TryFromVal, TryIntoVal};
+        let env = Env::default();
+        env.mock_all_auths();
+        let borrower = Address::generate(&env);
+        let (client, token_address, _admin) =
+            setup_contract_with_credit_line(&env, &borrower, 1_000, 1_000);
+        client.set_fee_config(&FeeConfig {
+            draw_fee_bps: 100,
+            origination_fee: 0,
+        });
+
+        client.draw_credit(&borrower, &500, &None);
+
+        let events = env.events().all();
+        let (_contract, topics, data) = events.get(events.len() - 2).unwrap();
+        assert_eq!(
+            Symbol::try_from_val(&env, &topics.get(1).unwrap()).unwrap(),
+            symbol_short!("fee")
+        );
+        let event: FeeChargedEvent = data.try_into_val(&env).unwrap();
+        assert_eq!(event.fee_type, symbol_short!("draw"));
+        assert_eq!(event.amount, 5);
+
+        assert_eq!(
+            client.get_credit_line(&borrower).unwrap().utilized_amount,
+            500
+        );
+        assert_eq!(
+            token::Client::new(&env, &token_address).balance(&borrower),
+            495
+        );
+        assert_eq!(client.get_protocol_fees(&token_address), 5);
+    }
+
+    #[test]
+    fn test_origination_fee_added_to_utilized() {
+        use soroban_sdk::testutils::Events;
+        use soroban_sdk::TryIntoVal;
+        let env = Env::default();
+        env.mock_all_auths();
+        let admin = Address::generate(&env);
+        let borrower = Address::generate(&env);
+        let contract_id = env.register(Credit, ());
+        let client = CreditClient::new(&env, &contract_id);
+        let (token_address, _) = setup_token(&env, &contract_id, 0);
+        client.init(&admin, &token_address);
+        client.set_fee_config(&FeeConfig {
+            draw_fee_bps: 0,
+            origination_fee: 25,
+        });
+
+        client.open_credit_line(
+            &admin,
+            &borrower,
+            &1_000,
+            &300,
+            &70,
+            &None,
+            &RateMode::Fixed,
+            &None,
+            &None,
+        );
+
+        let events = env.events().all();
+        let (_contract, _topics, data) = events.get(events.len() - 1).unwrap();
+        let event: FeeChargedEvent = data.try_into_val(&env).unwrap();
+        assert_eq!(event.fee_type, symbol_short!("origin"));
+        assert_eq!(event.amount, 25);
+
+        assert_eq!(
+            client.get_credit_line(&borrower).unwrap().utilized_amount,
+            25
+        );
+        assert_eq!(client.get_protocol_fees(&token_address), 25);
+    }
+
+    #[test]
+    #[should_panic(expected = "origination fee exceeds credit limit")]
+    fn test_origination_fee_exceeding_limit_reverts() {
+        let env = Env::default();
+        env.mock_all_auths();
+        let admin = Address::generate(&env);
+        let contract_id = env.register(Credit, ());
+        let client = CreditClient::new(&env, &contract_id);
+        let (token_address, _) = setup_token(&env, &contract_id, 0);
+        client.init(&admin, &token_address);
+        client.set_fee_config(&FeeConfig {
+            draw_fee_bps: 0,
+            origination_fee: 1_001,
+        });
+        client.open_credit_line(
+            &admin,
+            &Address::generate(&env),
+            &1_000,
+            &300,
+            &70,
+            &None,
+            &RateMode::Fixed,
+            &None,
+            &None,
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "draw_fee_bps cannot exceed 10000")]
+    fn test_set_fee_config_draw_fee_exceeds_max_reverts() {
+        let env = Env::default();
+        env.mock_all_auths();
+        let borrower = Address::generate(&env);
+        let (client, _token, _admin) = setup_contract_with_credit_line(&env, &borrower, 1_000, 0);
+        client.set_fee_config(&FeeConfig {
+            draw_fee_bps: 10_001,
+            origination_fee: 0,
+        });
+    }
+
+    // ── draw to recipient ─────────────────────────────────────────────────────
+
+    #[test]
+    fn test_draw_credit_to_sends_funds_to_recipient() {
+        use soroban_sdk::testutils::Events;
+        use soroban_sdk::TryIntoVal;
+        let env = Env::default();
+        env.mock_all_auths();
+        let borrower = Address::generate(&env);
+        let merchant = Address::generate(&env);
+        let (client, token_address, _admin) =
+            setup_contract_with_credit_line(&env, &borrower, 1_000, 1_000);
+
+        client.draw_credit_to(&borrower, &merchant, &400);
+
+        let events = env.events().all();
+        let drawn: DrawnEvent = events
+            .get(events.len() - 2)
+            .unwrap()
+            .2
+            .try_into_val(&env)
+            .unwrap();
+        assert_eq!(drawn.borrower, borrower);
+        assert_eq!(drawn.recipient, merchant);
+        assert_eq!(drawn.amount, 400);
+
+        let token_client = token::Client::new(&env, &token_address);
+        assert_eq!(token_client.balance(&merchant), 400);
+        assert_eq!(token_client.balance(&borrower), 0);
+        assert_eq!(
+            client.get_credit_line(&borrower).unwrap().utilized_amount,
+            400
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "exceeds credit limit")]
+    fn test_draw_credit_to_enforces_limit() {
+        let env = Env::default();
+        env.mock_all_auths();
+        let borrower = Address::generate(&env);
+        let (client, _token, _admin) =
+            setup_contract_with_credit_line(&env, &borrower, 1_000, 2_000);
+        client.draw_credit_to(&borrower, &Address::generate(&env), &1_001);
+    }
+
+    // ── borrower enumeration ──────────────────────────────────────────────────
+
+    #[test]
+    fn test_list_borrowers_tracks_open_and_close() {
+        let env = Env::default();
+        env.mock_all_auths();
+        let (b1, b2, b3) = (
+            Address::generate(&env),
+            Address::generate(&env),
+            Address::generate(&env),
+        );
+        let (client, _token, admin) = setup_contract_with_credit_line(&env, &b1, 1_000, 0);
+        client.open_credit_line(
+            &admin,
+            &b2,
+            &1_000,
+            &300,
+            &70,
+            &None,
+            &RateMode::Fixed,
+            &None,
+            &None,
+        );
+        client.open_credit_line(
+            &admin,
+            &b3,
+            &1_000,
+            &300,
+            &70,
+            &None,
+            &RateMode::Fixed,
+            &None,
+            &None,
+        );
+        assert_eq!(client.count_borrowers(), 3);
+        assert_eq!(
+            client.list_borrowers(&0, &10),
+            Vec::from_array(&env, [b1.clone(), b2.clone(), b3.clone()])
+        );
+        assert_eq!(
+            client.list_borrowers(&1, &1),
+            Vec::from_array(&env, [b2.clone()])
+        );
+        assert_eq!(client.list_borrowers(&5, &10).len(), 0);
+
+        // Closing swaps the last borrower into the freed slot.
+        client.close_credit_line(&b1, &admin);
+        assert_eq!(client.count_borrowers(), 2);
+        assert_eq!(
+            client.list_borrowers(&0, &10),
+            Vec::from_array(&env, [b3.clone(), b2.clone()])
+        );
+
+        // Reopening after close re-indexes the borrower.
+        client.open_credit_line(
+            &admin,
+            &b1,
+            &1_000,
+            &300,
+            &70,
+            &None,
+            &RateMode::Fixed,
+            &None,
+            &None,
+        );
+        assert_eq!(
+            client.list_borrowers(&0, &10),
+            Vec::from_array(&env, [b3, b2, b1])
+        );
+    }
+
+    #[test]
+    fn test_list_borrowers_spans_chunks() {
+        let env = Env::default();
+        env.mock_all_auths();
+        let first = Address::generate(&env);
+        let (client, _token, admin) = setup_contract_with_credit_line(&env, &first, 1_000, 0);
+        let mut last = first.clone();
+        for _ in 1..70 {
+            last = Address::generate(&env);
+            client.open_credit_line(
+                &admin,
+                &last,
+                &1_000,
+                &300,
+                &70,
+                &None,
+                &RateMode::Fixed,
+                &None,
+                &None,
+            );
+        }
+        assert_eq!(client.count_borrowers(), 70);
+        let page = client.list_borrowers(&60, &20);
+        assert_eq!(page.len(), 10);
+        assert_eq!(page.get(9).unwrap(), last);
+
+        client.close_credit_line(&first, &admin);
+        assert_eq!(client.count_borrowers(), 69);
+        assert_eq!(client.list_borrowers(&0, &1).get(0).unwrap(), last);
+    }
+
+    // ── audit log ─────────────────────────────────────────────────────────────
+
+    #[test]
+    fn test_admin_operations_recorded_in_audit_log() {
+        use soroban_sdk::testutils::Ledger;
+        let env = Env::default();
+        env.mock_all_auths();
+        let borrower = Address::generate(&env);
+        let (client, _token, admin) = setup_contract_with_credit_line(&env, &borrower, 1_000, 0);
+        env.ledger().with_mut(|li| li.timestamp = 500);
+        client.set_dust_threshold(&10);
+        client.suspend_credit_line(&borrower, &0, &None);
+        client.default_credit_line(&borrower);
+
+        assert_eq!(client.get_audit_count(), 4);
+        let log = client.get_audit_log(&0, &10);
+        assert_eq!(
+            log.get(0).unwrap(),
+            AuditEntry {
+                actor: admin.clone(),
+                action: symbol_short!("open"),
+                subject: Some(borrower.clone()),
+                ts: 0,
+            }
+        );
+        assert_eq!(
+            log.get(1).unwrap(),
+            AuditEntry {
+                actor: admin.clone(),
+                action: symbol_short!("dust"),
+                subject: None,
+                ts: 500,
+            }
+        );
+        assert_eq!(log.get(2).unwrap().action, symbol_short!("suspend"));
+        assert_eq!(log.get(3).unwrap().action, symbol_short!("default"));
+        assert_eq!(log.get(3).unwrap().subject, Some(borrower));
+    }
+
+    #[test]
+    fn test_audit_log_spans_chunks() {
+        let env = Env::default();
+        env.mock_all_auths();
+        let borrower = Address::generate(&env);
+        let (client, _token, _admin) = setup_contract_with_credit_line(&env, &borrower, 1_000, 0);
+        for threshold in 1..70 {
+            client.set_dust_threshold(&threshold);
+        }
+        assert_eq!(client.get_audit_count(), 70);
+        let page = client.get_audit_log(&60, &20);
+        assert_eq!(page.len(), 10);
+        assert!(page
+            .iter()
+            .all(|entry| entry.action == symbol_short!("dust")));
+        assert_eq!(client.get_audit_log(&70, &5).len(), 0);
+    }
+
+    // ── protocol statistics ───────────────────────────────────────────────────
+
+    #[test]
+    fn test_protocol_stats_track_lifecycle() {
+        let env = Env::default();
+        env.mock_all_auths();
+        let b1 = Address::generate(&env);
+        let b2 = Address::generate(&env);
+        let (client, token_address, admin) =
+            setup_contract_with_credit_line(&env, &b1, 1_000, 2_000);
+        client.open_credit_line(
+            &admin,
+            &b2,
+            &1_000,
+            &300,
+            &70,
+            &None,
+            &RateMode::Fixed,
+            &None,
+            &None,
+        );
+        client.draw_credit(&b1, &400, &None);
+        client.draw_credit(&b2, &300, &None);
+        token::StellarAssetClient::new(&env, &token_address).mint(&b1, &100);
+        client.repay_credit(&b1, &100);
+        client.default_credit_line(&b2);
+
+        let stats = client.get_protocol_stats();
+        assert_eq!(stats.total_lines_opened, 2);
+        assert_eq!(stats.total_active, 1);
+        assert_eq!(stats.total_outstanding_principal, 600);
+        assert_eq!(stats.total_repaid, 100);
+        assert_eq!(stats.total_defaulted_amount, 300);
+
+        client.close_credit_line(&b2, &admin);
+        client.open_credit_line(
+            &admin,
+            &b2,
+            &1_000,
+            &300,
+            &70,
+            &None,
+            &RateMode::Fixed,
+            &None,
+            &None,
+        );
+        let stats = client.get_protocol_stats();
+        assert_eq!(stats.total_lines_opened, 3);
+        assert_eq!(stats.total_active, 2);
+    }
+
+    #[test]
+    fn test_protocol_stats_split_repayments() {
+        use soroban_sdk::testutils::Ledger;
+        let env = Env::default();
+        env.mock_all_auths();
+        let borrower = Address::generate(&env);
+        let (client, _token, _admin) =
+            setup_contract_with_credit_line(&env, &borrower, 1_000, 1_000);
+        client.set_reserve_factor_bps(&1_000);
+        client.draw_credit(&borrower, &1_000, &None);
+        env.ledger()
+            .with_mut(|li| li.timestamp += math::SECONDS_PER_YEAR);
+
+        client.repay_credit(&borrower, &100);
+        client.repay_credit(&borrower, &200);
+        let stats = client.get_protocol_stats();
+        assert_eq!(stats.total_repaid, 300);
+        assert_eq!(stats.total_principal_repaid, 270);
+        assert_eq!(stats.total_interest_collected, 30);
+        assert_eq!(stats.total_fees_collected, 3);
+        assert_eq!(stats.total_outstanding_principal, 730);
+    }
+
+    // ── borrower history ──────────────────────────────────────────────────────
+
+    #[test]
+    fn test_borrower_history_records_draws_and_repayments() {
+        use soroban_sdk::testutils::Ledger;
+        let env = Env::default();
+        env.mock_all_auths();
+        let borrower = Address::generate(&env);
+        let (client, token_address, _admin) = setup_scheduled_line(&env, &borrower, 100, 1_000);
+        token::StellarAssetClient::new(&env, &token_address).mint(&borrower, &1_000);
+
+        env.ledger().with_mut(|li| li.timestamp = 10);
+        client.draw_credit(&borrower, &1_000, &None);
+        env.ledger().with_mut(|li| li.timestamp = 150);
+        client.draw_credit(&borrower, &500, &None);
+        client.repay_credit(&borrower, &200);
+        env.ledger().with_mut(|li| li.timestamp = 350);
+        client.repay_credit(&borrower, &200);
+
+        let history = client.get_borrower_history(&borrower);
+        assert_eq!(history.draw_count, 2);
+        assert_eq!(history.lifetime_volume, 1_500);
+        assert_eq!(history.repay_count, 2);
+        assert_eq!(history.on_time_payments, 1);
+        assert_eq!(history.late_payments, 1);
+        assert_eq!(history.last_activity_ts, 350);
+    }
+
+    #[test]
+    fn test_borrower_history_defaults_to_empty() {
+        let env = Env::default();
+        env.mock_all_auths();
+        let borrower = Address::generate(&env);
+        let (client, _token, _admin) = setup_contract_with_credit_line(&env, &borrower, 1_000, 0);
+        assert_eq!(
+            client.get_borrower_history(&Address::generate(&env)),
+            BorrowerHistory::default()
+        );
+    }
+
+    #[test]
+    fn test_transactions_paged_newest_first() {
+        use soroban_sdk::testutils::Ledger;
+        let env = Env::default();
+        env.mock_all_auths();
+        let borrower = Address::generate(&env);
+        let (client, _token, _admin) =
+            setup_contract_with_credit_line(&env, &borrower, 1_000, 1_000);
+        env.ledger().with_mut(|li| li.timestamp = 10);
+        client.draw_credit(&borrower, &300, &None);
+        env.ledger().with_mut(|li| li.timestamp = 20);
+        client.repay_credit(&borrower, &100);
+        env.ledger().with_mut(|li| li.timestamp = 30);
+        client.draw_credit(&borrower, &50, &None);
+
+        let page = client.get_transactions(&borrower, &0, &2);
+        assert_eq!(
+            page,
+            soroban_sdk::vec![
+                &env,
+                Transaction {
+                    kind: history::TransactionKind::Draw,
+                    amount: 50,
+                    timestamp: 30,
+                },
+                Transaction {
+                    kind: history::TransactionKind::Repay,
+                    amount: 100,
+                    timestamp: 20,
+                },
+            ]
+        );
+        let rest = client.get_transactions(&borrower, &2, &10);
+        assert_eq!(rest.len(), 1);
+        assert_eq!(rest.get(0).unwrap().amount, 300);
+        assert_eq!(client.get_transactions(&borrower, &3, &10).len(), 0);
+    }
+
+    #[test]
+    fn test_transaction_log_is_bounded() {
+        let env = Env::default();
+        env.mock_all_auths();
+        let borrower = Address::generate(&env);
+        let (client, _token, _admin) =
+            setup_contract_with_credit_line(&env, &borrower, 2_000, 2_000);
+        for amount in 1..=(history::TRANSACTION_LOG_SIZE as i128 + 1) {
+            client.draw_credit(&borrower, &amount, &None);
+        }
+        let log = client.get_transactions(&borrower, &0, &u32::MAX);
+        assert_eq!(log.len(), history::TRANSACTION_LOG_SIZE);
+        assert_eq!(log.get(0).unwrap().amount, 51);
+        assert_eq!(log.last().unwrap().amount, 2);
+    }
+
+    // ── automatic risk score adjustments ──────────────────────────────────────
+
+    fn risk_adjustment_config() -> RiskAdjustmentConfig {
+        RiskAdjustmentConfig {
+            enabled: true,
+            on_time_step: 5,
+            missed_step: 10,
+            min_score: 60,
+            max_score: 85,
+        }
+    }
+
+    #[test]
+    fn test_on_time_payment_lowers_risk_score() {
+        use soroban_sdk::testutils::{Events, Ledger};
+        use soroban_sdk::TryIntoVal;
+        let env = Env::default();
+        env.mock_all_auths();
+        let borrower = Address::generate(&env);
+        let (client, token_address, _admin) = setup_scheduled_line(&env, &borrower, 100, 1_000);
+        token::StellarAssetClient::new(&env, &token_address).mint(&borrower, &1_000);
+        client.set_risk_adjustment_config(&risk_adjustment_config());
+        client.draw_credit(&borrower, &1_000, &None);
+
+        // First cycle bills 100; clearing it before the due date is on time.
+        env.ledger().with_mut(|li| li.timestamp = 150);
+        let due = client.get_next_payment(&borrower).unwrap().min_due_amount;
+        client.repay_credit(&borrower, &due);
+        let events = env.events().all();
+        let updated: RiskParametersUpdatedEvent = events
+            .iter()
+            .find_map(|(_, _, data)| data.try_into_val(&env).ok())
+            .unwrap();
+        assert_eq!(updated.risk_score, 65);
+        assert_eq!(client.get_credit_line(&borrower).unwrap().risk_score, 65);
+
+        // A partial payment that leaves the minimum open does not adjust.
+        env.ledger().with_mut(|li| li.timestamp = 250);
+        client.repay_credit(&borrower, &1);
+        assert_eq!(client.get_credit_line(&borrower).unwrap().risk_score, 65);
+    }
+
+    #[test]
+    fn test_missed_payment_raises_risk_score_to_ceiling() {
+        use soroban_sdk::testutils::Ledger;
+        let env = Env::default();
+        env.mock_all_auths();
+        let borrower = Address::generate(&env);
+        let keeper = Address::generate(&env);
+        let config = KeeperConfig {
+            grace_period_secs: 0,
+            default_after_missed_cycles: 0,
+            keeper_reward: 0,
+            auto_default_after_secs: 0,
+        };
+        let (client, _token, _admin) = setup_overdue_line(&env, &borrower, &config);
+        client.set_risk_adjustment_config(&risk_adjustment_config());
+
+        env.ledger().with_mut(|li| li.timestamp = 1_201);
+        client.process_overdue(&keeper, &borrower);
+        assert_eq!(client.get_credit_line(&borrower).unwrap().risk_score, 80);
+        env.ledger().with_mut(|li| li.timestamp = 1_301);
+        client.process_overdue(&keeper, &borrower);
+        assert_eq!(client.get_credit_line(&borrower).unwrap().risk_score, 85);
+    }
+
+    #[test]
+    fn test_risk_adjustment_disabled_by_default() {
+        use soroban_sdk::testutils::Ledger;
+        let env = Env::default();
+        env.mock_all_auths();
+        let borrower = Address::generate(&env);
+        let config = KeeperConfig {
+            grace_period_secs: 0,
+            default_after_missed_cycles: 0,
+            keeper_reward: 0,
+            auto_default_after_secs: 0,
+        };
+        let (client, _token, _admin) = setup_overdue_line(&env, &borrower, &config);
+        env.ledger().with_mut(|li| li.timestamp = 1_201);
+        client.process_overdue(&Address::generate(&env), &borrower);
+        assert_eq!(client.get_credit_line(&borrower).unwrap().risk_score, 70);
+    }
+
+    #[test]
+    #[should_panic(expected = "min_score cannot exceed max_score")]
+    fn test_set_risk_adjustment_config_inverted_bounds_reverts() {
+        let env = Env::default();
+        env.mock_all_auths();
+        let borrower = Address::generate(&env);
+        let (client, _token, _admin) = setup_contract_with_credit_line(&env, &borrower, 1_000, 0);
+        client.set_risk_adjustment_config(&RiskAdjustmentConfig {
+            min_score: 90,
+            max_score: 80,
+            ..risk_adjustment_config()
+        });
+    }
+
+    // ── default recovery and write-offs ───────────────────────────────────────
+
+    #[test]
+    fn test_repayment_on_defaulted_line_records_recovery() {
+        let env = Env::default();
+        env.mock_all_auths();
+        let borrower = Address::generate(&env);
+        let (client, token_address, _admin) =
+            setup_contract_with_credit_line(&env, &borrower, 1_000, 1_000);
+        client.draw_credit(&borrower, &600, &None);
+        client.repay_credit(&borrower, &100);
+        client.default_credit_line(&borrower);
+        client.repay_credit(&borrower, &150);
+
+        let line = client.get_credit_line(&borrower).unwrap();
+        assert_eq!(line.recovered_amount, 150);
+        assert_eq!(line.utilized_amount, 350);
+        assert_eq!(
+            token::Client::new(&env, &token_address).balance(&borrower),
+            350
+        );
+    }
+
+    #[test]
+    fn test_write_off_reduces_debt_and_charges_pool() {
+        use soroban_sdk::testutils::Events;
+        use soroban_sdk::TryIntoVal;
+        let env = Env::default();
+        env.mock_all_auths();
+        let borrower = Address::generate(&env);
+        let lender = Address::generate(&env);
+        let (client, token_address, _admin) =
+            setup_contract_with_credit_line(&env, &borrower, 1_000, 0);
+        token::StellarAssetClient::new(&env, &token_address).mint(&lender, &1_000);
+        client.deposit_liquidity(&lender, &token_address, &1_000);
+        client.draw_credit(&borrower, &600, &None);
+        client.default_credit_line(&borrower);
+
+        assert_eq!(client.write_off(&borrower, &400), 400);
+        let event: WriteOffEvent = env
+            .events()
+            .all()
+            .last()
+            .unwrap()
+            .2
+            .try_into_val(&env)
+            .unwrap();
+        assert_eq!(event.remaining_outstanding, 200);
+
+        // Capped at the remaining balance.
+        assert_eq!(client.write_off(&borrower, &1_000), 200);
+        let line = client.get_credit_line(&borrower).unwrap();
+        assert_eq!(line.utilized_amount, 0);
+        assert_eq!(line.written_off_amount, 600);
+        assert_eq!(client.get_pool_assets(&token_address), 400);
+    }
+
+    #[test]
+    #[should_panic(expected = "credit line is not defaulted")]
+    fn test_write_off_requires_defaulted_line() {
+        let env = Env::default();
+        env.mock_all_auths();
+        let borrower = Address::generate(&env);
+        let (client, _token, _admin) =
+            setup_contract_with_credit_line(&env, &borrower, 1_000, 1_000);
+        client.draw_credit(&borrower, &600, &None);
+        client.write_off(&borrower, &100);
+    }
+
+    // ── debt forgiveness ──────────────────────────────────────────────────────
+
+    #[test]
+    fn test_forgive_debt_reduces_balance_and_tracks_stats() {
+        use soroban_sdk::testutils::Events;
+        use soroban_sdk::TryIntoVal;
+        let env = Env::default();
+        env.mock_all_auths();
+        let borrower = Address::generate(&env);
+        let lender = Address::generate(&env);
+        let (client, token_address, _admin) =
+            setup_contract_with_credit_line(&env, &borrower, 1_000, 0);
+        token::StellarAssetClient::new(&env, &token_address).mint(&lender, &1_000);
+        client.deposit_liquidity(&lender, &token_address, &1_000);
+        client.draw_credit(&borrower, &600, &None);
+
+        assert_eq!(client.forgive_debt(&borrower, &200, &7, &None), 200);
+        let event: ForgivenessEvent = env
+            .events()
+            .all()
+            .last()
+            .unwrap()
+            .2
+            .try_into_val(&env)
+            .unwrap();
+        assert_eq!(event.principal_portion, 200);
+        assert_eq!(event.reason_code, 7);
+        assert_eq!(event.approver, None);
+        assert_eq!(event.remaining_outstanding, 400);
+        let line = client.get_credit_line(&borrower).unwrap();
+        assert_eq!(line.utilized_amount, 400);
+        assert_eq!(line.status, CreditStatus::Active);
+        assert_eq!(client.get_pool_assets(&token_address), 800);
+        assert_eq!(client.get_protocol_stats().total_forgiven, 200);
+    }
+
+    #[test]
+    fn test_forgive_debt_above_threshold_with_approver() {
+        use soroban_sdk::testutils::Events;
+        use soroban_sdk::TryIntoVal;
+        let env = Env::default();
+        env.mock_all_auths();
+        let borrower = Address::generate(&env);
+        let approver = Address::generate(&env);
+        let (client, _token, _admin) =
+            setup_contract_with_credit_line(&env, &borrower, 1_000, 1_000);
+        client.draw_credit(&borrower, &600, &None);
+        client.set_forgiveness_threshold(&100);
+        client.grant_role(&Role::Approver, &approver);
+
+        // Capped at the outstanding balance.
+        assert_eq!(
+            client.forgive_debt(&borrower, &1_000, &1, &Some(approver.clone())),
+            600
+        );
+        let event: ForgivenessEvent = env
+            .events()
+            .all()
+            .last()
+            .unwrap()
+            .2
+            .try_into_val(&env)
+            .unwrap();
+        assert_eq!(event.approver, Some(approver));
+        assert_eq!(event.remaining_outstanding, 0);
+    }
+
+    #[test]
+    #[should_panic(expected = "forgiveness requires approval")]
+    fn test_forgive_debt_above_threshold_requires_approver() {
+        let env = Env::default();
+        env.mock_all_auths();
+        let borrower = Address::generate(&env);
+        let (client, _token, _admin) =
+            setup_contract_with_credit_line(&env, &borrower, 1_000, 1_000);
+        client.draw_credit(&borrower, &600, &None);
+        client.set_forgiveness_threshold(&100);
+        client.forgive_debt(&borrower, &101, &1, &None);
+    }
+
+    #[test]
+    fn test_forgive_debt_split_into_small_calls_needs_approver() {
+        let env = Env::default();
+        env.mock_all_auths();
+        let borrower = Address::generate(&env);
+        let approver = Address::generate(&env);
+        let (client, _token, _admin) =
+            setup_contract_with_credit_line(&env, &borrower, 1_000, 1_000);
+        client.draw_credit(&borrower, &600, &None);
+        client.set_forgiveness_threshold(&100);
+        client.grant_role(&Role::Approver, &approver);
+
+        client.forgive_debt(&borrower, &60, &1, &None);
+        client.forgive_debt(&borrower, &40, &1, &None);
+        assert_eq!(client.get_forgiven_total(&borrower), 100);
+        // The line's total is at the threshold: even one more unit needs approval.
+        assert!(client.try_forgive_debt(&borrower, &1, &1, &None).is_err());
+        client.forgive_debt(&borrower, &50, &1, &Some(approver));
+        assert_eq!(client.get_forgiven_total(&borrower), 150);
+        assert!(client.try_forgive_debt(&borrower, &50, &1, &None).is_err());
+        assert_eq!(
+            client.get_credit_line(&borrower).unwrap().utilized_amount,
+            450
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "approver lacks Approver role")]
+    fn test_forgive_debt_approver_needs_role() {
+        let env = Env::default();
+        env.mock_all_auths();
+        let borrower = Address::generate(&env);
+        let (client, _token, _admin) =
+            setup_contract_with_credit_line(&env, &borrower, 1_000, 1_000);
+        client.draw_credit(&borrower, &600, &None);
+        client.set_forgiveness_threshold(&100);
+        client.forgive_debt(&borrower, &300, &1, &Some(Address::generate(&env)));
+    }
+
+    // ── post-default interest ─────────────────────────────────────────────────
+
+    /// Line with 1_000 drawn at 3% that defaults a year in, then sits a year.
+    fn setup_defaulted_line<'a>(
+        env: &'a Env,
+        borrower: &'a Address,
+        config: &DefaultInterestConfig,
+    ) -> CreditClient<'a> {
+        use soroban_sdk::testutils::Ledger;
+        let (client, _token, _admin) = setup_contract_with_credit_line(env, borrower, 1_000, 1_000);
+        client.set_default_interest_config(config);
+        client.draw_credit(borrower, &1_000, &None);
+        env.ledger()
+            .with_mut(|li| li.timestamp += math::SECONDS_PER_YEAR);
+        client.default_credit_line(borrower);
+        env.ledger()
+            .with_mut(|li| li.timestamp += math::SECONDS_PER_YEAR);
+        client
+    }
+
+    #[test]
+    fn test_defaulted_line_continues_at_penalty_rate() {
+        let env = Env::default();
+        env.mock_all_auths();
+        let borrower = Address::generate(&env);
+        let client = setup_defaulted_line(
+            &env,
+            &borrower,
+            &DefaultInterestConfig {
+                accrual: DefaultAccrual::Continue,
+                penalty_rate_bps: 1_000,
+                freeze_after_secs: 0,
+            },
+        );
+        // 30 at 3% before the default, then 100 at 10% after it.
+        assert_eq!(client.accrue_interest(&borrower), 130);
+    }
+
+    #[test]
+    fn test_defaulted_line_freezes_at_default() {
+        let env = Env::default();
+        env.mock_all_auths();
+        let borrower = Address::generate(&env);
+        let client = setup_defaulted_line(
+            &env,
+            &borrower,
+            &DefaultInterestConfig {
+                accrual: DefaultAccrual::Freeze,
+                penalty_rate_bps: 0,
+                freeze_after_secs: 0,
+            },
+        );
+        assert_eq!(client.accrue_interest(&borrower), 30);
+        let line = client.get_credit_line(&borrower).unwrap();
+        assert_eq!(line.last_accrual_ts, env.ledger().timestamp());
+        assert_eq!(client.accrue_interest(&borrower), 0);
+    }
+
+    #[test]
+    fn test_defaulted_line_freezes_after_period() {
+        let env = Env::default();
+        env.mock_all_auths();
+        let borrower = Address::generate(&env);
+        let client = setup_defaulted_line(
+            &env,
+            &borrower,
+            &DefaultInterestConfig {
+                accrual: DefaultAccrual::FreezeAfter,
+                penalty_rate_bps: 0,
+                freeze_after_secs: math::SECONDS_PER_YEAR / 2,
+            },
+        );
+        // A further half year at the line's own 3% before the freeze.
+        assert_eq!(client.accrue_interest(&borrower), 45);
+        assert_eq!(
+            client.get_config().default_interest.accrual,
+            DefaultAccrual::FreezeAfter
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "freeze_after_secs must be greater than zero")]
+    fn test_default_interest_config_rejects_zero_freeze_period() {
+        let env = Env::default();
+        env.mock_all_auths();
+        let borrower = Address::generate(&env);
+        let (client, _token, _admin) = setup_contract_with_credit_line(&env, &borrower, 1_000, 0);
+        client.set_default_interest_config(&DefaultInterestConfig {
+            accrual: DefaultAccrual::FreezeAfter,
+            penalty_rate_bps: 0,
+            freeze_after_secs: 0,
+        });
+    }
+
+    // ── utilized amount corrections ───────────────────────────────────────────
+
+    #[test]
+    fn test_adjust_utilized_corrects_balance_and_emits_event() {
+        use soroban_sdk::testutils::Events;
+        use soroban_sdk::TryIntoVal;
+        let env = Env::default();
+        env.mock_all_auths();
+        let borrower = Address::generate(&env);
+        let (client, _token, _admin) =
+            setup_contract_with_credit_line(&env, &borrower, 1_000, 1_000);
+        client.draw_credit(&borrower, &600, &None);
+        client.adjust_utilized(&borrower, &-150, &7);
+
+        let event = env
+            .events()
+            .all()
+            .iter()
+            .find_map(|e| TryIntoVal::<Env, AdjustmentEvent>::try_into_val(&e.2, &env).ok())
+            .unwrap();
+        assert_eq!(event.delta, -150);
+        assert_eq!(event.reason_code, 7);
+        assert_eq!(event.old_utilized_amount, 600);
+        assert_eq!(event.new_utilized_amount, 450);
+        assert_eq!(
+            client.get_credit_line(&borrower).unwrap().utilized_amount,
+            450
+        );
+        assert_eq!(
+            client.get_audit_log(&1, &1).get(0).unwrap().action,
+            symbol_short!("adjust")
+        );
+
+        client.adjust_utilized(&borrower, &550, &8);
+        assert_eq!(
+            client.get_credit_line(&borrower).unwrap().utilized_amount,
+            1_000
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "utilized_amount out of bounds")]
+    fn test_adjust_utilized_below_zero_reverts() {
+        let env = Env::default();
+        env.mock_all_auths();
+        let borrower = Address::generate(&env);
+        let (client, _token, _admin) =
+            setup_contract_with_credit_line(&env, &borrower, 1_000, 1_000);
+        client.draw_credit(&borrower, &100, &None);
+        client.adjust_utilized(&borrower, &-101, &1);
+    }
+
+    #[test]
+    #[should_panic(expected = "utilized_amount out of bounds")]
+    fn test_adjust_utilized_above_limit_reverts() {
+        let env = Env::default();
+        env.mock_all_auths();
+        let borrower = Address::generate(&env);
+        let (client, _token, _admin) = setup_contract_with_credit_line(&env, &borrower, 1_000, 0);
+        client.adjust_utilized(&borrower, &1_001, &1);
+    }
+
+    // ── restructuring ─────────────────────────────────────────────────────────
+
+    #[test]
+    fn test_restructure_defaulted_line_keeps_balance() {
+        use soroban_sdk::testutils::Ledger;
+        let env = Env::default();
+        env.mock_all_auths();
+        let borrower = Address::generate(&env);
+        let keeper = Address::generate(&env);
+        let config = KeeperConfig {
+            grace_period_secs: 0,
+            default_after_missed_cycles: 1,
+            keeper_reward: 0,
+            auto_default_after_secs: 0,
+        };
+        let (client, _token, _admin) = setup_overdue_line(&env, &borrower, &config);
+        env.ledger().with_mut(|li| li.timestamp = 1_201);
+        client.process_overdue(&keeper, &borrower);
+        assert_eq!(
+            client.get_credit_line(&borrower).unwrap().status,
+            CreditStatus::Defaulted
+        );
+
+        client.restructure_credit_line(
+            &borrower,
+            &6_000,
+            &100,
+            &Some(RepaymentSchedule {
+                cycle_length_secs: 1_000,
+                min_payment_bps: 500,
+            }),
+        );
+
+        let line = client.get_credit_line(&borrower).unwrap();
+        assert_eq!(line.status, CreditStatus::Active);
+        assert_eq!(line.utilized_amount, 5_000);
+        assert_eq!(line.credit_limit, 6_000);
+        assert_eq!(line.interest_rate_bps, 100);
+        assert_eq!(line.missed_cycles, 0);
+        assert_eq!(line.cycle_length_secs, 1_000);
+        assert_eq!(line.next_due_ts, 2_201);
+        assert!(!client.get_next_payment(&borrower).unwrap().overdue);
+
+        client.draw_credit(&borrower, &1_000, &None);
+    }
+
+    #[test]
+    fn test_restructure_suspended_line_without_schedule() {
+        let env = Env::default();
+        env.mock_all_auths();
+        let borrower = Address::generate(&env);
+        let (client, _token, _admin) = setup_scheduled_line(&env, &borrower, 100, 1_000);
+        client.suspend_credit_line(&borrower, &0, &None);
+        client.restructure_credit_line(&borrower, &10_000, &300, &None);
+        let line = client.get_credit_line(&borrower).unwrap();
+        assert_eq!(line.status, CreditStatus::Active);
+        assert_eq!(client.get_next_payment(&borrower), None);
+    }
+
+    #[test]
+    #[should_panic(expected = "credit line is not suspended or defaulted")]
+    fn test_restructure_active_line_reverts() {
+        let env = Env::default();
+        env.mock_all_auths();
+        let borrower = Address::generate(&env);
+        let (client, _token, _admin) = setup_contract_with_credit_line(&env, &borrower, 1_000, 0);
+        client.restructure_credit_line(&borrower, &1_000, &300, &None);
+    }
+
+    // ── delegated draws (operators) ───────────────────────────────────────────
+
+    #[test]
+    fn test_operator_draws_within_allowance() {
+        use soroban_sdk::testutils::Events;
+        use soroban_sdk::TryIntoVal;
+        let env = Env::default();
+        env.mock_all_auths();
+        let borrower = Address::generate(&env);
+        let operator = Address::generate(&env);
+        let (client, token_address, _admin) =
+            setup_contract_with_credit_line(&env, &borrower, 1_000, 1_000);
+
+        client.approve_operator(&borrower, &operator, &300, &100);
+        let event: OperatorApprovedEvent = env
+            .events()
+            .all()
+            .last()
+            .unwrap()
+            .2
+            .try_into_val(&env)
+            .unwrap();
+        assert_eq!(event.max_amount, 300);
+
+        client.draw_credit_as_operator(&operator, &borrower, &200);
+        assert_eq!(
+            token::Client::new(&env, &token_address).balance(&operator),
+            200
+        );
+        assert_eq!(
+            client.get_credit_line(&borrower).unwrap().utilized_amount,
+            200
+        );
+        assert_eq!(
+            client
+                .get_operator_approval(&borrower, &operator)
+                .unwrap()
+                .max_amount,
+            100
+        );
+
+        client.draw_credit_as_operator(&operator, &borrower, &100);
+        assert_eq!(client.get_operator_approval(&borrower, &operator), None);
+    }
+
+    #[test]
+    #[should_panic(expected = "exceeds operator allowance")]
+    fn test_operator_draw_over_allowance_reverts() {
+        let env = Env::default();
+        env.mock_all_auths();
+        let borrower = Address::generate(&env);
+        let operator = Address::generate(&env);
+        let (client, _token, _admin) =
+            setup_contract_with_credit_line(&env, &borrower, 1_000, 1_000);
+        client.approve_operator(&borrower, &operator, &300, &100);
+        client.draw_credit_as_operator(&operator, &borrower, &301);
+    }
+
+    #[test]
+    #[should_panic(expected = "operator approval expired")]
+    fn test_operator_draw_after_expiry_reverts() {
+        use soroban_sdk::testutils::Ledger;
+        let env = Env::default();
+        env.mock_all_auths();
+        let borrower = Address::generate(&env);
+        let operator = Address::generate(&env);
+        let (client, _token, _admin) =
+            setup_contract_with_credit_line(&env, &borrower, 1_000, 1_000);
+        client.approve_operator(&borrower, &operator, &300, &100);
+        env.ledger().with_mut(|li| li.sequence_number = 101);
+        client.draw_credit_as_operator(&operator, &borrower, &100);
+    }
+
+    #[test]
+    #[should_panic(expected = "operator not approved")]
+    fn test_revoked_operator_cannot_draw() {
+        let env = Env::default();
+        env.mock_all_auths();
+        let borrower = Address::generate(&env);
+        let operator = Address::generate(&env);
+        let (client, _token, _admin) =
+            setup_contract_with_credit_line(&env, &borrower, 1_000, 1_000);
+        client.approve_operator(&borrower, &operator, &300, &100);
+        client.revoke_operator(&borrower, &operator);
+        client.draw_credit_as_operator(&operator, &borrower, &100);
+    }
+
+    // ── credit delegation ─────────────────────────────────────────────────────
+
+    #[test]
+    fn test_delegatee_draws_against_delegator_line() {
+        use soroban_sdk::testutils::Events;
+        use soroban_sdk::TryIntoVal;
+        let env = Env::default();
+        env.mock_all_auths();
+        let delegator = Address::generate(&env);
+        let delegatee = Address::generate(&env);
+        let (client, token_address, _admin) =
+            setup_contract_with_credit_line(&env, &delegator, 1_000, 1_000);
+
+        client.delegate_credit(&delegator, &delegatee, &400, &1_000);
+        client.draw_delegated_credit(&delegatee, &delegator, &150);
+        let event: DelegationEvent = env
+            .events()
+            .all()
+            .last()
+            .unwrap()
+            .2
+            .try_into_val(&env)
+            .unwrap();
+        assert_eq!(event.delegator, delegator);
+        assert_eq!(event.amount, 150);
+        assert_eq!(event.remaining, 250);
+        assert_eq!(event.drawn, 150);
+
+        assert_eq!(
+            token::Client::new(&env, &token_address).balance(&delegatee),
+            150
+        );
+        assert_eq!(
+            client.get_credit_line(&delegator).unwrap().utilized_amount,
+            150
+        );
+        assert_eq!(client.get_credit_line(&delegatee), None);
+        assert_eq!(
+            client.get_delegation(&delegator, &delegatee),
+            Some(Delegation {
+                remaining: 250,
+                drawn: 150,
+                expires_at: 1_000,
+            })
+        );
+        assert_eq!(
+            client.get_delegatees(&delegator),
+            soroban_sdk::vec![&env, delegatee]
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "exceeds credit limit")]
+    fn test_delegated_slice_held_against_delegator_limit() {
+        let env = Env::default();
+        env.mock_all_auths();
+        let delegator = Address::generate(&env);
+        let delegatee = Address::generate(&env);
+        let (client, _token, _admin) =
+            setup_contract_with_credit_line(&env, &delegator, 1_000, 1_000);
+        client.delegate_credit(&delegator, &delegatee, &600, &1_000);
+        client.draw_credit(&delegator, &500, &None);
+    }
+
+    #[test]
+    #[should_panic(expected = "delegation exceeds available credit")]
+    fn test_delegate_more_than_unused_limit_reverts() {
+        let env = Env::default();
+        env.mock_all_auths();
+        let delegator = Address::generate(&env);
+        let (client, _token, _admin) =
+            setup_contract_with_credit_line(&env, &delegator, 1_000, 1_000);
+        client.draw_credit(&delegator, &500, &None);
+        client.delegate_credit(&delegator, &Address::generate(&env), &300, &1_000);
+        client.delegate_credit(&delegator, &Address::generate(&env), &201, &1_000);
+    }
+
+    #[test]
+    fn test_revoked_or_expired_delegation_releases_limit() {
+        use soroban_sdk::testutils::Ledger;
+        let env = Env::default();
+        env.mock_all_auths();
+        let delegator = Address::generate(&env);
+        let delegatee = Address::generate(&env);
+        let other = Address::generate(&env);
+        let (client, _token, _admin) =
+            setup_contract_with_credit_line(&env, &delegator, 1_000, 1_000);
+        client.delegate_credit(&delegator, &delegatee, &400, &1_000);
+        client.delegate_credit(&delegator, &other, &300, &500);
+
+        client.revoke_delegation(&delegator, &delegatee);
+        assert_eq!(client.get_delegation(&delegator, &delegatee), None);
+        assert_eq!(
+            client.get_delegatees(&delegator),
+            soroban_sdk::vec![&env, other.clone()]
+        );
+        assert!(client
+            .try_draw_delegated_credit(&delegatee, &delegator, &100)
+            .is_err());
+
+        env.ledger().with_mut(|li| li.timestamp = 500);
+        assert!(client
+            .try_draw_delegated_credit(&other, &delegator, &100)
+            .is_err());
+        client.draw_credit(&delegator, &1_000, &None);
+        assert_eq!(
+            client.get_credit_line(&delegator).unwrap().utilized_amount,
+            1_000
+        );
+    }
+
+    // ── signed risk engine operations ─────────────────────────────────────────
+
+    fn risk_engine_signing_key() -> ed25519_dalek::SigningKey {
+        ed25519_dalek::SigningKey::from_bytes(&[7u8; 32])
+    }
+
+    fn sign_payload<T: soroban_sdk::IntoVal<Env, soroban_sdk::Val>>(
+        env: &Env,
+        contract_id: &Address,
+        payload: &T,
+    ) -> BytesN<64> {
+        extern crate std;
+        use ed25519_dalek::Signer;
+        use soroban_sdk::xdr::ToXdr;
+        let payload: soroban_sdk::Val = payload.into_val(env);
+        let message = (contract_id.clone(), payload).to_xdr(env);
+        let message: std::vec::Vec<u8> = message.iter().collect();
+        let signature = risk_engine_signing_key().sign(&message);
+        BytesN::from_array(env, &signature.to_bytes())
+    }
+
+    fn setup_signed(env: &Env) -> (CreditClient<'_>, Address) {
+        let admin = Address::generate(env);
+        let contract_id = env.register(Credit, ());
+        let client = CreditClient::new(env, &contract_id);
+        let (token_address, _) = setup_token(env, &contract_id, 0);
+        client.init(&admin, &token_address);
+        let public_key = risk_engine_signing_key().verifying_key().to_bytes();
+        client.set_risk_engine_key(&BytesN::from_array(env, &public_key));
+        (client, contract_id)
+    }
+
+    fn signed_open(borrower: &Address, nonce: u64) -> SignedOpenCreditLine {
+        SignedOpenCreditLine {
+            borrower: borrower.clone(),
+            credit_limit: 1_000,
+            interest_rate_bps: 300,
+            risk_score: 70,
+            cycle_length_secs: 0,
+            min_payment_bps: 0,
+            rate_mode: RateMode::Fixed,
+            token: None,
+            nonce,
+        }
+    }
+
+    #[test]
+    fn test_open_and_update_credit_line_signed() {
+        let env = Env::default();
+        env.mock_all_auths();
+        let borrower = Address::generate(&env);
+        let (client, contract_id) = setup_signed(&env);
+
+        let payload = signed_open(&borrower, 1);
+        let signature = sign_payload(&env, &contract_id, &payload);
+        client.open_credit_line_signed(&payload, &signature);
+        assert_eq!(
+            client.get_credit_line(&borrower).unwrap().credit_limit,
+            1_000
+        );
+        assert!(client.is_nonce_used(&1));
+
+        let update = SignedRiskUpdate {
+            borrower: borrower.clone(),
+            credit_limit: 2_000,
+            interest_rate_bps: 400,
+            risk_score: 60,
+            nonce: 2,
+        };
+        let signature = sign_payload(&env, &contract_id, &update);
+        client.update_risk_parameters_signed(&update, &signature);
+        let line = client.get_credit_line(&borrower).unwrap();
+        assert_eq!(line.credit_limit, 2_000);
+        assert_eq!(line.risk_score, 60);
+    }
+
+    #[test]
+    #[should_panic(expected = "nonce already used")]
+    fn test_signed_operation_replay_reverts() {
+        let env = Env::default();
+        env.mock_all_auths();
+        let borrower = Address::generate(&env);
+        let (client, contract_id) = setup_signed(&env);
+        let payload = signed_open(&borrower, 1);
+        let signature = sign_payload(&env, &contract_id, &payload);
+        client.open_credit_line_signed(&payload, &signature);
+        client.close_credit_line(&borrower, &borrower);
+        client.open_credit_line_signed(&payload, &signature);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_signed_operation_tampered_payload_reverts() {
+        let env = Env::default();
+        env.mock_all_auths();
+        let borrower = Address::generate(&env);
+        let (client, contract_id) = setup_signed(&env);
+        let payload = signed_open(&borrower, 1);
+        let signature = sign_payload(&env, &contract_id, &payload);
+        let tampered = SignedOpenCreditLine {
+            credit_limit: 1_000_000,
+            ..payload
+        };
+        client.open_credit_line_signed(&tampered, &signature);
+    }
+
+    #[test]
+    #[should_panic(expected = "risk engine key not set")]
+    fn test_signed_operation_without_key_reverts() {
+        let env = Env::default();
+        env.mock_all_auths();
+        let borrower = Address::generate(&env);
+        let (client, _token, _admin) = setup_contract_with_credit_line(&env, &borrower, 1_000, 0);
+        let payload = signed_open(&Address::generate(&env), 1);
+        client.open_credit_line_signed(&payload, &BytesN::from_array(&env, &[0u8; 64]));
+    }
+
+    // ── merkle onboarding ─────────────────────────────────────────────────────
+
+    fn line_terms(credit_limit: i128) -> LineTerms {
+        LineTerms {
+            credit_limit,
+            interest_rate_bps: 300,
+            risk_score: 70,
+            cycle_length_secs: 0,
+            min_payment_bps: 0,
+            rate_mode: RateMode::Fixed,
+            token: None,
+        }
+    }
+
+    /// Commit a two-leaf root for `first` and `second`; returns their leaves.
+    fn commit_two_leaves(
+        env: &Env,
+        client: &CreditClient,
+        contract_id: &Address,
+        first: (&Address, &LineTerms),
+        second: (&Address, &LineTerms),
+    ) -> (BytesN<32>, BytesN<32>) {
+        let (first_leaf, second_leaf, root) = env.as_contract(contract_id, || {
+            let first_leaf = onboarding::leaf(env, first.0, first.1);
+            let second_leaf = onboarding::leaf(env, second.0, second.1);
+            let root = onboarding::hash_pair(env, &first_leaf, &second_leaf);
+            (first_leaf, second_leaf, root)
+        });
+        let risk_engine = Address::generate(env);
+        client.grant_role(&Role::RiskEngine, &risk_engine);
+        client.commit_open_root(&risk_engine, &root);
+        assert_eq!(client.get_open_root(), Some(root));
+        (first_leaf, second_leaf)
+    }
+
+    #[test]
+    fn test_claim_credit_line_with_merkle_proof() {
+        let env = Env::default();
+        env.mock_all_auths();
+        let (client, contract_id) = setup_signed(&env);
+        let alice = Address::generate(&env);
+        let bob = Address::generate(&env);
+        let (alice_leaf, bob_leaf) = commit_two_leaves(
+            &env,
+            &client,
+            &contract_id,
+            (&alice, &line_terms(1_000)),
+            (&bob, &line_terms(2_000)),
+        );
+
+        client.claim_credit_line(
+            &bob,
+            &line_terms(2_000),
+            &soroban_sdk::vec![&env, alice_leaf],
+        );
+        let line = client.get_credit_line(&bob).unwrap();
+        assert_eq!(line.credit_limit, 2_000);
+        assert_eq!(line.interest_rate_bps, 300);
+        assert!(client.is_line_claimed(&bob));
+        assert!(!client.is_line_claimed(&alice));
+
+        client.claim_credit_line(
+            &alice,
+            &line_terms(1_000),
+            &soroban_sdk::vec![&env, bob_leaf],
+        );
+        assert_eq!(client.get_credit_line(&alice).unwrap().credit_limit, 1_000);
+    }
+
+    #[test]
+    #[should_panic(expected = "invalid merkle proof")]
+    fn test_claim_credit_line_with_altered_terms_reverts() {
+        let env = Env::default();
+        env.mock_all_auths();
+        let (client, contract_id) = setup_signed(&env);
+        let alice = Address::generate(&env);
+        let bob = Address::generate(&env);
+        let (_, bob_leaf) = commit_two_leaves(
+            &env,
+            &client,
+            &contract_id,
+            (&alice, &line_terms(1_000)),
+            (&bob, &line_terms(2_000)),
+        );
+        client.claim_credit_line(
+            &alice,
+            &line_terms(5_000),
+            &soroban_sdk::vec![&env, bob_leaf],
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "credit line already claimed")]
+    fn test_claim_credit_line_twice_reverts() {
+        let env = Env::default();
+        env.mock_all_auths();
+        let (client, contract_id) = setup_signed(&env);
+        let alice = Address::generate(&env);
+        let bob = Address::generate(&env);
+        let (_, bob_leaf) = commit_two_leaves(
+            &env,
+            &client,
+            &contract_id,
+            (&alice, &line_terms(1_000)),
+            (&bob, &line_terms(2_000)),
+        );
+        let proof = soroban_sdk::vec![&env, bob_leaf];
+        client.claim_credit_line(&alice, &line_terms(1_000), &proof);
+        client.close_credit_line(&alice, &alice);
+        client.claim_credit_line(&alice, &line_terms(1_000), &proof);
+    }
+
+    #[test]
+    #[should_panic(expected = "open root not committed")]
+    fn test_claim_credit_line_without_root_reverts() {
+        let env = Env::default();
+        env.mock_all_auths();
+        let (client, _contract_id) = setup_signed(&env);
+        let borrower = Address::generate(&env);
+        client.claim_credit_line(&borrower, &line_terms(1_000), &soroban_sdk::vec![&env]);
+    }
+
+    // ── idempotency keys ──────────────────────────────────────────────────────
+
+    #[test]
+    fn test_open_credit_line_duplicate_op_id_rejected() {
+        let env = Env::default();
+        env.mock_all_auths();
+        let borrower = Address::generate(&env);
+        let (client, _token, admin) = setup_contract_with_credit_line(&env, &borrower, 1_000, 0);
+        let other = Address::generate(&env);
+        let op_id = Some(BytesN::from_array(&env, &[1u8; 32]));
+
+        client.open_credit_line(
+            &admin,
+            &other,
+            &500,
+            &300,
+            &70,
+            &None,
+            &RateMode::Fixed,
+            &None,
+            &op_id,
+        );
+        assert!(client.is_operation_consumed(&BytesN::from_array(&env, &[1u8; 32])));
+
+        let retry = client.try_open_credit_line(
+            &admin,
+            &Address::generate(&env),
+            &500,
+            &300,
+            &70,
+            &None,
+            &RateMode::Fixed,
+            &None,
+            &op_id,
+        );
+        assert_eq!(
+            retry,
+            Err(Ok(soroban_sdk::Error::from_contract_error(
+                ContractError::DuplicateOperation as u32
+            )))
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "Error(Contract, #2)")]
+    fn test_update_risk_parameters_duplicate_op_id_rejected() {
+        let env = Env::default();
+        env.mock_all_auths();
+        let borrower = Address::generate(&env);
+        let (client, _token, admin) = setup_contract_with_credit_line(&env, &borrower, 1_000, 0);
+        let op_id = Some(BytesN::from_array(&env, &[2u8; 32]));
+        client.update_risk_parameters(&admin, &borrower, &2_000, &300, &70, &op_id);
+        client.update_risk_parameters(&admin, &borrower, &3_000, &300, &70, &op_id);
+    }
+
+    // ── term lines: maturity and renewal ──────────────────────────────────────
+
+    #[test]
+    #[should_panic(expected = "credit line has matured")]
+    fn test_draw_after_maturity_reverts() {
+        use soroban_sdk::testutils::Ledger;
+        let env = Env::default();
+        env.mock_all_auths();
+        let borrower = Address::generate(&env);
+        let (client, _token, _admin) =
+            setup_contract_with_credit_line(&env, &borrower, 1_000, 1_000);
+        client.set_maturity(&borrower, &100);
+        client.draw_credit(&borrower, &100, &None);
+        env.ledger().with_mut(|li| li.timestamp = 100);
+        client.draw_credit(&borrower, &100, &None);
+    }
+
+    #[test]
+    fn test_matured_line_allows_repayment_and_renewal() {
+        use soroban_sdk::testutils::{Events, Ledger};
+        use soroban_sdk::TryIntoVal;
+        let env = Env::default();
+        env.mock_all_auths();
+        let borrower = Address::generate(&env);
+        let (client, _token, _admin) =
+            setup_contract_with_credit_line(&env, &borrower, 1_000, 1_000);
+        client.set_maturity(&borrower, &100);
+        client.draw_credit(&borrower, &400, &None);
+
+        env.ledger().with_mut(|li| li.timestamp = 150);
+        client.repay_credit(&borrower, &100);
+
+        client.renew_credit_line(&borrower, &1_000, &2_000, &500);
+        let event: MaturityEvent = env
+            .events()
+            .all()
+            .last()
+            .unwrap()
+            .2
+            .try_into_val(&env)
+            .unwrap();
+        assert_eq!(event.maturity_ts, 1_000);
+        assert_eq!(event.credit_limit, 2_000);
+
+        client.draw_credit(&borrower, &100, &None);
+        let line = client.get_credit_line(&borrower).unwrap();
+        assert_eq!(line.maturity_ts, 1_000);
+        assert_eq!(line.interest_rate_bps, 500);
+    }
+
+    #[test]
+    #[should_panic(expected = "maturity must be in the future")]
+    fn test_renew_with_past_maturity_reverts() {
+        use soroban_sdk::testutils::Ledger;
+        let env = Env::default();
+        env.mock_all_auths();
+        let borrower = Address::generate(&env);
+        let (client, _token, _admin) = setup_contract_with_credit_line(&env, &borrower, 1_000, 0);
+        env.ledger().with_mut(|li| li.timestamp = 500);
+        client.renew_credit_line(&borrower, &500, &1_000, &300);
+    }
+
+    // ── fixed and variable rates ──────────────────────────────────────────────
+
+    #[test]
+    fn test_variable_rate_tracks_reference_rate() {
+        use soroban_sdk::testutils::{Events, Ledger};
+        use soroban_sdk::TryIntoVal;
+        let env = Env::default();
+        env.mock_all_auths();
+        let fixed = Address::generate(&env);
+        let variable = Address::generate(&env);
+        let oracle = Address::generate(&env);
+        let (client, _token, admin) = setup_contract_with_credit_line(&env, &fixed, 1_000, 2_000);
+        client.grant_role(&Role::RateOracle, &oracle);
+        client.set_reference_rate_bps(&oracle, &200);
+
+        client.open_credit_line(
+            &admin,
+            &variable,
+            &1_000,
+            &300,
+            &70,
+            &None,
+            &RateMode::Variable,
+            &None,
+            &None,
+        );
+        let event: CreditLineEvent = env
+            .events()
+            .all()
+            .last()
+            .unwrap()
+            .2
+            .try_into_val(&env)
+            .unwrap();
+        assert_eq!(event.rate_mode, RateMode::Variable);
+        assert_eq!(event.interest_rate_bps, 500);
+
+        client.draw_credit(&variable, &1_000, &None);
+        client.set_reference_rate_bps(&oracle, &400);
+        env.ledger()
+            .with_mut(|li| li.timestamp += math::SECONDS_PER_YEAR);
+        client.draw_credit(&fixed, &100, &None);
+
+        // The year before the touch accrues at the old 5%; the line then re-prices to 7%.
+        client.update_risk_parameters(&admin, &variable, &1_000, &300, &70, &None);
+        let line = client.get_credit_line(&variable).unwrap();
+        assert_eq!(line.accrued_interest, 50);
+        assert_eq!(line.interest_rate_bps, 700);
+        assert_eq!(line.rate_spread_bps, 300);
+        assert_eq!(
+            client.get_credit_line(&fixed).unwrap().interest_rate_bps,
+            300
+        );
+    }
+
+    #[test]
+    fn test_variable_rate_capped_at_maximum() {
+        let env = Env::default();
+        env.mock_all_auths();
+        let borrower = Address::generate(&env);
+        let (client, _token, admin) = setup_contract_with_credit_line(&env, &borrower, 1_000, 0);
+        client.set_reference_rate_bps(&admin, &9_000);
+        let variable = Address::generate(&env);
+        client.open_credit_line(
+            &admin,
+            &variable,
+            &1_000,
+            &2_000,
+            &70,
+            &None,
+            &RateMode::Variable,
+            &None,
+            &None,
+        );
+        assert_eq!(
+            client.get_credit_line(&variable).unwrap().interest_rate_bps,
+            10_000
+        );
+    }
+
+    #[test]
+    fn test_rate_bounds_clamp_variable_rate() {
+        use soroban_sdk::testutils::Ledger;
+        let env = Env::default();
+        env.mock_all_auths();
+        let borrower = Address::generate(&env);
+        let (client, _token, admin) = setup_contract_with_credit_line(&env, &borrower, 1_000, 0);
+        client.set_reference_rate_bps(&admin, &1_000);
+        let variable = Address::generate(&env);
+        client.open_credit_line(
+            &admin,
+            &variable,
+            &1_000,
+            &200,
+            &70,
+            &None,
+            &RateMode::Variable,
+            &None,
+            &None,
+        );
+
+        client.set_rate_bounds(&variable, &300, &800);
+        let line = client.get_credit_line(&variable).unwrap();
+        assert_eq!(line.interest_rate_bps, 800);
+        assert_eq!((line.rate_floor_bps, line.rate_cap_bps), (300, 800));
+
+        client.set_reference_rate_bps(&admin, &0);
+        env.ledger().with_mut(|li| li.timestamp += 1);
+        client.accrue_interest(&variable);
+        assert_eq!(
+            client.get_credit_line(&variable).unwrap().interest_rate_bps,
+            300
+        );
+    }
+
+    #[test]
+    fn test_rate_bounds_move_fixed_rate_inside() {
+        let env = Env::default();
+        env.mock_all_auths();
+        let borrower = Address::generate(&env);
+        let (client, _token, admin) = setup_contract_with_credit_line(&env, &borrower, 1_000, 0);
+
+        client.set_rate_bounds(&borrower, &500, &0);
+        assert_eq!(
+            client.get_credit_line(&borrower).unwrap().interest_rate_bps,
+            500
+        );
+        // No cap: any rate at or above the floor is accepted.
+        client.update_risk_parameters(&admin, &borrower, &1_000, &9_000, &70, &None);
+        assert_eq!(
+            client.get_credit_line(&borrower).unwrap().interest_rate_bps,
+            9_000
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "interest_rate_bps outside rate bounds")]
+    fn test_fixed_rate_update_above_cap_reverts() {
+        let env = Env::default();
+        env.mock_all_auths();
+        let borrower = Address::generate(&env);
+        let (client, _token, admin) = setup_contract_with_credit_line(&env, &borrower, 1_000, 0);
+        client.set_rate_bounds(&borrower, &200, &800);
+        client.update_risk_parameters(&admin, &borrower, &1_000, &900, &70, &None);
+    }
+
+    #[test]
+    #[should_panic(expected = "rate floor exceeds rate cap")]
+    fn test_rate_floor_above_cap_reverts() {
+        let env = Env::default();
+        env.mock_all_auths();
+        let borrower = Address::generate(&env);
+        let (client, _token, _admin) = setup_contract_with_credit_line(&env, &borrower, 1_000, 0);
+        client.set_rate_bounds(&borrower, &900, &800);
+    }
+
+    #[test]
+    #[should_panic(expected = "unauthorized")]
+    fn test_set_reference_rate_requires_rate_oracle_role() {
+        let env = Env::default();
+        env.mock_all_auths();
+        let borrower = Address::generate(&env);
+        let (client, _token, _admin) = setup_contract_with_credit_line(&env, &borrower, 1_000, 0);
+        client.set_reference_rate_bps(&Address::generate(&env), &200);
+    }
+
+    // ── multi-token ───────────────────────────────────────────────────────────
+
+    #[test]
+    fn test_line_in_second_token_uses_its_own_pool() {
+        let env = Env::default();
+        env.mock_all_auths();
+        let borrower = Address::generate(&env);
+        let lender = Address::generate(&env);
+        let (client, token_address, admin) =
+            setup_contract_with_credit_line(&env, &borrower, 1_000, 1_000);
+        let contract_id = client.address.clone();
+        let (second_token, second_sac) = setup_token(&env, &contract_id, 0);
+        client.set_token_supported(&second_token, &true);
+        assert_eq!(
+            client.get_supported_tokens(),
+            soroban_sdk::vec![&env, token_address.clone(), second_token.clone()]
+        );
+
+        let other = Address::generate(&env);
+        client.open_credit_line(
+            &admin,
+            &other,
+            &500,
+            &300_u32,
+            &70_u32,
+            &None,
+            &RateMode::Fixed,
+            &Some(second_token.clone()),
+            &None,
+        );
+        assert_eq!(client.get_credit_line(&other).unwrap().token, second_token);
+        assert_eq!(client.get_undrawn_commitments(&token_address), 1_000);
+        assert_eq!(client.get_undrawn_commitments(&second_token), 500);
+
+        second_sac.mint(&lender, &800);
+        client.deposit_liquidity(&lender, &second_token, &800);
+        assert_eq!(client.get_lender_shares(&lender, &second_token), 800);
+        assert_eq!(client.get_lender_shares(&lender, &token_address), 0);
+
+        client.draw_credit(&other, &200, &None);
+        assert_eq!(token::Client::new(&env, &second_token).balance(&other), 200);
+        assert_eq!(token::Client::new(&env, &token_address).balance(&other), 0);
+        assert_eq!(client.get_undrawn_commitments(&second_token), 300);
+        assert_eq!(client.get_undrawn_commitments(&token_address), 1_000);
+
+        client.repay_credit(&other, &200);
+        assert_eq!(token::Client::new(&env, &second_token).balance(&other), 0);
+        assert_eq!(
+            token::Client::new(&env, &second_token).balance(&contract_id),
+            800
+        );
+        assert_eq!(client.get_credit_line(&other).unwrap().utilized_amount, 0);
+    }
+
+    #[test]
+    #[should_panic(expected = "token not supported")]
+    fn test_open_credit_line_in_unsupported_token_reverts() {
+        let env = Env::default();
+        env.mock_all_auths();
+        let borrower = Address::generate(&env);
+        let (client, _token_address, admin) =
+            setup_contract_with_credit_line(&env, &borrower, 1_000, 0);
+        let (unlisted, _) = setup_token(&env, &client.address, 0);
+        client.open_credit_line(
+            &admin,
+            &Address::generate(&env),
+            &500,
+            &300_u32,
+            &70_u32,
+            &None,
+            &RateMode::Fixed,
+            &Some(unlisted),
+            &None,
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "cannot remove default token")]
+    fn test_default_token_cannot_be_delisted() {
+        let env = Env::default();
+        env.mock_all_auths();
+        let borrower = Address::generate(&env);
+        let (client, token_address, _admin) =
+            setup_contract_with_credit_line(&env, &borrower, 1_000, 0);
+        client.set_token_supported(&token_address, &false);
+    }
+
+    // ── dust closes and repay_and_close ───────────────────────────────────────
+
+    #[test]
+    fn test_borrower_close_within_dust_writes_off_residue() {
+        let env = Env::default();
+        env.mock_all_auths();
+        let borrower = Address::generate(&env);
+        let (client, _token, _admin) =
+            setup_contract_with_credit_line(&env, &borrower, 1_000, 1_000);
+        client.set_dust_threshold(&5);
+        assert_eq!(client.get_dust_threshold(), 5);
+        client.draw_credit(&borrower, &100, &None);
+        client.repay_credit(&borrower, &97);
+
+        client.close_credit_line(&borrower, &borrower);
+        let line = client.get_credit_line(&borrower).unwrap();
+        assert_eq!(line.status, CreditStatus::Closed);
+        assert_eq!(line.utilized_amount, 0);
+        assert_eq!(line.written_off_amount, 3);
+    }
+
+    #[test]
+    #[should_panic(expected = "cannot close: utilized amount not zero")]
+    fn test_borrower_close_above_dust_reverts() {
+        let env = Env::default();
+        env.mock_all_auths();
+        let borrower = Address::generate(&env);
+        let (client, _token, _admin) =
+            setup_contract_with_credit_line(&env, &borrower, 1_000, 1_000);
+        client.set_dust_threshold(&5);
+        client.draw_credit(&borrower, &100, &None);
+        client.repay_credit(&borrower, &94);
+        client.close_credit_line(&borrower, &borrower);
+    }
+
+    #[test]
+    #[should_panic(expected = "dust threshold must be non-negative")]
+    fn test_negative_dust_threshold_reverts() {
+        let env = Env::default();
+        env.mock_all_auths();
+        let borrower = Address::generate(&env);
+        let (client, _token, _admin) = setup_contract_with_credit_line(&env, &borrower, 1_000, 0);
+        client.set_dust_threshold(&-1);
+    }
+
+    #[test]
+    fn test_repay_and_close_settles_interest_and_closes() {
+        use soroban_sdk::testutils::Ledger;
+        let env = Env::default();
+        env.mock_all_auths();
+        let borrower = Address::generate(&env);
+        let (client, token_address, _admin) =
+            setup_contract_with_credit_line(&env, &borrower, 1_000, 1_000);
+        client.draw_credit(&borrower, &1_000, &None);
+        env.ledger()
+            .with_mut(|li| li.timestamp += math::SECONDS_PER_YEAR);
+        token::StellarAssetClient::new(&env, &token_address).mint(&borrower, &100);
+
+        client.repay_and_close(&borrower);
+        let line = client.get_credit_line(&borrower).unwrap();
+        assert_eq!(line.status, CreditStatus::Closed);
+        assert_eq!(line.utilized_amount, 0);
+        assert_eq!(line.accrued_interest, 0);
+        assert_eq!(line.written_off_amount, 0);
+        // 1_000 principal plus 3% interest.
+        assert_eq!(
+            token::Client::new(&env, &token_address).balance(&borrower),
+            70
+        );
+    }
+
+    // ── per-draw limits ───────────────────────────────────────────────────────
+
+    #[test]
+    #[should_panic(expected = "draw below minimum")]
+    fn test_draw_below_global_minimum_reverts() {
+        let env = Env::default();
+        env.mock_all_auths();
+        let borrower = Address::generate(&env);
+        let (client, _token, _admin) =
+            setup_contract_with_credit_line(&env, &borrower, 1_000, 1_000);
+        client.set_draw_limits(&DrawLimits {
+            min_draw_amount: 10,
+            max_draw_amount: 0,
+            min_seconds_between_draws: 0,
+        });
+        client.draw_credit(&borrower, &9, &None);
+    }
+
+    #[test]
+    #[should_panic(expected = "draw exceeds maximum")]
+    fn test_draw_above_global_maximum_reverts() {
+        let env = Env::default();
+        env.mock_all_auths();
+        let borrower = Address::generate(&env);
+        let (client, _token, _admin) =
+            setup_contract_with_credit_line(&env, &borrower, 1_000, 1_000);
+        client.set_draw_limits(&DrawLimits {
+            min_draw_amount: 0,
+            max_draw_amount: 300,
+            min_seconds_between_draws: 0,
+        });
+        client.draw_credit(&borrower, &301, &None);
+    }
+
+    #[test]
+    fn test_line_draw_limits_override_global() {
+        let env = Env::default();
+        env.mock_all_auths();
+        let borrower = Address::generate(&env);
+        let (client, _token, _admin) =
+            setup_contract_with_credit_line(&env, &borrower, 1_000, 1_000);
+        client.set_draw_limits(&DrawLimits {
+            min_draw_amount: 10,
+            max_draw_amount: 300,
+            min_seconds_between_draws: 0,
+        });
+        client.set_line_draw_limits(
+            &borrower,
+            &DrawLimits {
+                min_draw_amount: 0,
+                max_draw_amount: 600,
+                min_seconds_between_draws: 0,
+            },
+        );
+        // The line's maximum replaces the global one; the global minimum still applies.
+        client.draw_credit(&borrower, &600, &None);
+        assert!(client.try_draw_credit(&borrower, &5, &None).is_err());
+        let line = client.get_credit_line(&borrower).unwrap();
+        assert_eq!(line.max_draw_amount, 600);
+        assert_eq!(line.utilized_amount, 600);
+    }
+
+    #[test]
+    #[should_panic(expected = "min_draw_amount exceeds max_draw_amount")]
+    fn test_inverted_draw_limits_revert() {
+        let env = Env::default();
+        env.mock_all_auths();
+        let borrower = Address::generate(&env);
+        let (client, _token, _admin) = setup_contract_with_credit_line(&env, &borrower, 1_000, 0);
+        client.set_draw_limits(&DrawLimits {
+            min_draw_amount: 500,
+            max_draw_amount: 100,
+            min_seconds_between_draws: 0,
+        });
+    }
+
+    #[test]
+    fn test_draw_cooldown_rejects_until_window_passes() {
+        use soroban_sdk::testutils::Ledger;
+        let env = Env::default();
+        env.mock_all_auths();
+        env.ledger().with_mut(|li| li.timestamp = 1_000);
+        let borrower = Address::generate(&env);
+        let (client, _token, _admin) =
+            setup_contract_with_credit_line(&env, &borrower, 1_000, 1_000);
+        client.set_draw_limits(&DrawLimits {
+            min_draw_amount: 0,
+            max_draw_amount: 0,
+            min_seconds_between_draws: 60,
+        });
+        client.draw_credit(&borrower, &100, &None);
+
+        env.ledger().with_mut(|li| li.timestamp = 1_059);
+        assert_eq!(
+            client.try_draw_credit(&borrower, &100, &None),
+            Err(Ok(soroban_sdk::Error::from_contract_error(
+                ContractError::DrawCooldown as u32
+            )))
+        );
+        assert_eq!(
+            client.preview_draw(&borrower, &100, &None).error,
+            ContractError::DrawCooldown as u32
+        );
+
+        env.ledger().with_mut(|li| li.timestamp = 1_060);
+        client.draw_credit(&borrower, &100, &None);
+        assert_eq!(
+            client.get_credit_line(&borrower).unwrap().utilized_amount,
+            200
+        );
+    }
+
+    #[test]
+    fn test_line_draw_cooldown_overrides_global() {
+        use soroban_sdk::testutils::Ledger;
+        let env = Env::default();
+        env.mock_all_auths();
+        env.ledger().with_mut(|li| li.timestamp = 1_000);
+        let borrower = Address::generate(&env);
+        let (client, _token, _admin) =
+            setup_contract_with_credit_line(&env, &borrower, 1_000, 1_000);
+        client.set_draw_limits(&DrawLimits {
+            min_draw_amount: 0,
+            max_draw_amount: 0,
+            min_seconds_between_draws: 60,
+        });
+        client.set_line_draw_limits(
+            &borrower,
+            &DrawLimits {
+                min_draw_amount: 0,
+                max_draw_amount: 0,
+                min_seconds_between_draws: 3_600,
+            },
+        );
+        client.draw_credit(&borrower, &100, &None);
+
+        env.ledger().with_mut(|li| li.timestamp = 1_000 + 60);
+        assert!(client.try_draw_credit(&borrower, &100, &None).is_err());
+        env.ledger().with_mut(|li| li.timestamp = 1_000 + 3_600);
+        client.draw_credit(&borrower, &100, &None);
+    }
+
+    // ── draw velocity limits ──────────────────────────────────────────────────
+
+    #[test]
+    fn test_borrower_window_cap_resets_next_window() {
+        use soroban_sdk::testutils::Ledger;
+        let env = Env::default();
+        env.mock_all_auths();
+        env.ledger().with_mut(|li| li.sequence_number = 1_000);
+        let borrower = Address::generate(&env);
+        let (client, _token, _admin) =
+            setup_contract_with_credit_line(&env, &borrower, 1_000, 1_000);
+        client.set_velocity_config(&VelocityConfig {
+            window_ledgers: 100,
+            borrower_window_cap: 300,
+            protocol_ledger_cap: 0,
+        });
+        client.draw_credit(&borrower, &200, &None);
+        client.draw_credit(&borrower, &100, &None);
+        assert_eq!(client.get_drawn_in_window(&borrower), 300);
+        assert_eq!(
+            client.try_draw_credit(&borrower, &1, &None),
+            Err(Ok(soroban_sdk::Error::from_contract_error(
+                ContractError::RateLimited as u32
+            )))
+        );
+
+        env.ledger().with_mut(|li| li.sequence_number = 1_100);
+        assert_eq!(client.get_drawn_in_window(&borrower), 0);
+        client.draw_credit(&borrower, &300, &None);
+        assert_eq!(
+            client.get_credit_line(&borrower).unwrap().utilized_amount,
+            600
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "Error(Contract, #3)")]
+    fn test_protocol_ledger_cap_spans_borrowers() {
+        let env = Env::default();
+        env.mock_all_auths();
+        let b1 = Address::generate(&env);
+        let b2 = Address::generate(&env);
+        let (client, _token, admin) = setup_contract_with_credit_line(&env, &b1, 1_000, 1_000);
+        client.open_credit_line(
+            &admin,
+            &b2,
+            &1_000,
+            &300_u32,
+            &70_u32,
+            &None,
+            &RateMode::Fixed,
+            &None,
+            &None,
+        );
+        client.set_velocity_config(&VelocityConfig {
+            window_ledgers: 0,
+            borrower_window_cap: 0,
+            protocol_ledger_cap: 500,
+        });
+        client.draw_credit(&b1, &400, &None);
+        client.draw_credit(&b2, &101, &None);
+    }
+
+    #[test]
+    #[should_panic(expected = "window_ledgers must be positive")]
+    fn test_borrower_cap_without_window_reverts() {
+        let env = Env::default();
+        env.mock_all_auths();
+        let borrower = Address::generate(&env);
+        let (client, _token, _admin) = setup_contract_with_credit_line(&env, &borrower, 1_000, 0);
+        client.set_velocity_config(&VelocityConfig {
+            window_ledgers: 0,
+            borrower_window_cap: 100,
+            protocol_ledger_cap: 0,
+        });
+    }
+
+    #[test]
+    fn test_epoch_spend_cap_limits_burst_until_epoch_ends() {
+        use soroban_sdk::testutils::Ledger;
+        let env = Env::default();
+        env.mock_all_auths();
+        env.ledger().with_mut(|li| li.timestamp = 1_000);
+        let borrower = Address::generate(&env);
+        let (client, _token, admin) =
+            setup_contract_with_credit_line(&env, &borrower, 1_000, 1_000);
+        let cap = SpendCap {
+            amount: 300,
+            epoch_secs: 3_600,
+        };
+        client.set_epoch_spend_cap(&admin, &borrower, &cap);
+        assert_eq!(client.get_epoch_spend_cap(&borrower), cap);
+
+        client.draw_credit(&borrower, &200, &None);
+        env.ledger().with_mut(|li| li.timestamp = 2_000);
+        client.draw_credit(&borrower, &100, &None);
+        assert_eq!(client.get_spent_in_epoch(&borrower), 300);
+        assert_eq!(
+            client.try_draw_credit(&borrower, &1, &None),
+            Err(Ok(soroban_sdk::Error::from_contract_error(
+                ContractError::RateLimited as u32
+            )))
+        );
+        assert_eq!(
+            client.preview_draw(&borrower, &1, &None).error,
+            ContractError::RateLimited as u32
+        );
+
+        // The epoch runs from the first draw, not the last.
+        env.ledger().with_mut(|li| li.timestamp = 1_000 + 3_600);
+        assert_eq!(client.get_spent_in_epoch(&borrower), 0);
+        client.draw_credit(&borrower, &300, &None);
+        assert_eq!(
+            client.get_credit_line(&borrower).unwrap().utilized_amount,
+            600
+        );
+    }
+
+    #[test]
+    fn test_removing_epoch_spend_cap_lifts_it() {
+        let env = Env::default();
+        env.mock_all_auths();
+        let borrower = Address::generate(&env);
+        let (client, _token, admin) =
+            setup_contract_with_credit_line(&env, &borrower, 1_000, 1_000);
+        client.set_epoch_spend_cap(
+            &admin,
+            &borrower,
+            &SpendCap {
+                amount: 100,
+                epoch_secs: 60,
+            },
+        );
+        client.draw_credit(&borrower, &100, &None);
+        assert!(client.try_draw_credit(&borrower, &100, &None).is_err());
+
+        client.set_epoch_spend_cap(&admin, &borrower, &SpendCap::default());
+        client.draw_credit(&borrower, &100, &None);
+        assert_eq!(client.get_spent_in_epoch(&borrower), 0);
+    }
+
+    #[test]
+    #[should_panic(expected = "epoch_secs must be positive")]
+    fn test_epoch_spend_cap_without_epoch_reverts() {
+        let env = Env::default();
+        env.mock_all_auths();
+        let borrower = Address::generate(&env);
+        let (client, _token, admin) = setup_contract_with_credit_line(&env, &borrower, 1_000, 0);
+        client.set_epoch_spend_cap(
+            &admin,
+            &borrower,
+            &SpendCap {
+                amount: 100,
+                epoch_secs: 0,
+            },
+        );
+    }
+
+    // ── large-draw circuit breaker ────────────────────────────────────────────
+
+    fn setup_large_draw_breaker<'a>(
+        env: &'a Env,
+        borrower: &'a Address,
+    ) -> (CreditClient<'a>, Address, Address) {
+        let (client, token_address, admin) =
+            setup_contract_with_credit_line(env, borrower, 1_000, 1_000);
+        client.set_large_draw_config(&LargeDrawConfig {
+            threshold: 500,
+            expiry_ledgers: 10,
+        });
+        (client, token_address, admin)
+    }
+
+    #[test]
+    #[should_panic(expected = "draw requires approval")]
+    fn test_direct_draw_above_threshold_reverts() {
+        let env = Env::default();
+        env.mock_all_auths();
+        let borrower = Address::generate(&env);
+        let (client, _token, _admin) = setup_large_draw_breaker(&env, &borrower);
+        client.draw_credit(&borrower, &501, &None);
+    }
+
+    #[test]
+    fn test_large_draw_executes_on_approval() {
+        use soroban_sdk::testutils::Events;
+        use soroban_sdk::TryIntoVal;
+        let env = Env::default();
+        env.mock_all_auths();
+        let borrower = Address::generate(&env);
+        let (client, token_address, admin) = setup_large_draw_breaker(&env, &borrower);
+        client.draw_credit(&borrower, &200, &None);
+
+        let request_id = client.request_large_draw(&borrower, &700);
+        assert_eq!(client.get_pending_draw(&borrower).unwrap().amount, 700);
+        assert_eq!(
+            client.get_credit_line(&borrower).unwrap().utilized_amount,
+            200
+        );
+
+        client.approve_large_draw(&admin, &borrower, &request_id);
+        let event: LargeDrawEvent = env
+            .events()
+            .all()
+            .last()
+            .unwrap()
+            .2
+            .try_into_val(&env)
+            .unwrap();
+        assert_eq!(event.request_id, request_id);
+        assert_eq!(event.amount, 700);
+        assert_eq!(client.get_pending_draw(&borrower), None);
+        assert_eq!(
+            client.get_credit_line(&borrower).unwrap().utilized_amount,
+            900
+        );
+        assert_eq!(
+            token::Client::new(&env, &token_address).balance(&borrower),
+            900
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "draw request expired")]
+    fn test_expired_large_draw_request_reverts() {
+        use soroban_sdk::testutils::Ledger;
+        let env = Env::default();
+        env.mock_all_auths();
+        let borrower = Address::generate(&env);
+        let (client, _token, admin) = setup_large_draw_breaker(&env, &borrower);
+        let request_id = client.request_large_draw(&borrower, &800);
+        env.ledger().with_mut(|li| li.sequence_number += 11);
+        client.approve_large_draw(&admin, &borrower, &request_id);
+    }
+
+    #[test]
+    #[should_panic(expected = "request id mismatch")]
+    fn test_replaced_large_draw_request_cannot_be_approved() {
+        let env = Env::default();
+        env.mock_all_auths();
+        let borrower = Address::generate(&env);
+        let (client, _token, admin) = setup_large_draw_breaker(&env, &borrower);
+        let first = client.request_large_draw(&borrower, &800);
+        client.request_large_draw(&borrower, &600);
+        client.approve_large_draw(&admin, &borrower, &first);
+    }
+
+    #[test]
+    #[should_panic(expected = "unauthorized")]
+    fn test_large_draw_approval_requires_risk_role() {
+        let env = Env::default();
+        env.mock_all_auths();
+        let borrower = Address::generate(&env);
+        let (client, _token, _admin) = setup_large_draw_breaker(&env, &borrower);
+        let request_id = client.request_large_draw(&borrower, &800);
+        client.approve_large_draw(&borrower, &borrower, &request_id);
+    }
+
+    // ── scheduled draws ───────────────────────────────────────────────────────
+
+    #[test]
+    fn test_scheduled_draw_executes_once_due() {
+        use soroban_sdk::testutils::{Events, Ledger};
+        use soroban_sdk::TryIntoVal;
+        let env = Env::default();
+        env.mock_all_auths();
+        env.ledger().with_mut(|li| li.timestamp = 1_000);
+        let borrower = Address::generate(&env);
+        let (client, token_address, _admin) =
+            setup_contract_with_credit_line(&env, &borrower, 1_000, 1_000);
+        let draw_id = client.schedule_draw(&borrower, &400, &5_000);
+        assert_eq!(
+            client.get_scheduled_draw(&borrower, &draw_id),
+            Some(ScheduledDraw {
+                draw_id,
+                amount: 400,
+                execute_after_ts: 5_000,
+            })
+        );
+        assert!(client
+            .try_execute_scheduled_draw(&borrower, &draw_id)
+            .is_err());
+
+        env.ledger().with_mut(|li| li.timestamp = 5_000);
+        client.execute_scheduled_draw(&borrower, &draw_id);
+        let event: ScheduledDrawEvent = env
+            .events()
+            .all()
+            .last()
+            .unwrap()
+            .2
+            .try_into_val(&env)
+            .unwrap();
+        assert_eq!(event.draw_id, draw_id);
+        assert_eq!(event.amount, 400);
+        assert_eq!(client.get_scheduled_draw(&borrower, &draw_id), None);
+        assert_eq!(
+            token::Client::new(&env, &token_address).balance(&borrower),
+            400
+        );
+        assert!(client
+            .try_execute_scheduled_draw(&borrower, &draw_id)
+            .is_err());
+    }
+
+    #[test]
+    #[should_panic(expected = "exceeds credit limit")]
+    fn test_scheduled_draw_checked_at_execution() {
+        use soroban_sdk::testutils::Ledger;
+        let env = Env::default();
+        env.mock_all_auths();
+        let borrower = Address::generate(&env);
+        let (client, _token, _admin) =
+            setup_contract_with_credit_line(&env, &borrower, 1_000, 1_000);
+        let draw_id = client.schedule_draw(&borrower, &600, &100);
+        client.draw_credit(&borrower, &500, &None);
+        env.ledger().with_mut(|li| li.timestamp = 100);
+        client.execute_scheduled_draw(&borrower, &draw_id);
+    }
+
+    #[test]
+    #[should_panic(expected = "scheduled draw not found")]
+    fn test_cancelled_scheduled_draw_cannot_execute() {
+        use soroban_sdk::testutils::Ledger;
+        let env = Env::default();
+        env.mock_all_auths();
+        let borrower = Address::generate(&env);
+        let (client, _token, _admin) =
+            setup_contract_with_credit_line(&env, &borrower, 1_000, 1_000);
+        let draw_id = client.schedule_draw(&borrower, &100, &100);
+        client.cancel_scheduled_draw(&borrower, &draw_id);
+        env.ledger().with_mut(|li| li.timestamp = 100);
+        client.execute_scheduled_draw(&borrower, &draw_id);
+    }
+
+    // ── streaming disbursement ────────────────────────────────────────────────
+
+    #[test]
+    fn test_stream_vests_linearly_and_locks_limit() {
+        use soroban_sdk::testutils::Ledger;
+        let env = Env::default();
+        env.mock_all_auths();
+        env.ledger().with_mut(|li| li.timestamp = 1_000);
+        let borrower = Address::generate(&env);
+        let (client, token_address, _admin) =
+            setup_contract_with_credit_line(&env, &borrower, 1_000, 1_000);
+        client.open_stream(&borrower, &800, &1_000);
+        // The unclaimed stream is held against the limit.
+        assert!(client.try_draw_credit(&borrower, &201, &None).is_err());
+        assert_eq!(client.get_health(&borrower).unwrap().available, 200);
+
+        env.ledger().with_mut(|li| li.timestamp = 1_250);
+        assert_eq!(client.get_stream_claimable(&borrower), 200);
+        assert_eq!(client.claim_streamed(&borrower), 200);
+        assert_eq!(client.get_stream(&borrower).unwrap().claimed, 200);
+        assert!(client.try_claim_streamed(&borrower).is_err());
+
+        env.ledger().with_mut(|li| li.timestamp = 5_000);
+        assert_eq!(client.claim_streamed(&borrower), 600);
+        assert_eq!(client.get_stream(&borrower), None);
+        assert_eq!(
+            client.get_credit_line(&borrower).unwrap().utilized_amount,
+            800
+        );
+        assert_eq!(
+            token::Client::new(&env, &token_address).balance(&borrower),
+            800
+        );
+        client.draw_credit(&borrower, &200, &None);
+    }
+
+    #[test]
+    #[should_panic(expected = "exceeds credit limit")]
+    fn test_stream_above_available_credit_reverts() {
+        let env = Env::default();
+        env.mock_all_auths();
+        let borrower = Address::generate(&env);
+        let (client, _token, _admin) =
+            setup_contract_with_credit_line(&env, &borrower, 1_000, 1_000);
+        client.draw_credit(&borrower, &500, &None);
+        client.open_stream(&borrower, &501, &1_000);
+    }
+
+    #[test]
+    #[should_panic(expected = "stream already open")]
+    fn test_second_stream_reverts() {
+        let env = Env::default();
+        env.mock_all_auths();
+        let borrower = Address::generate(&env);
+        let (client, _token, _admin) =
+            setup_contract_with_credit_line(&env, &borrower, 1_000, 1_000);
+        client.open_stream(&borrower, &100, &1_000);
+        client.open_stream(&borrower, &100, &1_000);
+    }
+
+    // ── reserve ratio ─────────────────────────────────────────────────────────
+
+    #[test]
+    #[should_panic(expected = "reserve ratio too low")]
+    fn test_draw_below_min_reserve_ratio_reverts() {
+        let env = Env::default();
+        env.mock_all_auths();
+        let borrower = Address::generate(&env);
+        let (client, _token, _admin) =
+            setup_contract_with_credit_line(&env, &borrower, 1_000, 1_000);
+        client.set_reserve_config(&ReserveConfig {
+            min_reserve_ratio_bps: 5_000,
+            warning_ratio_bps: 0,
+        });
+        client.draw_credit(&borrower, &300, &None);
+        // 300 left against 700 outstanding is 42.85%.
+        client.draw_credit(&borrower, &400, &None);
+    }
+
+    #[test]
+    fn test_draw_under_warning_ratio_emits_reserve_low() {
+        use soroban_sdk::testutils::Events;
+        use soroban_sdk::{TryFromVal, TryIntoVal};
+        let env = Env::default();
+        env.mock_all_auths();
+        let borrower = Address::generate(&env);
+        let (client, token_address, _admin) =
+            setup_contract_with_credit_line(&env, &borrower, 1_000, 1_000);
+        assert_eq!(client.get_reserve_ratio_bps(&token_address), i128::MAX);
+        client.set_reserve_config(&ReserveConfig {
+            min_reserve_ratio_bps: 5_000,
+            warning_ratio_bps: 20_000,
+        });
+        client.draw_credit(&borrower, &400, &None);
+
+        let (_contract, _topics, data) = env
+            .events()
+            .all()
+            .iter()
+            .find(|(_, topics, _)| {
+                Symbol::try_from_val(&env, &topics.get(1).unwrap()).ok()
+                    == Some(symbol_short!("res_low"))
+            })
+            .unwrap();
+        let event: ReserveLowEvent = data.try_into_val(&env).unwrap();
+        assert_eq!(event.reserve, 600);
+        assert_eq!(event.outstanding, 400);
+        assert_eq!(event.ratio_bps, 15_000);
+        assert_eq!(client.get_reserve_ratio_bps(&token_address), 15_000);
+    }
+
+    /// Reserve threshold events published by the last invocation.
+    fn reserve_threshold_events(env: &Env) -> Vec<ReserveThresholdEvent> {
+        use soroban_sdk::testutils::Events;
+        use soroban_sdk::{TryFromVal, TryIntoVal};
+        let mut found = Vec::new(env);
+        for (_contract, topics, data) in env.events().all().iter() {
+            if Symbol::try_from_val(env, &topics.get(1).unwrap()).ok()
+                == Some(symbol_short!("res_thr"))
+            {
+                found.push_back(data.try_into_val(env).unwrap());
+            }
+        }
+        found
+    }
+
+    #[test]
+    fn test_draw_crossing_reserve_thresholds_emits_once_per_crossing() {
+        let env = Env::default();
+        env.mock_all_auths();
+        let borrower = Address::generate(&env);
+        let (client, token_address, _admin) =
+            setup_contract_with_credit_line(&env, &borrower, 1_000, 1_000);
+        client.set_reserve_critical_ratio(&10_000);
+        client.set_reserve_config(&ReserveConfig {
+            min_reserve_ratio_bps: 0,
+            warning_ratio_bps: 30_000,
+        });
+
+        // 800 against 200 outstanding is 400%.
+        client.draw_credit(&borrower, &200, &None);
+        assert_eq!(reserve_threshold_events(&env).len(), 0);
+
+        // 700 against 300 is 233%: below the warning.
+        client.draw_credit(&borrower, &100, &None);
+        let events = reserve_threshold_events(&env);
+        assert_eq!(events.len(), 1);
+        let warning = events.get(0).unwrap();
+        assert_eq!(warning.token, token_address);
+        assert_eq!(warning.level, reserve::ReserveLevel::Warning);
+        assert_eq!(warning.threshold_bps, 30_000);
+        assert_eq!(warning.reserve, 700);
+        assert_eq!(warning.outstanding, 300);
+        assert_eq!(warning.ratio_bps, 23_333);
+
+        // Still below the warning: no new crossing.
+        client.draw_credit(&borrower, &100, &None);
+        assert_eq!(reserve_threshold_events(&env).len(), 0);
+
+        // 400 against 600 is 66%: below the critical level.
+        client.draw_credit(&borrower, &200, &None);
+        let events = reserve_threshold_events(&env);
+        assert_eq!(events.len(), 1);
+        let critical = events.get(0).unwrap();
+        assert_eq!(critical.level, reserve::ReserveLevel::Critical);
+        assert_eq!(critical.threshold_bps, 10_000);
+        assert_eq!(critical.ratio_bps, 6_666);
+    }
+
+    #[test]
+    fn test_draw_below_critical_reserve_reverts_until_replenished() {
+        let env = Env::default();
+        env.mock_all_auths();
+        let borrower = Address::generate(&env);
+        let (client, _token, _admin) =
+            setup_contract_with_credit_line(&env, &borrower, 1_000, 1_000);
+        client.set_reserve_critical_ratio(&10_000);
+        // 400 against 600 crosses the critical level but is allowed.
+        client.draw_credit(&borrower, &600, &None);
+
+        let result = client.try_draw_credit(&borrower, &1, &None);
+        assert!(result.is_err());
+
+        // 500 against 500 is back at the critical level.
+        client.repay_credit(&borrower, &100);
+        client.draw_credit(&borrower, &1, &None);
+    }
+
+    #[test]
+    #[should_panic(expected = "warning_ratio_bps below critical_ratio_bps")]
+    fn test_reserve_critical_above_warning_reverts() {
+        let env = Env::default();
+        env.mock_all_auths();
+        let borrower = Address::generate(&env);
+        let (client, _token, _admin) = setup_contract_with_credit_line(&env, &borrower, 1_000, 0);
+        client.set_reserve_config(&ReserveConfig {
+            min_reserve_ratio_bps: 0,
+            warning_ratio_bps: 20_000,
+        });
+        client.set_reserve_critical_ratio(&25_000);
+    }
+
+    #[test]
+    #[should_panic(expected = "warning_ratio_bps below min_reserve_ratio_bps")]
+    fn test_reserve_warning_below_minimum_reverts() {
+        let env = Env::default();
+        env.mock_all_auths();
+        let borrower = Address::generate(&env);
+        let (client, _token, _admin) = setup_contract_with_credit_line(&env, &borrower, 1_000, 0);
+        client.set_reserve_config(&ReserveConfig {
+            min_reserve_ratio_bps: 5_000,
+            warning_ratio_bps: 4_000,
+        });
+    }
+
+    // ── liquidity sources ─────────────────────────────────────────────────────
+
+    /// Register `source` funded with `balance` and approved for the contract to spend.
+    fn add_funded_source(
+        env: &Env,
+        client: &CreditClient,
+        token_address: &Address,
+        balance: i128,
+        priority: u32,
+    ) -> Address {
+        let source = Address::generate(env);
+        token::StellarAssetClient::new(env, token_address).mint(&source, &balance);
+        token::Client::new(env, token_address).approve(&source, &client.address, &balance, &1_000);
+        client.add_liquidity_source(&source, &priority);
+        source
+    }
+
+    #[test]
+    fn test_draw_splits_across_sources_in_priority_order() {
+        let env = Env::default();
+        env.mock_all_auths();
+        let borrower = Address::generate(&env);
+        let (client, token_address, _admin) =
+            setup_contract_with_credit_line(&env, &borrower, 1_000, 300);
+        let low = add_funded_source(&env, &client, &token_address, 500, 2);
+        let high = add_funded_source(&env, &client, &token_address, 200, 1);
+        let sources = client.get_liquidity_sources();
+        assert_eq!(sources.get(0).unwrap().address, high);
+        assert_eq!(sources.get(1).unwrap().address, low);
+
+        client.draw_credit(&borrower, &600, &None);
+        let token_client = token::Client::new(&env, &token_address);
+        assert_eq!(token_client.balance(&borrower), 600);
+        assert_eq!(token_client.balance(&client.address), 0);
+        assert_eq!(token_client.balance(&high), 0);
+        assert_eq!(token_client.balance(&low), 400);
+    }
+
+    #[test]
+    #[should_panic(expected = "insufficient liquidity")]
+    fn test_draw_beyond_all_sources_reverts() {
+        let env = Env::default();
+        env.mock_all_auths();
+        let borrower = Address::generate(&env);
+        let (client, token_address, _admin) =
+            setup_contract_with_credit_line(&env, &borrower, 1_000, 300);
+        add_funded_source(&env, &client, &token_address, 200, 1);
+        client.draw_credit(&borrower, &600, &None);
+    }
+
+    #[test]
+    fn test_repayments_follow_route() {
+        let env = Env::default();
+        env.mock_all_auths();
+        let borrower = Address::generate(&env);
+        let sink = Address::generate(&env);
+        let (client, token_address, _admin) =
+            setup_contract_with_credit_line(&env, &borrower, 1_000, 1_000);
+        let source = add_funded_source(&env, &client, &token_address, 0, 1);
+        let token_client = token::Client::new(&env, &token_address);
+        client.draw_credit(&borrower, &500, &None);
+
+        client.repay_credit(&borrower, &100);
+        assert_eq!(token_client.balance(&client.address), 600);
+
+        client.set_repayment_route(&RepaymentRoute::TopSource);
+        client.repay_credit(&borrower, &100);
+        assert_eq!(token_client.balance(&source), 100);
+
+        client.set_repayment_route(&RepaymentRoute::Sink(sink.clone()));
+        client.repay_credit(&borrower, &100);
+        assert_eq!(token_client.balance(&sink), 100);
+        assert_eq!(token_client.balance(&client.address), 600);
+    }
+
+    #[test]
+    fn test_rebalance_reserve_moves_funds_between_sources() {
+        use soroban_sdk::testutils::Events;
+        use soroban_sdk::{TryFromVal, TryIntoVal};
+        let env = Env::default();
+        env.mock_all_auths();
+        let borrower = Address::generate(&env);
+        let treasurer = Address::generate(&env);
+        let (client, token_address, _admin) =
+            setup_contract_with_credit_line(&env, &borrower, 1_000, 1_000);
+        client.grant_role(&Role::Treasurer, &treasurer);
+        let vault = add_funded_source(&env, &client, &token_address, 500, 1);
+        let treasury = add_funded_source(&env, &client, &token_address, 0, 2);
+
+        client.rebalance_reserve(&treasurer, &token_address, &vault, &treasury, &200);
+        let (_contract, _topics, data) = env
+            .events()
+            .all()
+            .iter()
+            .find(|(_, topics, _)| {
+                Symbol::try_from_val(&env, &topics.get(1).unwrap()).ok()
+                    == Some(symbol_short!("rebalance"))
+            })
+            .unwrap();
+        let event: ReserveRebalancedEvent = data.try_into_val(&env).unwrap();
+        assert_eq!(event.caller, treasurer);
+        assert_eq!(event.amount, 200);
+        // The treasury has not approved the contract, so its 200 is not available.
+        assert_eq!(event.total_available, 1_300);
+
+        let token_client = token::Client::new(&env, &token_address);
+        assert_eq!(token_client.balance(&vault), 300);
+        assert_eq!(token_client.balance(&treasury), 200);
+        assert_eq!(token_client.balance(&client.address), 1_000);
+        assert_eq!(
+            token_client.allowance(&vault, &client.address),
+            300,
+            "the move spends the source's allowance"
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "rebalance breaches reserve policy")]
+    fn test_rebalance_reserve_below_min_ratio_reverts() {
+        let env = Env::default();
+        env.mock_all_auths();
+        let borrower = Address::generate(&env);
+        let (client, token_address, admin) =
+            setup_contract_with_credit_line(&env, &borrower, 1_000, 1_000);
+        let vault = add_funded_source(&env, &client, &token_address, 500, 1);
+        let treasury = add_funded_source(&env, &client, &token_address, 0, 2);
+        client.draw_credit(&borrower, &500, &None);
+        // 150% of 500 outstanding requires 750 available; 1_000 is.
+        client.set_reserve_config(&ReserveConfig {
+            min_reserve_ratio_bps: 15_000,
+            warning_ratio_bps: 0,
+        });
+
+        client.rebalance_reserve(&admin, &token_address, &vault, &treasury, &200);
+        client.rebalance_reserve(&admin, &token_address, &vault, &treasury, &100);
+    }
+
+    #[test]
+    fn test_rebalance_reserve_requires_treasurer() {
+        let env = Env::default();
+        env.mock_all_auths();
+        let borrower = Address::generate(&env);
+        let (client, token_address, _admin) =
+            setup_contract_with_credit_line(&env, &borrower, 1_000, 1_000);
+        let vault = add_funded_source(&env, &client, &token_address, 500, 1);
+        let treasury = add_funded_source(&env, &client, &token_address, 0, 2);
+        let result = client.try_rebalance_reserve(
+            &Address::generate(&env),
+            &token_address,
+            &vault,
+            &treasury,
+            &100,
+        );
+        assert!(result.is_err());
+    }
+
+    #[test]
+    #[should_panic(expected = "liquidity source not found")]
+    fn test_rebalance_reserve_to_unregistered_source_reverts() {
+        let env = Env::default();
+        env.mock_all_auths();
+        let borrower = Address::generate(&env);
+        let (client, token_address, admin) =
+            setup_contract_with_credit_line(&env, &borrower, 1_000, 1_000);
+        let vault = add_funded_source(&env, &client, &token_address, 500, 1);
+        client.rebalance_reserve(
+            &admin,
+            &token_address,
+            &vault,
+            &Address::generate(&env),
+            &100,
+        );
+    }
+
+    // ── token sweep ───────────────────────────────────────────────────────────
+
+    #[test]
+    fn test_treasurer_sweeps_stray_token() {
+        use soroban_sdk::testutils::Events;
+        use soroban_sdk::TryIntoVal;
+        let env = Env::default();
+        env.mock_all_auths();
+        let borrower = Address::generate(&env);
+        let treasurer = Address::generate(&env);
+        let to = Address::generate(&env);
+        let (client, _token, _admin) = setup_contract_with_credit_line(&env, &borrower, 1_000, 0);
+        client.grant_role(&Role::Treasurer, &treasurer);
+        let (stray, _) = setup_token(&env, &client.address, 250);
+
+        client.sweep_token(&treasurer, &stray, &to, &250);
+        let event: SweepEvent = env
+            .events()
+            .all()
+            .last()
+            .unwrap()
+            .2
+            .try_into_val(&env)
+            .unwrap();
+        assert_eq!(event.amount, 250);
+        assert_eq!(event.to, to);
+        assert_eq!(token::Client::new(&env, &stray).balance(&to), 250);
+    }
+
+    #[test]
+    #[should_panic(expected = "cannot sweep credit token")]
+    fn test_sweep_of_credit_token_reverts() {
+        let env = Env::default();
+        env.mock_all_auths();
+        let borrower = Address::generate(&env);
+        let (client, token_address, admin) =
+            setup_contract_with_credit_line(&env, &borrower, 1_000, 1_000);
+        client.sweep_token(&admin, &token_address, &admin, &1);
+    }
+
+    #[test]
+    #[should_panic(expected = "sweep exceeds stray balance")]
+    fn test_sweep_cannot_take_collateral() {
+        let env = Env::default();
+        env.mock_all_auths();
+        let borrower = Address::generate(&env);
+        let (client, _token, admin) = setup_contract_with_credit_line(&env, &borrower, 1_000, 0);
+        let (collateral_token, sac) = setup_token(&env, &borrower, 400);
+        client.set_collateral_token(&collateral_token, &true);
+        client.deposit_collateral(&borrower, &collateral_token, &400);
+        sac.mint(&client.address, &50);
+        client.sweep_token(&admin, &collateral_token, &admin, &51);
+    }
+
+    #[test]
+    #[should_panic(expected = "unauthorized")]
+    fn test_sweep_requires_treasurer_role() {
+        let env = Env::default();
+        env.mock_all_auths();
+        let borrower = Address::generate(&env);
+        let (client, _token, _admin) = setup_contract_with_credit_line(&env, &borrower, 1_000, 0);
+        let (stray, _) = setup_token(&env, &client.address, 250);
+        client.sweep_token(&borrower, &stray, &borrower, &250);
+    }
+
+    // ── insurance fund ────────────────────────────────────────────────────────
+
+    #[test]
+    fn test_insurance_premium_withheld_into_fund() {
+        let env = Env::default();
+        env.mock_all_auths();
+        let borrower = Address::generate(&env);
+        let (client, token_address, _admin) =
+            setup_contract_with_credit_line(&env, &borrower, 1_000, 1_000);
+        client.set_fee_config(&FeeConfig {
+            draw_fee_bps: 100,
+            origination_fee: 0,
+        });
+        client.set_insurance_config(&InsuranceConfig { premium_bps: 50 });
+
+        client.draw_credit(&borrower, &600, &None);
+
+        assert_eq!(
+            client.get_credit_line(&borrower).unwrap().utilized_amount,
+            600
+        );
+        assert_eq!(
+            token::Client::new(&env, &token_address).balance(&borrower),
+            591
+        );
+        assert_eq!(client.get_protocol_fees(&token_address), 6);
+        assert_eq!(client.get_insurance_fund(&token_address), 3);
+    }
+
+    #[test]
+    fn test_default_absorbed_by_insurance_fund() {
+        use soroban_sdk::testutils::Events;
+        use soroban_sdk::{TryFromVal, TryIntoVal};
+        let env = Env::default();
+        env.mock_all_auths();
+        let borrower = Address::generate(&env);
+        let (client, token_address, _admin) =
+            setup_contract_with_credit_line(&env, &borrower, 1_000, 1_000);
+        client.set_insurance_config(&InsuranceConfig { premium_bps: 1_000 });
+        client.draw_credit(&borrower, &500, &None);
+        assert_eq!(client.get_insurance_fund(&token_address), 50);
+
+        client.default_credit_line(&borrower);
+
+        let events = env.events().all();
+        let payout = events
+            .iter()
+            .find(|(_, topics, _)| {
+                Symbol::try_from_val(&env, &topics.get(1).unwrap()).unwrap()
+                    == symbol_short!("insured")
+            })
+            .expect("payout event");
+        let event: InsurancePayoutEvent = payout.2.try_into_val(&env).unwrap();
+        assert_eq!(event.borrower, borrower);
+        assert_eq!(event.token, token_address);
+        assert_eq!(event.covered, 50);
+        assert_eq!(event.uncovered, 450);
+
+        let line = client.get_credit_line(&borrower).unwrap();
+        assert_eq!(line.status, CreditStatus::Defaulted);
+        assert_eq!(line.utilized_amount, 450);
+        assert_eq!(client.get_insurance_fund(&token_address), 0);
+    }
+
+    #[test]
+    fn test_default_fully_covered_by_insurance_fund() {
+        let env = Env::default();
+        env.mock_all_auths();
+        let borrower = Address::generate(&env);
+        let other = Address::generate(&env);
+        let (client, token_address, admin) =
+            setup_contract_with_credit_line(&env, &borrower, 1_000, 2_000);
+        client.open_credit_line(
+            &admin,
+            &other,
+            &1_000,
+            &300_u32,
+            &70_u32,
+            &None,
+            &RateMode::Fixed,
+            &None,
+            &None,
+        );
+        client.set_insurance_config(&InsuranceConfig { premium_bps: 1_000 });
+        client.draw_credit(&other, &900, &None);
+        client.draw_credit(&borrower, &40, &None);
+
+        client.default_credit_line(&borrower);
+
+        assert_eq!(
+            client.get_credit_line(&borrower).unwrap().utilized_amount,
+            0
+        );
+        assert_eq!(client.get_insurance_fund(&token_address), 94 - 40);
+    }
+
+    #[test]
+    #[should_panic(expected = "premium and draw fee cannot exceed 10000 bps")]
+    fn test_insurance_premium_plus_draw_fee_over_max_reverts() {
+        let env = Env::default();
+        env.mock_all_auths();
+        let borrower = Address::generate(&env);
+        let (client, _token, _admin) = setup_contract_with_credit_line(&env, &borrower, 1_000, 0);
+        client.set_fee_config(&FeeConfig {
+            draw_fee_bps: 9_000,
+            origination_fee: 0,
+        });
+        client.set_insurance_config(&InsuranceConfig { premium_bps: 1_001 });
+    }
+
+    // ── rewards ───────────────────────────────────────────────────────────────
+
+    /// Line with 1_000 drawn a year ago and rewards at `config`, with 30 of interest due.
+    fn setup_rewarded_line<'a>(
+        env: &'a Env,
+        borrower: &'a Address,
+        config: &RewardsConfig,
+    ) -> CreditClient<'a> {
+        use soroban_sdk::testutils::Ledger;
+        let (client, _token, _admin) = setup_contract_with_credit_line(env, borrower, 1_000, 1_000);
+        client.set_rewards_config(config);
+        client.draw_credit(borrower, &1_000, &None);
+        env.ledger()
+            .with_mut(|li| li.timestamp += math::SECONDS_PER_YEAR);
+        client
+    }
+
+    #[test]
+    fn test_on_time_repayment_accrues_claimable_rewards() {
+        use soroban_sdk::testutils::Events;
+        let env = Env::default();
+        env.mock_all_auths();
+        let borrower = Address::generate(&env);
+        let client = setup_rewarded_line(&env, &borrower, &RewardsConfig::default());
+        let (reward_token, _) = setup_token(&env, &client.address, 1_000);
+        client.set_rewards_config(&RewardsConfig {
+            rate_bps: 5_000,
+            reward_token: Some(reward_token.clone()),
+            max_unclaimed: 0,
+            max_total: 0,
+        });
+
+        // Half of the 30 interest paid.
+        client.repay_credit(&borrower, &100);
+        assert_eq!(client.get_rewards(&borrower), 15);
+        // Principal only from here on: no interest, no points.
+        client.repay_credit(&borrower, &100);
+        assert_eq!(client.get_rewards(&borrower), 15);
+
+        assert_eq!(client.claim_rewards(&borrower), 15);
+        assert_eq!(env.events().all().len(), 2, "token transfer and claim");
+        assert_eq!(client.get_rewards(&borrower), 0);
+        assert_eq!(
+            token::Client::new(&env, &reward_token).balance(&borrower),
+            15
+        );
+        assert_eq!(client.claim_rewards(&borrower), 0);
+    }
+
+    #[test]
+    fn test_rewards_capped_per_borrower_and_in_total() {
+        let env = Env::default();
+        env.mock_all_auths();
+        let borrower = Address::generate(&env);
+        let client = setup_rewarded_line(
+            &env,
+            &borrower,
+            &RewardsConfig {
+                rate_bps: 10_000,
+                reward_token: None,
+                max_unclaimed: 10,
+                max_total: 15,
+            },
+        );
+        // 20 points earned, 10 kept under the unclaimed cap.
+        client.repay_credit(&borrower, &20);
+        assert_eq!(client.get_rewards(&borrower), 10);
+        assert_eq!(client.claim_rewards(&borrower), 10);
+
+        // The lifetime cap leaves 5 of this payment's 10 points.
+        client.repay_credit(&borrower, &10);
+        assert_eq!(client.get_rewards(&borrower), 5);
+    }
+
+    #[test]
+    fn test_late_repayment_accrues_no_rewards() {
+        use soroban_sdk::testutils::Ledger;
+        let env = Env::default();
+        env.mock_all_auths();
+        let borrower = Address::generate(&env);
+        let (client, token_address, _admin) =
+            setup_scheduled_line(&env, &borrower, 30 * 86_400, 1_000);
+        token::StellarAssetClient::new(&env, &token_address).mint(&borrower, &1_000);
+        client.set_rewards_config(&RewardsConfig {
+            rate_bps: 10_000,
+            reward_token: None,
+            max_unclaimed: 0,
+            max_total: 0,
+        });
+        client.draw_credit(&borrower, &1_000, &None);
+        env.ledger()
+            .with_mut(|li| li.timestamp += math::SECONDS_PER_YEAR);
+
+        client.repay_credit(&borrower, &500);
+        assert_eq!(client.get_rewards(&borrower), 0);
+    }
+
+    #[test]
+    fn test_paused_rewards_stop_accrual_and_claims() {
+        let env = Env::default();
+        env.mock_all_auths();
+        let borrower = Address::generate(&env);
+        let pauser = Address::generate(&env);
+        let client = setup_rewarded_line(
+            &env,
+            &borrower,
+            &RewardsConfig {
+                rate_bps: 10_000,
+                reward_token: None,
+                max_unclaimed: 0,
+                max_total: 0,
+            },
+        );
+        client.repay_credit(&borrower, &10);
+        client.grant_role(&Role::Pauser, &pauser);
+        client.set_rewards_paused(&pauser, &true);
+        assert!(client.is_rewards_paused());
+
+        client.repay_credit(&borrower, &10);
+        assert_eq!(client.get_rewards(&borrower), 10);
+        assert!(client.try_claim_rewards(&borrower).is_err());
+
+        client.set_rewards_paused(&pauser, &false);
+        assert_eq!(client.claim_rewards(&borrower), 10);
+    }
+
+    #[test]
+    #[should_panic(expected = "reward token cannot be a credit token")]
+    fn test_credit_token_as_reward_token_reverts() {
+        let env = Env::default();
+        env.mock_all_auths();
+        let borrower = Address::generate(&env);
+        let (client, token_address, _admin) =
+            setup_contract_with_credit_line(&env, &borrower, 1_000, 0);
+        client.set_rewards_config(&RewardsConfig {
+            rate_bps: 100,
+            reward_token: Some(token_address),
+            max_unclaimed: 0,
+            max_total: 0,
+        });
+    }
+
+    // ── staking ───────────────────────────────────────────────────────────────
+
+    /// Stake token funded to `borrower` and tiers of 50 bps off from 100 staked and
+    /// 150 bps off from 500, with stakes locked above `unstake_threshold`.
+    fn setup_staking(
+        env: &Env,
+        client: &CreditClient,
+        borrower: &Address,
+        unstake_threshold: i128,
+    ) -> Address {
+        let (stake_token, _) = setup_token(env, borrower, 1_000);
+        client.set_staking_config(&StakingConfig {
+            token: Some(stake_token.clone()),
+            tiers: Vec::from_array(
+                env,
+                [
+                    staking::StakeTier {
+                        min_stake: 100,
+                        discount_bps: 50,
+                    },
+                    staking::StakeTier {
+                        min_stake: 500,
+                        discount_bps: 150,
+                    },
+                ],
+            ),
+            unstake_threshold,
+        });
+        stake_token
+    }
+
+    #[test]
+    fn test_stake_discounts_accrued_interest() {
+        use soroban_sdk::testutils::Ledger;
+        let env = Env::default();
+        env.mock_all_auths();
+        let borrower = Address::generate(&env);
+        let (client, _token, _admin) =
+            setup_contract_with_credit_line(&env, &borrower, 1_000, 1_000);
+        let stake_token = setup_staking(&env, &client, &borrower, 0);
+        assert_eq!(client.get_effective_rate(&borrower), 300);
+
+        client.stake(&borrower, &100);
+        assert_eq!(client.get_effective_rate(&borrower), 250);
+        client.stake(&borrower, &400);
+        assert_eq!(client.get_stake(&borrower), 500);
+        assert_eq!(client.get_effective_rate(&borrower), 150);
+        assert_eq!(
+            token::Client::new(&env, &stake_token).balance(&client.address),
+            500
+        );
+
+        client.draw_credit(&borrower, &1_000, &None);
+        env.ledger()
+            .with_mut(|li| li.timestamp += math::SECONDS_PER_YEAR);
+        // 1.5% instead of 3% of 1_000 for a year.
+        assert_eq!(client.accrue_interest(&borrower), 15);
+    }
+
+    #[test]
+    fn test_unstake_locked_while_balance_outstanding() {
+        let env = Env::default();
+        env.mock_all_auths();
+        let borrower = Address::generate(&env);
+        let (client, _token, _admin) =
+            setup_contract_with_credit_line(&env, &borrower, 1_000, 1_000);
+        let stake_token = setup_staking(&env, &client, &borrower, 100);
+        client.stake(&borrower, &500);
+        client.draw_credit(&borrower, &300, &None);
+
+        let result = client.try_unstake(&borrower, &500);
+        assert!(result.is_err());
+
+        client.repay_credit(&borrower, &200);
+        client.unstake(&borrower, &500);
+        assert_eq!(client.get_stake(&borrower), 0);
+        assert_eq!(client.get_effective_rate(&borrower), 300);
+        assert_eq!(
+            token::Client::new(&env, &stake_token).balance(&borrower),
+            1_000
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "staking tiers must ascend")]
+    fn test_staking_tiers_out_of_order_revert() {
+        let env = Env::default();
+        env.mock_all_auths();
+        let borrower = Address::generate(&env);
+        let (client, _token, _admin) = setup_contract_with_credit_line(&env, &borrower, 1_000, 0);
+        client.set_staking_config(&StakingConfig {
+            token: Some(Address::generate(&env)),
+            tiers: Vec::from_array(
+                &env,
+                [
+                    staking::StakeTier {
+                        min_stake: 500,
+                        discount_bps: 150,
+                    },
+                    staking::StakeTier {
+                        min_stake: 100,
+                        discount_bps: 50,
+                    },
+                ],
+            ),
+            unstake_threshold: 0,
+        });
+    }
+
+    #[test]
+    #[should_panic(expected = "sweep exceeds stray balance")]
+    fn test_sweep_cannot_take_stakes() {
+        let env = Env::default();
+        env.mock_all_auths();
+        let borrower = Address::generate(&env);
+        let (client, _token, admin) = setup_contract_with_credit_line(&env, &borrower, 1_000, 0);
+        let stake_token = setup_staking(&env, &client, &borrower, 0);
+        client.stake(&borrower, &500);
+        client.sweep_token(&admin, &stake_token, &admin, &1);
+    }
+
+    // ── flash draws ───────────────────────────────────────────────────────────
+
+    mod mock_flash_receiver {
+        use soroban_sdk::{contract, contractimpl, token, Address, Bytes, Env, Symbol};
+
+        #[contract]
+        pub struct MockFlashReceiver;
+
+        #[contractimpl]
+        impl MockFlashReceiver {
+            pub fn set_lender(env: Env, lender: Address) {
+                env.storage()
+                    .instance()
+                    .set(&Symbol::new(&env, "lender"), &lender);
+            }
+
+            /// Repays `amount + fee`, or only `amount` when `data` is non-empty.
+            pub fn on_flash_draw(env: Env, token: Address, amount: i128, fee: i128, data: Bytes) {
+                let lender: Address = env
+                    .storage()
+                    .instance()
+                    .get(&Symbol::new(&env, "lender"))
+                    .unwrap();
+                let repay = if data.is_empty() {
+                    amount + fee
+                } else {
+                    amount
+                };
+                token::Client::new(&env, &token).transfer(
+                    &env.current_contract_address(),
+                    &lender,
+                    &repay,
+                );
+            }
+        }
+    }
+
+    /// Line with a reserve of 1_000, flash draws of up to 1_000 at 1%, and a
+    /// receiver holding 100 for fees.
+    fn setup_flash<'a>(
+        env: &'a Env,
+        borrower: &'a Address,
+    ) -> (CreditClient<'a>, Address, Address) {
+        let (client, token_address, _admin) =
+            setup_contract_with_credit_line(env, borrower, 1_000, 1_000);
+        client.set_flash_config(&FlashConfig {
+            fee_bps: 100,
+            max_amount: 1_000,
+        });
+        let receiver = env.register(mock_flash_receiver::MockFlashReceiver, ());
+        mock_flash_receiver::MockFlashReceiverClient::new(env, &receiver)
+            .set_lender(&client.address);
+        token::StellarAssetClient::new(env, &token_address).mint(&receiver, &100);
+        (client, token_address, receiver)
+    }
+
+    #[test]
+    fn test_flash_draw_repaid_with_fee() {
+        use soroban_sdk::testutils::Events;
+        use soroban_sdk::TryIntoVal;
+        let env = Env::default();
+        env.mock_all_auths();
+        let borrower = Address::generate(&env);
+        let (client, token_address, receiver) = setup_flash(&env, &borrower);
+
+        assert_eq!(client.flash_draw(&receiver, &500, &Bytes::new(&env)), 5);
+        let event: FlashDrawEvent = env
+            .events()
+            .all()
+            .last()
+            .unwrap()
+            .2
+            .try_into_val(&env)
+            .unwrap();
+        assert_eq!(event.receiver, receiver);
+        assert_eq!(event.amount, 500);
+        assert_eq!(event.fee, 5);
+
+        let token_client = token::Client::new(&env, &token_address);
+        assert_eq!(token_client.balance(&client.address), 1_005);
+        assert_eq!(token_client.balance(&receiver), 95);
+        assert_eq!(client.get_protocol_fees(&token_address), 5);
+    }
+
+    #[test]
+    #[should_panic(expected = "flash draw not repaid")]
+    fn test_flash_draw_without_fee_reverts() {
+        let env = Env::default();
+        env.mock_all_auths();
+        let borrower = Address::generate(&env);
+        let (client, _token, receiver) = setup_flash(&env, &borrower);
+        client.flash_draw(&receiver, &500, &Bytes::from_array(&env, &[1]));
+    }
+
+    #[test]
+    #[should_panic(expected = "flash draws not enabled")]
+    fn test_flash_draw_disabled_by_default() {
+        let env = Env::default();
+        env.mock_all_auths();
+        let borrower = Address::generate(&env);
+        let (client, _token, receiver) = setup_flash(&env, &borrower);
+        client.set_flash_config(&FlashConfig::default());
+        client.flash_draw(&receiver, &500, &Bytes::new(&env));
+    }
+
+    // ── refinancing ───────────────────────────────────────────────────────────
+
+    #[test]
+    fn test_refinance_in_opens_line_and_pays_old_lender() {
+        use soroban_sdk::testutils::Events;
+        use soroban_sdk::TryIntoVal;
+        let env = Env::default();
+        env.mock_all_auths();
+        let existing = Address::generate(&env);
+        let (client, token_address, admin) =
+            setup_contract_with_credit_line(&env, &existing, 1_000, 5_000);
+        let borrower = Address::generate(&env);
+        let old_lender = Address::generate(&env);
+
+        let paid_out =
+            client.refinance_in(&admin, &borrower, &800, &old_lender, &line_terms(1_000));
+        assert_eq!(paid_out, 800);
+        let event: RefinanceEvent = env
+            .events()
+            .all()
+            .last()
+            .unwrap()
+            .2
+            .try_into_val(&env)
+            .unwrap();
+        assert_eq!(event.borrower, borrower);
+        assert_eq!(event.payoff_recipient, old_lender);
+        assert_eq!(event.amount, 800);
+        assert_eq!(event.credit_limit, 1_000);
+        assert!(event.opened);
+
+        let line = client.get_credit_line(&borrower).unwrap();
+        assert_eq!(line.utilized_amount, 800);
+        assert_eq!(line.interest_rate_bps, 300);
+        assert_eq!(
+            token::Client::new(&env, &token_address).balance(&old_lender),
+            800
+        );
+    }
+
+    #[test]
+    fn test_refinance_in_extends_existing_line() {
+        let env = Env::default();
+        env.mock_all_auths();
+        let borrower = Address::generate(&env);
+        let (client, token_address, admin) =
+            setup_contract_with_credit_line(&env, &borrower, 1_000, 5_000);
+        client.draw_credit(&borrower, &400, &None);
+        let old_lender = Address::generate(&env);
+
+        let mut terms = line_terms(2_000);
+        terms.interest_rate_bps = 900;
+        client.refinance_in(&admin, &borrower, &1_500, &old_lender, &terms);
+        let line = client.get_credit_line(&borrower).unwrap();
+        assert_eq!(line.credit_limit, 2_000);
+        assert_eq!(line.utilized_amount, 1_900);
+        assert_eq!(line.interest_rate_bps, 300);
+        assert_eq!(
+            token::Client::new(&env, &token_address).balance(&old_lender),
+            1_500
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "exceeds credit limit")]
+    fn test_refinance_in_beyond_terms_reverts() {
+        let env = Env::default();
+        env.mock_all_auths();
+        let borrower = Address::generate(&env);
+        let (client, _token, admin) =
+            setup_contract_with_credit_line(&env, &borrower, 1_000, 5_000);
+        client.refinance_in(
+            &admin,
+            &borrower,
+            &1_200,
+            &Address::generate(&env),
+            &line_terms(1_000),
+        );
+    }
+
+    // ── repaying through a swap adapter ───────────────────────────────────────
+
+    mod mock_swap_adapter {
+        use soroban_sdk::{contract, contractimpl, token, Address, Env, Symbol};
+
+        #[contract]
+        pub struct MockSwapAdapter;
+
+        #[contractimpl]
+        impl MockSwapAdapter {
+            /// Pay out `rate_pct` percent of each amount swapped in.
+            pub fn set_rate(env: Env, rate_pct: i128) {
+                env.storage()
+                    .instance()
+                    .set(&Symbol::new(&env, "rate"), &rate_pct);
+            }
+
+            pub fn swap(
+                env: Env,
+                _token_in: Address,
+                token_out: Address,
+                amount_in: i128,
+                _min_amount_out: i128,
+                to: Address,
+            ) -> i128 {
+                let rate_pct: i128 = env
+                    .storage()
+                    .instance()
+                    .get(&Symbol::new(&env, "rate"))
+                    .unwrap();
+                let amount_out = amount_in * rate_pct / 100;
+                token::Client::new(&env, &token_out).transfer(
+                    &env.current_contract_address(),
+                    &to,
+                    &amount_out,
+                );
+                amount_out
+            }
+        }
+    }
+
+    /// Line with 500 drawn, an adapter paying `rate_pct` percent and holding 1_000 of
+    /// the line token, and a borrower holding 300 of another token.
+    fn setup_swap_repay<'a>(
+        env: &'a Env,
+        borrower: &'a Address,
+        rate_pct: i128,
+    ) -> (CreditClient<'a>, Address, Address, Address) {
+        let (client, token_address, _admin) =
+            setup_contract_with_credit_line(env, borrower, 1_000, 1_000);
+        client.draw_credit(borrower, &500, &None);
+        let adapter = env.register(mock_swap_adapter::MockSwapAdapter, ());
+        mock_swap_adapter::MockSwapAdapterClient::new(env, &adapter).set_rate(&rate_pct);
+        token::StellarAssetClient::new(env, &token_address).mint(&adapter, &1_000);
+        client.set_swap_adapter(&adapter);
+        let (token_in, _) = setup_token(env, borrower, 300);
+        (client, token_address, token_in, adapter)
+    }
+
+    #[test]
+    fn test_repay_with_token_swaps_into_line_token() {
+        let env = Env::default();
+        env.mock_all_auths();
+        let borrower = Address::generate(&env);
+        let (client, _token, token_in, adapter) = setup_swap_repay(&env, &borrower, 100);
+
+        assert_eq!(
+            client.repay_with_token(&borrower, &token_in, &300, &290),
+            300
+        );
+        assert_eq!(
+            client.get_credit_line(&borrower).unwrap().utilized_amount,
+            200
+        );
+        let token_in_client = token::Client::new(&env, &token_in);
+        assert_eq!(token_in_client.balance(&borrower), 0);
+        assert_eq!(token_in_client.balance(&adapter), 300);
+    }
+
+    #[test]
+    fn test_repay_with_token_refunds_excess_in_line_token() {
+        let env = Env::default();
+        env.mock_all_auths();
+        let borrower = Address::generate(&env);
+        let (client, token_address, token_in, _adapter) = setup_swap_repay(&env, &borrower, 200);
+        let line_token = token::Client::new(&env, &token_address);
+        let balance_before = line_token.balance(&borrower);
+
+        assert_eq!(client.repay_with_token(&borrower, &token_in, &300, &0), 600);
+        assert_eq!(
+            client.get_credit_line(&borrower).unwrap().utilized_amount,
+            0
+        );
+        assert_eq!(line_token.balance(&borrower), balance_before + 100);
+    }
+
+    #[test]
+    #[should_panic(expected = "slippage exceeded")]
+    fn test_repay_with_token_below_min_out_reverts() {
+        let env = Env::default();
+        env.mock_all_auths();
+        let borrower = Address::generate(&env);
+        let (client, _token, token_in, _adapter) = setup_swap_repay(&env, &borrower, 90);
+        client.repay_with_token(&borrower, &token_in, &300, &280);
+    }
+
+    // ── debt assignment ───────────────────────────────────────────────────────
+
+    #[test]
+    fn test_assign_debt_sells_claim_to_collector() {
+        use soroban_sdk::testutils::Events;
+        use soroban_sdk::TryIntoVal;
+        let env = Env::default();
+        env.mock_all_auths();
+        let borrower = Address::generate(&env);
+        let lender = Address::generate(&env);
+        let collector = Address::generate(&env);
+        let (client, token_address, _admin) =
+            setup_contract_with_credit_line(&env, &borrower, 1_000, 0);
+        let sac = token::StellarAssetClient::new(&env, &token_address);
+        sac.mint(&lender, &1_000);
+        sac.mint(&collector, &150);
+        client.deposit_liquidity(&lender, &token_address, &1_000);
+        client.draw_credit(&borrower, &600, &None);
+        client.default_credit_line(&borrower);
+
+        client.assign_debt(&borrower, &collector, &150);
+
+        let event: DebtAssignedEvent = env
+            .events()
+            .all()
+            .last()
+            .unwrap()
+            .2
+            .try_into_val(&env)
+            .unwrap();
+        assert_eq!(event.collector, collector);
+        assert_eq!(event.price, 150);
+        assert_eq!(event.outstanding, 600);
+
+        let line = client.get_credit_line(&borrower).unwrap();
+        assert_eq!(line.creditor, Some(collector.clone()));
+        assert_eq!(line.utilized_amount, 600);
+        // The pool swapped a 600 claim for 150 in cash.
+        assert_eq!(client.get_pool_assets(&token_address), 550);
+        let token_client = token::Client::new(&env, &token_address);
+        assert_eq!(token_client.balance(&collector), 0);
+        assert_eq!(token_client.balance(&client.address), 550);
+    }
+
+    #[test]
+    fn test_repayment_after_assignment_goes_to_collector() {
+        let env = Env::default();
+        env.mock_all_auths();
+        let borrower = Address::generate(&env);
+        let collector = Address::generate(&env);
+        let (client, token_address, _admin) =
+            setup_contract_with_credit_line(&env, &borrower, 1_000, 1_000);
+        client.draw_credit(&borrower, &600, &None);
+        client.default_credit_line(&borrower);
+        client.assign_debt(&borrower, &collector, &0);
+
+        client.repay_credit(&borrower, &250);
+
+        let token_client = token::Client::new(&env, &token_address);
+        assert_eq!(token_client.balance(&collector), 250);
+        assert_eq!(token_client.balance(&client.address), 400);
+        let line = client.get_credit_line(&borrower).unwrap();
+        assert_eq!(line.utilized_amount, 350);
+        assert_eq!(line.recovered_amount, 250);
+    }
+
+    #[test]
+    #[should_panic(expected = "credit line is not defaulted")]
+    fn test_assign_debt_requires_defaulted_line() {
+        let env = Env::default();
+        env.mock_all_auths();
+        let borrower = Address::generate(&env);
+        let collector = Address::generate(&env);
+        let (client, _token, _admin) =
+            setup_contract_with_credit_line(&env, &borrower, 1_000, 1_000);
+        client.draw_credit(&borrower, &600, &None);
+        client.assign_debt(&borrower, &collector, &0);
+    }
+
+    #[test]
+    #[should_panic(expected = "debt already assigned")]
+    fn test_assign_debt_twice_reverts() {
+        let env = Env::default();
+        env.mock_all_auths();
+        let borrower = Address::generate(&env);
+        let collector = Address::generate(&env);
+        let (client, _token, _admin) =
+            setup_contract_with_credit_line(&env, &borrower, 1_000, 1_000);
+        client.draw_credit(&borrower, &600, &None);
+        client.default_credit_line(&borrower);
+        client.assign_debt(&borrower, &collector, &0);
+        client.assign_debt(&borrower, &Address::generate(&env), &0);
+    }
+
+    // ── line metadata hash ────────────────────────────────────────────────────
+
+    #[test]
+    fn test_line_metadata_hash_update_trail() {
+        use soroban_sdk::testutils::Events;
+        use soroban_sdk::TryIntoVal;
+        let env = Env::default();
+        env.mock_all_auths();
+        let borrower = Address::generate(&env);
+        let (client, _token, admin) = setup_contract_with_credit_line(&env, &borrower, 1_000, 0);
+        let agreement = BytesN::from_array(&env, &[1; 32]);
+        let amendment = BytesN::from_array(&env, &[2; 32]);
+        assert_eq!(client.get_line_metadata_hash(&borrower), None);
+
+        client.set_line_metadata_hash(&admin, &borrower, &Some(agreement.clone()));
+        assert_eq!(
+            client.get_line_metadata_hash(&borrower),
+            Some(agreement.clone())
+        );
+
+        client.set_line_metadata_hash(&admin, &borrower, &Some(amendment.clone()));
+        let event: MetadataHashEvent = env
+            .events()
+            .all()
+            .last()
+            .unwrap()
+            .2
+            .try_into_val(&env)
+            .unwrap();
+        assert_eq!(event.borrower, borrower);
+        assert_eq!(event.previous, Some(agreement));
+        assert_eq!(event.metadata_hash, Some(amendment.clone()));
+
+        client.set_line_metadata_hash(&admin, &borrower, &None);
+        assert_eq!(client.get_line_metadata_hash(&borrower), None);
+    }
+
+    #[test]
+    fn test_line_metadata_hash_follows_transferred_line() {
+        let env = Env::default();
+        env.mock_all_auths();
+        let borrower = Address::generate(&env);
+        let new_borrower = Address::generate(&env);
+        let (client, _token, admin) = setup_contract_with_credit_line(&env, &borrower, 1_000, 0);
+        let agreement = BytesN::from_array(&env, &[1; 32]);
+        client.set_line_metadata_hash(&admin, &borrower, &Some(agreement.clone()));
+        client.mint_position(&borrower);
+
+        client.transfer_credit_line(&borrower, &new_borrower);
+        assert_eq!(client.get_line_metadata_hash(&borrower), None);
+        assert_eq!(
+            client.get_line_metadata_hash(&new_borrower),
+            Some(agreement)
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "Credit line not found")]
+    fn test_line_metadata_hash_requires_line() {
+        let env = Env::default();
+        env.mock_all_auths();
+        let borrower = Address::generate(&env);
+        let (client, _token, admin) = setup_contract_with_credit_line(&env, &borrower, 1_000, 0);
+        client.set_line_metadata_hash(
+            &admin,
+            &Address::generate(&env),
+            &Some(BytesN::from_array(&env, &[1; 32])),
+        );
+    }
+
+    // ── repayment modes ───────────────────────────────────────────────────────
+
+    #[test]
+    fn test_interest_only_bills_accrued_interest() {
+        use soroban_sdk::testutils::Ledger;
+        let env = Env::default();
+        env.mock_all_auths();
+        env.ledger().with_mut(|li| li.timestamp = 1_000);
+        let borrower = Address::generate(&env);
+        let (client, _token, admin) =
+            setup_scheduled_line(&env, &borrower, math::SECONDS_PER_YEAR, 1_000);
+        client.set_repayment_mode(&admin, &borrower, &RepaymentMode::InterestOnly);
+        client.draw_credit(&borrower, &2_000, &None);
+        env.ledger()
+            .with_mut(|li| li.timestamp = 1_000 + math::SECONDS_PER_YEAR);
+
+        let next = client.get_next_payment(&borrower).unwrap();
+        assert_eq!(next.due_ts, 1_000 + 2 * math::SECONDS_PER_YEAR);
+        assert_eq!(next.min_due_amount, 60);
+        assert_eq!(client.get_amortization_schedule(&borrower).len(), 0);
+    }
+
+    #[test]
+    fn test_amortizing_line_schedule_and_bills() {
+        use soroban_sdk::testutils::Ledger;
+        let env = Env::default();
+        env.mock_all_auths();
+        env.ledger().with_mut(|li| li.timestamp = 1_000);
+        let borrower = Address::generate(&env);
+        let (client, _token, admin) = setup_scheduled_line(&env, &borrower, 100, 1_000);
+        client.set_repayment_mode(&admin, &borrower, &RepaymentMode::Amortizing(4));
+        assert_eq!(
+            client.get_repayment_mode(&borrower),
+            RepaymentPlan {
+                mode: RepaymentMode::Amortizing(4),
+                start_due_ts: 1_100,
+            }
+        );
+        client.draw_credit(&borrower, &2_000, &None);
+
+        let schedule = client.get_amortization_schedule(&borrower);
+        assert_eq!(schedule.len(), 4);
+        for (i, installment) in schedule.iter().enumerate() {
+            assert_eq!(installment.due_ts, 1_200 + 100 * i as u64);
+            assert_eq!(installment.principal, 500);
+            assert_eq!(installment.payment, 500);
+            assert_eq!(installment.balance_after, 1_500 - 500 * i as i128);
+        }
+
+        env.ledger().with_mut(|li| li.timestamp = 1_100);
+        assert_eq!(
+            client.get_next_payment(&borrower).unwrap().min_due_amount,
+            500
+        );
+        client.repay_credit(&borrower, &500);
+        env.ledger().with_mut(|li| li.timestamp = 1_200);
+        assert_eq!(
+            client.get_next_payment(&borrower).unwrap().min_due_amount,
+            500
+        );
+        assert_eq!(client.get_amortization_schedule(&borrower).len(), 3);
+    }
+
+    #[test]
+    #[should_panic(expected = "n_installments out of range")]
+    fn test_amortizing_requires_installments() {
+        let env = Env::default();
+        env.mock_all_auths();
+        let borrower = Address::generate(&env);
+        let (client, _token, admin) = setup_scheduled_line(&env, &borrower, 100, 1_000);
+        client.set_repayment_mode(&admin, &borrower, &RepaymentMode::Amortizing(0));
+    }
+
+    #[test]
+    #[should_panic(expected = "credit line has no schedule")]
+    fn test_repayment_mode_requires_schedule() {
+        let env = Env::default();
+        env.mock_all_auths();
+        let borrower = Address::generate(&env);
+        let (client, _token, admin) = setup_contract_with_credit_line(&env, &borrower, 1_000, 0);
+        client.set_repayment_mode(&admin, &borrower, &RepaymentMode::InterestOnly);
+    }
+
+    // ── autopay ───────────────────────────────────────────────────────────────
+
+    /// Scheduled line opened at 1_000 with 5_000 drawn, rolled to a 500 minimum due at 1_200.
+    fn setup_autopay_line<'a>(
+        env: &'a Env,
+        borrower: &'a Address,
+        allowance: i128,
+    ) -> CreditClient<'a> {
+        use soroban_sdk::testutils::Ledger;
+        env.ledger().with_mut(|li| li.timestamp = 1_000);
+        let (client, token, _admin) = setup_scheduled_line(env, borrower, 100, 1_000);
+        client.draw_credit(borrower, &5_000, &None);
+        token::Client::new(env, &token).approve(borrower, &client.address, &allowance, &1_000);
+        env.ledger().with_mut(|li| li.timestamp = 1_100);
+        client
+    }
+
+    #[test]
+    fn test_run_autopay_pays_minimum_due() {
+        use soroban_sdk::testutils::Events;
+        use soroban_sdk::TryIntoVal;
+        let env = Env::default();
+        env.mock_all_auths();
+        let borrower = Address::generate(&env);
+        let client = setup_autopay_line(&env, &borrower, 1_000);
+        client.enable_autopay(&borrower, &1_000);
+
+        assert_eq!(client.run_autopay(&borrower), 500);
+        let event: AutopayEvent = env
+            .events()
+            .all()
+            .last()
+            .unwrap()
+            .2
+            .try_into_val(&env)
+            .unwrap();
+        assert_eq!(event.amount, 500);
+        assert_eq!(event.due_ts, 1_200);
+        assert_eq!(event.min_due_remaining, 0);
+        let next = client.get_next_payment(&borrower).unwrap();
+        assert_eq!(next.min_due_amount, 0);
+        assert_eq!(
+            client.get_credit_line(&borrower).unwrap().utilized_amount,
+            4_500
+        );
+        assert_eq!(client.get_autopay(&borrower).unwrap().paid_this_cycle, 500);
+    }
+
+    #[test]
+    fn test_run_autopay_bounded_by_cap_and_allowance() {
+        let env = Env::default();
+        env.mock_all_auths();
+        let borrower = Address::generate(&env);
+        let client = setup_autopay_line(&env, &borrower, 150);
+        client.enable_autopay(&borrower, &1_000);
+        assert_eq!(client.run_autopay(&borrower), 150);
+
+        let borrower = Address::generate(&env);
+        let client = setup_autopay_line(&env, &borrower, 1_000);
+        client.enable_autopay(&borrower, &200);
+        assert_eq!(client.run_autopay(&borrower), 200);
+        assert_eq!(
+            client.get_next_payment(&borrower).unwrap().min_due_amount,
+            300
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "nothing to autopay")]
+    fn test_run_autopay_cap_spent_for_cycle() {
+        let env = Env::default();
+        env.mock_all_auths();
+        let borrower = Address::generate(&env);
+        let client = setup_autopay_line(&env, &borrower, 1_000);
+        client.enable_autopay(&borrower, &200);
+        client.run_autopay(&borrower);
+        client.run_autopay(&borrower);
+    }
+
+    #[test]
+    #[should_panic(expected = "payment not due")]
+    fn test_run_autopay_requires_billed_minimum() {
+        use soroban_sdk::testutils::Ledger;
+        let env = Env::default();
+        env.mock_all_auths();
+        let borrower = Address::generate(&env);
+        env.ledger().with_mut(|li| li.timestamp = 1_000);
+        let (client, _token, _admin) = setup_scheduled_line(&env, &borrower, 100, 1_000);
+        client.draw_credit(&borrower, &5_000, &None);
+        client.enable_autopay(&borrower, &1_000);
+        client.run_autopay(&borrower);
+    }
+
+    // ── cycle statements ──────────────────────────────────────────────────────
+
+    #[test]
+    fn test_close_cycle_publishes_statement() {
+        use soroban_sdk::testutils::{Events, Ledger};
+        use soroban_sdk::TryIntoVal;
+        let env = Env::default();
+        env.mock_all_auths();
+        env.ledger().with_mut(|li| li.timestamp = 1_000);
+        let borrower = Address::generate(&env);
+        let (client, _token, _admin) =
+            setup_scheduled_line(&env, &borrower, math::SECONDS_PER_YEAR, 1_000);
+        client.draw_credit(&borrower, &2_000, &None);
+        env.ledger()
+            .with_mut(|li| li.timestamp = 1_000 + math::SECONDS_PER_YEAR / 2);
+        client.repay_credit(&borrower, &500);
+        env.ledger()
+            .with_mut(|li| li.timestamp = 1_000 + math::SECONDS_PER_YEAR);
+
+        assert!(client.close_cycle(&borrower));
+        let event: StatementEvent = env
+            .events()
+            .all()
+            .last()
+            .unwrap()
+            .2
+            .try_into_val(&env)
+            .unwrap();
+        let line = client.get_credit_line(&borrower).unwrap();
+        assert_eq!(event.cycle_start, 1_000);
+        assert_eq!(event.cycle_end, 1_000 + math::SECONDS_PER_YEAR);
+        assert_eq!(event.opening_balance, 0);
+        assert_eq!(event.draws, 2_000);
+        assert_eq!(event.payments, 500);
+        assert_eq!(event.fees, 0);
+        assert!(event.interest_charged > 0);
+        assert_eq!(
+            event.closing_balance,
+            event.opening_balance + event.draws + event.interest_charged - event.payments
+        );
+        assert_eq!(
+            event.closing_balance,
+            line.utilized_amount + line.accrued_interest
+        );
+        assert_eq!(event.min_due, line.min_due_amount);
+        assert_eq!(event.next_due_ts, line.next_due_ts);
+
+        let next = client.get_statement_cycle(&borrower).unwrap();
+        assert_eq!(next.cycle_start, 1_000 + math::SECONDS_PER_YEAR);
+        assert_eq!(next.opening_balance, event.closing_balance);
+        assert_eq!(next.payments, 0);
+    }
+
+    #[test]
+    #[should_panic(expected = "cycle not ended")]
+    fn test_close_cycle_before_cycle_end() {
+        use soroban_sdk::testutils::Ledger;
+        let env = Env::default();
+        env.mock_all_auths();
+        env.ledger().with_mut(|li| li.timestamp = 1_000);
+        let borrower = Address::generate(&env);
+        let (client, _token, _admin) = setup_scheduled_line(&env, &borrower, 100, 1_000);
+        client.draw_credit(&borrower, &1_000, &None);
+        env.ledger().with_mut(|li| li.timestamp = 1_099);
+        client.close_cycle(&borrower);
+    }
+
+    #[test]
+    #[should_panic(expected = "credit line has no schedule")]
+    fn test_close_cycle_requires_schedule() {
+        let env = Env::default();
+        env.mock_all_auths();
+        let borrower = Address::generate(&env);
+        let (client, _token, _admin) = setup_contract_with_credit_line(&env, &borrower, 1_000, 0);
+        client.close_cycle(&borrower);
+    }
+
+    // ── transferable positions ────────────────────────────────────────────────
+
+    #[test]
+    fn test_transfer_credit_line_rekeys_position() {
+        use soroban_sdk::testutils::Events;
+        use soroban_sdk::TryIntoVal;
+        let env = Env::default();
+        env.mock_all_auths();
+        let borrower = Address::generate(&env);
+        let new_borrower = Address::generate(&env);
+        let (client, token_address, _admin) =
+            setup_contract_with_credit_line(&env, &borrower, 1_000, 1_000);
+        client.draw_credit(&borrower, &400, &None);
+        let position_id = client.mint_position(&borrower);
+        assert_eq!(position_id, 1);
+        assert_eq!(
+            client.get_position_owner(&position_id),
+            Some(borrower.clone())
+        );
+        let commitments = client.get_undrawn_commitments(&token_address);
+
+        client.transfer_credit_line(&borrower, &new_borrower);
+
+        let event: PositionTransferEvent = env
+            .events()
+            .all()
+            .last()
+            .unwrap()
+            .2
+            .try_into_val(&env)
+            .unwrap();
+        assert_eq!(event.position_id, position_id);
+        assert_eq!(event.old_borrower, borrower);
+        assert_eq!(event.new_borrower, new_borrower);
+
+        assert!(client.get_credit_line(&borrower).is_none());
+        let line = client.get_credit_line(&new_borrower).unwrap();
+        assert_eq!(line.borrower, new_borrower);
+        assert_eq!(line.utilized_amount, 400);
+        assert_eq!(
+            client.get_position_owner(&position_id),
+            Some(new_borrower.clone())
+        );
+        assert_eq!(client.get_borrower_history(&new_borrower).draw_count, 1);
+        assert_eq!(client.get_borrower_history(&borrower).draw_count, 0);
+        assert_eq!(client.get_transactions(&new_borrower, &0, &10).len(), 1);
+        assert_eq!(client.get_transactions(&borrower, &0, &10).len(), 0);
+        assert_eq!(client.get_undrawn_commitments(&token_address), commitments);
+        assert_eq!(client.count_borrowers(), 1);
+        assert_eq!(
+            client.list_borrowers(&0, &10).get(0),
+            Some(new_borrower.clone())
+        );
+
+        token::StellarAssetClient::new(&env, &token_address).mint(&new_borrower, &400);
+        client.repay_credit(&new_borrower, &400);
+        assert_eq!(
+            client
+                .get_credit_line(&new_borrower)
+                .unwrap()
+                .utilized_amount,
+            0
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "position not minted")]
+    fn test_transfer_credit_line_requires_minted_position() {
+        let env = Env::default();
+        env.mock_all_auths();
+        let borrower = Address::generate(&env);
+        let (client, _token, _admin) = setup_contract_with_credit_line(&env, &borrower, 1_000, 0);
+        client.transfer_credit_line(&borrower, &Address::generate(&env));
+    }
+
+    #[test]
+    #[should_panic(expected = "new borrower already has a credit line")]
+    fn test_transfer_credit_line_to_existing_borrower_reverts() {
+        let env = Env::default();
+        env.mock_all_auths();
+        let borrower = Address::generate(&env);
+        let other = Address::generate(&env);
+        let (client, _token, admin) = setup_contract_with_credit_line(&env, &borrower, 1_000, 0);
+        client.open_credit_line(
+            &admin,
+            &other,
+            &1_000,
+            &300_u32,
+            &70_u32,
+            &None,
+            &RateMode::Fixed,
+            &None,
+            &None,
+        );
+        client.mint_position(&borrower);
+        client.transfer_credit_line(&borrower, &other);
+    }
+
+    #[test]
+    #[should_panic(expected = "position already minted")]
+    fn test_mint_position_twice_reverts() {
+        let env = Env::default();
+        env.mock_all_auths();
+        let borrower = Address::generate(&env);
+        let (client, _token, _admin) = setup_contract_with_credit_line(&env, &borrower, 1_000, 0);
+        client.mint_position(&borrower);
+        client.mint_position(&borrower);
+    }
+
+    // ── storage TTL ───────────────────────────────────────────────────────────
+
+    #[test]
+    fn test_credit_line_ttl_extended_on_write() {
+        use soroban_sdk::testutils::storage::{Instance, Persistent};
+        use soroban_sdk::testutils::Ledger;
+        let env = Env::default();
+        env.mock_all_auths();
+        // Keep the other entries the draw touches alive across the jump below.
+        env.ledger()
+            .with_mut(|li| li.min_persistent_entry_ttl = 100_000);
+        let borrower = Address::generate(&env);
+        let (client, _token, _admin) =
+            setup_contract_with_credit_line(&env, &borrower, 1_000, 1_000);
+        let line_ttl = || {
+            env.as_contract(&client.address, || {
+                env.storage()
+                    .persistent()
+                    .get_ttl(&DataKey::Line(borrower.clone()))
+            })
+        };
+        assert_eq!(line_ttl(), ttl::BUMP_AMOUNT);
+
+        // Within the threshold nothing is rewritten; past it the TTL is topped up.
+        env.ledger().with_mut(|li| li.sequence_number += 2 * 17_280);
+        client.draw_credit(&borrower, &100, &None);
+        assert_eq!(line_ttl(), ttl::BUMP_AMOUNT);
+        let instance_ttl = env.as_contract(&client.address, || env.storage().instance().get_ttl());
+        assert_eq!(instance_ttl, ttl::BUMP_AMOUNT);
+    }
+
+    #[test]
+    fn test_bump_credit_line_and_instance() {
+        use soroban_sdk::testutils::storage::{Instance, Persistent};
+        let env = Env::default();
+        env.mock_all_auths();
+        let borrower = Address::generate(&env);
+        let (client, _token, _admin) = setup_contract_with_credit_line(&env, &borrower, 1_000, 0);
+
+        client.bump_credit_line(&borrower, &(ttl::BUMP_AMOUNT * 2));
+        client.bump_instance(&(ttl::BUMP_AMOUNT * 3));
+
+        env.as_contract(&client.address, || {
+            assert_eq!(
+                env.storage()
+                    .persistent()
+                    .get_ttl(&DataKey::Line(borrower.clone())),
+                ttl::BUMP_AMOUNT * 2
+            );
+            assert_eq!(env.storage().instance().get_ttl(), ttl::BUMP_AMOUNT * 3);
+        });
+    }
+
+    #[test]
+    #[should_panic(expected = "Credit line not found")]
+    fn test_bump_credit_line_missing_line_reverts() {
+        let env = Env::default();
+        env.mock_all_auths();
+        let borrower = Address::generate(&env);
+        let (client, _token, _admin) = setup_contract_with_credit_line(&env, &borrower, 1_000, 0);
+        client.bump_credit_line(&Address::generate(&env), &1_000);
+    }
 
-    // ── helpers ───────────────────────────────────────────────────────────────
+    #[test]
+    #[should_panic(expected = "ledgers must be positive")]
+    fn test_bump_instance_zero_ledgers_reverts() {
+        let env = Env::default();
+        env.mock_all_auths();
+        let borrower = Address::generate(&env);
+        let (client, _token, _admin) = setup_contract_with_credit_line(&env, &borrower, 1_000, 0);
+        client.bump_instance(&0);
+    }
 
-    fn setup_token<'a>(
-        env: &'a Env,
-        contract_id: &'a Address,
-        reserve_amount: i128,
-    ) -> (Address, token::StellarAssetClient<'a>) {
-        let token_admin = Address::generate(env);
-        let token_id = env.register_stellar_asset_contract_v2(token_admin);
-        let token_address = token_id.address();
-        let sac = token::StellarAssetClient::new(env, &token_address);
-        if reserve_amount > 0 {
-            sac.mint(contract_id, &reserve_amount);
-        }
-        (token_address, sac)
+    // ── schema versioning ─────────────────────────────────────────────────────
+
+    fn store_v1_line(env: &Env, contract: &Address, borrower: &Address, utilized: i128) {
+        env.as_contract(contract, || {
+            env.storage().persistent().set(
+                borrower,
+                &schema::CreditLineDataV1 {
+                    borrower: borrower.clone(),
+                    credit_limit: 1_000,
+                    utilized_amount: utilized,
+                    interest_rate_bps: 300,
+                    risk_score: 70,
+                    status: CreditStatus::Active,
+                },
+            );
+        });
     }
 
-    fn setup_contract_with_credit_line<'a>(
-        env: &'a Env,
-        borrower: &'a Address,
-        credit_limit: i128,
-        reserve_amount: i128,
-    ) -> (CreditClient<'a>, Address, Address) {
-        let admin = Address::generate(env);
-        let contract_id = env.register(Credit, ());
-        let (token_address, _sac) = setup_token(env, &contract_id, reserve_amount);
-        let client = CreditClient::new(env, &contract_id);
-        client.init(&admin, &token_address);
-        client.open_credit_line(borrower, &credit_limit, &300_u32, &70_u32);
-        (client, token_address, admin)
+    fn stored_line(env: &Env, contract: &Address, borrower: &Address) -> schema::StoredLine {
+        env.as_contract(contract, || {
+            env.storage()
+                .persistent()
+                .get::<DataKey, schema::StoredLine>(&DataKey::Line(borrower.clone()))
+                .unwrap()
+        })
     }
 
-    // ── draw_credit: token transfer (#39) ─────────────────────────────────────
+    #[test]
+    fn test_legacy_line_upgraded_on_first_touch() {
+        let env = Env::default();
+        env.mock_all_auths();
+        let borrower = Address::generate(&env);
+        let legacy = Address::generate(&env);
+        let (client, token_address, _admin) =
+            setup_contract_with_credit_line(&env, &borrower, 1_000, 1_000);
+        store_v1_line(&env, &client.address, &legacy, 200);
+        let commitments = client.get_undrawn_commitments(&token_address);
+
+        client.draw_credit(&legacy, &100, &None);
+
+        let line = client.get_credit_line(&legacy).unwrap();
+        assert_eq!(line.token, token_address);
+        assert_eq!(line.utilized_amount, 300);
+        assert_eq!(line.rate_mode, RateMode::Fixed);
+        assert!(matches!(
+            stored_line(&env, &client.address, &legacy),
+            schema::StoredLine::V10(_)
+        ));
+        // The migrated line joins the running totals.
+        assert_eq!(
+            client.get_undrawn_commitments(&token_address),
+            commitments + 700
+        );
+    }
 
     #[test]
-    fn test_draw_transfers_correct_amount_to_borrower() {
+    fn test_migrate_credit_line_reports_upgrade() {
+        let env = Env::default();
+        env.mock_all_auths();
+        let borrower = Address::generate(&env);
+        let legacy = Address::generate(&env);
+        let (client, _token, _admin) = setup_contract_with_credit_line(&env, &borrower, 1_000, 0);
+        store_v1_line(&env, &client.address, &legacy, 0);
+
+        assert!(client.migrate_credit_line(&legacy));
+        assert!(!client.migrate_credit_line(&legacy));
+        assert!(!client.migrate_credit_line(&borrower));
+    }
+
+    #[test]
+    fn test_migrate_lines_batch() {
+        let env = Env::default();
+        env.mock_all_auths();
+        let borrower = Address::generate(&env);
+        let first = Address::generate(&env);
+        let second = Address::generate(&env);
+        let (client, _token, _admin) = setup_contract_with_credit_line(&env, &borrower, 1_000, 0);
+        store_v1_line(&env, &client.address, &first, 0);
+        store_v1_line(&env, &client.address, &second, 50);
+
+        let batch = soroban_sdk::vec![
+            &env,
+            first.clone(),
+            borrower.clone(),
+            second.clone(),
+            Address::generate(&env)
+        ];
+        assert_eq!(client.migrate_lines(&batch), 2);
+        assert!(matches!(
+            stored_line(&env, &client.address, &second),
+            schema::StoredLine::V10(_)
+        ));
+        assert_eq!(client.count_borrowers(), 3);
+    }
+
+    /// Rewrite `line` in layout `version` (2–9), dropping the fields added after it.
+    fn store_layout(env: &Env, contract: &Address, line: &CreditLineData, version: u32) {
+        use soroban_sdk::{IntoVal, TryIntoVal, Val};
+        let tags = ["V2", "V3", "V4", "V5", "V6", "V7", "V8", "V9"];
+        let added: [(u32, &[&str]); 8] = [
+            (3, &["suspension_reason", "suspended_until"]),
+            (4, &["restrictions"]),
+            (5, &["limit_boost", "boost_expires_at"]),
+            (6, &["delinquent_since_ts"]),
+            (7, &["interest_config"]),
+            (8, &["promo_rate_bps", "promo_until_ts"]),
+            (9, &["rate_floor_bps", "rate_cap_bps"]),
+            (10, &["grace_until_ts", "prepayment_penalty_bps"]),
+        ];
+        env.as_contract(contract, || {
+            let mut fields: soroban_sdk::Map<Symbol, Val> =
+                IntoVal::<Env, Val>::into_val(line, env)
+                    .try_into_val(env)
+                    .unwrap();
+            for (since, names) in added {
+                if since > version {
+                    for name in names {
+                        fields.remove(Symbol::new(env, name));
+                    }
+                }
+            }
+            let tag = Symbol::new(env, tags[version as usize - 2]);
+            let stored: soroban_sdk::Vec<Val> =
+                soroban_sdk::vec![env, tag.into_val(env), fields.into_val(env)];
+            env.storage()
+                .persistent()
+                .set(&DataKey::Line(line.borrower.clone()), &stored);
+        });
+    }
+
+    #[test]
+    fn test_migrating_tracked_layouts_keeps_totals() {
         let env = Env::default();
         env.mock_all_auths();
         let borrower = Address::generate(&env);
         let (client, token_address, _admin) =
             setup_contract_with_credit_line(&env, &borrower, 1_000, 1_000);
-        let token_client = token::Client::new(&env, &token_address);
-        let before = token_client.balance(&borrower);
-        client.draw_credit(&borrower, &500);
-        assert_eq!(token_client.balance(&borrower) - before, 500);
+        token::StellarAssetClient::new(&env, &token_address).mint(&borrower, &100);
+        client.deposit_collateral(&borrower, &token_address, &100);
+        client.draw_credit(&borrower, &400, &None);
+        let line = client.get_credit_line(&borrower).unwrap();
+        let stats = client.get_protocol_stats();
+        let commitments = client.get_undrawn_commitments(&token_address);
+        let held = || {
+            env.as_contract(&client.address, || {
+                collateral::total_held(&env, &token_address)
+            })
+        };
+        assert_eq!(held(), 100);
+
+        for version in 2..=9 {
+            store_layout(&env, &client.address, &line, version);
+            assert!(client.migrate_credit_line(&borrower));
+            assert!(matches!(
+                stored_line(&env, &client.address, &borrower),
+                schema::StoredLine::V10(_)
+            ));
+            assert_eq!(client.get_credit_line(&borrower).unwrap(), line);
+            assert_eq!(client.get_protocol_stats(), stats);
+            assert_eq!(client.get_undrawn_commitments(&token_address), commitments);
+            assert_eq!(held(), 100);
+        }
+
+        store_layout(&env, &client.address, &line, 9);
+        client.draw_credit(&borrower, &100, &None);
+        let stats = client.get_protocol_stats();
+        assert_eq!(stats.total_lines_opened, 1);
+        assert_eq!(stats.total_active, 1);
+        assert_eq!(stats.total_outstanding_principal, 500);
+        assert_eq!(client.get_undrawn_commitments(&token_address), 500);
     }
 
+    // ── storage keys ──────────────────────────────────────────────────────────
+
     #[test]
-    fn test_draw_reduces_contract_reserve() {
+    fn test_legacy_instance_key_read_through_data_key() {
+        let env = Env::default();
+        env.mock_all_auths();
+        let borrower = Address::generate(&env);
+        let (client, _token, _admin) = setup_contract_with_credit_line(&env, &borrower, 1_000, 0);
+        env.as_contract(&client.address, || {
+            env.storage()
+                .instance()
+                .set(&Symbol::new(&env, "dust"), &25_i128);
+        });
+
+        assert_eq!(client.get_dust_threshold(), 25);
+        let copied: Option<i128> = env.as_contract(&client.address, || {
+            env.storage()
+                .instance()
+                .get(&DataKey::Config(Symbol::new(&env, "dust")))
+        });
+        assert_eq!(copied, Some(25));
+    }
+
+    #[test]
+    fn test_legacy_persistent_key_moved_on_first_read() {
+        let env = Env::default();
+        env.mock_all_auths();
+        let borrower = Address::generate(&env);
+        let operator = Address::generate(&env);
+        let (client, _token, _admin) = setup_contract_with_credit_line(&env, &borrower, 1_000, 0);
+        let legacy = (
+            Symbol::new(&env, "Approval"),
+            borrower.clone(),
+            operator.clone(),
+        );
+        let approval = OperatorApproval {
+            max_amount: 300,
+            expiry_ledger: 1_000,
+        };
+        env.as_contract(&client.address, || {
+            env.storage().persistent().set(&legacy, &approval);
+        });
+
+        assert_eq!(
+            client.get_operator_approval(&borrower, &operator),
+            Some(approval)
+        );
+        env.as_contract(&client.address, || {
+            assert!(!env.storage().persistent().has(&legacy));
+            assert!(env
+                .storage()
+                .persistent()
+                .has(&DataKey::Operator(borrower.clone(), operator.clone())));
+        });
+    }
+
+    // ── access control: roles ─────────────────────────────────────────────────
+
+    #[test]
+    fn test_risk_engine_role_can_open_and_update_credit_line() {
+        let env = Env::default();
+        env.mock_all_auths();
+        let borrower = Address::generate(&env);
+        let risk_engine = Address::generate(&env);
+        let (client, _token, _admin) = setup_contract_with_credit_line(&env, &borrower, 1_000, 0);
+        client.grant_role(&Role::RiskEngine, &risk_engine);
+        assert!(client.has_role(&Role::RiskEngine, &risk_engine));
+
+        let other = Address::generate(&env);
+        client.open_credit_line(
+            &risk_engine,
+            &other,
+            &500,
+            &300_u32,
+            &50_u32,
+            &None,
+            &RateMode::Fixed,
+            &None,
+            &None,
+        );
+        client.update_risk_parameters(&risk_engine, &borrower, &2_000, &400_u32, &60_u32, &None);
+        assert_eq!(client.get_credit_line(&other).unwrap().credit_limit, 500);
+        assert_eq!(
+            client.get_credit_line(&borrower).unwrap().credit_limit,
+            2_000
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "unauthorized")]
+    fn test_open_credit_line_without_role_reverts() {
+        let env = Env::default();
+        env.mock_all_auths();
+        let borrower = Address::generate(&env);
+        let stranger = Address::generate(&env);
+        let (client, _token, _admin) = setup_contract_with_credit_line(&env, &borrower, 1_000, 0);
+        client.open_credit_line(
+            &stranger,
+            &Address::generate(&env),
+            &500,
+            &300_u32,
+            &50_u32,
+            &None,
+            &RateMode::Fixed,
+            &None,
+            &None,
+        );
+    }
+
+    #[test]
+    fn test_set_risk_engine_replaces_previous_engine() {
+        let env = Env::default();
+        env.mock_all_auths();
+        let borrower = Address::generate(&env);
+        let (client, _token, _admin) = setup_contract_with_credit_line(&env, &borrower, 1_000, 0);
+        let first = Address::generate(&env);
+        let second = Address::generate(&env);
+        assert_eq!(client.get_risk_engine(), None);
+
+        client.set_risk_engine(&first);
+        assert!(client.has_role(&Role::RiskEngine, &first));
+        client.set_risk_engine(&second);
+        assert_eq!(client.get_risk_engine(), Some(second.clone()));
+        assert!(client.has_role(&Role::RiskEngine, &second));
+        assert!(!client.has_role(&Role::RiskEngine, &first));
+
+        let other = Address::generate(&env);
+        client.open_credit_line(
+            &second,
+            &other,
+            &500,
+            &300_u32,
+            &50_u32,
+            &None,
+            &RateMode::Fixed,
+            &None,
+            &None,
+        );
+        assert_eq!(client.get_credit_line(&other).unwrap().credit_limit, 500);
+    }
+
+    #[test]
+    #[should_panic(expected = "unauthorized")]
+    fn test_replaced_risk_engine_cannot_open() {
+        let env = Env::default();
+        env.mock_all_auths();
+        let borrower = Address::generate(&env);
+        let (client, _token, _admin) = setup_contract_with_credit_line(&env, &borrower, 1_000, 0);
+        let first = Address::generate(&env);
+        client.set_risk_engine(&first);
+        client.set_risk_engine(&Address::generate(&env));
+        client.open_credit_line(
+            &first,
+            &Address::generate(&env),
+            &500,
+            &300_u32,
+            &50_u32,
+            &None,
+            &RateMode::Fixed,
+            &None,
+            &None,
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "Error(Auth, InvalidAction)")]
+    fn test_open_credit_line_without_risk_engine_signature_reverts() {
+        let env = Env::default();
+        env.mock_all_auths();
+        let borrower = Address::generate(&env);
+        let (client, _token, _admin) = setup_contract_with_credit_line(&env, &borrower, 1_000, 0);
+        let engine = Address::generate(&env);
+        client.set_risk_engine(&engine);
+        env.set_auths(&[]);
+        client.open_credit_line(
+            &engine,
+            &Address::generate(&env),
+            &500,
+            &300_u32,
+            &50_u32,
+            &None,
+            &RateMode::Fixed,
+            &None,
+            &None,
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "unauthorized")]
+    fn test_revoked_risk_engine_cannot_update() {
+        let env = Env::default();
+        env.mock_all_auths();
+        let borrower = Address::generate(&env);
+        let risk_engine = Address::generate(&env);
+        let (client, _token, _admin) = setup_contract_with_credit_line(&env, &borrower, 1_000, 0);
+        client.grant_role(&Role::RiskEngine, &risk_engine);
+        client.revoke_role(&Role::RiskEngine, &risk_engine);
+        assert!(!client.has_role(&Role::RiskEngine, &risk_engine));
+        client.update_risk_parameters(&risk_engine, &borrower, &2_000, &400_u32, &60_u32, &None);
+    }
+
+    #[test]
+    fn test_guardian_can_pause_draws_and_suspend_lines() {
+        let env = Env::default();
+        env.mock_all_auths();
+        let borrower = Address::generate(&env);
+        let guardian = Address::generate(&env);
+        let (client, _token, _admin) = setup_contract_with_credit_line(&env, &borrower, 1_000, 0);
+        client.set_guardian(&guardian);
+        assert_eq!(client.get_guardian(), Some(guardian.clone()));
+        assert_eq!(client.get_config().guardian, Some(guardian.clone()));
+
+        client.emergency_suspend(
+            &guardian,
+            &borrower,
+            &crate::suspension::REASON_FRAUD_REVIEW,
+            &None,
+        );
+        let line = client.get_credit_line(&borrower).unwrap();
+        assert_eq!(line.status, CreditStatus::Suspended);
+        assert_eq!(
+            line.suspension_reason,
+            crate::suspension::REASON_FRAUD_REVIEW
+        );
+
+        client.emergency_pause(&guardian);
+        let state = client.get_pause_state();
+        assert!(state.draws_paused);
+        assert!(!state.repays_paused);
+        assert!(client.try_unpause(&guardian, &true, &false).is_err());
+    }
+
+    #[test]
+    fn test_guardian_has_no_other_powers() {
+        let env = Env::default();
+        env.mock_all_auths();
+        let borrower = Address::generate(&env);
+        let guardian = Address::generate(&env);
+        let (client, _token, _admin) = setup_contract_with_credit_line(&env, &borrower, 1_000, 0);
+        client.set_guardian(&guardian);
+
+        assert!(client.try_pause(&guardian, &false, &true).is_err());
+        assert!(client
+            .try_update_risk_parameters(&guardian, &borrower, &2_000, &400_u32, &60_u32, &None)
+            .is_err());
+        assert!(client
+            .try_collect_protocol_fees(&guardian, &client.get_config().token)
+            .is_err());
+    }
+
+    #[test]
+    #[should_panic(expected = "unauthorized")]
+    fn test_replaced_guardian_cannot_suspend() {
+        let env = Env::default();
+        env.mock_all_auths();
+        let borrower = Address::generate(&env);
+        let first = Address::generate(&env);
+        let (client, _token, _admin) = setup_contract_with_credit_line(&env, &borrower, 1_000, 0);
+        client.set_guardian(&first);
+        client.set_guardian(&Address::generate(&env));
+        assert!(!client.has_role(&Role::Guardian, &first));
+        client.emergency_suspend(&first, &borrower, &0, &None);
+    }
+
+    #[test]
+    #[should_panic(expected = "unauthorized")]
+    fn test_pauser_role_cannot_open_credit_line() {
         let env = Env::default();
         env.mock_all_auths();
         let borrower = Address::generate(&env);
+        let pauser = Address::generate(&env);
+        let (client, _token, _admin) = setup_contract_with_credit_line(&env, &borrower, 1_000, 0);
+        client.grant_role(&Role::Pauser, &pauser);
+        client.open_credit_line(
+            &pauser,
+            &Address::generate(&env),
+            &500,
+            &300_u32,
+            &50_u32,
+            &None,
+            &RateMode::Fixed,
+            &None,
+            &None,
+        );
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_grant_role_requires_admin_auth() {
+        let env = Env::default();
         let admin = Address::generate(&env);
         let contract_id = env.register(Credit, ());
-        let (token_address, _sac) = setup_token(&env, &contract_id, 1_000);
         let client = CreditClient::new(&env, &contract_id);
+        client.init(&admin, &Address::generate(&env));
+        client.grant_role(&Role::RiskEngine, &Address::generate(&env));
+    }
+
+    // ── risk tiers ────────────────────────────────────────────────────────────
+
+    fn open_in_tier<'a>(
+        env: &'a Env,
+        borrower: &'a Address,
+        tier: &Tier,
+    ) -> (CreditClient<'a>, Address, Address) {
+        let admin = Address::generate(env);
+        let contract_id = env.register(Credit, ());
+        let (token_address, _sac) = setup_token(env, &contract_id, 10_000);
+        let client = CreditClient::new(env, &contract_id);
         client.init(&admin, &token_address);
-        client.open_credit_line(&borrower, &1_000, &300_u32, &70_u32);
-        let token_client = token::Client::new(&env, &token_address);
-        let reserve_before = token_client.balance(&contract_id);
-        client.draw_credit(&borrower, &300);
-        assert_eq!(reserve_before - token_client.balance(&contract_id), 300);
+        client.set_tier(&1, tier);
+        client.open_credit_line_in_tier(
+            &admin,
+            borrower,
+            &1,
+            &70_u32,
+            &None,
+            &RateMode::Fixed,
+            &None,
+            &None,
+        );
+        (client, token_address, admin)
     }
 
     #[test]
-    fn test_draw_updates_utilized_amount() {
+    fn test_tier_update_reprices_lines_on_next_touch() {
+        use soroban_sdk::testutils::Ledger;
         let env = Env::default();
         env.mock_all_auths();
         let borrower = Address::generate(&env);
-        let (client, _token, _admin) =
-            setup_contract_with_credit_line(&env, &borrower, 1_000, 1_000);
-        client.draw_credit(&borrower, &400);
-        assert_eq!(
-            client.get_credit_line(&borrower).unwrap().utilized_amount,
-            400
+        let tier = Tier {
+            max_limit: 1_000,
+            rate_bps: 1_000,
+            ltv_bps: 0,
+            draw_fee_bps: 0,
+        };
+        let (client, _token, _admin) = open_in_tier(&env, &borrower, &tier);
+        let line = client.get_credit_line(&borrower).unwrap();
+        assert_eq!(line.credit_limit, 1_000);
+        assert_eq!(line.interest_rate_bps, 1_000);
+        client.draw_credit(&borrower, &1_000, &None);
+
+        env.ledger().with_mut(|li| li.timestamp = 365 * 86_400);
+        client.set_tier(
+            &1,
+            &Tier {
+                max_limit: 5_000,
+                rate_bps: 2_000,
+                ..tier
+            },
         );
+        assert_eq!(client.get_line_tier(&borrower).unwrap().version, 1);
+
+        let line = client.get_credit_line(&borrower).unwrap();
+        assert_eq!(line.credit_limit, 5_000);
+        assert_eq!(line.interest_rate_bps, 2_000);
+        // The year before the update accrued at the old 10%.
+        assert_eq!(line.accrued_interest, 100);
+        assert_eq!(client.get_line_tier(&borrower).unwrap().version, 2);
     }
 
     #[test]
-    fn test_draw_accumulates_across_multiple_draws() {
+    fn test_tier_draw_fee_replaces_global_fee() {
         let env = Env::default();
         env.mock_all_auths();
         let borrower = Address::generate(&env);
-        let (client, token_address, _admin) =
-            setup_contract_with_credit_line(&env, &borrower, 1_000, 1_000);
-        client.draw_credit(&borrower, &200);
-        client.draw_credit(&borrower, &300);
-        let token_client = token::Client::new(&env, &token_address);
-        assert_eq!(token_client.balance(&borrower), 500);
+        let (client, token_address, _admin) = open_in_tier(
+            &env,
+            &borrower,
+            &Tier {
+                max_limit: 1_000,
+                rate_bps: 300,
+                ltv_bps: 0,
+                draw_fee_bps: 100,
+            },
+        );
+        client.set_fee_config(&FeeConfig {
+            draw_fee_bps: 500,
+            origination_fee: 0,
+        });
+        client.draw_credit(&borrower, &1_000, &None);
         assert_eq!(
-            client.get_credit_line(&borrower).unwrap().utilized_amount,
-            500
+            token::Client::new(&env, &token_address).balance(&borrower),
+            990
         );
     }
 
     #[test]
-    fn test_draw_exact_credit_limit() {
+    fn test_disputed_line_keeps_terms_when_tier_changes() {
         let env = Env::default();
         env.mock_all_auths();
         let borrower = Address::generate(&env);
-        let (client, token_address, _admin) =
-            setup_contract_with_credit_line(&env, &borrower, 1_000, 1_000);
-        client.draw_credit(&borrower, &1_000);
-        let token_client = token::Client::new(&env, &token_address);
-        assert_eq!(token_client.balance(&borrower), 1_000);
+        let tier = Tier {
+            max_limit: 1_000,
+            rate_bps: 300,
+            ltv_bps: 0,
+            draw_fee_bps: 0,
+        };
+        let (client, _token, _admin) = open_in_tier(&env, &borrower, &tier);
+        client.flag_dispute(&borrower, &1);
+        client.set_tier(
+            &1,
+            &Tier {
+                max_limit: 500,
+                ..tier
+            },
+        );
+        assert_eq!(
+            client.get_credit_line(&borrower).unwrap().credit_limit,
+            1_000
+        );
+
+        client.resolve_dispute(&borrower);
+        assert_eq!(client.get_credit_line(&borrower).unwrap().credit_limit, 500);
+    }
+
+    #[test]
+    fn test_tier_exposure_cap_limits_draws_across_lines() {
+        let env = Env::default();
+        env.mock_all_auths();
+        let first = Address::generate(&env);
+        let second = Address::generate(&env);
+        let (client, _token, admin) = open_in_tier(
+            &env,
+            &first,
+            &Tier {
+                max_limit: 1_000,
+                rate_bps: 300,
+                ltv_bps: 0,
+                draw_fee_bps: 0,
+            },
+        );
+        client.open_credit_line_in_tier(
+            &admin,
+            &second,
+            &1,
+            &70_u32,
+            &None,
+            &RateMode::Fixed,
+            &None,
+            &None,
+        );
+        client.set_exposure_cap(&1, &1_500);
+        client.draw_credit(&first, &1_000, &None);
+        assert_eq!(client.get_tier_exposure(&1), 1_000);
+
         assert_eq!(
-            client.get_credit_line(&borrower).unwrap().utilized_amount,
-            1_000
+            client.try_draw_credit(&second, &600, &None),
+            Err(Ok(soroban_sdk::Error::from_contract_error(
+                ContractError::ExposureCapExceeded as u32
+            )))
+        );
+        assert_eq!(
+            client.preview_draw(&second, &600, &None).error,
+            ContractError::ExposureCapExceeded as u32
         );
+        client.draw_credit(&second, &500, &None);
+        client.repay_credit(&first, &200);
+        assert_eq!(client.get_tier_exposure(&1), 1_300);
     }
 
     #[test]
-    fn test_draw_requires_borrower_auth() {
+    #[should_panic(expected = "Error(Contract, #20)")]
+    fn test_protocol_exposure_cap_limits_draws() {
         let env = Env::default();
         env.mock_all_auths();
         let borrower = Address::generate(&env);
         let (client, _token, _admin) =
             setup_contract_with_credit_line(&env, &borrower, 1_000, 1_000);
-        client.draw_credit(&borrower, &100);
-        assert!(
-            env.auths().iter().any(|(addr, _)| *addr == borrower),
-            "draw_credit must require borrower authorization"
-        );
+        client.set_protocol_exposure_cap(&400);
+        client.draw_credit(&borrower, &300, &None);
+        client.draw_credit(&borrower, &200, &None);
     }
 
     #[test]
-    fn test_multiple_borrowers_draw_independently() {
+    #[should_panic(expected = "Error(Contract, #20)")]
+    fn test_open_reverts_when_origination_fee_exceeds_protocol_cap() {
         let env = Env::default();
         env.mock_all_auths();
-        let admin = Address::generate(&env);
-        let b1 = Address::generate(&env);
-        let b2 = Address::generate(&env);
-        let contract_id = env.register(Credit, ());
-        let (token_address, _sac) = setup_token(&env, &contract_id, 3_000);
-        let client = CreditClient::new(&env, &contract_id);
-        client.init(&admin, &token_address);
-        client.open_credit_line(&b1, &1_000, &300_u32, &70_u32);
-        client.open_credit_line(&b2, &2_000, &400_u32, &80_u32);
-        client.draw_credit(&b1, &500);
-        client.draw_credit(&b2, &1_000);
-        let token_client = token::Client::new(&env, &token_address);
-        assert_eq!(token_client.balance(&b1), 500);
-        assert_eq!(token_client.balance(&b2), 1_000);
-        assert_eq!(client.get_credit_line(&b1).unwrap().utilized_amount, 500);
-        assert_eq!(client.get_credit_line(&b2).unwrap().utilized_amount, 1_000);
+        let borrower = Address::generate(&env);
+        let (client, _token, admin) = setup_contract_with_credit_line(&env, &borrower, 1_000, 0);
+        client.set_fee_config(&FeeConfig {
+            draw_fee_bps: 0,
+            origination_fee: 50,
+        });
+        client.set_protocol_exposure_cap(&40);
+        client.open_credit_line(
+            &admin,
+            &Address::generate(&env),
+            &500,
+            &300_u32,
+            &50_u32,
+            &None,
+            &RateMode::Fixed,
+            &None,
+            &None,
+        );
     }
 
-    // ── draw_credit: guards ───────────────────────────────────────────────────
-
     #[test]
-    #[should_panic(expected = "exceeds credit limit")]
-    fn test_draw_exceeds_credit_limit() {
+    #[should_panic(expected = "tier not found")]
+    fn test_open_in_unknown_tier_reverts() {
         let env = Env::default();
         env.mock_all_auths();
         let borrower = Address::generate(&env);
-        let (client, _token, _admin) = setup_contract_with_credit_line(&env, &borrower, 500, 1_000);
-        client.draw_credit(&borrower, &600);
+        let (client, _token, admin) = setup_contract_with_credit_line(&env, &borrower, 1_000, 0);
+        client.open_credit_line_in_tier(
+            &admin,
+            &Address::generate(&env),
+            &9,
+            &70_u32,
+            &None,
+            &RateMode::Fixed,
+            &None,
+            &None,
+        );
     }
 
+    // ── borrower allowlist ────────────────────────────────────────────────────
+
     #[test]
-    #[should_panic(expected = "exceeds credit limit")]
-    fn test_draw_cumulative_exceeds_limit() {
+    fn test_allowlist_gate_blocks_unlisted_borrower_draws() {
         let env = Env::default();
         env.mock_all_auths();
         let borrower = Address::generate(&env);
-        let (client, _token, _admin) = setup_contract_with_credit_line(&env, &borrower, 500, 1_000);
-        client.draw_credit(&borrower, &400);
-        client.draw_credit(&borrower, &200);
+        let compliance = Address::generate(&env);
+        let (client, _token, _admin) =
+            setup_contract_with_credit_line(&env, &borrower, 1_000, 1_000);
+        client.grant_role(&Role::Compliance, &compliance);
+        client.draw_credit(&borrower, &100, &None);
+        client.set_allowlist_enabled(&compliance, &true);
+        assert!(client.is_allowlist_enabled());
+
+        let not_allowlisted = Err(Ok(soroban_sdk::Error::from_contract_error(
+            ContractError::NotAllowlisted as u32,
+        )));
+        assert_eq!(
+            client.try_draw_credit(&borrower, &100, &None),
+            not_allowlisted
+        );
+        assert_eq!(
+            client.preview_draw(&borrower, &100, &None).error,
+            ContractError::NotAllowlisted as u32
+        );
+        // Repayment is not gated.
+        client.repay_credit(&borrower, &50);
+
+        client.allow_borrower(&compliance, &borrower);
+        assert!(client.is_allowlisted(&borrower));
+        client.draw_credit(&borrower, &100, &None);
+
+        client.deny_borrower(&compliance, &borrower);
+        assert!(!client.is_allowlisted(&borrower));
+        assert_eq!(
+            client.try_draw_credit(&borrower, &100, &None),
+            not_allowlisted
+        );
     }
 
     #[test]
-    #[should_panic(expected = "Credit line not active")]
-    fn test_draw_on_suspended_line_fails() {
+    #[should_panic(expected = "Error(Contract, #18)")]
+    fn test_allowlist_gate_blocks_opening_for_unlisted_borrower() {
         let env = Env::default();
         env.mock_all_auths();
         let borrower = Address::generate(&env);
-        let (client, _token, _admin) =
-            setup_contract_with_credit_line(&env, &borrower, 1_000, 1_000);
-        client.suspend_credit_line(&borrower);
-        client.draw_credit(&borrower, &100);
+        let (client, _token, admin) = setup_contract_with_credit_line(&env, &borrower, 1_000, 0);
+        client.set_allowlist_enabled(&admin, &true);
+        client.open_credit_line(
+            &admin,
+            &Address::generate(&env),
+            &500,
+            &300_u32,
+            &50_u32,
+            &None,
+            &RateMode::Fixed,
+            &None,
+            &None,
+        );
     }
 
     #[test]
-    #[should_panic(expected = "credit line is closed")]
-    fn test_draw_on_closed_line_fails() {
+    #[should_panic(expected = "unauthorized")]
+    fn test_allow_borrower_requires_compliance_role() {
         let env = Env::default();
         env.mock_all_auths();
         let borrower = Address::generate(&env);
-        let (client, _token, admin) =
-            setup_contract_with_credit_line(&env, &borrower, 1_000, 1_000);
-        client.close_credit_line(&borrower, &admin);
-        client.draw_credit(&borrower, &100);
+        let (client, _token, _admin) = setup_contract_with_credit_line(&env, &borrower, 1_000, 0);
+        client.allow_borrower(&Address::generate(&env), &borrower);
     }
 
+    // ── sanctions blocklist ───────────────────────────────────────────────────
+
     #[test]
-    #[should_panic(expected = "Credit line not active")]
-    fn test_draw_on_defaulted_line_fails() {
+    fn test_blocked_borrower_cannot_draw_until_unblocked() {
+        use soroban_sdk::testutils::Events;
+        use soroban_sdk::TryIntoVal;
         let env = Env::default();
         env.mock_all_auths();
         let borrower = Address::generate(&env);
+        let compliance = Address::generate(&env);
         let (client, _token, _admin) =
             setup_contract_with_credit_line(&env, &borrower, 1_000, 1_000);
-        client.default_credit_line(&borrower);
-        client.draw_credit(&borrower, &100);
+        client.grant_role(&Role::Compliance, &compliance);
+        client.draw_credit(&borrower, &100, &None);
+        client.block_address(&compliance, &borrower);
+        let event = env
+            .events()
+            .all()
+            .iter()
+            .find_map(|e| TryIntoVal::<Env, BlocklistEvent>::try_into_val(&e.2, &env).ok())
+            .unwrap();
+        assert!(event.blocked);
+        assert!(client.is_blocked(&borrower));
+
+        let blocked = Err(Ok(soroban_sdk::Error::from_contract_error(
+            ContractError::Blocked as u32,
+        )));
+        assert_eq!(client.try_draw_credit(&borrower, &100, &None), blocked);
+        assert_eq!(
+            client.preview_draw(&borrower, &100, &None).error,
+            ContractError::Blocked as u32
+        );
+        client.repay_credit(&borrower, &50);
+
+        client.unblock_address(&compliance, &borrower);
+        assert!(!client.is_blocked(&borrower));
+        client.draw_credit(&borrower, &100, &None);
     }
 
     #[test]
-    #[should_panic(expected = "amount must be positive")]
-    fn test_draw_zero_amount_fails() {
+    #[should_panic(expected = "Error(Contract, #19)")]
+    fn test_draw_to_blocked_recipient_reverts() {
         let env = Env::default();
         env.mock_all_auths();
         let borrower = Address::generate(&env);
-        let (client, _token, _admin) =
+        let merchant = Address::generate(&env);
+        let (client, _token, admin) =
             setup_contract_with_credit_line(&env, &borrower, 1_000, 1_000);
-        client.draw_credit(&borrower, &0);
+        client.block_address(&admin, &merchant);
+        client.draw_credit_to(&borrower, &merchant, &100);
     }
 
     #[test]
-    #[should_panic(expected = "amount must be positive")]
-    fn test_draw_negative_amount_fails() {
+    #[should_panic(expected = "Error(Contract, #19)")]
+    fn test_blocked_operator_cannot_draw() {
         let env = Env::default();
         env.mock_all_auths();
         let borrower = Address::generate(&env);
-        let (client, _token, _admin) =
+        let operator = Address::generate(&env);
+        let (client, _token, admin) =
             setup_contract_with_credit_line(&env, &borrower, 1_000, 1_000);
-        client.draw_credit(&borrower, &-50);
+        client.approve_operator(&borrower, &operator, &300, &100);
+        client.block_address(&admin, &operator);
+        client.draw_credit_as_operator(&operator, &borrower, &100);
     }
 
     #[test]
-    #[should_panic(expected = "Credit line not found")]
-    fn test_draw_no_credit_line_fails() {
+    fn test_frozen_transfers_block_draws_and_refunds() {
         let env = Env::default();
         env.mock_all_auths();
-        let stranger = Address::generate(&env);
-        let admin = Address::generate(&env);
-        let contract_id = env.register(Credit, ());
-        let (token_address, _sac) = setup_token(&env, &contract_id, 1_000);
-        let client = CreditClient::new(&env, &contract_id);
-        client.init(&admin, &token_address);
-        client.draw_credit(&stranger, &100);
+        let borrower = Address::generate(&env);
+        let (client, _token, _admin) =
+            setup_contract_with_credit_line(&env, &borrower, 1_000, 1_000);
+        client.draw_credit(&borrower, &300, &None);
+        client.set_line_restrictions(&borrower, &restrictions::TRANSFERS_FROZEN);
+
+        assert!(client.try_draw_credit(&borrower, &100, &None).is_err());
+        assert_eq!(
+            client.preview_draw(&borrower, &100, &None).error,
+            ContractError::Restricted as u32
+        );
+        assert!(client
+            .try_repay_credit_with_refund(&borrower, &400)
+            .is_err());
+        client.repay_credit(&borrower, &100);
+        assert_eq!(
+            client.get_credit_line(&borrower).unwrap().utilized_amount,
+            200
+        );
     }
 
-    // ── open_credit_line validation ───────────────────────────────────────────
+    // ── on-chain risk scoring ─────────────────────────────────────────────────
 
-    #[test]
-    #[should_panic(expected = "borrower already has an active credit line")]
-    fn test_open_credit_line_duplicate_active_borrower_reverts() {
-        let env = Env::default();
-        env.mock_all_auths();
-        let borrower = Address::generate(&env);
-        let (client, _token, _admin) = setup_contract_with_credit_line(&env, &borrower, 1_000, 0);
-        client.open_credit_line(&borrower, &2_000, &400_u32, &60_u32);
+    mod mock_scoring {
+        use soroban_sdk::{contract, contractimpl, Address, Env};
+
+        #[contract]
+        pub struct MockScoring;
+
+        #[contractimpl]
+        impl MockScoring {
+            pub fn set_score(env: Env, borrower: Address, score: u32) {
+                env.storage().instance().set(&borrower, &score);
+            }
+
+            pub fn score(env: Env, borrower: Address) -> u32 {
+                env.storage().instance().get(&borrower).unwrap_or(0)
+            }
+        }
+    }
+
+    fn setup_scoring<'a>(
+        env: &'a Env,
+        client: &CreditClient<'a>,
+    ) -> mock_scoring::MockScoringClient<'a> {
+        let scoring_id = env.register(mock_scoring::MockScoring, ());
+        client.set_scoring_contract(&scoring_id);
+        mock_scoring::MockScoringClient::new(env, &scoring_id)
     }
 
     #[test]
-    #[should_panic(expected = "credit_limit must be greater than zero")]
-    fn test_open_credit_line_zero_limit_reverts() {
+    fn test_scoring_contract_overrides_supplied_score_on_open() {
         let env = Env::default();
         env.mock_all_auths();
-        let admin = Address::generate(&env);
         let borrower = Address::generate(&env);
-        let contract_id = env.register(Credit, ());
-        let (token_address, _) = setup_token(&env, &contract_id, 0);
-        let client = CreditClient::new(&env, &contract_id);
-        client.init(&admin, &token_address);
-        client.open_credit_line(&borrower, &0, &300_u32, &70_u32);
+        let (client, _token, admin) = setup_contract_with_credit_line(&env, &borrower, 1_000, 0);
+        let scoring = setup_scoring(&env, &client);
+        let other = Address::generate(&env);
+        scoring.set_score(&other, &42);
+
+        client.open_credit_line(
+            &admin,
+            &other,
+            &1_000,
+            &300_u32,
+            &90_u32,
+            &None,
+            &RateMode::Fixed,
+            &None,
+            &None,
+        );
+        assert_eq!(client.get_credit_line(&other).unwrap().risk_score, 42);
+        assert_eq!(client.get_config().scoring_contract, Some(scoring.address));
     }
 
     #[test]
-    #[should_panic(expected = "credit_limit must be greater than zero")]
-    fn test_open_credit_line_negative_limit_reverts() {
+    fn test_draw_refreshes_score_from_scoring_contract() {
         let env = Env::default();
         env.mock_all_auths();
-        let admin = Address::generate(&env);
         let borrower = Address::generate(&env);
-        let contract_id = env.register(Credit, ());
-        let (token_address, _) = setup_token(&env, &contract_id, 0);
-        let client = CreditClient::new(&env, &contract_id);
-        client.init(&admin, &token_address);
-        client.open_credit_line(&borrower, &-1, &300_u32, &70_u32);
+        let (client, _token, _admin) =
+            setup_contract_with_credit_line(&env, &borrower, 1_000, 1_000);
+        let scoring = setup_scoring(&env, &client);
+        scoring.set_score(&borrower, &55);
+
+        assert_eq!(client.get_credit_line(&borrower).unwrap().risk_score, 70);
+        client.draw_credit(&borrower, &100, &None);
+        assert_eq!(client.get_credit_line(&borrower).unwrap().risk_score, 55);
     }
 
     #[test]
-    #[should_panic(expected = "interest_rate_bps cannot exceed 10000 (100%)")]
-    fn test_open_credit_line_interest_rate_exceeds_max_reverts() {
+    #[should_panic(expected = "risk_score exceeds maximum")]
+    fn test_out_of_range_score_from_scoring_contract_reverts_draw() {
         let env = Env::default();
         env.mock_all_auths();
-        let admin = Address::generate(&env);
         let borrower = Address::generate(&env);
-        let contract_id = env.register(Credit, ());
-        let (token_address, _) = setup_token(&env, &contract_id, 0);
-        let client = CreditClient::new(&env, &contract_id);
-        client.init(&admin, &token_address);
-        client.open_credit_line(&borrower, &1_000, &10_001_u32, &70_u32);
+        let (client, _token, _admin) =
+            setup_contract_with_credit_line(&env, &borrower, 1_000, 1_000);
+        let scoring = setup_scoring(&env, &client);
+        scoring.set_score(&borrower, &101);
+        client.draw_credit(&borrower, &100, &None);
     }
 
+    // ── pause / circuit breaker ───────────────────────────────────────────────
+
     #[test]
-    #[should_panic(expected = "risk_score must be between 0 and 100")]
-    fn test_open_credit_line_risk_score_exceeds_max_reverts() {
+    #[should_panic(expected = "Error(Contract, #1)")]
+    fn test_draw_rejected_while_draws_paused() {
         let env = Env::default();
         env.mock_all_auths();
-        let admin = Address::generate(&env);
         let borrower = Address::generate(&env);
-        let contract_id = env.register(Credit, ());
-        let (token_address, _) = setup_token(&env, &contract_id, 0);
-        let client = CreditClient::new(&env, &contract_id);
-        client.init(&admin, &token_address);
-        client.open_credit_line(&borrower, &1_000, &300_u32, &101_u32);
+        let (client, _token, admin) =
+            setup_contract_with_credit_line(&env, &borrower, 1_000, 1_000);
+        client.pause(&admin, &true, &false);
+        client.draw_credit(&borrower, &100, &None);
     }
 
-    // ── lifecycle ─────────────────────────────────────────────────────────────
-
     #[test]
-    fn test_init_and_open_credit_line() {
+    fn test_repay_allowed_while_only_draws_paused() {
         let env = Env::default();
         env.mock_all_auths();
         let borrower = Address::generate(&env);
-        let (client, _token, _admin) = setup_contract_with_credit_line(&env, &borrower, 1_000, 0);
-        let line = client.get_credit_line(&borrower).unwrap();
-        assert_eq!(line.borrower, borrower);
-        assert_eq!(line.credit_limit, 1_000);
-        assert_eq!(line.utilized_amount, 0);
-        assert_eq!(line.interest_rate_bps, 300);
-        assert_eq!(line.risk_score, 70);
-        assert_eq!(line.status, CreditStatus::Active);
+        let pauser = Address::generate(&env);
+        let (client, _token, _admin) =
+            setup_contract_with_credit_line(&env, &borrower, 1_000, 1_000);
+        client.grant_role(&Role::Pauser, &pauser);
+        client.draw_credit(&borrower, &300, &None);
+        client.pause(&pauser, &true, &false);
+        client.repay_credit(&borrower, &100);
+        assert_eq!(
+            client.get_credit_line(&borrower).unwrap().utilized_amount,
+            200
+        );
+        assert_eq!(
+            client.get_pause_state(),
+            PauseState {
+                draws_paused: true,
+                repays_paused: false
+            }
+        );
     }
 
     #[test]
-    fn test_suspend_credit_line() {
+    #[should_panic(expected = "Error(Contract, #1)")]
+    fn test_repay_rejected_while_repays_paused() {
         let env = Env::default();
         env.mock_all_auths();
         let borrower = Address::generate(&env);
-        let (client, _token, _admin) = setup_contract_with_credit_line(&env, &borrower, 1_000, 0);
-        client.suspend_credit_line(&borrower);
-        assert_eq!(
-            client.get_credit_line(&borrower).unwrap().status,
-            CreditStatus::Suspended
-        );
+        let (client, _token, admin) =
+            setup_contract_with_credit_line(&env, &borrower, 1_000, 1_000);
+        client.draw_credit(&borrower, &300, &None);
+        client.pause(&admin, &false, &true);
+        client.repay_credit(&borrower, &100);
     }
 
     #[test]
-    fn test_close_credit_line() {
+    fn test_unpause_restores_draws() {
         let env = Env::default();
         env.mock_all_auths();
         let borrower = Address::generate(&env);
-        let (client, _token, admin) = setup_contract_with_credit_line(&env, &borrower, 1_000, 0);
-        client.close_credit_line(&borrower, &admin);
+        let (client, _token, admin) =
+            setup_contract_with_credit_line(&env, &borrower, 1_000, 1_000);
+        client.pause(&admin, &true, &true);
+        client.unpause(&admin, &true, &false);
         assert_eq!(
-            client.get_credit_line(&borrower).unwrap().status,
-            CreditStatus::Closed
+            client.get_pause_state(),
+            PauseState {
+                draws_paused: false,
+                repays_paused: true
+            }
+        );
+        client.draw_credit(&borrower, &100, &None);
+        assert_eq!(
+            client.get_credit_line(&borrower).unwrap().utilized_amount,
+            100
         );
     }
 
     #[test]
-    fn test_default_credit_line() {
+    #[should_panic(expected = "unauthorized")]
+    fn test_pause_requires_pauser_role() {
         let env = Env::default();
         env.mock_all_auths();
         let borrower = Address::generate(&env);
+        let risk_engine = Address::generate(&env);
         let (client, _token, _admin) = setup_contract_with_credit_line(&env, &borrower, 1_000, 0);
-        client.default_credit_line(&borrower);
-        assert_eq!(
-            client.get_credit_line(&borrower).unwrap().status,
-            CreditStatus::Defaulted
-        );
+        client.grant_role(&Role::RiskEngine, &risk_engine);
+        client.pause(&risk_engine, &true, &true);
     }
 
     #[test]
-    fn test_full_lifecycle() {
+    fn test_event_pause_and_unpause() {
+        use soroban_sdk::testutils::Events;
+        use soroban_sdk::{TryFromVal, TryIntoVal};
         let env = Env::default();
         env.mock_all_auths();
         let borrower = Address::generate(&env);
-        let (client, _token, admin) =
-            setup_contract_with_credit_line(&env, &borrower, 5_000, 5_000);
-        assert_eq!(
-            client.get_credit_line(&borrower).unwrap().status,
-            CreditStatus::Active
-        );
-        client.suspend_credit_line(&borrower);
+        let (client, _token, admin) = setup_contract_with_credit_line(&env, &borrower, 1_000, 0);
+        client.pause(&admin, &true, &false);
+        let (_contract, topics, data) = env.events().all().last().unwrap();
         assert_eq!(
-            client.get_credit_line(&borrower).unwrap().status,
-            CreditStatus::Suspended
+            Symbol::try_from_val(&env, &topics.get(1).unwrap()).unwrap(),
+            symbol_short!("paused")
         );
-        client.close_credit_line(&borrower, &admin);
+        let event_data: PauseEvent = data.try_into_val(&env).unwrap();
+        assert!(event_data.draws_paused);
+        assert!(!event_data.repays_paused);
+
+        client.unpause(&admin, &true, &true);
+        let (_contract, topics, data) = env.events().all().last().unwrap();
         assert_eq!(
-            client.get_credit_line(&borrower).unwrap().status,
-            CreditStatus::Closed
+            Symbol::try_from_val(&env, &topics.get(1).unwrap()).unwrap(),
+            symbol_short!("unpaused")
         );
+        let event_data: PauseEvent = data.try_into_val(&env).unwrap();
+        assert!(!event_data.draws_paused);
+        assert_eq!(event_data.caller, admin);
     }
 
-    #[test]
-    fn test_close_credit_line_borrower_when_utilized_zero() {
-        let env = Env::default();
-        env.mock_all_auths();
-        let borrower = Address::generate(&env);
-        let (client, _token, _admin) = setup_contract_with_credit_line(&env, &borrower, 1_000, 0);
-        client.close_credit_line(&borrower, &borrower);
-        let line = client.get_credit_line(&borrower).unwrap();
-        assert_eq!(line.status, CreditStatus::Closed);
-        assert_eq!(line.utilized_amount, 0);
-    }
+    // ── event emission ────────────────────────────────────────────────────────
 
     #[test]
-    #[should_panic(expected = "cannot close: utilized amount not zero")]
-    fn test_close_credit_line_borrower_rejected_when_utilized_nonzero() {
+    fn test_event_open_credit_line() {
+        use soroban_sdk::testutils::Events;
+        use soroban_sdk::{TryFromVal, TryIntoVal};
         let env = Env::default();
         env.mock_all_auths();
         let borrower = Address::generate(&env);
-        let (client, _token, _admin) =
-            setup_contract_with_credit_line(&env, &borrower, 1_000, 1_000);
-        client.draw_credit(&borrower, &300);
-        client.close_credit_line(&borrower, &borrower);
+        let (client, _token, _admin) = setup_contract_with_credit_line(&env, &borrower, 1_000, 0);
+        let _ = client;
+        let events = env.events().all();
+        let (_contract, topics, data) = events.last().unwrap();
+        assert_eq!(
+            Symbol::try_from_val(&env, &topics.get(1).unwrap()).unwrap(),
+            symbol_short!("opened")
+        );
+        let event_data: CreditLineEvent = data.try_into_val(&env).unwrap();
+        assert_eq!(event_data.status, CreditStatus::Active);
+        assert_eq!(event_data.borrower, borrower);
     }
 
     #[test]
-    fn test_close_credit_line_admin_force_close_with_utilization() {
+    fn test_event_suspend_credit_line() {
+        use soroban_sdk::testutils::Events;
+        use soroban_sdk::{TryFromVal, TryIntoVal};
         let env = Env::default();
         env.mock_all_auths();
         let borrower = Address::generate(&env);
-        let (client, _token, admin) =
-            setup_contract_with_credit_line(&env, &borrower, 1_000, 1_000);
-        client.draw_credit(&borrower, &300);
-        client.close_credit_line(&borrower, &admin);
-        let line = client.get_credit_line(&borrower).unwrap();
-        assert_eq!(line.status, CreditStatus::Closed);
-        assert_eq!(line.utilized_amount, 300);
+        let (client, _token, _admin) = setup_contract_with_credit_line(&env, &borrower, 1_000, 0);
+        client.suspend_credit_line(&borrower, &0, &None);
+        let events = env.events().all();
+        let (_contract, topics, data) = events.last().unwrap();
+        assert_eq!(
+            Symbol::try_from_val(&env, &topics.get(1).unwrap()).unwrap(),
+            symbol_short!("suspend")
+        );
+        let event_data: CreditLineEvent = data.try_into_val(&env).unwrap();
+        assert_eq!(event_data.status, CreditStatus::Suspended);
     }
 
     #[test]
-    fn test_close_credit_line_idempotent_when_already_closed() {
+    fn test_event_close_credit_line() {
+        use soroban_sdk::testutils::Events;
+        use soroban_sdk::{TryFromVal, TryIntoVal};
         let env = Env::default();
         env.mock_all_auths();
         let borrower = Address::generate(&env);
         let (client, _token, admin) = setup_contract_with_credit_line(&env, &borrower, 1_000, 0);
         client.close_credit_line(&borrower, &admin);
-        client.close_credit_line(&borrower, &admin);
+        let events = env.events().all();
+        let (_contract, topics, data) = events.last().unwrap();
         assert_eq!(
-            client.get_credit_line(&borrower).unwrap().status,
-            CreditStatus::Closed
+            Symbol::try_from_val(&env, &topics.get(1).unwrap()).unwrap(),
+            symbol_short!("closed")
         );
+        let event_data: CreditLineEvent = data.try_into_val(&env).unwrap();
+        assert_eq!(event_data.status, CreditStatus::Closed);
     }
 
     #[test]
-    #[should_panic(expected = "unauthorized")]
-    fn test_close_credit_line_unauthorized_closer() {
+    fn test_event_default_credit_line() {
+        use soroban_sdk::testutils::Events;
+        use soroban_sdk::{TryFromVal, TryIntoVal};
         let env = Env::default();
         env.mock_all_auths();
         let borrower = Address::generate(&env);
-        let other = Address::generate(&env);
         let (client, _token, _admin) = setup_contract_with_credit_line(&env, &borrower, 1_000, 0);
-        client.close_credit_line(&borrower, &other);
+        client.default_credit_line(&borrower);
+        let events = env.events().all();
+        let (_contract, topics, data) = events.last().unwrap();
+        assert_eq!(
+            Symbol::try_from_val(&env, &topics.get(1).unwrap()).unwrap(),
+            symbol_short!("default")
+        );
+        let event_data: CreditLineEvent = data.try_into_val(&env).unwrap();
+        assert_eq!(event_data.status, CreditStatus::Defaulted);
     }
 
     #[test]
-    #[should_panic(expected = "Credit line not found")]
-    fn test_suspend_nonexistent_credit_line() {
+    fn test_event_lifecycle_sequence() {
+        use soroban_sdk::testutils::Events;
+        use soroban_sdk::{TryFromVal, TryIntoVal};
         let env = Env::default();
         env.mock_all_auths();
         let borrower = Address::generate(&env);
-        let admin = Address::generate(&env);
-        let contract_id = env.register(Credit, ());
-        let (token_address, _) = setup_token(&env, &contract_id, 0);
-        let client = CreditClient::new(&env, &contract_id);
-        client.init(&admin, &token_address);
-        client.suspend_credit_line(&borrower);
-    }
+        let (client, _token, admin) = setup_contract_with_credit_line(&env, &borrower, 1_000, 0);
+        let open_data: CreditLineEvent = env
+            .events()
+            .all()
+            .last()
+            .unwrap()
+            .2
+            .try_into_val(&env)
+            .unwrap();
+        assert_eq!(open_data.status, CreditStatus::Active);
+
+        client.suspend_credit_line(&borrower, &0, &None);
+        let suspend_data: CreditLineEvent = env
+            .events()
+            .all()
+            .last()
+            .unwrap()
+            .2
+            .try_into_val(&env)
+            .unwrap();
+        assert_eq!(suspend_data.status, CreditStatus::Suspended);
+        assert_eq!(
+            Symbol::try_from_val(&env, &env.events().all().last().unwrap().1.get(1).unwrap())
+                .unwrap(),
+            symbol_short!("suspend")
+        );
 
-    #[test]
-    #[should_panic(expected = "Credit line not found")]
-    fn test_close_nonexistent_credit_line() {
-        let env = Env::default();
-        env.mock_all_auths();
-        let borrower = Address::generate(&env);
-        let admin = Address::generate(&env);
-        let contract_id = env.register(Credit, ());
-        let (token_address, _) = setup_token(&env, &contract_id, 0);
-        let client = CreditClient::new(&env, &contract_id);
-        client.init(&admin, &token_address);
         client.close_credit_line(&borrower, &admin);
+        let close_data: CreditLineEvent = env
+            .events()
+            .all()
+            .last()
+            .unwrap()
+            .2
+            .try_into_val(&env)
+            .unwrap();
+        assert_eq!(close_data.status, CreditStatus::Closed);
     }
 
     #[test]
-    #[should_panic(expected = "Credit line not found")]
-    fn test_default_nonexistent_credit_line() {
+    fn test_event_topics_include_borrower() {
+        use soroban_sdk::testutils::Events;
+        use soroban_sdk::TryFromVal;
         let env = Env::default();
         env.mock_all_auths();
         let borrower = Address::generate(&env);
-        let admin = Address::generate(&env);
-        let contract_id = env.register(Credit, ());
-        let (token_address, _) = setup_token(&env, &contract_id, 0);
-        let client = CreditClient::new(&env, &contract_id);
-        client.init(&admin, &token_address);
-        client.default_credit_line(&borrower);
-    }
+        let (client, _token, _admin) =
+            setup_contract_with_credit_line(&env, &borrower, 1_000, 1_000);
+        let (_contract, topics, _data) = env.events().all().last().unwrap();
+        assert_eq!(
+            Address::try_from_val(&env, &topics.get(2).unwrap()).unwrap(),
+            borrower
+        );
 
-    // ── update_risk_parameters ────────────────────────────────────────────────
+        client.draw_credit(&borrower, &200, &None);
+        let events = env.events().all();
+        let (_contract, topics, _data) = events.last().unwrap();
+        assert_eq!(
+            Symbol::try_from_val(&env, &topics.get(1).unwrap()).unwrap(),
+            symbol_short!("draw")
+        );
+        assert_eq!(
+            Address::try_from_val(&env, &topics.get(2).unwrap()).unwrap(),
+            borrower
+        );
 
-    #[test]
-    fn test_update_risk_parameters_success() {
-        let env = Env::default();
-        env.mock_all_auths();
-        let borrower = Address::generate(&env);
-        let (client, _token, _admin) = setup_contract_with_credit_line(&env, &borrower, 1_000, 0);
-        client.update_risk_parameters(&borrower, &2_000, &400_u32, &85_u32);
-        let line = client.get_credit_line(&borrower).unwrap();
-        assert_eq!(line.credit_limit, 2_000);
-        assert_eq!(line.interest_rate_bps, 400);
-        assert_eq!(line.risk_score, 85);
+        client.repay_credit(&borrower, &50);
+        let events = env.events().all();
+        let (_contract, topics, _data) = events.last().unwrap();
+        assert_eq!(
+            Symbol::try_from_val(&env, &topics.get(1).unwrap()).unwrap(),
+            symbol_short!("repay")
+        );
+        assert_eq!(
+            Address::try_from_val(&env, &topics.get(2).unwrap()).unwrap(),
+            borrower
+        );
     }
 
     #[test]
-    #[should_panic]
-    fn test_update_risk_parameters_unauthorized_caller() {
+    fn test_event_sequence_increases_per_event() {
+        use soroban_sdk::testutils::Events;
+        use soroban_sdk::TryIntoVal;
         let env = Env::default();
-        let admin = Address::generate(&env);
+        env.mock_all_auths();
         let borrower = Address::generate(&env);
-        let token = Address::generate(&env);
-        let contract_id = env.register(Credit, ());
-        let client = CreditClient::new(&env, &contract_id);
+        let (client, _token, _admin) =
+            setup_contract_with_credit_line(&env, &borrower, 1_000, 1_000);
+        let open_data: CreditLineEvent = env
+            .events()
+            .all()
+            .last()
+            .unwrap()
+            .2
+            .try_into_val(&env)
+            .unwrap();
+        assert_eq!(open_data.event_version, EVENT_VERSION);
+        assert_eq!(open_data.sequence, 2);
 
-        client.init(&admin, &token);
-        client.open_credit_line(&borrower, &1_000, &300_u32, &70_u32);
-        client.update_risk_parameters(&borrower, &2_000, &400_u32, &85_u32);
+        client.suspend_credit_line(&borrower, &0, &None);
+        client.resume_credit_line(&borrower);
+        let resume_data: CreditLineEvent = env
+            .events()
+            .all()
+            .last()
+            .unwrap()
+            .2
+            .try_into_val(&env)
+            .unwrap();
+        assert_eq!(resume_data.sequence, 4);
+        assert_eq!(client.get_event_sequence(), 4);
     }
 
     #[test]
-    #[should_panic(expected = "Credit line not found")]
-    fn test_update_risk_parameters_nonexistent_line() {
+    fn test_event_init() {
+        use soroban_sdk::testutils::Events;
+        use soroban_sdk::{TryFromVal, TryIntoVal};
         let env = Env::default();
         env.mock_all_auths();
-        let borrower = Address::generate(&env);
         let admin = Address::generate(&env);
         let contract_id = env.register(Credit, ());
-        let (token_address, _) = setup_token(&env, &contract_id, 0);
         let client = CreditClient::new(&env, &contract_id);
+        let (token_address, _) = setup_token(&env, &contract_id, 0);
         client.init(&admin, &token_address);
-        client.update_risk_parameters(&borrower, &1_000, &300_u32, &70_u32);
+        let (_contract, topics, data) = env.events().all().last().unwrap();
+        assert_eq!(
+            Symbol::try_from_val(&env, &topics.get(1).unwrap()).unwrap(),
+            symbol_short!("init")
+        );
+        let event: InitializedEvent = data.try_into_val(&env).unwrap();
+        assert_eq!(event.admin, admin);
+        assert_eq!(event.token, token_address);
     }
 
     #[test]
-    #[should_panic(expected = "credit_limit cannot be less than utilized amount")]
-    fn test_update_risk_parameters_credit_limit_below_utilized() {
+    fn test_event_config_updates() {
+        use soroban_sdk::testutils::Events;
+        use soroban_sdk::{TryFromVal, TryIntoVal, Val};
         let env = Env::default();
         env.mock_all_auths();
         let borrower = Address::generate(&env);
-        let (client, _token, _admin) =
-            setup_contract_with_credit_line(&env, &borrower, 1_000, 1_000);
-        client.draw_credit(&borrower, &500);
-        client.update_risk_parameters(&borrower, &300, &300_u32, &70_u32);
+        let (client, _token, _admin) = setup_contract_with_credit_line(&env, &borrower, 1_000, 0);
+        let config = FeeConfig {
+            draw_fee_bps: 50,
+            origination_fee: 0,
+        };
+        client.set_fee_config(&config);
+        let (_contract, topics, data) = env.events().all().last().unwrap();
+        assert_eq!(
+            Symbol::try_from_val(&env, &topics.get(1).unwrap()).unwrap(),
+            symbol_short!("config")
+        );
+        assert_eq!(
+            Symbol::try_from_val(&env, &topics.get(2).unwrap()).unwrap(),
+            symbol_short!("fee_cfg")
+        );
+        let (version, sequence, value): (u32, u64, Val) = data.try_into_val(&env).unwrap();
+        assert_eq!(version, EVENT_VERSION);
+        assert_eq!(sequence, client.get_event_sequence());
+        assert_eq!(FeeConfig::try_from_val(&env, &value).unwrap(), config);
+
+        let source = Address::generate(&env);
+        client.add_liquidity_source(&source, &3);
+        let event: LiquiditySourceSetEvent = env
+            .events()
+            .all()
+            .last()
+            .unwrap()
+            .2
+            .try_into_val(&env)
+            .unwrap();
+        assert!(event.registered);
+        assert_eq!(event.priority, 3);
+
+        client.remove_liquidity_source(&source);
+        let event: LiquiditySourceSetEvent = env
+            .events()
+            .all()
+            .last()
+            .unwrap()
+            .2
+            .try_into_val(&env)
+            .unwrap();
+        assert!(!event.registered);
+        assert_eq!(event.source, source);
     }
 
     #[test]
-    #[should_panic(expected = "credit_limit must be non-negative")]
-    fn test_update_risk_parameters_negative_credit_limit() {
+    fn test_get_config_reflects_settings() {
         let env = Env::default();
         env.mock_all_auths();
         let borrower = Address::generate(&env);
-        let (client, _token, _admin) = setup_contract_with_credit_line(&env, &borrower, 1_000, 0);
-        client.update_risk_parameters(&borrower, &-1, &300_u32, &70_u32);
+        let (client, token, admin) = setup_contract_with_credit_line(&env, &borrower, 1_000, 0);
+        let treasury = Address::generate(&env);
+        client.set_treasury(&treasury);
+        client.set_reserve_factor_bps(&1_000);
+
+        let config = client.get_config();
+        assert_eq!(config.admin, admin);
+        assert_eq!(config.token, token);
+        assert_eq!(config.treasury, Some(treasury));
+        assert_eq!(config.reserve_factor_bps, 1_000);
+        assert_eq!(config.fees, client.get_fee_config());
+        assert_eq!(config.pause, client.get_pause_state());
     }
 
     #[test]
-    #[should_panic(expected = "interest_rate_bps exceeds maximum")]
-    fn test_update_risk_parameters_interest_rate_exceeds_max() {
+    fn test_protocol_config_is_stored_as_one_entry() {
         let env = Env::default();
         env.mock_all_auths();
         let borrower = Address::generate(&env);
-        let (client, _token, _admin) = setup_contract_with_credit_line(&env, &borrower, 1_000, 0);
-        client.update_risk_parameters(&borrower, &1_000, &10_001_u32, &70_u32);
+        let (client, _token, admin) = setup_contract_with_credit_line(&env, &borrower, 1_000, 0);
+        let stored = || -> ProtocolConfig {
+            env.as_contract(&client.address, || {
+                storage::get_instance(&env, &DataKey::Config(Symbol::new(&env, "protocol")))
+                    .unwrap()
+            })
+        };
+        assert_eq!(stored(), client.get_config());
+
+        client.set_fee_config(&FeeConfig {
+            draw_fee_bps: 25,
+            origination_fee: 10,
+        });
+        assert_eq!(stored().fees.draw_fee_bps, 25);
+        client.pause(&admin, &true, &false);
+        assert!(stored().pause.draws_paused);
+        let second_token = Address::generate(&env);
+        client.set_token_supported(&second_token, &true);
+        assert!(stored().supported_tokens.contains(&second_token));
+        let source = Address::generate(&env);
+        client.add_liquidity_source(&source, &1);
+        assert!(stored()
+            .liquidity_sources
+            .iter()
+            .any(|s| s.address == source));
+        assert_eq!(stored(), client.get_config());
     }
 
     #[test]
-    #[should_panic(expected = "risk_score exceeds maximum")]
-    fn test_update_risk_parameters_risk_score_exceeds_max() {
+    fn test_set_config_updates_only_present_fields() {
         let env = Env::default();
         env.mock_all_auths();
         let borrower = Address::generate(&env);
         let (client, _token, _admin) = setup_contract_with_credit_line(&env, &borrower, 1_000, 0);
-        client.update_risk_parameters(&borrower, &1_000, &300_u32, &101_u32);
+        client.set_dust_threshold(&5);
+        let fees = FeeConfig {
+            draw_fee_bps: 25,
+            origination_fee: 10,
+        };
+        let limits = DrawLimits {
+            min_draw_amount: 10,
+            max_draw_amount: 500,
+            min_seconds_between_draws: 0,
+        };
+        client.set_config(&soroban_sdk::vec![
+            &env,
+            ConfigUpdate::Fees(fees.clone()),
+            ConfigUpdate::DrawLimits(limits.clone()),
+        ]);
+
+        let config = client.get_config();
+        assert_eq!(config.fees, fees);
+        assert_eq!(config.draw_limits, limits);
+        assert_eq!(config.dust_threshold, 5);
+        assert_eq!(client.get_draw_limits(), limits);
     }
 
     #[test]
-    fn test_update_risk_parameters_at_boundaries() {
+    #[should_panic(expected = "min_draw_amount exceeds max_draw_amount")]
+    fn test_set_config_invalid_field_reverts() {
         let env = Env::default();
         env.mock_all_auths();
         let borrower = Address::generate(&env);
         let (client, _token, _admin) = setup_contract_with_credit_line(&env, &borrower, 1_000, 0);
-        client.update_risk_parameters(&borrower, &1_000, &10_000_u32, &100_u32);
-        let line = client.get_credit_line(&borrower).unwrap();
-        assert_eq!(line.interest_rate_bps, 10_000);
-        assert_eq!(line.risk_score, 100);
+        client.set_config(&soroban_sdk::vec![
+            &env,
+            ConfigUpdate::DrawLimits(DrawLimits {
+                min_draw_amount: 100,
+                max_draw_amount: 10,
+                min_seconds_between_draws: 0,
+            }),
+        ]);
     }
 
-    // ── repay_credit ──────────────────────────────────────────────────────────
+    // ── health check ──────────────────────────────────────────────────────────
 
     #[test]
-    fn test_repay_credit_reduces_utilized_amount() {
+    fn test_health_check_reports_reserve_pause_and_delinquency() {
+        use soroban_sdk::testutils::Ledger;
         let env = Env::default();
         env.mock_all_auths();
         let borrower = Address::generate(&env);
-        let (client, _token, _admin) =
-            setup_contract_with_credit_line(&env, &borrower, 1_000, 1_000);
-        client.draw_credit(&borrower, &500);
-        client.repay_credit(&borrower, &200);
-        assert_eq!(
-            client.get_credit_line(&borrower).unwrap().utilized_amount,
-            300
-        );
+        let keeper = Address::generate(&env);
+        let config = KeeperConfig {
+            grace_period_secs: 50,
+            default_after_missed_cycles: 3,
+            keeper_reward: 0,
+            auto_default_after_secs: 0,
+        };
+        let (client, token_address, admin) = setup_overdue_line(&env, &borrower, &config);
+        env.ledger().with_mut(|li| li.timestamp = 1_251);
+        client.process_overdue(&keeper, &borrower);
+        client.pause(&admin, &true, &false);
+
+        let report = client.health_check();
+        let reserve = token::Client::new(&env, &token_address).balance(&client.address);
+        assert_eq!(report.reserve_balance, reserve);
+        assert_eq!(report.outstanding, 5_000);
+        assert_eq!(report.reserve_ratio_bps, reserve * 10_000 / 5_000);
+        assert!(!report.reserve_below_min);
+        assert!(report.draws_paused);
+        assert!(!report.repays_paused);
+        assert!(!report.oracle_configured);
+        assert!(!report.oracle_stale);
+        assert_eq!(report.delinquent_lines, 1);
+        assert!(report.instance_ttl_ledgers >= crate::ttl::LIFETIME_THRESHOLD);
+
+        client.repay_credit(&borrower, &5_000);
+        assert_eq!(client.health_check().delinquent_lines, 0);
     }
 
     #[test]
-    #[should_panic]
-    fn test_open_credit_line_unauthorized() {
+    fn test_reserve_breakdown_per_token() {
+        use soroban_sdk::testutils::Ledger;
         let env = Env::default();
-        let admin = Address::generate(&env);
+        env.mock_all_auths();
         let borrower = Address::generate(&env);
-        let token = Address::generate(&env);
-        let contract_id = env.register(Credit, ());
-        let client = CreditClient::new(&env, &contract_id);
+        let (client, token_address, admin) =
+            setup_contract_with_credit_line(&env, &borrower, 1_000, 1_000);
+        let (second_token, _) = setup_token(&env, &client.address, 800);
+        client.set_token_supported(&second_token, &true);
+        let other = Address::generate(&env);
+        client.open_credit_line(
+            &admin,
+            &other,
+            &500,
+            &300_u32,
+            &70_u32,
+            &None,
+            &RateMode::Fixed,
+            &Some(second_token.clone()),
+            &None,
+        );
+        client.draw_credit(&borrower, &500, &None);
+        client.draw_credit(&other, &200, &None);
 
-        client.init(&admin, &token);
-        // No mock_all_auths for admin
-        client.open_credit_line(&borrower, &1000, &300, &70);
+        let breakdown = client.get_reserve_breakdown();
+        assert_eq!(breakdown.len(), 2);
+        let (token, info) = breakdown.get(0).unwrap();
+        assert_eq!(token, token_address);
+        assert_eq!(
+            info,
+            ReserveInfo {
+                reserve_balance: 500,
+                outstanding: 500,
+                undrawn_commitments: 500,
+                utilization_bps: 5_000,
+                interest_earned: 0,
+            }
+        );
+        let (token, info) = breakdown.get(1).unwrap();
+        assert_eq!(token, second_token);
+        assert_eq!(info.reserve_balance, 600);
+        assert_eq!(info.outstanding, 200);
+        assert_eq!(info.utilization_bps, 2_500);
+
+        env.ledger()
+            .with_mut(|li| li.timestamp += math::SECONDS_PER_YEAR);
+        token::StellarAssetClient::new(&env, &second_token).mint(&other, &6);
+        client.repay_credit(&other, &206);
+        let (_, info) = client.get_reserve_breakdown().get(1).unwrap();
+        assert_eq!(info.outstanding, 0);
+        assert_eq!(info.utilization_bps, 0);
+        assert_eq!(info.interest_earned, 6);
+        assert_eq!(
+            client
+                .get_reserve_breakdown()
+                .get(0)
+                .unwrap()
+                .1
+                .interest_earned,
+            0
+        );
     }
 
     #[test]
-    fn test_get_nonexistent_credit_line() {
+    fn test_health_check_flags_stale_oracle_price() {
+        use soroban_sdk::testutils::Ledger;
         let env = Env::default();
+        env.mock_all_auths();
+        env.ledger().with_mut(|li| li.timestamp = 10_000);
         let borrower = Address::generate(&env);
-        let contract_id = env.register(Credit, ());
-        let client = CreditClient::new(&env, &contract_id);
-
-        assert!(client.get_credit_line(&borrower).is_none());
+        let (client, token_address, _admin) =
+            setup_contract_with_credit_line(&env, &borrower, 1_000, 0);
+        let oracle_id = env.register(mock_oracle::MockOracle, ());
+        let oracle = mock_oracle::MockOracleClient::new(&env, &oracle_id);
+        client.set_price_oracle(&oracle_id, &300);
+
+        let report = client.health_check();
+        assert!(report.oracle_configured);
+        assert_eq!(report.oracle_price_age_secs, None);
+        assert!(report.oracle_stale);
+
+        oracle.set_price(&token_address, &1_0000000, &9_800);
+        let report = client.health_check();
+        assert_eq!(report.oracle_price_age_secs, Some(200));
+        assert!(!report.oracle_stale);
+
+        env.ledger().with_mut(|li| li.timestamp = 10_200);
+        assert!(client.health_check().oracle_stale);
     }
 
+    // ── debt token view ───────────────────────────────────────────────────────
+
     #[test]
-    #[should_panic]
-    fn test_draw_credit_overflow() {
+    fn test_debt_token_balance_includes_accrued_interest() {
+        use soroban_sdk::testutils::Ledger;
         let env = Env::default();
         env.mock_all_auths();
-        let admin = Address::generate(&env);
         let borrower = Address::generate(&env);
-        let contract_id = env.register(Credit, ());
-        let (token_address, _) = setup_token(&env, &contract_id, 0);
-        let client = CreditClient::new(&env, &contract_id);
-
-        client.init(&admin, &token_address);
-        client.open_credit_line(&borrower, &i128::MAX, &300, &70);
-        client.draw_credit(&borrower, &i128::MAX);
-        client.draw_credit(&borrower, &1);
+        let (client, _token, _admin) =
+            setup_contract_with_credit_line(&env, &borrower, 1_000, 1_000);
+        client.draw_credit(&borrower, &500, &None);
+        assert_eq!(client.balance(&borrower), 500);
+        assert_eq!(client.total_supply(), 500);
+
+        env.ledger()
+            .with_mut(|li| li.timestamp += 365 * 24 * 60 * 60);
+        let interest = client.quote_interest(&borrower, &0);
+        assert!(interest > 0);
+        assert_eq!(client.balance(&borrower), 500 + interest);
+        assert_eq!(client.total_supply(), 500);
+        client.accrue_interest(&borrower);
+        assert_eq!(client.total_supply(), 500 + interest);
+
+        assert_eq!(client.balance(&Address::generate(&env)), 0);
+        assert_eq!(client.allowance(&borrower, &Address::generate(&env)), 0);
+        assert_eq!(client.decimals(), 7);
+        assert_eq!(client.symbol(), String::from_str(&env, "CRDEBT"));
     }
 
     #[test]
-    fn test_repay_credit_saturates_at_zero() {
+    fn test_debt_token_leaves_out_other_token_lines() {
+        use soroban_sdk::testutils::Ledger;
         let env = Env::default();
         env.mock_all_auths();
         let borrower = Address::generate(&env);
-        let (client, _token, _admin) =
+        let lender = Address::generate(&env);
+        let (client, _token, admin) =
             setup_contract_with_credit_line(&env, &borrower, 1_000, 1_000);
-        client.draw_credit(&borrower, &100);
-        client.repay_credit(&borrower, &500);
-        assert_eq!(
-            client.get_credit_line(&borrower).unwrap().utilized_amount,
-            0
+        client.draw_credit(&borrower, &500, &None);
+        let (second_token, second_sac) = setup_token(&env, &client.address, 0);
+        client.set_token_supported(&second_token, &true);
+        second_sac.mint(&lender, &800);
+        client.deposit_liquidity(&lender, &second_token, &800);
+        let other = Address::generate(&env);
+        client.open_credit_line(
+            &admin,
+            &other,
+            &500,
+            &300_u32,
+            &70_u32,
+            &None,
+            &RateMode::Fixed,
+            &Some(second_token),
+            &None,
         );
+
+        client.draw_credit(&other, &200, &None);
+        env.ledger()
+            .with_mut(|li| li.timestamp += 365 * 24 * 60 * 60);
+        client.accrue_interest(&other);
+        assert!(client.get_credit_line(&other).unwrap().accrued_interest > 0);
+        assert_eq!(client.balance(&other), 0);
+        assert_eq!(client.total_supply(), 500);
+        assert_eq!(client.get_protocol_stats().total_outstanding_principal, 700);
+
+        client.repay_credit(&other, &100);
+        assert_eq!(client.total_supply(), 500);
     }
 
     #[test]
-    #[should_panic(expected = "amount must be positive")]
-    fn test_repay_credit_rejects_non_positive_amount() {
+    #[should_panic(expected = "debt token is non-transferable")]
+    fn test_debt_token_transfer_reverts() {
         let env = Env::default();
         env.mock_all_auths();
         let borrower = Address::generate(&env);
-        let (client, _token, _admin) = setup_contract_with_credit_line(&env, &borrower, 1_000, 0);
-        client.repay_credit(&borrower, &0);
+        let (client, _token, _admin) =
+            setup_contract_with_credit_line(&env, &borrower, 1_000, 1_000);
+        client.draw_credit(&borrower, &500, &None);
+        client.transfer(&borrower, &Address::generate(&env), &100);
     }
 
+    // ── timelock ──────────────────────────────────────────────────────────────
+
     #[test]
-    #[should_panic(expected = "Credit line not found")]
-    fn test_repay_credit_nonexistent_line() {
+    fn test_timelocked_config_change_applies_after_delay() {
+        use soroban_sdk::testutils::{Events, Ledger};
+        use soroban_sdk::TryIntoVal;
         let env = Env::default();
         env.mock_all_auths();
-        let stranger = Address::generate(&env);
-        let admin = Address::generate(&env);
-        let contract_id = env.register(Credit, ());
-        let (token_address, _) = setup_token(&env, &contract_id, 0);
-        let client = CreditClient::new(&env, &contract_id);
-        client.init(&admin, &token_address);
-        client.repay_credit(&stranger, &100);
+        let borrower = Address::generate(&env);
+        let (client, _token, _admin) = setup_contract_with_credit_line(&env, &borrower, 1_000, 0);
+        env.ledger().with_mut(|li| li.timestamp = 1_000);
+        client.set_timelock_delay(&3_600);
+        let fees = FeeConfig {
+            draw_fee_bps: 25,
+            origination_fee: 0,
+        };
+        let action =
+            TimelockAction::Config(soroban_sdk::vec![&env, ConfigUpdate::Fees(fees.clone())]);
+
+        let action_id = client.queue_action(&action, &4_600);
+        let event: TimelockEvent = env
+            .events()
+            .all()
+            .last()
+            .unwrap()
+            .2
+            .try_into_val(&env)
+            .unwrap();
+        assert_eq!(event.action, action);
+        assert_eq!(event.eta, 4_600);
+        assert!(client.try_execute_action(&action_id).is_err());
+
+        env.ledger().with_mut(|li| li.timestamp = 4_600);
+        client.execute_action(&action_id);
+        assert_eq!(client.get_fee_config(), fees);
+        assert_eq!(client.get_queued_action(&action_id), None);
     }
 
     #[test]
-    #[should_panic(expected = "credit line is closed")]
-    fn test_repay_credit_rejected_when_closed() {
+    #[should_panic(expected = "change must be queued through the timelock")]
+    fn test_direct_setter_reverts_while_timelocked() {
         let env = Env::default();
         env.mock_all_auths();
         let borrower = Address::generate(&env);
-        let (client, _token, admin) = setup_contract_with_credit_line(&env, &borrower, 1_000, 0);
-        client.close_credit_line(&borrower, &admin);
-        client.repay_credit(&borrower, &100);
+        let (client, _token, _admin) = setup_contract_with_credit_line(&env, &borrower, 1_000, 0);
+        client.set_timelock_delay(&3_600);
+        client.set_reserve_factor_bps(&1_000);
     }
 
     #[test]
-    fn test_repay_credit_succeeds_when_suspended() {
+    #[should_panic(expected = "change must be queued through the timelock")]
+    fn test_set_tier_reverts_while_timelocked() {
         let env = Env::default();
         env.mock_all_auths();
         let borrower = Address::generate(&env);
-        let (client, _token, _admin) =
-            setup_contract_with_credit_line(&env, &borrower, 1_000, 1_000);
-
-        client.draw_credit(&borrower, &300);
-        client.suspend_credit_line(&borrower);
-
-        client.repay_credit(&borrower, &100);
-
-        let line = client.get_credit_line(&borrower).unwrap();
-        assert_eq!(line.status, CreditStatus::Suspended);
-        assert_eq!(line.utilized_amount, 200);
+        let (client, _token, _admin) = setup_contract_with_credit_line(&env, &borrower, 1_000, 0);
+        client.set_timelock_delay(&3_600);
+        client.set_tier(
+            &1,
+            &Tier {
+                max_limit: 1_000,
+                rate_bps: 300,
+                ltv_bps: 5_000,
+                draw_fee_bps: 0,
+            },
+        );
     }
 
-    // ── admin-only enforcement ────────────────────────────────────────────────
-
     #[test]
-    #[should_panic]
-    fn test_suspend_credit_line_unauthorized() {
+    #[should_panic(expected = "change must be queued through the timelock")]
+    fn test_set_repayment_terms_reverts_while_timelocked() {
         let env = Env::default();
+        env.mock_all_auths();
         let borrower = Address::generate(&env);
-        let admin = Address::generate(&env);
-        let contract_id = env.register(Credit, ());
-        let (token_address, _) = setup_token(&env, &contract_id, 0);
-        let client = CreditClient::new(&env, &contract_id);
-
-        client.init(&admin, &token_address);
-        client.open_credit_line(&borrower, &1_000, &300, &70);
-
-        // No mock_all_auths
-        client.suspend_credit_line(&borrower);
+        let (client, _token, _admin) = setup_contract_with_credit_line(&env, &borrower, 1_000, 0);
+        client.set_timelock_delay(&3_600);
+        client.set_repayment_terms(&client.get_repayment_terms());
     }
 
     #[test]
-    #[should_panic]
-    fn test_default_credit_line_unauthorized() {
+    #[should_panic(expected = "change must be queued through the timelock")]
+    fn test_set_staking_config_reverts_while_timelocked() {
         let env = Env::default();
+        env.mock_all_auths();
         let borrower = Address::generate(&env);
-        let admin = Address::generate(&env);
-        let contract_id = env.register(Credit, ());
-        let (token_address, _) = setup_token(&env, &contract_id, 0);
-        let client = CreditClient::new(&env, &contract_id);
-
-        client.init(&admin, &token_address);
-        client.open_credit_line(&borrower, &1_000, &300, &70);
-
-        // No mock_all_auths
-        client.default_credit_line(&borrower);
+        let (client, _token, _admin) = setup_contract_with_credit_line(&env, &borrower, 1_000, 0);
+        client.set_timelock_delay(&3_600);
+        client.set_staking_config(&client.get_staking_config());
     }
 
     #[test]
-    #[should_panic(expected = "Already initialized")]
-    fn test_init_twice() {
+    #[should_panic(expected = "change must be queued through the timelock")]
+    fn test_set_keeper_config_reverts_while_timelocked() {
         let env = Env::default();
-        let admin = Address::generate(&env);
-        let token = Address::generate(&env);
-        let contract_id = env.register(Credit, ());
-        let client = CreditClient::new(&env, &contract_id);
-
-        client.init(&admin, &token);
-        client.init(&admin, &token);
+        env.mock_all_auths();
+        let borrower = Address::generate(&env);
+        let (client, _token, _admin) = setup_contract_with_credit_line(&env, &borrower, 1_000, 0);
+        client.set_timelock_delay(&3_600);
+        client.set_keeper_config(&client.get_keeper_config());
     }
 
     #[test]
-    #[should_panic(expected = "Credit line not active")]
-    fn test_draw_credit_suspended() {
+    fn test_tier_change_goes_through_timelock() {
+        use soroban_sdk::testutils::Ledger;
         let env = Env::default();
         env.mock_all_auths();
-
-        let admin = Address::generate(&env);
         let borrower = Address::generate(&env);
-
-        let contract_id = env.register(Credit, ());
-        let client = CreditClient::new(&env, &contract_id);
-
-        let (token_address, _) = setup_token(&env, &contract_id, 0);
-        client.init(&admin, &token_address);
-        client.open_credit_line(&borrower, &1000_i128, &300_u32, &70_u32);
-        client.suspend_credit_line(&borrower);
-
-        client.draw_credit(&borrower, &100_i128);
+        let (client, _token, _admin) = setup_contract_with_credit_line(&env, &borrower, 1_000, 0);
+        env.ledger().with_mut(|li| li.timestamp = 1_000);
+        client.set_timelock_delay(&3_600);
+        let tier = Tier {
+            max_limit: 2_000,
+            rate_bps: 400,
+            ltv_bps: 5_000,
+            draw_fee_bps: 0,
+        };
+        let action =
+            TimelockAction::Config(soroban_sdk::vec![&env, ConfigUpdate::Tier(1, tier.clone())]);
+        let action_id = client.queue_action(&action, &4_600);
+        env.ledger().with_mut(|li| li.timestamp = 4_600);
+        client.execute_action(&action_id);
+        assert_eq!(client.get_tier(&1), Some(tier));
     }
 
-    // ── reentrancy guard ──────────────────────────────────────────────────────
-
     #[test]
-    fn test_reentrancy_guard_cleared_after_draw() {
+    fn test_collateral_and_oracle_setters_go_through_timelock() {
+        use soroban_sdk::testutils::Ledger;
         let env = Env::default();
         env.mock_all_auths();
         let borrower = Address::generate(&env);
-        let (client, _token, _admin) =
-            setup_contract_with_credit_line(&env, &borrower, 1_000, 1_000);
-        client.draw_credit(&borrower, &100);
-        client.draw_credit(&borrower, &100);
+        let (client, _token, _admin) = setup_contract_with_credit_line(&env, &borrower, 1_000, 0);
+        env.ledger().with_mut(|li| li.timestamp = 1_000);
+        client.set_timelock_delay(&3_600);
+        let oracle = Address::generate(&env);
+        let collateral_token = Address::generate(&env);
+
+        assert!(client
+            .try_set_insurance_config(&client.get_insurance_config())
+            .is_err());
+        assert!(client
+            .try_set_collateral_config(&client.get_collateral_config())
+            .is_err());
+        assert!(client
+            .try_set_collateral_token(&collateral_token, &true)
+            .is_err());
+        assert!(client
+            .try_set_liquidation_config(&client.get_liquidation_config())
+            .is_err());
+        assert!(client.try_set_price_oracle(&oracle, &300).is_err());
+
+        let price_oracle = OracleConfig {
+            oracle,
+            max_age_secs: 300,
+        };
+        let action = TimelockAction::Config(soroban_sdk::vec![
+            &env,
+            ConfigUpdate::PriceOracle(price_oracle.clone()),
+            ConfigUpdate::CollateralToken(collateral_token.clone(), true),
+        ]);
+        let action_id = client.queue_action(&action, &4_600);
+        env.ledger().with_mut(|li| li.timestamp = 4_600);
+        client.execute_action(&action_id);
+        assert_eq!(client.get_price_oracle(), Some(price_oracle));
         assert_eq!(
-            client.get_credit_line(&borrower).unwrap().utilized_amount,
-            200
+            client.get_collateral_tokens(),
+            soroban_sdk::vec![&env, collateral_token]
         );
     }
 
     #[test]
-    fn test_reentrancy_guard_cleared_after_repay() {
+    #[should_panic(expected = "eta is before the timelock delay")]
+    fn test_queue_before_delay_reverts() {
         let env = Env::default();
         env.mock_all_auths();
         let borrower = Address::generate(&env);
-        let (client, _token, _admin) =
-            setup_contract_with_credit_line(&env, &borrower, 1_000, 1_000);
-        client.draw_credit(&borrower, &200);
-        client.repay_credit(&borrower, &50);
-        client.repay_credit(&borrower, &50);
-        assert_eq!(
-            client.get_credit_line(&borrower).unwrap().utilized_amount,
-            100
-        );
+        let (client, _token, _admin) = setup_contract_with_credit_line(&env, &borrower, 1_000, 0);
+        client.set_timelock_delay(&3_600);
+        client.queue_action(&TimelockAction::SetDelay(0), &3_599);
     }
 
-    // ── event emission ────────────────────────────────────────────────────────
-
     #[test]
-    fn test_event_open_credit_line() {
-        use soroban_sdk::testutils::Events;
-        use soroban_sdk::{TryFromVal, TryIntoVal};
+    #[should_panic(expected = "queued action not found")]
+    fn test_cancelled_action_cannot_execute() {
         let env = Env::default();
         env.mock_all_auths();
         let borrower = Address::generate(&env);
         let (client, _token, _admin) = setup_contract_with_credit_line(&env, &borrower, 1_000, 0);
-        let _ = client;
-        let events = env.events().all();
-        let (_contract, topics, data) = events.last().unwrap();
-        assert_eq!(
-            Symbol::try_from_val(&env, &topics.get(1).unwrap()).unwrap(),
-            symbol_short!("opened")
-        );
-        let event_data: CreditLineEvent = data.try_into_val(&env).unwrap();
-        assert_eq!(event_data.status, CreditStatus::Active);
-        assert_eq!(event_data.borrower, borrower);
+        let action_id = client.queue_action(&TimelockAction::SetDelay(60), &0);
+        client.cancel_action(&action_id);
+        client.execute_action(&action_id);
     }
 
     #[test]
-    fn test_event_suspend_credit_line() {
-        use soroban_sdk::testutils::Events;
-        use soroban_sdk::{TryFromVal, TryIntoVal};
+    fn test_delay_changes_go_through_timelock() {
+        use soroban_sdk::testutils::Ledger;
         let env = Env::default();
         env.mock_all_auths();
         let borrower = Address::generate(&env);
         let (client, _token, _admin) = setup_contract_with_credit_line(&env, &borrower, 1_000, 0);
-        client.suspend_credit_line(&borrower);
-        let events = env.events().all();
-        let (_contract, topics, data) = events.last().unwrap();
-        assert_eq!(
-            Symbol::try_from_val(&env, &topics.get(1).unwrap()).unwrap(),
-            symbol_short!("suspend")
-        );
-        let event_data: CreditLineEvent = data.try_into_val(&env).unwrap();
-        assert_eq!(event_data.status, CreditStatus::Suspended);
+        client.set_timelock_delay(&3_600);
+        assert!(client.try_set_timelock_delay(&0).is_err());
+
+        let action_id = client.queue_action(&TimelockAction::SetDelay(0), &3_600);
+        env.ledger().with_mut(|li| li.timestamp = 3_600);
+        client.execute_action(&action_id);
+        assert_eq!(client.get_timelock_delay(), 0);
+        client.set_reserve_factor_bps(&1_000);
     }
 
     #[test]
-    fn test_event_close_credit_line() {
-        use soroban_sdk::testutils::Events;
-        use soroban_sdk::{TryFromVal, TryIntoVal};
+    fn test_limit_boost_raises_draw_limit_until_expiry() {
         let env = Env::default();
         env.mock_all_auths();
         let borrower = Address::generate(&env);
-        let (client, _token, admin) = setup_contract_with_credit_line(&env, &borrower, 1_000, 0);
-        client.close_credit_line(&borrower, &admin);
-        let events = env.events().all();
-        let (_contract, topics, data) = events.last().unwrap();
+        let (client, _token, admin) =
+            setup_contract_with_credit_line(&env, &borrower, 1_000, 2_000);
+        client.grant_limit_boost(&admin, &borrower, &500, &1_000);
+        let line = client.get_credit_line(&borrower).unwrap();
+        assert_eq!(line.limit_boost, 500);
+        assert_eq!(line.boost_expires_at, 1_000);
+
+        client.draw_credit(&borrower, &1_400, &None);
         assert_eq!(
-            Symbol::try_from_val(&env, &topics.get(1).unwrap()).unwrap(),
-            symbol_short!("closed")
+            client.get_credit_line(&borrower).unwrap().utilized_amount,
+            1_400
         );
-        let event_data: CreditLineEvent = data.try_into_val(&env).unwrap();
-        assert_eq!(event_data.status, CreditStatus::Closed);
     }
 
     #[test]
-    fn test_event_default_credit_line() {
-        use soroban_sdk::testutils::Events;
-        use soroban_sdk::{TryFromVal, TryIntoVal};
+    #[should_panic(expected = "exceeds credit limit")]
+    fn test_expired_limit_boost_is_ignored() {
+        use soroban_sdk::testutils::Ledger;
         let env = Env::default();
         env.mock_all_auths();
         let borrower = Address::generate(&env);
-        let (client, _token, _admin) = setup_contract_with_credit_line(&env, &borrower, 1_000, 0);
-        client.default_credit_line(&borrower);
-        let events = env.events().all();
-        let (_contract, topics, data) = events.last().unwrap();
-        assert_eq!(
-            Symbol::try_from_val(&env, &topics.get(1).unwrap()).unwrap(),
-            symbol_short!("default")
-        );
-        let event_data: CreditLineEvent = data.try_into_val(&env).unwrap();
-        assert_eq!(event_data.status, CreditStatus::Defaulted);
+        let (client, _token, admin) =
+            setup_contract_with_credit_line(&env, &borrower, 1_000, 2_000);
+        client.grant_limit_boost(&admin, &borrower, &500, &1_000);
+        env.ledger().with_mut(|li| li.timestamp = 1_000);
+        client.draw_credit(&borrower, &1_100, &None);
     }
 
     #[test]
-    fn test_event_lifecycle_sequence() {
-        use soroban_sdk::testutils::Events;
-        use soroban_sdk::{TryFromVal, TryIntoVal};
+    fn test_expired_limit_boost_cleared_on_draw() {
+        use soroban_sdk::testutils::{Events, Ledger};
+        use soroban_sdk::TryIntoVal;
         let env = Env::default();
         env.mock_all_auths();
         let borrower = Address::generate(&env);
-        let (client, _token, admin) = setup_contract_with_credit_line(&env, &borrower, 1_000, 0);
-        let open_data: CreditLineEvent = env
-            .events()
-            .all()
-            .last()
-            .unwrap()
-            .2
-            .try_into_val(&env)
-            .unwrap();
-        assert_eq!(open_data.status, CreditStatus::Active);
+        let (client, _token, admin) =
+            setup_contract_with_credit_line(&env, &borrower, 1_000, 2_000);
+        client.grant_limit_boost(&admin, &borrower, &500, &1_000);
+        env.ledger().with_mut(|li| li.timestamp = 1_500);
 
-        client.suspend_credit_line(&borrower);
-        let suspend_data: CreditLineEvent = env
+        client.draw_credit(&borrower, &100, &None);
+        let expired = env
             .events()
             .all()
-            .last()
-            .unwrap()
-            .2
-            .try_into_val(&env)
+            .iter()
+            .find_map(|(_contract, _topics, data)| {
+                TryIntoVal::<Env, LimitBoostEvent>::try_into_val(&data, &env).ok()
+            })
             .unwrap();
-        assert_eq!(suspend_data.status, CreditStatus::Suspended);
+        assert!(expired.expired);
+        assert_eq!(expired.amount, 500);
+        assert_eq!(expired.expires_at, 1_000);
+        let line = client.get_credit_line(&borrower).unwrap();
+        assert_eq!(line.limit_boost, 0);
+        assert_eq!(line.boost_expires_at, 0);
+    }
+
+    #[test]
+    #[should_panic(expected = "unauthorized")]
+    fn test_grant_limit_boost_requires_risk_engine() {
+        let env = Env::default();
+        env.mock_all_auths();
+        let borrower = Address::generate(&env);
+        let (client, _token, _admin) = setup_contract_with_credit_line(&env, &borrower, 1_000, 0);
+        client.grant_limit_boost(&Address::generate(&env), &borrower, &500, &1_000);
+    }
+
+    #[test]
+    fn test_math_mul_div_rounding() {
+        use math::{mul_div, Rounding};
+        assert_eq!(mul_div(10, 3, 4, Rounding::Down), Ok(7));
+        assert_eq!(mul_div(10, 3, 4, Rounding::Up), Ok(8));
+        assert_eq!(mul_div(12, 3, 4, Rounding::Up), Ok(9));
+        assert_eq!(mul_div(0, 3, 4, Rounding::Up), Ok(0));
+    }
+
+    #[test]
+    fn test_math_reports_overflow() {
+        use math::{apply_bps, mul_div, per_second_interest, Rounding, SECONDS_PER_YEAR};
         assert_eq!(
-            Symbol::try_from_val(&env, &env.events().all().last().unwrap().1.get(1).unwrap())
-                .unwrap(),
-            symbol_short!("suspend")
+            mul_div(i128::MAX, 2, 2, Rounding::Down),
+            Err(ContractError::Overflow)
+        );
+        assert_eq!(
+            mul_div(1, 1, 0, Rounding::Down),
+            Err(ContractError::Overflow)
+        );
+        assert_eq!(
+            apply_bps(i128::MAX, 10_000, Rounding::Down),
+            Err(ContractError::Overflow)
+        );
+        assert_eq!(
+            per_second_interest(i128::MAX / 2, 1_000, 1, SECONDS_PER_YEAR, Rounding::Down),
+            Err(ContractError::Overflow)
         );
+        assert_eq!(math::add(i128::MAX, 1), Err(ContractError::Overflow));
+    }
 
-        client.close_credit_line(&borrower, &admin);
-        let close_data: CreditLineEvent = env
-            .events()
-            .all()
-            .last()
-            .unwrap()
-            .2
-            .try_into_val(&env)
-            .unwrap();
-        assert_eq!(close_data.status, CreditStatus::Closed);
+    #[test]
+    fn test_math_bps_and_per_second_interest() {
+        use math::{apply_bps, per_second_interest, to_bps, Rounding, SECONDS_PER_YEAR};
+        assert_eq!(apply_bps(1_001, 250, Rounding::Down), Ok(25));
+        assert_eq!(apply_bps(1_001, 250, Rounding::Up), Ok(26));
+        assert_eq!(to_bps(3, 2, Rounding::Down), Ok(15_000));
+        assert_eq!(
+            per_second_interest(
+                10_000,
+                1_000,
+                SECONDS_PER_YEAR,
+                SECONDS_PER_YEAR,
+                Rounding::Down
+            ),
+            Ok(1_000)
+        );
+        assert_eq!(
+            per_second_interest(
+                10_000,
+                1_000,
+                SECONDS_PER_YEAR / 2,
+                SECONDS_PER_YEAR,
+                Rounding::Down
+            ),
+            Ok(500)
+        );
     }
 }
 
@@ -1396,7 +14692,17 @@ mod test_close_utilized {
         }
         let client = CreditClient::new(env, &contract_id);
         client.init(&admin, &token_address);
-        client.open_credit_line(borrower, &credit_limit, &300_u32, &70_u32);
+        client.open_credit_line(
+            &admin,
+            borrower,
+            &credit_limit,
+            &300_u32,
+            &70_u32,
+            &None,
+            &RateMode::Fixed,
+            &None,
+            &None,
+        );
         (client, admin)
     }
 
@@ -1407,7 +14713,7 @@ mod test_close_utilized {
         env.mock_all_auths();
         let borrower = Address::generate(&env);
         let (client, _admin) = setup(&env, &borrower, 1_000, 1_000);
-        client.draw_credit(&borrower, &1);
+        client.draw_credit(&borrower, &1, &None);
         client.close_credit_line(&borrower, &borrower);
     }
 
@@ -1418,7 +14724,7 @@ mod test_close_utilized {
         env.mock_all_auths();
         let borrower = Address::generate(&env);
         let (client, _admin) = setup(&env, &borrower, 500, 500);
-        client.draw_credit(&borrower, &500);
+        client.draw_credit(&borrower, &500, &None);
         client.close_credit_line(&borrower, &borrower);
     }
 
@@ -1428,7 +14734,7 @@ mod test_close_utilized {
         env.mock_all_auths();
         let borrower = Address::generate(&env);
         let (client, admin) = setup(&env, &borrower, 1_000, 1_000);
-        client.draw_credit(&borrower, &750);
+        client.draw_credit(&borrower, &750, &None);
         client.close_credit_line(&borrower, &admin);
         let line = client.get_credit_line(&borrower).unwrap();
         assert_eq!(line.status, CreditStatus::Closed);
@@ -1441,7 +14747,7 @@ mod test_close_utilized {
         env.mock_all_auths();
         let borrower = Address::generate(&env);
         let (client, admin) = setup(&env, &borrower, 1_000, 1_000);
-        client.draw_credit(&borrower, &400);
+        client.draw_credit(&borrower, &400, &None);
         client.close_credit_line(&borrower, &admin);
         // Verify state: status is Closed and utilized_amount is preserved.
         // Event payload correctness is covered by test::test_event_close_credit_line.
@@ -1457,8 +14763,8 @@ mod test_close_utilized {
         env.mock_all_auths();
         let borrower = Address::generate(&env);
         let (client, _admin) = setup(&env, &borrower, 1_000, 1_000);
-        client.draw_credit(&borrower, &200);
-        client.suspend_credit_line(&borrower);
+        client.draw_credit(&borrower, &200, &None);
+        client.suspend_credit_line(&borrower, &0, &None);
         client.close_credit_line(&borrower, &borrower);
     }
 
@@ -1468,8 +14774,8 @@ mod test_close_utilized {
         env.mock_all_auths();
         let borrower = Address::generate(&env);
         let (client, admin) = setup(&env, &borrower, 1_000, 1_000);
-        client.draw_credit(&borrower, &600);
-        client.suspend_credit_line(&borrower);
+        client.draw_credit(&borrower, &600, &None);
+        client.suspend_credit_line(&borrower, &0, &None);
         client.close_credit_line(&borrower, &admin);
         let line = client.get_credit_line(&borrower).unwrap();
         assert_eq!(line.status, CreditStatus::Closed);
@@ -1482,7 +14788,7 @@ mod test_close_utilized {
         env.mock_all_auths();
         let borrower = Address::generate(&env);
         let (client, _admin) = setup(&env, &borrower, 1_000, 1_000);
-        client.draw_credit(&borrower, &350);
+        client.draw_credit(&borrower, &350, &None);
         client.repay_credit(&borrower, &350);
         assert_eq!(
             client.get_credit_line(&borrower).unwrap().utilized_amount,
@@ -1512,9 +14818,9 @@ mod test_close_utilized {
         env.mock_all_auths();
         let borrower = Address::generate(&env);
         let (client, admin) = setup(&env, &borrower, 1_000, 1_000);
-        client.draw_credit(&borrower, &100);
-        client.draw_credit(&borrower, &150);
-        client.draw_credit(&borrower, &250);
+        client.draw_credit(&borrower, &100, &None);
+        client.draw_credit(&borrower, &150, &None);
+        client.draw_credit(&borrower, &250, &None);
         assert_eq!(
             client.get_credit_line(&borrower).unwrap().utilized_amount,
             500
@@ -1532,7 +14838,7 @@ mod test_close_utilized {
         env.mock_all_auths();
         let borrower = Address::generate(&env);
         let (client, _admin) = setup(&env, &borrower, 1_000, 1_000);
-        client.draw_credit(&borrower, &400);
+        client.draw_credit(&borrower, &400, &None);
         client.repay_credit(&borrower, &200);
         assert_eq!(
             client.get_credit_line(&borrower).unwrap().utilized_amount,
@@ -0,0 +1,134 @@
+//! Liquidation of defaulted collateralized credit lines.
+//!
+//! Collateral worth the outstanding debt (plus the keeper bonus) is seized from
+//! a Defaulted line. The bonus goes to the liquidator when liquidation is open
+//! to keepers; the rest goes to the collector that bought the debt, else to the
+//! auction address if one is configured, and otherwise stays in the contract
+//! reserve. The recovered value reduces the
+//! line's utilized amount; whatever is left is bad debt.
+
+use soroban_sdk::{contracttype, token, Address, Env, Symbol};
+
+use crate::collateral;
+use crate::math::{self, mul_div, Rounding, BPS_DENOMINATOR};
+use crate::storage::{self, DataKey};
+use crate::types::{CreditLineData, CreditStatus};
+
+/// Liquidation policy (admin-configured).
+#[contracttype]
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct LiquidationConfig {
+    /// When true anyone may liquidate; otherwise only the admin.
+    pub open_to_keepers: bool,
+    /// Share of seized collateral paid to a keeper liquidator, in basis points.
+    pub keeper_bonus_bps: u32,
+    /// Destination for seized collateral; `None` keeps it in the contract reserve.
+    pub auction_address: Option<Address>,
+}
+
+/// Amounts moved by a single liquidation.
+pub struct LiquidationOutcome {
+    pub collateral_token: Address,
+    pub seized_amount: i128,
+    pub keeper_bonus: i128,
+    pub recovered_value: i128,
+}
+
+/// Instance storage key for liquidation config.
+fn liquidation_config_key(env: &Env) -> DataKey {
+    DataKey::Config(Symbol::new(env, "liq_cfg"))
+}
+
+pub fn get_config(env: &Env) -> LiquidationConfig {
+    storage::get_instance(env, &liquidation_config_key(env)).unwrap_or_default()
+}
+
+/// # Panics
+/// * `"keeper_bonus_bps cannot exceed 10000"`
+pub fn set_config(env: &Env, config: &LiquidationConfig) {
+    if config.keeper_bonus_bps > 10_000 {
+        panic!("keeper_bonus_bps cannot exceed 10000");
+    }
+    storage::set_instance(env, &liquidation_config_key(env), config);
+}
+
+/// Seize collateral from `line`, pay the bonus to `liquidator` (if `pay_bonus`),
+/// and apply the recovered value to the line's debt.
+///
+/// # Panics
+/// * `"credit line not defaulted"`
+/// * `"no collateral to liquidate"`
+/// * `"nothing to liquidate"` – line has no outstanding debt
+/// * `"collateral has no price"`
+pub fn liquidate(
+    env: &Env,
+    line: &mut CreditLineData,
+    liquidator: &Address,
+    pay_bonus: bool,
+) -> LiquidationOutcome {
+    if line.status != CreditStatus::Defaulted {
+        panic!("credit line not defaulted");
+    }
+    let collateral_token = match (&line.collateral_token, line.collateral_amount > 0) {
+        (Some(token), true) => token.clone(),
+        _ => panic!("no collateral to liquidate"),
+    };
+    if line.utilized_amount <= 0 {
+        panic!("nothing to liquidate");
+    }
+
+    let config = get_config(env);
+    let bonus_bps = if pay_bonus {
+        config.keeper_bonus_bps as i128
+    } else {
+        0
+    };
+
+    // Seize enough to cover the debt plus the bonus carved out of it.
+    let debt_collateral = collateral::amount_for_value(env, line, line.utilized_amount);
+    let gross = math::checked(
+        env,
+        mul_div(
+            debt_collateral,
+            BPS_DENOMINATOR + bonus_bps,
+            BPS_DENOMINATOR,
+            Rounding::Down,
+        ),
+    );
+    let seized_amount = gross.min(line.collateral_amount);
+    let keeper_bonus = math::checked(
+        env,
+        mul_div(
+            seized_amount,
+            bonus_bps,
+            BPS_DENOMINATOR + bonus_bps,
+            Rounding::Down,
+        ),
+    );
+    let recovered_value =
+        collateral::value_of(env, line, seized_amount - keeper_bonus).min(line.utilized_amount);
+
+    line.collateral_amount -= seized_amount;
+    if line.collateral_amount == 0 {
+        line.collateral_token = None;
+    }
+    let previous_utilized = line.utilized_amount;
+    line.utilized_amount -= recovered_value;
+    crate::fx::apply_principal_reduction(env, line, previous_utilized);
+
+    let token_client = token::Client::new(env, &collateral_token);
+    let contract = env.current_contract_address();
+    if keeper_bonus > 0 {
+        token_client.transfer(&contract, liquidator, &keeper_bonus);
+    }
+    if let Some(destination) = line.creditor.as_ref().or(config.auction_address.as_ref()) {
+        token_client.transfer(&contract, destination, &(seized_amount - keeper_bonus));
+    }
+
+    LiquidationOutcome {
+        collateral_token,
+        seized_amount,
+        keeper_bonus,
+        recovered_value,
+    }
+}
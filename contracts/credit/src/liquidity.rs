@@ -0,0 +1,295 @@
+//! Lender-facing liquidity: a share-based pool per supported token.
+//!
+//! Deposits mint shares in proportion to the pool's assets, so interest repaid
+//! by borrowers (added to `PoolAssets`) raises the share price and lenders earn
+//! yield, ERC4626-style. Withdrawals burn shares and must leave the token's
+//! reserve covering the undrawn credit committed to Active lines denominated in
+//! it. Those commitment totals are maintained incrementally on every credit
+//! line write (see `track_commitment`).
+
+use soroban_sdk::{contracttype, token, Address, Env};
+
+use crate::math::{self, mul_div, Rounding};
+use crate::storage::{self, DataKey};
+use crate::types::{CreditLineData, CreditStatus};
+
+/// Fixed-point scale for `share_price` (7 decimals, matching Stellar assets).
+pub const SHARE_PRICE_SCALE: i128 = 10_000_000;
+
+#[contracttype]
+#[derive(Clone)]
+pub enum LiquidityKey {
+    /// Shares of a token's pool held by a lender (persistent).
+    Shares(Address, Address),
+    /// Total shares outstanding in a token's pool (instance).
+    TotalShares(Address),
+    /// Assets owned by a token's pool: deposits plus interest, less withdrawals (instance).
+    PoolAssets(Address),
+    /// Undrawn credit committed to Active lines in a token (instance).
+    Commitments(Address),
+    /// Principal outstanding on lines in a token (instance).
+    Outstanding(Address),
+}
+
+fn get_instance(env: &Env, key: &DataKey) -> i128 {
+    storage::get_instance(env, key).unwrap_or(0)
+}
+
+pub fn shares_of(env: &Env, token: &Address, lender: &Address) -> i128 {
+    storage::get_persistent(
+        env,
+        &DataKey::Liquidity(LiquidityKey::Shares(token.clone(), lender.clone())),
+    )
+    .unwrap_or(0)
+}
+
+pub fn total_shares(env: &Env, token: &Address) -> i128 {
+    get_instance(
+        env,
+        &DataKey::Liquidity(LiquidityKey::TotalShares(token.clone())),
+    )
+}
+
+pub fn pool_assets(env: &Env, token: &Address) -> i128 {
+    get_instance(
+        env,
+        &DataKey::Liquidity(LiquidityKey::PoolAssets(token.clone())),
+    )
+}
+
+/// Undrawn commitments plus uncollected protocol fees and the insurance fund:
+/// the part of the reserve lenders cannot withdraw.
+pub fn reserved(env: &Env, token: &Address) -> i128 {
+    math::checked(
+        env,
+        math::add(
+            undrawn_commitments(env, token),
+            crate::treasury::accrued_fees(env, token),
+        )
+        .and_then(|r| math::add(r, crate::insurance::fund_balance(env, token))),
+    )
+}
+
+pub fn undrawn_commitments(env: &Env, token: &Address) -> i128 {
+    get_instance(
+        env,
+        &DataKey::Liquidity(LiquidityKey::Commitments(token.clone())),
+    )
+}
+
+pub fn outstanding(env: &Env, token: &Address) -> i128 {
+    get_instance(
+        env,
+        &DataKey::Liquidity(LiquidityKey::Outstanding(token.clone())),
+    )
+}
+
+/// Shares minted for depositing `amount` (rounded down).
+pub fn convert_to_shares(env: &Env, token: &Address, amount: i128) -> i128 {
+    let shares = total_shares(env, token);
+    let assets = pool_assets(env, token);
+    if shares == 0 || assets == 0 {
+        return amount;
+    }
+    math::checked(env, mul_div(amount, shares, assets, Rounding::Down))
+}
+
+/// Assets redeemable for `shares` (rounded down).
+pub fn convert_to_assets(env: &Env, token: &Address, shares: i128) -> i128 {
+    let total = total_shares(env, token);
+    if total == 0 {
+        return shares;
+    }
+    math::checked(
+        env,
+        mul_div(shares, pool_assets(env, token), total, Rounding::Down),
+    )
+}
+
+/// Pool assets per share, scaled by `SHARE_PRICE_SCALE`.
+pub fn share_price(env: &Env, token: &Address) -> i128 {
+    let total = total_shares(env, token);
+    if total == 0 {
+        return SHARE_PRICE_SCALE;
+    }
+    math::checked(
+        env,
+        mul_div(
+            pool_assets(env, token),
+            SHARE_PRICE_SCALE,
+            total,
+            Rounding::Down,
+        ),
+    )
+}
+
+/// Credit interest collected from borrowers to the pool. Ignored while the pool
+/// has no lenders; the interest then stays in the reserve unattributed.
+pub fn accrue_to_pool(env: &Env, token: &Address, interest: i128) {
+    if interest <= 0 || total_shares(env, token) == 0 {
+        return;
+    }
+    let assets = math::checked(env, math::add(pool_assets(env, token), interest));
+    storage::set_instance(
+        env,
+        &DataKey::Liquidity(LiquidityKey::PoolAssets(token.clone())),
+        &assets,
+    );
+}
+
+/// Charge written-off principal against the pool, lowering the share price.
+pub fn realize_loss(env: &Env, token: &Address, principal: i128) {
+    if principal <= 0 || total_shares(env, token) == 0 {
+        return;
+    }
+    let assets = (pool_assets(env, token) - principal).max(0);
+    storage::set_instance(
+        env,
+        &DataKey::Liquidity(LiquidityKey::PoolAssets(token.clone())),
+        &assets,
+    );
+}
+
+/// Undrawn credit the contract is committed to fund for `line`.
+fn commitment_of(line: &CreditLineData) -> i128 {
+    if line.status == CreditStatus::Active {
+        (line.credit_limit - line.utilized_amount).max(0)
+    } else {
+        0
+    }
+}
+
+/// Principal the pool has lent out on `line`; an assigned line is owed to its collector.
+fn outstanding_of(line: &CreditLineData) -> i128 {
+    if line.creditor.is_none() {
+        line.utilized_amount
+    } else {
+        0
+    }
+}
+
+fn adjust(env: &Env, key: DataKey, delta: i128) {
+    if delta != 0 {
+        let total = math::checked(env, math::add(get_instance(env, &key), delta));
+        storage::set_instance(env, &key, &total);
+    }
+}
+
+/// Adjust the commitment and outstanding totals for a credit line moving from
+/// `before` to `after`.
+pub fn track_commitment(env: &Env, before: Option<&CreditLineData>, after: &CreditLineData) {
+    if let Some(before) = before {
+        adjust(
+            env,
+            DataKey::Liquidity(LiquidityKey::Commitments(before.token.clone())),
+            -commitment_of(before),
+        );
+        adjust(
+            env,
+            DataKey::Liquidity(LiquidityKey::Outstanding(before.token.clone())),
+            -outstanding_of(before),
+        );
+    }
+    adjust(
+        env,
+        DataKey::Liquidity(LiquidityKey::Commitments(after.token.clone())),
+        commitment_of(after),
+    );
+    adjust(
+        env,
+        DataKey::Liquidity(LiquidityKey::Outstanding(after.token.clone())),
+        outstanding_of(after),
+    );
+}
+
+fn set_position(
+    env: &Env,
+    token: &Address,
+    lender: &Address,
+    shares: i128,
+    total: i128,
+    assets: i128,
+) {
+    let key = DataKey::Liquidity(LiquidityKey::Shares(token.clone(), lender.clone()));
+    if shares == 0 {
+        storage::remove_persistent(env, &key);
+    } else {
+        storage::set_persistent(env, &key, &shares);
+    }
+    storage::set_instance(
+        env,
+        &DataKey::Liquidity(LiquidityKey::TotalShares(token.clone())),
+        &total,
+    );
+    storage::set_instance(
+        env,
+        &DataKey::Liquidity(LiquidityKey::PoolAssets(token.clone())),
+        &assets,
+    );
+}
+
+/// Pull `amount` of `token` from `lender` and mint shares in its pool.
+/// Returns the shares minted.
+///
+/// # Panics
+/// * `"amount must be positive"`
+/// * `"deposit too small"` – amount would mint zero shares
+pub fn deposit(env: &Env, token: &Address, lender: &Address, amount: i128) -> i128 {
+    if amount <= 0 {
+        panic!("amount must be positive");
+    }
+    let minted = convert_to_shares(env, token, amount);
+    if minted <= 0 {
+        panic!("deposit too small");
+    }
+    token::Client::new(env, token).transfer(lender, &env.current_contract_address(), &amount);
+    set_position(
+        env,
+        token,
+        lender,
+        math::checked(env, math::add(shares_of(env, token, lender), minted)),
+        math::checked(env, math::add(total_shares(env, token), minted)),
+        math::checked(env, math::add(pool_assets(env, token), amount)),
+    );
+    minted
+}
+
+/// Burn the shares worth `amount` (rounded up) and send `amount` of `token` to
+/// `lender`. Returns the shares burned.
+///
+/// # Panics
+/// * `"amount must be positive"`
+/// * `"insufficient shares"` – amount exceeds the lender's share value
+/// * `"withdrawal exceeds free liquidity"` – reserve would drop below undrawn commitments
+///   plus uncollected protocol fees
+pub fn withdraw(env: &Env, token: &Address, lender: &Address, amount: i128) -> i128 {
+    if amount <= 0 {
+        panic!("amount must be positive");
+    }
+    let total = total_shares(env, token);
+    let assets = pool_assets(env, token);
+    if total == 0 || assets == 0 {
+        panic!("insufficient shares");
+    }
+    let burned = math::checked(env, mul_div(amount, total, assets, Rounding::Up));
+    let held = shares_of(env, token, lender);
+    if burned > held {
+        panic!("insufficient shares");
+    }
+
+    let token_client = token::Client::new(env, token);
+    let contract = env.current_contract_address();
+    if token_client.balance(&contract) - amount < reserved(env, token) {
+        panic!("withdrawal exceeds free liquidity");
+    }
+    token_client.transfer(&contract, lender, &amount);
+    set_position(
+        env,
+        token,
+        lender,
+        held - burned,
+        total - burned,
+        assets - amount,
+    );
+    burned
+}
@@ -0,0 +1,128 @@
+//! Overflow-checked fixed-point arithmetic on `i128`.
+//!
+//! Basis-point, price, share, and rate math all reduce to `a * b / denominator`
+//! with an explicit rounding direction. The helpers here return
+//! `ContractError::Overflow` instead of wrapping or panicking, so pure
+//! functions can propagate it and entrypoints can revert with the typed error
+//! through `checked`.
+
+use soroban_sdk::{panic_with_error, Env};
+
+use crate::errors::ContractError;
+
+/// 100% in basis points.
+pub const BPS_DENOMINATOR: i128 = 10_000;
+pub const SECONDS_PER_DAY: u64 = 24 * 60 * 60;
+pub const SECONDS_PER_YEAR: u64 = 365 * SECONDS_PER_DAY;
+/// Fixed-point scale for per-period rates and growth factors: 1.0 is `RATE_SCALE`.
+/// Small enough that squaring a factor below ~10^7 cannot overflow `i128`.
+pub const RATE_SCALE: i128 = 1_000_000_000_000;
+
+/// Direction to round a quotient in.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Rounding {
+    /// Toward zero; the default for amounts paid out or credited.
+    Down,
+    /// Away from zero; for amounts owed or burned, so rounding never favours the caller.
+    Up,
+}
+
+/// `a * b / denominator`, rounded as requested.
+///
+/// Fails with `Overflow` if the product overflows or `denominator` is not
+/// positive.
+pub fn mul_div(
+    a: i128,
+    b: i128,
+    denominator: i128,
+    rounding: Rounding,
+) -> Result<i128, ContractError> {
+    if denominator <= 0 {
+        return Err(ContractError::Overflow);
+    }
+    let product = a.checked_mul(b).ok_or(ContractError::Overflow)?;
+    let quotient = product / denominator;
+    let remainder = product % denominator;
+    match rounding {
+        Rounding::Up if remainder > 0 => quotient.checked_add(1).ok_or(ContractError::Overflow),
+        _ => Ok(quotient),
+    }
+}
+
+/// `bps` basis points of `amount`.
+pub fn apply_bps(amount: i128, bps: u32, rounding: Rounding) -> Result<i128, ContractError> {
+    mul_div(amount, bps as i128, BPS_DENOMINATOR, rounding)
+}
+
+/// `numerator / denominator` expressed in basis points.
+pub fn to_bps(
+    numerator: i128,
+    denominator: i128,
+    rounding: Rounding,
+) -> Result<i128, ContractError> {
+    mul_div(numerator, BPS_DENOMINATOR, denominator, rounding)
+}
+
+/// Interest on `principal` at `annual_rate_bps`, charged per second over
+/// `elapsed_secs` of a `year_secs`-long year.
+pub fn per_second_interest(
+    principal: i128,
+    annual_rate_bps: u32,
+    elapsed_secs: u64,
+    year_secs: u64,
+    rounding: Rounding,
+) -> Result<i128, ContractError> {
+    let rate_time = (annual_rate_bps as i128)
+        .checked_mul(elapsed_secs as i128)
+        .ok_or(ContractError::Overflow)?;
+    mul_div(
+        principal,
+        rate_time,
+        BPS_DENOMINATOR * year_secs as i128,
+        rounding,
+    )
+}
+
+/// `annual_rate_bps` converted to the rate for one `period_secs` period,
+/// scaled by `RATE_SCALE` (rounded down).
+pub fn periodic_rate(
+    annual_rate_bps: u32,
+    period_secs: u64,
+    year_secs: u64,
+) -> Result<i128, ContractError> {
+    per_second_interest(
+        RATE_SCALE,
+        annual_rate_bps,
+        period_secs,
+        year_secs,
+        Rounding::Down,
+    )
+}
+
+/// `factor ^ exponent` for a `RATE_SCALE`-scaled `factor`, by repeated squaring.
+/// Each step rounds down.
+pub fn pow_scaled(factor: i128, exponent: u64) -> Result<i128, ContractError> {
+    let mut result = RATE_SCALE;
+    let mut base = factor;
+    let mut remaining = exponent;
+    while remaining > 0 {
+        if remaining & 1 == 1 {
+            result = mul_div(result, base, RATE_SCALE, Rounding::Down)?;
+        }
+        remaining >>= 1;
+        if remaining > 0 {
+            base = mul_div(base, base, RATE_SCALE, Rounding::Down)?;
+        }
+    }
+    Ok(result)
+}
+
+/// `a + b`, failing with `Overflow`.
+pub fn add(a: i128, b: i128) -> Result<i128, ContractError> {
+    a.checked_add(b).ok_or(ContractError::Overflow)
+}
+
+/// Unwrap a math result, reverting with its `ContractError`.
+pub fn checked<T>(env: &Env, result: Result<T, ContractError>) -> T {
+    result.unwrap_or_else(|error| panic_with_error!(env, error))
+}
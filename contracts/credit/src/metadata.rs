@@ -0,0 +1,44 @@
+//! Metadata hash tying a line to its off-chain credit agreement.
+//!
+//! The risk engine records a 32-byte hash, e.g. of the signed credit agreement,
+//! against a line, so the on-chain line can be matched to its legal documents.
+//! Each change is published with the previous hash, giving an event trail of
+//! every agreement the line has been tied to. The hash is kept beside the line,
+//! not in `CreditLineData`, which is at the 40-field limit for contract types.
+//! It follows the line when the line is transferred.
+
+use soroban_sdk::{contracttype, Address, BytesN, Env};
+
+use crate::storage::{self, DataKey};
+
+#[contracttype]
+#[derive(Clone)]
+pub enum MetadataKey {
+    /// Metadata hash of a borrower's line (persistent).
+    Hash(Address),
+}
+
+fn hash_key(borrower: &Address) -> DataKey {
+    DataKey::Metadata(MetadataKey::Hash(borrower.clone()))
+}
+
+pub fn get(env: &Env, borrower: &Address) -> Option<BytesN<32>> {
+    storage::get_persistent(env, &hash_key(borrower))
+}
+
+/// Set or, with `None`, clear `borrower`'s hash. Returns the previous one.
+pub fn set(env: &Env, borrower: &Address, hash: &Option<BytesN<32>>) -> Option<BytesN<32>> {
+    let previous = get(env, borrower);
+    match hash {
+        Some(hash) => storage::set_persistent(env, &hash_key(borrower), hash),
+        None => storage::remove_persistent(env, &hash_key(borrower)),
+    }
+    previous
+}
+
+pub fn transfer(env: &Env, from: &Address, to: &Address) {
+    if let Some(hash) = get(env, from) {
+        storage::remove_persistent(env, &hash_key(from));
+        storage::set_persistent(env, &hash_key(to), &hash);
+    }
+}
@@ -0,0 +1,137 @@
+//! Single-call health report for off-chain monitoring.
+//!
+//! Monitors simulate `health_check` and alert on the fields below instead of
+//! stitching many reads together. Reserve figures are for the default token.
+//! The report never reverts on a misbehaving oracle: a failed price read shows
+//! as a missing price.
+//!
+//! `breakdown` reports each supported token's reserve side by side. Apart
+//! from the token balances, its figures are running totals kept on every line
+//! write and repayment, so the view reads a few instance entries per token.
+
+use soroban_sdk::{contracttype, token, Address, Env, Vec};
+
+use crate::delinquency;
+use crate::liquidity;
+use crate::math::{self, Rounding};
+use crate::oracle::{self, Asset, PriceOracleClient};
+use crate::pause;
+use crate::reserve;
+use crate::revenue;
+use crate::tokens;
+use crate::ttl;
+
+/// Protocol health at the current ledger.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct HealthReport {
+    /// Contract balance of the default token.
+    pub reserve_balance: i128,
+    /// Principal outstanding on lines in the default token.
+    pub outstanding: i128,
+    /// `reserve_balance` over `outstanding`; `i128::MAX` when nothing is outstanding.
+    pub reserve_ratio_bps: i128,
+    /// True while the ratio is below `ReserveConfig::min_reserve_ratio_bps`.
+    pub reserve_below_min: bool,
+    pub draws_paused: bool,
+    pub repays_paused: bool,
+    pub oracle_configured: bool,
+    /// Age of the oracle's default token price; `None` without a price.
+    pub oracle_price_age_secs: Option<u64>,
+    /// True if an oracle is configured and its price is missing or older than
+    /// `OracleConfig::max_age_secs`.
+    pub oracle_stale: bool,
+    /// Lines currently Delinquent.
+    pub delinquent_lines: u32,
+    /// Ledgers the contract instance is known to live for at least.
+    pub instance_ttl_ledgers: u32,
+}
+
+/// Reserve figures for one token.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ReserveInfo {
+    /// Contract balance of the token.
+    pub reserve_balance: i128,
+    /// Principal outstanding on lines in the token.
+    pub outstanding: i128,
+    /// Undrawn credit committed to Active lines in the token.
+    pub undrawn_commitments: i128,
+    /// `outstanding` over `outstanding` plus `reserve_balance`, in basis points.
+    pub utilization_bps: u32,
+    /// Interest repaid in the token and distributed since the revenue split was introduced.
+    pub interest_earned: i128,
+}
+
+pub fn reserve_info(env: &Env, token: &Address) -> ReserveInfo {
+    let reserve_balance = token::Client::new(env, token)
+        .balance(&env.current_contract_address())
+        .max(0);
+    let outstanding = liquidity::outstanding(env, token);
+    let utilization_bps = if outstanding > 0 {
+        math::checked(
+            env,
+            math::to_bps(outstanding, outstanding + reserve_balance, Rounding::Down),
+        ) as u32
+    } else {
+        0
+    };
+    let earned = revenue::distributed(env, token);
+    ReserveInfo {
+        reserve_balance,
+        outstanding,
+        undrawn_commitments: liquidity::undrawn_commitments(env, token),
+        utilization_bps,
+        interest_earned: earned.lp + earned.treasury + earned.insurance,
+    }
+}
+
+/// Reserve figures for every supported token, in the order they were added.
+pub fn breakdown(env: &Env) -> Vec<(Address, ReserveInfo)> {
+    let mut out = Vec::new(env);
+    for token in tokens::list(env).iter() {
+        let info = reserve_info(env, &token);
+        out.push_back((token, info));
+    }
+    out
+}
+
+pub fn report(env: &Env) -> HealthReport {
+    let token = crate::require_token(env);
+    let reserve_balance = token::Client::new(env, &token).balance(&env.current_contract_address());
+    let outstanding = liquidity::outstanding(env, &token);
+    let reserve_ratio_bps = math::checked(env, reserve::ratio_bps(reserve_balance, outstanding));
+    let pause_state = pause::get_pause_state(env);
+
+    let oracle_config = oracle::get_config(env);
+    let price_timestamp = oracle_config.as_ref().and_then(|config| {
+        PriceOracleClient::new(env, &config.oracle)
+            .try_lastprice(&Asset::Stellar(token.clone()))
+            .ok()
+            .and_then(|result| result.ok())
+            .flatten()
+            .map(|data| data.timestamp)
+    });
+    let oracle_price_age_secs =
+        price_timestamp.map(|timestamp| env.ledger().timestamp().saturating_sub(timestamp));
+    let oracle_stale = match (&oracle_config, oracle_price_age_secs) {
+        (Some(config), Some(age)) => age > config.max_age_secs,
+        (Some(_), None) => true,
+        (None, _) => false,
+    };
+
+    HealthReport {
+        reserve_balance,
+        outstanding,
+        reserve_ratio_bps,
+        reserve_below_min: reserve_ratio_bps
+            < reserve::get_config(env).min_reserve_ratio_bps as i128,
+        draws_paused: pause_state.draws_paused,
+        repays_paused: pause_state.repays_paused,
+        oracle_configured: oracle_config.is_some(),
+        oracle_price_age_secs,
+        oracle_stale,
+        delinquent_lines: delinquency::count(env),
+        instance_ttl_ledgers: ttl::instance_remaining(env),
+    }
+}
@@ -0,0 +1,110 @@
+//! Batch opening of credit lines from a committed Merkle root.
+//!
+//! For mass onboarding, the risk engine commits the root of a Merkle tree whose
+//! leaves are approved `(borrower, terms)` pairs instead of opening each line
+//! itself. Borrowers then open their own line with `claim_credit_line` by
+//! presenting their terms and the proof of their leaf. A leaf is the SHA-256 of
+//! the XDR encoding of `(contract_address, borrower, terms)`, and each parent
+//! is the SHA-256 of its two children in ascending order, so proofs need no
+//! position bits. Each borrower can claim once per root; committing a new root
+//! replaces the previous one.
+
+use soroban_sdk::{contracttype, xdr::ToXdr, Address, Bytes, BytesN, Env, Symbol, Vec};
+
+use crate::storage::{self, DataKey};
+use crate::types::{RateMode, RepaymentSchedule};
+
+/// Terms of a credit line approved in a committed root.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct LineTerms {
+    pub credit_limit: i128,
+    pub interest_rate_bps: u32,
+    pub risk_score: u32,
+    /// Billing cycle length in seconds; 0 opens the line without a schedule.
+    pub cycle_length_secs: u64,
+    pub min_payment_bps: u32,
+    pub rate_mode: RateMode,
+    /// Denomination; `None` uses the default token.
+    pub token: Option<Address>,
+}
+
+impl LineTerms {
+    pub fn schedule(&self) -> Option<RepaymentSchedule> {
+        (self.cycle_length_secs > 0).then_some(RepaymentSchedule {
+            cycle_length_secs: self.cycle_length_secs,
+            min_payment_bps: self.min_payment_bps,
+        })
+    }
+}
+
+#[contracttype]
+#[derive(Clone)]
+pub enum OnboardingKey {
+    /// Set once a borrower has claimed a line under a root (persistent).
+    Claimed(BytesN<32>, Address),
+}
+
+/// Instance storage key for the committed root.
+fn root_key(env: &Env) -> DataKey {
+    DataKey::Config(Symbol::new(env, "open_root"))
+}
+
+fn claimed_key(root: &BytesN<32>, borrower: &Address) -> DataKey {
+    DataKey::Onboarding(OnboardingKey::Claimed(root.clone(), borrower.clone()))
+}
+
+pub fn get_root(env: &Env) -> Option<BytesN<32>> {
+    storage::get_instance(env, &root_key(env))
+}
+
+pub fn set_root(env: &Env, root: &BytesN<32>) {
+    storage::set_instance(env, &root_key(env), root);
+}
+
+/// Returns true if `borrower` has claimed a line under the committed root.
+pub fn is_claimed(env: &Env, borrower: &Address) -> bool {
+    get_root(env).is_some_and(|root| storage::has_persistent(env, &claimed_key(&root, borrower)))
+}
+
+/// Leaf committing to `terms` for `borrower`.
+pub fn leaf(env: &Env, borrower: &Address, terms: &LineTerms) -> BytesN<32> {
+    let encoded = (
+        env.current_contract_address(),
+        borrower.clone(),
+        terms.clone(),
+    )
+        .to_xdr(env);
+    env.crypto().sha256(&encoded).to_bytes()
+}
+
+/// Parent of two nodes, hashed in ascending order.
+pub fn hash_pair(env: &Env, a: &BytesN<32>, b: &BytesN<32>) -> BytesN<32> {
+    let (first, second) = if a <= b { (a, b) } else { (b, a) };
+    let mut joined = Bytes::from(first.clone());
+    joined.append(&Bytes::from(second.clone()));
+    env.crypto().sha256(&joined).to_bytes()
+}
+
+/// Check `proof` for `borrower`'s `terms` against the committed root and mark
+/// the borrower as claimed.
+///
+/// # Panics
+/// * `"open root not committed"`
+/// * `"credit line already claimed"`
+/// * `"invalid merkle proof"`
+pub fn claim(env: &Env, borrower: &Address, terms: &LineTerms, proof: &Vec<BytesN<32>>) {
+    let root = get_root(env).expect("open root not committed");
+    let key = claimed_key(&root, borrower);
+    if storage::has_persistent(env, &key) {
+        panic!("credit line already claimed");
+    }
+    let mut node = leaf(env, borrower, terms);
+    for sibling in proof.iter() {
+        node = hash_pair(env, &node, &sibling);
+    }
+    if node != root {
+        panic!("invalid merkle proof");
+    }
+    storage::set_persistent(env, &key, &true);
+}
@@ -0,0 +1,66 @@
+//! Delegated draw authorization.
+//!
+//! A borrower may approve an operator (e.g. a card-payment smart wallet) to draw
+//! on their line up to `max_amount` until `expiry_ledger`. Each operator draw
+//! consumes the allowance; revoking removes it.
+
+use soroban_sdk::{contracttype, Address, Env};
+
+use crate::storage::{self, DataKey};
+
+/// Remaining draw allowance granted by a borrower to an operator.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct OperatorApproval {
+    /// Amount the operator may still draw.
+    pub max_amount: i128,
+    /// Last ledger sequence at which the approval is valid.
+    pub expiry_ledger: u32,
+}
+
+fn key(borrower: &Address, operator: &Address) -> DataKey {
+    DataKey::Operator(borrower.clone(), operator.clone())
+}
+
+pub fn get(env: &Env, borrower: &Address, operator: &Address) -> Option<OperatorApproval> {
+    storage::get_persistent(env, &key(borrower, operator))
+}
+
+/// # Panics
+/// * `"max_amount must be positive"`
+/// * `"expiry_ledger is in the past"`
+pub fn approve(env: &Env, borrower: &Address, operator: &Address, approval: &OperatorApproval) {
+    if approval.max_amount <= 0 {
+        panic!("max_amount must be positive");
+    }
+    if approval.expiry_ledger < env.ledger().sequence() {
+        panic!("expiry_ledger is in the past");
+    }
+    storage::set_persistent(env, &key(borrower, operator), approval);
+}
+
+pub fn revoke(env: &Env, borrower: &Address, operator: &Address) {
+    storage::remove_persistent(env, &key(borrower, operator));
+}
+
+/// Deduct `amount` from the operator's allowance.
+///
+/// # Panics
+/// * `"operator not approved"`
+/// * `"operator approval expired"`
+/// * `"exceeds operator allowance"`
+pub fn consume(env: &Env, borrower: &Address, operator: &Address, amount: i128) {
+    let mut approval = get(env, borrower, operator).expect("operator not approved");
+    if env.ledger().sequence() > approval.expiry_ledger {
+        panic!("operator approval expired");
+    }
+    if amount > approval.max_amount {
+        panic!("exceeds operator allowance");
+    }
+    approval.max_amount -= amount;
+    if approval.max_amount == 0 {
+        revoke(env, borrower, operator);
+    } else {
+        storage::set_persistent(env, &key(borrower, operator), &approval);
+    }
+}
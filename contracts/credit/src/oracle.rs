@@ -0,0 +1,77 @@
+//! Price oracle adapter for collateral valuation.
+//!
+//! Talks to a SEP-40 / Reflector-compatible price feed. Prices for the
+//! collateral token and the credit token are quoted in the oracle's base asset,
+//! so their ratio converts collateral into credit token units. Both tokens are
+//! assumed to use the same number of decimals (true for Stellar assets).
+
+use soroban_sdk::{contractclient, contracttype, Address, Env, Symbol};
+
+use crate::storage::{self, DataKey};
+
+/// SEP-40 asset identifier.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum Asset {
+    Stellar(Address),
+    Other(Symbol),
+}
+
+/// SEP-40 price record.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct PriceData {
+    pub price: i128,
+    pub timestamp: u64,
+}
+
+/// Subset of the SEP-40 price feed interface used by the Credit contract.
+/// Only the generated `PriceOracleClient` is used.
+#[allow(dead_code)]
+#[contractclient(name = "PriceOracleClient")]
+pub trait PriceOracle {
+    fn lastprice(env: Env, asset: Asset) -> Option<PriceData>;
+    fn decimals(env: Env) -> u32;
+}
+
+/// Configured price feed.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct OracleConfig {
+    pub oracle: Address,
+    /// Prices older than this many seconds are rejected.
+    pub max_age_secs: u64,
+}
+
+/// Instance storage key for oracle config.
+fn oracle_key(env: &Env) -> DataKey {
+    DataKey::Config(Symbol::new(env, "oracle"))
+}
+
+pub fn get_config(env: &Env) -> Option<OracleConfig> {
+    storage::get_instance(env, &oracle_key(env))
+}
+
+pub fn set_config(env: &Env, config: &OracleConfig) {
+    storage::set_instance(env, &oracle_key(env), config);
+}
+
+/// Fetch a fresh price for `token`.
+///
+/// # Panics
+/// * `"oracle price unavailable"` – feed has no price for `token`
+/// * `"stale oracle price"` – price is older than `max_age_secs`
+/// * `"invalid oracle price"` – price is zero or negative
+pub fn fresh_price(env: &Env, config: &OracleConfig, token: &Address) -> i128 {
+    let data = PriceOracleClient::new(env, &config.oracle)
+        .lastprice(&Asset::Stellar(token.clone()))
+        .expect("oracle price unavailable");
+    let now = env.ledger().timestamp();
+    if now.saturating_sub(data.timestamp) > config.max_age_secs {
+        panic!("stale oracle price");
+    }
+    if data.price <= 0 {
+        panic!("invalid oracle price");
+    }
+    data.price
+}
@@ -0,0 +1,44 @@
+//! Emergency pause (circuit breaker) for the Credit contract.
+//!
+//! Draws and repayments are paused independently so that borrowers can keep
+//! repaying while draws are halted during an incident.
+
+use soroban_sdk::{contracttype, panic_with_error, Env, Symbol};
+
+use crate::errors::ContractError;
+use crate::storage::{self, DataKey};
+
+/// Current pause flags.
+#[contracttype]
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct PauseState {
+    pub draws_paused: bool,
+    pub repays_paused: bool,
+}
+
+/// Instance storage key for pause flags.
+fn pause_key(env: &Env) -> DataKey {
+    DataKey::Config(Symbol::new(env, "paused"))
+}
+
+pub fn get_pause_state(env: &Env) -> PauseState {
+    storage::get_instance(env, &pause_key(env)).unwrap_or_default()
+}
+
+pub fn set_pause_state(env: &Env, state: &PauseState) {
+    storage::set_instance(env, &pause_key(env), state);
+}
+
+/// Revert with `ContractError::Paused` if draws are paused.
+pub fn require_draws_not_paused(env: &Env) {
+    if get_pause_state(env).draws_paused {
+        panic_with_error!(env, ContractError::Paused);
+    }
+}
+
+/// Revert with `ContractError::Paused` if repayments are paused.
+pub fn require_repays_not_paused(env: &Env) {
+    if get_pause_state(env).repays_paused {
+        panic_with_error!(env, ContractError::Paused);
+    }
+}
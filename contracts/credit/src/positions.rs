@@ -0,0 +1,48 @@
+//! Transferable credit line positions.
+//!
+//! A borrower can mint a position identifier for their line, after which the
+//! borrower-of-record may be moved to a new address (for example when a
+//! corporate account migrates wallets). The line, its activity history, and
+//! its position id follow the new borrower.
+
+use soroban_sdk::{contracttype, Address, Env};
+
+use crate::storage::{self, DataKey};
+use crate::types::CreditLineData;
+
+#[contracttype]
+#[derive(Clone)]
+pub enum PositionKey {
+    /// Last minted position id (instance).
+    LastId,
+    /// Current borrower of a position (persistent).
+    Owner(u64),
+}
+
+pub fn owner(env: &Env, position_id: u64) -> Option<Address> {
+    storage::get_persistent(env, &DataKey::Position(PositionKey::Owner(position_id)))
+}
+
+pub fn set_owner(env: &Env, position_id: u64, borrower: &Address) {
+    storage::set_persistent(
+        env,
+        &DataKey::Position(PositionKey::Owner(position_id)),
+        borrower,
+    );
+}
+
+/// Assign the next position id to `line`. The caller saves the line.
+///
+/// # Panics
+/// * `"position already minted"`
+pub fn mint(env: &Env, line: &mut CreditLineData) -> u64 {
+    if line.position_id != 0 {
+        panic!("position already minted");
+    }
+    let position_id: u64 =
+        storage::get_instance(env, &DataKey::Position(PositionKey::LastId)).unwrap_or(0) + 1;
+    storage::set_instance(env, &DataKey::Position(PositionKey::LastId), &position_id);
+    set_owner(env, position_id, &line.borrower);
+    line.position_id = position_id;
+    position_id
+}
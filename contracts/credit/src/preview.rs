@@ -0,0 +1,201 @@
+//! Read-only previews of draws and repayments.
+//!
+//! Each preview runs the checks of `draw_credit` / `repay_credit`, in the same
+//! order, against a copy of the line, and reports the first failing check as a
+//! `ContractError` code instead of reverting. Nothing is written. Keep these in
+//! step with `draw_guarded` and `repay_guarded` when either changes.
+
+use soroban_sdk::{contracttype, token, Address, Env, Symbol};
+
+use crate::errors::ContractError;
+use crate::types::{CreditLineData, CreditStatus};
+use crate::{
+    allowlist, blocklist, boosts, collateral, delegation, draw_limits, exposure, fees, fx,
+    insurance, interest, large_draws, liquidity, math, pause, purposes, reserve, restrictions,
+    revenue, schema, streams, suspension, terms, tiers, velocity,
+};
+
+/// Outcome of a draw, as `preview_draw` predicts it.
+#[contracttype]
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct DrawPreview {
+    /// `ContractError` code the draw would revert with; 0 if it would succeed.
+    /// The remaining fields are 0 when it would revert.
+    pub error: u32,
+    pub new_utilized_amount: i128,
+    pub fee: i128,
+    pub premium: i128,
+    /// Amount the recipient would receive after the fee and premium.
+    pub net_amount: i128,
+    /// Interest accrued on the line up to now.
+    pub accrued_interest: i128,
+    /// Credit left to draw afterwards, counting any active limit boost.
+    pub remaining_available: i128,
+}
+
+/// Outcome of a repayment, as `preview_repay` predicts it.
+#[contracttype]
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct RepayPreview {
+    /// `ContractError` code the repayment would revert with; 0 if it would succeed.
+    /// The remaining fields are 0 when it would revert.
+    pub error: u32,
+    /// Amount that would be applied; anything above the outstanding balance is not.
+    pub applied: i128,
+    pub principal_portion: i128,
+    pub interest_portion: i128,
+    /// Share of `interest_portion` kept by the protocol under the reserve factor.
+    pub fee_portion: i128,
+    pub new_utilized_amount: i128,
+    /// Accrued interest left unpaid afterwards.
+    pub remaining_interest: i128,
+    /// Charged on top of `applied` for paying off a term line before maturity.
+    pub prepayment_penalty: i128,
+}
+
+fn read_line(env: &Env, borrower: &Address) -> Result<CreditLineData, ContractError> {
+    let (mut line, _) = schema::read(env, borrower).ok_or(ContractError::LineNotFound)?;
+    tiers::apply_pending(env, &mut line);
+    if line.status == CreditStatus::Closed {
+        return Err(ContractError::LineClosed);
+    }
+    Ok(line)
+}
+
+/// Preview a direct draw of `amount` by `borrower` for `purpose`.
+pub fn draw(env: &Env, borrower: &Address, amount: i128, purpose: &Option<Symbol>) -> DrawPreview {
+    quote_draw(env, borrower, amount, purpose).unwrap_or_else(|error| DrawPreview {
+        error: error as u32,
+        ..Default::default()
+    })
+}
+
+fn quote_draw(
+    env: &Env,
+    borrower: &Address,
+    amount: i128,
+    purpose: &Option<Symbol>,
+) -> Result<DrawPreview, ContractError> {
+    if pause::get_pause_state(env).draws_paused {
+        return Err(ContractError::Paused);
+    }
+    if amount <= 0 {
+        return Err(ContractError::InvalidAmount);
+    }
+    if large_draws::needs_approval(env, amount) {
+        return Err(ContractError::ApprovalRequired);
+    }
+    let mut line = read_line(env, borrower)?;
+    allowlist::check(env, borrower)?;
+    blocklist::check(env, borrower, borrower)?;
+    suspension::lift_if_expired(env, &mut line);
+    if line.status != CreditStatus::Active {
+        return Err(ContractError::LineNotActive);
+    }
+    if restrictions::is_frozen(
+        &line,
+        restrictions::DRAWS_FROZEN | restrictions::TRANSFERS_FROZEN,
+    ) {
+        return Err(ContractError::Restricted);
+    }
+    if line.maturity_ts != 0 && env.ledger().timestamp() >= line.maturity_ts {
+        return Err(ContractError::Matured);
+    }
+    if !purposes::is_allowed(env, borrower, purpose) {
+        return Err(ContractError::PurposeNotAllowed);
+    }
+    let limits = draw_limits::effective(env, &line);
+    if amount < limits.min_draw_amount
+        || (limits.max_draw_amount > 0 && amount > limits.max_draw_amount)
+    {
+        return Err(ContractError::DrawLimit);
+    }
+    if draw_limits::cooling_down(env, borrower, &limits) {
+        return Err(ContractError::DrawCooldown);
+    }
+    if velocity::would_exceed(env, borrower, amount) {
+        return Err(ContractError::RateLimited);
+    }
+
+    let new_utilized = math::add(line.utilized_amount, amount)?;
+    fx::reprice(env, &mut line, new_utilized);
+    let limit = boosts::effective_limit(env, &line)
+        - streams::locked(env, borrower)
+        - delegation::reserved(env, borrower);
+    if new_utilized > limit {
+        return Err(ContractError::CreditLimitExceeded);
+    }
+    let boost = boosts::active(env, &line);
+    if new_utilized > collateral::max_drawable(env, &line).saturating_add(boost) {
+        return Err(ContractError::CollateralLimitExceeded);
+    }
+    exposure::check(env, borrower, amount)?;
+    interest::accrue(env, &mut line);
+
+    let fee = fees::draw_fee(env, &line, amount);
+    let premium = insurance::premium(env, amount);
+    let net_amount = amount - fee - premium;
+    let balance = token::Client::new(env, &line.token).balance(&env.current_contract_address());
+    let ratio_bps = reserve::ratio_bps(
+        balance - net_amount,
+        liquidity::outstanding(env, &line.token),
+    )?;
+    if ratio_bps < reserve::get_config(env).min_reserve_ratio_bps as i128 {
+        return Err(ContractError::ReserveTooLow);
+    }
+
+    Ok(DrawPreview {
+        error: 0,
+        new_utilized_amount: new_utilized,
+        fee,
+        premium,
+        net_amount,
+        accrued_interest: line.accrued_interest,
+        remaining_available: limit - new_utilized,
+    })
+}
+
+/// Preview a repayment of `amount` by `borrower`.
+pub fn repay(env: &Env, borrower: &Address, amount: i128) -> RepayPreview {
+    quote_repay(env, borrower, amount).unwrap_or_else(|error| RepayPreview {
+        error: error as u32,
+        ..Default::default()
+    })
+}
+
+fn quote_repay(env: &Env, borrower: &Address, amount: i128) -> Result<RepayPreview, ContractError> {
+    if pause::get_pause_state(env).repays_paused {
+        return Err(ContractError::Paused);
+    }
+    let mut line = read_line(env, borrower)?;
+    if restrictions::is_frozen(&line, restrictions::REPAYS_FROZEN) {
+        return Err(ContractError::Restricted);
+    }
+    if amount <= 0 {
+        return Err(ContractError::InvalidAmount);
+    }
+
+    interest::accrue(env, &mut line);
+    let applied = amount.min(interest::outstanding(&line));
+    let interest_portion = interest::apply_repayment(env, &mut line, applied);
+    let (fee_portion, prepayment_penalty) = if line.creditor.is_none() {
+        let penalty = if interest::outstanding(&line) == 0 {
+            terms::prepayment_penalty(env, &line, applied - interest_portion)
+        } else {
+            0
+        };
+        (revenue::shares(env, interest_portion).treasury, penalty)
+    } else {
+        (0, 0)
+    };
+    Ok(RepayPreview {
+        error: 0,
+        applied,
+        principal_portion: applied - interest_portion,
+        interest_portion,
+        fee_portion,
+        new_utilized_amount: line.utilized_amount,
+        remaining_interest: line.accrued_interest,
+        prepayment_penalty,
+    })
+}
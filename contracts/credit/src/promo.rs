@@ -0,0 +1,45 @@
+//! Promotional rate periods.
+//!
+//! The risk engine can give a line an intro rate, `promo_rate_bps`, that
+//! accrues instead of `interest_rate_bps` until `promo_until_ts`. Accrual that
+//! spans the end of the promotion is split at `promo_until_ts`, and the first
+//! accrual past it clears the promotion, so the line falls back to its
+//! standard rate without anyone acting.
+
+use soroban_sdk::Env;
+
+use crate::types::CreditLineData;
+
+/// Replace any promotion on `line` with `promo_rate_bps` until `promo_until_ts`.
+///
+/// # Panics
+/// * `"interest_rate_bps exceeds maximum"`
+/// * `"promo expiry must be in the future"`
+pub fn grant(env: &Env, line: &mut CreditLineData, promo_rate_bps: u32, promo_until_ts: u64) {
+    if promo_rate_bps > crate::MAX_INTEREST_RATE_BPS {
+        panic!("interest_rate_bps exceeds maximum");
+    }
+    if promo_until_ts <= env.ledger().timestamp() {
+        panic!("promo expiry must be in the future");
+    }
+    line.promo_rate_bps = promo_rate_bps;
+    line.promo_until_ts = promo_until_ts;
+}
+
+/// Rate `line` accrues at right now: the promotional rate until it ends, the
+/// standard rate after.
+pub fn active_rate(env: &Env, line: &CreditLineData) -> u32 {
+    if env.ledger().timestamp() < line.promo_until_ts {
+        line.promo_rate_bps
+    } else {
+        line.interest_rate_bps
+    }
+}
+
+/// Drop the promotion from `line` once accrual has reached `promo_until_ts`.
+pub fn clear_if_ended(line: &mut CreditLineData) {
+    if line.promo_until_ts != 0 && line.last_accrual_ts >= line.promo_until_ts {
+        line.promo_rate_bps = 0;
+        line.promo_until_ts = 0;
+    }
+}
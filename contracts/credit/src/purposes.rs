@@ -0,0 +1,78 @@
+//! Purpose codes on draws.
+//!
+//! A draw can carry a `purpose` symbol (e.g. `payroll`, `inventory`). Every
+//! draw is appended to a short per-borrower log with its purpose, for
+//! category-level analytics. The admin can restrict a line to a set of allowed
+//! purposes; draws on such a line must name one of them.
+
+use soroban_sdk::{contracttype, Address, Env, Symbol, Vec};
+
+use crate::storage::{self, DataKey};
+
+/// Entries kept in a borrower's draw log; older ones are dropped.
+pub const DRAW_LOG_SIZE: u32 = 32;
+
+/// One entry of a borrower's draw log.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct DrawRecord {
+    pub amount: i128,
+    pub purpose: Option<Symbol>,
+    pub timestamp: u64,
+}
+
+#[contracttype]
+#[derive(Clone)]
+pub enum PurposeKey {
+    /// Purposes a line may draw for; absent when unrestricted (persistent).
+    Allowed(Address),
+    /// Most recent draws of a borrower, oldest first (persistent).
+    Log(Address),
+}
+
+pub fn allowed(env: &Env, borrower: &Address) -> Vec<Symbol> {
+    storage::get_persistent(
+        env,
+        &DataKey::Purposes(PurposeKey::Allowed(borrower.clone())),
+    )
+    .unwrap_or_else(|| Vec::new(env))
+}
+
+/// Restrict `borrower`'s draws to `purposes`; an empty list lifts the restriction.
+pub fn set_allowed(env: &Env, borrower: &Address, purposes: &Vec<Symbol>) {
+    let key = DataKey::Purposes(PurposeKey::Allowed(borrower.clone()));
+    if purposes.is_empty() {
+        storage::remove_persistent(env, &key);
+    } else {
+        storage::set_persistent(env, &key, purposes);
+    }
+}
+
+/// True if `borrower`'s line accepts a draw for `purpose`.
+pub fn is_allowed(env: &Env, borrower: &Address, purpose: &Option<Symbol>) -> bool {
+    let allowed = allowed(env, borrower);
+    allowed.is_empty() || purpose.as_ref().is_some_and(|p| allowed.contains(p))
+}
+
+pub fn log(env: &Env, borrower: &Address) -> Vec<DrawRecord> {
+    storage::get_persistent(env, &DataKey::Purposes(PurposeKey::Log(borrower.clone())))
+        .unwrap_or_else(|| Vec::new(env))
+}
+
+/// Append a draw of `amount` for `purpose` to `borrower`'s log.
+pub fn record_draw(env: &Env, borrower: &Address, amount: i128, purpose: Option<Symbol>) {
+    let mut entries = log(env, borrower);
+    if entries.len() >= DRAW_LOG_SIZE {
+        entries.pop_front();
+    }
+    entries.push_back(DrawRecord {
+        amount,
+        purpose,
+        timestamp: env.ledger().timestamp(),
+    });
+    storage::set_persistent(
+        env,
+        &DataKey::Purposes(PurposeKey::Log(borrower.clone())),
+        &entries,
+    );
+}
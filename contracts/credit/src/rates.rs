@@ -0,0 +1,104 @@
+//! Fixed and variable interest rates.
+//!
+//! Fixed lines keep the `interest_rate_bps` they were given. Variable lines
+//! store a spread and track the protocol reference rate plus that spread. A
+//! new reference rate is picked up the next time a variable line accrues
+//! interest; interest up to that point accrues at the previous rate.
+//!
+//! A line may also carry a rate floor and cap. Explicit rates outside them are
+//! rejected, and variable rates are clamped into them.
+
+use soroban_sdk::{Env, Symbol};
+
+use crate::storage::{self, DataKey};
+use crate::types::{CreditLineData, RateMode};
+
+/// Instance storage key for the reference rate.
+fn reference_rate_key(env: &Env) -> DataKey {
+    DataKey::Config(Symbol::new(env, "ref_rate"))
+}
+
+pub fn reference_rate_bps(env: &Env) -> u32 {
+    storage::get_instance(env, &reference_rate_key(env)).unwrap_or(0)
+}
+
+/// # Panics
+/// * `"interest_rate_bps exceeds maximum"`
+pub fn set_reference_rate_bps(env: &Env, bps: u32) {
+    if bps > crate::MAX_INTEREST_RATE_BPS {
+        panic!("interest_rate_bps exceeds maximum");
+    }
+    storage::set_instance(env, &reference_rate_key(env), &bps);
+}
+
+fn variable_rate(env: &Env, spread_bps: u32) -> u32 {
+    reference_rate_bps(env)
+        .saturating_add(spread_bps)
+        .min(crate::MAX_INTEREST_RATE_BPS)
+}
+
+/// `bps` clamped into `line`'s rate floor and cap.
+fn bounded(line: &CreditLineData, bps: u32) -> u32 {
+    let bps = bps.max(line.rate_floor_bps);
+    if line.rate_cap_bps > 0 {
+        bps.min(line.rate_cap_bps)
+    } else {
+        bps
+    }
+}
+
+/// Apply a new rate: the rate itself for Fixed lines, the spread for Variable lines.
+///
+/// # Panics
+/// * `"interest_rate_bps outside rate bounds"` – a Fixed rate below the line's floor
+///   or above its cap
+pub fn set_rate(env: &Env, line: &mut CreditLineData, bps: u32) {
+    match line.rate_mode {
+        RateMode::Fixed => {
+            if bounded(line, bps) != bps {
+                panic!("interest_rate_bps outside rate bounds");
+            }
+            line.interest_rate_bps = bps;
+        }
+        RateMode::Variable => {
+            line.rate_spread_bps = bps;
+            line.interest_rate_bps = bounded(line, variable_rate(env, bps));
+        }
+    }
+}
+
+/// Like `set_rate`, but a Fixed rate outside the line's bounds is clamped into
+/// them instead of rejected.
+pub fn set_rate_clamped(env: &Env, line: &mut CreditLineData, bps: u32) {
+    match line.rate_mode {
+        RateMode::Fixed => line.interest_rate_bps = bounded(line, bps),
+        RateMode::Variable => set_rate(env, line, bps),
+    }
+}
+
+/// Re-price a Variable line against the current reference rate.
+pub fn refresh(env: &Env, line: &mut CreditLineData) {
+    if line.rate_mode == RateMode::Variable {
+        line.interest_rate_bps = bounded(line, variable_rate(env, line.rate_spread_bps));
+    }
+}
+
+/// Set `line`'s rate floor and cap (0 for none) and move its current rate into them.
+///
+/// # Panics
+/// * `"interest_rate_bps exceeds maximum"`
+/// * `"rate floor exceeds rate cap"`
+pub fn set_bounds(env: &Env, line: &mut CreditLineData, floor_bps: u32, cap_bps: u32) {
+    if floor_bps > crate::MAX_INTEREST_RATE_BPS || cap_bps > crate::MAX_INTEREST_RATE_BPS {
+        panic!("interest_rate_bps exceeds maximum");
+    }
+    if cap_bps > 0 && floor_bps > cap_bps {
+        panic!("rate floor exceeds rate cap");
+    }
+    line.rate_floor_bps = floor_bps;
+    line.rate_cap_bps = cap_bps;
+    match line.rate_mode {
+        RateMode::Fixed => line.interest_rate_bps = bounded(line, line.interest_rate_bps),
+        RateMode::Variable => refresh(env, line),
+    }
+}
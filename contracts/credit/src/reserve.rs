@@ -0,0 +1,112 @@
+//! Minimum reserve ratio.
+//!
+//! The reserve ratio of a token is the contract's balance of it over the
+//! principal outstanding on lines denominated in it. Draws that would push the
+//! ratio below `min_reserve_ratio_bps` revert; draws that leave it below
+//! `warning_ratio_bps` emit a `ReserveLowEvent` so monitoring can top up
+//! liquidity.
+//!
+//! A draw that takes the ratio from at or above the warning or critical
+//! threshold to below it also emits a `ReserveThresholdEvent`, which treasury
+//! operations can act on to rebalance liquidity. Once the ratio is below `critical_ratio_bps`, draws
+//! are blocked entirely until liquidity is added or principal repaid.
+
+use soroban_sdk::{contracttype, Env, Symbol, Vec};
+
+use crate::errors::ContractError;
+use crate::math::{self, Rounding};
+use crate::storage::{self, DataKey};
+
+/// Reserve ratio thresholds in basis points (admin-configured). 0 disables a threshold.
+#[contracttype]
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct ReserveConfig {
+    /// Draws may not leave the ratio below this.
+    pub min_reserve_ratio_bps: u32,
+    /// Draws leaving the ratio below this emit a `ReserveLowEvent`.
+    pub warning_ratio_bps: u32,
+}
+
+/// Reserve ratio threshold crossed by a draw.
+#[contracttype]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum ReserveLevel {
+    /// `ReserveConfig::warning_ratio_bps`.
+    Warning = 0,
+    /// The critical ratio, below which draws are blocked.
+    Critical = 1,
+}
+
+/// Instance storage key for reserve config.
+fn reserve_config_key(env: &Env) -> DataKey {
+    DataKey::Config(Symbol::new(env, "reserve_cfg"))
+}
+
+pub fn get_config(env: &Env) -> ReserveConfig {
+    storage::get_instance(env, &reserve_config_key(env)).unwrap_or_default()
+}
+
+/// # Panics
+/// * `"warning_ratio_bps below min_reserve_ratio_bps"` – the warning would never fire
+/// * `"warning_ratio_bps below critical_ratio_bps"`
+pub fn set_config(env: &Env, config: &ReserveConfig) {
+    if config.warning_ratio_bps != 0 && config.warning_ratio_bps < config.min_reserve_ratio_bps {
+        panic!("warning_ratio_bps below min_reserve_ratio_bps");
+    }
+    if config.warning_ratio_bps != 0 && config.warning_ratio_bps < critical_ratio_bps(env) {
+        panic!("warning_ratio_bps below critical_ratio_bps");
+    }
+    storage::set_instance(env, &reserve_config_key(env), config);
+}
+
+/// Instance storage key for the critical ratio.
+fn critical_key(env: &Env) -> DataKey {
+    DataKey::Config(Symbol::new(env, "res_crit"))
+}
+
+/// Ratio below which draws are blocked; 0 if disabled.
+pub fn critical_ratio_bps(env: &Env) -> u32 {
+    storage::get_instance(env, &critical_key(env)).unwrap_or(0)
+}
+
+/// # Panics
+/// * `"warning_ratio_bps below critical_ratio_bps"` – the warning would fire after the critical level
+pub fn set_critical_ratio_bps(env: &Env, bps: u32) {
+    let warning = get_config(env).warning_ratio_bps;
+    if warning != 0 && warning < bps {
+        panic!("warning_ratio_bps below critical_ratio_bps");
+    }
+    storage::set_instance(env, &critical_key(env), &bps);
+}
+
+/// # Panics
+/// * `"reserve below critical level"` – `ratio_bps` is below the critical ratio
+pub fn require_above_critical(env: &Env, ratio_bps: i128) {
+    if ratio_bps < critical_ratio_bps(env) as i128 {
+        panic!("reserve below critical level");
+    }
+}
+
+/// Thresholds a move of the ratio from `before_bps` to `after_bps` falls through,
+/// warning first.
+pub fn crossed(env: &Env, before_bps: i128, after_bps: i128) -> Vec<(ReserveLevel, u32)> {
+    let mut crossed = Vec::new(env);
+    for (level, threshold) in [
+        (ReserveLevel::Warning, get_config(env).warning_ratio_bps),
+        (ReserveLevel::Critical, critical_ratio_bps(env)),
+    ] {
+        let bps = threshold as i128;
+        if threshold > 0 && before_bps >= bps && after_bps < bps {
+            crossed.push_back((level, threshold));
+        }
+    }
+    crossed
+}
+
+/// `reserve / outstanding` in basis points; `i128::MAX` when nothing is outstanding.
+pub fn ratio_bps(reserve: i128, outstanding: i128) -> Result<i128, ContractError> {
+    if outstanding <= 0 {
+        return Ok(i128::MAX);
+    }
+    math::to_bps(reserve.max(0), outstanding, Rounding::Down)
+}
@@ -0,0 +1,67 @@
+//! Per-line operation freezes.
+//!
+//! `Suspended` freezes a line's draws outright. Restrictions are finer-grained
+//! flags the admin can set on a line whatever its status, freezing only draws,
+//! only repayments, only changes to the line's terms, or every transfer out of
+//! the contract to the borrower. `DISPUTED` is not a
+//! freeze the admin sets here; it is owned by `disputes`.
+
+use crate::types::CreditLineData;
+
+/// Draws are rejected.
+pub const DRAWS_FROZEN: u32 = 1 << 0;
+/// Repayments are rejected.
+pub const REPAYS_FROZEN: u32 = 1 << 1;
+/// Changes to the line's limit, rate, and other terms are rejected.
+pub const UPDATES_FROZEN: u32 = 1 << 2;
+/// The line is under dispute (see `disputes`).
+pub const DISPUTED: u32 = 1 << 3;
+/// Draws, repayment refunds, and collateral returns are rejected.
+pub const TRANSFERS_FROZEN: u32 = 1 << 4;
+
+const ALL: u32 = DRAWS_FROZEN | REPAYS_FROZEN | UPDATES_FROZEN | TRANSFERS_FROZEN;
+
+/// # Panics
+/// * `"invalid restrictions"` – unknown flag bits are set
+pub fn validate(restrictions: u32) {
+    if restrictions & !ALL != 0 {
+        panic!("invalid restrictions");
+    }
+}
+
+/// True if any of `flags` is set on `line`.
+pub fn is_frozen(line: &CreditLineData, flags: u32) -> bool {
+    line.restrictions & flags != 0
+}
+
+/// # Panics
+/// * `"draws frozen"`
+pub fn require_draws_allowed(line: &CreditLineData) {
+    if is_frozen(line, DRAWS_FROZEN) {
+        panic!("draws frozen");
+    }
+}
+
+/// # Panics
+/// * `"repayments frozen"`
+pub fn require_repays_allowed(line: &CreditLineData) {
+    if is_frozen(line, REPAYS_FROZEN) {
+        panic!("repayments frozen");
+    }
+}
+
+/// # Panics
+/// * `"transfers frozen"`
+pub fn require_transfers_allowed(line: &CreditLineData) {
+    if is_frozen(line, TRANSFERS_FROZEN) {
+        panic!("transfers frozen");
+    }
+}
+
+/// # Panics
+/// * `"updates frozen"`
+pub fn require_updates_allowed(line: &CreditLineData) {
+    if is_frozen(line, UPDATES_FROZEN) {
+        panic!("updates frozen");
+    }
+}
@@ -0,0 +1,109 @@
+//! Revenue split of collected interest.
+//!
+//! Interest repaid on a line is divided between liquidity providers, the
+//! protocol treasury and the insurance fund. Until the admin sets a split, the
+//! treasury takes `reserve_factor_bps` and providers the rest; once set, the
+//! split replaces the reserve factor. The treasury and insurance shares are
+//! rounded down, so rounding dust goes to providers. What each bucket has
+//! received is tracked per token.
+
+use soroban_sdk::{contracttype, Address, Env, Symbol};
+
+use crate::insurance;
+use crate::liquidity;
+use crate::math::{self, apply_bps, Rounding};
+use crate::storage::{self, DataKey};
+use crate::treasury;
+
+/// Shares of collected interest in basis points, summing to 10000 (admin-configured).
+#[contracttype]
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct RevenueSplit {
+    /// Added to the liquidity pool, raising the share price.
+    pub lp_bps: u32,
+    /// Booked as protocol fees for the treasury.
+    pub treasury_bps: u32,
+    /// Added to the insurance fund.
+    pub insurance_bps: u32,
+}
+
+/// Amounts of interest per bucket.
+#[contracttype]
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct RevenueShares {
+    pub lp: i128,
+    pub treasury: i128,
+    pub insurance: i128,
+}
+
+#[contracttype]
+#[derive(Clone)]
+pub enum RevenueKey {
+    /// Interest distributed in a token since the split was introduced (instance).
+    Distributed(Address),
+}
+
+/// Instance storage key for the split.
+fn split_key(env: &Env) -> DataKey {
+    DataKey::Config(Symbol::new(env, "rev_split"))
+}
+
+fn distributed_key(token: &Address) -> DataKey {
+    DataKey::Revenue(RevenueKey::Distributed(token.clone()))
+}
+
+/// The split in effect: the configured one, or the reserve factor split.
+pub fn get_split(env: &Env) -> RevenueSplit {
+    storage::get_instance(env, &split_key(env)).unwrap_or_else(|| {
+        let treasury_bps = treasury::reserve_factor_bps(env);
+        RevenueSplit {
+            lp_bps: 10_000 - treasury_bps,
+            treasury_bps,
+            insurance_bps: 0,
+        }
+    })
+}
+
+/// # Panics
+/// * `"revenue split must sum to 10000"`
+pub fn set_split(env: &Env, split: &RevenueSplit) {
+    let total = split.lp_bps as u64 + split.treasury_bps as u64 + split.insurance_bps as u64;
+    if total != 10_000 {
+        panic!("revenue split must sum to 10000");
+    }
+    storage::set_instance(env, &split_key(env), split);
+}
+
+/// How `interest` is divided under the current split.
+pub fn shares(env: &Env, interest: i128) -> RevenueShares {
+    let split = get_split(env);
+    let treasury = math::checked(env, apply_bps(interest, split.treasury_bps, Rounding::Down));
+    let insurance = math::checked(
+        env,
+        apply_bps(interest, split.insurance_bps, Rounding::Down),
+    );
+    RevenueShares {
+        lp: interest - treasury - insurance,
+        treasury,
+        insurance,
+    }
+}
+
+/// Credit each bucket its share of `interest` collected in `token`.
+pub fn distribute(env: &Env, token: &Address, interest: i128) -> RevenueShares {
+    let shares = shares(env, interest);
+    liquidity::accrue_to_pool(env, token, shares.lp);
+    treasury::accrue_fees(env, token, shares.treasury);
+    insurance::pay_premium(env, token, shares.insurance);
+
+    let mut total = distributed(env, token);
+    total.lp = math::checked(env, math::add(total.lp, shares.lp));
+    total.treasury = math::checked(env, math::add(total.treasury, shares.treasury));
+    total.insurance = math::checked(env, math::add(total.insurance, shares.insurance));
+    storage::set_instance(env, &distributed_key(token), &total);
+    shares
+}
+
+pub fn distributed(env: &Env, token: &Address) -> RevenueShares {
+    storage::get_instance(env, &distributed_key(token)).unwrap_or_default()
+}
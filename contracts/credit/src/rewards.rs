@@ -0,0 +1,140 @@
+//! Rewards on on-time repayments.
+//!
+//! The program is off until the admin sets a non-zero `rate_bps`. Each
+//! repayment made on time then accrues reward points worth `rate_bps` of the
+//! interest it paid. Borrowers claim their points with `claim_rewards`: with a
+//! reward token configured, each point pays out one unit of that token from
+//! the contract's balance; without one, claimed points are redeemed off-chain
+//! from the claim events. Emissions are bounded by a per-borrower cap on
+//! unclaimed points and a lifetime cap on points accrued, and a `Pauser` can
+//! halt both accrual and claims.
+
+use soroban_sdk::{contracttype, token, Address, Env, Symbol};
+
+use crate::math::{self, apply_bps, Rounding};
+use crate::storage::{self, DataKey};
+use crate::tokens;
+
+/// Reward program settings (admin-configured). 0 disables a cap.
+#[contracttype]
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct RewardsConfig {
+    /// Points accrued per unit of interest paid on time, in basis points; 0 disables accrual.
+    pub rate_bps: u32,
+    /// Token paid out one unit per point on claim; `None` for points only.
+    pub reward_token: Option<Address>,
+    /// Most unclaimed points a borrower can hold.
+    pub max_unclaimed: i128,
+    /// Most points accrued across all borrowers, ever.
+    pub max_total: i128,
+}
+
+#[contracttype]
+#[derive(Clone)]
+pub enum RewardKey {
+    /// Unclaimed points of a borrower (persistent).
+    Points(Address),
+    /// Points accrued across all borrowers (instance).
+    Emitted,
+}
+
+/// Instance storage key for rewards config.
+fn config_key(env: &Env) -> DataKey {
+    DataKey::Config(Symbol::new(env, "rewards"))
+}
+
+/// Instance storage key for the pause flag.
+fn paused_key(env: &Env) -> DataKey {
+    DataKey::Config(Symbol::new(env, "rwd_paused"))
+}
+
+fn points_key(borrower: &Address) -> DataKey {
+    DataKey::Rewards(RewardKey::Points(borrower.clone()))
+}
+
+pub fn get_config(env: &Env) -> RewardsConfig {
+    storage::get_instance(env, &config_key(env)).unwrap_or_default()
+}
+
+/// # Panics
+/// * `"reward caps cannot be negative"`
+/// * `"reward token cannot be a credit token"` – claims would drain the reserve
+pub fn set_config(env: &Env, config: &RewardsConfig) {
+    if config.max_unclaimed < 0 || config.max_total < 0 {
+        panic!("reward caps cannot be negative");
+    }
+    if let Some(reward_token) = &config.reward_token {
+        if tokens::is_supported(env, reward_token) {
+            panic!("reward token cannot be a credit token");
+        }
+    }
+    storage::set_instance(env, &config_key(env), config);
+}
+
+pub fn is_paused(env: &Env) -> bool {
+    storage::get_instance(env, &paused_key(env)).unwrap_or(false)
+}
+
+pub fn set_paused(env: &Env, paused: bool) {
+    storage::set_instance(env, &paused_key(env), &paused);
+}
+
+pub fn points(env: &Env, borrower: &Address) -> i128 {
+    storage::get_persistent(env, &points_key(borrower)).unwrap_or(0)
+}
+
+pub fn emitted(env: &Env) -> i128 {
+    storage::get_instance(env, &DataKey::Rewards(RewardKey::Emitted)).unwrap_or(0)
+}
+
+/// Accrue points for `interest` paid on time by `borrower`, within the caps.
+/// Returns the points accrued.
+pub fn accrue(env: &Env, borrower: &Address, interest: i128) -> i128 {
+    let config = get_config(env);
+    if config.rate_bps == 0 || interest <= 0 || is_paused(env) {
+        return 0;
+    }
+    let mut earned = math::checked(env, apply_bps(interest, config.rate_bps, Rounding::Down));
+    let balance = points(env, borrower);
+    let emitted = emitted(env);
+    if config.max_unclaimed > 0 {
+        earned = earned.min(config.max_unclaimed - balance);
+    }
+    if config.max_total > 0 {
+        earned = earned.min(config.max_total - emitted);
+    }
+    if earned <= 0 {
+        return 0;
+    }
+    storage::set_persistent(env, &points_key(borrower), &(balance + earned));
+    storage::set_instance(
+        env,
+        &DataKey::Rewards(RewardKey::Emitted),
+        &math::checked(env, math::add(emitted, earned)),
+    );
+    earned
+}
+
+/// Clear `borrower`'s points, paying them out in the reward token if one is
+/// configured. Returns the points claimed.
+///
+/// # Panics
+/// * `"rewards are paused"`
+pub fn claim(env: &Env, borrower: &Address) -> i128 {
+    if is_paused(env) {
+        panic!("rewards are paused");
+    }
+    let claimed = points(env, borrower);
+    if claimed == 0 {
+        return 0;
+    }
+    storage::remove_persistent(env, &points_key(borrower));
+    if let Some(reward_token) = get_config(env).reward_token {
+        token::Client::new(env, &reward_token).transfer(
+            &env.current_contract_address(),
+            borrower,
+            &claimed,
+        );
+    }
+    claimed
+}
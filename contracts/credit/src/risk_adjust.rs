@@ -0,0 +1,70 @@
+//! Opt-in automatic risk score adjustments from repayment behavior.
+//!
+//! When enabled, each scheduled minimum payment cleared on time lowers the
+//! borrower's `risk_score` by `on_time_step`, and each missed cycle processed
+//! by a keeper raises it by `missed_step`, clamped to `[min_score, max_score]`.
+
+use soroban_sdk::{contracttype, Env, Symbol};
+
+use crate::storage::{self, DataKey};
+use crate::types::CreditLineData;
+
+/// Automatic adjustment policy (admin-configured).
+#[contracttype]
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct RiskAdjustmentConfig {
+    pub enabled: bool,
+    /// Score decrease for each minimum payment cleared on time.
+    pub on_time_step: u32,
+    /// Score increase for each missed cycle.
+    pub missed_step: u32,
+    /// Lowest score automatic adjustments may reach.
+    pub min_score: u32,
+    /// Highest score automatic adjustments may reach (at most 100).
+    pub max_score: u32,
+}
+
+/// Instance storage key for risk adjustment config.
+fn risk_adjust_config_key(env: &Env) -> DataKey {
+    DataKey::Config(Symbol::new(env, "risk_adj"))
+}
+
+pub fn get_config(env: &Env) -> RiskAdjustmentConfig {
+    storage::get_instance(env, &risk_adjust_config_key(env)).unwrap_or_default()
+}
+
+/// # Panics
+/// * `"max_score cannot exceed 100"`
+/// * `"min_score cannot exceed max_score"`
+pub fn set_config(env: &Env, config: &RiskAdjustmentConfig) {
+    if config.max_score > crate::MAX_RISK_SCORE {
+        panic!("max_score cannot exceed 100");
+    }
+    if config.min_score > config.max_score {
+        panic!("min_score cannot exceed max_score");
+    }
+    storage::set_instance(env, &risk_adjust_config_key(env), config);
+}
+
+fn apply(line: &mut CreditLineData, config: &RiskAdjustmentConfig, target: u32) -> bool {
+    let next = target.clamp(config.min_score, config.max_score);
+    if !config.enabled || next == line.risk_score {
+        return false;
+    }
+    line.risk_score = next;
+    true
+}
+
+/// Reward an on-time minimum payment. Returns true if the score changed.
+pub fn on_time_payment(env: &Env, line: &mut CreditLineData) -> bool {
+    let config = get_config(env);
+    let target = line.risk_score.saturating_sub(config.on_time_step);
+    apply(line, &config, target)
+}
+
+/// Penalize a missed cycle. Returns true if the score changed.
+pub fn missed_payment(env: &Env, line: &mut CreditLineData) -> bool {
+    let config = get_config(env);
+    let target = line.risk_score.saturating_add(config.missed_step);
+    apply(line, &config, target)
+}
@@ -0,0 +1,151 @@
+//! Repayment schedules: billing cycles, due dates, and minimum payments.
+//!
+//! A scheduled line owes `min_due_amount` by `next_due_ts`. When a cycle ends
+//! with nothing outstanding the schedule rolls forward and the next minimum is
+//! billed at that point under the line's repayment mode, statement-style. A
+//! cycle that ends with an unpaid minimum is left in place as overdue so the
+//! keeper flow can act on it.
+
+use soroban_sdk::{Address, Env};
+
+use crate::amortization::{self, RepaymentMode};
+use crate::errors::ContractError;
+use crate::interest;
+use crate::math::{apply_bps, Rounding};
+use crate::terms;
+use crate::types::{CreditLineData, NextPayment, RepaymentSchedule};
+
+/// Validate a schedule supplied for `borrower`'s line at open or restructure time.
+///
+/// # Panics
+/// * `"cycle_length_secs must be greater than zero"`
+/// * `"min_payment_bps cannot exceed 10000"`
+/// * `"min_payment_bps must be greater than zero"` – a revolving line would never bill
+pub fn validate(env: &Env, borrower: &Address, schedule: &RepaymentSchedule) {
+    if schedule.cycle_length_secs == 0 {
+        panic!("cycle_length_secs must be greater than zero");
+    }
+    if schedule.min_payment_bps > 10_000 {
+        panic!("min_payment_bps cannot exceed 10000");
+    }
+    if schedule.min_payment_bps == 0
+        && amortization::get(env, borrower).mode == RepaymentMode::Revolving
+    {
+        panic!("min_payment_bps must be greater than zero");
+    }
+}
+
+/// Attach `schedule` to a freshly opened or restructured line; the first cycle starts now.
+pub fn attach(env: &Env, line: &mut CreditLineData, schedule: &RepaymentSchedule) {
+    line.cycle_length_secs = schedule.cycle_length_secs;
+    line.min_payment_bps = schedule.min_payment_bps;
+    line.next_due_ts = env
+        .ledger()
+        .timestamp()
+        .saturating_add(schedule.cycle_length_secs);
+    line.min_due_amount = 0;
+}
+
+/// Remove any schedule from `line`.
+pub fn detach(line: &mut CreditLineData) {
+    line.cycle_length_secs = 0;
+    line.min_payment_bps = 0;
+    line.next_due_ts = 0;
+    line.min_due_amount = 0;
+}
+
+pub fn is_scheduled(line: &CreditLineData) -> bool {
+    line.cycle_length_secs > 0
+}
+
+/// Minimum payment owed for a cycle opened with `utilized` outstanding (rounded up).
+pub fn minimum_payment(utilized: i128, min_payment_bps: u32) -> Result<i128, ContractError> {
+    if utilized <= 0 || min_payment_bps == 0 {
+        return Ok(0);
+    }
+    let due = apply_bps(utilized, min_payment_bps, Rounding::Up)?;
+    Ok(due.min(utilized))
+}
+
+/// Move `line`'s due date `cycles` cycles on.
+fn advance(line: &mut CreditLineData, cycles: u64) {
+    line.next_due_ts = line
+        .next_due_ts
+        .saturating_add(cycles.saturating_mul(line.cycle_length_secs));
+}
+
+/// Roll completed cycles forward while nothing is owed. Leaves an unpaid
+/// minimum untouched once its due date has passed.
+///
+/// Elapsed cycles are skipped arithmetically rather than one at a time, so a
+/// line left untouched for many cycles costs the same to bring up to date.
+pub fn roll_forward(env: &Env, line: &mut CreditLineData) {
+    if !is_scheduled(line) {
+        return;
+    }
+    let now = env.ledger().timestamp();
+    if now < line.next_due_ts || line.min_due_amount != 0 {
+        return;
+    }
+    if line.utilized_amount > 0 {
+        // Cycles due within the first-payment grace period bill nothing; the
+        // first bill falls at the first due date past it, if that has come.
+        let to_grace =
+            line.grace_until_ts.saturating_sub(line.next_due_ts) / line.cycle_length_secs + 1;
+        advance(line, to_grace.min(cycles_elapsed(line, now)));
+        if line.next_due_ts > line.grace_until_ts {
+            line.min_due_amount = amortization::bill(env, line, 0);
+        }
+        if line.min_due_amount > 0 || now < line.next_due_ts {
+            return;
+        }
+    }
+    // Nothing billed, and the balance cannot change in between: skip every
+    // remaining elapsed cycle at once.
+    advance(line, cycles_elapsed(line, now));
+}
+
+/// Cycles to advance so the due date passes `now`, which it has reached.
+fn cycles_elapsed(line: &CreditLineData, now: u64) -> u64 {
+    (now - line.next_due_ts) / line.cycle_length_secs + 1
+}
+
+/// Apply a repayment of `amount` against the current cycle's minimum.
+pub fn apply_repayment(line: &mut CreditLineData, amount: i128) {
+    if !is_scheduled(line) {
+        return;
+    }
+    line.min_due_amount = line.min_due_amount.saturating_sub(amount).max(0);
+    if line.utilized_amount == 0 {
+        line.min_due_amount = 0;
+    }
+    if line.min_due_amount == 0 {
+        line.missed_cycles = 0;
+    }
+}
+
+/// True once the due date has passed with a minimum still unpaid.
+pub fn is_overdue(env: &Env, line: &CreditLineData) -> bool {
+    is_scheduled(line) && line.min_due_amount > 0 && env.ledger().timestamp() >= line.next_due_ts
+}
+
+/// Next payment for `line`, rolled forward to the current ledger time. Lines
+/// without a schedule report only their grace period and prepayment penalty,
+/// and `None` when neither applies.
+pub fn next_payment(env: &Env, line: &CreditLineData) -> Option<NextPayment> {
+    let now = env.ledger().timestamp();
+    let prepayment_penalty = terms::prepayment_penalty(env, line, line.utilized_amount);
+    if !is_scheduled(line) && now >= line.grace_until_ts && prepayment_penalty == 0 {
+        return None;
+    }
+    let mut line = line.clone();
+    interest::accrue(env, &mut line);
+    roll_forward(env, &mut line);
+    Some(NextPayment {
+        due_ts: line.next_due_ts,
+        min_due_amount: line.min_due_amount,
+        overdue: is_overdue(env, &line),
+        grace_until_ts: line.grace_until_ts,
+        prepayment_penalty,
+    })
+}
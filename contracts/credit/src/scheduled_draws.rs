@@ -0,0 +1,90 @@
+//! Future-dated draws.
+//!
+//! A borrower schedules a draw of `amount` to run at or after
+//! `execute_after_ts`, e.g. for payroll-style disbursements. Once due, anyone
+//! can execute it; it then goes through every `draw_credit` check as of
+//! execution. The borrower can cancel it until then.
+
+use soroban_sdk::{contracttype, Address, Env};
+
+use crate::storage::{self, DataKey};
+
+/// A draw waiting for its execution time.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ScheduledDraw {
+    pub draw_id: u64,
+    pub amount: i128,
+    /// Earliest timestamp the draw can be executed at.
+    pub execute_after_ts: u64,
+}
+
+#[contracttype]
+#[derive(Clone)]
+pub enum ScheduledDrawKey {
+    /// Scheduled draw of a borrower by id (persistent).
+    Pending(Address, u64),
+    /// Last issued draw id (instance).
+    LastId,
+}
+
+fn pending_key(borrower: &Address, draw_id: u64) -> DataKey {
+    DataKey::ScheduledDraws(ScheduledDrawKey::Pending(borrower.clone(), draw_id))
+}
+
+pub fn get(env: &Env, borrower: &Address, draw_id: u64) -> Option<ScheduledDraw> {
+    storage::get_persistent(env, &pending_key(borrower, draw_id))
+}
+
+/// Schedule a draw of `amount` by `borrower` for `execute_after_ts`.
+///
+/// # Panics
+/// * `"amount must be positive"`
+/// * `"execution time must be in the future"`
+pub fn schedule(
+    env: &Env,
+    borrower: &Address,
+    amount: i128,
+    execute_after_ts: u64,
+) -> ScheduledDraw {
+    if amount <= 0 {
+        panic!("amount must be positive");
+    }
+    if execute_after_ts <= env.ledger().timestamp() {
+        panic!("execution time must be in the future");
+    }
+    let key = DataKey::ScheduledDraws(ScheduledDrawKey::LastId);
+    let draw_id: u64 = storage::get_instance(env, &key).unwrap_or(0) + 1;
+    storage::set_instance(env, &key, &draw_id);
+    let draw = ScheduledDraw {
+        draw_id,
+        amount,
+        execute_after_ts,
+    };
+    storage::set_persistent(env, &pending_key(borrower, draw_id), &draw);
+    draw
+}
+
+/// Remove and return `borrower`'s scheduled draw `draw_id` for execution.
+///
+/// # Panics
+/// * `"scheduled draw not found"`
+/// * `"scheduled draw not due"` – before `execute_after_ts`
+pub fn take_due(env: &Env, borrower: &Address, draw_id: u64) -> ScheduledDraw {
+    let draw = get(env, borrower, draw_id).expect("scheduled draw not found");
+    if env.ledger().timestamp() < draw.execute_after_ts {
+        panic!("scheduled draw not due");
+    }
+    storage::remove_persistent(env, &pending_key(borrower, draw_id));
+    draw
+}
+
+/// Remove and return `borrower`'s scheduled draw `draw_id` without executing it.
+///
+/// # Panics
+/// * `"scheduled draw not found"`
+pub fn cancel(env: &Env, borrower: &Address, draw_id: u64) -> ScheduledDraw {
+    let draw = get(env, borrower, draw_id).expect("scheduled draw not found");
+    storage::remove_persistent(env, &pending_key(borrower, draw_id));
+    draw
+}
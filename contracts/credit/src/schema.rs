@@ -0,0 +1,963 @@
+//! Versioned storage for credit lines.
+//!
+//! Lines are stored as a `StoredLine` so the layout of `CreditLineData` can
+//! change between releases. Entries written by an older layout — including the
+//! original, unwrapped `CreditLineDataV1` — are upgraded on first touch, or in
+//! bulk by the admin after an upgrade. Each upgrade step moves a line one
+//! layout forward.
+
+use soroban_sdk::{contracttype, Address, Env, TryFromVal, Val};
+
+use crate::storage::{self, DataKey};
+use crate::types::{CreditLineData, CreditStatus, InterestConfig, RateMode};
+
+/// Credit line layout of the first release.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct CreditLineDataV1 {
+    pub borrower: Address,
+    pub credit_limit: i128,
+    pub utilized_amount: i128,
+    pub interest_rate_bps: u32,
+    pub risk_score: u32,
+    pub status: CreditStatus,
+}
+
+/// Credit line layout before suspension reasons and expiries.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct CreditLineDataV2 {
+    pub borrower: Address,
+    /// Token the line is denominated in: drawn, repaid, and priced in this token.
+    pub token: Address,
+    pub credit_limit: i128,
+    pub utilized_amount: i128,
+    pub interest_rate_bps: u32,
+    pub risk_score: u32,
+    pub status: CreditStatus,
+    /// Billing cycle length in seconds; 0 when the line has no repayment schedule.
+    pub cycle_length_secs: u64,
+    /// Minimum payment per cycle in basis points of the utilized amount.
+    pub min_payment_bps: u32,
+    /// Timestamp by which `min_due_amount` must be repaid; 0 when unscheduled.
+    pub next_due_ts: u64,
+    /// Amount still owed for the current cycle.
+    pub min_due_amount: i128,
+    /// Consecutive cycles processed as missed; reset once arrears are repaid.
+    pub missed_cycles: u32,
+    /// Token deposited as collateral, if any.
+    pub collateral_token: Option<Address>,
+    /// Collateral held by the contract for this line.
+    pub collateral_amount: i128,
+    /// Interest accrued and not yet repaid.
+    pub accrued_interest: i128,
+    /// Timestamp up to which interest has been accrued.
+    pub last_accrual_ts: u64,
+    /// Repayments received while the line was Defaulted.
+    pub recovered_amount: i128,
+    /// Debt written off by the admin.
+    pub written_off_amount: i128,
+    /// Collector that bought the defaulted claim; `None` while the pool is the creditor.
+    pub creditor: Option<Address>,
+    /// Timestamp after which draws are rejected and only repayment is allowed; 0 for no term.
+    pub maturity_ts: u64,
+    pub rate_mode: RateMode,
+    /// Spread over the reference rate for Variable lines; 0 for Fixed lines.
+    pub rate_spread_bps: u32,
+    /// Credit limit in the price oracle's reference currency; 0 when the limit is in token units.
+    pub reference_limit: i128,
+    /// `utilized_amount` in reference units, valued at the last draw and reduced pro rata by repayments.
+    pub reference_utilized: i128,
+    /// Per-line minimum draw overriding the global limit; 0 to use the global limit.
+    pub min_draw_amount: i128,
+    /// Per-line maximum draw overriding the global limit; 0 to use the global limit.
+    pub max_draw_amount: i128,
+    /// Transferable position id minted for the line; 0 until minted.
+    pub position_id: u64,
+}
+
+/// Credit line layout before per-line restrictions.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct CreditLineDataV3 {
+    pub borrower: Address,
+    /// Token the line is denominated in: drawn, repaid, and priced in this token.
+    pub token: Address,
+    pub credit_limit: i128,
+    pub utilized_amount: i128,
+    pub interest_rate_bps: u32,
+    pub risk_score: u32,
+    pub status: CreditStatus,
+    /// Billing cycle length in seconds; 0 when the line has no repayment schedule.
+    pub cycle_length_secs: u64,
+    /// Minimum payment per cycle in basis points of the utilized amount.
+    pub min_payment_bps: u32,
+    /// Timestamp by which `min_due_amount` must be repaid; 0 when unscheduled.
+    pub next_due_ts: u64,
+    /// Amount still owed for the current cycle.
+    pub min_due_amount: i128,
+    /// Consecutive cycles processed as missed; reset once arrears are repaid.
+    pub missed_cycles: u32,
+    /// Token deposited as collateral, if any.
+    pub collateral_token: Option<Address>,
+    /// Collateral held by the contract for this line.
+    pub collateral_amount: i128,
+    /// Interest accrued and not yet repaid.
+    pub accrued_interest: i128,
+    /// Timestamp up to which interest has been accrued.
+    pub last_accrual_ts: u64,
+    /// Repayments received while the line was Defaulted.
+    pub recovered_amount: i128,
+    /// Debt written off by the admin.
+    pub written_off_amount: i128,
+    /// Collector that bought the defaulted claim; `None` while the pool is the creditor.
+    pub creditor: Option<Address>,
+    /// Timestamp after which draws are rejected and only repayment is allowed; 0 for no term.
+    pub maturity_ts: u64,
+    pub rate_mode: RateMode,
+    /// Spread over the reference rate for Variable lines; 0 for Fixed lines.
+    pub rate_spread_bps: u32,
+    /// Credit limit in the price oracle's reference currency; 0 when the limit is in token units.
+    pub reference_limit: i128,
+    /// `utilized_amount` in reference units, valued at the last draw and reduced pro rata by repayments.
+    pub reference_utilized: i128,
+    /// Per-line minimum draw overriding the global limit; 0 to use the global limit.
+    pub min_draw_amount: i128,
+    /// Per-line maximum draw overriding the global limit; 0 to use the global limit.
+    pub max_draw_amount: i128,
+    /// Transferable position id minted for the line; 0 until minted.
+    pub position_id: u64,
+    /// Why the line was suspended (see `suspension`); 0 when not suspended.
+    pub suspension_reason: u32,
+    /// Timestamp at which a suspension lifts on the next draw; 0 for an indefinite one.
+    pub suspended_until: u64,
+}
+
+/// Credit line layout before limit boosts.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct CreditLineDataV4 {
+    pub borrower: Address,
+    /// Token the line is denominated in: drawn, repaid, and priced in this token.
+    pub token: Address,
+    pub credit_limit: i128,
+    pub utilized_amount: i128,
+    pub interest_rate_bps: u32,
+    pub risk_score: u32,
+    pub status: CreditStatus,
+    /// Billing cycle length in seconds; 0 when the line has no repayment schedule.
+    pub cycle_length_secs: u64,
+    /// Minimum payment per cycle in basis points of the utilized amount.
+    pub min_payment_bps: u32,
+    /// Timestamp by which `min_due_amount` must be repaid; 0 when unscheduled.
+    pub next_due_ts: u64,
+    /// Amount still owed for the current cycle.
+    pub min_due_amount: i128,
+    /// Consecutive cycles processed as missed; reset once arrears are repaid.
+    pub missed_cycles: u32,
+    /// Token deposited as collateral, if any.
+    pub collateral_token: Option<Address>,
+    /// Collateral held by the contract for this line.
+    pub collateral_amount: i128,
+    /// Interest accrued and not yet repaid.
+    pub accrued_interest: i128,
+    /// Timestamp up to which interest has been accrued.
+    pub last_accrual_ts: u64,
+    /// Repayments received while the line was Defaulted.
+    pub recovered_amount: i128,
+    /// Debt written off by the admin.
+    pub written_off_amount: i128,
+    /// Collector that bought the defaulted claim; `None` while the pool is the creditor.
+    pub creditor: Option<Address>,
+    /// Timestamp after which draws are rejected and only repayment is allowed; 0 for no term.
+    pub maturity_ts: u64,
+    pub rate_mode: RateMode,
+    /// Spread over the reference rate for Variable lines; 0 for Fixed lines.
+    pub rate_spread_bps: u32,
+    /// Credit limit in the price oracle's reference currency; 0 when the limit is in token units.
+    pub reference_limit: i128,
+    /// `utilized_amount` in reference units, valued at the last draw and reduced pro rata by repayments.
+    pub reference_utilized: i128,
+    /// Per-line minimum draw overriding the global limit; 0 to use the global limit.
+    pub min_draw_amount: i128,
+    /// Per-line maximum draw overriding the global limit; 0 to use the global limit.
+    pub max_draw_amount: i128,
+    /// Transferable position id minted for the line; 0 until minted.
+    pub position_id: u64,
+    /// Why the line was suspended (see `suspension`); 0 when not suspended.
+    pub suspension_reason: u32,
+    /// Timestamp at which a suspension lifts on the next draw; 0 for an indefinite one.
+    pub suspended_until: u64,
+    /// Bitflags of operations frozen on the line (see `restrictions`).
+    pub restrictions: u32,
+}
+
+/// Credit line layout before delinquency tracking.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct CreditLineDataV5 {
+    pub borrower: Address,
+    /// Token the line is denominated in: drawn, repaid, and priced in this token.
+    pub token: Address,
+    pub credit_limit: i128,
+    pub utilized_amount: i128,
+    pub interest_rate_bps: u32,
+    pub risk_score: u32,
+    pub status: CreditStatus,
+    /// Billing cycle length in seconds; 0 when the line has no repayment schedule.
+    pub cycle_length_secs: u64,
+    /// Minimum payment per cycle in basis points of the utilized amount.
+    pub min_payment_bps: u32,
+    /// Timestamp by which `min_due_amount` must be repaid; 0 when unscheduled.
+    pub next_due_ts: u64,
+    /// Amount still owed for the current cycle.
+    pub min_due_amount: i128,
+    /// Consecutive cycles processed as missed; reset once arrears are repaid.
+    pub missed_cycles: u32,
+    /// Token deposited as collateral, if any.
+    pub collateral_token: Option<Address>,
+    /// Collateral held by the contract for this line.
+    pub collateral_amount: i128,
+    /// Interest accrued and not yet repaid.
+    pub accrued_interest: i128,
+    /// Timestamp up to which interest has been accrued.
+    pub last_accrual_ts: u64,
+    /// Repayments received while the line was Defaulted.
+    pub recovered_amount: i128,
+    /// Debt written off by the admin.
+    pub written_off_amount: i128,
+    /// Collector that bought the defaulted claim; `None` while the pool is the creditor.
+    pub creditor: Option<Address>,
+    /// Timestamp after which draws are rejected and only repayment is allowed; 0 for no term.
+    pub maturity_ts: u64,
+    pub rate_mode: RateMode,
+    /// Spread over the reference rate for Variable lines; 0 for Fixed lines.
+    pub rate_spread_bps: u32,
+    /// Credit limit in the price oracle's reference currency; 0 when the limit is in token units.
+    pub reference_limit: i128,
+    /// `utilized_amount` in reference units, valued at the last draw and reduced pro rata by repayments.
+    pub reference_utilized: i128,
+    /// Per-line minimum draw overriding the global limit; 0 to use the global limit.
+    pub min_draw_amount: i128,
+    /// Per-line maximum draw overriding the global limit; 0 to use the global limit.
+    pub max_draw_amount: i128,
+    /// Transferable position id minted for the line; 0 until minted.
+    pub position_id: u64,
+    /// Why the line was suspended (see `suspension`); 0 when not suspended.
+    pub suspension_reason: u32,
+    /// Timestamp at which a suspension lifts on the next draw; 0 for an indefinite one.
+    pub suspended_until: u64,
+    /// Bitflags of operations frozen on the line (see `restrictions`).
+    pub restrictions: u32,
+    /// Temporary extra limit granted by the risk engine; 0 when none.
+    pub limit_boost: i128,
+    /// Timestamp at which `limit_boost` stops counting; 0 when no boost.
+    pub boost_expires_at: u64,
+}
+
+/// Credit line layout before interest conventions.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct CreditLineDataV6 {
+    pub borrower: Address,
+    /// Token the line is denominated in: drawn, repaid, and priced in this token.
+    pub token: Address,
+    pub credit_limit: i128,
+    pub utilized_amount: i128,
+    pub interest_rate_bps: u32,
+    pub risk_score: u32,
+    pub status: CreditStatus,
+    /// Billing cycle length in seconds; 0 when the line has no repayment schedule.
+    pub cycle_length_secs: u64,
+    /// Minimum payment per cycle in basis points of the utilized amount.
+    pub min_payment_bps: u32,
+    /// Timestamp by which `min_due_amount` must be repaid; 0 when unscheduled.
+    pub next_due_ts: u64,
+    /// Amount still owed for the current cycle.
+    pub min_due_amount: i128,
+    /// Consecutive cycles processed as missed; reset once arrears are repaid.
+    pub missed_cycles: u32,
+    /// Token deposited as collateral, if any.
+    pub collateral_token: Option<Address>,
+    /// Collateral held by the contract for this line.
+    pub collateral_amount: i128,
+    /// Interest accrued and not yet repaid.
+    pub accrued_interest: i128,
+    /// Timestamp up to which interest has been accrued.
+    pub last_accrual_ts: u64,
+    /// Repayments received while the line was Defaulted.
+    pub recovered_amount: i128,
+    /// Debt written off by the admin.
+    pub written_off_amount: i128,
+    /// Collector that bought the defaulted claim; `None` while the pool is the creditor.
+    pub creditor: Option<Address>,
+    /// Timestamp after which draws are rejected and only repayment is allowed; 0 for no term.
+    pub maturity_ts: u64,
+    pub rate_mode: RateMode,
+    /// Spread over the reference rate for Variable lines; 0 for Fixed lines.
+    pub rate_spread_bps: u32,
+    /// Credit limit in the price oracle's reference currency; 0 when the limit is in token units.
+    pub reference_limit: i128,
+    /// `utilized_amount` in reference units, valued at the last draw and reduced pro rata by repayments.
+    pub reference_utilized: i128,
+    /// Per-line minimum draw overriding the global limit; 0 to use the global limit.
+    pub min_draw_amount: i128,
+    /// Per-line maximum draw overriding the global limit; 0 to use the global limit.
+    pub max_draw_amount: i128,
+    /// Transferable position id minted for the line; 0 until minted.
+    pub position_id: u64,
+    /// Why the line was suspended (see `suspension`); 0 when not suspended.
+    pub suspension_reason: u32,
+    /// Timestamp at which a suspension lifts on the next draw; 0 for an indefinite one.
+    pub suspended_until: u64,
+    /// Bitflags of operations frozen on the line (see `restrictions`).
+    pub restrictions: u32,
+    /// Temporary extra limit granted by the risk engine; 0 when none.
+    pub limit_boost: i128,
+    /// Timestamp at which `limit_boost` stops counting; 0 when no boost.
+    pub boost_expires_at: u64,
+    /// Due date of the earliest unpaid cycle once a missed payment is processed; 0 when current.
+    pub delinquent_since_ts: u64,
+}
+
+/// Credit line layout before promotional rates.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct CreditLineDataV7 {
+    pub borrower: Address,
+    /// Token the line is denominated in: drawn, repaid, and priced in this token.
+    pub token: Address,
+    pub credit_limit: i128,
+    pub utilized_amount: i128,
+    pub interest_rate_bps: u32,
+    pub risk_score: u32,
+    pub status: CreditStatus,
+    /// Billing cycle length in seconds; 0 when the line has no repayment schedule.
+    pub cycle_length_secs: u64,
+    /// Minimum payment per cycle in basis points of the utilized amount.
+    pub min_payment_bps: u32,
+    /// Timestamp by which `min_due_amount` must be repaid; 0 when unscheduled.
+    pub next_due_ts: u64,
+    /// Amount still owed for the current cycle.
+    pub min_due_amount: i128,
+    /// Consecutive cycles processed as missed; reset once arrears are repaid.
+    pub missed_cycles: u32,
+    /// Token deposited as collateral, if any.
+    pub collateral_token: Option<Address>,
+    /// Collateral held by the contract for this line.
+    pub collateral_amount: i128,
+    /// Interest accrued and not yet repaid.
+    pub accrued_interest: i128,
+    /// Timestamp up to which interest has been accrued.
+    pub last_accrual_ts: u64,
+    /// Repayments received while the line was Defaulted.
+    pub recovered_amount: i128,
+    /// Debt written off by the admin.
+    pub written_off_amount: i128,
+    /// Collector that bought the defaulted claim; `None` while the pool is the creditor.
+    pub creditor: Option<Address>,
+    /// Timestamp after which draws are rejected and only repayment is allowed; 0 for no term.
+    pub maturity_ts: u64,
+    pub rate_mode: RateMode,
+    /// Spread over the reference rate for Variable lines; 0 for Fixed lines.
+    pub rate_spread_bps: u32,
+    /// Credit limit in the price oracle's reference currency; 0 when the limit is in token units.
+    pub reference_limit: i128,
+    /// `utilized_amount` in reference units, valued at the last draw and reduced pro rata by repayments.
+    pub reference_utilized: i128,
+    /// Per-line minimum draw overriding the global limit; 0 to use the global limit.
+    pub min_draw_amount: i128,
+    /// Per-line maximum draw overriding the global limit; 0 to use the global limit.
+    pub max_draw_amount: i128,
+    /// Transferable position id minted for the line; 0 until minted.
+    pub position_id: u64,
+    /// Why the line was suspended (see `suspension`); 0 when not suspended.
+    pub suspension_reason: u32,
+    /// Timestamp at which a suspension lifts on the next draw; 0 for an indefinite one.
+    pub suspended_until: u64,
+    /// Bitflags of operations frozen on the line (see `restrictions`).
+    pub restrictions: u32,
+    /// Temporary extra limit granted by the risk engine; 0 when none.
+    pub limit_boost: i128,
+    /// Timestamp at which `limit_boost` stops counting; 0 when no boost.
+    pub boost_expires_at: u64,
+    /// Due date of the earliest unpaid cycle once a missed payment is processed; 0 when current.
+    pub delinquent_since_ts: u64,
+    /// Compounding and day-count convention used to accrue interest.
+    pub interest_config: InterestConfig,
+}
+
+/// Credit line layout before rate bounds.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct CreditLineDataV8 {
+    pub borrower: Address,
+    /// Token the line is denominated in: drawn, repaid, and priced in this token.
+    pub token: Address,
+    pub credit_limit: i128,
+    pub utilized_amount: i128,
+    pub interest_rate_bps: u32,
+    pub risk_score: u32,
+    pub status: CreditStatus,
+    /// Billing cycle length in seconds; 0 when the line has no repayment schedule.
+    pub cycle_length_secs: u64,
+    /// Minimum payment per cycle in basis points of the utilized amount.
+    pub min_payment_bps: u32,
+    /// Timestamp by which `min_due_amount` must be repaid; 0 when unscheduled.
+    pub next_due_ts: u64,
+    /// Amount still owed for the current cycle.
+    pub min_due_amount: i128,
+    /// Consecutive cycles processed as missed; reset once arrears are repaid.
+    pub missed_cycles: u32,
+    /// Token deposited as collateral, if any.
+    pub collateral_token: Option<Address>,
+    /// Collateral held by the contract for this line.
+    pub collateral_amount: i128,
+    /// Interest accrued and not yet repaid.
+    pub accrued_interest: i128,
+    /// Timestamp up to which interest has been accrued.
+    pub last_accrual_ts: u64,
+    /// Repayments received while the line was Defaulted.
+    pub recovered_amount: i128,
+    /// Debt written off by the admin.
+    pub written_off_amount: i128,
+    /// Collector that bought the defaulted claim; `None` while the pool is the creditor.
+    pub creditor: Option<Address>,
+    /// Timestamp after which draws are rejected and only repayment is allowed; 0 for no term.
+    pub maturity_ts: u64,
+    pub rate_mode: RateMode,
+    /// Spread over the reference rate for Variable lines; 0 for Fixed lines.
+    pub rate_spread_bps: u32,
+    /// Credit limit in the price oracle's reference currency; 0 when the limit is in token units.
+    pub reference_limit: i128,
+    /// `utilized_amount` in reference units, valued at the last draw and reduced pro rata by repayments.
+    pub reference_utilized: i128,
+    /// Per-line minimum draw overriding the global limit; 0 to use the global limit.
+    pub min_draw_amount: i128,
+    /// Per-line maximum draw overriding the global limit; 0 to use the global limit.
+    pub max_draw_amount: i128,
+    /// Transferable position id minted for the line; 0 until minted.
+    pub position_id: u64,
+    /// Why the line was suspended (see `suspension`); 0 when not suspended.
+    pub suspension_reason: u32,
+    /// Timestamp at which a suspension lifts on the next draw; 0 for an indefinite one.
+    pub suspended_until: u64,
+    /// Bitflags of operations frozen on the line (see `restrictions`).
+    pub restrictions: u32,
+    /// Temporary extra limit granted by the risk engine; 0 when none.
+    pub limit_boost: i128,
+    /// Timestamp at which `limit_boost` stops counting; 0 when no boost.
+    pub boost_expires_at: u64,
+    /// Due date of the earliest unpaid cycle once a missed payment is processed; 0 when current.
+    pub delinquent_since_ts: u64,
+    /// Compounding and day-count convention used to accrue interest.
+    pub interest_config: InterestConfig,
+    /// Promotional rate accrued instead of `interest_rate_bps` until `promo_until_ts`.
+    pub promo_rate_bps: u32,
+    /// Timestamp the promotional rate ends; 0 when there is none.
+    pub promo_until_ts: u64,
+}
+
+/// Credit line layout before repayment terms.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct CreditLineDataV9 {
+    pub borrower: Address,
+    /// Token the line is denominated in: drawn, repaid, and priced in this token.
+    pub token: Address,
+    pub credit_limit: i128,
+    pub utilized_amount: i128,
+    pub interest_rate_bps: u32,
+    pub risk_score: u32,
+    pub status: CreditStatus,
+    /// Billing cycle length in seconds; 0 when the line has no repayment schedule.
+    pub cycle_length_secs: u64,
+    /// Minimum payment per cycle in basis points of the utilized amount.
+    pub min_payment_bps: u32,
+    /// Timestamp by which `min_due_amount` must be repaid; 0 when unscheduled.
+    pub next_due_ts: u64,
+    /// Amount still owed for the current cycle.
+    pub min_due_amount: i128,
+    /// Consecutive cycles processed as missed; reset once arrears are repaid.
+    pub missed_cycles: u32,
+    /// Token deposited as collateral, if any.
+    pub collateral_token: Option<Address>,
+    /// Collateral held by the contract for this line.
+    pub collateral_amount: i128,
+    /// Interest accrued and not yet repaid.
+    pub accrued_interest: i128,
+    /// Timestamp up to which interest has been accrued.
+    pub last_accrual_ts: u64,
+    /// Repayments received while the line was Defaulted.
+    pub recovered_amount: i128,
+    /// Debt written off by the admin.
+    pub written_off_amount: i128,
+    /// Collector that bought the defaulted claim; `None` while the pool is the creditor.
+    pub creditor: Option<Address>,
+    /// Timestamp after which draws are rejected and only repayment is allowed; 0 for no term.
+    pub maturity_ts: u64,
+    pub rate_mode: RateMode,
+    /// Spread over the reference rate for Variable lines; 0 for Fixed lines.
+    pub rate_spread_bps: u32,
+    /// Credit limit in the price oracle's reference currency; 0 when the limit is in token units.
+    pub reference_limit: i128,
+    /// `utilized_amount` in reference units, valued at the last draw and reduced pro rata by repayments.
+    pub reference_utilized: i128,
+    /// Per-line minimum draw overriding the global limit; 0 to use the global limit.
+    pub min_draw_amount: i128,
+    /// Per-line maximum draw overriding the global limit; 0 to use the global limit.
+    pub max_draw_amount: i128,
+    /// Transferable position id minted for the line; 0 until minted.
+    pub position_id: u64,
+    /// Why the line was suspended (see `suspension`); 0 when not suspended.
+    pub suspension_reason: u32,
+    /// Timestamp at which a suspension lifts on the next draw; 0 for an indefinite one.
+    pub suspended_until: u64,
+    /// Bitflags of operations frozen on the line (see `restrictions`).
+    pub restrictions: u32,
+    /// Temporary extra limit granted by the risk engine; 0 when none.
+    pub limit_boost: i128,
+    /// Timestamp at which `limit_boost` stops counting; 0 when no boost.
+    pub boost_expires_at: u64,
+    /// Due date of the earliest unpaid cycle once a missed payment is processed; 0 when current.
+    pub delinquent_since_ts: u64,
+    /// Compounding and day-count convention used to accrue interest.
+    pub interest_config: InterestConfig,
+    /// Promotional rate accrued instead of `interest_rate_bps` until `promo_until_ts`.
+    pub promo_rate_bps: u32,
+    /// Timestamp the promotional rate ends; 0 when there is none.
+    pub promo_until_ts: u64,
+    /// Lowest rate the line can be priced at; 0 for no floor.
+    pub rate_floor_bps: u32,
+    /// Highest rate the line can be priced at; 0 for no cap.
+    pub rate_cap_bps: u32,
+}
+
+/// Persistent representation of a credit line, tagged with its layout version.
+// Contract types cannot be boxed; the size gap between layouts is expected.
+#[allow(clippy::large_enum_variant)]
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum StoredLine {
+    V1(CreditLineDataV1),
+    V2(CreditLineDataV2),
+    V3(CreditLineDataV3),
+    V4(CreditLineDataV4),
+    V5(CreditLineDataV5),
+    V6(CreditLineDataV6),
+    V7(CreditLineDataV7),
+    V8(CreditLineDataV8),
+    V9(CreditLineDataV9),
+    V10(CreditLineData),
+}
+
+fn load(env: &Env, borrower: &Address) -> Option<StoredLine> {
+    let raw: Val = storage::get_persistent(env, &DataKey::Line(borrower.clone()))?;
+    let stored = StoredLine::try_from_val(env, &raw)
+        .or_else(|_| CreditLineDataV1::try_from_val(env, &raw).map(StoredLine::V1))
+        .unwrap_or_else(|_| panic!("unrecognized credit line layout"));
+    Some(stored)
+}
+
+/// Bring a stored line up to the current layout, one layout at a time.
+fn upgrade(env: &Env, stored: StoredLine) -> CreditLineData {
+    match stored {
+        StoredLine::V10(line) => line,
+        StoredLine::V9(line) => upgrade_v9(line),
+        StoredLine::V8(line) => upgrade(env, StoredLine::V9(upgrade_v8(line))),
+        StoredLine::V7(line) => upgrade(env, StoredLine::V8(upgrade_v7(line))),
+        StoredLine::V6(line) => upgrade(env, StoredLine::V7(upgrade_v6(line))),
+        StoredLine::V5(line) => upgrade(env, StoredLine::V6(upgrade_v5(line))),
+        StoredLine::V4(line) => upgrade(env, StoredLine::V5(upgrade_v4(line))),
+        StoredLine::V3(line) => upgrade(env, StoredLine::V4(upgrade_v3(line))),
+        StoredLine::V2(line) => upgrade(env, StoredLine::V3(upgrade_v2(line))),
+        StoredLine::V1(line) => upgrade(env, StoredLine::V2(upgrade_v1(env, line))),
+    }
+}
+
+/// Read the line under `borrower`, upgraded to the current layout. The flag is
+/// true when the stored entry is already current.
+///
+/// # Panics
+/// * `"unrecognized credit line layout"`
+pub fn read(env: &Env, borrower: &Address) -> Option<(CreditLineData, bool)> {
+    let stored = load(env, borrower)?;
+    let current = matches!(stored, StoredLine::V10(_));
+    Some((upgrade(env, stored), current))
+}
+
+/// The line under `borrower` as the running totals last counted it, upgraded
+/// to the current layout. `None` for a V1 entry: those predate the totals, so
+/// the line counts as new when it is next saved. Every later layout was
+/// written with the totals in place and is already counted.
+///
+/// # Panics
+/// * `"unrecognized credit line layout"`
+pub fn read_counted(env: &Env, borrower: &Address) -> Option<CreditLineData> {
+    match load(env, borrower)? {
+        StoredLine::V1(_) => None,
+        stored => Some(upgrade(env, stored)),
+    }
+}
+
+pub fn write(env: &Env, line: &CreditLineData) {
+    storage::set_persistent(
+        env,
+        &DataKey::Line(line.borrower.clone()),
+        &StoredLine::V10(line.clone()),
+    );
+}
+
+/// A V1 line is a fixed-rate, unscheduled line in the default token with no
+/// interest accrued yet.
+fn upgrade_v1(env: &Env, line: CreditLineDataV1) -> CreditLineDataV2 {
+    CreditLineDataV2 {
+        borrower: line.borrower,
+        token: crate::require_token(env),
+        credit_limit: line.credit_limit,
+        utilized_amount: line.utilized_amount,
+        interest_rate_bps: line.interest_rate_bps,
+        risk_score: line.risk_score,
+        status: line.status,
+        cycle_length_secs: 0,
+        min_payment_bps: 0,
+        next_due_ts: 0,
+        min_due_amount: 0,
+        missed_cycles: 0,
+        collateral_token: None,
+        collateral_amount: 0,
+        accrued_interest: 0,
+        last_accrual_ts: env.ledger().timestamp(),
+        recovered_amount: 0,
+        written_off_amount: 0,
+        creditor: None,
+        maturity_ts: 0,
+        rate_mode: RateMode::Fixed,
+        rate_spread_bps: 0,
+        reference_limit: 0,
+        reference_utilized: 0,
+        min_draw_amount: 0,
+        max_draw_amount: 0,
+        position_id: 0,
+    }
+}
+
+/// A V2 line has no suspension reason or expiry.
+fn upgrade_v2(line: CreditLineDataV2) -> CreditLineDataV3 {
+    CreditLineDataV3 {
+        borrower: line.borrower,
+        token: line.token,
+        credit_limit: line.credit_limit,
+        utilized_amount: line.utilized_amount,
+        interest_rate_bps: line.interest_rate_bps,
+        risk_score: line.risk_score,
+        status: line.status,
+        cycle_length_secs: line.cycle_length_secs,
+        min_payment_bps: line.min_payment_bps,
+        next_due_ts: line.next_due_ts,
+        min_due_amount: line.min_due_amount,
+        missed_cycles: line.missed_cycles,
+        collateral_token: line.collateral_token,
+        collateral_amount: line.collateral_amount,
+        accrued_interest: line.accrued_interest,
+        last_accrual_ts: line.last_accrual_ts,
+        recovered_amount: line.recovered_amount,
+        written_off_amount: line.written_off_amount,
+        creditor: line.creditor,
+        maturity_ts: line.maturity_ts,
+        rate_mode: line.rate_mode,
+        rate_spread_bps: line.rate_spread_bps,
+        reference_limit: line.reference_limit,
+        reference_utilized: line.reference_utilized,
+        min_draw_amount: line.min_draw_amount,
+        max_draw_amount: line.max_draw_amount,
+        position_id: line.position_id,
+        suspension_reason: 0,
+        suspended_until: 0,
+    }
+}
+
+/// A V3 line has no restrictions.
+fn upgrade_v3(line: CreditLineDataV3) -> CreditLineDataV4 {
+    CreditLineDataV4 {
+        borrower: line.borrower,
+        token: line.token,
+        credit_limit: line.credit_limit,
+        utilized_amount: line.utilized_amount,
+        interest_rate_bps: line.interest_rate_bps,
+        risk_score: line.risk_score,
+        status: line.status,
+        cycle_length_secs: line.cycle_length_secs,
+        min_payment_bps: line.min_payment_bps,
+        next_due_ts: line.next_due_ts,
+        min_due_amount: line.min_due_amount,
+        missed_cycles: line.missed_cycles,
+        collateral_token: line.collateral_token,
+        collateral_amount: line.collateral_amount,
+        accrued_interest: line.accrued_interest,
+        last_accrual_ts: line.last_accrual_ts,
+        recovered_amount: line.recovered_amount,
+        written_off_amount: line.written_off_amount,
+        creditor: line.creditor,
+        maturity_ts: line.maturity_ts,
+        rate_mode: line.rate_mode,
+        rate_spread_bps: line.rate_spread_bps,
+        reference_limit: line.reference_limit,
+        reference_utilized: line.reference_utilized,
+        min_draw_amount: line.min_draw_amount,
+        max_draw_amount: line.max_draw_amount,
+        position_id: line.position_id,
+        suspension_reason: line.suspension_reason,
+        suspended_until: line.suspended_until,
+        restrictions: 0,
+    }
+}
+
+/// A V4 line has no limit boost.
+fn upgrade_v4(line: CreditLineDataV4) -> CreditLineDataV5 {
+    CreditLineDataV5 {
+        borrower: line.borrower,
+        token: line.token,
+        credit_limit: line.credit_limit,
+        utilized_amount: line.utilized_amount,
+        interest_rate_bps: line.interest_rate_bps,
+        risk_score: line.risk_score,
+        status: line.status,
+        cycle_length_secs: line.cycle_length_secs,
+        min_payment_bps: line.min_payment_bps,
+        next_due_ts: line.next_due_ts,
+        min_due_amount: line.min_due_amount,
+        missed_cycles: line.missed_cycles,
+        collateral_token: line.collateral_token,
+        collateral_amount: line.collateral_amount,
+        accrued_interest: line.accrued_interest,
+        last_accrual_ts: line.last_accrual_ts,
+        recovered_amount: line.recovered_amount,
+        written_off_amount: line.written_off_amount,
+        creditor: line.creditor,
+        maturity_ts: line.maturity_ts,
+        rate_mode: line.rate_mode,
+        rate_spread_bps: line.rate_spread_bps,
+        reference_limit: line.reference_limit,
+        reference_utilized: line.reference_utilized,
+        min_draw_amount: line.min_draw_amount,
+        max_draw_amount: line.max_draw_amount,
+        position_id: line.position_id,
+        suspension_reason: line.suspension_reason,
+        suspended_until: line.suspended_until,
+        restrictions: line.restrictions,
+        limit_boost: 0,
+        boost_expires_at: 0,
+    }
+}
+
+/// A V5 line has never been marked delinquent.
+fn upgrade_v5(line: CreditLineDataV5) -> CreditLineDataV6 {
+    CreditLineDataV6 {
+        borrower: line.borrower,
+        token: line.token,
+        credit_limit: line.credit_limit,
+        utilized_amount: line.utilized_amount,
+        interest_rate_bps: line.interest_rate_bps,
+        risk_score: line.risk_score,
+        status: line.status,
+        cycle_length_secs: line.cycle_length_secs,
+        min_payment_bps: line.min_payment_bps,
+        next_due_ts: line.next_due_ts,
+        min_due_amount: line.min_due_amount,
+        missed_cycles: line.missed_cycles,
+        collateral_token: line.collateral_token,
+        collateral_amount: line.collateral_amount,
+        accrued_interest: line.accrued_interest,
+        last_accrual_ts: line.last_accrual_ts,
+        recovered_amount: line.recovered_amount,
+        written_off_amount: line.written_off_amount,
+        creditor: line.creditor,
+        maturity_ts: line.maturity_ts,
+        rate_mode: line.rate_mode,
+        rate_spread_bps: line.rate_spread_bps,
+        reference_limit: line.reference_limit,
+        reference_utilized: line.reference_utilized,
+        min_draw_amount: line.min_draw_amount,
+        max_draw_amount: line.max_draw_amount,
+        position_id: line.position_id,
+        suspension_reason: line.suspension_reason,
+        suspended_until: line.suspended_until,
+        restrictions: line.restrictions,
+        limit_boost: line.limit_boost,
+        boost_expires_at: line.boost_expires_at,
+        delinquent_since_ts: 0,
+    }
+}
+
+/// A V6 line accrues simple interest over a 365-day year.
+fn upgrade_v6(line: CreditLineDataV6) -> CreditLineDataV7 {
+    CreditLineDataV7 {
+        borrower: line.borrower,
+        token: line.token,
+        credit_limit: line.credit_limit,
+        utilized_amount: line.utilized_amount,
+        interest_rate_bps: line.interest_rate_bps,
+        risk_score: line.risk_score,
+        status: line.status,
+        cycle_length_secs: line.cycle_length_secs,
+        min_payment_bps: line.min_payment_bps,
+        next_due_ts: line.next_due_ts,
+        min_due_amount: line.min_due_amount,
+        missed_cycles: line.missed_cycles,
+        collateral_token: line.collateral_token,
+        collateral_amount: line.collateral_amount,
+        accrued_interest: line.accrued_interest,
+        last_accrual_ts: line.last_accrual_ts,
+        recovered_amount: line.recovered_amount,
+        written_off_amount: line.written_off_amount,
+        creditor: line.creditor,
+        maturity_ts: line.maturity_ts,
+        rate_mode: line.rate_mode,
+        rate_spread_bps: line.rate_spread_bps,
+        reference_limit: line.reference_limit,
+        reference_utilized: line.reference_utilized,
+        min_draw_amount: line.min_draw_amount,
+        max_draw_amount: line.max_draw_amount,
+        position_id: line.position_id,
+        suspension_reason: line.suspension_reason,
+        suspended_until: line.suspended_until,
+        restrictions: line.restrictions,
+        limit_boost: line.limit_boost,
+        boost_expires_at: line.boost_expires_at,
+        delinquent_since_ts: line.delinquent_since_ts,
+        interest_config: InterestConfig::default(),
+    }
+}
+
+/// A V7 line has no promotional rate.
+fn upgrade_v7(line: CreditLineDataV7) -> CreditLineDataV8 {
+    CreditLineDataV8 {
+        borrower: line.borrower,
+        token: line.token,
+        credit_limit: line.credit_limit,
+        utilized_amount: line.utilized_amount,
+        interest_rate_bps: line.interest_rate_bps,
+        risk_score: line.risk_score,
+        status: line.status,
+        cycle_length_secs: line.cycle_length_secs,
+        min_payment_bps: line.min_payment_bps,
+        next_due_ts: line.next_due_ts,
+        min_due_amount: line.min_due_amount,
+        missed_cycles: line.missed_cycles,
+        collateral_token: line.collateral_token,
+        collateral_amount: line.collateral_amount,
+        accrued_interest: line.accrued_interest,
+        last_accrual_ts: line.last_accrual_ts,
+        recovered_amount: line.recovered_amount,
+        written_off_amount: line.written_off_amount,
+        creditor: line.creditor,
+        maturity_ts: line.maturity_ts,
+        rate_mode: line.rate_mode,
+        rate_spread_bps: line.rate_spread_bps,
+        reference_limit: line.reference_limit,
+        reference_utilized: line.reference_utilized,
+        min_draw_amount: line.min_draw_amount,
+        max_draw_amount: line.max_draw_amount,
+        position_id: line.position_id,
+        suspension_reason: line.suspension_reason,
+        suspended_until: line.suspended_until,
+        restrictions: line.restrictions,
+        limit_boost: line.limit_boost,
+        boost_expires_at: line.boost_expires_at,
+        delinquent_since_ts: line.delinquent_since_ts,
+        interest_config: line.interest_config,
+        promo_rate_bps: 0,
+        promo_until_ts: 0,
+    }
+}
+
+/// A V8 line has no rate bounds.
+fn upgrade_v8(line: CreditLineDataV8) -> CreditLineDataV9 {
+    CreditLineDataV9 {
+        borrower: line.borrower,
+        token: line.token,
+        credit_limit: line.credit_limit,
+        utilized_amount: line.utilized_amount,
+        interest_rate_bps: line.interest_rate_bps,
+        risk_score: line.risk_score,
+        status: line.status,
+        cycle_length_secs: line.cycle_length_secs,
+        min_payment_bps: line.min_payment_bps,
+        next_due_ts: line.next_due_ts,
+        min_due_amount: line.min_due_amount,
+        missed_cycles: line.missed_cycles,
+        collateral_token: line.collateral_token,
+        collateral_amount: line.collateral_amount,
+        accrued_interest: line.accrued_interest,
+        last_accrual_ts: line.last_accrual_ts,
+        recovered_amount: line.recovered_amount,
+        written_off_amount: line.written_off_amount,
+        creditor: line.creditor,
+        maturity_ts: line.maturity_ts,
+        rate_mode: line.rate_mode,
+        rate_spread_bps: line.rate_spread_bps,
+        reference_limit: line.reference_limit,
+        reference_utilized: line.reference_utilized,
+        min_draw_amount: line.min_draw_amount,
+        max_draw_amount: line.max_draw_amount,
+        position_id: line.position_id,
+        suspension_reason: line.suspension_reason,
+        suspended_until: line.suspended_until,
+        restrictions: line.restrictions,
+        limit_boost: line.limit_boost,
+        boost_expires_at: line.boost_expires_at,
+        delinquent_since_ts: line.delinquent_since_ts,
+        interest_config: line.interest_config,
+        promo_rate_bps: line.promo_rate_bps,
+        promo_until_ts: line.promo_until_ts,
+        rate_floor_bps: 0,
+        rate_cap_bps: 0,
+    }
+}
+
+/// A V9 line has no grace period or prepayment penalty.
+fn upgrade_v9(line: CreditLineDataV9) -> CreditLineData {
+    CreditLineData {
+        borrower: line.borrower,
+        token: line.token,
+        credit_limit: line.credit_limit,
+        utilized_amount: line.utilized_amount,
+        interest_rate_bps: line.interest_rate_bps,
+        risk_score: line.risk_score,
+        status: line.status,
+        cycle_length_secs: line.cycle_length_secs,
+        min_payment_bps: line.min_payment_bps,
+        next_due_ts: line.next_due_ts,
+        min_due_amount: line.min_due_amount,
+        missed_cycles: line.missed_cycles,
+        collateral_token: line.collateral_token,
+        collateral_amount: line.collateral_amount,
+        accrued_interest: line.accrued_interest,
+        last_accrual_ts: line.last_accrual_ts,
+        recovered_amount: line.recovered_amount,
+        written_off_amount: line.written_off_amount,
+        creditor: line.creditor,
+        maturity_ts: line.maturity_ts,
+        rate_mode: line.rate_mode,
+        rate_spread_bps: line.rate_spread_bps,
+        reference_limit: line.reference_limit,
+        reference_utilized: line.reference_utilized,
+        min_draw_amount: line.min_draw_amount,
+        max_draw_amount: line.max_draw_amount,
+        position_id: line.position_id,
+        suspension_reason: line.suspension_reason,
+        suspended_until: line.suspended_until,
+        restrictions: line.restrictions,
+        limit_boost: line.limit_boost,
+        boost_expires_at: line.boost_expires_at,
+        delinquent_since_ts: line.delinquent_since_ts,
+        interest_config: line.interest_config,
+        promo_rate_bps: line.promo_rate_bps,
+        promo_until_ts: line.promo_until_ts,
+        rate_floor_bps: line.rate_floor_bps,
+        rate_cap_bps: line.rate_cap_bps,
+        grace_until_ts: 0,
+        prepayment_penalty_bps: 0,
+    }
+}
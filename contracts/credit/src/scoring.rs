@@ -0,0 +1,56 @@
+//! On-chain risk scoring hook.
+//!
+//! Once the admin configures a scoring contract, the Credit contract stops
+//! trusting caller-supplied risk scores: opening a line takes the score from
+//! the scoring contract, and every draw refreshes the line's stored score from
+//! it. A scoring contract that reverts blocks opens and draws until it is
+//! fixed or replaced.
+
+use soroban_sdk::{contractclient, Address, Env, Symbol};
+
+use crate::storage::{self, DataKey};
+use crate::types::CreditLineData;
+
+/// Interface a scoring contract must implement.
+/// Only the generated `ScoringContractClient` is used.
+#[allow(dead_code)]
+#[contractclient(name = "ScoringContractClient")]
+pub trait ScoringContract {
+    /// Risk score of `borrower`, from 0 to 100.
+    fn score(env: Env, borrower: Address) -> u32;
+}
+
+/// Instance storage key for the scoring contract address.
+fn scoring_key(env: &Env) -> DataKey {
+    DataKey::Config(Symbol::new(env, "scoring"))
+}
+
+pub fn get_contract(env: &Env) -> Option<Address> {
+    storage::get_instance(env, &scoring_key(env))
+}
+
+pub fn set_contract(env: &Env, contract: &Address) {
+    storage::set_instance(env, &scoring_key(env), contract);
+}
+
+/// Score of `borrower` from the scoring contract, or `supplied` if none is
+/// configured.
+///
+/// # Panics
+/// * `"risk_score exceeds maximum"` – the scoring contract returned more than 100
+pub fn resolve(env: &Env, borrower: &Address, supplied: u32) -> u32 {
+    let Some(contract) = get_contract(env) else {
+        return supplied;
+    };
+    let score = ScoringContractClient::new(env, &contract).score(borrower);
+    if score > crate::MAX_RISK_SCORE {
+        panic!("risk_score exceeds maximum");
+    }
+    score
+}
+
+/// Refresh `line`'s risk score from the scoring contract, if one is configured.
+/// The caller saves the line.
+pub fn refresh(env: &Env, line: &mut CreditLineData) {
+    line.risk_score = resolve(env, &line.borrower, line.risk_score);
+}
@@ -0,0 +1,84 @@
+//! Risk engine operations authorized by an off-chain ed25519 signature.
+//!
+//! Instead of holding a hot account with the `RiskEngine` role, the backend
+//! signs operation payloads with a key whose public half is registered by the
+//! admin. The signed message is the XDR encoding of
+//! `(contract_address, payload)`, so a signature is bound to this contract.
+//! Each payload carries a nonce that may be consumed only once.
+
+use soroban_sdk::{contracttype, xdr::ToXdr, Address, BytesN, Env, IntoVal, Symbol, Val};
+
+use crate::storage::{self, DataKey};
+use crate::types::{RateMode, RepaymentSchedule};
+
+/// Signed request to open a credit line.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct SignedOpenCreditLine {
+    pub borrower: Address,
+    pub credit_limit: i128,
+    pub interest_rate_bps: u32,
+    pub risk_score: u32,
+    /// Billing cycle length in seconds; 0 opens the line without a schedule.
+    pub cycle_length_secs: u64,
+    pub min_payment_bps: u32,
+    pub rate_mode: RateMode,
+    /// Denomination; `None` uses the default token.
+    pub token: Option<Address>,
+    pub nonce: u64,
+}
+
+impl SignedOpenCreditLine {
+    pub fn schedule(&self) -> Option<RepaymentSchedule> {
+        (self.cycle_length_secs > 0).then_some(RepaymentSchedule {
+            cycle_length_secs: self.cycle_length_secs,
+            min_payment_bps: self.min_payment_bps,
+        })
+    }
+}
+
+/// Signed request to update a credit line's risk parameters.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct SignedRiskUpdate {
+    pub borrower: Address,
+    pub credit_limit: i128,
+    pub interest_rate_bps: u32,
+    pub risk_score: u32,
+    pub nonce: u64,
+}
+
+/// Instance storage key for the risk engine public key.
+fn public_key_key(env: &Env) -> DataKey {
+    DataKey::Config(Symbol::new(env, "re_pubkey"))
+}
+
+pub fn get_public_key(env: &Env) -> Option<BytesN<32>> {
+    storage::get_instance(env, &public_key_key(env))
+}
+
+pub fn set_public_key(env: &Env, public_key: &BytesN<32>) {
+    storage::set_instance(env, &public_key_key(env), public_key);
+}
+
+pub fn is_nonce_used(env: &Env, nonce: u64) -> bool {
+    storage::has_persistent(env, &DataKey::Nonce(nonce))
+}
+
+/// Verify `signature` over `payload` and consume `nonce`.
+///
+/// # Panics
+/// * `"risk engine key not set"`
+/// * `"nonce already used"`
+/// * If the signature does not verify
+pub fn verify<T: IntoVal<Env, Val>>(env: &Env, payload: &T, nonce: u64, signature: &BytesN<64>) {
+    let public_key = get_public_key(env).expect("risk engine key not set");
+    if is_nonce_used(env, nonce) {
+        panic!("nonce already used");
+    }
+    let payload: Val = payload.into_val(env);
+    let message = (env.current_contract_address(), payload).to_xdr(env);
+    env.crypto()
+        .ed25519_verify(&public_key, &message, signature);
+    storage::set_persistent(env, &DataKey::Nonce(nonce), &true);
+}
@@ -0,0 +1,220 @@
+//! External liquidity sources.
+//!
+//! Besides the contract's own reserve, the admin may register reserve accounts
+//! (treasuries, vaults) that approve this contract to spend their tokens. A
+//! draw is paid from the contract's reserve first and any shortfall is pulled
+//! from the sources in priority order (lowest `priority` first) with
+//! `transfer_from`, split across as many as needed. Repayments stay in the
+//! reserve unless a `RepaymentRoute` forwards them.
+//!
+//! A treasurer can also move balances between sources with the same
+//! `transfer_from` authority, as long as the liquidity available to draws
+//! still covers the reserve policy afterwards.
+
+use soroban_sdk::{contracttype, token, Address, Env, Symbol, Vec};
+
+use crate::liquidity;
+use crate::math::{self, Rounding};
+use crate::reserve;
+use crate::storage::{self, DataKey};
+
+/// A registered reserve account.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct LiquiditySource {
+    pub address: Address,
+    /// Lower values are drawn from first.
+    pub priority: u32,
+}
+
+/// Where repayments go once received.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum RepaymentRoute {
+    /// Stay in the contract's reserve.
+    Reserve,
+    /// Forwarded to the highest-priority source.
+    TopSource,
+    /// Forwarded to a fixed address.
+    Sink(Address),
+}
+
+/// Instance storage key for the source list, kept sorted by priority.
+fn sources_key(env: &Env) -> DataKey {
+    DataKey::Config(Symbol::new(env, "liq_src"))
+}
+
+/// Instance storage key for the repayment route.
+fn route_key(env: &Env) -> DataKey {
+    DataKey::Config(Symbol::new(env, "repay_route"))
+}
+
+pub fn list(env: &Env) -> Vec<LiquiditySource> {
+    storage::get_instance(env, &sources_key(env)).unwrap_or(Vec::new(env))
+}
+
+/// The highest-priority source, if any is registered.
+pub fn top(env: &Env) -> Option<Address> {
+    list(env).first().map(|source| source.address)
+}
+
+/// Register `address` at `priority`, replacing its previous priority if listed.
+pub fn add(env: &Env, address: &Address, priority: u32) {
+    let mut sources = list(env);
+    if let Some(index) = sources.iter().position(|s| s.address == *address) {
+        sources.remove(index as u32);
+    }
+    let index = sources
+        .iter()
+        .position(|s| s.priority > priority)
+        .unwrap_or(sources.len() as usize);
+    sources.insert(
+        index as u32,
+        LiquiditySource {
+            address: address.clone(),
+            priority,
+        },
+    );
+    storage::set_instance(env, &sources_key(env), &sources);
+}
+
+/// # Panics
+/// * `"liquidity source not found"`
+pub fn remove(env: &Env, address: &Address) {
+    let mut sources = list(env);
+    let index = sources
+        .iter()
+        .position(|s| s.address == *address)
+        .expect("liquidity source not found");
+    sources.remove(index as u32);
+    storage::set_instance(env, &sources_key(env), &sources);
+}
+
+pub fn get_route(env: &Env) -> RepaymentRoute {
+    storage::get_instance(env, &route_key(env)).unwrap_or(RepaymentRoute::Reserve)
+}
+
+pub fn set_route(env: &Env, route: &RepaymentRoute) {
+    storage::set_instance(env, &route_key(env), route);
+}
+
+/// Pay `amount` of `token` to `recipient`: from the reserve first, then from the
+/// sources in priority order.
+///
+/// # Panics
+/// * `"insufficient liquidity"` – reserve and sources together cannot cover `amount`
+pub fn disburse(env: &Env, token: &Address, recipient: &Address, amount: i128) {
+    let client = token::Client::new(env, token);
+    let contract = env.current_contract_address();
+    let sources = list(env);
+    if sources.is_empty() {
+        client.transfer(&contract, recipient, &amount);
+        return;
+    }
+
+    let from_reserve = client.balance(&contract).clamp(0, amount);
+    if from_reserve > 0 {
+        client.transfer(&contract, recipient, &from_reserve);
+    }
+    let mut remaining = amount - from_reserve;
+    for source in sources.iter() {
+        if remaining == 0 {
+            break;
+        }
+        let available = available_from(env, &client, &source.address);
+        let take = available.clamp(0, remaining);
+        if take > 0 {
+            client.transfer_from(&contract, &source.address, recipient, &take);
+            remaining -= take;
+        }
+    }
+    if remaining > 0 {
+        panic!("insufficient liquidity");
+    }
+}
+
+/// What the contract can pull from `source`: its balance, up to its allowance.
+fn available_from(env: &Env, client: &token::Client, source: &Address) -> i128 {
+    client
+        .allowance(source, &env.current_contract_address())
+        .min(client.balance(source))
+        .max(0)
+}
+
+/// Liquidity available to draws in `token`: the reserve plus what every source
+/// can supply.
+pub fn total_available(env: &Env, token: &Address) -> i128 {
+    let client = token::Client::new(env, token);
+    let mut total = client.balance(&env.current_contract_address()).max(0);
+    for source in list(env).iter() {
+        total = math::checked(
+            env,
+            math::add(total, available_from(env, &client, &source.address)),
+        );
+    }
+    total
+}
+
+/// Liquidity in `token` the reserve policy requires to stay available: the
+/// reserved balance, and at least the minimum or critical ratio of outstanding
+/// principal, whichever is higher.
+pub fn required_available(env: &Env, token: &Address) -> i128 {
+    let ratio_bps = reserve::get_config(env)
+        .min_reserve_ratio_bps
+        .max(reserve::critical_ratio_bps(env));
+    let by_ratio = math::checked(
+        env,
+        math::apply_bps(liquidity::outstanding(env, token), ratio_bps, Rounding::Up),
+    );
+    liquidity::reserved(env, token).max(by_ratio)
+}
+
+/// Move `amount` of `token` from source `from` to source `to` with `transfer_from`.
+///
+/// # Panics
+/// * `"amount must be positive"`
+/// * `"cannot rebalance to the same source"`
+/// * `"liquidity source not found"` – `from` or `to` is not registered
+/// * `"rebalance breaches reserve policy"` – available liquidity would drop below
+///   `required_available`
+pub fn rebalance(env: &Env, token: &Address, from: &Address, to: &Address, amount: i128) {
+    if amount <= 0 {
+        panic!("amount must be positive");
+    }
+    if from == to {
+        panic!("cannot rebalance to the same source");
+    }
+    let sources = list(env);
+    for address in [from, to] {
+        if !sources.iter().any(|s| s.address == *address) {
+            panic!("liquidity source not found");
+        }
+    }
+    let before = total_available(env, token);
+    token::Client::new(env, token).transfer_from(
+        &env.current_contract_address(),
+        from,
+        to,
+        &amount,
+    );
+    let after = total_available(env, token);
+    if after < before && after < required_available(env, token) {
+        panic!("rebalance breaches reserve policy");
+    }
+}
+
+/// Forward `amount` of repaid `token` from the reserve according to the route.
+pub fn route_repayment(env: &Env, token: &Address, amount: i128) {
+    if amount <= 0 {
+        return;
+    }
+    let destination = match get_route(env) {
+        RepaymentRoute::Reserve => return,
+        RepaymentRoute::TopSource => match top(env) {
+            Some(address) => address,
+            None => return,
+        },
+        RepaymentRoute::Sink(address) => address,
+    };
+    token::Client::new(env, token).transfer(&env.current_contract_address(), &destination, &amount);
+}
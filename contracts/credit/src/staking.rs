@@ -0,0 +1,193 @@
+//! Staking for interest rate discounts.
+//!
+//! Borrowers can stake the protocol token, set by the admin, into the
+//! contract. The highest discount tier their stake reaches is taken off the
+//! rate their line accrues at, promotional or standard, down to zero. Stakes
+//! can only be withdrawn while the line's outstanding balance is at most
+//! `unstake_threshold`, so a discount cannot be enjoyed and then pulled out
+//! from under a large debt. Lines are accrued before a stake changes, so each
+//! period accrues at the discount that applied during it.
+
+use soroban_sdk::{contracttype, token, Address, Env, Symbol, Vec};
+
+use crate::math;
+use crate::promo;
+use crate::storage::{self, DataKey};
+use crate::tokens;
+use crate::types::CreditLineData;
+
+/// A discount earned by staking at least `min_stake`.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct StakeTier {
+    pub min_stake: i128,
+    /// Taken off the line's rate, in basis points.
+    pub discount_bps: u32,
+}
+
+/// Staking program settings (admin-configured).
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct StakingConfig {
+    /// Token staked; `None` disables staking.
+    pub token: Option<Address>,
+    /// Tiers in ascending `min_stake` order.
+    pub tiers: Vec<StakeTier>,
+    /// Largest outstanding balance, principal plus interest, at which a
+    /// borrower can still unstake.
+    pub unstake_threshold: i128,
+}
+
+#[contracttype]
+#[derive(Clone)]
+pub enum StakeKey {
+    /// Amount staked by a borrower (persistent).
+    Staked(Address),
+    /// Amount staked across all borrowers (instance).
+    TotalStaked,
+}
+
+/// Instance storage key for staking config.
+fn config_key(env: &Env) -> DataKey {
+    DataKey::Config(Symbol::new(env, "staking"))
+}
+
+fn staked_key(borrower: &Address) -> DataKey {
+    DataKey::Staking(StakeKey::Staked(borrower.clone()))
+}
+
+pub fn get_config(env: &Env) -> StakingConfig {
+    storage::get_instance(env, &config_key(env)).unwrap_or(StakingConfig {
+        token: None,
+        tiers: Vec::new(env),
+        unstake_threshold: 0,
+    })
+}
+
+/// # Panics
+/// * `"staking token cannot be a credit token"` – stakes would mix with the reserve
+/// * `"staking token cannot change while staked"`
+/// * `"staking tiers must ascend"` – `min_stake` not strictly increasing from a positive value
+/// * `"discount_bps cannot exceed 10000"`
+/// * `"unstake_threshold cannot be negative"`
+pub fn set_config(env: &Env, config: &StakingConfig) {
+    if let Some(stake_token) = &config.token {
+        if tokens::is_supported(env, stake_token) {
+            panic!("staking token cannot be a credit token");
+        }
+    }
+    if total_staked(env) > 0 && config.token != get_config(env).token {
+        panic!("staking token cannot change while staked");
+    }
+    let mut previous_min = 0;
+    for tier in config.tiers.iter() {
+        if tier.min_stake <= previous_min {
+            panic!("staking tiers must ascend");
+        }
+        if tier.discount_bps > 10_000 {
+            panic!("discount_bps cannot exceed 10000");
+        }
+        previous_min = tier.min_stake;
+    }
+    if config.unstake_threshold < 0 {
+        panic!("unstake_threshold cannot be negative");
+    }
+    storage::set_instance(env, &config_key(env), config);
+}
+
+pub fn staked(env: &Env, borrower: &Address) -> i128 {
+    storage::get_persistent(env, &staked_key(borrower)).unwrap_or(0)
+}
+
+pub fn total_staked(env: &Env) -> i128 {
+    storage::get_instance(env, &DataKey::Staking(StakeKey::TotalStaked)).unwrap_or(0)
+}
+
+/// Amount of `token` held for stakers.
+pub fn held(env: &Env, token: &Address) -> i128 {
+    if get_config(env).token.as_ref() == Some(token) {
+        total_staked(env)
+    } else {
+        0
+    }
+}
+
+fn set_staked(env: &Env, borrower: &Address, amount: i128, total: i128) {
+    if amount == 0 {
+        storage::remove_persistent(env, &staked_key(borrower));
+    } else {
+        storage::set_persistent(env, &staked_key(borrower), &amount);
+    }
+    storage::set_instance(env, &DataKey::Staking(StakeKey::TotalStaked), &total);
+}
+
+/// Discount `borrower`'s stake earns, in basis points.
+pub fn discount_bps(env: &Env, borrower: &Address) -> u32 {
+    let config = get_config(env);
+    if config.tiers.is_empty() {
+        return 0;
+    }
+    let stake = staked(env, borrower);
+    config
+        .tiers
+        .iter()
+        .filter(|tier| stake >= tier.min_stake)
+        .last()
+        .map_or(0, |tier| tier.discount_bps)
+}
+
+/// Rate `line` accrues at right now, after its staking discount.
+pub fn effective_rate(env: &Env, line: &CreditLineData) -> u32 {
+    promo::active_rate(env, line).saturating_sub(discount_bps(env, &line.borrower))
+}
+
+/// Move `amount` of the staking token from `borrower` into the contract.
+/// Returns the borrower's new stake.
+///
+/// # Panics
+/// * `"amount must be positive"`
+/// * `"staking not enabled"` – no staking token configured
+pub fn stake(env: &Env, borrower: &Address, amount: i128) -> i128 {
+    if amount <= 0 {
+        panic!("amount must be positive");
+    }
+    let stake_token = get_config(env).token.expect("staking not enabled");
+    let staked = math::checked(env, math::add(staked(env, borrower), amount));
+    let total = math::checked(env, math::add(total_staked(env), amount));
+    set_staked(env, borrower, staked, total);
+    token::Client::new(env, &stake_token).transfer(
+        borrower,
+        &env.current_contract_address(),
+        &amount,
+    );
+    staked
+}
+
+/// Return `amount` of `borrower`'s stake, whose line owes `outstanding`.
+/// Returns the borrower's remaining stake.
+///
+/// # Panics
+/// * `"amount must be positive"`
+/// * `"insufficient stake"`
+/// * `"unstake locked while balance outstanding"` – `outstanding` is above the threshold
+pub fn unstake(env: &Env, borrower: &Address, amount: i128, outstanding: i128) -> i128 {
+    if amount <= 0 {
+        panic!("amount must be positive");
+    }
+    let staked = staked(env, borrower);
+    if amount > staked {
+        panic!("insufficient stake");
+    }
+    let config = get_config(env);
+    if outstanding > config.unstake_threshold {
+        panic!("unstake locked while balance outstanding");
+    }
+    let stake_token = config.token.expect("staking not enabled");
+    set_staked(env, borrower, staked - amount, total_staked(env) - amount);
+    token::Client::new(env, &stake_token).transfer(
+        &env.current_contract_address(),
+        borrower,
+        &amount,
+    );
+    staked - amount
+}
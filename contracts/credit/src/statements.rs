@@ -0,0 +1,126 @@
+//! Per-cycle statement totals for scheduled lines.
+//!
+//! Each scheduled line keeps running totals for its current statement cycle:
+//! the balance it opened with, and the draws, fees and payments booked since.
+//! A keeper calls `close_cycle` once a cycle length has passed since the cycle
+//! started; the totals are published as a statement and a new cycle opens at
+//! the closing balance. A cycle runs from one close to the next, so nothing
+//! booked between the nominal end and the close is lost. Interest charged is
+//! derived from the change in accrued interest plus the interest paid, rather
+//! than recorded on every accrual. Lines opened before statements existed
+//! start their first cycle at their next draw, repayment or close call.
+
+use soroban_sdk::{contracttype, Address, Env};
+
+use crate::interest;
+use crate::math;
+use crate::storage::{self, DataKey};
+use crate::types::CreditLineData;
+
+/// Running totals of a line's open statement cycle.
+#[contracttype]
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct StatementCycle {
+    pub cycle_start: u64,
+    /// Principal plus accrued interest at the start of the cycle.
+    pub opening_balance: i128,
+    /// Accrued interest at the start of the cycle.
+    pub opening_interest: i128,
+    /// Amount drawn, including withheld fees.
+    pub draws: i128,
+    /// Draw fees, insurance premiums and prepayment penalties charged.
+    pub fees: i128,
+    /// Repayments applied to the line.
+    pub payments: i128,
+    /// Interest portion of `payments`.
+    pub interest_paid: i128,
+}
+
+#[contracttype]
+#[derive(Clone)]
+pub enum StatementKey {
+    /// Open statement cycle of a borrower's line (persistent).
+    Cycle(Address),
+}
+
+fn cycle_key(borrower: &Address) -> DataKey {
+    DataKey::Statement(StatementKey::Cycle(borrower.clone()))
+}
+
+pub fn get(env: &Env, borrower: &Address) -> Option<StatementCycle> {
+    storage::get_persistent(env, &cycle_key(borrower))
+}
+
+/// Open a fresh cycle for `line` at its current balance, or drop any cycle
+/// if the line has no schedule.
+pub fn start(env: &Env, line: &CreditLineData) -> StatementCycle {
+    let cycle = StatementCycle {
+        cycle_start: env.ledger().timestamp(),
+        opening_balance: interest::outstanding(line),
+        opening_interest: line.accrued_interest,
+        ..Default::default()
+    };
+    if line.cycle_length_secs == 0 {
+        storage::remove_persistent(env, &cycle_key(&line.borrower));
+    } else {
+        storage::set_persistent(env, &cycle_key(&line.borrower), &cycle);
+    }
+    cycle
+}
+
+/// Book activity on a scheduled `line`, already saved with it applied, to its
+/// open cycle. A line without a cycle opens one at its balance before the activity.
+pub fn record(
+    env: &Env,
+    line: &CreditLineData,
+    draws: i128,
+    fees: i128,
+    payments: i128,
+    interest_paid: i128,
+) {
+    if line.cycle_length_secs == 0 {
+        return;
+    }
+    let mut cycle = get(env, &line.borrower).unwrap_or_else(|| StatementCycle {
+        cycle_start: env.ledger().timestamp(),
+        opening_balance: interest::outstanding(line) - draws + payments,
+        opening_interest: line.accrued_interest + interest_paid,
+        ..Default::default()
+    });
+    cycle.draws = math::checked(env, math::add(cycle.draws, draws));
+    cycle.fees = math::checked(env, math::add(cycle.fees, fees));
+    cycle.payments = math::checked(env, math::add(cycle.payments, payments));
+    cycle.interest_paid = math::checked(env, math::add(cycle.interest_paid, interest_paid));
+    storage::set_persistent(env, &cycle_key(&line.borrower), &cycle);
+}
+
+/// Interest charged on `line` since `cycle` opened.
+pub fn interest_charged(cycle: &StatementCycle, line: &CreditLineData) -> i128 {
+    line.accrued_interest
+        .saturating_sub(cycle.opening_interest)
+        .saturating_add(cycle.interest_paid)
+}
+
+/// Close `line`'s open cycle and open the next one. Returns the closed cycle,
+/// or `None` if the line had no cycle and one was just opened.
+///
+/// # Panics
+/// * `"cycle not ended"` – less than a cycle length since the cycle started
+pub fn close(env: &Env, line: &CreditLineData) -> Option<StatementCycle> {
+    let Some(closed) = get(env, &line.borrower) else {
+        start(env, line);
+        return None;
+    };
+    if env.ledger().timestamp() < closed.cycle_start.saturating_add(line.cycle_length_secs) {
+        panic!("cycle not ended");
+    }
+    start(env, line);
+    Some(closed)
+}
+
+pub fn transfer(env: &Env, from: &Address, to: &Address) {
+    if let Some(cycle) = get(env, from) {
+        storage::remove_persistent(env, &cycle_key(from));
+        storage::set_persistent(env, &cycle_key(to), &cycle);
+    }
+}
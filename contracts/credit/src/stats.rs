@@ -0,0 +1,162 @@
+//! Protocol-wide statistics maintained on every credit line write.
+//!
+//! Counters are updated incrementally from the before/after state of each line
+//! (see `track`), so dashboards can read aggregates without replaying events.
+//! Repayments are also split into principal, interest and protocol fees, so
+//! revenue reporting doesn't need to replay every `RepaymentEvent`.
+
+use soroban_sdk::{contracttype, Env, Symbol};
+
+use crate::math;
+use crate::storage::{self, DataKey};
+use crate::types::{CreditLineData, CreditStatus};
+
+/// Aggregate protocol counters.
+#[contracttype]
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct ProtocolStats {
+    /// Credit lines ever opened, including reopenings after close.
+    pub total_lines_opened: u64,
+    /// Credit lines currently Active.
+    pub total_active: u32,
+    /// Sum of `utilized_amount` across all lines.
+    pub total_outstanding_principal: i128,
+    /// Borrower repayments applied, principal and interest.
+    pub total_repaid: i128,
+    /// Principal outstanding on lines at the moment they defaulted.
+    pub total_defaulted_amount: i128,
+    /// Principal part of `total_repaid`.
+    pub total_principal_repaid: i128,
+    /// Interest part of `total_repaid`, including the protocol's share.
+    pub total_interest_collected: i128,
+    /// Protocol fees taken on repayments: the treasury share of interest
+    /// plus prepayment penalties.
+    pub total_fees_collected: i128,
+    /// Debt forgiven by `forgive_debt`, principal and interest.
+    pub total_forgiven: i128,
+}
+
+/// The line counters, stored as one entry and updated on every line write.
+#[contracttype]
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct LineCounters {
+    pub total_lines_opened: u64,
+    pub total_active: u32,
+    pub total_outstanding_principal: i128,
+    pub total_repaid: i128,
+    pub total_defaulted_amount: i128,
+}
+
+/// Cumulative split of repayments, stored apart from the line counters.
+#[contracttype]
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct RepaymentTotals {
+    pub principal: i128,
+    pub interest: i128,
+    pub fees: i128,
+}
+
+/// Instance storage key for the line counters.
+fn stats_key(env: &Env) -> DataKey {
+    DataKey::Config(Symbol::new(env, "stats"))
+}
+
+/// Instance storage key for the repayment split.
+fn repayments_key(env: &Env) -> DataKey {
+    DataKey::Config(Symbol::new(env, "repay_tot"))
+}
+
+/// Instance storage key for the total forgiven.
+fn forgiven_key(env: &Env) -> DataKey {
+    DataKey::Config(Symbol::new(env, "forgiven"))
+}
+
+pub fn counters(env: &Env) -> LineCounters {
+    storage::get_instance(env, &stats_key(env)).unwrap_or_default()
+}
+
+fn set(env: &Env, stats: &LineCounters) {
+    storage::set_instance(env, &stats_key(env), stats);
+}
+
+fn repayment_totals(env: &Env) -> RepaymentTotals {
+    storage::get_instance(env, &repayments_key(env)).unwrap_or_default()
+}
+
+pub fn get(env: &Env) -> ProtocolStats {
+    let counters = counters(env);
+    let repayments = repayment_totals(env);
+    ProtocolStats {
+        total_lines_opened: counters.total_lines_opened,
+        total_active: counters.total_active,
+        total_outstanding_principal: counters.total_outstanding_principal,
+        total_repaid: counters.total_repaid,
+        total_defaulted_amount: counters.total_defaulted_amount,
+        total_principal_repaid: repayments.principal,
+        total_interest_collected: repayments.interest,
+        total_fees_collected: repayments.fees,
+        total_forgiven: storage::get_instance(env, &forgiven_key(env)).unwrap_or(0),
+    }
+}
+
+/// Update counters for a credit line moving from `before` to `after`.
+pub fn track(env: &Env, before: Option<&CreditLineData>, after: &CreditLineData) {
+    let mut stats = counters(env);
+
+    let was_open = before.is_some_and(|line| line.status != CreditStatus::Closed);
+    if !was_open && after.status != CreditStatus::Closed {
+        stats.total_lines_opened += 1;
+    }
+
+    let was_active = before.is_some_and(|line| line.status == CreditStatus::Active);
+    let is_active = after.status == CreditStatus::Active;
+    if !was_active && is_active {
+        stats.total_active += 1;
+    } else if was_active && !is_active {
+        stats.total_active -= 1;
+    }
+
+    let previous_utilized = before.map(|line| line.utilized_amount).unwrap_or(0);
+    stats.total_outstanding_principal = math::checked(
+        env,
+        math::add(
+            stats.total_outstanding_principal,
+            after.utilized_amount - previous_utilized,
+        ),
+    );
+
+    let was_defaulted = before.is_some_and(|line| line.status == CreditStatus::Defaulted);
+    if !was_defaulted && after.status == CreditStatus::Defaulted {
+        stats.total_defaulted_amount = math::checked(
+            env,
+            math::add(stats.total_defaulted_amount, after.utilized_amount),
+        );
+    }
+
+    set(env, &stats);
+}
+
+/// Record a borrower repayment split into `principal` and `interest`, and the
+/// protocol `fees` taken on it.
+pub fn record_repayment(env: &Env, principal: i128, interest: i128, fees: i128) {
+    let mut stats = counters(env);
+    let paid = math::checked(env, math::add(principal, interest));
+    stats.total_repaid = math::checked(env, math::add(stats.total_repaid, paid));
+    set(env, &stats);
+
+    let mut totals = repayment_totals(env);
+    totals.principal = math::checked(env, math::add(totals.principal, principal));
+    totals.interest = math::checked(env, math::add(totals.interest, interest));
+    totals.fees = math::checked(env, math::add(totals.fees, fees));
+    storage::set_instance(env, &repayments_key(env), &totals);
+}
+
+/// Record `amount` of debt forgiven.
+pub fn record_forgiveness(env: &Env, amount: i128) {
+    let total: i128 = storage::get_instance(env, &forgiven_key(env)).unwrap_or(0);
+    storage::set_instance(
+        env,
+        &forgiven_key(env),
+        &math::checked(env, math::add(total, amount)),
+    );
+}
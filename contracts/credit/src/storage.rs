@@ -0,0 +1,210 @@
+//! Storage keys for the Credit contract.
+//!
+//! Every entry lives under the single `DataKey` namespace. Feature modules with
+//! several entries own a sub-key enum nested under their own variant, so keys
+//! from different modules can never collide. Entries written under the earlier
+//! unnamespaced keys are moved under `DataKey` the first time they are read.
+
+use soroban_sdk::{contracttype, Address, BytesN, Env, IntoVal, Symbol, TryFromVal, Val};
+
+use crate::access::Role;
+use crate::amortization::AmortizationKey;
+use crate::audit::AuditKey;
+use crate::autopay::AutopayKey;
+use crate::borrowers::BorrowerKey;
+use crate::collateral::CollateralKey;
+use crate::default_interest::DefaultInterestKey;
+use crate::delegation::DelegationKey;
+use crate::draw_limits::DrawLimitKey;
+use crate::forgiveness::ForgivenessKey;
+use crate::insurance::InsuranceKey;
+use crate::large_draws::LargeDrawKey;
+use crate::liquidity::LiquidityKey;
+use crate::metadata::MetadataKey;
+use crate::onboarding::OnboardingKey;
+use crate::positions::PositionKey;
+use crate::purposes::PurposeKey;
+use crate::revenue::RevenueKey;
+use crate::rewards::RewardKey;
+use crate::scheduled_draws::ScheduledDrawKey;
+use crate::staking::StakeKey;
+use crate::statements::StatementKey;
+use crate::tiers::TierKey;
+use crate::timelock::TimelockKey;
+use crate::treasury::TreasuryKey;
+use crate::velocity::VelocityKey;
+
+#[contracttype]
+#[derive(Clone)]
+pub enum DataKey {
+    /// Contract admin (instance).
+    Admin,
+    /// Default token for new lines (instance).
+    Token,
+    /// Reentrancy guard flag (instance).
+    Guard,
+    /// Singleton config owned by a feature module, by name (instance).
+    Config(Symbol),
+    /// Credit line of a borrower (persistent).
+    Line(Address),
+    /// Activity history of a borrower (persistent).
+    History(Address),
+    /// Recent draws and repayments of a borrower (persistent).
+    Transactions(Address),
+    /// Role grant (persistent).
+    Role(Role, Address),
+    /// Streaming disbursement of a borrower (persistent).
+    Stream(Address),
+    /// Open dispute on a borrower's line (persistent).
+    Dispute(Address),
+    /// Consumed idempotency key (persistent).
+    Operation(BytesN<32>),
+    /// Operator approval by (borrower, operator) (persistent).
+    Operator(Address, Address),
+    /// Allowlist entry of a borrower (persistent).
+    Allowlisted(Address),
+    /// Blocklist entry of an address (persistent).
+    Blocked(Address),
+    /// Consumed risk engine signature nonce (persistent).
+    Nonce(u64),
+    Amortization(AmortizationKey),
+    Audit(AuditKey),
+    Autopay(AutopayKey),
+    Borrowers(BorrowerKey),
+    Collateral(CollateralKey),
+    DefaultInterest(DefaultInterestKey),
+    Delegation(DelegationKey),
+    DrawLimits(DrawLimitKey),
+    Forgiveness(ForgivenessKey),
+    Insurance(InsuranceKey),
+    LargeDraw(LargeDrawKey),
+    Liquidity(LiquidityKey),
+    Metadata(MetadataKey),
+    Onboarding(OnboardingKey),
+    Position(PositionKey),
+    Purposes(PurposeKey),
+    Revenue(RevenueKey),
+    Rewards(RewardKey),
+    ScheduledDraws(ScheduledDrawKey),
+    Staking(StakeKey),
+    Statement(StatementKey),
+    Tiers(TierKey),
+    Timelock(TimelockKey),
+    Treasury(TreasuryKey),
+    Velocity(VelocityKey),
+}
+
+/// The key `key`'s entry was stored under before `DataKey` was introduced.
+/// `History` and `Nonce` kept their variant names, so their encodings match;
+/// `Transactions`, `Stream`, `Dispute`, `Allowlisted` and `Blocked` postdate
+/// `DataKey` and are encoded the same way.
+fn legacy_key(env: &Env, key: &DataKey) -> Val {
+    match key {
+        DataKey::Admin => Symbol::new(env, "admin").into_val(env),
+        DataKey::Token => Symbol::new(env, "token").into_val(env),
+        DataKey::Guard => Symbol::new(env, "reentrancy").into_val(env),
+        DataKey::Config(name) => name.into_val(env),
+        DataKey::Line(borrower) => borrower.into_val(env),
+        DataKey::History(borrower) => (Symbol::new(env, "History"), borrower.clone()).into_val(env),
+        DataKey::Role(role, account) => {
+            (Symbol::new(env, "Member"), *role, account.clone()).into_val(env)
+        }
+        DataKey::Transactions(borrower) => {
+            (Symbol::new(env, "Transactions"), borrower.clone()).into_val(env)
+        }
+        DataKey::Stream(borrower) => (Symbol::new(env, "Stream"), borrower.clone()).into_val(env),
+        DataKey::Dispute(borrower) => (Symbol::new(env, "Dispute"), borrower.clone()).into_val(env),
+        DataKey::Operation(op_id) => (Symbol::new(env, "Consumed"), op_id.clone()).into_val(env),
+        DataKey::Operator(borrower, operator) => (
+            Symbol::new(env, "Approval"),
+            borrower.clone(),
+            operator.clone(),
+        )
+            .into_val(env),
+        DataKey::Allowlisted(borrower) => {
+            (Symbol::new(env, "Allowlisted"), borrower.clone()).into_val(env)
+        }
+        DataKey::Blocked(account) => (Symbol::new(env, "Blocked"), account.clone()).into_val(env),
+        DataKey::Nonce(nonce) => (Symbol::new(env, "Nonce"), *nonce).into_val(env),
+        DataKey::Amortization(key) => key.into_val(env),
+        DataKey::Audit(key) => key.into_val(env),
+        DataKey::Autopay(key) => key.into_val(env),
+        DataKey::Borrowers(key) => key.into_val(env),
+        DataKey::Collateral(key) => key.into_val(env),
+        DataKey::DefaultInterest(key) => key.into_val(env),
+        DataKey::Delegation(key) => key.into_val(env),
+        DataKey::DrawLimits(key) => key.into_val(env),
+        DataKey::Forgiveness(key) => key.into_val(env),
+        DataKey::Insurance(key) => key.into_val(env),
+        DataKey::LargeDraw(key) => key.into_val(env),
+        DataKey::Liquidity(key) => key.into_val(env),
+        DataKey::Metadata(key) => key.into_val(env),
+        DataKey::Onboarding(key) => key.into_val(env),
+        DataKey::Position(key) => key.into_val(env),
+        DataKey::Purposes(key) => key.into_val(env),
+        DataKey::Revenue(key) => key.into_val(env),
+        DataKey::Rewards(key) => key.into_val(env),
+        DataKey::ScheduledDraws(key) => key.into_val(env),
+        DataKey::Staking(key) => key.into_val(env),
+        DataKey::Statement(key) => key.into_val(env),
+        DataKey::Tiers(key) => key.into_val(env),
+        DataKey::Timelock(key) => key.into_val(env),
+        DataKey::Treasury(key) => key.into_val(env),
+        DataKey::Velocity(key) => key.into_val(env),
+    }
+}
+
+fn convert<V: TryFromVal<Env, Val>>(env: &Env, raw: Val) -> V {
+    V::try_from_val(env, &raw).unwrap_or_else(|_| panic!("invalid stored value"))
+}
+
+/// Read an instance entry, copying it from its legacy key on first read.
+///
+/// Legacy instance keys are left in place: some sub-keys shared an encoding
+/// across modules, and each module must find its own copy.
+pub fn get_instance<V: TryFromVal<Env, Val>>(env: &Env, key: &DataKey) -> Option<V> {
+    let storage = env.storage().instance();
+    if let Some(raw) = storage.get::<DataKey, Val>(key) {
+        return Some(convert(env, raw));
+    }
+    let raw: Val = storage.get(&legacy_key(env, key))?;
+    storage.set(key, &raw);
+    Some(convert(env, raw))
+}
+
+pub fn set_instance<V: IntoVal<Env, Val>>(env: &Env, key: &DataKey, value: &V) {
+    env.storage().instance().set(key, value);
+}
+
+pub fn has_instance(env: &Env, key: &DataKey) -> bool {
+    let storage = env.storage().instance();
+    storage.has(key) || storage.has(&legacy_key(env, key))
+}
+
+/// Read a persistent entry, moving it from its legacy key on first read.
+pub fn get_persistent<V: TryFromVal<Env, Val>>(env: &Env, key: &DataKey) -> Option<V> {
+    let storage = env.storage().persistent();
+    if let Some(raw) = storage.get::<DataKey, Val>(key) {
+        return Some(convert(env, raw));
+    }
+    let legacy = legacy_key(env, key);
+    let raw: Val = storage.get(&legacy)?;
+    storage.set(key, &raw);
+    storage.remove(&legacy);
+    Some(convert(env, raw))
+}
+
+pub fn set_persistent<V: IntoVal<Env, Val>>(env: &Env, key: &DataKey, value: &V) {
+    env.storage().persistent().set(key, value);
+}
+
+pub fn has_persistent(env: &Env, key: &DataKey) -> bool {
+    let storage = env.storage().persistent();
+    storage.has(key) || storage.has(&legacy_key(env, key))
+}
+
+pub fn remove_persistent(env: &Env, key: &DataKey) {
+    let storage = env.storage().persistent();
+    storage.remove(key);
+    storage.remove(&legacy_key(env, key));
+}
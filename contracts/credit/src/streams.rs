@@ -0,0 +1,104 @@
+//! Streaming disbursement of a draw.
+//!
+//! `open_stream` locks `total_amount` against the borrower's limit and vests it
+//! linearly over `duration_secs`. The borrower claims the vested part with
+//! `claim_streamed`, each claim being an ordinary draw, so a large disbursement
+//! leaves the reserve gradually instead of in one shot. A borrower has at most
+//! one stream; it is removed once fully claimed.
+
+use soroban_sdk::{contracttype, Address, Env};
+
+use crate::math::{self, Rounding};
+use crate::storage::{self, DataKey};
+
+/// A borrower's streaming disbursement.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Stream {
+    pub total_amount: i128,
+    /// Amount claimed (drawn) so far.
+    pub claimed: i128,
+    pub start_ts: u64,
+    pub duration_secs: u64,
+}
+
+fn stream_key(borrower: &Address) -> DataKey {
+    DataKey::Stream(borrower.clone())
+}
+
+pub fn get(env: &Env, borrower: &Address) -> Option<Stream> {
+    storage::get_persistent(env, &stream_key(borrower))
+}
+
+/// Amount of `borrower`'s stream not yet claimed, held against the limit.
+pub fn locked(env: &Env, borrower: &Address) -> i128 {
+    get(env, borrower).map_or(0, |stream| stream.total_amount - stream.claimed)
+}
+
+/// Amount of `stream` vested by now and not yet claimed.
+pub fn claimable(env: &Env, stream: &Stream) -> i128 {
+    let elapsed = env
+        .ledger()
+        .timestamp()
+        .saturating_sub(stream.start_ts)
+        .min(stream.duration_secs);
+    let vested = math::checked(
+        env,
+        math::mul_div(
+            stream.total_amount,
+            elapsed as i128,
+            stream.duration_secs as i128,
+            Rounding::Down,
+        ),
+    );
+    vested - stream.claimed
+}
+
+/// Start a stream of `total_amount` over `duration_secs` for `borrower`. The
+/// caller checks that the amount fits under the limit.
+///
+/// # Panics
+/// * `"amount must be positive"`
+/// * `"duration_secs must be positive"`
+/// * `"stream already open"`
+pub fn open(env: &Env, borrower: &Address, total_amount: i128, duration_secs: u64) -> Stream {
+    if total_amount <= 0 {
+        panic!("amount must be positive");
+    }
+    if duration_secs == 0 {
+        panic!("duration_secs must be positive");
+    }
+    if get(env, borrower).is_some() {
+        panic!("stream already open");
+    }
+    let stream = Stream {
+        total_amount,
+        claimed: 0,
+        start_ts: env.ledger().timestamp(),
+        duration_secs,
+    };
+    storage::set_persistent(env, &stream_key(borrower), &stream);
+    stream
+}
+
+/// Mark the vested, unclaimed part of `borrower`'s stream as claimed and return
+/// the updated stream with the amount to draw. This releases the amount from
+/// the lock, so the caller's draw is checked against the limit like any other.
+///
+/// # Panics
+/// * `"no open stream"`
+/// * `"nothing to claim"`
+pub fn take_claimable(env: &Env, borrower: &Address) -> (Stream, i128) {
+    let mut stream = get(env, borrower).expect("no open stream");
+    let amount = claimable(env, &stream);
+    if amount <= 0 {
+        panic!("nothing to claim");
+    }
+    stream.claimed += amount;
+    if stream.claimed == stream.total_amount {
+        storage::remove_persistent(env, &stream_key(borrower));
+    } else {
+        storage::set_persistent(env, &stream_key(borrower), &stream);
+    }
+    (stream, amount)
+}
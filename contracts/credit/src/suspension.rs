@@ -0,0 +1,70 @@
+//! Suspension reasons and expiring suspensions.
+//!
+//! A suspension records why the line was frozen as one of the reason codes
+//! below, and may carry an expiry. An expired suspension is lifted by the
+//! borrower's next draw, so temporary holds need no admin follow-up.
+
+use soroban_sdk::Env;
+
+use crate::types::{CreditLineData, CreditStatus};
+
+/// No reason given.
+pub const REASON_UNSPECIFIED: u32 = 0;
+/// Held while the risk engine re-scores the borrower.
+pub const REASON_RISK_REVIEW: u32 = 1;
+/// Arrears on scheduled payments.
+pub const REASON_MISSED_PAYMENT: u32 = 2;
+/// Suspected fraud or account compromise.
+pub const REASON_FRAUD_REVIEW: u32 = 3;
+/// Compliance or KYC hold.
+pub const REASON_COMPLIANCE: u32 = 4;
+/// Requested by the borrower.
+pub const REASON_BORROWER_REQUEST: u32 = 5;
+
+fn is_known_reason(reason: u32) -> bool {
+    matches!(
+        reason,
+        REASON_UNSPECIFIED
+            | REASON_RISK_REVIEW
+            | REASON_MISSED_PAYMENT
+            | REASON_FRAUD_REVIEW
+            | REASON_COMPLIANCE
+            | REASON_BORROWER_REQUEST
+    )
+}
+
+/// Suspend `line` for `reason` until `until_ts` (`None` for no expiry).
+///
+/// # Panics
+/// * `"invalid suspension reason"`
+/// * `"suspension expiry must be in the future"`
+pub fn suspend(env: &Env, line: &mut CreditLineData, reason: u32, until_ts: Option<u64>) {
+    if !is_known_reason(reason) {
+        panic!("invalid suspension reason");
+    }
+    let until_ts = until_ts.unwrap_or(0);
+    if until_ts != 0 && until_ts <= env.ledger().timestamp() {
+        panic!("suspension expiry must be in the future");
+    }
+    line.status = CreditStatus::Suspended;
+    line.suspension_reason = reason;
+    line.suspended_until = until_ts;
+}
+
+/// Clear the suspension details; the caller sets the new status.
+pub fn clear(line: &mut CreditLineData) {
+    line.suspension_reason = REASON_UNSPECIFIED;
+    line.suspended_until = 0;
+}
+
+/// Reactivate `line` if its suspension has expired. Returns true if it was lifted.
+pub fn lift_if_expired(env: &Env, line: &mut CreditLineData) -> bool {
+    let expired = line.status == CreditStatus::Suspended
+        && line.suspended_until != 0
+        && env.ledger().timestamp() >= line.suspended_until;
+    if expired {
+        line.status = CreditStatus::Active;
+        clear(line);
+    }
+    expired
+}
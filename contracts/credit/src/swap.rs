@@ -0,0 +1,82 @@
+//! Swap adapter for repaying in a different asset.
+//!
+//! The admin registers an adapter contract that wraps a DEX or aggregator.
+//! `repay_with_token` moves the borrower's `token_in` to the adapter, has it
+//! swap into the line's token for this contract, and applies whatever arrives
+//! as a repayment. The amount received is measured from this contract's
+//! balance rather than taken from the adapter's return value, and the call
+//! reverts if it is below the borrower's `min_repay_out`.
+
+use soroban_sdk::{contractclient, token, Address, Env, Symbol};
+
+use crate::storage::{self, DataKey};
+
+/// Interface a swap adapter must implement.
+/// Only the generated `SwapAdapterClient` is used.
+#[allow(dead_code)]
+#[contractclient(name = "SwapAdapterClient")]
+pub trait SwapAdapter {
+    /// Swap `amount_in` of `token_in`, already held by the adapter, into at
+    /// least `min_amount_out` of `token_out` sent to `to`. Returns the amount sent.
+    fn swap(
+        env: Env,
+        token_in: Address,
+        token_out: Address,
+        amount_in: i128,
+        min_amount_out: i128,
+        to: Address,
+    ) -> i128;
+}
+
+/// Instance storage key for the adapter address.
+fn adapter_key(env: &Env) -> DataKey {
+    DataKey::Config(Symbol::new(env, "swap"))
+}
+
+pub fn get_adapter(env: &Env) -> Option<Address> {
+    storage::get_instance(env, &adapter_key(env))
+}
+
+pub fn set_adapter(env: &Env, adapter: &Address) {
+    storage::set_instance(env, &adapter_key(env), adapter);
+}
+
+/// Swap `amount_in` of `borrower`'s `token_in` into `token_out` held by this
+/// contract. Returns the amount of `token_out` received.
+///
+/// # Panics
+/// * `"amount must be positive"`
+/// * `"min_repay_out cannot be negative"`
+/// * `"token_in is the line token"` – nothing to swap; repay directly
+/// * `"swap adapter not set"`
+/// * `"slippage exceeded"` – less than `min_out` arrived
+pub fn swap_in(
+    env: &Env,
+    borrower: &Address,
+    token_in: &Address,
+    token_out: &Address,
+    amount_in: i128,
+    min_out: i128,
+) -> i128 {
+    if amount_in <= 0 {
+        panic!("amount must be positive");
+    }
+    if min_out < 0 {
+        panic!("min_repay_out cannot be negative");
+    }
+    if token_in == token_out {
+        panic!("token_in is the line token");
+    }
+    let adapter = get_adapter(env).expect("swap adapter not set");
+    let contract = env.current_contract_address();
+    let out_client = token::Client::new(env, token_out);
+    let balance_before = out_client.balance(&contract);
+    token::Client::new(env, token_in).transfer(borrower, &adapter, &amount_in);
+    SwapAdapterClient::new(env, &adapter)
+        .swap(token_in, token_out, &amount_in, &min_out, &contract);
+    let received = out_client.balance(&contract) - balance_before;
+    if received < min_out {
+        panic!("slippage exceeded");
+    }
+    received
+}
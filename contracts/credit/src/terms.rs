@@ -0,0 +1,64 @@
+//! First-payment grace periods and prepayment penalties.
+//!
+//! `RepaymentTerms` is a protocol-wide setting copied onto each line when it is
+//! opened. For `grace_period_secs` after opening, a line accrues no interest
+//! and its billing cycles bill no minimum payment. A term line repaid in full
+//! before its maturity pays `prepayment_penalty_bps` of the principal cleared
+//! by that final payment on top, kept by the protocol as a fee.
+
+use soroban_sdk::{contracttype, Env, Symbol};
+
+use crate::math::{self, apply_bps, Rounding};
+use crate::storage::{self, DataKey};
+use crate::types::CreditLineData;
+
+/// Terms given to newly opened lines (admin-configured). 0 disables either term.
+#[contracttype]
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct RepaymentTerms {
+    pub grace_period_secs: u64,
+    pub prepayment_penalty_bps: u32,
+}
+
+/// Instance storage key for the repayment terms.
+fn terms_key(env: &Env) -> DataKey {
+    DataKey::Config(Symbol::new(env, "terms"))
+}
+
+pub fn get_config(env: &Env) -> RepaymentTerms {
+    storage::get_instance(env, &terms_key(env)).unwrap_or_default()
+}
+
+/// # Panics
+/// * `"prepayment_penalty_bps cannot exceed 10000"`
+pub fn set_config(env: &Env, terms: &RepaymentTerms) {
+    if terms.prepayment_penalty_bps > 10_000 {
+        panic!("prepayment_penalty_bps cannot exceed 10000");
+    }
+    storage::set_instance(env, &terms_key(env), terms);
+}
+
+/// Copy the current terms onto a line being opened now.
+pub fn apply(env: &Env, line: &mut CreditLineData) {
+    let terms = get_config(env);
+    line.grace_until_ts = if terms.grace_period_secs > 0 {
+        env.ledger()
+            .timestamp()
+            .saturating_add(terms.grace_period_secs)
+    } else {
+        0
+    };
+    line.prepayment_penalty_bps = terms.prepayment_penalty_bps;
+}
+
+/// Penalty for clearing `principal` of `line` in a final payment made now; 0
+/// unless the line has a maturity still ahead.
+pub fn prepayment_penalty(env: &Env, line: &CreditLineData, principal: i128) -> i128 {
+    if line.maturity_ts == 0 || env.ledger().timestamp() >= line.maturity_ts {
+        return 0;
+    }
+    math::checked(
+        env,
+        apply_bps(principal, line.prepayment_penalty_bps, Rounding::Down),
+    )
+}
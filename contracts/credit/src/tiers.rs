@@ -0,0 +1,210 @@
+//! Admin-defined risk tiers.
+//!
+//! A line opened in a tier takes its limit and rate from the tier instead of
+//! raw values. The tier's LTV and draw fee replace the global collateral and
+//! fee settings for its lines, and are read at draw time. Limit and rate are
+//! stored on each line, so a tier update bumps the tier's version and every
+//! line in it is repriced lazily, the next time it is loaded: interest is
+//! accrued at the old rate first. Disputed lines, and lines whose updates are
+//! frozen, keep their terms until the flag is lifted. Tier limits are in token
+//! units.
+
+use soroban_sdk::{contracttype, Address, Env};
+
+use crate::interest;
+use crate::math;
+use crate::rates;
+use crate::restrictions;
+use crate::storage::{self, DataKey};
+use crate::types::{CreditLineData, CreditStatus};
+
+/// Terms shared by every line in a tier.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Tier {
+    /// Credit limit given to lines in the tier.
+    pub max_limit: i128,
+    /// Rate for Fixed lines, spread over the reference rate for Variable lines.
+    pub rate_bps: u32,
+    /// Loan-to-value applied to collateral, replacing `CollateralConfig::ltv_bps`.
+    pub ltv_bps: u32,
+    /// Share of each draw withheld as a fee, replacing `FeeConfig::draw_fee_bps`.
+    pub draw_fee_bps: u32,
+}
+
+/// A line's tier and the tier version its terms were last synced to.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct LineTier {
+    pub tier_id: u32,
+    pub version: u32,
+}
+
+#[contracttype]
+#[derive(Clone)]
+pub enum TierKey {
+    /// Terms of a tier (persistent).
+    Tier(u32),
+    /// Number of updates applied to a tier (persistent).
+    Version(u32),
+    /// Tier of a borrower's line (persistent).
+    Assignment(Address),
+    /// Sum of `utilized_amount` across a tier's lines (persistent).
+    Outstanding(u32),
+    /// Cap on a tier's outstanding total; see `exposure` (persistent).
+    Cap(u32),
+}
+
+pub fn get(env: &Env, tier_id: u32) -> Option<Tier> {
+    storage::get_persistent(env, &DataKey::Tiers(TierKey::Tier(tier_id)))
+}
+
+fn version(env: &Env, tier_id: u32) -> u32 {
+    storage::get_persistent(env, &DataKey::Tiers(TierKey::Version(tier_id))).unwrap_or(0)
+}
+
+/// Create or replace tier `tier_id`. Lines already in it are repriced on their
+/// next touch.
+///
+/// # Panics
+/// * `"credit_limit must be greater than zero"`
+/// * `"interest_rate_bps exceeds maximum"`
+/// * `"ltv_bps cannot exceed 10000"`
+/// * `"draw_fee_bps cannot exceed 10000"`
+/// * `"premium and draw fee cannot exceed 10000 bps"`
+pub fn set(env: &Env, tier_id: u32, tier: &Tier) {
+    if tier.max_limit <= 0 {
+        panic!("credit_limit must be greater than zero");
+    }
+    if tier.rate_bps > crate::MAX_INTEREST_RATE_BPS {
+        panic!("interest_rate_bps exceeds maximum");
+    }
+    if tier.ltv_bps > 10_000 {
+        panic!("ltv_bps cannot exceed 10000");
+    }
+    if tier.draw_fee_bps > 10_000 {
+        panic!("draw_fee_bps cannot exceed 10000");
+    }
+    crate::insurance::check_withholding(
+        crate::insurance::get_config(env).premium_bps,
+        tier.draw_fee_bps,
+    );
+    storage::set_persistent(env, &DataKey::Tiers(TierKey::Tier(tier_id)), tier);
+    storage::set_persistent(
+        env,
+        &DataKey::Tiers(TierKey::Version(tier_id)),
+        &version(env, tier_id).saturating_add(1),
+    );
+}
+
+pub fn line_tier(env: &Env, borrower: &Address) -> Option<LineTier> {
+    storage::get_persistent(env, &DataKey::Tiers(TierKey::Assignment(borrower.clone())))
+}
+
+/// Terms of the tier `borrower`'s line is in, if any.
+pub fn of_line(env: &Env, borrower: &Address) -> Option<Tier> {
+    line_tier(env, borrower).and_then(|assigned| get(env, assigned.tier_id))
+}
+
+/// Put `line` in tier `tier_id`, synced to the tier's current terms, and add
+/// its utilized amount to the tier's outstanding total. The caller gives the
+/// line those terms.
+pub fn assign(env: &Env, line: &CreditLineData, tier_id: u32) {
+    storage::set_persistent(
+        env,
+        &DataKey::Tiers(TierKey::Assignment(line.borrower.clone())),
+        &LineTier {
+            tier_id,
+            version: version(env, tier_id),
+        },
+    );
+    adjust_outstanding(env, tier_id, line.utilized_amount);
+}
+
+/// Take `line` out of its tier, if it is in one.
+pub fn unassign(env: &Env, line: &CreditLineData) {
+    if let Some(assigned) = line_tier(env, &line.borrower) {
+        storage::remove_persistent(
+            env,
+            &DataKey::Tiers(TierKey::Assignment(line.borrower.clone())),
+        );
+        adjust_outstanding(env, assigned.tier_id, -line.utilized_amount);
+    }
+}
+
+/// Sum of `utilized_amount` across the lines in tier `tier_id`.
+pub fn outstanding(env: &Env, tier_id: u32) -> i128 {
+    storage::get_persistent(env, &DataKey::Tiers(TierKey::Outstanding(tier_id))).unwrap_or(0)
+}
+
+fn adjust_outstanding(env: &Env, tier_id: u32, delta: i128) {
+    if delta != 0 {
+        let total = math::checked(env, math::add(outstanding(env, tier_id), delta));
+        storage::set_persistent(env, &DataKey::Tiers(TierKey::Outstanding(tier_id)), &total);
+    }
+}
+
+/// Update the outstanding total of `after`'s tier for a credit line moving
+/// from `before` to `after`.
+pub fn track(env: &Env, before: Option<&CreditLineData>, after: &CreditLineData) {
+    if let Some(assigned) = line_tier(env, &after.borrower) {
+        let previous_utilized = before.map_or(0, |line| line.utilized_amount);
+        adjust_outstanding(
+            env,
+            assigned.tier_id,
+            after.utilized_amount - previous_utilized,
+        );
+    }
+}
+
+fn apply(env: &Env, line: &mut CreditLineData, tier: &Tier) {
+    line.credit_limit = tier.max_limit;
+    rates::set_rate_clamped(env, line, tier.rate_bps);
+}
+
+/// Bring `line` up to date with its tier if the tier changed since the last
+/// sync, accruing interest at the old rate first. Returns the assignment to
+/// store, or `None` when nothing changed. Writes nothing.
+pub fn apply_pending(env: &Env, line: &mut CreditLineData) -> Option<LineTier> {
+    let mut assigned = line_tier(env, &line.borrower)?;
+    let current = version(env, assigned.tier_id);
+    if assigned.version == current
+        || line.status == CreditStatus::Closed
+        || restrictions::is_frozen(line, restrictions::DISPUTED | restrictions::UPDATES_FROZEN)
+    {
+        return None;
+    }
+    let tier = get(env, assigned.tier_id)?;
+    interest::accrue(env, line);
+    apply(env, line, &tier);
+    assigned.version = current;
+    Some(assigned)
+}
+
+/// `apply_pending`, storing the updated assignment. Returns true if `line`
+/// changed and needs saving.
+pub fn sync(env: &Env, line: &mut CreditLineData) -> bool {
+    match apply_pending(env, line) {
+        Some(assigned) => {
+            storage::set_persistent(
+                env,
+                &DataKey::Tiers(TierKey::Assignment(line.borrower.clone())),
+                &assigned,
+            );
+            true
+        }
+        None => false,
+    }
+}
+
+/// Move the tier assignment along with a transferred line.
+pub fn transfer(env: &Env, from: &Address, to: &Address) {
+    if let Some(assigned) = line_tier(env, from) {
+        storage::remove_persistent(env, &DataKey::Tiers(TierKey::Assignment(from.clone())));
+        storage::set_persistent(
+            env,
+            &DataKey::Tiers(TierKey::Assignment(to.clone())),
+            &assigned,
+        );
+    }
+}
@@ -0,0 +1,126 @@
+//! Timelock for sensitive admin changes.
+//!
+//! Once the admin sets a minimum delay, rate, fee, risk and batched
+//! config changes can no longer be made directly: they are queued with an
+//! execution time at least the delay away, giving borrowers and lenders time
+//! to react, and the admin executes them once due or cancels them. Contract
+//! upgrades, and changes to the delay itself, only ever go through the queue.
+//! With a delay of 0, the default, the direct setters keep working.
+
+use soroban_sdk::{contracttype, BytesN, Env, Symbol, Vec};
+
+use crate::config::ConfigUpdate;
+use crate::storage::{self, DataKey};
+
+/// A change that can be queued.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum TimelockAction {
+    /// Settings applied as by `set_config`.
+    Config(Vec<ConfigUpdate>),
+    /// Replace the contract code with the uploaded wasm `hash`.
+    Upgrade(BytesN<32>),
+    /// Change the minimum delay.
+    SetDelay(u64),
+}
+
+/// An action waiting for its execution time.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct QueuedAction {
+    pub action_id: u64,
+    pub action: TimelockAction,
+    /// Earliest timestamp the action can be executed at.
+    pub eta: u64,
+}
+
+#[contracttype]
+#[derive(Clone)]
+pub enum TimelockKey {
+    /// Queued action by id (persistent).
+    Queued(u64),
+    /// Last issued action id (instance).
+    LastActionId,
+}
+
+/// Instance storage key for the minimum delay.
+fn delay_key(env: &Env) -> DataKey {
+    DataKey::Config(Symbol::new(env, "tl_delay"))
+}
+
+fn queued_key(action_id: u64) -> DataKey {
+    DataKey::Timelock(TimelockKey::Queued(action_id))
+}
+
+pub fn delay(env: &Env) -> u64 {
+    storage::get_instance(env, &delay_key(env)).unwrap_or(0)
+}
+
+/// Set the minimum delay directly.
+///
+/// # Panics
+/// * `"change must be queued through the timelock"` – a delay is already set
+pub fn set_initial_delay(env: &Env, delay_secs: u64) {
+    require_unlocked(env);
+    set_delay(env, delay_secs);
+}
+
+pub fn set_delay(env: &Env, delay_secs: u64) {
+    storage::set_instance(env, &delay_key(env), &delay_secs);
+}
+
+/// # Panics
+/// * `"change must be queued through the timelock"` – a delay is set
+pub fn require_unlocked(env: &Env) {
+    if delay(env) > 0 {
+        panic!("change must be queued through the timelock");
+    }
+}
+
+pub fn get(env: &Env, action_id: u64) -> Option<QueuedAction> {
+    storage::get_persistent(env, &queued_key(action_id))
+}
+
+/// Queue `action` for execution at or after `eta`.
+///
+/// # Panics
+/// * `"eta is before the timelock delay"` – `eta` is sooner than now plus the delay
+pub fn queue(env: &Env, action: TimelockAction, eta: u64) -> QueuedAction {
+    if eta < env.ledger().timestamp().saturating_add(delay(env)) {
+        panic!("eta is before the timelock delay");
+    }
+    let key = DataKey::Timelock(TimelockKey::LastActionId);
+    let action_id: u64 = storage::get_instance(env, &key).unwrap_or(0) + 1;
+    storage::set_instance(env, &key, &action_id);
+    let queued = QueuedAction {
+        action_id,
+        action,
+        eta,
+    };
+    storage::set_persistent(env, &queued_key(action_id), &queued);
+    queued
+}
+
+/// Remove and return queued action `action_id` for execution.
+///
+/// # Panics
+/// * `"queued action not found"`
+/// * `"queued action not due"` – before its `eta`
+pub fn take_due(env: &Env, action_id: u64) -> QueuedAction {
+    let queued = get(env, action_id).expect("queued action not found");
+    if env.ledger().timestamp() < queued.eta {
+        panic!("queued action not due");
+    }
+    storage::remove_persistent(env, &queued_key(action_id));
+    queued
+}
+
+/// Remove and return queued action `action_id`.
+///
+/// # Panics
+/// * `"queued action not found"`
+pub fn cancel(env: &Env, action_id: u64) -> QueuedAction {
+    let queued = get(env, action_id).expect("queued action not found");
+    storage::remove_persistent(env, &queued_key(action_id));
+    queued
+}
@@ -0,0 +1,55 @@
+//! Allowlist of tokens credit lines may be denominated in.
+//!
+//! The token passed to `init` is the default denomination and is always
+//! supported. The admin may add further tokens; each gets its own liquidity
+//! pool, commitments, and protocol fee balance.
+
+use soroban_sdk::{Address, Env, Symbol, Vec};
+
+use crate::storage::{self, DataKey};
+
+/// Instance storage key for the supported token list.
+fn tokens_key(env: &Env) -> DataKey {
+    DataKey::Config(Symbol::new(env, "tokens"))
+}
+
+pub fn list(env: &Env) -> Vec<Address> {
+    storage::get_instance(env, &tokens_key(env)).unwrap_or(Vec::new(env))
+}
+
+pub fn is_supported(env: &Env, token: &Address) -> bool {
+    list(env).contains(token)
+}
+
+/// # Panics
+/// * `"cannot remove default token"`
+pub fn set_supported(env: &Env, token: &Address, supported: bool) {
+    let mut tokens = list(env);
+    match (tokens.first_index_of(token), supported) {
+        (None, true) => tokens.push_back(token.clone()),
+        (Some(index), false) => {
+            if *token == crate::require_token(env) {
+                panic!("cannot remove default token");
+            }
+            tokens.remove(index);
+        }
+        _ => return,
+    }
+    storage::set_instance(env, &tokens_key(env), &tokens);
+}
+
+/// Denomination for a new line: `token` if supported, else the default token.
+///
+/// # Panics
+/// * `"token not supported"`
+pub fn resolve(env: &Env, token: Option<Address>) -> Address {
+    match token {
+        Some(token) => {
+            if !is_supported(env, &token) {
+                panic!("token not supported");
+            }
+            token
+        }
+        None => crate::require_token(env),
+    }
+}
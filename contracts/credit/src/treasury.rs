@@ -0,0 +1,81 @@
+//! Protocol treasury: reserve factor and accumulated protocol fees.
+//!
+//! A `reserve_factor_bps` slice of all interest collected is set aside for the
+//! protocol instead of the liquidity pool, unless a revenue split replaces it
+//! (see `revenue`). Accumulated fees are tracked per token and stay in the
+//! contract until a Treasurer collects them to the treasury address.
+
+use soroban_sdk::{contracttype, Address, Env};
+
+use crate::math;
+use crate::storage::{self, DataKey};
+
+#[contracttype]
+#[derive(Clone)]
+pub enum TreasuryKey {
+    Treasury,
+    ReserveFactorBps,
+    AccruedFees(Address),
+}
+
+pub fn get_treasury(env: &Env) -> Option<Address> {
+    storage::get_instance(env, &DataKey::Treasury(TreasuryKey::Treasury))
+}
+
+pub fn set_treasury(env: &Env, treasury: &Address) {
+    storage::set_instance(env, &DataKey::Treasury(TreasuryKey::Treasury), treasury);
+}
+
+pub fn reserve_factor_bps(env: &Env) -> u32 {
+    storage::get_instance(env, &DataKey::Treasury(TreasuryKey::ReserveFactorBps)).unwrap_or(0)
+}
+
+/// # Panics
+/// * `"reserve_factor_bps cannot exceed 10000"`
+pub fn set_reserve_factor_bps(env: &Env, bps: u32) {
+    if bps > 10_000 {
+        panic!("reserve_factor_bps cannot exceed 10000");
+    }
+    storage::set_instance(env, &DataKey::Treasury(TreasuryKey::ReserveFactorBps), &bps);
+}
+
+pub fn accrued_fees(env: &Env, token: &Address) -> i128 {
+    storage::get_instance(
+        env,
+        &DataKey::Treasury(TreasuryKey::AccruedFees(token.clone())),
+    )
+    .unwrap_or(0)
+}
+
+fn set_accrued_fees(env: &Env, token: &Address, amount: i128) {
+    storage::set_instance(
+        env,
+        &DataKey::Treasury(TreasuryKey::AccruedFees(token.clone())),
+        &amount,
+    );
+}
+
+/// Add `amount` of `token` to the protocol's accumulated fees.
+pub fn accrue_fees(env: &Env, token: &Address, amount: i128) {
+    if amount > 0 {
+        let total = math::checked(env, math::add(accrued_fees(env, token), amount));
+        set_accrued_fees(env, token, total);
+    }
+}
+
+/// Take up to `amount` of `token` from the accumulated fees and return the
+/// amount taken, capped at what has accumulated.
+pub fn take(env: &Env, token: &Address, amount: i128) -> i128 {
+    let taken = amount.clamp(0, accrued_fees(env, token));
+    if taken > 0 {
+        set_accrued_fees(env, token, accrued_fees(env, token) - taken);
+    }
+    taken
+}
+
+/// Reset accumulated fees in `token` and return the amount to transfer out.
+pub fn take_accrued_fees(env: &Env, token: &Address) -> i128 {
+    let amount = accrued_fees(env, token);
+    set_accrued_fees(env, token, 0);
+    amount
+}
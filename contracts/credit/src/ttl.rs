@@ -0,0 +1,83 @@
+//! Storage TTL management.
+//!
+//! Persistent credit lines and instance config are archived once their TTL
+//! runs out. Every read or write of a line extends it (and the instance) back
+//! to about 30 days whenever less than a day is left; keepers can bump dormant
+//! lines explicitly.
+
+use soroban_sdk::{Address, Env, Symbol};
+
+use crate::storage::{self, DataKey};
+
+/// Ledgers per day at a 5 second close time.
+const DAY_IN_LEDGERS: u32 = 17_280;
+
+/// TTL a touched entry is extended to.
+pub const BUMP_AMOUNT: u32 = 30 * DAY_IN_LEDGERS;
+
+/// Remaining TTL below which a touched entry is extended.
+pub const LIFETIME_THRESHOLD: u32 = BUMP_AMOUNT - DAY_IN_LEDGERS;
+
+/// Extend the credit line stored under `borrower` and the instance.
+pub fn extend_line(env: &Env, borrower: &Address) {
+    env.storage().persistent().extend_ttl(
+        &DataKey::Line(borrower.clone()),
+        LIFETIME_THRESHOLD,
+        BUMP_AMOUNT,
+    );
+    extend_instance(env);
+}
+
+pub fn extend_instance(env: &Env) {
+    env.storage()
+        .instance()
+        .extend_ttl(LIFETIME_THRESHOLD, BUMP_AMOUNT);
+    record_instance_ttl(env, LIFETIME_THRESHOLD);
+}
+
+/// Instance storage key for the ledger the instance is known to live until.
+fn instance_live_until_key(env: &Env) -> DataKey {
+    DataKey::Config(Symbol::new(env, "inst_ttl"))
+}
+
+/// Record that the instance now lives at least `ledgers` more ledgers. Contracts
+/// cannot read TTLs, so this keeps a lower bound, written at most once a day.
+fn record_instance_ttl(env: &Env, ledgers: u32) {
+    let live_until = env.ledger().sequence().saturating_add(ledgers);
+    let recorded: u32 = storage::get_instance(env, &instance_live_until_key(env)).unwrap_or(0);
+    if live_until > recorded.saturating_add(DAY_IN_LEDGERS) {
+        storage::set_instance(env, &instance_live_until_key(env), &live_until);
+    }
+}
+
+/// Ledgers the instance is known to live for at least; 0 if unknown.
+pub fn instance_remaining(env: &Env) -> u32 {
+    let recorded: u32 = storage::get_instance(env, &instance_live_until_key(env)).unwrap_or(0);
+    recorded.saturating_sub(env.ledger().sequence())
+}
+
+/// Clamp a requested TTL to the network maximum.
+///
+/// # Panics
+/// * `"ledgers must be positive"`
+fn requested(env: &Env, ledgers: u32) -> u32 {
+    if ledgers == 0 {
+        panic!("ledgers must be positive");
+    }
+    ledgers.min(env.storage().max_ttl())
+}
+
+/// Extend the credit line under `borrower` to live at least `ledgers` more ledgers.
+pub fn bump_line(env: &Env, borrower: &Address, ledgers: u32) {
+    let ledgers = requested(env, ledgers);
+    env.storage()
+        .persistent()
+        .extend_ttl(&DataKey::Line(borrower.clone()), ledgers, ledgers);
+}
+
+/// Extend the instance to live at least `ledgers` more ledgers.
+pub fn bump_instance(env: &Env, ledgers: u32) {
+    let ledgers = requested(env, ledgers);
+    env.storage().instance().extend_ttl(ledgers, ledgers);
+    record_instance_ttl(env, ledgers);
+}
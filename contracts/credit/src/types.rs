@@ -9,15 +9,174 @@ pub enum CreditStatus {
     Suspended = 1,
     Defaulted = 2,
     Closed = 3,
+    /// A scheduled payment was missed; draws are blocked until the arrears are repaid.
+    Delinquent = 4,
+}
+
+/// How a credit line's interest rate is set.
+#[contracttype]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum RateMode {
+    /// `interest_rate_bps` never changes unless updated explicitly.
+    Fixed = 0,
+    /// `interest_rate_bps` tracks the reference rate plus `rate_spread_bps`.
+    Variable = 1,
+}
+
+/// How often accrued interest is folded into the balance that earns interest.
+#[contracttype]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Compounding {
+    /// Interest accrues on principal only.
+    Simple = 0,
+    /// Interest compounds on the outstanding balance every day.
+    Daily = 1,
+    /// Interest compounds on the outstanding balance every 30 days.
+    Monthly = 2,
+}
+
+/// Number of days in the year an annual rate is spread over.
+#[contracttype]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum DayCount {
+    /// Actual/365: the annual rate accrues over 365 days.
+    Actual365 = 0,
+    /// Actual/360: the annual rate accrues over 360 days.
+    Actual360 = 1,
+}
+
+/// Interest convention of a credit line.
+#[contracttype]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct InterestConfig {
+    pub compounding: Compounding,
+    pub day_count: DayCount,
+}
+
+impl Default for InterestConfig {
+    fn default() -> Self {
+        InterestConfig {
+            compounding: Compounding::Simple,
+            day_count: DayCount::Actual365,
+        }
+    }
 }
 
 /// Stored credit line for a borrower.
 #[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
 pub struct CreditLineData {
     pub borrower: Address,
+    /// Token the line is denominated in: drawn, repaid, and priced in this token.
+    pub token: Address,
     pub credit_limit: i128,
     pub utilized_amount: i128,
     pub interest_rate_bps: u32,
     pub risk_score: u32,
     pub status: CreditStatus,
+    /// Billing cycle length in seconds; 0 when the line has no repayment schedule.
+    pub cycle_length_secs: u64,
+    /// Minimum payment per cycle in basis points of the utilized amount.
+    pub min_payment_bps: u32,
+    /// Timestamp by which `min_due_amount` must be repaid; 0 when unscheduled.
+    pub next_due_ts: u64,
+    /// Amount still owed for the current cycle.
+    pub min_due_amount: i128,
+    /// Consecutive cycles processed as missed; reset once arrears are repaid.
+    pub missed_cycles: u32,
+    /// Token deposited as collateral, if any.
+    pub collateral_token: Option<Address>,
+    /// Collateral held by the contract for this line.
+    pub collateral_amount: i128,
+    /// Interest accrued and not yet repaid.
+    pub accrued_interest: i128,
+    /// Timestamp up to which interest has been accrued.
+    pub last_accrual_ts: u64,
+    /// Repayments received while the line was Defaulted.
+    pub recovered_amount: i128,
+    /// Debt written off by the admin.
+    pub written_off_amount: i128,
+    /// Collector that bought the defaulted claim; `None` while the pool is the creditor.
+    pub creditor: Option<Address>,
+    /// Timestamp after which draws are rejected and only repayment is allowed; 0 for no term.
+    pub maturity_ts: u64,
+    pub rate_mode: RateMode,
+    /// Spread over the reference rate for Variable lines; 0 for Fixed lines.
+    pub rate_spread_bps: u32,
+    /// Credit limit in the price oracle's reference currency; 0 when the limit is in token units.
+    pub reference_limit: i128,
+    /// `utilized_amount` in reference units, valued at the last draw and reduced pro rata by repayments.
+    pub reference_utilized: i128,
+    /// Per-line minimum draw overriding the global limit; 0 to use the global limit.
+    pub min_draw_amount: i128,
+    /// Per-line maximum draw overriding the global limit; 0 to use the global limit.
+    pub max_draw_amount: i128,
+    /// Transferable position id minted for the line; 0 until minted.
+    pub position_id: u64,
+    /// Why the line was suspended (see `suspension`); 0 when not suspended.
+    pub suspension_reason: u32,
+    /// Timestamp at which a suspension lifts on the next draw; 0 for an indefinite one.
+    pub suspended_until: u64,
+    /// Bitflags of operations frozen on the line (see `restrictions`).
+    pub restrictions: u32,
+    /// Temporary extra limit granted by the risk engine; 0 when none.
+    pub limit_boost: i128,
+    /// Timestamp at which `limit_boost` stops counting; 0 when no boost.
+    pub boost_expires_at: u64,
+    /// Due date of the earliest unpaid cycle once a missed payment is processed; 0 when current.
+    pub delinquent_since_ts: u64,
+    /// Compounding and day-count convention used to accrue interest.
+    pub interest_config: InterestConfig,
+    /// Promotional rate accrued instead of `interest_rate_bps` until `promo_until_ts`.
+    pub promo_rate_bps: u32,
+    /// Timestamp the promotional rate ends; 0 when there is none.
+    pub promo_until_ts: u64,
+    /// Lowest rate the line can be priced at; 0 for no floor.
+    pub rate_floor_bps: u32,
+    /// Highest rate the line can be priced at; 0 for no cap.
+    pub rate_cap_bps: u32,
+    /// End of the first-payment grace period; 0 when there is none.
+    pub grace_until_ts: u64,
+    /// Penalty on principal cleared by paying off a term line before maturity.
+    pub prepayment_penalty_bps: u32,
+}
+
+/// Billing cycle attached to a credit line at open time.
+#[contracttype]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct RepaymentSchedule {
+    /// Length of each billing cycle in seconds.
+    pub cycle_length_secs: u64,
+    /// Minimum payment per cycle in basis points of the utilized amount.
+    pub min_payment_bps: u32,
+}
+
+/// Next payment due on a scheduled credit line.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct NextPayment {
+    pub due_ts: u64,
+    pub min_due_amount: i128,
+    /// True once `due_ts` has passed with `min_due_amount` still outstanding.
+    pub overdue: bool,
+    /// End of the first-payment grace period; 0 when the line has none.
+    pub grace_until_ts: u64,
+    /// Penalty a payoff now would add on top of the outstanding balance.
+    pub prepayment_penalty: i128,
+}
+
+/// Snapshot of a credit line's standing.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct LineHealth {
+    pub status: CreditStatus,
+    pub utilized_amount: i128,
+    /// Credit still drawable, counting any active limit boost.
+    pub available: i128,
+    /// Scheduled minimum owed while a due date has passed unpaid; 0 when current.
+    pub amount_past_due: i128,
+    /// Whole days since the earliest unpaid due date; 0 when current.
+    pub days_past_due: u32,
+    pub delinquent_since_ts: u64,
+    pub missed_cycles: u32,
 }
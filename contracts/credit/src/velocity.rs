@@ -0,0 +1,228 @@
+//! Draw velocity controls.
+//!
+//! Caps the total a borrower may draw per window of `window_ledgers` ledgers
+//! (e.g. 17_280 ledgers ≈ 24h) and the total drawn across the protocol in a
+//! single ledger. Windows are fixed epochs of the ledger sequence. Counters
+//! live in temporary storage and expire with their window.
+//!
+//! A line can also carry its own `SpendCap`, set by the risk engine: at most
+//! `amount` drawn per `epoch_secs` seconds, so a large limit cannot be spent in
+//! one burst. The epoch starts at the first draw after the previous one ended;
+//! the cap and the epoch's running total are persistent entries beside the line.
+
+use soroban_sdk::{contracttype, Address, Env, Symbol};
+
+use crate::math;
+use crate::storage::{self, DataKey};
+
+/// Velocity caps (admin-configured). A zero field disables that cap.
+#[contracttype]
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct VelocityConfig {
+    /// Length of the per-borrower window in ledgers.
+    pub window_ledgers: u32,
+    /// Maximum a single borrower may draw within one window.
+    pub borrower_window_cap: i128,
+    /// Maximum drawn across all borrowers within one ledger.
+    pub protocol_ledger_cap: i128,
+}
+
+/// Per-line spending cap (risk-engine-configured). A zero `amount` disables it.
+#[contracttype]
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct SpendCap {
+    /// Maximum the line may draw within one epoch.
+    pub amount: i128,
+    /// Length of an epoch in seconds.
+    pub epoch_secs: u64,
+}
+
+/// Running total of a line's current spend-cap epoch.
+#[contracttype]
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct SpendEpoch {
+    pub start_ts: u64,
+    pub spent: i128,
+}
+
+#[contracttype]
+#[derive(Clone)]
+pub enum VelocityKey {
+    /// Amount drawn by a borrower in a window epoch (temporary).
+    Borrower(Address, u32),
+    /// Amount drawn protocol-wide in a ledger (temporary).
+    Ledger(u32),
+    /// Spending cap of a line (persistent).
+    SpendCap(Address),
+    /// Current spend-cap epoch of a line (persistent).
+    SpendEpoch(Address),
+}
+
+/// Instance storage key for velocity config.
+fn velocity_key(env: &Env) -> DataKey {
+    DataKey::Config(Symbol::new(env, "velocity"))
+}
+
+pub fn get_config(env: &Env) -> VelocityConfig {
+    storage::get_instance(env, &velocity_key(env)).unwrap_or_default()
+}
+
+/// # Panics
+/// * `"velocity caps cannot be negative"`
+/// * `"window_ledgers must be positive"` – a borrower cap needs a window
+pub fn set_config(env: &Env, config: &VelocityConfig) {
+    if config.borrower_window_cap < 0 || config.protocol_ledger_cap < 0 {
+        panic!("velocity caps cannot be negative");
+    }
+    if config.borrower_window_cap > 0 && config.window_ledgers == 0 {
+        panic!("window_ledgers must be positive");
+    }
+    storage::set_instance(env, &velocity_key(env), config);
+}
+
+pub fn get_spend_cap(env: &Env, borrower: &Address) -> SpendCap {
+    storage::get_persistent(
+        env,
+        &DataKey::Velocity(VelocityKey::SpendCap(borrower.clone())),
+    )
+    .unwrap_or_default()
+}
+
+/// Set `borrower`'s spending cap; a zero `amount` removes it. The current epoch's
+/// total is kept, so lowering the cap takes effect immediately.
+///
+/// # Panics
+/// * `"spend cap cannot be negative"`
+/// * `"epoch_secs must be positive"` – a cap needs an epoch
+pub fn set_spend_cap(env: &Env, borrower: &Address, cap: &SpendCap) {
+    if cap.amount < 0 {
+        panic!("spend cap cannot be negative");
+    }
+    let key = DataKey::Velocity(VelocityKey::SpendCap(borrower.clone()));
+    if cap.amount == 0 {
+        storage::remove_persistent(env, &key);
+        return;
+    }
+    if cap.epoch_secs == 0 {
+        panic!("epoch_secs must be positive");
+    }
+    storage::set_persistent(env, &key, cap);
+}
+
+/// `borrower`'s spend-cap epoch as of now: the stored one, or a fresh one if it
+/// has ended.
+fn current_epoch(env: &Env, borrower: &Address, cap: &SpendCap) -> SpendEpoch {
+    let now = env.ledger().timestamp();
+    let stored: Option<SpendEpoch> = storage::get_persistent(
+        env,
+        &DataKey::Velocity(VelocityKey::SpendEpoch(borrower.clone())),
+    );
+    match stored {
+        Some(epoch) if now < epoch.start_ts.saturating_add(cap.epoch_secs) => epoch,
+        _ => SpendEpoch {
+            start_ts: now,
+            spent: 0,
+        },
+    }
+}
+
+/// Amount `borrower` has drawn in the current spend-cap epoch; 0 without a cap.
+pub fn spent_in_epoch(env: &Env, borrower: &Address) -> i128 {
+    let cap = get_spend_cap(env, borrower);
+    if cap.amount == 0 {
+        return 0;
+    }
+    current_epoch(env, borrower, &cap).spent
+}
+
+fn borrower_key(env: &Env, config: &VelocityConfig, borrower: &Address) -> DataKey {
+    let epoch = env.ledger().sequence() / config.window_ledgers;
+    DataKey::Velocity(VelocityKey::Borrower(borrower.clone(), epoch))
+}
+
+/// Amount `borrower` has drawn in the current window; 0 when the cap is disabled.
+pub fn drawn_in_window(env: &Env, borrower: &Address) -> i128 {
+    let config = get_config(env);
+    if config.window_ledgers == 0 {
+        return 0;
+    }
+    env.storage()
+        .temporary()
+        .get(&borrower_key(env, &config, borrower))
+        .unwrap_or(0)
+}
+
+/// True if a draw of `amount` by `borrower` would exceed an enabled cap. Read-only
+/// counterpart of `try_record_draw`.
+pub fn would_exceed(env: &Env, borrower: &Address, amount: i128) -> bool {
+    let config = get_config(env);
+    let exceeds = |key: &DataKey, cap: i128| {
+        let drawn: i128 = env.storage().temporary().get(key).unwrap_or(0);
+        math::checked(env, math::add(drawn, amount)) > cap
+    };
+    let ledger_key = DataKey::Velocity(VelocityKey::Ledger(env.ledger().sequence()));
+    let cap = get_spend_cap(env, borrower);
+    (config.protocol_ledger_cap > 0 && exceeds(&ledger_key, config.protocol_ledger_cap))
+        || (config.borrower_window_cap > 0
+            && exceeds(
+                &borrower_key(env, &config, borrower),
+                config.borrower_window_cap,
+            ))
+        || (cap.amount > 0
+            && math::checked(
+                env,
+                math::add(current_epoch(env, borrower, &cap).spent, amount),
+            ) > cap.amount)
+}
+
+/// Add `amount` to `key` unless the total would exceed `cap`, keeping the entry
+/// alive for `ttl_ledgers`. Returns false if the cap would be exceeded.
+fn try_add(env: &Env, key: &DataKey, amount: i128, cap: i128, ttl_ledgers: u32) -> bool {
+    let drawn: i128 = env.storage().temporary().get(key).unwrap_or(0);
+    let total = math::checked(env, math::add(drawn, amount));
+    if total > cap {
+        return false;
+    }
+    let storage = env.storage().temporary();
+    storage.set(key, &total);
+    storage.extend_ttl(key, ttl_ledgers, ttl_ledgers);
+    true
+}
+
+/// Count a draw of `amount` by `borrower` against the enabled caps.
+/// Returns false if any cap would be exceeded; the caller must then revert.
+pub fn try_record_draw(env: &Env, borrower: &Address, amount: i128) -> bool {
+    let config = get_config(env);
+    if config.protocol_ledger_cap > 0 {
+        let key = DataKey::Velocity(VelocityKey::Ledger(env.ledger().sequence()));
+        if !try_add(env, &key, amount, config.protocol_ledger_cap, 1) {
+            return false;
+        }
+    }
+    if config.borrower_window_cap > 0 {
+        let key = borrower_key(env, &config, borrower);
+        if !try_add(
+            env,
+            &key,
+            amount,
+            config.borrower_window_cap,
+            config.window_ledgers,
+        ) {
+            return false;
+        }
+    }
+    let cap = get_spend_cap(env, borrower);
+    if cap.amount > 0 {
+        let mut epoch = current_epoch(env, borrower, &cap);
+        epoch.spent = math::checked(env, math::add(epoch.spent, amount));
+        if epoch.spent > cap.amount {
+            return false;
+        }
+        storage::set_persistent(
+            env,
+            &DataKey::Velocity(VelocityKey::SpendEpoch(borrower.clone())),
+            &epoch,
+        );
+    }
+    true
+}
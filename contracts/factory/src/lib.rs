@@ -0,0 +1,344 @@
+#![no_std]
+
+//! Creditra factory contract: deploys one isolated Credit contract per market.
+//!
+//! Each market (e.g. `USDC`, `XLM`, `EURC`) gets its own Credit instance, with
+//! its own liquidity, lines and configuration, deployed from the Credit wasm
+//! hash stored here. Every instance is initialized with the factory admin as
+//! its admin, so all markets share one governance root. Instance addresses are
+//! derived from the market name, so they are predictable before deployment.
+
+use soroban_sdk::{
+    contract, contractclient, contractimpl, contracttype, symbol_short, xdr::ToXdr, Address,
+    BytesN, Env, Symbol, Vec,
+};
+
+#[contracttype]
+#[derive(Clone)]
+pub enum DataKey {
+    /// Factory admin, also the admin of every deployed market (instance).
+    Admin,
+    /// Wasm hash new markets are deployed from (instance).
+    WasmHash,
+    /// Deployed markets, in deployment order (instance).
+    Markets,
+}
+
+/// A deployed Credit instance.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Market {
+    /// Market name, also the deployment salt.
+    pub market: Symbol,
+    /// Default token of the market's Credit contract.
+    pub token: Address,
+    /// Address of the market's Credit contract.
+    pub credit: Address,
+}
+
+/// Event emitted when a market is deployed.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct MarketDeployedEvent {
+    pub market: Symbol,
+    pub token: Address,
+    pub credit: Address,
+}
+
+/// Subset of the Credit contract interface used by the factory.
+/// Only the generated `CreditContractClient` is used.
+#[allow(dead_code)]
+#[contractclient(name = "CreditContractClient")]
+pub trait CreditContract {
+    fn init(env: Env, admin: Address, token: Address);
+}
+
+fn require_admin(env: &Env) -> Address {
+    env.storage()
+        .instance()
+        .get(&DataKey::Admin)
+        .expect("factory not initialized")
+}
+
+fn require_admin_auth(env: &Env) -> Address {
+    let admin = require_admin(env);
+    admin.require_auth();
+    admin
+}
+
+fn markets(env: &Env) -> Vec<Market> {
+    env.storage()
+        .instance()
+        .get(&DataKey::Markets)
+        .unwrap_or(Vec::new(env))
+}
+
+fn find_market(env: &Env, market: &Symbol) -> Option<Market> {
+    markets(env).iter().find(|entry| entry.market == *market)
+}
+
+fn record_market(env: &Env, entry: &Market) {
+    let mut all = markets(env);
+    all.push_back(entry.clone());
+    env.storage().instance().set(&DataKey::Markets, &all);
+}
+
+/// Deployment salt for `market`.
+fn salt(env: &Env, market: &Symbol) -> BytesN<32> {
+    env.crypto().sha256(&market.clone().to_xdr(env)).into()
+}
+
+#[contract]
+pub struct CreditFactory;
+
+#[contractimpl]
+impl CreditFactory {
+    /// Initialize the factory with its admin and the Credit wasm hash to deploy.
+    ///
+    /// # Panics
+    /// * `"Already initialized"`
+    pub fn init(env: Env, admin: Address, wasm_hash: BytesN<32>) {
+        if env.storage().instance().has(&DataKey::Admin) {
+            panic!("Already initialized");
+        }
+        env.storage().instance().set(&DataKey::Admin, &admin);
+        env.storage().instance().set(&DataKey::WasmHash, &wasm_hash);
+    }
+
+    /// Replace the wasm hash future markets are deployed from (admin only).
+    /// Markets already deployed keep their code.
+    pub fn set_wasm_hash(env: Env, wasm_hash: BytesN<32>) {
+        require_admin_auth(&env);
+        env.storage().instance().set(&DataKey::WasmHash, &wasm_hash);
+    }
+
+    /// Get the wasm hash new markets are deployed from (view function).
+    pub fn get_wasm_hash(env: Env) -> Option<BytesN<32>> {
+        env.storage().instance().get(&DataKey::WasmHash)
+    }
+
+    /// Get the factory admin (view function).
+    pub fn get_admin(env: Env) -> Address {
+        require_admin(&env)
+    }
+
+    /// Deploy and initialize a Credit contract for `market`, with `token` as its
+    /// default token and the factory admin as its admin (admin only). Returns the
+    /// new contract's address. Emits a MarketDeployedEvent.
+    ///
+    /// # Panics
+    /// * `"market already exists"`
+    pub fn deploy_market(env: Env, market: Symbol, token: Address) -> Address {
+        let admin = require_admin_auth(&env);
+        if find_market(&env, &market).is_some() {
+            panic!("market already exists");
+        }
+        let wasm_hash: BytesN<32> = env
+            .storage()
+            .instance()
+            .get(&DataKey::WasmHash)
+            .expect("factory not initialized");
+        let credit = env
+            .deployer()
+            .with_current_contract(salt(&env, &market))
+            .deploy_v2(wasm_hash, ());
+        CreditContractClient::new(&env, &credit).init(&admin, &token);
+
+        let entry = Market {
+            market: market.clone(),
+            token: token.clone(),
+            credit: credit.clone(),
+        };
+        record_market(&env, &entry);
+        env.events().publish(
+            (symbol_short!("factory"), symbol_short!("deployed"), market),
+            MarketDeployedEvent {
+                market: entry.market,
+                token,
+                credit: credit.clone(),
+            },
+        );
+        credit
+    }
+
+    /// Address `market`'s Credit contract has, or will have once deployed (view function).
+    pub fn market_address(env: Env, market: Symbol) -> Address {
+        env.deployer()
+            .with_current_contract(salt(&env, &market))
+            .deployed_address()
+    }
+
+    /// Get a deployed market by name (view function).
+    pub fn get_market(env: Env, market: Symbol) -> Option<Market> {
+        find_market(&env, &market)
+    }
+
+    /// All deployed markets, in deployment order (view function).
+    pub fn list_markets(env: Env) -> Vec<Market> {
+        markets(&env)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use soroban_sdk::testutils::{Address as _, Events};
+
+    mod credit {
+        soroban_sdk::contractimport!(
+            file = "../../target/wasm32v1-none/release/creditra_credit.wasm"
+        );
+    }
+
+    fn setup(env: &Env) -> (CreditFactoryClient<'_>, Address) {
+        let admin = Address::generate(env);
+        let factory_id = env.register(CreditFactory, ());
+        let client = CreditFactoryClient::new(env, &factory_id);
+        client.init(&admin, &BytesN::from_array(env, &[1; 32]));
+        (client, admin)
+    }
+
+    /// Record `market` as deployed without deploying it, as `deploy_market` would.
+    fn record(env: &Env, client: &CreditFactoryClient, market: &str) -> Market {
+        let market = Symbol::new(env, market);
+        let entry = Market {
+            market: market.clone(),
+            token: Address::generate(env),
+            credit: client.market_address(&market),
+        };
+        env.as_contract(&client.address, || record_market(env, &entry));
+        entry
+    }
+
+    #[test]
+    fn test_init_stores_admin_and_wasm_hash() {
+        let env = Env::default();
+        let (client, admin) = setup(&env);
+        assert_eq!(client.get_admin(), admin);
+        assert_eq!(
+            client.get_wasm_hash(),
+            Some(BytesN::from_array(&env, &[1; 32]))
+        );
+        assert_eq!(client.list_markets().len(), 0);
+    }
+
+    #[test]
+    #[should_panic(expected = "Already initialized")]
+    fn test_init_twice_reverts() {
+        let env = Env::default();
+        let (client, admin) = setup(&env);
+        client.init(&admin, &BytesN::from_array(&env, &[2; 32]));
+    }
+
+    #[test]
+    fn test_set_wasm_hash_requires_admin() {
+        let env = Env::default();
+        let (client, admin) = setup(&env);
+        let hash = BytesN::from_array(&env, &[2; 32]);
+        assert!(client.try_set_wasm_hash(&hash).is_err());
+
+        env.mock_all_auths();
+        client.set_wasm_hash(&hash);
+        assert_eq!(env.auths()[0].0, admin);
+        assert_eq!(client.get_wasm_hash(), Some(hash));
+    }
+
+    #[test]
+    fn test_markets_listed_in_deployment_order() {
+        let env = Env::default();
+        let (client, _admin) = setup(&env);
+        let usdc = record(&env, &client, "USDC");
+        let xlm = record(&env, &client, "XLM");
+
+        assert_eq!(
+            client.list_markets(),
+            Vec::from_array(&env, [usdc.clone(), xlm])
+        );
+        assert_eq!(client.get_market(&usdc.market), Some(usdc));
+        assert_eq!(client.get_market(&Symbol::new(&env, "EURC")), None);
+    }
+
+    #[test]
+    fn test_market_addresses_are_distinct_and_stable() {
+        let env = Env::default();
+        let (client, _admin) = setup(&env);
+        let usdc = Symbol::new(&env, "USDC");
+        let xlm = Symbol::new(&env, "XLM");
+        assert_eq!(client.market_address(&usdc), client.market_address(&usdc));
+        assert_ne!(client.market_address(&usdc), client.market_address(&xlm));
+    }
+
+    #[test]
+    fn test_deploy_market_deploys_initialized_credit_contract() {
+        use soroban_sdk::{IntoVal, TryIntoVal};
+        let env = Env::default();
+        env.mock_all_auths();
+        // Uploading and instantiating the full Credit wasm exceeds the default test budget.
+        env.cost_estimate().budget().reset_unlimited();
+        let admin = Address::generate(&env);
+        let factory_id = env.register(CreditFactory, ());
+        let client = CreditFactoryClient::new(&env, &factory_id);
+        let wasm_hash = env.deployer().upload_contract_wasm(credit::WASM);
+        client.init(&admin, &wasm_hash);
+        let usdc = Symbol::new(&env, "USDC");
+        let token = Address::generate(&env);
+
+        let credit = client.deploy_market(&usdc, &token);
+
+        let (contract, topics, data) = env.events().all().last().unwrap();
+        assert_eq!(contract, client.address);
+        assert_eq!(
+            topics,
+            (
+                symbol_short!("factory"),
+                symbol_short!("deployed"),
+                usdc.clone()
+            )
+                .into_val(&env)
+        );
+        let event: MarketDeployedEvent = data.try_into_val(&env).unwrap();
+        assert_eq!(
+            event,
+            MarketDeployedEvent {
+                market: usdc.clone(),
+                token: token.clone(),
+                credit: credit.clone(),
+            }
+        );
+        assert_eq!(credit, client.market_address(&usdc));
+        let market = credit::Client::new(&env, &credit);
+        assert_eq!(market.get_admin(), Some(admin));
+        assert_eq!(market.get_liquidity_token(), Some(token.clone()));
+        assert_eq!(
+            client.list_markets(),
+            Vec::from_array(
+                &env,
+                [Market {
+                    market: usdc,
+                    token,
+                    credit,
+                }]
+            )
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "market already exists")]
+    fn test_deploy_existing_market_reverts() {
+        let env = Env::default();
+        env.mock_all_auths();
+        let (client, _admin) = setup(&env);
+        let usdc = record(&env, &client, "USDC");
+        client.deploy_market(&usdc.market, &Address::generate(&env));
+    }
+
+    #[test]
+    fn test_deploy_market_requires_admin() {
+        let env = Env::default();
+        let (client, _admin) = setup(&env);
+        let result = client.try_deploy_market(&Symbol::new(&env, "USDC"), &Address::generate(&env));
+        assert!(result.is_err());
+        assert_eq!(client.list_markets().len(), 0);
+        assert_eq!(env.events().all().len(), 0);
+    }
+}